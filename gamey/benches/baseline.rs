@@ -0,0 +1,135 @@
+//! Performance baseline of the engine and the bots.
+//!
+//! Reference numbers are kept in `benches/baseline.json`; run `cargo
+//! bench-baseline` and compare by hand before arguing about a speed-up. Only
+//! the public API is used, so these benchmarks also break when it changes.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use gamey::{
+    BridgeBot, Coordinates, GameY, MctsBot, MinimaxBot, Movement, RandomBot, SmartRandomBot,
+    TriangularTopology, YBot, YEN,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Seed of the random games and of the bots that take one.
+const SEED: u64 = 42;
+
+/// Plays a game of random moves on a board of `size` and returns the number of moves.
+fn random_game(size: u32, rng: &mut StdRng) -> u64 {
+    let mut game = GameY::new(size);
+    let mut moves = 0;
+    while let Some(player) = game.next_player() {
+        let available = game.available_cells();
+        let index = available[rng.random_range(0..available.len())];
+        let coords = Coordinates::from_index(index, size);
+        game.add_move(Movement::Placement { player, coords }).unwrap();
+        moves += 1;
+    }
+    moves
+}
+
+/// A game whose first `moves` moves are random, for benchmarks that need a middle game.
+fn middle_game(size: u32, moves: usize) -> GameY {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut game = GameY::new(size);
+    for _ in 0..moves {
+        let Some(player) = game.next_player() else {
+            break;
+        };
+        let available = game.available_cells();
+        let index = available[rng.random_range(0..available.len())];
+        let coords = Coordinates::from_index(index, size);
+        game.add_move(Movement::Placement { player, coords }).unwrap();
+    }
+    game
+}
+
+/// Full games of random moves, measured in moves per second.
+fn bench_random_games(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random_game");
+    for size in [7, 11, 15] {
+        let moves = random_game(size, &mut StdRng::seed_from_u64(SEED));
+        group.throughput(Throughput::Elements(moves));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter_batched(
+                || StdRng::seed_from_u64(SEED),
+                |mut rng| black_box(random_game(size, &mut rng)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Construction of the topology, with its adjacency and regions.
+fn bench_topology(c: &mut Criterion) {
+    let mut group = c.benchmark_group("topology_new");
+    for size in [7, 11, 15, 25] {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| black_box(TriangularTopology::new(size)))
+        });
+    }
+    group.finish();
+}
+
+/// Cloning the engine of a half-full board, as the searching bots do.
+fn bench_engine_clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("engine_clone");
+    for size in [7, 11, 15] {
+        let game = middle_game(size, (size * (size + 1) / 4) as usize);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &game, |b, game| {
+            b.iter(|| black_box(game.engine().clone()))
+        });
+    }
+    group.finish();
+}
+
+/// YEN to and from JSON, and YEN to a game.
+fn bench_yen(c: &mut Criterion) {
+    let mut group = c.benchmark_group("yen");
+    for size in [7, 11, 15] {
+        let game = middle_game(size, (size * (size + 1) / 4) as usize);
+        let yen = YEN::from(&game);
+        let json = serde_json::to_string(&yen).unwrap();
+        group.bench_with_input(BenchmarkId::new("serialize", size), &yen, |b, yen| {
+            b.iter(|| black_box(serde_json::to_string(yen).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("deserialize", size), &json, |b, json| {
+            b.iter(|| black_box(serde_json::from_str::<YEN>(json).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("to_game", size), &yen, |b, yen| {
+            b.iter(|| black_box(GameY::try_from(yen.clone()).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+/// Latency of `choose_move` of every bot on the same middle game.
+fn bench_bots(c: &mut Criterion) {
+    let mut group = c.benchmark_group("choose_move");
+    group.sample_size(10);
+    let game = middle_game(9, 12);
+    let bots: Vec<Box<dyn YBot>> = vec![
+        Box::new(RandomBot),
+        Box::new(SmartRandomBot),
+        Box::new(BridgeBot),
+        Box::new(MinimaxBot::new(2, None)),
+        Box::new(MctsBot::new(1000).with_seed(SEED)),
+    ];
+    for bot in &bots {
+        group.bench_function(bot.name(), |b| b.iter(|| black_box(bot.choose_move(&game))));
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_random_games,
+    bench_topology,
+    bench_engine_clone,
+    bench_yen,
+    bench_bots,
+);
+
+criterion_main!(benches);
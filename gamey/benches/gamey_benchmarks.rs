@@ -1,5 +1,5 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use gamey::{Coordinates, GameY, Movement, PlayerId, RenderOptions};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gamey::{Coordinates, GameY, MctsBot, Movement, PlayerId, RenderOptions, YBot};
 
 /// Benchmarks for coordinate conversion functions
 fn bench_coordinates(c: &mut Criterion) {
@@ -131,12 +131,20 @@ fn bench_render(c: &mut Criterion) {
         show_3d_coords: false,
         show_idx: false,
         show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
     };
 
     let options_full = RenderOptions {
         show_3d_coords: true,
         show_idx: true,
         show_colors: true,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
     };
 
     for board_size in [5, 10, 15].iter() {
@@ -198,6 +206,27 @@ fn bench_touches_side(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmarks MCTS playouts per second for several thread counts.
+///
+/// Build with `--features parallel` to run the playouts of a batch in
+/// parallel; without it the thread count only changes the batch size.
+fn bench_mcts_threads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mcts_threads");
+    group.sample_size(10);
+
+    let iterations = 2000;
+    let game = GameY::new(9);
+    group.throughput(Throughput::Elements(iterations as u64));
+    for threads in [1, 2, 4, 8].iter() {
+        group.bench_with_input(BenchmarkId::new("playouts", threads), threads, |b, &threads| {
+            let bot = MctsBot::new(iterations).with_threads(threads).with_seed(1);
+            b.iter(|| black_box(bot.choose_move(&game)))
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_coordinates,
@@ -205,6 +234,7 @@ criterion_group!(
     bench_add_move,
     bench_render,
     bench_touches_side,
+    bench_mcts_threads,
 );
 
 criterion_main!(benches);
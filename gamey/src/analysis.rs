@@ -0,0 +1,692 @@
+//! Position analysis shared by the evaluation, hints and reviews.
+//!
+//! [`connection_distance`] tells how many stones a player still needs to
+//! connect all the sides it has to connect, [`dead_cells`] finds empty
+//! cells that can no longer change the outcome, [`virtual_connections`]
+//! lists the bridges between a player's stones and [`ownership`] estimates
+//! which player will control each cell. [`report`] gathers them with the
+//! evaluation and the suggestion of a bot into a [`PositionReport`].
+
+use crate::{
+    BoardTopology, CellIndex, Coordinates, GameEngine, GameSnapshot, GameStatus, GameY, PlayerId, RegionMask,
+    TriangularTopology, YBot,
+};
+use crate::core::connection::next_player_after;
+use crate::eval::evaluate;
+use crate::review::{WIN_SCORE, eval_after};
+use crate::tactics::{immediate_win, must_block};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::VecDeque;
+
+/// Minimum number of stones `player` still has to place to connect the three
+/// sides of the board (or its own sides in games with more players).
+///
+/// Own stones are free, empty cells cost one stone and opponent stones cannot
+/// be crossed. Returns `Some(0)` if the player already connects its sides and
+/// `None` if the opponent has cut some side off.
+///
+/// See [`connection_distance_in`] for how the distance is computed.
+pub fn connection_distance(game: &GameY, player: PlayerId) -> Option<u32> {
+    connection_distance_in(game.engine(), player)
+}
+
+/// [`connection_distance`] on any [`BoardTopology`].
+///
+/// Each side the player must connect is a virtual node joined to the cells
+/// on that side, and a 0-1 BFS gives the distance from every cell to every
+/// side. A set of cells connecting three sides always contains a cell from
+/// which three disjoint paths lead to the sides (a Steiner tree with three
+/// terminals has at most one branching node), so the minimum over all cells
+/// of the three distances, counting the meeting cell once, is exact. With
+/// more than three sides it would only be an upper bound.
+pub fn connection_distance_in<T: BoardTopology>(
+    engine: &GameEngine<T>,
+    player: PlayerId,
+) -> Option<u32> {
+    let sides = needed_sides(engine, player);
+    if sides.is_empty() {
+        return Some(0);
+    }
+    let distances: Vec<Vec<Option<u32>>> = sides
+        .iter()
+        .map(|side| side_distances(engine, player, *side))
+        .collect();
+
+    (0..engine.topology().total_cells())
+        .filter_map(|cell| {
+            let own_cost = cell_cost(engine, player, cell)?;
+            let mut total = 0;
+            for side in &distances {
+                total += side[cell]?;
+            }
+            // The meeting cell is counted once per side, keep it only once
+            Some(total - own_cost * (sides.len() as u32 - 1))
+        })
+        .min()
+}
+
+/// Two stones of the same player that the opponent cannot separate: they share
+/// exactly two empty neighbors, the carriers, and if the opponent takes one the
+/// player answers on the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bridge {
+    /// The end with the smaller index.
+    pub a: Coordinates,
+    /// The other end.
+    pub b: Coordinates,
+    /// The two empty cells that connect the ends.
+    pub carriers: [Coordinates; 2],
+}
+
+/// Bridges between the stones of `player`, ordered by the index of their ends.
+///
+/// Two stones form a bridge when they are not neighbors and share exactly two
+/// neighbors, both empty. Ends that already belong to the same group are
+/// included; the bridge then only adds a second way to connect them.
+pub fn virtual_connections(game: &GameY, player: PlayerId) -> Vec<Bridge> {
+    let size = game.board_size();
+    let coords = |cell: CellIndex| Coordinates::from_index(cell as u32, size);
+    bridge_patterns(game.engine(), player)
+        .into_iter()
+        .filter(|(_, _, carriers)| carriers.iter().all(|c| game.engine().occupant(*c).is_none()))
+        .map(|(a, b, [c1, c2])| Bridge {
+            a: coords(a),
+            b: coords(b),
+            carriers: [coords(c1), coords(c2)],
+        })
+        .collect()
+}
+
+/// Pairs of stones of `player` with exactly two common neighbors, none of them
+/// owned by `player`, whatever else occupies the carriers.
+pub(crate) fn bridge_patterns<T: BoardTopology>(
+    engine: &GameEngine<T>,
+    player: PlayerId,
+) -> Vec<(CellIndex, CellIndex, [CellIndex; 2])> {
+    let mut patterns = Vec::new();
+    for a in 0..engine.topology().total_cells() {
+        if engine.occupant(a) != Some(player) {
+            continue;
+        }
+        let around_a = engine.topology().get_neighbors(a);
+        // Candidate ends are the neighbors of the neighbors of `a`
+        let mut candidates: Vec<CellIndex> = around_a
+            .iter()
+            .flat_map(|n| engine.topology().get_neighbors(*n).iter().copied())
+            .filter(|b| *b > a && engine.occupant(*b) == Some(player) && !around_a.contains(b))
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        for b in candidates {
+            let around_b = engine.topology().get_neighbors(b);
+            let common: Vec<CellIndex> = around_a.iter().copied().filter(|n| around_b.contains(n)).collect();
+            if let [c1, c2] = common[..]
+                && engine.occupant(c1) != Some(player)
+                && engine.occupant(c2) != Some(player)
+            {
+                patterns.push((a, b, [c1, c2]));
+            }
+        }
+    }
+    patterns
+}
+
+/// Empty cells that can no longer influence who wins, in index order.
+///
+/// A stone on a dead cell never helps any player, so bots can skip them and
+/// interfaces can dim them. Only cells proven dead by one of these rules are
+/// returned; other cells may be dead too:
+///
+/// - **Captured**: every neighbor belongs to the same player, whose groups
+///   around the cell already touch every needed side the cell touches, and no
+///   other player could win with a lone stone there.
+/// - **Cut off**: for every player, the empty and own cells reachable from the
+///   cell do not touch all the sides that player needs.
+pub fn dead_cells(game: &GameY) -> Vec<Coordinates> {
+    let players: Vec<PlayerId> = (0..game.num_players()).map(PlayerId::new).collect();
+    dead_cells_in(game.engine(), &players)
+        .into_iter()
+        .map(|cell| Coordinates::from_index(cell as u32, game.board_size()))
+        .collect()
+}
+
+/// [`dead_cells`] on any [`BoardTopology`], for the given players.
+pub fn dead_cells_in<T: BoardTopology>(engine: &GameEngine<T>, players: &[PlayerId]) -> Vec<CellIndex> {
+    let reach: Vec<Vec<RegionMask>> = players
+        .iter()
+        .map(|player| reachable_regions(engine, *player))
+        .collect();
+    (0..engine.topology().total_cells())
+        .filter(|cell| engine.occupant(*cell).is_none())
+        .filter(|cell| is_captured(engine, *cell, players) || is_cut_off(engine, *cell, players, &reach))
+        .collect()
+}
+
+/// Whether `cell` is surrounded by one player and adds nothing to its groups.
+fn is_captured<T: BoardTopology>(engine: &GameEngine<T>, cell: CellIndex, players: &[PlayerId]) -> bool {
+    let neighbors = engine.topology().get_neighbors(cell);
+    let Some(owner) = neighbors.first().and_then(|n| engine.occupant(*n)) else {
+        return false;
+    };
+    if neighbors.iter().any(|n| engine.occupant(*n) != Some(owner)) {
+        return false;
+    }
+    let regions = engine.topology().get_cell_regions(cell);
+    let needed = engine.topology().winning_mask_for(owner);
+    // Joining groups that already touch everything the cell adds changes nothing
+    let joined = neighbors
+        .iter()
+        .fold(regions, |acc, n| acc | engine.regions_of_group(*n).unwrap_or(0))
+        & needed;
+    let adds_nothing = neighbors
+        .iter()
+        .all(|n| engine.regions_of_group(*n).unwrap_or(0) & needed == joined);
+    // Any other player's stone there stays alone forever
+    let useless_to_others = players.iter().filter(|p| **p != owner).all(|p| {
+        let needed = engine.topology().winning_mask_for(*p);
+        regions & needed != needed
+    });
+    adds_nothing && useless_to_others
+}
+
+/// Whether no player can build a winning chain through `cell`.
+fn is_cut_off<T: BoardTopology>(
+    engine: &GameEngine<T>,
+    cell: CellIndex,
+    players: &[PlayerId],
+    reach: &[Vec<RegionMask>],
+) -> bool {
+    players.iter().zip(reach).all(|(player, reach)| {
+        let needed = engine.topology().winning_mask_for(*player);
+        reach[cell] & needed != needed
+    })
+}
+
+/// For every cell, the regions touched by the component of empty and own
+/// cells of `player` containing it (0 for opponent stones).
+fn reachable_regions<T: BoardTopology>(engine: &GameEngine<T>, player: PlayerId) -> Vec<RegionMask> {
+    let total = engine.topology().total_cells();
+    let mut component: Vec<Option<usize>> = vec![None; total];
+    let mut regions: Vec<RegionMask> = Vec::new();
+    for start in 0..total {
+        if component[start].is_some() || cell_cost(engine, player, start).is_none() {
+            continue;
+        }
+        let id = regions.len();
+        let mut touched = 0;
+        let mut stack = vec![start];
+        component[start] = Some(id);
+        while let Some(cell) = stack.pop() {
+            touched |= engine.topology().get_cell_regions(cell);
+            for &neighbor in engine.topology().get_neighbors(cell) {
+                if component[neighbor].is_none() && cell_cost(engine, player, neighbor).is_some() {
+                    component[neighbor] = Some(id);
+                    stack.push(neighbor);
+                }
+            }
+        }
+        regions.push(touched);
+    }
+    component
+        .into_iter()
+        .map(|id| id.map_or(0, |id| regions[id]))
+        .collect()
+}
+
+/// For every cell index, the fraction of `playouts` random playouts in which
+/// player 0 ends up owning the cell.
+///
+/// Each playout copies the engine and fills the empty cells at random in
+/// turn order until a player connects its sides; the cells still empty then
+/// belong to the winner, who controls the board. Stones are 1.0 or 0.0
+/// without playing, and so are the empty cells of a finished game, which go
+/// to its winner. The same `seed` always gives the same values.
+pub fn ownership(game: &GameY, playouts: u32, seed: u64) -> Vec<f32> {
+    ownership_on(game.engine(), game.status(), game.num_players(), playouts, seed)
+}
+
+/// Like [`ownership`], for the position of a [`GameSnapshot`].
+pub fn snapshot_ownership(snapshot: &GameSnapshot, playouts: u32, seed: u64) -> Vec<f32> {
+    ownership_on(&snapshot.engine(), snapshot.status(), snapshot.num_players(), playouts, seed)
+}
+
+fn ownership_on(
+    engine: &GameEngine<TriangularTopology>,
+    status: &GameStatus,
+    num_players: u32,
+    playouts: u32,
+    seed: u64,
+) -> Vec<f32> {
+    let total = engine.topology().total_cells();
+    let first = PlayerId::new(0);
+    let empty: Vec<CellIndex> = (0..total).filter(|cell| engine.occupant(*cell).is_none()).collect();
+    let mut owned = vec![0u32; total];
+    let playouts = playouts.max(1);
+    match status {
+        GameStatus::Finished { winner, .. } => {
+            if *winner == first {
+                for &cell in &empty {
+                    owned[cell] = playouts;
+                }
+            }
+        }
+        GameStatus::Ongoing { next_player } => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut order = empty.clone();
+            for _ in 0..playouts {
+                let mut board = engine.clone();
+                order.shuffle(&mut rng);
+                let mut player = *next_player;
+                let mut winner = None;
+                for &cell in &order {
+                    if board.make_move(cell, player) == Ok(true) {
+                        winner = Some(player);
+                        break;
+                    }
+                    player = next_player_after(player, num_players);
+                }
+                for &cell in &empty {
+                    if board.occupant(cell).or(winner) == Some(first) {
+                        owned[cell] += 1;
+                    }
+                }
+            }
+        }
+    }
+    (0..total)
+        .map(|cell| match engine.occupant(cell) {
+            Some(player) if player == first => 1.0,
+            Some(_) => 0.0,
+            None => owned[cell] as f32 / playouts as f32,
+        })
+        .collect()
+}
+
+/// The sides `player` must connect, one bit each.
+pub(crate) fn needed_sides<T: BoardTopology>(engine: &GameEngine<T>, player: PlayerId) -> Vec<RegionMask> {
+    let winning = engine.topology().winning_mask_for(player);
+    (0..RegionMask::BITS)
+        .map(|bit| 1 << bit)
+        .filter(|bit| winning & bit != 0)
+        .collect()
+}
+
+/// Cost of occupying `cell` for `player`: 0 if already own, 1 if empty, `None` if blocked.
+fn cell_cost<T: BoardTopology>(engine: &GameEngine<T>, player: PlayerId, cell: CellIndex) -> Option<u32> {
+    match engine.occupant(cell) {
+        Some(p) if p == player => Some(0),
+        Some(_) => None,
+        None => Some(1),
+    }
+}
+
+/// 0-1 BFS distances from the virtual node of `side` to every cell, including
+/// the cost of the cell itself.
+pub(crate) fn side_distances<T: BoardTopology>(
+    engine: &GameEngine<T>,
+    player: PlayerId,
+    side: RegionMask,
+) -> Vec<Option<u32>> {
+    let total = engine.topology().total_cells();
+    let mut dist: Vec<Option<u32>> = vec![None; total];
+    let mut queue = VecDeque::new();
+
+    // The virtual side node reaches every cell on the side
+    for (cell, slot) in dist.iter_mut().enumerate() {
+        if engine.topology().get_cell_regions(cell) & side != 0
+            && let Some(cost) = cell_cost(engine, player, cell)
+        {
+            *slot = Some(cost);
+            if cost == 0 {
+                queue.push_front(cell);
+            } else {
+                queue.push_back(cell);
+            }
+        }
+    }
+
+    while let Some(cell) = queue.pop_front() {
+        let current = dist[cell].unwrap_or(0);
+        for &neighbor in engine.topology().get_neighbors(cell) {
+            let Some(cost) = cell_cost(engine, player, neighbor) else {
+                continue;
+            };
+            let candidate = current + cost;
+            if dist[neighbor].is_none_or(|d| candidate < d) {
+                dist[neighbor] = Some(candidate);
+                if cost == 0 {
+                    queue.push_front(neighbor);
+                } else {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// A move of a [`PositionReport`] with its score.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CandidateMove {
+    /// Index of the cell, counting row by row from the top.
+    pub index: u32,
+    /// Coordinates of the cell.
+    pub coords: Coordinates,
+    /// Evaluation for the player to move after the stone is placed.
+    pub score: f32,
+}
+
+/// Everything [`report`] finds out about a position.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PositionReport {
+    /// Size of the board.
+    pub size: u32,
+    /// The player the report is for: the one to move, or the winner of a finished game.
+    pub player: u32,
+    /// Whether the game is over.
+    pub finished: bool,
+    /// Static evaluation for `player`, between `-WIN_SCORE` and `WIN_SCORE`.
+    pub evaluation: f32,
+    /// The bot asked for a suggestion.
+    pub bot: String,
+    /// The move the bot would play, if it found one.
+    pub bot_move: Option<CandidateMove>,
+    /// The best moves by static evaluation, best first.
+    pub top_moves: Vec<CandidateMove>,
+    /// [`connection_distance`] of every player, `None` if cut off.
+    pub connection_distances: Vec<Option<u32>>,
+    /// A cell that wins at once for `player`, if there is one.
+    pub winning_move: Option<Coordinates>,
+    /// The cells `player` has to block not to lose on the next move, by index.
+    pub must_block: Vec<Coordinates>,
+}
+
+/// Analyzes `game` for the player to move: its evaluation, the move `bot`
+/// suggests, the `top` best moves by [`crate::eval::evaluate`], the
+/// connection distance of every player and the wins and losses one move away.
+///
+/// Scores are those of the game review, so decided positions count as
+/// `±WIN_SCORE`. A finished game is reported for its winner, with no moves.
+pub fn report(game: &GameY, bot: &dyn YBot, top: usize) -> PositionReport {
+    let size = game.board_size();
+    let (player, finished) = match game.status() {
+        GameStatus::Ongoing { next_player } => (*next_player, false),
+        GameStatus::Finished { winner, .. } => (*winner, true),
+    };
+    let candidate = |coords: Coordinates| CandidateMove {
+        index: coords.to_index(size),
+        coords,
+        score: eval_after(game, player, coords),
+    };
+    let mut moves: Vec<Coordinates> = if finished {
+        Vec::new()
+    } else {
+        game.available_cells().iter().map(|cell| Coordinates::from_index(*cell, size)).collect()
+    };
+    moves.sort_by_key(|coords| coords.to_index(size));
+    let mut top_moves: Vec<CandidateMove> = moves.into_iter().map(candidate).collect();
+    // The sort is stable, so equal scores keep the smaller index first
+    top_moves.sort_by(|a, b| b.score.total_cmp(&a.score));
+    top_moves.truncate(top);
+    let mut blocks = if finished { Vec::new() } else { must_block(game, player) };
+    blocks.sort_by_key(|coords| coords.to_index(size));
+    PositionReport {
+        size,
+        player: player.id(),
+        finished,
+        evaluation: evaluate(game, player).clamp(-WIN_SCORE, WIN_SCORE),
+        bot: bot.name().to_string(),
+        bot_move: if finished { None } else { bot.choose_move(game).map(candidate) },
+        top_moves,
+        connection_distances: (0..game.num_players())
+            .map(|id| connection_distance(game, PlayerId::new(id)))
+            .collect(),
+        winning_move: if finished { None } else { immediate_win(game, player) },
+        must_block: blocks,
+    }
+}
+
+impl fmt::Display for CandidateMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>3} {:<12} {:+.2}", self.index, self.coords.to_string(), self.score)
+    }
+}
+
+impl fmt::Display for PositionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.finished {
+            writeln!(f, "Game over, won by player {}", self.player)?;
+        } else {
+            writeln!(f, "Player {} to move", self.player)?;
+        }
+        writeln!(f, "Evaluation: {:+.2}", self.evaluation)?;
+        for (player, distance) in self.connection_distances.iter().enumerate() {
+            match distance {
+                Some(distance) => writeln!(f, "Connection distance of player {}: {}", player, distance)?,
+                None => writeln!(f, "Connection distance of player {}: cut off", player)?,
+            }
+        }
+        if let Some(coords) = &self.winning_move {
+            writeln!(f, "Wins at once: {} {}", coords.to_index(self.size), coords)?;
+        }
+        if !self.must_block.is_empty() {
+            let cells: Vec<String> = self
+                .must_block
+                .iter()
+                .map(|coords| format!("{} {}", coords.to_index(self.size), coords))
+                .collect();
+            writeln!(f, "Must block: {}", cells.join(", "))?;
+        }
+        match &self.bot_move {
+            Some(chosen) => writeln!(f, "{} plays: {}", self.bot, chosen)?,
+            None => writeln!(f, "{} has no move", self.bot)?,
+        }
+        if !self.top_moves.is_empty() {
+            writeln!(f, "Top moves:")?;
+            for (rank, candidate) in self.top_moves.iter().enumerate() {
+                writeln!(f, "{:>3}. {}", rank + 1, candidate)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameAction, Movement, YEN};
+
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    fn position(size: u32, layout: &str) -> GameY {
+        GameY::try_from(YEN::new(size, 0, vec!['B', 'R'], layout.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_empty_board() {
+        // A size n board needs a chain of n cells to touch the three sides
+        for size in 1..=6 {
+            let game = GameY::new(size);
+            assert_eq!(connection_distance(&game, PlayerId::new(0)), Some(size));
+        }
+    }
+
+    #[test]
+    fn test_own_stones_are_free() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 3, 0));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        assert_eq!(connection_distance(&game, PlayerId::new(0)), Some(2));
+    }
+
+    #[test]
+    fn test_single_stone_saves_one() {
+        // The distances of a cell to the three sides add up to size - 1
+        let game = position(5, "./../.../.B../.....");
+        assert_eq!(connection_distance(&game, PlayerId::new(0)), Some(4));
+        let game = position(5, "./../..B/..../.....");
+        assert_eq!(connection_distance(&game, PlayerId::new(0)), Some(4));
+    }
+
+    #[test]
+    fn test_opponent_stones_force_detours() {
+        // Red's wall on the bottom row leaves Blue only the corner at the right
+        let game = position(3, "./../RR.");
+        assert_eq!(connection_distance(&game, PlayerId::new(0)), Some(3));
+        assert_eq!(connection_distance(&game, PlayerId::new(1)), Some(1));
+    }
+
+    #[test]
+    fn test_connected_player_needs_nothing() {
+        let game = position(3, "B/.B/.B.");
+        assert!(game.check_game_over());
+        assert_eq!(connection_distance(&game, PlayerId::new(0)), Some(0));
+        assert_eq!(connection_distance(&game, PlayerId::new(1)), None);
+    }
+
+    #[test]
+    fn test_blocked() {
+        // Player 1 owns the whole bottom row, player 0 can never reach it
+        let mut game = GameY::new(2);
+        play(&mut game, 0, Coordinates::new(1, 0, 0));
+        play(&mut game, 1, Coordinates::new(0, 1, 0));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        play(&mut game, 1, Coordinates::new(0, 0, 1));
+        assert_eq!(connection_distance(&game, PlayerId::new(0)), None);
+    }
+
+    #[test]
+    fn test_three_player_goals() {
+        // With three players each one connects a pair of sides; corners belong to
+        // no side, so the two cells next to a corner are needed
+        let game = GameY::with_players(4, 3);
+        for player in 0..3 {
+            assert_eq!(connection_distance(&game, PlayerId::new(player)), Some(2));
+        }
+    }
+
+    #[test]
+    fn test_cell_captured_by_one_player_is_dead() {
+        // The empty cell (1,1,2) is surrounded by Blue and touches no side; the
+        // corner (0,0,4) only touches sides that its Blue neighbors already touch
+        let game = position(5, "./../BB./B.B./.BB..");
+        assert_eq!(
+            dead_cells(&game),
+            vec![Coordinates::new(1, 1, 2), Coordinates::new(0, 0, 4)]
+        );
+    }
+
+    #[test]
+    fn test_cell_with_an_empty_neighbor_is_not_dead() {
+        // Same pocket, but Blue's stone at (0,2,2) is missing so Red could escape
+        let game = position(5, "./../BB./B.B./.B...");
+        assert_eq!(dead_cells(&game), vec![Coordinates::new(0, 0, 4)]);
+        // Mixed neighbors are not enough either
+        let game = position(5, "./../BB./B.B./.BR..");
+        assert!(!dead_cells(&game).contains(&Coordinates::new(1, 1, 2)));
+    }
+
+    #[test]
+    fn test_cell_cut_off_from_both_players_is_dead() {
+        // Blue can only reach the top corner from (3,0,1), Red from its three stones
+        // above Blue's wall; neither reaches the bottom side that way
+        let game = position(5, "./BR/RRB/BBB./...RR");
+        assert!(!game.check_game_over());
+        assert_eq!(dead_cells(&game), vec![Coordinates::new(4, 0, 0)]);
+    }
+
+    #[test]
+    fn test_empty_board_has_no_dead_cells() {
+        assert!(dead_cells(&GameY::new(6)).is_empty());
+        assert!(dead_cells(&GameY::with_players(6, 3)).is_empty());
+    }
+
+    #[test]
+    fn test_ownership_of_a_decided_position() {
+        // Blue's row cuts Red off from the bottom side, so every playout is a Blue win
+        let game = position(4, "R/.R/BBB/....");
+        let values = ownership(&game, 200, 7);
+        assert_eq!(snapshot_ownership(&game.snapshot(), 200, 7), values);
+        assert_eq!(values.len(), 10);
+        assert_eq!(values[0], 0.0);
+        for cell in [3, 4, 5] {
+            assert_eq!(values[cell], 1.0);
+        }
+        // Red may still drop a stone there before Blue completes the connection
+        for cell in [1, 6, 7, 8, 9] {
+            assert!(values[cell] > 0.9, "cell {} is {}", cell, values[cell]);
+        }
+    }
+
+    #[test]
+    fn test_ownership_of_an_open_position() {
+        let game = GameY::new(5);
+        let values = ownership(&game, 300, 1);
+        assert_eq!(values, ownership(&game, 300, 1));
+        assert!(values.iter().all(|v| (0.2..=0.8).contains(v)), "{:?}", values);
+    }
+
+    #[test]
+    fn test_ownership_of_a_finished_game() {
+        // Blue connects the sides along y = 0, the empty cells go to Blue
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(2, 0, 0));
+        play(&mut game, 1, Coordinates::new(1, 1, 0));
+        play(&mut game, 0, Coordinates::new(1, 0, 1));
+        play(&mut game, 1, Coordinates::new(0, 1, 1));
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+        assert!(game.check_game_over());
+        let values = ownership(&game, 10, 0);
+        let red = [Coordinates::new(1, 1, 0), Coordinates::new(0, 1, 1)].map(|c| c.to_index(3) as usize);
+        for (cell, value) in values.iter().enumerate() {
+            assert_eq!(*value, if red.contains(&cell) { 0.0 } else { 1.0 });
+        }
+    }
+
+
+    #[test]
+    fn test_report_of_a_finished_game_has_no_moves() {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        let report = report(&game, &crate::RandomBot, 5);
+        assert!(report.finished);
+        assert_eq!(report.player, 1);
+        assert_eq!(report.bot_move, None);
+        assert!(report.top_moves.is_empty());
+        assert_eq!(report.connection_distances.len(), 2);
+        assert!(report.to_string().starts_with("Game over, won by player 1"));
+    }
+
+    #[test]
+    fn test_report_lists_the_threats_to_block() {
+        // Red, to move, faces Blue's win in one at the corner
+        let yen = YEN::new(4, 1, vec!['B', 'R'], "R/.R/..R/.BBB".to_string());
+        let game = GameY::try_from(yen).unwrap();
+        let report = report(&game, &crate::RandomBot, 3);
+        assert_eq!(report.player, 1);
+        assert_eq!(report.top_moves.len(), 3);
+        assert!(report.top_moves.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        let blocks: Vec<u32> = report.must_block.iter().map(|coords| coords.to_index(4)).collect();
+        assert_eq!(blocks, vec![3, 6]);
+        assert!(report.to_string().contains("Must block: 3 (1, 0, 2), 6 (0, 0, 3)"));
+    }
+}
@@ -0,0 +1,539 @@
+//! Opening books and a bot layer that consults them.
+//!
+//! An [`OpeningBook`] maps known positions to recommended moves. A [`BookBot`]
+//! wraps any [`YBot`], plays from the book while the position is known and
+//! falls back to the wrapped bot once the game leaves the book.
+//!
+//! # File format
+//!
+//! Books are JSON documents for a single board size. Positions are written as
+//! YEN layout strings and moves as cell indices; when a position has several
+//! moves, one is picked at random proportionally to its weight. Entries are
+//! stored by [`GameY::canonical_key`], so an entry also covers every position
+//! symmetric to it, with its moves mapped accordingly.
+//!
+//! ```json
+//! {
+//!   "size": 3,
+//!   "entries": [
+//!     { "layout": "./../...", "moves": [ { "index": 4, "weight": 1.0 } ] },
+//!     { "layout": "./../.B.", "moves": [ { "index": 2, "weight": 3.0 },
+//!                                        { "index": 1, "weight": 1.0 } ] }
+//!   ]
+//! }
+//! ```
+
+use crate::{AnalysisUpdate, BotContext, CellIndex, Coordinates, GameY, GameYError, MoveStats, Movement, PlayerId, YBot, YEN};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+
+/// A recommended move in a book position.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BookMove {
+    /// Cell index of the move.
+    pub index: u32,
+    /// Relative weight used when the position has several moves.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// A position of the book with its recommended moves.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BookEntry {
+    /// YEN layout of the position.
+    pub layout: String,
+    /// Recommended moves for the position.
+    pub moves: Vec<BookMove>,
+}
+
+/// The on-disk representation of an opening book.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BookFile {
+    /// The board size every entry refers to.
+    pub size: u32,
+    /// The positions of the book.
+    pub entries: Vec<BookEntry>,
+}
+
+/// A book position as stored in memory.
+#[derive(Debug, Clone)]
+struct StoredEntry {
+    /// Cell occupancy of the position as written in the file.
+    cells: Vec<u8>,
+    moves: Vec<BookMove>,
+}
+
+/// A validated opening book for a single board size.
+#[derive(Debug, Clone)]
+pub struct OpeningBook {
+    size: u32,
+    positions: HashMap<u64, StoredEntry>,
+}
+
+impl OpeningBook {
+    /// Loads and validates a book from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(&path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read opening book: {}", filename),
+            error: e.to_string(),
+        })?;
+        let file: BookFile =
+            serde_json::from_str(&content).map_err(|e| GameYError::InvalidOpeningBook {
+                path: filename.clone(),
+                message: e.to_string(),
+            })?;
+        Self::from_file(file).map_err(|message| GameYError::InvalidOpeningBook {
+            path: filename,
+            message,
+        })
+    }
+
+    /// Builds a book from its file representation, validating every entry.
+    ///
+    /// Each layout must describe a legal position of the declared size, and
+    /// every move must be an empty cell of that position with a positive weight.
+    pub fn from_file(file: BookFile) -> Result<Self, String> {
+        let mut positions = HashMap::new();
+        for (i, entry) in file.entries.into_iter().enumerate() {
+            let rows = entry.layout.split('/').count() as u32;
+            if rows != file.size {
+                return Err(format!(
+                    "entry {} has {} rows but the book is for size {} (stale book?)",
+                    i, rows, file.size
+                ));
+            }
+            let yen = YEN::new(file.size, 0, vec!['B', 'R'], entry.layout.clone());
+            let game = GameY::try_from(yen).map_err(|e| format!("entry {}: {}", i, e))?;
+            if entry.moves.is_empty() {
+                return Err(format!("entry {} has no moves", i));
+            }
+            for mv in &entry.moves {
+                if !game.available_cells().contains(&mv.index) {
+                    return Err(format!(
+                        "entry {}: move {} is not an empty cell of the position",
+                        i, mv.index
+                    ));
+                }
+                if mv.weight <= 0.0 || !mv.weight.is_finite() {
+                    return Err(format!("entry {}: move {} has an invalid weight", i, mv.index));
+                }
+            }
+            let identity: Vec<CellIndex> = (0..game.total_cells() as CellIndex).collect();
+            positions.insert(
+                game.canonical_key(),
+                StoredEntry {
+                    cells: game.cell_codes(&identity),
+                    moves: entry.moves,
+                },
+            );
+        }
+        Ok(Self {
+            size: file.size,
+            positions,
+        })
+    }
+
+    /// Returns the board size of the book.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the book has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the recommended moves for the game's position, if it is in the book.
+    ///
+    /// When the book entry is a symmetric image of the position, the returned
+    /// cell indices are mapped back onto the game's board.
+    pub fn lookup(&self, game: &GameY) -> Option<Vec<BookMove>> {
+        if game.board_size() != self.size {
+            return None;
+        }
+        let entry = self.positions.get(&game.canonical_key())?;
+        let perm = game
+            .engine()
+            .topology()
+            .symmetries()
+            .into_iter()
+            .find(|perm| game.cell_codes(perm) == entry.cells)?;
+        Some(
+            entry
+                .moves
+                .iter()
+                .filter_map(|mv| {
+                    let cell = perm.iter().position(|&image| image == mv.index as CellIndex)?;
+                    Some(BookMove {
+                        index: cell as u32,
+                        weight: mv.weight,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Picks one of the recommended moves for the game's position, weighted at random.
+    pub fn choose(&self, game: &GameY) -> Option<Coordinates> {
+        let moves = self.lookup(game)?;
+        let total: f64 = moves.iter().map(|m| m.weight).sum();
+        let mut target = rand::rng().random_range(0.0..total);
+        let chosen = moves
+            .iter()
+            .find(|m| {
+                target -= m.weight;
+                target < 0.0
+            })
+            .unwrap_or(&moves[moves.len() - 1]);
+        Some(Coordinates::from_index(chosen.index, self.size))
+    }
+}
+
+/// A bot that plays from an opening book and defers to an inner bot otherwise.
+///
+/// The book is keyed by the stones alone and its moves are for the player
+/// whose turn it was in the games it was built from, so a `BookBot` does not
+/// play out of turn even if its inner bot can.
+pub struct BookBot<B: YBot> {
+    book: OpeningBook,
+    inner: B,
+}
+
+impl<B: YBot> BookBot<B> {
+    /// Wraps `inner` with the given book.
+    pub fn new(book: OpeningBook, inner: B) -> Self {
+        Self { book, inner }
+    }
+
+    /// Returns the book used by this bot.
+    pub fn book(&self) -> &OpeningBook {
+        &self.book
+    }
+}
+
+impl<B: YBot> YBot for BookBot<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.book
+            .choose(board)
+            .or_else(|| self.inner.choose_move(board))
+    }
+
+    fn choose_move_with_deadline(&self, board: &GameY, deadline: Instant) -> Option<Coordinates> {
+        self.book
+            .choose(board)
+            .or_else(|| self.inner.choose_move_with_deadline(board, deadline))
+    }
+
+    /// Book moves are reported with zero work; other moves with the inner bot's statistics.
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        let start = Instant::now();
+        match self.book.choose(board) {
+            Some(coords) => {
+                let stats = MoveStats {
+                    elapsed: start.elapsed(),
+                    ..MoveStats::default()
+                };
+                (Some(coords), stats)
+            }
+            None => self.inner.choose_move_with_stats(board, deadline),
+        }
+    }
+
+    /// Book moves are placed; out of book the inner bot may also resign.
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        let start = Instant::now();
+        match self.book.choose(board) {
+            Some(coords) => {
+                let stats = MoveStats {
+                    elapsed: start.elapsed(),
+                    ..MoveStats::default()
+                };
+                (crate::resign::place(board, Some(coords)), stats)
+            }
+            None => self.inner.choose_movement(board, deadline),
+        }
+    }
+
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        let start = Instant::now();
+        match self.book.choose(board) {
+            Some(coords) => {
+                let stats = MoveStats {
+                    elapsed: start.elapsed(),
+                    ..MoveStats::default()
+                };
+                (Some(coords), stats)
+            }
+            None => self.inner.choose_move_with_context(board, context, deadline),
+        }
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        let start = Instant::now();
+        match self.book.choose(board) {
+            Some(coords) => {
+                let stats = MoveStats {
+                    elapsed: start.elapsed(),
+                    ..MoveStats::default()
+                };
+                (crate::resign::place(board, Some(coords)), stats)
+            }
+            None => self.inner.choose_movement_with_context(board, context, deadline),
+        }
+    }
+
+    /// A book move is reported once; other positions are analyzed by the inner bot.
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        match self.book.choose(board) {
+            Some(coords) => {
+                let best_index = coords.to_index(board.board_size());
+                sink(AnalysisUpdate {
+                    depth_or_playouts: 0,
+                    best_index,
+                    score: None,
+                    pv: vec![best_index],
+                });
+            }
+            None => self.inner.analyze_progressive(board, sink, deadline),
+        }
+    }
+
+    fn should_swap(&self, board: &GameY) -> bool {
+        self.inner.should_swap(board)
+    }
+
+    fn evaluate(&self, board: &GameY, player: PlayerId) -> Option<f32> {
+        self.inner.evaluate(board, player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    /// A bot that always answers with the last cell of the board.
+    struct LastCellBot;
+
+    impl YBot for LastCellBot {
+        fn name(&self) -> &str {
+            "last_cell_bot"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            let idx = *board.available_cells().iter().max()?;
+            Some(Coordinates::from_index(idx, board.board_size()))
+        }
+    }
+
+    const TWO_ENTRY_BOOK: &str = r#"{
+        "size": 3,
+        "entries": [
+            { "layout": "./../...", "moves": [ { "index": 4, "weight": 1.0 } ] },
+            { "layout": "./../.B.", "moves": [ { "index": 2 } ] }
+        ]
+    }"#;
+
+    fn write_book(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    fn play(game: &mut GameY, player: u32, idx: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::from_index(idx, game.board_size()),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_two_entry_book() {
+        let file = write_book(TWO_ENTRY_BOOK);
+        let book = OpeningBook::load(file.path()).unwrap();
+        assert_eq!(book.size(), 3);
+        assert_eq!(book.len(), 2);
+    }
+
+    #[test]
+    fn test_book_bot_plays_book_then_defers() {
+        let file = write_book(TWO_ENTRY_BOOK);
+        let bot = BookBot::new(OpeningBook::load(file.path()).unwrap(), LastCellBot);
+        let mut game = GameY::new(3);
+
+        // Move one: in book
+        let first = bot.choose_move(&game).unwrap();
+        assert_eq!(first.to_index(3), 4);
+        play(&mut game, 0, 4);
+
+        // Move two (opponent): in book as well
+        assert_eq!(bot.choose_move(&game).unwrap().to_index(3), 2);
+        play(&mut game, 1, 0);
+
+        // Move three: out of book, the inner bot answers
+        let third = bot.choose_move(&game).unwrap();
+        assert_eq!(third.to_index(3), 5);
+    }
+
+    /// Plays like [`LastCellBot`] and records the board size of every context it is given.
+    struct ContextSpyBot(std::sync::Arc<std::sync::Mutex<Vec<u32>>>);
+
+    impl YBot for ContextSpyBot {
+        fn name(&self) -> &str {
+            "context_spy_bot"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            LastCellBot.choose_move(board)
+        }
+
+        fn choose_movement_with_context(
+            &self,
+            board: &GameY,
+            context: &BotContext,
+            deadline: Option<Instant>,
+        ) -> (Option<Movement>, MoveStats) {
+            self.0.lock().unwrap().push(context.board_size);
+            self.choose_movement(board, deadline)
+        }
+    }
+
+    #[test]
+    fn test_book_bot_passes_the_context_out_of_book() {
+        let file = write_book(TWO_ENTRY_BOOK);
+        let sizes = std::sync::Arc::default();
+        let bot = BookBot::new(OpeningBook::load(file.path()).unwrap(), ContextSpyBot(std::sync::Arc::clone(&sizes)));
+        let context = BotContext::new(3);
+        let mut game = GameY::new(3);
+
+        // In book, the inner bot is not asked
+        bot.choose_movement_with_context(&game, &context, None);
+        assert!(sizes.lock().unwrap().is_empty());
+
+        play(&mut game, 0, 5);
+        let (movement, _) = bot.choose_movement_with_context(&game, &context, None);
+        assert!(matches!(movement, Some(Movement::Placement { coords, .. }) if coords.to_index(3) == 4));
+        assert_eq!(*sizes.lock().unwrap(), [3]);
+    }
+
+    #[test]
+    fn test_book_bot_does_not_play_out_of_turn() {
+        let file = write_book(TWO_ENTRY_BOOK);
+        let bot = BookBot::new(OpeningBook::load(file.path()).unwrap(), crate::RandomBot);
+        assert!(crate::RandomBot.plays_out_of_turn());
+        assert!(!bot.plays_out_of_turn());
+    }
+
+    #[test]
+    fn test_symmetric_position_uses_mapped_book_move() {
+        // Blue in corner (2, 0, 0), book answers at (1, 1, 0)
+        let file = write_book(
+            r#"{ "size": 3, "entries": [ { "layout": "B/../...", "moves": [ { "index": 1 } ] } ] }"#,
+        );
+        let book = OpeningBook::load(file.path()).unwrap();
+
+        let mut reference = GameY::new(3);
+        play(&mut reference, 0, 0);
+        play(&mut reference, 1, 1);
+
+        // The same opening in the corner (0, 0, 2)
+        let mut game = GameY::new(3);
+        play(&mut game, 0, 5);
+        let answer = book.choose(&game).unwrap();
+        assert!(game.available_cells().contains(&answer.to_index(3)));
+        play(&mut game, 1, answer.to_index(3));
+        assert_eq!(game.canonical_key(), reference.canonical_key());
+    }
+
+    #[test]
+    fn test_book_ignores_other_sizes() {
+        let file = write_book(TWO_ENTRY_BOOK);
+        let book = OpeningBook::load(file.path()).unwrap();
+        assert!(book.lookup(&GameY::new(4)).is_none());
+    }
+
+    #[test]
+    fn test_stale_size_is_rejected() {
+        let file = write_book(
+            r#"{ "size": 4, "entries": [ { "layout": "./../...", "moves": [ { "index": 0 } ] } ] }"#,
+        );
+        let err = OpeningBook::load(file.path()).unwrap_err();
+        match err {
+            GameYError::InvalidOpeningBook { message, .. } => {
+                assert!(message.contains("entry 0"));
+                assert!(message.contains("size 4"));
+            }
+            other => panic!("Unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_occupied_book_move_is_rejected() {
+        let file = write_book(
+            r#"{ "size": 2, "entries": [ { "layout": "B/..", "moves": [ { "index": 0 } ] } ] }"#,
+        );
+        assert!(matches!(
+            OpeningBook::load(file.path()),
+            Err(GameYError::InvalidOpeningBook { .. })
+        ));
+    }
+
+    #[test]
+    fn test_malformed_json_is_rejected() {
+        let file = write_book("{ not json");
+        assert!(matches!(
+            OpeningBook::load(file.path()),
+            Err(GameYError::InvalidOpeningBook { .. })
+        ));
+    }
+
+    #[test]
+    fn test_weighted_choice_stays_in_book() {
+        let book = OpeningBook::from_file(BookFile {
+            size: 2,
+            entries: vec![BookEntry {
+                layout: "./..".to_string(),
+                moves: vec![
+                    BookMove { index: 1, weight: 3.0 },
+                    BookMove { index: 2, weight: 1.0 },
+                ],
+            }],
+        })
+        .unwrap();
+        let game = GameY::new(2);
+        for _ in 0..20 {
+            let idx = book.choose(&game).unwrap().to_index(2);
+            assert!(idx == 1 || idx == 2);
+        }
+    }
+}
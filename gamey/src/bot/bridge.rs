@@ -0,0 +1,204 @@
+//! A heuristic bot built on bridges.
+//!
+//! This module provides [`BridgeBot`], which plays the classic bridge strategy
+//! of Y: keep every bridge connected and grow the position with new bridges
+//! toward the side that is still furthest away.
+
+use crate::analysis::{bridge_patterns, needed_sides, side_distances};
+use crate::{BoardTopology, CellIndex, Coordinates, GameY, Movement, PlayerId, YBot, center_distance};
+
+/// A bot that defends its bridges and extends toward its furthest side.
+///
+/// On each move it, in order:
+///
+/// 1. wins if a single stone connects its sides,
+/// 2. answers an intrusion into the carrier of one of its bridges by playing
+///    the other carrier, preferring the bridge attacked by the last move,
+/// 3. otherwise extends toward the side it is furthest from, with a bridge
+///    from one of its stones if possible, or from the center on an empty board.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{BridgeBot, GameY, YBot};
+///
+/// let game = GameY::new(5);
+/// assert!(BridgeBot.choose_move(&game).is_some());
+/// ```
+pub struct BridgeBot;
+
+impl YBot for BridgeBot {
+    fn name(&self) -> &str {
+        "bridge_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let player = board.next_player()?;
+        let engine = board.engine();
+        let available: Vec<CellIndex> = board.available_cells().iter().map(|c| *c as CellIndex).collect();
+        let size = board.board_size();
+        let coords = |cell: CellIndex| Coordinates::from_index(cell as u32, size);
+
+        if let Some(cell) = available.iter().find(|cell| engine.would_win(**cell, player)) {
+            return Some(coords(*cell));
+        }
+        if let Some(cell) = defend_bridge(board, player) {
+            return Some(coords(cell));
+        }
+        extend(board, player, &available).map(coords)
+    }
+
+    fn plays_out_of_turn(&self) -> bool {
+        true
+    }
+}
+
+/// The empty carrier of a bridge whose other carrier the opponent took.
+fn defend_bridge(board: &GameY, player: PlayerId) -> Option<CellIndex> {
+    let engine = board.engine();
+    let last = match board.last_move() {
+        Some(Movement::Placement { coords, .. }) => Some(coords.to_index(board.board_size()) as CellIndex),
+        _ => None,
+    };
+    let mut defenses: Vec<(CellIndex, CellIndex)> = bridge_patterns(engine, player)
+        .into_iter()
+        .filter(|(a, b, _)| !engine.same_group(*a, *b))
+        .filter_map(|(_, _, [c1, c2])| match (engine.occupant(c1), engine.occupant(c2)) {
+            (Some(_), None) => Some((c1, c2)),
+            (None, Some(_)) => Some((c2, c1)),
+            _ => None,
+        })
+        .collect();
+    defenses.sort_by_key(|(intruded, _)| Some(*intruded) != last);
+    defenses.first().map(|(_, defense)| *defense)
+}
+
+/// The best cell to approach the side `player` is furthest from.
+fn extend(board: &GameY, player: PlayerId, available: &[CellIndex]) -> Option<CellIndex> {
+    let engine = board.engine();
+    let size = board.board_size();
+    let center = |cell: &CellIndex| center_distance(&Coordinates::from_index(*cell as u32, size), size);
+    let own: Vec<CellIndex> = (0..engine.topology().total_cells())
+        .filter(|cell| engine.occupant(*cell) == Some(player))
+        .collect();
+    if own.is_empty() {
+        return available.iter().copied().min_by(|a, b| center(a).total_cmp(&center(b)));
+    }
+
+    // Distance of every cell to the reachable side the stones are furthest from
+    let Some(distances) = needed_sides(engine, player)
+        .into_iter()
+        .map(|side| side_distances(engine, player, side))
+        .filter_map(|dist| {
+            let gap = own.iter().filter_map(|cell| dist[*cell]).min()?;
+            Some((gap, dist))
+        })
+        .max_by_key(|(gap, _)| *gap)
+        .map(|(_, dist)| dist)
+    else {
+        return available.first().copied();
+    };
+
+    // Rank by distance to that side, then bridges before plain neighbors, then centrality
+    let step = |cell: CellIndex| -> Option<u32> {
+        let around = engine.topology().get_neighbors(cell);
+        if own.iter().any(|stone| around.contains(stone)) {
+            return Some(1);
+        }
+        let bridged = own.iter().any(|stone| {
+            let common: Vec<&CellIndex> = engine
+                .topology()
+                .get_neighbors(*stone)
+                .iter()
+                .filter(|n| around.contains(n))
+                .collect();
+            common.len() == 2 && common.iter().all(|n| engine.occupant(**n).is_none())
+        });
+        bridged.then_some(0)
+    };
+    available
+        .iter()
+        .copied()
+        .filter_map(|cell| Some((cell, distances[cell]?, step(cell)?)))
+        .min_by(|(a, da, sa), (b, db, sb)| (da, sa).cmp(&(db, sb)).then(center(a).total_cmp(&center(b))))
+        .map(|(cell, _, _)| cell)
+        .or_else(|| available.iter().copied().min_by_key(|cell| distances[*cell].unwrap_or(u32::MAX)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::virtual_connections;
+
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    /// Blue's bridge between (2,1,1) and (0,2,2), with carriers (1,2,1) and (1,1,2).
+    fn bridged_game() -> GameY {
+        let mut game = GameY::new(5);
+        play(&mut game, 0, Coordinates::new(2, 1, 1));
+        play(&mut game, 1, Coordinates::new(4, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 2));
+        game
+    }
+
+    #[test]
+    fn test_bridge_bot_name() {
+        assert_eq!(BridgeBot.name(), "bridge_bot");
+    }
+
+    #[test]
+    fn test_defends_bridge_under_attack() {
+        let mut game = bridged_game();
+        play(&mut game, 1, Coordinates::new(1, 1, 2));
+        assert_eq!(BridgeBot.choose_move(&game), Some(Coordinates::new(1, 2, 1)));
+
+        let mut game = bridged_game();
+        play(&mut game, 1, Coordinates::new(1, 2, 1));
+        assert_eq!(BridgeBot.choose_move(&game), Some(Coordinates::new(1, 1, 2)));
+    }
+
+    #[test]
+    fn test_virtual_connections_lists_intact_bridges() {
+        let game = bridged_game();
+        let bridges = virtual_connections(&game, PlayerId::new(0));
+        assert_eq!(bridges.len(), 1);
+        let mut carriers = bridges[0].carriers;
+        carriers.sort_by_key(|c| c.to_index(5));
+        assert_eq!(carriers, [Coordinates::new(1, 1, 2), Coordinates::new(1, 2, 1)]);
+        assert!(virtual_connections(&game, PlayerId::new(1)).is_empty());
+
+        let mut attacked = game;
+        play(&mut attacked, 1, Coordinates::new(1, 1, 2));
+        assert!(virtual_connections(&attacked, PlayerId::new(0)).is_empty());
+    }
+
+    #[test]
+    fn test_takes_a_winning_move() {
+        // Blue's chain from the top corner only lacks the bottom side
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(2, 0, 0));
+        play(&mut game, 1, Coordinates::new(0, 0, 2));
+        play(&mut game, 0, Coordinates::new(1, 0, 1));
+        play(&mut game, 1, Coordinates::new(0, 2, 0));
+        assert_eq!(BridgeBot.choose_move(&game), Some(Coordinates::new(0, 1, 1)));
+    }
+
+    #[test]
+    fn test_opens_in_the_center_and_extends_with_bridges() {
+        let mut game = GameY::new(7);
+        let opening = BridgeBot.choose_move(&game).unwrap();
+        assert_eq!(opening, Coordinates::new(2, 2, 2));
+
+        play(&mut game, 0, opening);
+        play(&mut game, 1, Coordinates::new(6, 0, 0));
+        let next = BridgeBot.choose_move(&game).unwrap();
+        play(&mut game, 0, next);
+        assert_eq!(virtual_connections(&game, PlayerId::new(0)).len(), 1);
+    }
+}
@@ -0,0 +1,198 @@
+//! Tables of a board size shared by the bots across requests.
+//!
+//! A server answers positions of many sizes with the same bot instances.
+//! [`BotContexts`] builds the topology and the Zobrist keys of each size once
+//! and hands them to the bots as a [`BotContext`], through
+//! [`crate::YBot::choose_move_with_context`], so bots that need such tables do
+//! not rebuild them on every request or assume a single board size. The
+//! search bots key their positions with the Zobrist keys of the context and
+//! skip moves that its symmetries make equivalent.
+
+use crate::{BoardTopology, CellIndex, GameY, TriangularTopology, Zobrist};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The shared tables of one board size.
+///
+/// Cloning a context only clones the `Arc`s, so every clone shares the tables.
+#[derive(Debug, Clone)]
+pub struct BotContext {
+    /// Number of cells of each side of the board.
+    pub board_size: u32,
+    /// Neighbors, sides and symmetries of the two-player board.
+    pub topology: Arc<TriangularTopology>,
+    /// Zobrist keys of the cells of the board.
+    pub zobrist: Arc<Zobrist>,
+}
+
+impl BotContext {
+    /// Builds the tables of a board of `board_size`.
+    pub fn new(board_size: u32) -> Self {
+        let topology = TriangularTopology::new(board_size);
+        let zobrist = Zobrist::new(topology.total_cells());
+        BotContext {
+            board_size,
+            topology: Arc::new(topology),
+            zobrist: Arc::new(zobrist),
+        }
+    }
+
+    /// Builds the context of the board of `game`, sharing its topology.
+    ///
+    /// Bots asked to move without a context search with this one, so only
+    /// the Zobrist keys are built again.
+    pub fn of(game: &GameY) -> Self {
+        let topology = game.engine().topology().clone();
+        let zobrist = Zobrist::new(topology.total_cells());
+        BotContext {
+            board_size: game.board_size(),
+            topology: Arc::new(topology),
+            zobrist: Arc::new(zobrist),
+        }
+    }
+
+    /// The hash of the stones of `game` with the Zobrist keys of the context.
+    pub fn position_key(&self, game: &GameY) -> u64 {
+        game.engine()
+            .occupants()
+            .iter()
+            .enumerate()
+            .filter_map(|(cell, occupant)| occupant.map(|player| self.zobrist.key(cell, player)))
+            .fold(0, |key, stone| key ^ stone)
+    }
+
+    /// The free cells of `game`, keeping one of every set of cells that a
+    /// symmetry of the position maps onto each other.
+    ///
+    /// Such cells lead to the same position up to symmetry, so a search only
+    /// needs to try one of them. On a board of another size, or with more
+    /// than two players, whose sides tell the players apart, every free cell
+    /// is returned.
+    pub fn distinct_moves(&self, game: &GameY) -> Vec<u32> {
+        let available = game.available_cells().clone();
+        let occupants = game.engine().occupants();
+        if game.num_players() > 2
+            || game.board_size() != self.board_size
+            || occupants.len() != self.topology.total_cells()
+        {
+            return available;
+        }
+        // The first symmetry is the identity
+        let fixing: Vec<&[CellIndex]> = (1..6)
+            .map(|index| self.topology.symmetry(index))
+            .filter(|perm| (0..occupants.len()).all(|cell| occupants[perm[cell]] == occupants[cell]))
+            .collect();
+        let mut seen = vec![false; occupants.len()];
+        available
+            .into_iter()
+            .filter(|&cell| {
+                let cell = cell as CellIndex;
+                if seen[cell] {
+                    return false;
+                }
+                seen[cell] = true;
+                for perm in &fixing {
+                    seen[perm[cell]] = true;
+                }
+                true
+            })
+            .collect()
+    }
+}
+
+/// A cache of [`BotContext`]s by board size, safe to share between threads.
+#[derive(Debug, Default)]
+pub struct BotContexts {
+    contexts: Mutex<HashMap<u32, BotContext>>,
+}
+
+impl BotContexts {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the context of `board_size`, building it on first use.
+    pub fn get(&self, board_size: u32) -> BotContext {
+        let mut contexts = self.contexts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        contexts
+            .entry(board_size)
+            .or_insert_with(|| BotContext::new(board_size))
+            .clone()
+    }
+
+    /// Returns the board sizes with a cached context, in increasing order.
+    pub fn sizes(&self) -> Vec<u32> {
+        let contexts = self.contexts.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut sizes: Vec<u32> = contexts.keys().copied().collect();
+        sizes.sort_unstable();
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    #[test]
+    fn test_contexts_are_built_once_per_size() {
+        let contexts = BotContexts::new();
+        let five = contexts.get(5);
+        let again = contexts.get(5);
+        assert!(Arc::ptr_eq(&five.topology, &again.topology));
+        assert!(Arc::ptr_eq(&five.zobrist, &again.zobrist));
+
+        let nine = contexts.get(9);
+        assert_eq!(nine.board_size, 9);
+        assert_eq!(nine.topology.total_cells(), 45);
+        assert!(!Arc::ptr_eq(&five.topology, &nine.topology));
+        assert_eq!(contexts.sizes(), [5, 9]);
+    }
+
+    #[test]
+    fn test_context_keys_match_the_engine_hashes() {
+        let context = BotContext::new(4);
+        let mut game = crate::GameY::new(4);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: crate::Coordinates::from_index(3, 4),
+        })
+        .unwrap();
+        assert_eq!(game.position_hash(), context.zobrist.key(3, PlayerId::new(0)));
+        assert_eq!(context.position_key(&game), game.position_hash());
+    }
+
+    #[test]
+    fn test_distinct_moves_skip_symmetric_cells() {
+        let context = BotContext::new(4);
+        // Three corners, six edge cells and the center
+        let mut game = crate::GameY::new(4);
+        assert_eq!(context.distinct_moves(&game).len(), 3);
+
+        // A stone in the center keeps every symmetry
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: crate::Coordinates::new(1, 1, 1),
+        })
+        .unwrap();
+        assert_eq!(context.distinct_moves(&game).len(), 2);
+
+        // A corner only keeps its mirror
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(1),
+            coords: crate::Coordinates::new(3, 0, 0),
+        })
+        .unwrap();
+        let moves = context.distinct_moves(&game);
+        assert!(moves.len() > 2 && moves.len() < game.available_cells().len());
+        assert_eq!(BotContext::new(5).distinct_moves(&game), *game.available_cells());
+    }
+
+    #[test]
+    fn test_three_player_boards_are_not_pruned() {
+        let game = crate::GameY::with_players(4, 3);
+        assert_eq!(BotContext::new(4).distinct_moves(&game), *game.available_cells());
+        assert_eq!(BotContext::of(&game).distinct_moves(&game).len(), 10);
+    }
+}
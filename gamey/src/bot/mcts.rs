@@ -0,0 +1,567 @@
+//! A Monte Carlo tree search bot.
+//!
+//! This module provides [`MctsBot`], which runs UCT (upper confidence bounds
+//! applied to trees) with uniformly random playouts. The search is anytime: it
+//! can be stopped at a deadline and still returns the best move found so far.
+//!
+//! With [`MctsBot::with_threads`] the search is leaf-parallel: selection and
+//! expansion pick a batch of leaves on the calling thread, and the playouts of
+//! the batch run on the rayon thread pool when the `parallel` feature is
+//! enabled (one after the other otherwise). Visits are counted when a leaf is
+//! selected, which keeps the leaves of a batch apart, and wins are added to
+//! atomic counters when each playout finishes.
+//!
+//! The moves of the root that a symmetry of the position makes equivalent,
+//! found with the topology of the [`BotContext`] the bot is given, are
+//! explored once.
+
+use crate::{AnalysisUpdate, BotContext, Coordinates, GameStatus, GameY, MoveStats, Movement, PlayerId, ResignWatch, YBot};
+use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
+
+/// Exploration constant of the UCT formula.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// A bot that picks the most visited move after a number of MCTS iterations.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, MctsBot, YBot};
+/// use std::time::{Duration, Instant};
+///
+/// let bot = MctsBot::new(200).with_threads(2).with_seed(7);
+/// let game = GameY::new(4);
+/// let deadline = Instant::now() + Duration::from_millis(50);
+/// assert!(bot.choose_move_with_deadline(&game, deadline).is_some());
+/// ```
+pub struct MctsBot {
+    iterations: u32,
+    threads: usize,
+    seed: Option<u64>,
+    resign: Option<ResignWatch>,
+}
+
+/// A node of the search tree, stored in an arena.
+struct Node {
+    /// Cell index of the move leading to this node (None for the root).
+    mv: Option<u32>,
+    /// Player who made the move leading to this node.
+    player: Option<PlayerId>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<u32>,
+    visits: AtomicU32,
+    wins: AtomicU32,
+}
+
+impl Node {
+    fn new(mv: Option<u32>, player: Option<PlayerId>, parent: Option<usize>, game: &GameY, rng: &mut StdRng) -> Self {
+        let mut untried = if game.check_game_over() {
+            Vec::new()
+        } else {
+            game.available_cells().clone()
+        };
+        untried.shuffle(rng);
+        Self {
+            mv,
+            player,
+            parent,
+            children: Vec::new(),
+            untried,
+            visits: AtomicU32::new(0),
+            wins: AtomicU32::new(0),
+        }
+    }
+
+    fn visits(&self) -> u32 {
+        self.visits.load(Ordering::Relaxed)
+    }
+
+    fn uct(&self, parent_visits: u32) -> f64 {
+        let visits = self.visits() as f64;
+        let wins = self.wins.load(Ordering::Relaxed) as f64;
+        wins / visits + EXPLORATION * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+}
+
+/// A leaf selected for simulation: its node, the position there and the seed of its playout.
+struct Leaf {
+    node: usize,
+    state: GameY,
+    seed: u64,
+}
+
+impl MctsBot {
+    /// Creates a bot running at most `iterations` playouts per move on one thread.
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            iterations: iterations.max(1),
+            threads: 1,
+            seed: None,
+            resign: None,
+        }
+    }
+
+    /// Runs the playouts in batches of `threads` leaves (at least 1).
+    ///
+    /// The batches only run in parallel with the `parallel` feature; the
+    /// number of threads of the pool is chosen by rayon.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Makes the search reproducible: with the same seed, position and number
+    /// of threads, a search that is not cut short by a deadline always
+    /// chooses the same move.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Resigns when the win rate of the chosen move, as a score in [-1, 1],
+    /// stays below `threshold` for two own moves in a row.
+    pub fn with_resign_threshold(mut self, threshold: f32) -> Self {
+        self.resign = Some(ResignWatch::new(threshold));
+        self
+    }
+
+    /// Returns the resign threshold, if the bot resigns lost games.
+    pub fn resign_threshold(&self) -> Option<f32> {
+        self.resign.as_ref().map(ResignWatch::threshold)
+    }
+
+    /// Returns the maximum number of iterations per move.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Returns the number of leaves simulated per batch.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Runs the search until the iteration limit or the deadline and returns
+    /// the best move with the work done to find it.
+    ///
+    /// With a `sink`, the best line is reported about ten times during the
+    /// search and once at the end, and the search stops if the sink returns `false`.
+    fn search(
+        &self,
+        game: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+        mut sink: Option<&mut dyn FnMut(AnalysisUpdate) -> bool>,
+    ) -> (Option<u32>, MoveStats) {
+        let start = Instant::now();
+        let mut stats = MoveStats::default();
+        let Some(player) = game.next_player() else {
+            return (None, stats);
+        };
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        let mut root = Node::new(None, None, None, game, &mut rng);
+        let distinct = context.distinct_moves(game);
+        root.untried.retain(|mv| distinct.contains(mv));
+        let mut tree = vec![root];
+        if tree[0].untried.is_empty() {
+            return (None, stats);
+        }
+
+        // Playouts dilute immediate wins among other good moves, so check them first
+        for &mv in &tree[0].untried {
+            let mut state = game.clone();
+            apply(&mut state, mv);
+            stats.nodes += 1;
+            if state.check_game_over() {
+                stats.elapsed = start.elapsed();
+                stats.eval = Some(1.0);
+                if let Some(sink) = sink {
+                    sink(AnalysisUpdate {
+                        depth_or_playouts: 0,
+                        best_index: mv,
+                        score: Some(1.0),
+                        pv: vec![mv],
+                    });
+                }
+                return (Some(mv), stats);
+            }
+        }
+
+        let report_every = (self.iterations / 10).max(1);
+        let mut reported = 0;
+        let mut done = 0;
+        while done < self.iterations {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                break;
+            }
+            let batch = self.threads.min((self.iterations - done) as usize);
+            let leaves: Vec<Leaf> = (0..batch)
+                .map(|_| select_and_expand(&mut tree, game, &mut rng))
+                .collect();
+            simulate(&tree, leaves);
+            done += batch as u32;
+            if let Some(sink) = sink.as_mut()
+                && done - reported >= report_every
+            {
+                reported = done;
+                if let Some(update) = progress(&tree, done) && !sink(update) {
+                    break;
+                }
+            }
+        }
+        if let Some(sink) = sink
+            && reported != done
+            && let Some(update) = progress(&tree, done)
+        {
+            sink(update);
+        }
+
+        // Most visited child; fall back to an unexplored move if time ran out immediately
+        let best = tree[0].children.iter().copied().max_by_key(|child| tree[*child].visits());
+        stats.elapsed = start.elapsed();
+        stats.nodes += tree.len() as u64;
+        stats.playouts = done as u64;
+        // Wins of a child are counted for the player who moved into it, here the side to move
+        stats.eval = best
+            .map(|child| &tree[child])
+            .filter(|node| node.visits() > 0)
+            .map(|node| 2.0 * node.wins.load(Ordering::Relaxed) as f32 / node.visits() as f32 - 1.0);
+        let mv = best.and_then(|child| tree[child].mv).or_else(|| {
+            tracing::debug!("MCTS for {} stopped before expanding the root", player);
+            tree[0].untried.last().copied()
+        });
+        (mv, stats)
+    }
+}
+
+/// The most visited child of `node`, if it has been visited.
+fn most_visited(tree: &[Node], node: usize) -> Option<usize> {
+    tree[node]
+        .children
+        .iter()
+        .copied()
+        .filter(|child| tree[*child].visits() > 0)
+        .max_by_key(|child| tree[*child].visits())
+}
+
+/// The best line of the tree after `done` playouts, following the most visited children.
+fn progress(tree: &[Node], done: u32) -> Option<AnalysisUpdate> {
+    let best = most_visited(tree, 0)?;
+    let mut pv = Vec::new();
+    let mut node = Some(best);
+    while let Some(current) = node {
+        pv.extend(tree[current].mv);
+        node = most_visited(tree, current);
+    }
+    let wins = tree[best].wins.load(Ordering::Relaxed) as f32;
+    Some(AnalysisUpdate {
+        depth_or_playouts: done as u64,
+        best_index: tree[best].mv?,
+        score: Some(2.0 * wins / tree[best].visits() as f32 - 1.0),
+        pv,
+    })
+}
+
+/// Walks down the tree with UCT, expands one move and counts a visit on the path.
+fn select_and_expand(tree: &mut Vec<Node>, game: &GameY, rng: &mut StdRng) -> Leaf {
+    let mut state = game.clone();
+
+    // 1. Selection
+    let mut node = 0;
+    while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+        let parent_visits = tree[node].visits();
+        node = tree[node]
+            .children
+            .iter()
+            .copied()
+            .max_by(|a, b| tree[*a].uct(parent_visits).total_cmp(&tree[*b].uct(parent_visits)))
+            .unwrap_or(node);
+        if let Some(mv) = tree[node].mv {
+            apply(&mut state, mv);
+        }
+    }
+
+    // 2. Expansion
+    if let Some(mv) = tree[node].untried.pop() {
+        let mover = state.next_player();
+        apply(&mut state, mv);
+        let child = tree.len();
+        tree.push(Node::new(Some(mv), mover, Some(node), &state, rng));
+        tree[node].children.push(child);
+        node = child;
+    }
+
+    // The visit is counted now so that the next leaves of the batch look elsewhere
+    let mut current = Some(node);
+    while let Some(idx) = current {
+        tree[idx].visits.fetch_add(1, Ordering::Relaxed);
+        current = tree[idx].parent;
+    }
+    Leaf {
+        node,
+        state,
+        seed: rng.random(),
+    }
+}
+
+/// 3. Simulation and 4. backpropagation of the wins of every leaf.
+fn simulate(tree: &[Node], leaves: Vec<Leaf>) {
+    let run = |leaf: Leaf| {
+        let winner = playout(leaf.state, &mut StdRng::seed_from_u64(leaf.seed));
+        let mut current = Some(leaf.node);
+        while let Some(idx) = current {
+            if tree[idx].player.is_some() && tree[idx].player == winner {
+                tree[idx].wins.fetch_add(1, Ordering::Relaxed);
+            }
+            current = tree[idx].parent;
+        }
+    };
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        leaves.into_par_iter().for_each(run);
+    }
+    #[cfg(not(feature = "parallel"))]
+    leaves.into_iter().for_each(run);
+}
+
+/// Places a stone of the side to move on `idx`.
+fn apply(game: &mut GameY, idx: u32) {
+    if let Some(player) = game.next_player() {
+        let coords = Coordinates::from_index(idx, game.board_size());
+        let _ = game.add_move(Movement::Placement { player, coords });
+    }
+}
+
+/// Plays random moves until the game ends and returns the winner.
+fn playout(mut game: GameY, rng: &mut StdRng) -> Option<PlayerId> {
+    while !game.check_game_over() {
+        let idx = *game.available_cells().choose(rng)?;
+        apply(&mut game, idx);
+    }
+    match game.status() {
+        GameStatus::Finished { winner, .. } => Some(*winner),
+        GameStatus::Ongoing { .. } => None,
+    }
+}
+
+impl YBot for MctsBot {
+    fn name(&self) -> &str {
+        "mcts_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.choose_move_with_stats(board, None).0
+    }
+
+    fn choose_move_with_deadline(&self, board: &GameY, deadline: Instant) -> Option<Coordinates> {
+        self.choose_move_with_stats(board, Some(deadline)).0
+    }
+
+    /// Reports the playouts run, the nodes of the tree and the win rate of the chosen move as a score in [-1, 1].
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        self.choose_move_with_context(board, &BotContext::of(board), deadline)
+    }
+
+    /// Resigns instead when the bot has a resign threshold and the game stays lost.
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        self.choose_movement_with_context(board, &BotContext::of(board), deadline)
+    }
+
+    /// Explores the root with the symmetries of `context`.
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        let (idx, stats) = self.search(board, context, deadline, None);
+        (idx.map(|idx| Coordinates::from_index(idx, board.board_size())), stats)
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        let (coords, stats) = self.choose_move_with_context(board, context, deadline);
+        let movement = match &self.resign {
+            Some(watch) => watch.decide(board, coords, stats.eval),
+            None => crate::resign::place(board, coords),
+        };
+        (movement, stats)
+    }
+
+    /// Reports the most visited line about ten times per search, with the
+    /// playouts done so far.
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        self.search(board, &BotContext::of(board), Some(deadline), Some(sink));
+    }
+
+    /// The tree is rooted at the side to move of the board it is given.
+    fn plays_out_of_turn(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_mcts_bot_name() {
+        assert_eq!(MctsBot::new(10).name(), "mcts_bot");
+    }
+
+    #[test]
+    fn test_returns_available_move() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(1, 1, 1));
+        let coords = MctsBot::new(100).choose_move(&game).unwrap();
+        assert!(game.available_cells().contains(&coords.to_index(4)));
+    }
+
+    #[test]
+    fn test_finds_winning_move() {
+        // Player 0 wins immediately at (0, 0, 3) or (1, 0, 2)
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 3, 0));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        play(&mut game, 1, Coordinates::new(2, 1, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 2));
+        play(&mut game, 1, Coordinates::new(1, 2, 0));
+
+        let coords = MctsBot::new(2000).choose_move(&game).unwrap();
+        play(&mut game, 0, coords);
+        assert!(game.check_game_over());
+    }
+
+    #[test]
+    fn test_no_move_on_finished_game() {
+        let mut game = GameY::new(1);
+        play(&mut game, 0, Coordinates::new(0, 0, 0));
+        assert_eq!(MctsBot::new(10).choose_move(&game), None);
+    }
+
+    #[test]
+    fn test_deadline_returns_best_so_far() {
+        let game = GameY::new(9);
+        let bot = MctsBot::new(u32::MAX);
+        let start = Instant::now();
+        let coords = bot.choose_move_with_deadline(&game, start + Duration::from_millis(50));
+        assert!(coords.is_some());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_move() {
+        let mut game = GameY::new(6);
+        play(&mut game, 0, Coordinates::new(2, 2, 1));
+        let choose = |threads| MctsBot::new(500).with_threads(threads).with_seed(42).choose_move(&game);
+        for threads in [1, 4] {
+            let first = choose(threads);
+            assert!(first.is_some());
+            for _ in 0..3 {
+                assert_eq!(choose(threads), first);
+            }
+        }
+    }
+
+    #[test]
+    fn test_threads_find_winning_move() {
+        // Same position as test_finds_winning_move, searched in batches of 8
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 3, 0));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        play(&mut game, 1, Coordinates::new(2, 1, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 2));
+        play(&mut game, 1, Coordinates::new(1, 2, 0));
+
+        let bot = MctsBot::new(2000).with_threads(8);
+        assert_eq!(bot.threads(), 8);
+        let coords = bot.choose_move(&game).unwrap();
+        play(&mut game, 0, coords);
+        assert!(game.check_game_over());
+    }
+
+    #[test]
+    fn test_expired_deadline_still_returns_a_move() {
+        let game = GameY::new(3);
+        let coords = MctsBot::new(100).choose_move_with_deadline(&game, Instant::now());
+        assert!(game.available_cells().contains(&coords.unwrap().to_index(3)));
+    }
+
+    #[test]
+    fn test_stats_count_playouts_and_nodes() {
+        let game = GameY::new(5);
+        let (coords, stats) = MctsBot::new(300).with_seed(3).choose_move_with_stats(&game, None);
+        assert!(coords.is_some());
+        assert_eq!(stats.playouts, 300);
+        assert!(stats.nodes > 1);
+        let eval = stats.eval.unwrap();
+        assert!((-1.0..=1.0).contains(&eval), "{}", eval);
+    }
+
+    #[test]
+    fn test_root_moves_are_explored_up_to_symmetry() {
+        let game = GameY::new(4);
+        let context = BotContext::new(4);
+        let distinct = context.distinct_moves(&game);
+        assert_eq!(distinct.len(), 3);
+        let bot = MctsBot::new(3).with_seed(5);
+        let (coords, stats) = bot.choose_move_with_context(&game, &context, None);
+        assert!(distinct.contains(&coords.unwrap().to_index(4)));
+        // One check per distinct root move, then the root and its three children
+        assert_eq!(stats.nodes, 3 + 4);
+    }
+
+    #[test]
+    fn test_analysis_reports_growing_playouts() {
+        let game = GameY::new(5);
+        let mut updates = Vec::new();
+        let mut sink = |update: AnalysisUpdate| {
+            updates.push(update);
+            true
+        };
+        let deadline = Instant::now() + Duration::from_secs(60);
+        MctsBot::new(500).with_seed(1).analyze_progressive(&game, &mut sink, deadline);
+        assert_eq!(updates.len(), 10);
+        assert!(updates.windows(2).all(|w| w[0].depth_or_playouts < w[1].depth_or_playouts));
+        let last = updates.last().unwrap();
+        assert_eq!(last.depth_or_playouts, 500);
+        assert_eq!(last.pv.first(), Some(&last.best_index));
+    }
+
+    #[test]
+    fn test_analysis_stops_when_the_sink_refuses() {
+        let game = GameY::new(5);
+        let mut calls = 0;
+        let mut sink = |_: AnalysisUpdate| {
+            calls += 1;
+            false
+        };
+        let deadline = Instant::now() + Duration::from_secs(60);
+        MctsBot::new(1000).analyze_progressive(&game, &mut sink, deadline);
+        assert_eq!(calls, 1);
+    }
+}
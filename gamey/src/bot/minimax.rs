@@ -0,0 +1,396 @@
+//! A depth-limited alpha-beta search bot.
+//!
+//! This module provides [`MinimaxBot`], which searches a fixed number of plies
+//! with negamax and alpha-beta pruning and scores the leaves with
+//! [`crate::eval::evaluate`]. Results can be cached in a shared
+//! [`TranspositionTable`] so consecutive requests on the same game reuse the
+//! work done by previous searches. Positions are keyed with the Zobrist keys
+//! of the [`BotContext`] the bot is given, and moves that a symmetry of the
+//! position makes equivalent are searched once.
+
+use crate::eval::evaluate;
+use crate::{AnalysisUpdate, BotContext, Bound, Coordinates, GameY, MoveStats, Movement, PlayerId, ResignWatch, TranspositionTable, TtEntry, YBot};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// A bot that plays the best move found by an alpha-beta search of fixed depth.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, MinimaxBot, YBot};
+///
+/// let bot = MinimaxBot::new(2, None);
+/// let game = GameY::new(4);
+/// assert!(bot.choose_move(&game).is_some());
+/// ```
+pub struct MinimaxBot {
+    depth: u32,
+    table: Option<Arc<TranspositionTable>>,
+    nodes: Option<Arc<AtomicU64>>,
+    resign: Option<ResignWatch>,
+}
+
+impl MinimaxBot {
+    /// Creates a bot searching `depth` plies, optionally caching results in `table`.
+    pub fn new(depth: u32, table: Option<Arc<TranspositionTable>>) -> Self {
+        Self {
+            depth: depth.max(1),
+            table,
+            nodes: None,
+            resign: None,
+        }
+    }
+
+    /// Counts every node expanded by the search in `counter`.
+    pub fn with_node_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.nodes = Some(counter);
+        self
+    }
+
+    /// Resigns when the search score stays below `threshold` for two own moves in a row.
+    pub fn with_resign_threshold(mut self, threshold: f32) -> Self {
+        self.resign = Some(ResignWatch::new(threshold));
+        self
+    }
+
+    /// Returns the resign threshold, if the bot resigns lost games.
+    pub fn resign_threshold(&self) -> Option<f32> {
+        self.resign.as_ref().map(ResignWatch::threshold)
+    }
+
+    /// Returns the search depth in plies.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Searches the position and returns the best move with its score for the side to move.
+    pub fn search(&self, game: &GameY) -> Option<(Coordinates, f32)> {
+        self.search_counting(game, &BotContext::of(game), &mut 0)
+    }
+
+    /// Like [`MinimaxBot::search`] with the tables of `context`, adding the
+    /// nodes expanded to `nodes`.
+    fn search_counting(&self, game: &GameY, context: &BotContext, nodes: &mut u64) -> Option<(Coordinates, f32)> {
+        self.search_to(game, context, self.depth, nodes)
+            .map(|(idx, score)| (Coordinates::from_index(idx, game.board_size()), score))
+    }
+
+    /// Searches `depth` plies and returns the best cell index with its score.
+    fn search_to(&self, game: &GameY, context: &BotContext, depth: u32, nodes: &mut u64) -> Option<(u32, f32)> {
+        let (score, best) = self.negamax(game, context, depth, f32::NEG_INFINITY, f32::INFINITY, nodes);
+        best.map(|idx| (idx, score))
+    }
+
+    /// Returns the score of `game` for the side to move and the best cell index.
+    fn negamax(
+        &self,
+        game: &GameY,
+        context: &BotContext,
+        depth: u32,
+        mut alpha: f32,
+        mut beta: f32,
+        nodes: &mut u64,
+    ) -> (f32, Option<u32>) {
+        // Only the player who just moved can have won
+        let Some(player) = game.next_player() else {
+            return (f32::NEG_INFINITY, None);
+        };
+        if depth == 0 {
+            return (evaluate(game, player), None);
+        }
+
+        let key = search_key(context, game, player);
+        let cached = self.table.as_ref().and_then(|table| table.probe(key));
+        if let Some(entry) = cached
+            && entry.depth >= depth
+        {
+            match entry.bound {
+                Bound::Exact => return (entry.score, entry.best_move),
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return (entry.score, entry.best_move);
+            }
+        }
+
+        *nodes += 1;
+        if let Some(counter) = &self.nodes {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // Try the move suggested by the table first
+        let mut moves = context.distinct_moves(game);
+        if let Some(hint) = cached.and_then(|entry| entry.best_move)
+            && let Some(pos) = moves.iter().position(|&idx| idx == hint)
+        {
+            moves.swap(0, pos);
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = f32::NEG_INFINITY;
+        let mut best_move = None;
+        for idx in moves {
+            let mut child = game.clone();
+            let placement = Movement::Placement {
+                player,
+                coords: Coordinates::from_index(idx, game.board_size()),
+            };
+            if child.add_move(placement).is_err() {
+                continue;
+            }
+            let score = -self.negamax(&child, context, depth - 1, -beta, -alpha, nodes).0;
+            if best_move.is_none() || score > best_score {
+                best_score = score;
+                best_move = Some(idx);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if let Some(table) = &self.table {
+            let bound = if best_score <= original_alpha {
+                Bound::Upper
+            } else if best_score >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            table.store(TtEntry {
+                key,
+                depth,
+                score: best_score,
+                bound,
+                best_move,
+            });
+        }
+        (best_score, best_move)
+    }
+}
+
+/// Position hash with the keys of `context`, combined with the side to move.
+fn search_key(context: &BotContext, game: &GameY, player: PlayerId) -> u64 {
+    context.position_key(game) ^ (player.id() as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+impl YBot for MinimaxBot {
+    fn name(&self) -> &str {
+        "minimax_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.search(board).map(|(coords, _)| coords)
+    }
+
+    /// Reports the nodes expanded and the search score of the chosen move.
+    ///
+    /// The search has a fixed depth, so the deadline is ignored.
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        self.choose_move_with_context(board, &BotContext::of(board), deadline)
+    }
+
+    /// Resigns instead when the bot has a resign threshold and the game stays lost.
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        self.choose_movement_with_context(board, &BotContext::of(board), deadline)
+    }
+
+    /// Searches with the Zobrist keys and the symmetries of `context`.
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        _deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        let start = Instant::now();
+        let mut nodes = 0;
+        let best = self.search_counting(board, context, &mut nodes);
+        let stats = MoveStats {
+            elapsed: start.elapsed(),
+            nodes,
+            playouts: 0,
+            eval: best.map(|(_, score)| score),
+        };
+        (best.map(|(coords, _)| coords), stats)
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        let (coords, stats) = self.choose_move_with_context(board, context, deadline);
+        let movement = match &self.resign {
+            Some(watch) => watch.decide(board, coords, stats.eval),
+            None => crate::resign::place(board, coords),
+        };
+        (movement, stats)
+    }
+
+    /// Deepens the search one ply at a time up to the bot's depth and reports
+    /// the best move of each depth. The principal variation is only that move.
+    ///
+    /// A new depth is not started once the deadline has passed.
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        let context = BotContext::of(board);
+        let mut nodes = 0;
+        for depth in 1..=self.depth {
+            if depth > 1 && Instant::now() >= deadline {
+                break;
+            }
+            let Some((best_index, score)) = self.search_to(board, &context, depth, &mut nodes) else {
+                break;
+            };
+            let update = AnalysisUpdate {
+                depth_or_playouts: depth as u64,
+                best_index,
+                score: Some(score),
+                pv: vec![best_index],
+            };
+            if !sink(update) {
+                break;
+            }
+        }
+    }
+
+    /// The static evaluation used at the leaves of the search.
+    fn evaluate(&self, board: &GameY, player: PlayerId) -> Option<f32> {
+        Some(evaluate(board, player))
+    }
+
+    /// The search starts from the side to move of the board it is given.
+    fn plays_out_of_turn(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_minimax_bot_name() {
+        assert_eq!(MinimaxBot::new(2, None).name(), "minimax_bot");
+    }
+
+    #[test]
+    fn test_resigning_is_off_by_default() {
+        assert_eq!(MinimaxBot::new(2, None).resign_threshold(), None);
+        let bot = MinimaxBot::new(2, None).with_resign_threshold(-0.5);
+        assert_eq!(bot.resign_threshold(), Some(-0.5));
+        let (movement, _) = bot.choose_movement(&GameY::new(4), None);
+        assert!(matches!(movement, Some(Movement::Placement { .. })));
+    }
+
+    #[test]
+    fn test_finds_winning_move() {
+        // Player 0 owns the side x = 0 except the corner (0, 0, 3)
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 3, 0));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        play(&mut game, 1, Coordinates::new(2, 1, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 2));
+        play(&mut game, 1, Coordinates::new(1, 2, 0));
+
+        let bot = MinimaxBot::new(1, None);
+        let (coords, score) = bot.search(&game).unwrap();
+        assert_eq!(score, f32::INFINITY);
+        play(&mut game, 0, coords);
+        assert!(game.check_game_over());
+    }
+
+    #[test]
+    fn test_no_move_on_finished_game() {
+        let mut game = GameY::new(1);
+        play(&mut game, 0, Coordinates::new(0, 0, 0));
+        assert_eq!(MinimaxBot::new(2, None).choose_move(&game), None);
+    }
+
+    #[test]
+    fn test_table_reduces_work_on_repeated_search() {
+        let table = Arc::new(TranspositionTable::new(1 << 14));
+        let nodes = Arc::new(AtomicU64::new(0));
+        let bot = MinimaxBot::new(3, Some(Arc::clone(&table))).with_node_counter(Arc::clone(&nodes));
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(1, 1, 1));
+
+        let first_move = bot.choose_move(&game);
+        let first = nodes.swap(0, Ordering::Relaxed);
+        let second_move = bot.choose_move(&game);
+        let second = nodes.load(Ordering::Relaxed);
+
+        assert_eq!(first_move, second_move);
+        assert!(second < first, "second search expanded {} nodes, first {}", second, first);
+        assert!(table.stats().hits > 0);
+        assert!(table.stats().stores > 0);
+    }
+
+    #[test]
+    fn test_table_does_not_change_the_result() {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(3, 0, 0));
+        play(&mut game, 1, Coordinates::new(1, 1, 1));
+        let plain = MinimaxBot::new(2, None).search(&game).unwrap().1;
+        let table = Arc::new(TranspositionTable::new(1 << 12));
+        let cached = MinimaxBot::new(2, Some(table)).search(&game).unwrap().1;
+        assert_eq!(plain, cached);
+    }
+
+    #[test]
+    fn test_stats_count_nodes_and_score() {
+        let game = GameY::new(4);
+        let bot = MinimaxBot::new(2, None);
+        let (coords, stats) = bot.choose_move_with_stats(&game, None);
+        let (best, score) = bot.search(&game).unwrap();
+        assert_eq!(coords, Some(best));
+        assert_eq!(stats.eval, Some(score));
+        assert!(stats.nodes > 1);
+        assert_eq!(stats.playouts, 0);
+    }
+
+    #[test]
+    fn test_search_uses_the_tables_of_the_context() {
+        let table = Arc::new(TranspositionTable::new(1 << 12));
+        let bot = MinimaxBot::new(1, Some(Arc::clone(&table)));
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(1, 1, 1));
+        // Keys unlike those of the board, so the table shows which ones were used
+        let context = BotContext {
+            zobrist: Arc::new(crate::Zobrist::new(11)),
+            ..BotContext::new(4)
+        };
+        bot.choose_move_with_context(&game, &context, None);
+        assert!(table.probe(search_key(&context, &game, PlayerId::new(1))).is_some());
+        assert!(table.probe(search_key(&BotContext::of(&game), &game, PlayerId::new(1))).is_none());
+
+        // The empty board has three moves up to symmetry: a corner, an edge and the center
+        let (_, stats) = MinimaxBot::new(2, None).choose_move_with_context(&GameY::new(4), &context, None);
+        assert_eq!(stats.nodes, 4);
+    }
+
+    #[test]
+    fn test_analysis_deepens_one_ply_at_a_time() {
+        let game = GameY::new(4);
+        let bot = MinimaxBot::new(3, None);
+        let mut depths = Vec::new();
+        let mut sink = |update: AnalysisUpdate| {
+            depths.push(update.depth_or_playouts);
+            true
+        };
+        bot.analyze_progressive(&game, &mut sink, Instant::now() + std::time::Duration::from_secs(60));
+        assert_eq!(depths, vec![1, 2, 3]);
+    }
+}
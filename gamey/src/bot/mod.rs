@@ -4,12 +4,39 @@
 //! that can play the Game of Y. It includes:
 //!
 //! - [`YBot`] - A trait that defines the interface for all bots
+//! - [`BotContext`] - The tables of a board size shared by the bots, cached in [`BotContexts`]
 //! - [`YBotRegistry`] - A registry for managing multiple bot implementations
 //! - [`RandomBot`] - A simple bot that makes random valid moves
+//! - [`SmartRandomBot`] - A random bot that never plays on dead cells
+//! - [`SwapAdvisor`] - A wrapper that decides when to apply the swap rule
+//! - [`MinimaxBot`] - An alpha-beta search bot backed by a [`TranspositionTable`]
+//! - [`MctsBot`] - A Monte Carlo tree search bot that can stop at a deadline
+//! - [`BridgeBot`] - A heuristic bot that defends and extends bridges
+//! - [`BookBot`] - A wrapper that plays from an [`OpeningBook`] while in book
+//! - [`ResignWatch`] - Lets the search bots resign games they keep evaluating as lost
+//! - [`TacticalFilter`] - A wrapper that never misses a win or a forced block
 
+pub mod book;
+pub mod bridge;
+pub mod context;
+pub mod mcts;
+pub mod minimax;
 pub mod random;
+pub mod resign;
+pub mod swap_advisor;
+pub mod tactics;
+pub mod transposition;
 pub mod ybot;
 pub mod ybot_registry;
+pub use book::*;
+pub use bridge::*;
+pub use context::*;
+pub use mcts::*;
+pub use minimax::*;
 pub use random::*;
+pub use resign::*;
+pub use swap_advisor::*;
+pub use tactics::*;
+pub use transposition::*;
 pub use ybot::*;
 pub use ybot_registry::*;
@@ -1,8 +1,10 @@
 //! A simple random bot implementation.
 //!
-//! This module provides [`RandomBot`], a bot that makes random valid moves.
-//! It is useful for testing and as a baseline opponent.
+//! This module provides [`RandomBot`], a bot that makes random valid moves,
+//! and [`SmartRandomBot`], which never wastes a move on a dead cell.
+//! They are useful for testing and as baseline opponents.
 
+use crate::analysis::dead_cells;
 use crate::{Coordinates, GameY, YBot};
 use rand::prelude::IndexedRandom;
 
@@ -34,8 +36,46 @@ impl YBot for RandomBot {
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
         let available_cells = board.available_cells();
         let cell = available_cells.choose(&mut rand::rng())?;
-        let coordinates = Coordinates::from_index(*cell, board.board_size());
-        Some(coordinates)
+        Coordinates::try_from_index(*cell, board.board_size()).ok()
+    }
+
+    fn plays_out_of_turn(&self) -> bool {
+        true
+    }
+}
+
+/// A random bot that skips the cells [`dead_cells`] proves useless.
+///
+/// Plays like [`RandomBot`] but only among the cells that can still matter,
+/// which makes it a slightly stronger baseline and a better playout policy.
+/// If every empty cell is dead it plays any of them.
+pub struct SmartRandomBot;
+
+impl YBot for SmartRandomBot {
+    fn name(&self) -> &str {
+        "smart_random_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let size = board.board_size();
+        let dead: Vec<u32> = dead_cells(board)
+            .iter()
+            .map(|coords| coords.to_index(size))
+            .collect();
+        let alive: Vec<u32> = board
+            .available_cells()
+            .iter()
+            .copied()
+            .filter(|cell| !dead.contains(cell))
+            .collect();
+        let cell = alive
+            .choose(&mut rand::rng())
+            .or_else(|| board.available_cells().choose(&mut rand::rng()))?;
+        Coordinates::try_from_index(*cell, size).ok()
+    }
+
+    fn plays_out_of_turn(&self) -> bool {
+        true
     }
 }
 
@@ -137,4 +177,15 @@ mod tests {
             assert!(game.available_cells().contains(&index));
         }
     }
+
+    #[test]
+    fn test_smart_random_bot_avoids_captured_cell() {
+        let yen = crate::YEN::new(5, 1, vec!['B', 'R'], "./../BB./B.B./.BB..".to_string());
+        let game = GameY::try_from(yen).unwrap();
+        for _ in 0..50 {
+            let coords = SmartRandomBot.choose_move(&game).unwrap();
+            assert_ne!(coords, Coordinates::new(1, 1, 2));
+            assert_ne!(coords, Coordinates::new(0, 0, 4));
+        }
+    }
 }
@@ -0,0 +1,142 @@
+//! Resigning lost games.
+//!
+//! A bot with a resign threshold gives up instead of dragging a lost game out:
+//! when the evaluation of its search stays below the threshold for two of its
+//! moves in a row, it answers with [`GameAction::Resign`]. The evaluation is on
+//! the bot's own scale, e.g. a win rate in [-1, 1] for [`crate::MctsBot`].
+
+use crate::{Coordinates, GameAction, GameY, Movement};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of lost games remembered at once by a [`ResignWatch`].
+const TRACKED_GAMES: usize = 16;
+
+/// Remembers the games in which a bot played a move it evaluated as lost.
+///
+/// A bot can play many games at once, so the games are told apart by their
+/// move history: the next own move of a game is the one whose history is the
+/// remembered one plus the opponent's answer.
+#[derive(Debug)]
+pub struct ResignWatch {
+    threshold: f32,
+    lost: Mutex<VecDeque<(u32, Vec<Movement>)>>,
+}
+
+impl ResignWatch {
+    /// Creates a watch resigning after two own moves evaluated below `threshold`.
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            lost: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Returns the resign threshold.
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Turns the move chosen by a search into the movement to play.
+    ///
+    /// Returns a resignation if `eval` is below the threshold and so was the
+    /// evaluation of the bot's previous move in the same game, and a
+    /// placement at `coords` otherwise. Returns `None` when there is nobody
+    /// to move or no move was chosen.
+    pub fn decide(&self, board: &GameY, coords: Option<Coordinates>, eval: Option<f32>) -> Option<Movement> {
+        let player = board.next_player()?;
+        let coords = coords?;
+        if eval.is_none_or(|eval| eval >= self.threshold) {
+            return Some(Movement::Placement { player, coords });
+        }
+        let size = board.board_size();
+        let history = board.history();
+        let mut lost = self.lost.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let previous = lost.iter().position(|(lost_size, moves)| {
+            *lost_size == size && moves.len() + 1 == history.len() && history.starts_with(moves)
+        });
+        if let Some(previous) = previous {
+            lost.remove(previous);
+            return Some(Movement::Action {
+                player,
+                action: GameAction::Resign,
+            });
+        }
+        let placement = Movement::Placement { player, coords };
+        let mut moves = history.to_vec();
+        moves.push(placement.clone());
+        if lost.len() == TRACKED_GAMES {
+            lost.pop_front();
+        }
+        lost.push_back((size, moves));
+        Some(placement)
+    }
+}
+
+/// The placement at `coords` for the side to move of `board`.
+pub(crate) fn place(board: &GameY, coords: Option<Coordinates>) -> Option<Movement> {
+    let player = board.next_player()?;
+    coords.map(|coords| Movement::Placement { player, coords })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    fn play(game: &mut GameY, player: u32, index: u32) {
+        let coords = Coordinates::from_index(index, game.board_size());
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_resigns_on_the_second_lost_move_in_a_row() {
+        let watch = ResignWatch::new(-0.5);
+        let mut game = GameY::new(4);
+        play(&mut game, 0, 0);
+        let first = watch.decide(&game, Some(Coordinates::from_index(1, 4)), Some(-0.8)).unwrap();
+        assert!(matches!(first, Movement::Placement { .. }));
+        game.add_move(first).unwrap();
+        play(&mut game, 0, 2);
+        let second = watch.decide(&game, Some(Coordinates::from_index(3, 4)), Some(-0.9)).unwrap();
+        assert_eq!(
+            second,
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Resign,
+            }
+        );
+    }
+
+    #[test]
+    fn test_a_good_move_in_between_resets_the_count() {
+        let watch = ResignWatch::new(-0.5);
+        let mut game = GameY::new(4);
+        let mut evals = [-0.8, 0.1, -0.8].into_iter();
+        for index in [0, 2, 4] {
+            play(&mut game, 0, index);
+            let movement = watch.decide(&game, Some(Coordinates::from_index(index + 1, 4)), evals.next());
+            let movement = movement.unwrap();
+            assert!(matches!(movement, Movement::Placement { .. }));
+            game.add_move(movement).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_other_games_do_not_count() {
+        let watch = ResignWatch::new(0.0);
+        let mut game = GameY::new(4);
+        play(&mut game, 0, 0);
+        watch.decide(&game, Some(Coordinates::from_index(1, 4)), Some(-1.0));
+        let mut other = GameY::new(4);
+        play(&mut other, 0, 5);
+        play(&mut other, 1, 1);
+        play(&mut other, 0, 2);
+        let movement = watch.decide(&other, Some(Coordinates::from_index(3, 4)), Some(-1.0));
+        assert!(matches!(movement, Some(Movement::Placement { .. })));
+    }
+}
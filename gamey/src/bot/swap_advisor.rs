@@ -0,0 +1,196 @@
+//! A bot wrapper that decides when to apply the swap (pie) rule.
+//!
+//! This module provides [`SwapAdvisor`], which wraps any [`YBot`] and swaps
+//! when the opponent's opening stone is close to the center of the board.
+
+use crate::{AnalysisUpdate, BotContext, Coordinates, GameY, MoveStats, Movement, PlayerId, YBot};
+use std::time::Instant;
+
+/// Wraps a bot and swaps when the opening stone is within a distance of the center.
+///
+/// Openings near the center are strong in Y, so the second player takes them
+/// over; openings near the sides or corners are left to the opponent.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, Movement, PlayerId, RandomBot, SwapAdvisor, YBot};
+///
+/// let bot = SwapAdvisor::new(RandomBot, 1.0);
+/// let mut game = GameY::new(5);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(2, 1, 1),
+/// }).unwrap();
+///
+/// assert!(bot.should_swap(&game));
+/// ```
+pub struct SwapAdvisor<B: YBot> {
+    inner: B,
+    max_center_distance: f64,
+}
+
+impl<B: YBot> SwapAdvisor<B> {
+    /// Creates a wrapper that swaps openings at most `max_center_distance` cells from the center.
+    pub fn new(inner: B, max_center_distance: f64) -> Self {
+        Self {
+            inner,
+            max_center_distance,
+        }
+    }
+
+    /// Returns the wrapped bot.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+/// Returns the distance (in cells) between `coords` and the center of a board of `board_size`.
+///
+/// The center has barycentric coordinates `((n-1)/3, (n-1)/3, (n-1)/3)`, so the
+/// distance is the usual hexagonal distance measured against a fractional cell.
+pub fn center_distance(coords: &Coordinates, board_size: u32) -> f64 {
+    let center = (board_size as f64 - 1.0) / 3.0;
+    let dx = (coords.x() as f64 - center).abs();
+    let dy = (coords.y() as f64 - center).abs();
+    let dz = (coords.z() as f64 - center).abs();
+    (dx + dy + dz) / 2.0
+}
+
+impl<B: YBot> YBot for SwapAdvisor<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.inner.choose_move(board)
+    }
+
+    fn choose_move_with_deadline(&self, board: &GameY, deadline: Instant) -> Option<Coordinates> {
+        self.inner.choose_move_with_deadline(board, deadline)
+    }
+
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        self.inner.choose_move_with_stats(board, deadline)
+    }
+
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        self.inner.choose_movement(board, deadline)
+    }
+
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        self.inner.choose_move_with_context(board, context, deadline)
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        self.inner.choose_movement_with_context(board, context, deadline)
+    }
+
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        self.inner.analyze_progressive(board, sink, deadline)
+    }
+
+    fn should_swap(&self, board: &GameY) -> bool {
+        match &*board.history() {
+            [Movement::Placement { coords, .. }] => {
+                center_distance(coords, board.board_size()) <= self.max_center_distance
+            }
+            _ => false,
+        }
+    }
+
+    fn evaluate(&self, board: &GameY, player: PlayerId) -> Option<f32> {
+        self.inner.evaluate(board, player)
+    }
+
+    fn plays_out_of_turn(&self) -> bool {
+        self.inner.plays_out_of_turn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MinimaxBot, RandomBot};
+
+    fn game_with_opening(size: u32, coords: Coordinates) -> GameY {
+        let mut game = GameY::new(size);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_name_is_delegated() {
+        let bot = SwapAdvisor::new(RandomBot, 1.0);
+        assert_eq!(bot.name(), "random_bot");
+    }
+
+    #[test]
+    fn test_evaluation_is_delegated() {
+        let game = game_with_opening(4, Coordinates::new(1, 1, 1));
+        let minimax = MinimaxBot::new(1, None);
+        let expected = minimax.evaluate(&game, PlayerId::new(0));
+        assert!(expected.is_some());
+        assert_eq!(SwapAdvisor::new(minimax, 1.0).evaluate(&game, PlayerId::new(0)), expected);
+        assert_eq!(SwapAdvisor::new(RandomBot, 1.0).evaluate(&game, PlayerId::new(0)), None);
+    }
+
+    #[test]
+    fn test_center_opening_is_swapped() {
+        let bot = SwapAdvisor::new(RandomBot, 1.0);
+        let game = game_with_opening(5, Coordinates::new(2, 1, 1));
+        assert!(bot.should_swap(&game));
+    }
+
+    #[test]
+    fn test_corner_opening_is_not_swapped() {
+        let bot = SwapAdvisor::new(RandomBot, 1.0);
+        let game = game_with_opening(5, Coordinates::new(4, 0, 0));
+        assert!(!bot.should_swap(&game));
+    }
+
+    #[test]
+    fn test_no_swap_on_empty_board() {
+        let bot = SwapAdvisor::new(RandomBot, 10.0);
+        assert!(!bot.should_swap(&GameY::new(5)));
+    }
+
+    #[test]
+    fn test_no_swap_after_first_decision() {
+        let bot = SwapAdvisor::new(RandomBot, 10.0);
+        let mut game = game_with_opening(5, Coordinates::new(2, 1, 1));
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(4, 0, 0),
+        })
+        .unwrap();
+        assert!(!bot.should_swap(&game));
+    }
+
+    #[test]
+    fn test_center_distance() {
+        // On a size 4 board the center is exactly the cell (1, 1, 1)
+        assert_eq!(center_distance(&Coordinates::new(1, 1, 1), 4), 0.0);
+        assert_eq!(center_distance(&Coordinates::new(3, 0, 0), 4), 2.0);
+    }
+
+    #[test]
+    fn test_random_bot_never_swaps() {
+        let game = game_with_opening(5, Coordinates::new(2, 1, 1));
+        assert!(!RandomBot.should_swap(&game));
+    }
+}
@@ -0,0 +1,292 @@
+//! Wins and losses one move away.
+//!
+//! This module provides [`immediate_win`] and [`must_block`], which find the
+//! cells that win or lose a game on the next stone, and [`TacticalFilter`], a
+//! wrapper that makes any bot take its wins and block the opponent's.
+
+use crate::{AnalysisUpdate, BotContext, CellIndex, Coordinates, GameY, MoveStats, Movement, PlayerId, YBot};
+use std::time::Instant;
+
+/// Returns a cell where a stone of `player` wins the game at once, if there is one.
+pub fn immediate_win(game: &GameY, player: PlayerId) -> Option<Coordinates> {
+    let engine = game.engine();
+    let size = game.board_size();
+    game.available_cells()
+        .iter()
+        .find(|cell| engine.would_win(**cell as CellIndex, player))
+        .map(|cell| Coordinates::from_index(*cell, size))
+}
+
+/// Returns the cells where a stone of an opponent of `player` would win the game.
+///
+/// `player` has to take one of them on their move; if there are two or more,
+/// the game is lost unless `player` wins first.
+pub fn must_block(game: &GameY, player: PlayerId) -> Vec<Coordinates> {
+    let engine = game.engine();
+    let size = game.board_size();
+    let opponents: Vec<PlayerId> = (0..game.num_players())
+        .map(PlayerId::new)
+        .filter(|opponent| *opponent != player)
+        .collect();
+    game.available_cells()
+        .iter()
+        .filter(|cell| opponents.iter().any(|opponent| engine.would_win(**cell as CellIndex, *opponent)))
+        .map(|cell| Coordinates::from_index(*cell, size))
+        .collect()
+}
+
+/// Wraps a bot so that it never misses a win or a forced block.
+///
+/// Before asking the wrapped bot, it takes a cell that wins at once, and
+/// otherwise the only cell that stops an opponent from winning on their next
+/// stone. With no such cell, or with several threats to block, the wrapped
+/// bot chooses.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, RandomBot, TacticalFilter, YBot};
+///
+/// let bot = TacticalFilter::new(RandomBot);
+/// assert_eq!(bot.name(), "random_bot");
+/// assert!(bot.choose_move(&GameY::new(5)).is_some());
+/// ```
+pub struct TacticalFilter<B: YBot> {
+    inner: B,
+}
+
+impl<B: YBot> TacticalFilter<B> {
+    /// Creates a wrapper checking for wins and forced blocks before `inner` chooses.
+    pub fn new(inner: B) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped bot.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// The move the side to move of `board` has to play, if there is one.
+    fn forced_move(&self, board: &GameY) -> Option<Coordinates> {
+        let player = board.next_player()?;
+        if let Some(win) = immediate_win(board, player) {
+            return Some(win);
+        }
+        match must_block(board, player).as_slice() {
+            [block] => Some(*block),
+            _ => None,
+        }
+    }
+}
+
+impl<B: YBot> YBot for TacticalFilter<B> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.forced_move(board).or_else(|| self.inner.choose_move(board))
+    }
+
+    fn choose_move_with_deadline(&self, board: &GameY, deadline: Instant) -> Option<Coordinates> {
+        self.forced_move(board)
+            .or_else(|| self.inner.choose_move_with_deadline(board, deadline))
+    }
+
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        let start = Instant::now();
+        match self.forced_move(board) {
+            Some(coords) => (Some(coords), MoveStats {
+                elapsed: start.elapsed(),
+                ..MoveStats::default()
+            }),
+            None => self.inner.choose_move_with_stats(board, deadline),
+        }
+    }
+
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        let start = Instant::now();
+        match self.forced_move(board) {
+            Some(coords) => (crate::resign::place(board, Some(coords)), MoveStats {
+                elapsed: start.elapsed(),
+                ..MoveStats::default()
+            }),
+            None => self.inner.choose_movement(board, deadline),
+        }
+    }
+
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        let start = Instant::now();
+        match self.forced_move(board) {
+            Some(coords) => (Some(coords), MoveStats {
+                elapsed: start.elapsed(),
+                ..MoveStats::default()
+            }),
+            None => self.inner.choose_move_with_context(board, context, deadline),
+        }
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        let start = Instant::now();
+        match self.forced_move(board) {
+            Some(coords) => (crate::resign::place(board, Some(coords)), MoveStats {
+                elapsed: start.elapsed(),
+                ..MoveStats::default()
+            }),
+            None => self.inner.choose_movement_with_context(board, context, deadline),
+        }
+    }
+
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        match self.forced_move(board) {
+            Some(coords) => {
+                let best_index = coords.to_index(board.board_size());
+                sink(AnalysisUpdate {
+                    depth_or_playouts: 0,
+                    best_index,
+                    score: None,
+                    pv: vec![best_index],
+                });
+            }
+            None => self.inner.analyze_progressive(board, sink, deadline),
+        }
+    }
+
+    fn should_swap(&self, board: &GameY) -> bool {
+        self.inner.should_swap(board)
+    }
+
+    fn evaluate(&self, board: &GameY, player: PlayerId) -> Option<f32> {
+        self.inner.evaluate(board, player)
+    }
+
+    fn plays_out_of_turn(&self) -> bool {
+        self.inner.plays_out_of_turn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomBot;
+
+    fn game(moves: &[u32]) -> GameY {
+        let moves: Vec<Movement> = moves
+            .iter()
+            .enumerate()
+            .map(|(i, index)| Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::from_index(*index, 4),
+            })
+            .collect();
+        GameY::from_moves(4, &moves).unwrap()
+    }
+
+    /// Player 0 holds 1, 3 and 6 on a size 4 board and connects the last side
+    /// at 2, the only free cell touching it; player 1 is to move.
+    fn threat() -> GameY {
+        game(&[1, 0, 3, 9, 6])
+    }
+
+    #[test]
+    fn test_immediate_win_finds_the_connecting_cell() {
+        let mut position = threat();
+        position
+            .add_move(Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::from_index(8, 4),
+            })
+            .unwrap();
+        assert_eq!(immediate_win(&position, PlayerId::new(0)), Some(Coordinates::from_index(2, 4)));
+        assert_eq!(immediate_win(&position, PlayerId::new(1)), None);
+        for _ in 0..20 {
+            assert_eq!(TacticalFilter::new(RandomBot).choose_move(&position), Some(Coordinates::from_index(2, 4)));
+        }
+    }
+
+    /// Plays the first free cell and records the board size of every context it is given.
+    struct ContextSpyBot(std::sync::Arc<std::sync::Mutex<Vec<u32>>>);
+
+    impl YBot for ContextSpyBot {
+        fn name(&self) -> &str {
+            "context_spy_bot"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            let idx = *board.available_cells().iter().min()?;
+            Some(Coordinates::from_index(idx, board.board_size()))
+        }
+
+        fn choose_move_with_context(
+            &self,
+            board: &GameY,
+            context: &BotContext,
+            deadline: Option<Instant>,
+        ) -> (Option<Coordinates>, MoveStats) {
+            self.0.lock().unwrap().push(context.board_size);
+            self.choose_move_with_stats(board, deadline)
+        }
+
+        fn choose_movement_with_context(
+            &self,
+            board: &GameY,
+            context: &BotContext,
+            deadline: Option<Instant>,
+        ) -> (Option<Movement>, MoveStats) {
+            self.0.lock().unwrap().push(context.board_size);
+            self.choose_movement(board, deadline)
+        }
+    }
+
+    #[test]
+    fn test_context_reaches_the_inner_bot_unless_the_move_is_forced() {
+        let sizes = std::sync::Arc::default();
+        let bot = TacticalFilter::new(ContextSpyBot(std::sync::Arc::clone(&sizes)));
+        let context = BotContext::new(4);
+
+        bot.choose_move_with_context(&threat(), &context, None);
+        bot.choose_movement_with_context(&threat(), &context, None);
+        assert!(sizes.lock().unwrap().is_empty());
+
+        let (coords, _) = bot.choose_move_with_context(&game(&[]), &context, None);
+        assert_eq!(coords, Some(Coordinates::from_index(0, 4)));
+        bot.choose_movement_with_context(&game(&[]), &context, None);
+        assert_eq!(*sizes.lock().unwrap(), [4, 4]);
+    }
+
+    #[test]
+    fn test_a_single_threat_is_blocked() {
+        let position = threat();
+        assert_eq!(must_block(&position, PlayerId::new(1)), vec![Coordinates::from_index(2, 4)]);
+        assert!(must_block(&position, PlayerId::new(0)).is_empty());
+        let bot = TacticalFilter::new(RandomBot);
+        for _ in 0..20 {
+            let (movement, _) = bot.choose_movement(&position, None);
+            assert_eq!(
+                movement,
+                Some(Movement::Placement {
+                    player: PlayerId::new(1),
+                    coords: Coordinates::from_index(2, 4),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_quiet_positions_are_left_to_the_inner_bot() {
+        let position = game(&[4]);
+        assert_eq!(immediate_win(&position, PlayerId::new(1)), None);
+        assert!(must_block(&position, PlayerId::new(1)).is_empty());
+        assert!(TacticalFilter::new(RandomBot).choose_move(&position).is_some());
+    }
+}
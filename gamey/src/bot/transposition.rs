@@ -0,0 +1,210 @@
+//! A fixed-size transposition table shared between searches.
+//!
+//! This module provides [`TranspositionTable`], a cache of search results keyed
+//! by position hash. The table is split into independently locked shards so
+//! concurrent requests on the server rarely contend, and it keeps hit, miss and
+//! store counters that can be reported by the metrics endpoint.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of independently locked shards.
+const SHARDS: usize = 16;
+
+/// How the stored score relates to the true value of the position.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the exact value of the position.
+    Exact,
+    /// The true value is at least the score (the search failed high).
+    Lower,
+    /// The true value is at most the score (the search failed low).
+    Upper,
+}
+
+/// A search result for a single position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TtEntry {
+    /// Hash of the position, including the side to move.
+    pub key: u64,
+    /// Remaining search depth the score was computed with.
+    pub depth: u32,
+    /// Score from the point of view of the side to move.
+    pub score: f32,
+    /// Kind of bound the score represents.
+    pub bound: Bound,
+    /// Best cell index found for the position, if any.
+    pub best_move: Option<u32>,
+}
+
+/// Cache counters of a [`TranspositionTable`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TtStats {
+    /// Number of probes that found an entry for the position.
+    pub hits: u64,
+    /// Number of probes that found nothing.
+    pub misses: u64,
+    /// Number of entries written.
+    pub stores: u64,
+    /// Number of slots of the table.
+    pub capacity: u64,
+}
+
+/// A fixed-size, sharded transposition table with depth-preferred replacement.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Bound, TranspositionTable, TtEntry};
+///
+/// let table = TranspositionTable::new(1024);
+/// table.store(TtEntry { key: 42, depth: 3, score: 1.5, bound: Bound::Exact, best_move: Some(4) });
+/// assert_eq!(table.probe(42).map(|e| e.best_move), Some(Some(4)));
+/// assert_eq!(table.stats().hits, 1);
+/// ```
+#[derive(Debug)]
+pub struct TranspositionTable {
+    shards: Vec<Mutex<Vec<Option<TtEntry>>>>,
+    slots_per_shard: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stores: AtomicU64,
+}
+
+impl TranspositionTable {
+    /// Creates a table with room for roughly `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        let slots_per_shard = capacity.div_ceil(SHARDS).max(1);
+        Self {
+            shards: (0..SHARDS)
+                .map(|_| Mutex::new(vec![None; slots_per_shard]))
+                .collect(),
+            slots_per_shard,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            stores: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of slots of the table.
+    pub fn capacity(&self) -> usize {
+        self.slots_per_shard * SHARDS
+    }
+
+    /// Looks up the entry stored for `key`.
+    pub fn probe(&self, key: u64) -> Option<TtEntry> {
+        let (shard, slot) = self.locate(key);
+        let entry = self.shards[shard].lock().unwrap()[slot].filter(|e| e.key == key);
+        let counter = if entry.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        entry
+    }
+
+    /// Stores `entry`, replacing the current occupant of its slot only if the
+    /// slot is empty, holds the same position, or was searched less deeply.
+    pub fn store(&self, entry: TtEntry) {
+        let (shard, slot) = self.locate(entry.key);
+        let mut slots = self.shards[shard].lock().unwrap();
+        let replace = match &slots[slot] {
+            None => true,
+            Some(current) => current.key == entry.key || entry.depth >= current.depth,
+        };
+        if replace {
+            slots[slot] = Some(entry);
+            self.stores.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Removes every entry, keeping the counters.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap().fill(None);
+        }
+    }
+
+    /// Returns the cache counters.
+    pub fn stats(&self) -> TtStats {
+        TtStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            stores: self.stores.load(Ordering::Relaxed),
+            capacity: self.capacity() as u64,
+        }
+    }
+
+    fn locate(&self, key: u64) -> (usize, usize) {
+        let shard = (key % SHARDS as u64) as usize;
+        let slot = ((key / SHARDS as u64) % self.slots_per_shard as u64) as usize;
+        (shard, slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: u64, depth: u32) -> TtEntry {
+        TtEntry {
+            key,
+            depth,
+            score: 0.0,
+            bound: Bound::Exact,
+            best_move: None,
+        }
+    }
+
+    #[test]
+    fn test_probe_empty_table_is_a_miss() {
+        let table = TranspositionTable::new(64);
+        assert_eq!(table.probe(7), None);
+        assert_eq!(table.stats().misses, 1);
+        assert_eq!(table.stats().hits, 0);
+    }
+
+    #[test]
+    fn test_store_and_probe() {
+        let table = TranspositionTable::new(64);
+        table.store(entry(7, 2));
+        assert_eq!(table.probe(7), Some(entry(7, 2)));
+        assert_eq!(table.stats().stores, 1);
+        assert_eq!(table.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_depth_preferred_replacement() {
+        // A single slot per shard: keys 0 and 16 collide
+        let table = TranspositionTable::new(16);
+        table.store(entry(0, 5));
+        table.store(entry(16, 3));
+        assert_eq!(table.probe(16), None);
+        assert_eq!(table.probe(0).map(|e| e.depth), Some(5));
+
+        table.store(entry(16, 5));
+        assert_eq!(table.probe(16).map(|e| e.depth), Some(5));
+        assert_eq!(table.stats().stores, 2);
+    }
+
+    #[test]
+    fn test_same_position_is_always_replaced() {
+        let table = TranspositionTable::new(16);
+        table.store(entry(3, 6));
+        table.store(entry(3, 1));
+        assert_eq!(table.probe(3).map(|e| e.depth), Some(1));
+    }
+
+    #[test]
+    fn test_clear() {
+        let table = TranspositionTable::new(64);
+        table.store(entry(9, 1));
+        table.clear();
+        assert_eq!(table.probe(9), None);
+        assert_eq!(table.stats().stores, 1);
+    }
+
+    #[test]
+    fn test_capacity_is_rounded_to_shards() {
+        assert_eq!(TranspositionTable::new(100).capacity(), 112);
+        assert_eq!(TranspositionTable::new(0).capacity(), 16);
+    }
+}
@@ -1,4 +1,112 @@
-use crate::{Coordinates, GameY};
+use crate::{BotContext, Coordinates, GameY, Movement, PlayerId};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// How much work a bot did to choose one move.
+///
+/// Bots that do not count their work leave `nodes` and `playouts` at zero.
+/// In JSON the elapsed time is written in whole milliseconds as `elapsed_ms`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MoveStats {
+    /// Wall-clock time spent choosing the move.
+    #[serde(rename = "elapsed_ms", with = "millis")]
+    #[cfg_attr(feature = "schema", schemars(with = "u64"))]
+    pub elapsed: Duration,
+    /// Positions expanded by the search.
+    pub nodes: u64,
+    /// Random games played to the end by the search.
+    pub playouts: u64,
+    /// The bot's score of the chosen move for the side to move, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eval: Option<f32>,
+}
+
+/// The best move found so far by a bot that is still searching, see
+/// [`YBot::analyze_progressive`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AnalysisUpdate {
+    /// How far the search got: the depth for minimax, the playouts for MCTS.
+    pub depth_or_playouts: u64,
+    /// Index of the cell of the best move.
+    pub best_index: u32,
+    /// The bot's score of the best move for the side to move, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// The cells of the line the bot expects, starting with the best move.
+    pub pv: Vec<u32>,
+}
+
+/// Serializes a [`Duration`] as whole milliseconds.
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// The [`MoveStats`] of many moves added together, for example over a self-play run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveStatsTotals {
+    /// Moves recorded.
+    pub moves: u64,
+    /// Total time spent choosing them.
+    #[serde(rename = "elapsed_ms", with = "millis")]
+    pub elapsed: Duration,
+    /// Total positions expanded.
+    pub nodes: u64,
+    /// Total playouts.
+    pub playouts: u64,
+}
+
+impl MoveStatsTotals {
+    /// Adds the statistics of one move.
+    pub fn record(&mut self, stats: &MoveStats) {
+        self.moves += 1;
+        self.elapsed += stats.elapsed;
+        self.nodes += stats.nodes;
+        self.playouts += stats.playouts;
+    }
+
+    /// Adds every move recorded in `other`.
+    pub fn merge(&mut self, other: &MoveStatsTotals) {
+        self.moves += other.moves;
+        self.elapsed += other.elapsed;
+        self.nodes += other.nodes;
+        self.playouts += other.playouts;
+    }
+
+    /// Average time per move, zero when no move was recorded.
+    pub fn mean_elapsed(&self) -> Duration {
+        match u32::try_from(self.moves) {
+            Ok(0) => Duration::ZERO,
+            Ok(moves) => self.elapsed / moves,
+            Err(_) => Duration::from_secs_f64(self.elapsed.as_secs_f64() / self.moves as f64),
+        }
+    }
+}
+
+impl Display for MoveStatsTotals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} moves in {:.2?} ({:.2?} per move), {} nodes, {} playouts",
+            self.moves,
+            self.elapsed,
+            self.mean_elapsed(),
+            self.nodes,
+            self.playouts
+        )
+    }
+}
 
 /// Trait representing a Y game bot (YBot)
 /// A YBot is an AI that can choose moves in the game of Y.
@@ -9,4 +117,178 @@ pub trait YBot: Send + Sync {
 
     /// Chooses a move based on the current game state.
     fn choose_move(&self, board: &GameY) -> Option<Coordinates>;
+
+    /// Chooses a move, trying to answer before `deadline`.
+    ///
+    /// Anytime bots should stop searching at the deadline and return their best
+    /// move so far. The default implementation ignores the deadline and calls
+    /// [`YBot::choose_move`].
+    fn choose_move_with_deadline(&self, board: &GameY, _deadline: Instant) -> Option<Coordinates> {
+        self.choose_move(board)
+    }
+
+    /// Chooses a move, before `deadline` if there is one, and reports the work done.
+    ///
+    /// The default implementation calls [`YBot::choose_move`] or
+    /// [`YBot::choose_move_with_deadline`] and only measures the elapsed time;
+    /// searching bots override it to count their nodes and playouts.
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        let start = Instant::now();
+        let coords = match deadline {
+            Some(deadline) => self.choose_move_with_deadline(board, deadline),
+            None => self.choose_move(board),
+        };
+        let stats = MoveStats {
+            elapsed: start.elapsed(),
+            ..MoveStats::default()
+        };
+        (coords, stats)
+    }
+
+    /// Chooses what to play, before `deadline` if there is one: usually a
+    /// placement, or a resignation for bots that give up lost games.
+    ///
+    /// The default implementation places the stone chosen by
+    /// [`YBot::choose_move_with_stats`]; bots with a [`crate::ResignWatch`]
+    /// override it.
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        let (coords, stats) = self.choose_move_with_stats(board, deadline);
+        (crate::resign::place(board, coords), stats)
+    }
+
+    /// Chooses a move like [`YBot::choose_move_with_stats`], with the shared
+    /// tables of the board size in `context`.
+    ///
+    /// Bots that cache tables per board size, such as symmetries or Zobrist
+    /// keys, should take them from `context` instead of building their own.
+    /// The default implementation ignores the context.
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        _context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        self.choose_move_with_stats(board, deadline)
+    }
+
+    /// Chooses what to play like [`YBot::choose_movement`], with the shared
+    /// tables of the board size in `context`.
+    ///
+    /// The default implementation ignores the context.
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        _context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        self.choose_movement(board, deadline)
+    }
+
+    /// Searches until `deadline`, reporting each improvement of its best move to `sink`.
+    ///
+    /// The search stops early when `sink` returns `false`, for example
+    /// because nobody is listening any more. The default implementation
+    /// chooses a move with [`YBot::choose_move_with_stats`] and reports it
+    /// once.
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        let (coords, stats) = self.choose_move_with_stats(board, Some(deadline));
+        if let Some(coords) = coords {
+            let best_index = coords.to_index(board.board_size());
+            sink(AnalysisUpdate {
+                depth_or_playouts: stats.playouts,
+                best_index,
+                score: stats.eval,
+                pv: vec![best_index],
+            });
+        }
+    }
+
+    /// Decides whether to apply the swap (pie) rule instead of placing a stone.
+    ///
+    /// Only meaningful when the bot is the second player facing its first decision.
+    /// The default implementation never swaps.
+    fn should_swap(&self, _board: &GameY) -> bool {
+        false
+    }
+
+    /// Scores `board` from the point of view of `player`, for bots that have
+    /// an evaluation of their own.
+    ///
+    /// Positive values favor `player`. The default implementation returns `None`.
+    fn evaluate(&self, _board: &GameY, _player: PlayerId) -> Option<f32> {
+        None
+    }
+
+    /// Whether the bot can choose a move for a player who is not the side to
+    /// move, given the position with that player to move.
+    ///
+    /// Bots whose choice depends only on the stones and on the side to move
+    /// can answer questions such as "what would player 0 play here?" while it
+    /// is player 1's turn. The default implementation returns `false`.
+    fn plays_out_of_turn(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomBot;
+
+    #[test]
+    fn test_default_stats_only_measure_time() {
+        let game = GameY::new(4);
+        let (coords, stats) = RandomBot.choose_move_with_stats(&game, None);
+        assert!(game.available_cells().contains(&coords.unwrap().to_index(4)));
+        assert_eq!(stats.nodes, 0);
+        assert_eq!(stats.playouts, 0);
+        assert_eq!(stats.eval, None);
+    }
+
+    #[test]
+    fn test_default_analysis_reports_once() {
+        let game = GameY::new(4);
+        let mut updates = Vec::new();
+        let mut sink = |update: AnalysisUpdate| {
+            updates.push(update);
+            true
+        };
+        RandomBot.analyze_progressive(&game, &mut sink, Instant::now());
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].pv, vec![updates[0].best_index]);
+        assert!(game.available_cells().contains(&updates[0].best_index));
+    }
+
+    #[test]
+    fn test_stats_json_uses_milliseconds() {
+        let stats = MoveStats {
+            elapsed: Duration::from_millis(1250),
+            nodes: 7,
+            playouts: 0,
+            eval: None,
+        };
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(json, r#"{"elapsed_ms":1250,"nodes":7,"playouts":0}"#);
+        assert_eq!(serde_json::from_str::<MoveStats>(&json).unwrap(), stats);
+    }
+
+    #[test]
+    fn test_totals_add_up_moves() {
+        let mut totals = MoveStatsTotals::default();
+        assert_eq!(totals.mean_elapsed(), Duration::ZERO);
+        for ms in [10, 30] {
+            totals.record(&MoveStats {
+                elapsed: Duration::from_millis(ms),
+                nodes: 5,
+                playouts: 2,
+                eval: Some(0.5),
+            });
+        }
+        let mut merged = MoveStatsTotals::default();
+        merged.merge(&totals);
+        assert_eq!(merged.moves, 2);
+        assert_eq!(merged.nodes, 10);
+        assert_eq!(merged.playouts, 4);
+        assert_eq!(merged.mean_elapsed(), Duration::from_millis(20));
+    }
 }
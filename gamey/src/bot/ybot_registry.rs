@@ -1,7 +1,8 @@
 //! Registry for managing YBot implementations.
 //!
 //! The [`YBotRegistry`] provides a centralized way to register and retrieve
-//! bot implementations by name.
+//! bot implementations by name. Names are matched ignoring case, and a bot
+//! can also be found by an alias such as `easy` or `hard`.
 
 use std::{collections::HashMap, sync::Arc};
 
@@ -26,6 +27,8 @@ use crate::YBot;
 /// ```
 pub struct YBotRegistry {
     bots: HashMap<String, Arc<dyn YBot>>,
+    /// Bot names by lowercase alias.
+    aliases: HashMap<String, String>,
 }
 
 impl YBotRegistry {
@@ -33,6 +36,7 @@ impl YBotRegistry {
     pub fn new() -> Self {
         YBotRegistry {
             bots: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
 
@@ -44,16 +48,47 @@ impl YBotRegistry {
         self
     }
 
-    /// Finds a bot by name.
+    /// Lets `alias` find the bot registered as `name`, and returns the registry for chaining.
     ///
-    /// Returns `Some(bot)` if a bot with the given name exists, `None` otherwise.
+    /// Aliases are matched ignoring case. A bot whose name is the alias
+    /// itself is still found first.
+    pub fn with_alias(mut self, alias: &str, name: &str) -> Self {
+        self.aliases.insert(alias.to_lowercase(), name.to_string());
+        self
+    }
+
+    /// Finds a bot by name or alias.
+    ///
+    /// The exact name is tried first, then the name ignoring case and then the
+    /// aliases. Returns `None` if nothing matches.
     pub fn find(&self, name: &str) -> Option<Arc<dyn YBot>> {
-        self.bots.get(name).cloned()
+        if let Some(bot) = self.bots.get(name) {
+            return Some(Arc::clone(bot));
+        }
+        let lowercase = name.to_lowercase();
+        self.bots
+            .iter()
+            .find(|(bot_name, _)| bot_name.to_lowercase() == lowercase)
+            .map(|(_, bot)| Arc::clone(bot))
+            .or_else(|| self.aliases.get(&lowercase).and_then(|target| self.bots.get(target).cloned()))
     }
 
-    /// Returns a list of all registered bot names.
+    /// Returns the names of all registered bots, sorted.
     pub fn names(&self) -> Vec<String> {
-        self.bots.keys().cloned().collect()
+        let mut names: Vec<String> = self.bots.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the aliases and the bot names they stand for, sorted by alias.
+    pub fn aliases(&self) -> Vec<(String, String)> {
+        let mut aliases: Vec<(String, String)> = self
+            .aliases
+            .iter()
+            .map(|(alias, name)| (alias.clone(), name.clone()))
+            .collect();
+        aliases.sort();
+        aliases
     }
 }
 
@@ -135,6 +170,39 @@ mod tests {
         assert!(registry.find("random_bot").is_some());
     }
 
+    #[test]
+    fn test_find_ignores_case() {
+        let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+        assert!(registry.find("Random_Bot").is_some());
+        assert!(registry.find("RANDOM_BOT").is_some());
+        assert!(registry.find("random").is_none());
+    }
+
+    #[test]
+    fn test_aliases_find_their_bot() {
+        let registry = YBotRegistry::new()
+            .with_bot(Arc::new(RandomBot))
+            .with_bot(Arc::new(MockBot::new("hard")))
+            .with_alias("Easy", "random_bot")
+            .with_alias("hard", "random_bot")
+            .with_alias("broken", "missing_bot");
+
+        assert_eq!(registry.find("easy").unwrap().name(), "random_bot");
+        assert_eq!(registry.find("EASY").unwrap().name(), "random_bot");
+        // A bot registered under the alias wins over the alias
+        assert_eq!(registry.find("hard").unwrap().name(), "hard");
+        assert!(registry.find("broken").is_none());
+        assert_eq!(registry.aliases()[1], ("easy".to_string(), "random_bot".to_string()));
+    }
+
+    #[test]
+    fn test_names_are_sorted() {
+        let registry = YBotRegistry::new()
+            .with_bot(Arc::new(MockBot::new("zeta")))
+            .with_bot(Arc::new(MockBot::new("alpha")));
+        assert_eq!(registry.names(), vec!["alpha".to_string(), "zeta".to_string()]);
+    }
+
     #[test]
     fn test_duplicate_name_overwrites() {
         let bot1 = Arc::new(MockBot::new("same_name"));
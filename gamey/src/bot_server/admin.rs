@@ -0,0 +1,128 @@
+//! Administration routes for end-to-end test suites and operators.
+//!
+//! The routes are mounted under `/admin` only when
+//! [`crate::ServerConfig::enable_admin`] is set; otherwise they do not exist
+//! and answer 404 like any unknown path. Every request must carry one of the
+//! configured [`crate::ServerConfig::api_keys`] in the `X-API-Key` header.
+//!
+//! - `POST /admin/reset-all` - Reset the server game, drop every hosted game and clear the statistics
+//! - `GET /admin/games` - Ids of the hosted games and the seconds since each was last used
+//! - `POST /admin/gc?older_than_s=` - Drop the hosted games unused for that long
+
+use crate::{
+    GameStats, GameY,
+    error::ErrorResponse,
+    state::AppState,
+    wire::{Wire, WireFormat},
+};
+use axum::extract::{Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Header carrying the API key of admin requests.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// A hosted game as listed by `GET /admin/games`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AdminGame {
+    /// The id of the game.
+    pub id: u64,
+    /// Seconds since a seat last used the game.
+    pub age_s: u64,
+}
+
+/// Reply of `GET /admin/games`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AdminGamesResponse {
+    /// The hosted games, by id.
+    pub games: Vec<AdminGame>,
+}
+
+/// Query parameters of `POST /admin/gc`.
+#[derive(Deserialize, Debug, Default)]
+pub struct GcQuery {
+    /// Games unused for at least this many seconds are dropped,
+    /// [`crate::ServerConfig::game_ttl_s`] if missing.
+    pub older_than_s: Option<u64>,
+}
+
+/// Reply of `POST /admin/gc`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GcResponse {
+    /// Ids of the dropped games.
+    pub removed: Vec<u64>,
+}
+
+/// Reply of `POST /admin/reset-all`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ResetAllResponse {
+    /// Number of hosted games that were dropped.
+    pub removed_games: usize,
+}
+
+/// The `/admin` routes, guarded by the API keys of the state's settings.
+pub fn router(state: AppState) -> axum::Router<AppState> {
+    axum::Router::new()
+        .route("/reset-all", axum::routing::post(reset_all))
+        .route("/games", axum::routing::get(list_games))
+        .route("/gc", axum::routing::post(collect_games))
+        .route_layer(axum::middleware::from_fn_with_state(state, require_api_key))
+}
+
+/// Middleware rejecting the requests without a configured API key with 401.
+pub async fn require_api_key(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    match key {
+        Some(key) if state.config.api_keys.iter().any(|k| k == key) => next.run(request).await,
+        _ => ErrorResponse::error("A valid API key is required", None, None)
+            .with_code("UNAUTHORIZED")
+            .with_status(StatusCode::UNAUTHORIZED)
+            .into_response(),
+    }
+}
+
+/// Handler for `POST /admin/reset-all`.
+pub async fn reset_all(State(state): State<AppState>, format: WireFormat) -> Wire<ResetAllResponse> {
+    let removed_games = state.hosted_games.clear();
+    {
+        let mut game = state.game.lock();
+        game.reset_to(GameY::new(state.config.default_board_size));
+        *state.clock.lock() = None;
+    }
+    // After the reset, so that the abandoned game is not counted either
+    *state.stats.lock() = GameStats::new();
+    format.reply(ResetAllResponse { removed_games })
+}
+
+/// Handler for `GET /admin/games`.
+pub async fn list_games(State(state): State<AppState>, format: WireFormat) -> Wire<AdminGamesResponse> {
+    let games = state
+        .hosted_games
+        .ages(Instant::now())
+        .into_iter()
+        .map(|(id, age)| AdminGame { id, age_s: age.as_secs() })
+        .collect();
+    format.reply(AdminGamesResponse { games })
+}
+
+/// Handler for `POST /admin/gc?older_than_s=`.
+pub async fn collect_games(
+    State(state): State<AppState>,
+    Query(query): Query<GcQuery>,
+    format: WireFormat,
+) -> Wire<GcResponse> {
+    let idle = query.older_than_s.map_or(state.config.game_ttl(), Duration::from_secs);
+    let removed = state
+        .hosted_games
+        .remove_idle(idle, Instant::now())
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    format.reply(GcResponse { removed })
+}
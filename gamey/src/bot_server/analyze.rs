@@ -0,0 +1,90 @@
+//! Streaming analysis of a position over server-sent events.
+//!
+//! `POST /{api_version}/ybot/analyze-stream?bot_id=&time_ms=` takes the same
+//! body as the choose endpoint and answers with a `text/event-stream`. Each
+//! improvement of the bot's best move arrives as an `update` event whose data
+//! is an [`AnalysisUpdate`], and the stream ends with a `done` event carrying
+//! the last update (or `null` if the bot found no move). Searching bots such
+//! as MCTS and minimax report while they search; the others send one update.
+//!
+//! The search runs on a blocking thread and stops as soon as the client
+//! disconnects.
+
+use crate::{
+    AnalysisUpdate, check_api_version,
+    choose::{ChooseRequest, game_of_request, think_budget},
+    error::ErrorResponse,
+    state::AppState,
+    wire::{Negotiated, Wire, WireFormat},
+};
+use axum::extract::{Path, Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Updates buffered for a slow client before the search waits for it.
+const STREAM_BUFFER: usize = 16;
+
+/// Path parameters extracted from the analyze endpoint URL.
+#[derive(Deserialize)]
+pub struct AnalyzeParams {
+    /// The API version (e.g., "v1").
+    api_version: String,
+}
+
+/// Query parameters accepted by the analyze endpoint.
+#[derive(Deserialize, Debug, Default)]
+pub struct AnalyzeQuery {
+    /// The bot to analyze with; the server's default bot if missing.
+    pub bot_id: Option<String>,
+    /// Analysis time in milliseconds, clamped to the server maximum.
+    pub time_ms: Option<u64>,
+}
+
+/// Handler for the analyze-stream endpoint.
+///
+/// Errors in the request (unknown version or bot, invalid position) are
+/// answered before the stream starts, as an `ErrorResponse` in the format
+/// asked for in `Accept`: 404 `UNKNOWN_BOT` for an unknown bot and 400
+/// otherwise.
+pub async fn analyze_stream(
+    State(state): State<AppState>,
+    Path(params): Path<AnalyzeParams>,
+    Query(query): Query<AnalyzeQuery>,
+    format: WireFormat,
+    Negotiated(request): Negotiated<ChooseRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Wire<ErrorResponse>> {
+    let bot_id = query.bot_id.unwrap_or_else(|| state.config.default_bot.clone());
+    check_api_version(&params.api_version).map_err(|error| format.error(error))?;
+    let bot = state
+        .find_bot(&bot_id, Some(&params.api_version))
+        .map_err(|error| format.error(error))?;
+    let game = game_of_request(request, &params.api_version, &bot_id).map_err(|error| format.error(error))?;
+    let deadline = Instant::now() + think_budget(&state, query.time_ms);
+
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        let mut last = None;
+        bot.analyze_progressive(
+            &game,
+            &mut |update: AnalysisUpdate| {
+                let event = Event::default().event("update").json_data(&update);
+                last = Some(update);
+                // A closed channel means the client went away.
+                event.is_ok_and(|event| tx.blocking_send(event).is_ok())
+            },
+            deadline,
+        );
+        if let Ok(done) = Event::default().event("done").json_data(&last) {
+            let _ = tx.blocking_send(done);
+        }
+    });
+
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,439 @@
+//! Archive of finished games: `GET /archive` and `GET /archive/{id}`.
+//!
+//! When the server has a [`GameArchive`], an [`ArchiveObserver`] subscribed to
+//! its game stores every game as soon as it finishes. [`MemoryArchive`] keeps
+//! the games for the lifetime of the process; with the `sqlite` feature,
+//! [`SqliteArchive`] keeps them in a SQLite database.
+
+use crate::{ErrorResponse, GameObserver, GameStatus, GameY, GameYError, Movement, PlayerId, WinReason, YEN, state::AppState};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use crate::sync::Mutex;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of games returned by `GET /archive` when no limit is given.
+pub const DEFAULT_ARCHIVE_LIMIT: u32 = 20;
+
+/// Largest number of games returned by one `GET /archive` request.
+pub const MAX_ARCHIVE_LIMIT: u32 = 100;
+
+/// A finished game as stored in the archive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchivedGame {
+    /// Id assigned by the archive.
+    pub id: i64,
+    /// When the game was archived, in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Board size.
+    pub size: u32,
+    /// Id of the winning player.
+    pub winner: Option<u32>,
+    /// Every move of the game, in order.
+    pub moves: Vec<Movement>,
+    /// The final position.
+    pub yen: YEN,
+}
+
+impl ArchivedGame {
+    /// Rebuilds the game by replaying its moves.
+    ///
+    /// Games lost on time or forfeited do not end with their last move, so
+    /// the player to move after it is made to lose the way the `result` of
+    /// the stored YEN says.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidMoveList` if a move cannot be played.
+    pub fn to_game(&self) -> Result<GameY, GameYError> {
+        let mut game = GameY::from_moves(self.size, &self.moves)?;
+        let reason = self.yen.result().and_then(|result| result.split_once('+')).map(|(_, reason)| reason);
+        if let (Some(loser), Some(reason)) = (game.side_to_move(), reason) {
+            if reason == WinReason::Timeout.notation() {
+                game.lose_on_time(loser);
+            } else if reason == WinReason::Forfeit.notation() {
+                game.forfeit(loser);
+            }
+        }
+        Ok(game)
+    }
+}
+
+/// Storage of finished games.
+///
+/// Implementations are shared between request handlers, so they use interior
+/// mutability. Games are listed from the newest to the oldest.
+pub trait GameArchive: Send + Sync {
+    /// Stores `game` and returns its id.
+    fn insert(&self, game: &GameY) -> Result<i64, GameYError>;
+
+    /// Returns up to `limit` games, skipping the `offset` newest ones.
+    fn list(&self, limit: u32, offset: u32) -> Result<Vec<ArchivedGame>, GameYError>;
+
+    /// Returns the game with the given id, if it exists.
+    fn get(&self, id: i64) -> Result<Option<ArchivedGame>, GameYError>;
+}
+
+/// An archive that keeps the games in memory.
+#[derive(Debug, Default)]
+pub struct MemoryArchive {
+    games: Mutex<Vec<ArchivedGame>>,
+}
+
+impl MemoryArchive {
+    /// Creates an empty archive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GameArchive for MemoryArchive {
+    fn insert(&self, game: &GameY) -> Result<i64, GameYError> {
+        let mut games = self.games.lock();
+        let id = games.len() as i64 + 1;
+        games.push(ArchivedGame {
+            id,
+            created_at: now(),
+            size: game.board_size(),
+            winner: winner_of(game),
+            moves: game.history().to_vec(),
+            yen: game.into(),
+        });
+        Ok(id)
+    }
+
+    fn list(&self, limit: u32, offset: u32) -> Result<Vec<ArchivedGame>, GameYError> {
+        let games = self.games.lock();
+        Ok(games
+            .iter()
+            .rev()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn get(&self, id: i64) -> Result<Option<ArchivedGame>, GameYError> {
+        let games = self.games.lock();
+        Ok(games.iter().find(|g| g.id == id).cloned())
+    }
+}
+
+/// An archive stored in a SQLite database, in the `games` table.
+///
+/// The table is created when the archive is opened if it does not exist yet.
+#[cfg(feature = "sqlite")]
+pub struct SqliteArchive {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteArchive {
+    /// Opens the database at `url`, creating the file and the schema if needed.
+    ///
+    /// The url may be a plain path or start with `sqlite://` or `sqlite:`;
+    /// `:memory:` opens a private in-memory database.
+    pub fn open(url: &str) -> Result<Self, GameYError> {
+        let path = url
+            .strip_prefix("sqlite://")
+            .or_else(|| url.strip_prefix("sqlite:"))
+            .unwrap_or(url);
+        let connection = if path == ":memory:" {
+            rusqlite::Connection::open_in_memory()
+        } else {
+            rusqlite::Connection::open(path)
+        }
+        .map_err(archive_error)?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS games (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    created_at INTEGER NOT NULL,
+                    size INTEGER NOT NULL,
+                    winner INTEGER,
+                    moves TEXT NOT NULL,
+                    yen TEXT NOT NULL
+                )",
+            )
+            .map_err(archive_error)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Opens an empty in-memory database, mostly for tests.
+    pub fn in_memory() -> Result<Self, GameYError> {
+        Self::open(":memory:")
+    }
+
+    fn read_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, i64, u32, Option<u32>, String, String)> {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+    }
+
+    fn to_game(row: (i64, i64, u32, Option<u32>, String, String)) -> Result<ArchivedGame, GameYError> {
+        let (id, created_at, size, winner, moves, yen) = row;
+        Ok(ArchivedGame {
+            id,
+            created_at: created_at as u64,
+            size,
+            winner,
+            moves: serde_json::from_str(&moves).map_err(|e| GameYError::SerdeError { error: e })?,
+            yen: serde_json::from_str(&yen).map_err(|e| GameYError::SerdeError { error: e })?,
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl GameArchive for SqliteArchive {
+    fn insert(&self, game: &GameY) -> Result<i64, GameYError> {
+        let moves = serde_json::to_string(&game.history()).map_err(|e| GameYError::SerdeError { error: e })?;
+        let yen: YEN = game.into();
+        let yen = serde_json::to_string(&yen).map_err(|e| GameYError::SerdeError { error: e })?;
+        let connection = self.connection.lock();
+        connection
+            .execute(
+                "INSERT INTO games (created_at, size, winner, moves, yen) VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![now() as i64, game.board_size(), winner_of(game), moves, yen],
+            )
+            .map_err(archive_error)?;
+        Ok(connection.last_insert_rowid())
+    }
+
+    fn list(&self, limit: u32, offset: u32) -> Result<Vec<ArchivedGame>, GameYError> {
+        let connection = self.connection.lock();
+        let mut statement = connection
+            .prepare(
+                "SELECT id, created_at, size, winner, moves, yen FROM games
+                 ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+            )
+            .map_err(archive_error)?;
+        let rows = statement
+            .query_map([limit, offset], Self::read_row)
+            .map_err(archive_error)?;
+        rows.map(|row| Self::to_game(row.map_err(archive_error)?))
+            .collect()
+    }
+
+    fn get(&self, id: i64) -> Result<Option<ArchivedGame>, GameYError> {
+        use rusqlite::OptionalExtension;
+        let connection = self.connection.lock();
+        let row = connection
+            .query_row(
+                "SELECT id, created_at, size, winner, moves, yen FROM games WHERE id = ?1",
+                [id],
+                Self::read_row,
+            )
+            .optional()
+            .map_err(archive_error)?;
+        row.map(Self::to_game).transpose()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn archive_error(e: rusqlite::Error) -> GameYError {
+    GameYError::ArchiveError {
+        message: e.to_string(),
+    }
+}
+
+/// Game observer that stores every finished game in an archive.
+pub struct ArchiveObserver {
+    archive: Arc<dyn GameArchive>,
+}
+
+impl ArchiveObserver {
+    /// Creates an observer storing the finished games in `archive`.
+    pub fn new(archive: Arc<dyn GameArchive>) -> Self {
+        Self { archive }
+    }
+}
+
+impl GameObserver for ArchiveObserver {
+    fn on_finish(&self, game: &GameY, _winner: PlayerId) {
+        match self.archive.insert(game) {
+            Ok(id) => tracing::debug!("Archived game {}", id),
+            Err(e) => tracing::warn!("Could not archive finished game: {}", e),
+        }
+    }
+}
+
+/// Query parameters of `GET /archive`.
+#[derive(Deserialize, Debug, Default)]
+pub struct ArchiveQuery {
+    /// Number of games to return, at most [`MAX_ARCHIVE_LIMIT`].
+    pub limit: Option<u32>,
+    /// Number of newer games to skip.
+    pub offset: Option<u32>,
+}
+
+/// A page of archived games.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchivePage {
+    /// The games, newest first.
+    pub games: Vec<ArchivedGame>,
+    /// The limit that was applied.
+    pub limit: u32,
+    /// The offset that was applied.
+    pub offset: u32,
+}
+
+/// Handler for `GET /archive?limit=&offset=`: the archived games, newest first.
+pub async fn list_archive(
+    State(state): State<AppState>,
+    Query(query): Query<ArchiveQuery>,
+) -> Result<Json<ArchivePage>, ErrorResponse> {
+    let archive = archive_of(&state)?;
+    let limit = query.limit.unwrap_or(DEFAULT_ARCHIVE_LIMIT).min(MAX_ARCHIVE_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    let games = archive.list(limit, offset).map_err(internal_error)?;
+    Ok(Json(ArchivePage { games, limit, offset }))
+}
+
+/// Handler for `GET /archive/{id}`: one archived game.
+pub async fn get_archived_game(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ArchivedGame>, ErrorResponse> {
+    let archive = archive_of(&state)?;
+    match archive.get(id).map_err(internal_error)? {
+        Some(game) => Ok(Json(game)),
+        None => Err(ErrorResponse::error(&format!("No archived game with id {}", id), None, None)
+            .with_code("GAME_NOT_FOUND")
+            .with_status(StatusCode::NOT_FOUND)),
+    }
+}
+
+/// Reads every archived game, newest first, one page of
+/// [`MAX_ARCHIVE_LIMIT`] games at a time.
+pub(crate) fn all_games(archive: &dyn GameArchive) -> Result<Vec<ArchivedGame>, GameYError> {
+    let mut games = Vec::new();
+    loop {
+        let page = archive.list(MAX_ARCHIVE_LIMIT, games.len() as u32)?;
+        let last = page.len() < MAX_ARCHIVE_LIMIT as usize;
+        games.extend(page);
+        if last {
+            return Ok(games);
+        }
+    }
+}
+
+pub(crate) fn archive_of(state: &AppState) -> Result<&Arc<dyn GameArchive>, ErrorResponse> {
+    state.archive.as_ref().ok_or_else(|| {
+        ErrorResponse::error("This server does not archive games", None, None)
+            .with_code("ARCHIVE_DISABLED")
+            .with_status(StatusCode::NOT_FOUND)
+    })
+}
+
+pub(crate) fn internal_error(e: GameYError) -> ErrorResponse {
+    tracing::error!("Archive failure: {}", e);
+    ErrorResponse::error(&e.to_string(), None, None)
+        .with_code("ARCHIVE_ERROR")
+        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn winner_of(game: &GameY) -> Option<u32> {
+    match game.status() {
+        GameStatus::Finished { winner, .. } => Some(winner.id()),
+        GameStatus::Ongoing { .. } => None,
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameAction};
+
+    fn finished_game(size: u32) -> GameY {
+        let mut game = GameY::new(size);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(0, size),
+        })
+        .unwrap();
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game
+    }
+
+    fn check_archive(archive: &dyn GameArchive) {
+        assert_eq!(archive.insert(&finished_game(3)).unwrap(), 1);
+        assert_eq!(archive.insert(&finished_game(4)).unwrap(), 2);
+        assert_eq!(archive.insert(&finished_game(5)).unwrap(), 3);
+
+        let sizes: Vec<u32> = archive.list(2, 0).unwrap().iter().map(|g| g.size).collect();
+        assert_eq!(sizes, vec![5, 4]);
+        let sizes: Vec<u32> = archive.list(10, 2).unwrap().iter().map(|g| g.size).collect();
+        assert_eq!(sizes, vec![3]);
+
+        let game = archive.get(2).unwrap().unwrap();
+        assert_eq!(game.winner, Some(0));
+        assert_eq!(game.moves, finished_game(4).history().to_vec());
+        assert_eq!(game.yen.layout(), "B/../.../....");
+        assert!(archive.get(42).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_memory_archive() {
+        check_archive(&MemoryArchive::new());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_archive() {
+        check_archive(&SqliteArchive::in_memory().unwrap());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_archive_keeps_games_in_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("games.db").display());
+        SqliteArchive::open(&url).unwrap().insert(&finished_game(3)).unwrap();
+        let reopened = SqliteArchive::open(&url).unwrap();
+        assert_eq!(reopened.list(10, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_observer_archives_finished_games_once() {
+        let archive = Arc::new(MemoryArchive::new());
+        let mut game = GameY::new(3);
+        game.subscribe(Box::new(ArchiveObserver::new(archive.clone())));
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(0, 3),
+        })
+        .unwrap();
+        assert!(archive.list(10, 0).unwrap().is_empty());
+        game.lose_on_time(PlayerId::new(1));
+        assert_eq!(archive.list(10, 0).unwrap().len(), 1);
+
+        let replayed = archive.list(10, 0).unwrap()[0].to_game().unwrap();
+        assert_eq!(replayed.status(), game.status());
+        assert_eq!(replayed.lost_on_time(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_all_games_reads_every_page() {
+        let archive = MemoryArchive::new();
+        for _ in 0..MAX_ARCHIVE_LIMIT + 5 {
+            archive.insert(&finished_game(3)).unwrap();
+        }
+        let games = all_games(&archive).unwrap();
+        assert_eq!(games.len(), MAX_ARCHIVE_LIMIT as usize + 5);
+        assert_eq!(games[0].id, MAX_ARCHIVE_LIMIT as i64 + 5);
+    }
+}
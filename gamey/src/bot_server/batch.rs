@@ -0,0 +1,189 @@
+//! Moves for many positions in one request.
+//!
+//! `POST /{api_version}/ybot/choose-batch/{bot_id}?time_ms=` takes an array of
+//! positions, each one a YEN or a move list as the choose endpoint accepts,
+//! and answers with one result per position, in the same order: the chosen
+//! move, or an error for a position that cannot be played. A bad position
+//! does not fail the others.
+//!
+//! At most [`BATCH_CONCURRENCY`] positions are searched at once, each on its
+//! own blocking thread with the thinking time of `time_ms`.
+
+use crate::{
+    Coordinates, check_api_version,
+    choose::{ChooseRequest, choose_within, game_of_request, think_budget},
+    error::ErrorResponse,
+    state::AppState,
+    wire::{Negotiated, Wire, WireFormat},
+};
+use axum::extract::{Path, Query, State};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Largest number of positions accepted by one batch.
+pub const MAX_BATCH_SIZE: usize = 256;
+
+/// Positions of a batch searched at the same time.
+pub const BATCH_CONCURRENCY: usize = 4;
+
+/// Path parameters extracted from the batch endpoint URL.
+#[derive(Deserialize)]
+pub struct BatchParams {
+    /// The API version (e.g., "v1").
+    api_version: String,
+    /// The identifier of the bot to use for every position.
+    bot_id: String,
+}
+
+/// Query parameters accepted by the batch endpoint.
+#[derive(Deserialize, Debug, Default)]
+pub struct BatchQuery {
+    /// Thinking time for each position in milliseconds, clamped to the server maximum.
+    pub time_ms: Option<u64>,
+}
+
+/// The result of one position of a batch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum BatchResult {
+    /// The move the bot chose.
+    Move {
+        /// Index of the chosen cell, counting row by row from the top.
+        index: u32,
+        /// Coordinates of the chosen cell.
+        coords: Coordinates,
+    },
+    /// Why the position got no move.
+    Error {
+        /// The error, as the choose endpoint would have answered it.
+        error: ErrorResponse,
+    },
+}
+
+/// Reply of the batch endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BatchResponse {
+    /// The API version used for this request.
+    pub api_version: String,
+    /// The bot that chose the moves.
+    pub bot_id: String,
+    /// One result per position, in the order of the request.
+    pub results: Vec<BatchResult>,
+}
+
+/// Handler for the batch endpoint.
+///
+/// The body is an array of positions. Each one is read on its own, so a
+/// position that is not valid YEN or a playable move list gets an inline
+/// error while the others are answered. The request as a whole fails only
+/// for an unknown version or bot (404 `UNKNOWN_BOT`), a body that is not an
+/// array, or more than [`MAX_BATCH_SIZE`] positions (400 `BATCH_TOO_LARGE`).
+pub async fn choose_batch(
+    State(state): State<AppState>,
+    Path(params): Path<BatchParams>,
+    Query(query): Query<BatchQuery>,
+    format: WireFormat,
+    Negotiated(positions): Negotiated<Vec<serde_json::Value>>,
+) -> Result<Wire<BatchResponse>, Wire<ErrorResponse>> {
+    check_api_version(&params.api_version).map_err(|error| format.error(error))?;
+    let bot = state
+        .find_bot(&params.bot_id, Some(&params.api_version))
+        .map_err(|error| format.error(error))?;
+    if positions.len() > MAX_BATCH_SIZE {
+        return Err(format.error(
+            ErrorResponse::error(
+                &format!("A batch has at most {} positions, found {}", MAX_BATCH_SIZE, positions.len()),
+                Some(params.api_version),
+                Some(params.bot_id),
+            )
+            .with_code("BATCH_TOO_LARGE"),
+        ));
+    }
+
+    let budget = think_budget(&state, query.time_ms);
+    let permits = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (slot, position) in positions.into_iter().enumerate() {
+        let (state, bot, permits) = (state.clone(), Arc::clone(&bot), Arc::clone(&permits));
+        let (api_version, bot_id) = (params.api_version.clone(), params.bot_id.clone());
+        tasks.spawn(async move {
+            let error = |message: String| ErrorResponse::error(&message, Some(api_version.clone()), Some(bot_id.clone()));
+            let request: ChooseRequest = match serde_json::from_value(position) {
+                Ok(request) => request,
+                Err(e) => {
+                    let error = error(format!("Invalid position: {}", e)).with_code("INVALID_POSITION");
+                    return (slot, BatchResult::Error { error });
+                }
+            };
+            let game = match game_of_request(request, &api_version, &bot_id) {
+                Ok(game) => game,
+                Err(error) => return (slot, BatchResult::Error { error }),
+            };
+            // El semáforo nunca se cierra, así que siempre hay permiso
+            let _permit = permits.acquire().await;
+            let context = state.bot_contexts.get(game.board_size());
+            let (coords, stats) = choose_within(bot, &game, context, budget).await;
+            state.move_latency.record(stats.elapsed);
+            let result = match coords {
+                Some(coords) => BatchResult::Move {
+                    index: coords.to_index(game.board_size()),
+                    coords,
+                },
+                None => BatchResult::Error {
+                    error: error("No valid moves available for the bot".to_string()),
+                },
+            };
+            (slot, result)
+        });
+    }
+
+    let mut results: Vec<Option<BatchResult>> = vec![None; tasks.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((slot, result)) => results[slot] = Some(result),
+            Err(e) => tracing::warn!("Batch task failed: {}", e),
+        }
+    }
+    let results = results
+        .into_iter()
+        .map(|result| {
+            result.unwrap_or_else(|| BatchResult::Error {
+                error: ErrorResponse::error(
+                    "The bot failed on this position",
+                    Some(params.api_version.clone()),
+                    Some(params.bot_id.clone()),
+                ),
+            })
+        })
+        .collect();
+    Ok(format.reply(BatchResponse {
+        api_version: params.api_version,
+        bot_id: params.bot_id,
+        results,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_results_serialize_without_a_tag() {
+        let chosen = BatchResult::Move {
+            index: 4,
+            coords: Coordinates::from_index(4, 3),
+        };
+        assert_eq!(
+            serde_json::to_value(&chosen).unwrap(),
+            serde_json::json!({"index": 4, "coords": {"x": 0, "y": 1, "z": 1}})
+        );
+        let failed = BatchResult::Error {
+            error: ErrorResponse::error("bad", None, None).with_code("INVALID_POSITION"),
+        };
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["error"]["code"], "INVALID_POSITION");
+        assert_eq!(serde_json::from_value::<BatchResult>(json).unwrap(), failed);
+    }
+}
@@ -0,0 +1,108 @@
+//! Simulated latency and failures, to test the timeouts and retries of clients.
+//!
+//! When [`crate::ServerConfig::chaos`] is set, every request that changes
+//! state (any method but `GET`, `HEAD` and `OPTIONS`) waits a random time of
+//! up to `latency_ms`, and a share `error_rate` of them gets a 500 or a 429
+//! with the `CHAOS` code instead of reaching its handler. Every response
+//! touched carries the [`CHAOS_HEADER`], so an injected failure is never
+//! mistaken for a real one.
+//!
+//! Chaos is meant for development: release builds refuse to start with it
+//! unless the server is run with `--allow-chaos` (see
+//! [`crate::ServerConfig::allow_chaos`]).
+
+use crate::ErrorResponse;
+use crate::state::AppState;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Header marking the responses delayed or replaced by the chaos middleware.
+///
+/// Its value is `latency=<ms>ms` for a delayed response and `error` for an
+/// injected failure.
+pub const CHAOS_HEADER: &str = "x-gamey-chaos";
+
+/// Settings of the simulated latency and failures.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChaosConfig {
+    /// Longest delay added to a request, in milliseconds. Each request waits
+    /// a random time between 0 and this.
+    pub latency_ms: u64,
+    /// Share of the requests, between 0 and 1, answered with an injected 500 or 429.
+    pub error_rate: f64,
+}
+
+impl ChaosConfig {
+    /// Checks that the error rate is a share.
+    ///
+    /// # Errors
+    /// Returns the reason if `error_rate` is not between 0 and 1.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.error_rate) {
+            return Err(format!("chaos error_rate must be between 0 and 1, found {}", self.error_rate));
+        }
+        Ok(())
+    }
+}
+
+/// Middleware delaying and failing the state-changing requests as the state's
+/// [`ChaosConfig`] says, if it has one.
+pub async fn inject_chaos(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(chaos) = state.config.chaos else {
+        return next.run(request).await;
+    };
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+    let (delay, fail, status) = {
+        let mut rng = rand::rng();
+        let delay = Duration::from_millis(rng.random_range(0..=chaos.latency_ms));
+        let fail = rng.random_bool(chaos.error_rate.clamp(0.0, 1.0));
+        let status = if rng.random_bool(0.5) {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::TOO_MANY_REQUESTS
+        };
+        (delay, fail, status)
+    };
+    tokio::time::sleep(delay).await;
+    if fail {
+        tracing::debug!("Chaos: failing {} {} with {}", request.method(), request.uri(), status);
+        let mut response = ErrorResponse::error("Failure injected by the chaos mode", None, None)
+            .with_code("CHAOS")
+            .with_status(status)
+            .into_response();
+        response.headers_mut().insert(CHAOS_HEADER, HeaderValue::from_static("error"));
+        return response;
+    }
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&format!("latency={}ms", delay.as_millis())) {
+        response.headers_mut().insert(CHAOS_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rate_must_be_a_share() {
+        assert!(ChaosConfig::default().validate().is_ok());
+        let always = ChaosConfig {
+            latency_ms: 200,
+            error_rate: 1.0,
+        };
+        assert!(always.validate().is_ok());
+        for error_rate in [-0.1, 1.5, f64::NAN] {
+            let error = ChaosConfig { latency_ms: 0, error_rate }.validate().unwrap_err();
+            assert!(error.contains("error_rate"), "{}", error);
+        }
+    }
+}
@@ -1,9 +1,19 @@
-use crate::{Coordinates, GameY, YEN, check_api_version, error::ErrorResponse, state::AppState};
-use axum::{
-    Json,
-    extract::{Path, State},
+use crate::prelude::*;
+use crate::{
+    BotContext, MoveStats, RandomBot, check_api_version, check_board_size,
+    error::ErrorResponse,
+    review::WIN_SCORE,
+    state::AppState,
+    wire::{Negotiated, Wire, WireFormat},
 };
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Extra time given to a bot past its deadline before the handler gives up on it.
+const DEADLINE_GRACE: Duration = Duration::from_millis(100);
 
 /// Path parameters extracted from the choose endpoint URL.
 #[derive(Deserialize)]
@@ -14,11 +24,102 @@ pub struct ChooseParams {
     bot_id: String,
 }
 
+/// Query parameters accepted by the choose endpoint.
+#[derive(Deserialize, Debug, Default)]
+pub struct ChooseQuery {
+    /// Thinking time for the bot in milliseconds, clamped to the server maximum.
+    pub time_ms: Option<u64>,
+    /// Comma-separated optional parts of the response: `board`, `eval` and `stats`.
+    pub include: Option<String>,
+    /// The player the bot moves for, defaulting to the side to move.
+    pub player: Option<u32>,
+}
+
+/// Optional parts of a [`MoveResponse`] asked for with `?include=`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Include {
+    board: bool,
+    eval: bool,
+    stats: bool,
+}
+
+impl Include {
+    /// Parses the `include` query parameter, rejecting unknown parts.
+    fn parse(include: Option<&str>) -> Result<Self, String> {
+        let mut parts = Include::default();
+        for part in include.unwrap_or_default().split(',').map(str::trim) {
+            match part {
+                "" => {}
+                "board" => parts.board = true,
+                "eval" => parts.eval = true,
+                "stats" => parts.stats = true,
+                other => return Err(format!("Unknown include '{}', expected board, eval or stats", other)),
+            }
+        }
+        Ok(parts)
+    }
+}
+
+/// A stone placed by a player, as sent in a [`MoveList`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IndexedMove {
+    /// The player placing the stone.
+    pub player: u32,
+    /// Index of the cell, counting row by row from the top.
+    pub index: u32,
+}
+
+/// A game given as its board size and the stones placed so far, in order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MoveList {
+    /// The size of the board.
+    pub size: u32,
+    /// The moves played from the empty board.
+    pub moves: Vec<IndexedMove>,
+}
+
+impl MoveList {
+    /// Rebuilds the game by playing every move on an empty board.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidMoveList` with the index of the first move
+    /// that is out of the board, out of turn or cannot be played.
+    pub fn to_game(&self) -> crate::Result<GameY> {
+        let mut game = GameY::try_new(self.size)?;
+        for (index, mv) in self.moves.iter().enumerate() {
+            let error = |message: String| GameYError::InvalidMoveList { index, message };
+            let coords = Coordinates::try_from_index(mv.index, self.size).map_err(|e| error(e.to_string()))?;
+            let movement = Movement::Placement {
+                player: PlayerId::new(mv.player),
+                coords,
+            };
+            game.check_player_turn(&movement).map_err(|e| error(e.to_string()))?;
+            game.add_move(movement).map_err(|e| error(e.to_string()))?;
+        }
+        Ok(game)
+    }
+}
+
+/// Body of the choose endpoint: a position in YEN or the moves that led to it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum ChooseRequest {
+    /// The current position in YEN format.
+    Yen(YEN),
+    /// The moves played so far, which keeps the history of the game.
+    Moves(MoveList),
+}
+
 /// Response returned by the choose endpoint on success.
 ///
 /// Contains the bot's chosen move coordinates along with context
-/// about which API version and bot were used.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// about which API version and bot were used. The position after the move
+/// and its evaluation are only included when asked for with `?include=`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MoveResponse {
     /// The API version used for this request.
     pub api_version: String,
@@ -26,70 +127,259 @@ pub struct MoveResponse {
     pub bot_id: String,
     /// The coordinates where the bot chooses to place its piece.
     pub coords: Coordinates,
+    /// Zobrist hash of the position after the move, as 16 hex digits, so
+    /// clients can check that their board agrees with the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position_hash: Option<String>,
+    /// The position after the move, with `include=board`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub board: Option<YEN>,
+    /// The status of the game after the move, with `include=board`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<GameStatus>,
+    /// Whether the move wins the game, with `include=board`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wins: Option<bool>,
+    /// The bot's evaluation of the position after the move, from its point of
+    /// view and clamped to `±WIN_SCORE`, with `include=eval` and only for bots
+    /// that can evaluate positions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eval: Option<f32>,
+    /// The time the bot took and the work it did to choose the move, with
+    /// `include=stats`. Moves played by the random fallback report its stats.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<MoveStats>,
 }
 
 /// Handler for the bot move selection endpoint.
 ///
-/// This endpoint accepts a game state in YEN format and returns the
-/// coordinates of the bot's chosen move.
+/// This endpoint accepts a game state, in YEN format or as a move list, and
+/// returns the coordinates of the bot's chosen move.
 ///
 /// # Route
 /// `POST /{api_version}/ybot/choose/{bot_id}`
 ///
+/// # Query Parameters
+/// - `time_ms` - Thinking time for the bot, clamped to [`AppState::max_think_time`]
+///   (which is also the default).
+/// - `include` - Comma-separated extra parts of the response: `board` adds the
+///   position, status and whether the move wins; `eval` adds the bot's evaluation;
+///   `stats` adds the time and work the bot spent on the move.
+/// - `player` - The player the bot moves for. When it is not the side to move,
+///   bots that can play out of turn (see [`YBot::plays_out_of_turn`]) search
+///   the position as if it were that player's turn, without its move history;
+///   other bots answer with a 422 `NOT_TO_MOVE` error giving the
+///   `side_to_move` in its details. A player not in the game is a 400
+///   `INVALID_PLAYER` error.
+///
 /// # Request Body
-/// A JSON object in YEN format representing the current game state.
+/// A JSON (or MessagePack) object in YEN format representing the current game
+/// state, or a [`MoveList`] such as
+/// `{ "size": 7, "moves": [{ "player": 0, "index": 12 }] }`. Move lists are
+/// replayed from the empty board; an illegal move is rejected with an
+/// `INVALID_MOVE` error whose details give its `move_number`, starting at 1.
+///
+/// The bot runs on a blocking thread so it does not stall the async runtime.
+/// If it does not answer in time (or panics), a random move is played instead.
 ///
 /// # Response
-/// On success, returns a `MoveResponse` with the chosen coordinates.
-/// On failure, returns an `ErrorResponse` with details about what went wrong.
+/// On success, returns a `MoveResponse` with the chosen coordinates and the
+/// hash of the resulting position.
+/// On failure, returns an `ErrorResponse` with details about what went wrong:
+/// a 404 `UNKNOWN_BOT` if `bot_id` is neither a registered bot (ignoring
+/// case) nor one of the configured aliases, listing the registered bots in
+/// its details, and a 400 otherwise. Both are encoded in the format asked
+/// for in `Accept`.
 #[axum::debug_handler]
 pub async fn choose(
     State(state): State<AppState>,
     Path(params): Path<ChooseParams>,
-    Json(yen): Json<YEN>,
-) -> Result<Json<MoveResponse>, Json<ErrorResponse>> {
+    Query(query): Query<ChooseQuery>,
+    format: WireFormat,
+    Negotiated(request): Negotiated<ChooseRequest>,
+) -> Result<Wire<MoveResponse>, Wire<ErrorResponse>> {
+    choose_move(state, params, query, request)
+        .await
+        .map(|response| format.reply(response))
+        .map_err(|error| format.error(error))
+}
+
+/// Validates the request and asks the bot for its move.
+async fn choose_move(
+    state: AppState,
+    params: ChooseParams,
+    query: ChooseQuery,
+    request: ChooseRequest,
+) -> Result<MoveResponse, ErrorResponse> {
     check_api_version(&params.api_version)?;
-    let game_y = match GameY::try_from(yen) {
-        Ok(game) => game,
-        Err(err) => {
-            return Err(Json(ErrorResponse::error(
-                &format!("Invalid YEN format: {}", err),
-                Some(params.api_version),
-                Some(params.bot_id),
-            )));
-        }
+    let bot = state.find_bot(&params.bot_id, Some(&params.api_version))?;
+    let include = Include::parse(query.include.as_deref()).map_err(|message| {
+        ErrorResponse::error(&message, Some(params.api_version.clone()), Some(params.bot_id.clone()))
+            .with_code("INVALID_INCLUDE")
+    })?;
+    let game_y = game_of_request(request, &params.api_version, &params.bot_id)?;
+    let game_y = match query.player {
+        Some(player) => from_perspective(game_y, player, bot.as_ref(), &params.api_version, &params.bot_id)?,
+        None => game_y,
     };
-    let bot = match state.bots().find(&params.bot_id) {
-        Some(bot) => bot,
-        None => {
-            let available_bots = state.bots().names().join(", ");
-            return Err(Json(ErrorResponse::error(
-                &format!(
-                    "Bot not found: {}, available bots: [{}]",
-                    params.bot_id, available_bots
-                ),
-                Some(params.api_version),
-                Some(params.bot_id),
-            )));
-        }
-    };
-    let coords = match bot.choose_move(&game_y) {
+    let budget = think_budget(&state, query.time_ms);
+    let context = state.bot_contexts.get(game_y.board_size());
+    let (coords, stats) = choose_within(bot.clone(), &game_y, context, budget).await;
+    state.move_latency.record(stats.elapsed);
+    let coords = match coords {
         Some(coords) => coords,
         None => {
             // Handle the case where the bot has no valid moves
-            return Err(Json(ErrorResponse::error(
+            return Err(ErrorResponse::error(
                 "No valid moves available for the bot",
                 Some(params.api_version),
                 Some(params.bot_id),
-            )));
+            ));
         }
     };
-    let response = MoveResponse {
+    let mut after = game_y;
+    let player = after.side_to_move();
+    let played = player.is_some_and(|player| after.add_move(Movement::Placement { player, coords }).is_ok());
+    let mut response = MoveResponse {
         api_version: params.api_version,
         bot_id: params.bot_id,
         coords,
+        position_hash: played.then(|| format!("{:016x}", after.position_hash())),
+        board: None,
+        status: None,
+        wins: None,
+        eval: None,
+        stats: include.stats.then_some(stats),
+    };
+    if let Some(player) = player.filter(|_| played) {
+        if include.board {
+            response.board = Some(YEN::from(&after));
+            response.wins = Some(matches!(after.status(), GameStatus::Finished { winner, .. } if *winner == player));
+            response.status = Some(after.status().clone());
+        }
+        if include.eval {
+            response.eval = bot
+                .evaluate(&after, player)
+                .map(|score| score.clamp(-WIN_SCORE, WIN_SCORE));
+        }
+    }
+    Ok(response)
+}
+
+/// Builds the game of a choose request, turning its errors into responses.
+pub(crate) fn game_of_request(request: ChooseRequest, api_version: &str, bot_id: &str) -> Result<GameY, ErrorResponse> {
+    match request {
+        ChooseRequest::Yen(yen) => GameY::try_from(yen).map_err(|err| {
+            ErrorResponse::error(
+                &format!("Invalid YEN format: {}", err),
+                Some(api_version.to_string()),
+                Some(bot_id.to_string()),
+            )
+        }),
+        ChooseRequest::Moves(list) => replay(&list, api_version, bot_id),
+    }
+}
+
+/// Returns the position of `game` with `player` to move, for the bot to
+/// search from that player's point of view.
+///
+/// The stones are kept but not the moves that placed them. Finished games and
+/// games where `player` is already to move are returned as they are.
+fn from_perspective(
+    game: GameY,
+    player: u32,
+    bot: &dyn YBot,
+    api_version: &str,
+    bot_id: &str,
+) -> Result<GameY, ErrorResponse> {
+    let error = |message: &str| ErrorResponse::error(message, Some(api_version.to_string()), Some(bot_id.to_string()));
+    if player >= game.num_players() {
+        return Err(error(&format!(
+            "Player {} is not in a game of {} players",
+            player,
+            game.num_players()
+        ))
+        .with_code("INVALID_PLAYER"));
+    }
+    let side_to_move = match game.side_to_move() {
+        Some(side) if side.id() != player => side,
+        _ => return Ok(game),
+    };
+    if !bot.plays_out_of_turn() {
+        return Err(error(&format!(
+            "Bot {} only moves for the side to move, player {}",
+            bot_id, side_to_move
+        ))
+        .with_code("NOT_TO_MOVE")
+        .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+        .with_details(serde_json::json!({ "side_to_move": side_to_move.id(), "player": player })));
+    }
+    let yen = YEN::from(&game);
+    GameY::try_from(YEN::new(yen.size(), player, yen.players().to_vec(), yen.layout().to_string()))
+        .map_err(|err| error(&format!("Invalid YEN format: {}", err)))
+}
+
+/// The thinking time asked for in `time_ms`, clamped to [`AppState::max_think_time`]
+/// (which is also the default).
+pub(crate) fn think_budget(state: &AppState, time_ms: Option<u64>) -> Duration {
+    time_ms
+        .map(Duration::from_millis)
+        .unwrap_or(state.max_think_time)
+        .min(state.max_think_time)
+}
+
+/// Rebuilds the game of a move list, turning its errors into responses.
+fn replay(list: &MoveList, api_version: &str, bot_id: &str) -> Result<GameY, ErrorResponse> {
+    let context = || (Some(api_version.to_string()), Some(bot_id.to_string()));
+    if let Err(err) = check_board_size(list.size) {
+        let (api_version, bot_id) = context();
+        return Err(ErrorResponse::error(&err.to_string(), api_version, bot_id).with_code("INVALID_SIZE"));
+    }
+    list.to_game().map_err(|err| {
+        let (api_version, bot_id) = context();
+        let error = ErrorResponse::error(&err.to_string(), api_version, bot_id).with_code("INVALID_MOVE");
+        match err {
+            GameYError::InvalidMoveList { index, .. } => {
+                error.with_details(serde_json::json!({ "move_number": index + 1 }))
+            }
+            _ => error,
+        }
+    })
+}
+
+/// Runs the bot on a blocking thread with the tables of `context` and waits
+/// at most `budget` (plus a grace period).
+///
+/// Falls back to [`RandomBot`] if the bot overruns its budget or panics. A bot
+/// that overruns keeps running in the background until it returns; the
+/// statistics of the fallback then cover the whole wait.
+pub(crate) async fn choose_within(
+    bot: Arc<dyn YBot>,
+    game: &GameY,
+    context: BotContext,
+    budget: Duration,
+) -> (Option<Coordinates>, MoveStats) {
+    let start = Instant::now();
+    let deadline = start + budget;
+    let task_game = game.clone();
+    let task =
+        tokio::task::spawn_blocking(move || bot.choose_move_with_context(&task_game, &context, Some(deadline)));
+    let fallback = match tokio::time::timeout(budget + DEADLINE_GRACE, task).await {
+        Ok(Ok(chosen)) => return chosen,
+        Ok(Err(err)) => {
+            tracing::warn!("Bot task failed, playing a random move: {}", err);
+            RandomBot.choose_move(game)
+        }
+        Err(_) => {
+            tracing::warn!("Bot exceeded its {:?} budget, playing a random move", budget);
+            RandomBot.choose_move(game)
+        }
+    };
+    let stats = MoveStats {
+        elapsed: start.elapsed(),
+        ..MoveStats::default()
     };
-    Ok(Json(response))
+    (fallback, stats)
 }
 
 #[cfg(test)]
@@ -102,6 +392,12 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 2, 3),
+            position_hash: None,
+            board: None,
+            status: None,
+            wins: None,
+            eval: None,
+            stats: None,
         };
         assert_eq!(response.api_version, "v1");
         assert_eq!(response.bot_id, "random");
@@ -114,6 +410,12 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 2, 3),
+            position_hash: None,
+            board: None,
+            status: None,
+            wins: None,
+            eval: None,
+            stats: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"api_version\":\"v1\""));
@@ -134,6 +436,12 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(0, 0, 0),
+            position_hash: None,
+            board: None,
+            status: None,
+            wins: None,
+            eval: None,
+            stats: None,
         };
         let cloned = response.clone();
         assert_eq!(response, cloned);
@@ -145,18 +453,84 @@ mod tests {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 1, 1),
+            position_hash: None,
+            board: None,
+            status: None,
+            wins: None,
+            eval: None,
+            stats: None,
         };
         let r2 = MoveResponse {
             api_version: "v1".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 1, 1),
+            position_hash: None,
+            board: None,
+            status: None,
+            wins: None,
+            eval: None,
+            stats: None,
         };
         let r3 = MoveResponse {
             api_version: "v2".to_string(),
             bot_id: "random".to_string(),
             coords: Coordinates::new(1, 1, 1),
+            position_hash: None,
+            board: None,
+            status: None,
+            wins: None,
+            eval: None,
+            stats: None,
         };
         assert_eq!(r1, r2);
         assert_ne!(r1, r3);
     }
+
+    #[test]
+    fn test_include_parsing() {
+        assert_eq!(Include::parse(None), Ok(Include::default()));
+        assert_eq!(
+            Include::parse(Some("board, eval")),
+            Ok(Include { board: true, eval: true, stats: false })
+        );
+        assert_eq!(Include::parse(Some("eval")), Ok(Include { eval: true, ..Include::default() }));
+        assert_eq!(Include::parse(Some("stats")), Ok(Include { stats: true, ..Include::default() }));
+        assert!(Include::parse(Some("board,moves")).unwrap_err().contains("'moves'"));
+    }
+
+    #[test]
+    fn test_choose_request_accepts_both_shapes() {
+        let yen = r#"{"size":3,"turn":0,"players":["B","R"],"layout":"./../..."}"#;
+        assert!(matches!(serde_json::from_str(yen).unwrap(), ChooseRequest::Yen(_)));
+
+        let moves = r#"{"size":3,"moves":[{"player":0,"index":4}]}"#;
+        let ChooseRequest::Moves(list) = serde_json::from_str(moves).unwrap() else {
+            panic!("expected a move list");
+        };
+        let game = list.to_game().unwrap();
+        assert_eq!(game.history().len(), 1);
+        assert_eq!(game.cell(&Coordinates::from_index(4, 3)), crate::Cell::Occupied(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_move_list_reports_the_offending_move() {
+        let list = MoveList {
+            size: 3,
+            moves: vec![
+                IndexedMove { player: 0, index: 0 },
+                IndexedMove { player: 1, index: 1 },
+                IndexedMove { player: 0, index: 9 },
+            ],
+        };
+        assert!(matches!(list.to_game(), Err(GameYError::InvalidMoveList { index: 2, .. })));
+    }
+
+    #[test]
+    fn test_move_list_rejects_a_player_moving_twice() {
+        let list = MoveList {
+            size: 3,
+            moves: vec![IndexedMove { player: 0, index: 0 }, IndexedMove { player: 0, index: 1 }],
+        };
+        assert!(matches!(list.to_game(), Err(GameYError::InvalidMoveList { index: 1, .. })));
+    }
 }
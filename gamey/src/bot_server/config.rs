@@ -0,0 +1,597 @@
+//! Settings of the bot server.
+//!
+//! Settings are read from an optional TOML file (`gamey.toml` by default) and
+//! can be overridden with `GAMEY_*` environment variables, which take
+//! precedence over the file:
+//!
+//! ```toml
+//! port = 8080
+//! host = "127.0.0.1"
+//! default_board_size = 7
+//! default_bot = "bridge_bot"
+//! cors_origins = ["https://yovi.example"]
+//! rate_limit_per_minute = 600
+//! ladder_k_factor = 24.0
+//! bots_manifest = "bots.toml"
+//!
+//! [bot_aliases]
+//! minimax = "minimax_bot"
+//!
+//! [difficulty_presets.hard]
+//! bot = "mcts_bot"
+//! time_ms = 2000
+//!
+//! # Development only, see the chaos module
+//! [chaos]
+//! latency_ms = 300
+//! error_rate = 0.1
+//! ```
+//!
+//! Every field but `allow_chaos` has an environment variable named after it
+//! in upper case, e.g. `GAMEY_PORT` or `GAMEY_DEFAULT_BOT`. Lists are
+//! comma-separated, and so are the `alias=bot` pairs of `GAMEY_BOT_ALIASES`,
+//! the `name=bot` or `name=bot:time_ms` presets of `GAMEY_DIFFICULTY_PRESETS`
+//! and the `latency_ms=300, error_rate=0.1` settings of `GAMEY_CHAOS`.
+
+use crate::GameYError;
+use crate::chaos::ChaosConfig;
+use crate::limits::{DEFAULT_LONG_REQUEST_TIMEOUT_MS, DEFAULT_MAX_BODY_BYTES, DEFAULT_REQUEST_TIMEOUT_MS};
+use crate::state::GAME_BOT_ID;
+use crate::MAX_BOARD_SIZE;
+use crate::rating::DEFAULT_K_FACTOR;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Port used when none is configured.
+pub const DEFAULT_PORT: u16 = 3000;
+
+/// Address the server listens on when none is configured.
+pub const DEFAULT_HOST: &str = "0.0.0.0";
+
+/// Size of new games when none is configured.
+pub const DEFAULT_BOARD_SIZE: u32 = 5;
+
+/// File read by [`ServerConfig::load`] when no path is given, if it exists.
+pub const DEFAULT_CONFIG_FILE: &str = "gamey.toml";
+
+/// Seconds a hosted game may stay unused before it is removed, when none is configured.
+pub const DEFAULT_GAME_TTL_S: u64 = 60 * 60;
+
+/// Seconds between two scans for abandoned games, when none is configured.
+pub const DEFAULT_EXPIRY_INTERVAL_S: u64 = 60;
+
+/// Prefix of the environment variables read by [`ServerConfig::apply_env`].
+pub const ENV_PREFIX: &str = "GAMEY_";
+
+/// A difficulty level of the server game: the bot that answers and how long it thinks.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DifficultyPreset {
+    /// Name or alias of the bot.
+    pub bot: String,
+    /// Thinking time of the bot in milliseconds, clamped to the server
+    /// maximum. Missing means the bot plays at its own pace.
+    #[serde(default)]
+    pub time_ms: Option<u64>,
+}
+
+impl DifficultyPreset {
+    /// A preset playing `bot` at its own pace.
+    pub fn new(bot: &str) -> Self {
+        Self {
+            bot: bot.to_string(),
+            time_ms: None,
+        }
+    }
+
+    /// Gives the bot `time_ms` milliseconds to think.
+    pub fn with_time_ms(mut self, time_ms: u64) -> Self {
+        self.time_ms = Some(time_ms);
+        self
+    }
+}
+
+/// The presets offered when none are configured: `easy`, `medium` and `hard`.
+pub fn default_difficulty_presets() -> BTreeMap<String, DifficultyPreset> {
+    BTreeMap::from([
+        ("easy".to_string(), DifficultyPreset::new("random_bot")),
+        ("medium".to_string(), DifficultyPreset::new("bridge_bot")),
+        ("hard".to_string(), DifficultyPreset::new("mcts_bot").with_time_ms(2000)),
+    ])
+}
+
+/// Settings used by [`crate::run_bot_server_with_config`] to build and start the server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// The TCP port to listen on.
+    pub port: u16,
+    /// The address to listen on.
+    pub host: String,
+    /// SQLite database where finished games are archived, if any.
+    ///
+    /// Needs the `sqlite` feature. Accepts a path, a `sqlite://` url or
+    /// `:memory:`.
+    pub database_url: Option<String>,
+    /// Directory where the server keeps its files: the Elo ladder in
+    /// `ladder.json` and, with the `sqlite` feature and no `database_url`, the
+    /// finished games in `games.db`.
+    pub state_dir: Option<PathBuf>,
+    /// Size of the server game and of new games that do not give one.
+    pub default_board_size: u32,
+    /// The bot that answers the human moves of the server game.
+    pub default_bot: String,
+    /// Other names for the bots, such as `easy` for `random_bot`, matched ignoring case.
+    pub bot_aliases: BTreeMap<String, String>,
+    /// Difficulty levels a move of the server game can ask for instead of a
+    /// bot, matched ignoring case. Defaults to
+    /// [`default_difficulty_presets`]; configuring any replaces them all.
+    pub difficulty_presets: BTreeMap<String, DifficultyPreset>,
+    /// Origins allowed to call the server from a browser. Empty disables CORS.
+    pub cors_origins: Vec<String>,
+    /// Keys accepted by the routes that require an API key.
+    pub api_keys: Vec<String>,
+    /// Requests accepted per client and minute, unlimited if missing.
+    pub rate_limit_per_minute: Option<u32>,
+    /// Mounts the `/admin` routes, which require one of the `api_keys`.
+    pub enable_admin: bool,
+    /// Seconds a hosted game may stay unused before it is removed.
+    pub game_ttl_s: u64,
+    /// Seconds between two scans for abandoned hosted games.
+    pub expiry_interval_s: u64,
+    /// Stores the hosted games with moves in the archive before removing them.
+    pub archive_expired: bool,
+    /// Largest request body accepted, in bytes.
+    pub max_body_bytes: usize,
+    /// Longest time a request may take, in milliseconds.
+    pub request_timeout_ms: u64,
+    /// Longest time a game review or long poll may take, in milliseconds.
+    pub long_request_timeout_ms: u64,
+    /// Compresses the responses with gzip or deflate for the clients that accept it.
+    pub enable_compression: bool,
+    /// PEM certificate chain to serve HTTPS with. Needs the `tls` feature and `tls_key_path`.
+    pub tls_cert_path: Option<PathBuf>,
+    /// PEM private key of `tls_cert_path`.
+    pub tls_key_path: Option<PathBuf>,
+    /// Score below which the minimax and MCTS bots resign, after two moves
+    /// in a row. Missing means they never resign.
+    pub resign_threshold: Option<f32>,
+    /// Elo K-factor of the ladder, doubled for the first games of each participant.
+    pub ladder_k_factor: f64,
+    /// TOML or JSON file listing the bots to offer instead of the built-in
+    /// ones (see [`crate::manifest`]). The default bot, aliases and presets
+    /// must then name bots of the manifest.
+    pub bots_manifest: Option<PathBuf>,
+    /// Random delays and failures injected into the state-changing requests,
+    /// for testing clients (see [`crate::chaos`]). Release builds refuse it
+    /// unless `allow_chaos` is set.
+    pub chaos: Option<ChaosConfig>,
+    /// Lets a release build start with `chaos`. Only set by the `--allow-chaos`
+    /// flag, never by the file or the environment.
+    #[serde(skip)]
+    pub allow_chaos: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT,
+            host: DEFAULT_HOST.to_string(),
+            database_url: None,
+            state_dir: None,
+            default_board_size: DEFAULT_BOARD_SIZE,
+            default_bot: GAME_BOT_ID.to_string(),
+            bot_aliases: BTreeMap::new(),
+            difficulty_presets: default_difficulty_presets(),
+            cors_origins: Vec::new(),
+            api_keys: Vec::new(),
+            rate_limit_per_minute: None,
+            enable_admin: false,
+            game_ttl_s: DEFAULT_GAME_TTL_S,
+            expiry_interval_s: DEFAULT_EXPIRY_INTERVAL_S,
+            archive_expired: false,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            request_timeout_ms: DEFAULT_REQUEST_TIMEOUT_MS,
+            long_request_timeout_ms: DEFAULT_LONG_REQUEST_TIMEOUT_MS,
+            enable_compression: false,
+            tls_cert_path: None,
+            tls_key_path: None,
+            resign_threshold: None,
+            ladder_k_factor: DEFAULT_K_FACTOR,
+            bots_manifest: None,
+            chaos: None,
+            allow_chaos: false,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Creates the default settings listening on `port`.
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            ..Self::default()
+        }
+    }
+
+    /// Archives finished games in the SQLite database at `url`.
+    pub fn with_database_url(mut self, url: &str) -> Self {
+        self.database_url = Some(url.to_string());
+        self
+    }
+
+    /// Reads the settings from `path` (or [`DEFAULT_CONFIG_FILE`] if it exists),
+    /// applies the `GAMEY_*` environment variables and validates the result.
+    ///
+    /// # Errors
+    /// Returns `GameYError::IoError` if `path` cannot be read and
+    /// `GameYError::InvalidConfig` if the file, a variable or the resulting
+    /// settings are invalid.
+    pub fn load(path: Option<&Path>) -> Result<Self, GameYError> {
+        let config = match path {
+            Some(path) => Self::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?,
+            None => Self::default(),
+        };
+        let config = config.apply_env(std::env::vars())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads the settings from a TOML file; missing fields keep their defaults.
+    pub fn from_file(path: &Path) -> Result<Self, GameYError> {
+        let text = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read config file {}", path.display()),
+            error: e.to_string(),
+        })?;
+        Self::from_toml(&text).map_err(|e| match e {
+            GameYError::InvalidConfig { message } => GameYError::InvalidConfig {
+                message: format!("{}: {}", path.display(), message),
+            },
+            e => e,
+        })
+    }
+
+    /// Parses settings written in TOML; missing fields keep their defaults.
+    pub fn from_toml(text: &str) -> Result<Self, GameYError> {
+        toml::from_str(text).map_err(|e| invalid(e.message().to_string()))
+    }
+
+    /// Overrides the settings with the `GAMEY_*` variables among `vars`.
+    ///
+    /// Other variables are ignored, and so are unknown `GAMEY_*` ones, which
+    /// may belong to other tools.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidConfig` if a value cannot be parsed.
+    pub fn apply_env<I: IntoIterator<Item = (String, String)>>(mut self, vars: I) -> Result<Self, GameYError> {
+        for (name, value) in vars {
+            let Some(field) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let parse_error = |e: &dyn std::fmt::Display| invalid(format!("{}={:?}: {}", name, value, e));
+            match field {
+                "PORT" => self.port = value.parse().map_err(|e| parse_error(&e))?,
+                "HOST" => self.host = value,
+                "DATABASE_URL" => self.database_url = Some(value).filter(|v| !v.is_empty()),
+                "STATE_DIR" => self.state_dir = Some(PathBuf::from(value)).filter(|v| !v.as_os_str().is_empty()),
+                "DEFAULT_BOARD_SIZE" => self.default_board_size = value.parse().map_err(|e| parse_error(&e))?,
+                "DEFAULT_BOT" => self.default_bot = value,
+                "BOT_ALIASES" => {
+                    self.bot_aliases = split_list(&value)
+                        .iter()
+                        .map(|pair| match pair.split_once('=') {
+                            Some((alias, bot)) => Ok((alias.trim().to_string(), bot.trim().to_string())),
+                            None => Err(parse_error(&"expected alias=bot pairs")),
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "DIFFICULTY_PRESETS" => {
+                    self.difficulty_presets = split_list(&value)
+                        .iter()
+                        .map(|pair| {
+                            let (name, preset) = pair
+                                .split_once('=')
+                                .ok_or_else(|| parse_error(&"expected name=bot or name=bot:time_ms pairs"))?;
+                            let preset = match preset.split_once(':') {
+                                Some((bot, time_ms)) => DifficultyPreset::new(bot.trim())
+                                    .with_time_ms(time_ms.trim().parse().map_err(|e| parse_error(&e))?),
+                                None => DifficultyPreset::new(preset.trim()),
+                            };
+                            Ok((name.trim().to_string(), preset))
+                        })
+                        .collect::<Result<_, GameYError>>()?;
+                }
+                "CORS_ORIGINS" => self.cors_origins = split_list(&value),
+                "API_KEYS" => self.api_keys = split_list(&value),
+                "ENABLE_ADMIN" => self.enable_admin = value.parse().map_err(|e| parse_error(&e))?,
+                "GAME_TTL_S" => self.game_ttl_s = value.parse().map_err(|e| parse_error(&e))?,
+                "EXPIRY_INTERVAL_S" => self.expiry_interval_s = value.parse().map_err(|e| parse_error(&e))?,
+                "ARCHIVE_EXPIRED" => self.archive_expired = value.parse().map_err(|e| parse_error(&e))?,
+                "MAX_BODY_BYTES" => self.max_body_bytes = value.parse().map_err(|e| parse_error(&e))?,
+                "REQUEST_TIMEOUT_MS" => self.request_timeout_ms = value.parse().map_err(|e| parse_error(&e))?,
+                "LONG_REQUEST_TIMEOUT_MS" => self.long_request_timeout_ms = value.parse().map_err(|e| parse_error(&e))?,
+                "ENABLE_COMPRESSION" => self.enable_compression = value.parse().map_err(|e| parse_error(&e))?,
+                "TLS_CERT_PATH" => self.tls_cert_path = Some(PathBuf::from(value)).filter(|v| !v.as_os_str().is_empty()),
+                "TLS_KEY_PATH" => self.tls_key_path = Some(PathBuf::from(value)).filter(|v| !v.as_os_str().is_empty()),
+                "BOTS_MANIFEST" => self.bots_manifest = Some(PathBuf::from(value)).filter(|v| !v.as_os_str().is_empty()),
+                "CHAOS" => {
+                    self.chaos = match value.trim() {
+                        "" => None,
+                        v => Some(parse_chaos(v).map_err(|e| parse_error(&e))?),
+                    }
+                }
+                "LADDER_K_FACTOR" => self.ladder_k_factor = value.parse().map_err(|e| parse_error(&e))?,
+                "RESIGN_THRESHOLD" => {
+                    self.resign_threshold = match value.as_str() {
+                        "" => None,
+                        v => Some(v.parse().map_err(|e| parse_error(&e))?),
+                    }
+                }
+                "RATE_LIMIT_PER_MINUTE" => {
+                    self.rate_limit_per_minute = match value.as_str() {
+                        "" => None,
+                        v => Some(v.parse().map_err(|e| parse_error(&e))?),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(self)
+    }
+
+    /// Checks the settings that do not depend on the bot registry.
+    ///
+    /// Whether `default_bot` and the bots of the aliases and presets are
+    /// registered is checked by [`crate::create_state`].
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidConfig` describing the first invalid setting.
+    pub fn validate(&self) -> Result<(), GameYError> {
+        if self.port == 0 {
+            return Err(invalid("port must be between 1 and 65535".to_string()));
+        }
+        if self.host.trim().is_empty() {
+            return Err(invalid("host must not be empty".to_string()));
+        }
+        if !(1..=MAX_BOARD_SIZE).contains(&self.default_board_size) {
+            return Err(invalid(format!(
+                "default_board_size must be between 1 and {}, found {}",
+                MAX_BOARD_SIZE, self.default_board_size
+            )));
+        }
+        if self.rate_limit_per_minute == Some(0) {
+            return Err(invalid("rate_limit_per_minute must be at least 1".to_string()));
+        }
+        if self.game_ttl_s == 0 || self.expiry_interval_s == 0 {
+            return Err(invalid("game_ttl_s and expiry_interval_s must be at least 1".to_string()));
+        }
+        if self.max_body_bytes == 0 || self.request_timeout_ms == 0 || self.long_request_timeout_ms == 0 {
+            return Err(invalid(
+                "max_body_bytes, request_timeout_ms and long_request_timeout_ms must be at least 1".to_string(),
+            ));
+        }
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            return Err(invalid("tls_cert_path and tls_key_path must be given together".to_string()));
+        }
+        if self.resign_threshold.is_some_and(|threshold| !threshold.is_finite()) {
+            return Err(invalid("resign_threshold must be a finite number".to_string()));
+        }
+        if !(self.ladder_k_factor.is_finite() && self.ladder_k_factor > 0.0) {
+            return Err(invalid("ladder_k_factor must be a positive number".to_string()));
+        }
+        if let Some((name, _)) = self.difficulty_presets.iter().find(|(_, preset)| preset.time_ms == Some(0)) {
+            return Err(invalid(format!("time_ms of difficulty preset '{}' must be at least 1", name)));
+        }
+        if self.enable_admin && self.api_keys.is_empty() {
+            return Err(invalid("enable_admin needs at least one of api_keys".to_string()));
+        }
+        if let Some(chaos) = &self.chaos {
+            chaos.validate().map_err(invalid)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that a `release` build only uses `chaos` with `allow_chaos`.
+    ///
+    /// Called by [`crate::create_state`] rather than [`ServerConfig::validate`],
+    /// so the flag can be set after the settings are loaded.
+    pub(crate) fn check_chaos(&self, release: bool) -> Result<(), GameYError> {
+        if release && self.chaos.is_some() && !self.allow_chaos {
+            return Err(invalid(
+                "chaos is for development, a release build only enables it with --allow-chaos".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// How long a hosted game may stay unused before it is removed.
+    pub fn game_ttl(&self) -> Duration {
+        Duration::from_secs(self.game_ttl_s)
+    }
+
+    /// Time between two scans for abandoned hosted games.
+    pub fn expiry_interval(&self) -> Duration {
+        Duration::from_secs(self.expiry_interval_s)
+    }
+
+    /// Longest time a request may take.
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    /// Longest time a game review or long poll may take.
+    pub fn long_request_timeout(&self) -> Duration {
+        Duration::from_millis(self.long_request_timeout_ms)
+    }
+
+    /// The address to listen on, as `host:port`.
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+fn invalid(message: String) -> GameYError {
+    GameYError::InvalidConfig { message }
+}
+
+/// Reads the `latency_ms=300, error_rate=0.1` settings of `GAMEY_CHAOS`; missing ones are 0.
+fn parse_chaos(value: &str) -> Result<ChaosConfig, String> {
+    let mut chaos = ChaosConfig::default();
+    for pair in split_list(value) {
+        let (key, number) = pair
+            .split_once('=')
+            .ok_or_else(|| "expected latency_ms=... and error_rate=... pairs".to_string())?;
+        match key.trim() {
+            "latency_ms" => chaos.latency_ms = number.trim().parse().map_err(|e| format!("latency_ms: {}", e))?,
+            "error_rate" => chaos.error_rate = number.trim().parse().map_err(|e| format!("error_rate: {}", e))?,
+            other => return Err(format!("unknown chaos setting '{}'", other)),
+        }
+    }
+    Ok(chaos)
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_partial_file_keeps_defaults() {
+        let config = ServerConfig::from_toml("port = 8080\ncors_origins = [\"http://a\"]").unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.cors_origins, vec!["http://a".to_string()]);
+        assert_eq!(config.host, DEFAULT_HOST);
+        assert_eq!(config.default_bot, GAME_BOT_ID);
+        assert_eq!(config.resign_threshold, None);
+    }
+
+    #[test]
+    fn test_environment_overrides_file() {
+        let file = ServerConfig::from_toml("port = 8080\ndefault_bot = \"bridge_bot\"\nhost = \"127.0.0.1\"").unwrap();
+        let config = file
+            .apply_env(env(&[
+                ("GAMEY_PORT", "9090"),
+                ("GAMEY_API_KEYS", "one, two,"),
+                ("GAMEY_RATE_LIMIT_PER_MINUTE", "60"),
+                ("GAMEY_RESIGN_THRESHOLD", "-0.9"),
+                ("GAMEY_BOT_ALIASES", "easy=random_bot, hard = mcts_bot"),
+                ("GAMEY_BOTS_MANIFEST", "bots.toml"),
+                ("PORT", "1"),
+                ("GAMEY_SOMETHING_ELSE", "x"),
+            ]))
+            .unwrap();
+        assert_eq!(config.port, 9090);
+        assert_eq!(config.api_keys, vec!["one".to_string(), "two".to_string()]);
+        assert_eq!(config.rate_limit_per_minute, Some(60));
+        assert_eq!(config.resign_threshold, Some(-0.9));
+        assert_eq!(config.bot_aliases["easy"], "random_bot");
+        assert_eq!(config.bot_aliases["hard"], "mcts_bot");
+        assert_eq!(config.bots_manifest, Some(PathBuf::from("bots.toml")));
+        // Settings without a variable keep the value of the file
+        assert_eq!(config.default_bot, "bridge_bot");
+        assert_eq!(config.host, "127.0.0.1");
+    }
+
+    #[test]
+    fn test_difficulty_presets() {
+        assert_eq!(ServerConfig::default().difficulty_presets["hard"].bot, "mcts_bot");
+        let file = ServerConfig::from_toml("[difficulty_presets.hard]\nbot = \"minimax_bot\"\ntime_ms = 500").unwrap();
+        assert_eq!(
+            file.difficulty_presets,
+            BTreeMap::from([("hard".to_string(), DifficultyPreset::new("minimax_bot").with_time_ms(500))])
+        );
+        let config = file
+            .apply_env(env(&[("GAMEY_DIFFICULTY_PRESETS", "easy=random_bot, hard = mcts_bot:1500")]))
+            .unwrap();
+        assert_eq!(config.difficulty_presets["easy"], DifficultyPreset::new("random_bot"));
+        assert_eq!(config.difficulty_presets["hard"], DifficultyPreset::new("mcts_bot").with_time_ms(1500));
+
+        let err = ServerConfig::default()
+            .apply_env(env(&[("GAMEY_DIFFICULTY_PRESETS", "hard=mcts_bot:soon")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("GAMEY_DIFFICULTY_PRESETS"), "{}", err);
+        let zero = ServerConfig::default()
+            .apply_env(env(&[("GAMEY_DIFFICULTY_PRESETS", "hard=mcts_bot:0")]))
+            .unwrap();
+        assert!(zero.validate().unwrap_err().to_string().contains("'hard'"));
+    }
+
+    #[test]
+    fn test_bad_values_are_reported() {
+        let err = ServerConfig::default().apply_env(env(&[("GAMEY_PORT", "70000")])).unwrap_err();
+        assert!(err.to_string().contains("GAMEY_PORT"), "{}", err);
+        let err = ServerConfig::default().apply_env(env(&[("GAMEY_BOT_ALIASES", "easy")])).unwrap_err();
+        assert!(err.to_string().contains("alias=bot"), "{}", err);
+
+        let err = ServerConfig::from_toml("prot = 1").unwrap_err();
+        assert!(err.to_string().contains("prot"), "{}", err);
+
+        assert!(ServerConfig::new(0).validate().is_err());
+        let config = ServerConfig {
+            default_board_size: 0,
+            ..ServerConfig::default()
+        };
+        assert!(config.validate().unwrap_err().to_string().contains("default_board_size"));
+        assert!(ServerConfig::default().validate().is_ok());
+
+        let admin = ServerConfig::default().apply_env(env(&[("GAMEY_ENABLE_ADMIN", "true")])).unwrap();
+        assert!(admin.validate().unwrap_err().to_string().contains("api_keys"));
+
+        let ladder = ServerConfig::default().apply_env(env(&[("GAMEY_LADDER_K_FACTOR", "16")])).unwrap();
+        assert_eq!(ladder.ladder_k_factor, 16.0);
+        let ladder = ServerConfig::from_toml("ladder_k_factor = 0.0").unwrap();
+        assert!(ladder.validate().unwrap_err().to_string().contains("ladder_k_factor"));
+    }
+
+    #[test]
+    fn test_chaos_settings() {
+        let config = ServerConfig::from_toml("[chaos]\nlatency_ms = 300\nerror_rate = 0.1\n").unwrap();
+        assert_eq!(config.chaos, Some(ChaosConfig { latency_ms: 300, error_rate: 0.1 }));
+        assert!(ServerConfig::from_toml("allow_chaos = true").is_err(), "only the flag allows chaos");
+
+        let config = ServerConfig::default()
+            .apply_env(env(&[("GAMEY_CHAOS", "error_rate=1, latency_ms = 50")]))
+            .unwrap();
+        assert_eq!(config.chaos, Some(ChaosConfig { latency_ms: 50, error_rate: 1.0 }));
+        let err = ServerConfig::default().apply_env(env(&[("GAMEY_CHAOS", "jitter=5")])).unwrap_err();
+        assert!(err.to_string().contains("jitter"), "{}", err);
+
+        // Release builds need --allow-chaos
+        assert!(config.check_chaos(false).is_ok());
+        assert!(config.check_chaos(true).unwrap_err().to_string().contains("--allow-chaos"));
+        let allowed = ServerConfig { allow_chaos: true, ..config };
+        assert!(allowed.check_chaos(true).is_ok());
+        assert!(ServerConfig::default().check_chaos(true).is_ok());
+
+        let wild = ServerConfig::from_toml("[chaos]\nerror_rate = 2.0\n").unwrap();
+        assert!(wild.validate().unwrap_err().to_string().contains("error_rate"));
+    }
+
+    #[test]
+    fn test_load_reads_the_given_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.toml");
+        std::fs::write(&path, "default_board_size = 9\n").unwrap();
+        assert_eq!(ServerConfig::from_file(&path).unwrap().default_board_size, 9);
+
+        std::fs::write(&path, "default_board_size = \"big\"\n").unwrap();
+        let err = ServerConfig::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("server.toml"), "{}", err);
+        assert!(ServerConfig::from_file(&dir.path().join("missing.toml")).is_err());
+    }
+}
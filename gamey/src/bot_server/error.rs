@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 /// This type is serialized to JSON and returned when API requests fail.
 /// It includes context about which API version and bot were involved.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ErrorResponse {
     /// The API version that was requested, if available.
     pub api_version: Option<String>,
@@ -13,6 +14,15 @@ pub struct ErrorResponse {
     pub bot_id: Option<String>,
     /// A human-readable error message describing what went wrong.
     pub message: String,
+    /// A machine-readable error code (e.g., "BAD_VERSION"), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Additional structured information that helps the client recover.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Box<serde_json::Value>>,
+    /// HTTP status of the response; `400 Bad Request` when not set.
+    #[serde(skip)]
+    pub status: Option<StatusCode>,
 }
 
 impl ErrorResponse {
@@ -27,13 +37,39 @@ impl ErrorResponse {
             bot_id,
             api_version,
             message: message.to_string(),
+            code: None,
+            details: None,
+            status: None,
         }
     }
+
+    /// Sets the machine-readable error code.
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    /// Attaches structured details to the error.
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(Box::new(details));
+        self
+    }
+
+    /// Sets the HTTP status of the response.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Returns the HTTP status of the response.
+    pub fn status_code(&self) -> StatusCode {
+        self.status.unwrap_or(StatusCode::BAD_REQUEST)
+    }
 }
 
 impl IntoResponse for ErrorResponse {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+        (self.status_code(), Json(self)).into_response()
     }
 }
 
@@ -87,6 +123,34 @@ mod tests {
         assert_eq!(err.bot_id, Some("random".to_string()));
     }
 
+    #[test]
+    fn test_code_and_details_are_omitted_when_absent() {
+        let err = ErrorResponse::error("Plain error", None, None);
+        let json = serde_json::to_string(&err).unwrap();
+        assert!(!json.contains("code"));
+        assert!(!json.contains("details"));
+    }
+
+    #[test]
+    fn test_with_code_and_details() {
+        let err = ErrorResponse::error("Coded error", None, None)
+            .with_code("SOME_CODE")
+            .with_details(serde_json::json!({"hint": 1}));
+        let json = serde_json::to_string(&err).unwrap();
+        assert!(json.contains("\"code\":\"SOME_CODE\""));
+        assert!(json.contains("\"hint\":1"));
+    }
+
+    #[test]
+    fn test_status_defaults_to_bad_request() {
+        let response = ErrorResponse::error("Plain error", None, None).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = ErrorResponse::error("Missing", None, None)
+            .with_status(StatusCode::NOT_FOUND)
+            .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[test]
     fn test_clone() {
         let err = ErrorResponse::error("Clone test", Some("v1".to_string()), None);
@@ -0,0 +1,82 @@
+//! Removal of abandoned hosted games.
+//!
+//! Hosted games stay in memory until someone removes them, so games whose
+//! players left would pile up forever. [`run_bot_server_with_config`] spawns
+//! [`spawn_expiry`], which every [`ServerConfig::expiry_interval_s`] seconds
+//! removes the games unused for longer than [`ServerConfig::game_ttl_s`].
+//! Every removal is logged and counted in the `expired_games` metric, and with
+//! [`ServerConfig::archive_expired`] the games with moves are stored in the
+//! archive first.
+//!
+//! [`run_bot_server_with_config`]: crate::run_bot_server_with_config
+//! [`ServerConfig::expiry_interval_s`]: crate::ServerConfig::expiry_interval_s
+//! [`ServerConfig::game_ttl_s`]: crate::ServerConfig::game_ttl_s
+//! [`ServerConfig::archive_expired`]: crate::ServerConfig::archive_expired
+
+use crate::state::AppState;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Removes the hosted games of `state` unused for longer than its TTL at `now`.
+///
+/// Returns the ids of the removed games, in increasing order.
+pub fn expire_games(state: &AppState, now: Instant) -> Vec<u64> {
+    let removed = state.hosted_games.remove_idle(state.config.game_ttl(), now);
+    for (id, game) in &removed {
+        tracing::info!(game_id = *id, moves = game.move_count(), "Expired abandoned game");
+        state.expired_games.fetch_add(1, Ordering::Relaxed);
+        if state.config.archive_expired
+            && game.move_count() > 0
+            && let Some(archive) = &state.archive
+            && let Err(e) = archive.insert(game)
+        {
+            tracing::warn!("Could not archive expired game {}: {}", id, e);
+        }
+    }
+    removed.into_iter().map(|(id, _)| id).collect()
+}
+
+/// Spawns the task that expires the abandoned games of `state` periodically.
+///
+/// The task ends as soon as `shutdown` becomes `true` or its sender is dropped.
+pub fn spawn_expiry(state: AppState, mut shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(state.config.expiry_interval());
+        // The first tick is immediate and there is nothing to expire yet
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    expire_games(&state, Instant::now());
+                }
+                _ = shutdown.wait_for(|stop| *stop) => break,
+            }
+        }
+        tracing::debug!("Game expiry stopped");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YBotRegistry;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_nothing_expires_without_games() {
+        let state = AppState::new(YBotRegistry::new());
+        assert!(expire_games(&state, Instant::now() + Duration::from_secs(1 << 20)).is_empty());
+        assert_eq!(state.expired_games.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_task_stops_on_shutdown() {
+        let state = AppState::new(YBotRegistry::new());
+        let (stop, shutdown) = watch::channel(false);
+        let task = spawn_expiry(state, shutdown);
+        stop.send_replace(true);
+        tokio::time::timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,577 @@
+//! Hosted games with seats for humans and bots.
+//!
+//! Besides its single built-in game, the server can host any number of games
+//! identified by a number. Each seat is played by a human or by one of the
+//! registered bots. Creating a game hands out one opaque token per human seat;
+//! moves must carry the token of the seat whose turn it is, and bot seats
+//! answer on their own. Clients waiting for the opponent can long-poll
+//! `GET /games/{id}/wait` until a new move is played.
+//!
+//! Tokens are sent as `Authorization: Bearer <token>`.
+
+use crate::{
+    Coordinates, GameStatus, GameY, GameYError, Movement, OpeningOptions, PlayerId, PlayerInfo, PlayerKind, YBot, YEN,
+    error::ErrorResponse,
+    ladder::LadderObserver,
+    state::AppState,
+    wire::{Negotiated, Wire, WireFormat},
+};
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::sync::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Longest time `GET /games/{id}/wait` holds a request.
+pub const MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Who plays a seat.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SeatKind {
+    /// A person, who needs the seat token to move.
+    Human,
+    /// One of the registered bots, which moves as soon as it is its turn.
+    Bot {
+        /// The id of the bot.
+        bot_id: String,
+    },
+}
+
+/// Body of `POST /games`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CreateGameRequest {
+    /// Size of the board, the configured default size if missing.
+    pub size: Option<u32>,
+    /// Who plays each seat, in player order. Two humans if missing.
+    pub seats: Option<Vec<SeatKind>>,
+    /// Names of the human seats, in player order, under which the game is
+    /// rated on the ladder. Games with an unnamed human are not rated.
+    #[serde(default)]
+    pub names: Vec<Option<String>>,
+    /// Whether the second player may swap after the first move. On unless
+    /// one of the other opening options is given, which replace it.
+    pub swap_rule: Option<bool>,
+    /// Whether the first player is drawn at random.
+    #[serde(default)]
+    pub random_first_player: bool,
+    /// Number of stones placed on random cells before play starts.
+    #[serde(default)]
+    pub random_opening_stones: u8,
+    /// Radius around the center where the first move may not be played.
+    pub forbid_center_opening: Option<u32>,
+}
+
+impl CreateGameRequest {
+    /// The opening options of the request.
+    pub fn opening(&self) -> OpeningOptions {
+        let others = self.random_first_player || self.random_opening_stones > 0 || self.forbid_center_opening.is_some();
+        OpeningOptions {
+            swap_rule: self.swap_rule.unwrap_or(!others),
+            random_first_player: self.random_first_player,
+            random_opening_stones: self.random_opening_stones,
+            forbid_center_opening: self.forbid_center_opening,
+        }
+    }
+}
+
+/// A seat as shown to clients.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SeatState {
+    /// The player of the seat.
+    pub player: u32,
+    /// Who plays it.
+    #[serde(flatten)]
+    pub kind: SeatKind,
+    /// Whether someone plays the seat: bots always do, humans once they used their token.
+    pub taken: bool,
+}
+
+/// State of a hosted game, as seen by the holder of a token (if any).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HostedGameState {
+    /// The id of the game.
+    pub id: u64,
+    /// The position in YEN format.
+    pub board: YEN,
+    /// Number of moves played so far; `GET /games/{id}/wait?after=` waits for more.
+    pub moves: usize,
+    /// The id of the winner, if the game is over.
+    pub winner: Option<u32>,
+    /// The seats, in player order.
+    pub seats: Vec<SeatState>,
+    /// The seat of the token sent with the request, if any.
+    pub your_seat: Option<u32>,
+    /// Whether the holder of the token is to move.
+    pub your_turn: bool,
+}
+
+/// Reply of `POST /games`: the new game and the tokens of its human seats.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatedGame {
+    /// One entry per seat, in player order; `None` for bot seats.
+    pub tokens: Vec<Option<String>>,
+    /// The new game.
+    pub game: HostedGameState,
+}
+
+/// Body of `POST /games/{id}/move`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SeatMoveRequest {
+    /// Index of the cell, counting row by row from the top.
+    pub index: u32,
+}
+
+/// Query parameters of `GET /games/{id}/wait`.
+#[derive(Deserialize, Debug, Default)]
+pub struct WaitQuery {
+    /// Number of moves the client already knows; the request returns when there are more.
+    pub after: Option<usize>,
+    /// Longest wait in milliseconds, clamped to [`MAX_WAIT`] (which is also the default).
+    pub timeout_ms: Option<u64>,
+}
+
+struct Seat {
+    kind: SeatKind,
+    token: Option<String>,
+    taken: bool,
+}
+
+/// A game hosted by the server, with its seats.
+pub struct HostedGame {
+    game: GameY,
+    seats: Vec<Seat>,
+    /// Number of moves played, watched by the clients waiting for a move.
+    moves: watch::Sender<usize>,
+    /// When a seat last used the game, for removing abandoned games.
+    last_activity: Instant,
+}
+
+impl HostedGame {
+    fn state(&self, id: u64, seat: Option<PlayerId>) -> HostedGameState {
+        let winner = match self.game.status() {
+            GameStatus::Finished { winner, .. } => Some(winner.id()),
+            GameStatus::Ongoing { .. } => None,
+        };
+        HostedGameState {
+            id,
+            board: YEN::from(&self.game),
+            moves: self.game.ply(),
+            winner,
+            seats: self
+                .seats
+                .iter()
+                .enumerate()
+                .map(|(player, seat)| SeatState {
+                    player: player as u32,
+                    kind: seat.kind.clone(),
+                    taken: seat.taken,
+                })
+                .collect(),
+            your_seat: seat.map(|p| p.id()),
+            your_turn: seat.is_some_and(|seat| self.game.is_players_turn(seat)),
+        }
+    }
+
+    /// The seat of `token`, marking it as taken.
+    fn seat_of(&mut self, token: &str) -> Option<PlayerId> {
+        let player = self.seats.iter().position(|seat| seat.token.as_deref() == Some(token))?;
+        self.seats[player].taken = true;
+        self.last_activity = Instant::now();
+        Some(PlayerId::new(player as u32))
+    }
+
+    /// The bot that plays the next move, if it is a bot's turn.
+    fn bot_to_move(&self) -> Option<(PlayerId, String)> {
+        let player = self.game.side_to_move()?;
+        match &self.seats.get(player.id() as usize)?.kind {
+            SeatKind::Bot { bot_id } => Some((player, bot_id.clone())),
+            SeatKind::Human => None,
+        }
+    }
+
+    fn play(&mut self, movement: Movement) -> crate::Result<()> {
+        self.game.add_move(movement)?;
+        self.last_activity = Instant::now();
+        self.moves.send_replace(self.game.ply());
+        Ok(())
+    }
+}
+
+/// The games hosted by the server.
+#[derive(Default)]
+pub struct HostedGames {
+    games: Mutex<HashMap<u64, Arc<Mutex<HostedGame>>>>,
+    next_id: AtomicU64,
+}
+
+impl HostedGames {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of hosted games.
+    pub fn len(&self) -> usize {
+        self.games.lock().len()
+    }
+
+    /// Whether no game is hosted.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The id of every hosted game with the time since it was last used at `now`, by id.
+    pub fn ages(&self, now: Instant) -> Vec<(u64, Duration)> {
+        let games = self.games.lock();
+        let mut ages: Vec<(u64, Duration)> = games
+            .iter()
+            .map(|(id, game)| (*id, now.saturating_duration_since(game.lock().last_activity)))
+            .collect();
+        ages.sort_unstable_by_key(|(id, _)| *id);
+        ages
+    }
+
+    /// Removes the games unused for at least `idle` at `now` and returns them, by id.
+    pub fn remove_idle(&self, idle: Duration, now: Instant) -> Vec<(u64, GameY)> {
+        let mut games = self.games.lock();
+        let mut ids: Vec<u64> = games
+            .iter()
+            .filter(|(_, game)| now.saturating_duration_since(game.lock().last_activity) >= idle)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| games.remove(&id).map(|game| (id, game.lock().game.clone())))
+            .collect()
+    }
+
+    /// Removes every hosted game and returns how many there were.
+    pub fn clear(&self) -> usize {
+        let mut games = self.games.lock();
+        let count = games.len();
+        games.clear();
+        count
+    }
+
+    fn insert(&self, game: HostedGame) -> (u64, Arc<Mutex<HostedGame>>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let game = Arc::new(Mutex::new(game));
+        self.games.lock().insert(id, Arc::clone(&game));
+        (id, game)
+    }
+
+    fn get(&self, id: u64) -> Result<Arc<Mutex<HostedGame>>, ErrorResponse> {
+        self.games.lock().get(&id).cloned().ok_or_else(|| {
+            ErrorResponse::error(&format!("No game with id {}", id), None, None)
+                .with_code("GAME_NOT_FOUND")
+                .with_status(StatusCode::NOT_FOUND)
+        })
+    }
+}
+
+/// Handler for `POST /games`: creates a game and returns the tokens of its human seats.
+///
+/// Bot seats must name registered bots. If the first seat is a bot, it has
+/// already moved when the reply is sent. The game is rated on the ladder when
+/// it finishes if every human seat was given a name. Opening options that
+/// conflict are rejected with the code `INVALID_OPENING`.
+pub async fn create_game(
+    State(state): State<AppState>,
+    format: WireFormat,
+    Negotiated(request): Negotiated<CreateGameRequest>,
+) -> Result<Wire<CreatedGame>, Wire<ErrorResponse>> {
+    let size = request.size.unwrap_or(state.config.default_board_size);
+    let new_game = GameY::try_new(size)
+        .map_err(|e| format.error(ErrorResponse::error(&e.to_string(), None, None).with_code("INVALID_SIZE")))?;
+    let new_game = new_game
+        .with_opening(&request.opening(), &mut rand::rng())
+        .map_err(|e| format.error(ErrorResponse::error(&e.to_string(), None, None).with_code("INVALID_OPENING")))?;
+    let kinds = request.seats.unwrap_or_else(|| vec![SeatKind::Human, SeatKind::Human]);
+    if kinds.len() != 2 {
+        return Err(format.error(
+            ErrorResponse::error(&format!("A game has 2 seats, found {}", kinds.len()), None, None)
+                .with_code("INVALID_SEATS"),
+        ));
+    }
+    for kind in &kinds {
+        if let SeatKind::Bot { bot_id } = kind {
+            state.find_bot(bot_id, None).map_err(|error| format.error(error))?;
+        }
+    }
+
+    // Los jugadores identifican la partida en el ladder; los humanos sin nombre no puntúan
+    let players = kinds
+        .iter()
+        .enumerate()
+        .filter_map(|(seat, kind)| {
+            let player = PlayerId::new(seat as u32);
+            match kind {
+                SeatKind::Bot { bot_id } => Some(PlayerInfo::new(player, bot_id.clone(), PlayerKind::Bot(bot_id.clone()))),
+                SeatKind::Human => request
+                    .names
+                    .get(seat)
+                    .cloned()
+                    .flatten()
+                    .map(|name| PlayerInfo::new(player, name, PlayerKind::Human)),
+            }
+        })
+        .collect();
+    let mut new_game = new_game.with_player_info(players);
+    new_game.subscribe(Box::new(LadderObserver::new(Arc::clone(&state.ladder))));
+
+    let seats: Vec<Seat> = kinds
+        .into_iter()
+        .map(|kind| Seat {
+            token: (kind == SeatKind::Human).then(new_token),
+            taken: kind != SeatKind::Human,
+            kind,
+        })
+        .collect();
+    let tokens = seats.iter().map(|seat| seat.token.clone()).collect();
+    let (id, game) = state.hosted_games.insert(HostedGame {
+        game: new_game,
+        seats,
+        moves: watch::Sender::new(0),
+        last_activity: Instant::now(),
+    });
+    play_bots(&state, &game).await;
+
+    let reply = game.lock().state(id, None);
+    Ok(format.reply(CreatedGame { tokens, game: reply }))
+}
+
+/// Handler for `GET /games/{id}`: the game as seen by the holder of the token, if any.
+///
+/// An unknown token is rejected with 401.
+pub async fn get_game(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+    format: WireFormat,
+) -> Result<Wire<HostedGameState>, Wire<ErrorResponse>> {
+    let game = state.hosted_games.get(id).map_err(|e| format.error(e))?;
+    let mut game = game.lock();
+    let seat = match bearer_token(&headers) {
+        Some(token) => Some(game.seat_of(token).ok_or_else(|| format.error(unauthorized()))?),
+        None => None,
+    };
+    Ok(format.reply(game.state(id, seat)))
+}
+
+/// Handler for `POST /games/{id}/move`: plays a move for the seat of the token.
+///
+/// Fails with 401 without a valid token, 403 if it is not the turn of the
+/// token's seat and 400 if the move is illegal. Bot seats answer before the
+/// reply is sent.
+pub async fn play_move(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+    format: WireFormat,
+    Negotiated(request): Negotiated<SeatMoveRequest>,
+) -> Result<Wire<HostedGameState>, Wire<ErrorResponse>> {
+    let game = state.hosted_games.get(id).map_err(|e| format.error(e))?;
+    let player = {
+        let mut hosted = game.lock();
+        let token = bearer_token(&headers).ok_or_else(|| format.error(unauthorized()))?;
+        let player = hosted.seat_of(token).ok_or_else(|| format.error(unauthorized()))?;
+        if !hosted.game.is_players_turn(player) {
+            return Err(format.error(
+                ErrorResponse::error(&format!("It is not the turn of player {}", player), None, None)
+                    .with_code("NOT_YOUR_TURN")
+                    .with_status(StatusCode::FORBIDDEN),
+            ));
+        }
+        let invalid = |e: GameYError| {
+            let code = match e {
+                GameYError::ForbiddenOpening { .. } => "FORBIDDEN_OPENING",
+                _ => "INVALID_MOVE",
+            };
+            format.error(ErrorResponse::error(&e.to_string(), None, None).with_code(code))
+        };
+        let coords = Coordinates::try_from_index(request.index, hosted.game.board_size()).map_err(invalid)?;
+        hosted.play(Movement::Placement { player, coords }).map_err(invalid)?;
+        player
+    };
+    play_bots(&state, &game).await;
+
+    let reply = game.lock().state(id, Some(player));
+    Ok(format.reply(reply))
+}
+
+/// Handler for `GET /games/{id}/wait?after=&timeout_ms=`: long-poll for the opponent's move.
+///
+/// Returns as soon as the game has more than `after` moves (immediately if it
+/// already has), or with the current state when the timeout expires.
+pub async fn wait_for_move(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Query(query): Query<WaitQuery>,
+    headers: HeaderMap,
+    format: WireFormat,
+) -> Result<Wire<HostedGameState>, Wire<ErrorResponse>> {
+    let game = state.hosted_games.get(id).map_err(|e| format.error(e))?;
+    let mut moves = game.lock().moves.subscribe();
+    let after = query.after.unwrap_or(0);
+    let timeout = query.timeout_ms.map(Duration::from_millis).unwrap_or(MAX_WAIT).min(MAX_WAIT);
+    // Timing out is a normal outcome of a long poll
+    let _ = tokio::time::timeout(timeout, moves.wait_for(|count| *count > after)).await;
+    get_game(State(state), Path(id), headers, format).await
+}
+
+/// Lets the bots play while it is the turn of a bot seat.
+///
+/// Bots think on a copy of the game without holding its lock. A move is only
+/// applied if the game did not change meanwhile.
+async fn play_bots(state: &AppState, game: &Arc<Mutex<HostedGame>>) {
+    loop {
+        let (player, bot_id, snapshot) = {
+            let hosted = game.lock();
+            let Some((player, bot_id)) = hosted.bot_to_move() else {
+                return;
+            };
+            (player, bot_id, hosted.game.clone())
+        };
+        let Some(bot) = state.bots().find(&bot_id) else {
+            return;
+        };
+        let version = snapshot.ply();
+        let position = snapshot.clone();
+        let context = state.bot_contexts.get(snapshot.board_size());
+        let coords = tokio::task::spawn_blocking(move || bot.choose_move_with_context(&position, &context, None).0)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Bot {} failed, playing a random move: {}", bot_id, e);
+                crate::RandomBot.choose_move(&snapshot)
+            });
+        let Some(mut coords) = coords else {
+            return;
+        };
+        // Los bots no conocen las reglas de apertura: si eligen el centro prohibido, juegan otra celda
+        let placement = |coords| Movement::Placement { player, coords };
+        if snapshot.check_opening_rules(&placement(coords)).is_err() {
+            let size = snapshot.board_size();
+            let allowed = snapshot.available_cells().iter().map(|idx| Coordinates::from_index(*idx, size));
+            match allowed.filter(|c| snapshot.check_opening_rules(&placement(*c)).is_ok()).min_by_key(|c| c.to_index(size)) {
+                Some(other) => coords = other,
+                None => return,
+            }
+        }
+        let mut hosted = game.lock();
+        if hosted.game.ply() != version || hosted.play(Movement::Placement { player, coords }).is_err() {
+            return;
+        }
+    }
+}
+
+/// The token of an `Authorization: Bearer` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn unauthorized() -> ErrorResponse {
+    ErrorResponse::error("A valid seat token is required", None, None)
+        .with_code("UNAUTHORIZED")
+        .with_status(StatusCode::UNAUTHORIZED)
+}
+
+/// A new random seat token.
+fn new_token() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosted(kinds: [SeatKind; 2]) -> HostedGame {
+        HostedGame {
+            game: GameY::new(3),
+            seats: kinds
+                .into_iter()
+                .map(|kind| Seat {
+                    token: (kind == SeatKind::Human).then(new_token),
+                    taken: false,
+                    kind,
+                })
+                .collect(),
+            moves: watch::Sender::new(0),
+            last_activity: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_tokens_identify_their_seat() {
+        let mut game = hosted([SeatKind::Human, SeatKind::Human]);
+        let second = game.seats[1].token.clone().unwrap();
+        assert_ne!(game.seats[0].token, game.seats[1].token);
+        assert_eq!(game.seat_of(&second), Some(PlayerId::new(1)));
+        assert_eq!(game.seat_of("nope"), None);
+
+        let state = game.state(1, Some(PlayerId::new(1)));
+        assert!(!state.seats[0].taken);
+        assert!(state.seats[1].taken);
+        assert!(!state.your_turn);
+    }
+
+    #[test]
+    fn test_bot_to_move() {
+        let bot = SeatKind::Bot {
+            bot_id: "random_bot".to_string(),
+        };
+        let game = hosted([bot.clone(), SeatKind::Human]);
+        assert_eq!(game.bot_to_move(), Some((PlayerId::new(0), "random_bot".to_string())));
+        let game = hosted([SeatKind::Human, bot]);
+        assert_eq!(game.bot_to_move(), None);
+    }
+
+    #[test]
+    fn test_seat_kind_serialization() {
+        let bot = SeatKind::Bot {
+            bot_id: "random_bot".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&bot).unwrap(),
+            serde_json::json!({"kind": "bot", "bot_id": "random_bot"})
+        );
+        let human: SeatKind = serde_json::from_str(r#"{"kind":"human"}"#).unwrap();
+        assert_eq!(human, SeatKind::Human);
+    }
+
+    #[test]
+    fn test_remove_idle_games() {
+        let games = HostedGames::new();
+        let (first, _) = games.insert(hosted([SeatKind::Human, SeatKind::Human]));
+        let later = Instant::now() + Duration::from_secs(60);
+        let (second, recent) = games.insert(hosted([SeatKind::Human, SeatKind::Human]));
+        recent.lock().last_activity = later;
+
+        assert_eq!(games.ages(later).iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![first, second]);
+        let removed = games.remove_idle(Duration::from_secs(30), later);
+        assert_eq!(removed.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![first]);
+        assert_eq!(games.len(), 1);
+        assert_eq!(games.clear(), 1);
+        assert!(games.is_empty());
+        assert_eq!(second, first + 1);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+        headers.insert(AUTHORIZATION, "Bearer abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("abc"));
+        headers.insert(AUTHORIZATION, "Basic abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+    }
+}
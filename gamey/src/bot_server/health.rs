@@ -0,0 +1,260 @@
+//! Detailed health report for orchestrators and dashboards.
+//!
+//! `GET /health` returns a [`HealthResponse`] with the build and the runtime
+//! state of the server. `GET /status` keeps answering the plain `OK` that the
+//! existing deploy checks expect.
+//!
+//! Orchestrators such as Kubernetes probe two things apart: `GET /livez`
+//! answers `OK` as soon as the process serves requests, and `GET /readyz`
+//! answers 503 until the server is ready for traffic, that is until the
+//! [`Readiness`] of its state is marked ready, with the state of every
+//! component in a [`ReadinessResponse`]. [`warm_up`] gives every bot a first
+//! search and then marks the server ready.
+
+use crate::GameY;
+use crate::state::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Git commit the server was built from, if `GAMEY_GIT_HASH` was set at build time.
+pub const GIT_HASH: Option<&str> = option_env!("GAMEY_GIT_HASH");
+
+/// State of the archive of finished games.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveHealth {
+    /// The server does not archive games.
+    Disabled,
+    /// The archive answers queries.
+    Ok,
+    /// The archive failed to answer a query.
+    Failing,
+}
+
+/// Reply of `GET /health`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HealthResponse {
+    /// `"ok"`, or `"degraded"` when the archive is failing.
+    pub status: String,
+    /// Version of the crate.
+    pub version: String,
+    /// Git commit of the build, when known.
+    pub git_hash: Option<String>,
+    /// Seconds since the server state was created.
+    pub uptime_s: u64,
+    /// Number of registered bots.
+    pub bot_count: usize,
+    /// Ids of the registered bots.
+    pub bots: Vec<String>,
+    /// Number of hosted games in memory.
+    pub active_games: usize,
+    /// State of the archive of finished games.
+    pub archive: ArchiveHealth,
+}
+
+impl HealthResponse {
+    /// Collects the report from the application state at `now`.
+    pub fn collect(state: &AppState, now: Instant) -> Self {
+        let archive = match &state.archive {
+            None => ArchiveHealth::Disabled,
+            Some(archive) if archive.list(1, 0).is_ok() => ArchiveHealth::Ok,
+            Some(_) => ArchiveHealth::Failing,
+        };
+        let bots = state.bots().names();
+        Self {
+            status: if archive == ArchiveHealth::Failing { "degraded" } else { "ok" }.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: GIT_HASH.map(str::to_string),
+            uptime_s: now.saturating_duration_since(state.started_at).as_secs(),
+            bot_count: bots.len(),
+            bots,
+            active_games: state.hosted_games.len(),
+            archive,
+        }
+    }
+}
+
+/// Handler for `GET /health`: 200 when healthy, 503 when degraded.
+pub async fn health(State(state): State<AppState>) -> Response {
+    let report = HealthResponse::collect(&state, Instant::now());
+    let status = match report.archive {
+        ArchiveHealth::Failing => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    (status, axum::Json(report)).into_response()
+}
+
+/// Board size of the warm-up searches of [`warm_up`].
+const WARM_UP_BOARD_SIZE: u32 = 5;
+
+/// Longest time a bot searches during [`warm_up`].
+const WARM_UP_TIME: Duration = Duration::from_millis(100);
+
+/// State of one part of the server that has to be loaded before it takes traffic.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    /// Still loading.
+    Pending,
+    /// Loaded.
+    Ready,
+    /// Not used by this server.
+    Disabled,
+}
+
+/// Whether the server is ready for traffic, and the state of its components.
+///
+/// A new `Readiness` is not ready. The server is only ready once
+/// [`Readiness::mark_ready`] is called, whatever its components say.
+#[derive(Debug, Default)]
+pub struct Readiness {
+    ready: AtomicBool,
+    components: crate::sync::Mutex<BTreeMap<String, ComponentStatus>>,
+}
+
+impl Readiness {
+    /// Creates a readiness that is not ready, with the given components.
+    pub fn new(components: &[(&str, ComponentStatus)]) -> Self {
+        let readiness = Self::default();
+        for (name, status) in components {
+            readiness.set(name, *status);
+        }
+        readiness
+    }
+
+    /// Records the state of `component`.
+    pub fn set(&self, component: &str, status: ComponentStatus) {
+        self.components.lock().insert(component.to_string(), status);
+    }
+
+    /// Marks the server ready for traffic.
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` once [`Readiness::mark_ready`] was called.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// The reply of `GET /readyz`.
+    pub fn report(&self) -> ReadinessResponse {
+        ReadinessResponse {
+            ready: self.is_ready(),
+            components: self.components.lock().clone(),
+        }
+    }
+}
+
+/// Reply of `GET /readyz`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReadinessResponse {
+    /// Whether the server takes traffic.
+    pub ready: bool,
+    /// The state of each component, by name: `bots`, `archive`, `ladder` and `warm_up`.
+    pub components: BTreeMap<String, ComponentStatus>,
+}
+
+/// Handler for `GET /livez`: `OK` while the process serves requests.
+pub async fn livez() -> impl IntoResponse {
+    "OK"
+}
+
+/// Handler for `GET /readyz`: 200 when the server is ready, 503 before.
+pub async fn readyz(State(state): State<AppState>) -> Response {
+    let report = state.readiness.report();
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, axum::Json(report)).into_response()
+}
+
+/// Lets every registered bot search an empty board once, so the first
+/// requests do not pay for their allocations, and marks the server ready.
+///
+/// Blocks for up to a tenth of a second per bot.
+pub fn warm_up(state: &AppState) {
+    let bots = state.bots();
+    let board = GameY::new(WARM_UP_BOARD_SIZE);
+    for name in bots.names() {
+        if let Some(bot) = bots.find(&name) {
+            bot.choose_move_with_deadline(&board, Instant::now() + WARM_UP_TIME);
+        }
+    }
+    state.readiness.set("warm_up", ComponentStatus::Ready);
+    state.readiness.mark_ready();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArchivedGame, GameArchive, GameY, GameYError, RandomBot, YBotRegistry};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    struct BrokenArchive;
+
+    impl GameArchive for BrokenArchive {
+        fn insert(&self, _game: &GameY) -> Result<i64, GameYError> {
+            Err(GameYError::ArchiveError { message: "down".to_string() })
+        }
+        fn list(&self, _limit: u32, _offset: u32) -> Result<Vec<ArchivedGame>, GameYError> {
+            Err(GameYError::ArchiveError { message: "down".to_string() })
+        }
+        fn get(&self, _id: i64) -> Result<Option<ArchivedGame>, GameYError> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_collect_reports_bots_and_uptime() {
+        let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(RandomBot)));
+        let report = HealthResponse::collect(&state, state.started_at + Duration::from_secs(90));
+        assert_eq!(report.status, "ok");
+        assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(report.uptime_s, 90);
+        assert_eq!(report.bots, vec!["random_bot".to_string()]);
+        assert_eq!(report.bot_count, 1);
+        assert_eq!(report.active_games, 0);
+        assert_eq!(report.archive, ArchiveHealth::Disabled);
+    }
+
+    #[test]
+    fn test_failing_archive_degrades_the_report() {
+        let state = AppState::new(YBotRegistry::new()).with_archive(Arc::new(BrokenArchive));
+        let report = HealthResponse::collect(&state, Instant::now());
+        assert_eq!(report.archive, ArchiveHealth::Failing);
+        assert_eq!(report.status, "degraded");
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["archive"], "failing");
+    }
+
+    #[test]
+    fn test_readiness_waits_for_the_flag() {
+        let readiness = Readiness::new(&[("bots", ComponentStatus::Ready), ("warm_up", ComponentStatus::Pending)]);
+        assert!(!readiness.report().ready);
+        readiness.set("warm_up", ComponentStatus::Ready);
+        assert!(!readiness.is_ready(), "ready components do not make the server ready");
+        readiness.mark_ready();
+        let report = readiness.report();
+        assert!(report.ready);
+        assert_eq!(report.components["warm_up"], ComponentStatus::Ready);
+    }
+
+    #[test]
+    fn test_warm_up_marks_the_state_ready() {
+        let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(RandomBot)));
+        assert_eq!(state.readiness.report().components["warm_up"], ComponentStatus::Pending);
+        warm_up(&state);
+        let report = state.readiness.report();
+        assert!(report.ready);
+        assert_eq!(report.components["warm_up"], ComponentStatus::Ready);
+    }
+}
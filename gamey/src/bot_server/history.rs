@@ -0,0 +1,215 @@
+//! The move list of the server game, for the move-list sidebar of the frontend.
+//!
+//! `GET /game/history?offset=&limit=` returns a page of the moves of the
+//! current game, oldest first. Placements say whether they connected the
+//! player's stones to a side they did not touch before.
+
+use crate::{
+    BoardTopology, CellIndex, Coordinates, GameAction, GameEngine, GameSnapshot, Movement, TriangularTopology,
+    state::AppState,
+    wire::{Wire, WireFormat},
+};
+use axum::extract::{Query, State};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Number of moves per page when the query does not give a limit.
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Largest page the endpoint returns, whatever the query asks for.
+pub const MAX_HISTORY_LIMIT: usize = 200;
+
+/// What a move did.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveKind {
+    /// A stone was placed.
+    Placement,
+    /// The second player took the first player's stone (pie rule).
+    Swap,
+    /// The player gave up.
+    Resign,
+}
+
+/// One move of the history.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Number of the move, starting at 1.
+    pub ply: usize,
+    /// The id of the player who made it.
+    pub player: u32,
+    /// What the move did.
+    #[serde(rename = "type")]
+    pub kind: MoveKind,
+    /// Where the stone was placed, for placements.
+    pub coords: Option<Coordinates>,
+    /// The cell of the stone, counting row by row from the top, for placements.
+    pub index: Option<u32>,
+    /// Whether the stone's group touches a side that none of the groups it
+    /// joined touched before, for placements.
+    pub new_side: Option<bool>,
+}
+
+/// Query parameters of `GET /game/history`.
+#[derive(Deserialize, Debug, Default)]
+pub struct HistoryQuery {
+    /// Number of moves to skip.
+    pub offset: Option<usize>,
+    /// Number of moves to return, at most [`MAX_HISTORY_LIMIT`].
+    pub limit: Option<usize>,
+}
+
+/// A page of the history.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryPage {
+    /// The moves of the page, oldest first. Empty past the last move.
+    pub moves: Vec<HistoryEntry>,
+    /// The offset that was applied.
+    pub offset: usize,
+    /// The limit that was applied.
+    pub limit: usize,
+    /// Number of moves of the whole game.
+    pub total: usize,
+}
+
+/// Describes every move of the game of `snapshot`, oldest first.
+pub fn history_entries(snapshot: &GameSnapshot) -> Vec<HistoryEntry> {
+    let size = snapshot.board_size();
+    let history: Vec<Movement> = snapshot.moves().collect();
+    let placed: HashSet<CellIndex> = history
+        .iter()
+        .filter_map(|movement| match movement {
+            Movement::Placement { coords, .. } => Some(coords.to_index(size) as CellIndex),
+            Movement::Action { .. } => None,
+        })
+        .collect();
+
+    // Se parte de las piedras colocadas antes de la primera jugada (aperturas
+    // aleatorias) y se reproducen las colocaciones para ver los grupos antes y
+    // después de cada una
+    let mut engine = GameEngine::new(TriangularTopology::with_players(size, snapshot.num_players()));
+    for cell in 0..snapshot.total_cells() {
+        if let Some(player) = snapshot.occupant(cell)
+            && !placed.contains(&cell)
+        {
+            let _ = engine.make_move(cell, player);
+        }
+    }
+
+    history
+        .iter()
+        .enumerate()
+        .map(|(ply, movement)| match movement {
+            Movement::Placement { player, coords } => {
+                let cell = coords.to_index(size) as CellIndex;
+                let before = engine
+                    .topology()
+                    .get_neighbors(cell)
+                    .iter()
+                    .filter(|n| engine.occupant(**n) == Some(*player))
+                    .fold(0, |regions, n| regions | engine.regions_of_group(*n).unwrap_or(0));
+                let _ = engine.make_move(cell, *player);
+                let after = engine.regions_of_group(cell).unwrap_or(0);
+                HistoryEntry {
+                    ply: ply + 1,
+                    player: player.id(),
+                    kind: MoveKind::Placement,
+                    coords: Some(*coords),
+                    index: Some(coords.to_index(size)),
+                    new_side: Some(after & !before != 0),
+                }
+            }
+            Movement::Action { player, action } => HistoryEntry {
+                ply: ply + 1,
+                player: player.id(),
+                kind: match action {
+                    GameAction::Swap => MoveKind::Swap,
+                    GameAction::Resign => MoveKind::Resign,
+                },
+                coords: None,
+                index: None,
+                new_side: None,
+            },
+        })
+        .collect()
+}
+
+/// Handler for `GET /game/history?offset=&limit=`: a page of the moves of the server game.
+pub async fn game_history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+    format: WireFormat,
+) -> Wire<HistoryPage> {
+    // La copia se toma con el bloqueo; la descripción de las jugadas, sin él
+    let snapshot = state.game.lock().snapshot();
+    let entries = history_entries(&snapshot);
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).min(MAX_HISTORY_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    let total = entries.len();
+    let moves = entries.into_iter().skip(offset).take(limit).collect();
+    format.reply(HistoryPage {
+        moves,
+        offset,
+        limit,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameY, PlayerId};
+
+    fn place(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_new_side_of_placements() {
+        let mut game = GameY::new(4);
+        // A corner stone touches two sides at once
+        place(&mut game, 0, Coordinates::new(3, 0, 0));
+        // An inner stone touches none
+        place(&mut game, 1, Coordinates::new(1, 1, 1));
+        // Extending the corner stone along its sides adds nothing
+        place(&mut game, 0, Coordinates::new(2, 1, 0));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        let entries = history_entries(&game.snapshot());
+        let new_sides: Vec<Option<bool>> = entries.iter().map(|e| e.new_side).collect();
+        assert_eq!(new_sides, vec![Some(true), Some(false), Some(false), None]);
+        assert_eq!(entries[3].kind, MoveKind::Resign);
+        assert_eq!(entries[3].ply, 4);
+        assert_eq!(entries[0].index, Some(Coordinates::new(3, 0, 0).to_index(4)));
+    }
+
+    #[test]
+    fn test_joining_a_side_is_new() {
+        let mut game = GameY::new(4);
+        place(&mut game, 0, Coordinates::new(1, 1, 1));
+        place(&mut game, 1, Coordinates::new(3, 0, 0));
+        // Connects the inner stone to the z = 0 side
+        place(&mut game, 0, Coordinates::new(1, 2, 0));
+        assert_eq!(history_entries(&game.snapshot())[2].new_side, Some(true));
+    }
+
+    #[test]
+    fn test_stones_placed_before_the_first_move_count() {
+        let mut editor = GameY::new(4).editor();
+        editor.place(PlayerId::new(0), Coordinates::new(3, 0, 0)).unwrap();
+        let mut game = editor.finish(PlayerId::new(0)).unwrap();
+        // The corner stone already touches both sides of this one
+        place(&mut game, 0, Coordinates::new(2, 1, 0));
+        let entries = history_entries(&game.snapshot());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].new_side, Some(false));
+    }
+}
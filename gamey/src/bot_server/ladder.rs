@@ -0,0 +1,215 @@
+//! Elo ladder of the server: `GET /ladder`.
+//!
+//! A [`LadderObserver`] subscribed to the server game and to every hosted
+//! game rates its players as soon as the game finishes. Bots are rated by
+//! their id and humans by the name given when the game was created; games
+//! with an unnamed human are not rated. With a state directory the ladder is
+//! saved to [`LADDER_FILE`] there after every rated game, and read back when
+//! the server starts.
+
+use crate::rating::{Ladder, LadderEntry};
+use crate::{GameObserver, GameY, GameYError, PlayerId, PlayerInfo, state::AppState};
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+use crate::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// File of the state directory where the ladder is saved.
+pub const LADDER_FILE: &str = "ladder.json";
+
+/// The ladder of the server, saved to a file if it has one.
+#[derive(Debug, Default)]
+pub struct LadderStore {
+    ladder: Mutex<Ladder>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl LadderStore {
+    /// Creates an empty ladder kept in memory, with the given base K-factor.
+    pub fn new(k_factor: f64) -> Self {
+        Self {
+            ladder: Mutex::new(Ladder::new(k_factor)),
+            path: Mutex::new(None),
+        }
+    }
+
+    /// Saves the ladder to `path` from now on, first reading the ratings
+    /// already saved there, if the file exists.
+    ///
+    /// The K-factor stays the configured one, not the one of the file.
+    ///
+    /// # Errors
+    /// Returns `GameYError::IoError` if the file cannot be read and
+    /// `GameYError::CorruptFile` if it is not a saved ladder.
+    pub fn persist_to(&self, path: &Path) -> Result<(), GameYError> {
+        if path.exists() {
+            let text = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+                message: format!("Failed to read ladder file {}", path.display()),
+                error: e.to_string(),
+            })?;
+            let saved: Ladder = serde_json::from_str(&text).map_err(|error| GameYError::CorruptFile {
+                path: path.display().to_string(),
+                error,
+            })?;
+            let mut ladder = self.ladder.lock();
+            let k_factor = ladder.k_factor;
+            *ladder = saved;
+            ladder.k_factor = k_factor;
+        }
+        *self.path.lock() = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Updates the ratings after `winner` beat `loser` and saves the ladder.
+    pub fn record(&self, winner: &PlayerInfo, loser: &PlayerInfo) {
+        let mut ladder = self.ladder.lock();
+        ladder.record(winner, loser);
+        if let Some(path) = self.path.lock().as_ref()
+            && let Err(e) = save(&ladder, path)
+        {
+            tracing::warn!("Could not save the ladder to {}: {}", path.display(), e);
+        }
+    }
+
+    /// A copy of the current ladder.
+    pub fn snapshot(&self) -> Ladder {
+        self.ladder.lock().clone()
+    }
+}
+
+fn save(ladder: &Ladder, path: &Path) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(ladder).map_err(std::io::Error::other)?;
+    // Se escribe aparte y se renombra para no dejar un fichero a medias
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(tmp, path)
+}
+
+/// Game observer that rates the players of every finished game on a ladder.
+///
+/// The players are rated as the game names them: the server game names its
+/// player 1 after the bot that answers it (see [`AppState::game_bot`]), and
+/// hosted games name their bot seats when they are created.
+pub struct LadderObserver {
+    ladder: Arc<LadderStore>,
+}
+
+impl LadderObserver {
+    /// Creates an observer rating the finished games on `ladder`.
+    pub fn new(ladder: Arc<LadderStore>) -> Self {
+        Self { ladder }
+    }
+
+    /// The participant of every player, or `None` if someone cannot be rated.
+    fn participants(&self, game: &GameY) -> Option<Vec<PlayerInfo>> {
+        game.players()
+            .into_iter()
+            .map(|info| (info != PlayerInfo::default_for(info.id)).then_some(info))
+            .collect()
+    }
+}
+
+impl GameObserver for LadderObserver {
+    fn on_finish(&self, game: &GameY, winner: PlayerId) {
+        let Some(players) = self.participants(game) else {
+            return;
+        };
+        // El ladder solo puntúa partidas de dos jugadores
+        if let [first, second] = players.as_slice() {
+            let (winner, loser) = if first.id == winner { (first, second) } else { (second, first) };
+            self.ladder.record(winner, loser);
+        }
+    }
+}
+
+/// Reply of `GET /ladder`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LadderResponse {
+    /// K-factor of the participants past their provisional games.
+    pub k_factor: f64,
+    /// The participants, from the highest rating to the lowest.
+    pub ratings: Vec<LadderEntry>,
+}
+
+/// Handler for `GET /ladder`: the ratings of every rated participant.
+pub async fn ladder(State(state): State<AppState>) -> Json<LadderResponse> {
+    let ladder = state.ladder.snapshot();
+    Json(LadderResponse {
+        k_factor: ladder.k_factor,
+        ratings: ladder.standings(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, Movement, PlayerKind};
+
+    fn resigned_by(game: GameY, player: u32) -> GameY {
+        let mut game = game;
+        game.add_move(Movement::Action {
+            player: PlayerId::new(player),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game
+    }
+
+    fn observed(game: GameY, observer: LadderObserver) -> GameY {
+        let mut game = game;
+        game.subscribe(Box::new(observer));
+        game
+    }
+
+    #[test]
+    fn test_named_human_against_a_bot_is_rated() {
+        let ladder = Arc::new(LadderStore::new(32.0));
+        let observer = LadderObserver::new(Arc::clone(&ladder));
+        let game = GameY::new(3).with_player_info(vec![
+            PlayerInfo::new(PlayerId::new(0), "alice", PlayerKind::Human),
+            PlayerInfo::new(PlayerId::new(1), "Random", PlayerKind::Bot("random_bot".to_string())),
+        ]);
+        resigned_by(observed(game, observer), 1);
+
+        let ratings = ladder.snapshot().standings();
+        assert_eq!(ratings.len(), 2);
+        assert_eq!((ratings[0].name.as_str(), ratings[0].bot), ("alice", false));
+        assert_eq!((ratings[1].name.as_str(), ratings[1].bot), ("random_bot", true));
+    }
+
+    #[test]
+    fn test_unnamed_humans_are_not_rated() {
+        let ladder = Arc::new(LadderStore::new(32.0));
+        let game = GameY::new(3).with_player_info(vec![PlayerInfo::new(
+            PlayerId::new(1),
+            "Random",
+            PlayerKind::Bot("random_bot".to_string()),
+        )]);
+        resigned_by(observed(game, LadderObserver::new(Arc::clone(&ladder))), 0);
+        assert!(ladder.snapshot().standings().is_empty());
+    }
+
+    #[test]
+    fn test_ladder_is_saved_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LADDER_FILE);
+
+        let ladder = LadderStore::new(32.0);
+        ladder.persist_to(&path).unwrap();
+        let alice = PlayerInfo::new(PlayerId::new(0), "alice", PlayerKind::Human);
+        let bot = PlayerInfo::new(PlayerId::new(1), "Bot", PlayerKind::Bot("bridge_bot".to_string()));
+        ladder.record(&alice, &bot);
+
+        let reloaded = LadderStore::new(16.0);
+        reloaded.persist_to(&path).unwrap();
+        assert_eq!(reloaded.snapshot().standings(), ladder.snapshot().standings());
+        assert_eq!(reloaded.snapshot().k_factor, 16.0);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            LadderStore::new(32.0).persist_to(&path),
+            Err(GameYError::CorruptFile { .. })
+        ));
+    }
+}
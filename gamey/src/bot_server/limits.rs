@@ -0,0 +1,150 @@
+//! Request body size limit and request timeouts of the bot server.
+//!
+//! Every request body is limited to [`crate::ServerConfig::max_body_bytes`]
+//! and every request to [`crate::ServerConfig::request_timeout_ms`], except
+//! the slow routes (game reviews and long polls), which get
+//! [`crate::ServerConfig::long_request_timeout_ms`]. Requests over the limits
+//! are answered with the usual [`ErrorResponse`] body: 413 `PAYLOAD_TOO_LARGE`
+//! and 408 `REQUEST_TIMEOUT`. A handler that panics gets a 500
+//! `INTERNAL_ERROR` instead of a reset connection.
+
+use crate::error::ErrorResponse;
+use crate::wire::WireFormat;
+use axum::extract::{DefaultBodyLimit, Request};
+use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::any::Any;
+use std::time::Duration;
+use tower_http::catch_panic::CatchPanicLayer;
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
+
+/// Largest request body accepted when none is configured: 64 KiB.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Longest time a request may take when none is configured.
+pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 10_000;
+
+/// Longest time a slow request (reviews, long polls) may take when none is configured.
+pub const DEFAULT_LONG_REQUEST_TIMEOUT_MS: u64 = 60_000;
+
+/// Applies the body size limit to every route of `router`.
+///
+/// Axum's own default limit is disabled so that the configured one is the only limit.
+pub fn limit_body<S: Clone + Send + Sync + 'static>(router: axum::Router<S>, max_bytes: usize) -> axum::Router<S> {
+    router
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(max_bytes))
+}
+
+/// Answers with 408 the requests of `router` that take longer than `timeout`.
+pub fn limit_time<S: Clone + Send + Sync + 'static>(router: axum::Router<S>, timeout: Duration) -> axum::Router<S> {
+    router.layer(TimeoutLayer::with_status_code(StatusCode::REQUEST_TIMEOUT, timeout))
+}
+
+/// Answers with a 500 [`ErrorResponse`] the requests whose handler panics.
+pub fn catch_panics(router: axum::Router) -> axum::Router {
+    router.layer(CatchPanicLayer::custom(panic_error))
+}
+
+/// The response to a request whose handler panicked, always in JSON.
+fn panic_error(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let reason = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    tracing::error!("Request handler panicked: {}", reason);
+    ErrorResponse::error("Internal server error", None, None)
+        .with_code("INTERNAL_ERROR")
+        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+        .into_response()
+}
+
+/// Middleware replacing the bare 413 and 408 responses of the limits with an [`ErrorResponse`].
+///
+/// The error is encoded in the format the client accepts. Responses that
+/// already carry a body type, such as the errors of the handlers, are kept.
+pub async fn limit_errors(request: Request, next: Next) -> Response {
+    let format = WireFormat::from_accept(request.headers());
+    let response = next.run(request).await;
+    let error = match response.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => ErrorResponse::error("The request body is too large", None, None)
+            .with_code("PAYLOAD_TOO_LARGE")
+            .with_status(StatusCode::PAYLOAD_TOO_LARGE),
+        StatusCode::REQUEST_TIMEOUT => ErrorResponse::error("The request took too long", None, None)
+            .with_code("REQUEST_TIMEOUT")
+            .with_status(StatusCode::REQUEST_TIMEOUT),
+        _ => return response,
+    };
+    let typed = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(WireFormat::from_media_type)
+        .is_some();
+    if typed {
+        return response;
+    }
+    format.error(error).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app() -> axum::Router {
+        let slow = axum::Router::new().route(
+            "/slow",
+            axum::routing::get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "done"
+            }),
+        );
+        let echo = axum::Router::new().route("/echo", axum::routing::post(|body: String| async move { body }));
+        let router = limit_time(slow, Duration::from_millis(20)).merge(echo);
+        limit_body(router, 16).layer(axum::middleware::from_fn(limit_errors))
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_gets_a_timeout_error() {
+        let (status, body) = send(Request::builder().uri("/slow").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+        assert_eq!(body["code"], "REQUEST_TIMEOUT");
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handler_gets_an_internal_error() {
+        let router = axum::Router::new().route("/panic", axum::routing::get(|| async { panic!("boom") as &str }));
+        let response = catch_panics(router)
+            .oneshot(Request::builder().uri("/panic").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code.as_deref(), Some("INTERNAL_ERROR"));
+    }
+
+    #[tokio::test]
+    async fn test_body_limit() {
+        let request = |body: &str| Request::builder().method("POST").uri("/echo").body(Body::from(body.to_string())).unwrap();
+        let (status, _) = send(request("short")).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = send(request(&"x".repeat(17))).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["code"], "PAYLOAD_TOO_LARGE");
+    }
+}
@@ -0,0 +1,363 @@
+//! Bots registered from a manifest file instead of the built-in registry.
+//!
+//! A manifest lists the bots a deployment offers, each with its own id and
+//! search parameters, so the playouts of an MCTS bot or the depth of a
+//! minimax bot can be tuned without recompiling. Files ending in `.json` are
+//! read as JSON and any other file as TOML:
+//!
+//! ```toml
+//! [[bots]]
+//! id = "mcts_fast"
+//! kind = "mcts"
+//! playouts = 2000
+//! seed = 42
+//!
+//! [[bots]]
+//! id = "minimax_deep"
+//! kind = "minimax"
+//! depth = 5
+//! ```
+//!
+//! The kinds are `random`, `smart_random`, `bridge`, `minimax` and `mcts`.
+//! Every bot but a `random` one is wrapped in a [`TacticalFilter`], as the
+//! built-in ones are, unless its entry sets `tactical = false`.
+
+use crate::{
+    AnalysisUpdate, BotContext, BridgeBot, Coordinates, GameY, GameYError, MctsBot, MinimaxBot, MoveStats, Movement, PlayerId,
+    RandomBot, SmartRandomBot, TacticalFilter, TranspositionTable, YBot, YBotRegistry,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Kinds of bot a manifest entry can ask for.
+pub const BOT_KINDS: [&str; 5] = ["random", "smart_random", "bridge", "minimax", "mcts"];
+
+/// One bot of a manifest.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BotEntry {
+    /// The id the bot is found by.
+    pub id: String,
+    /// The kind of bot, one of [`BOT_KINDS`].
+    pub kind: String,
+    /// Playouts per move of an `mcts` bot.
+    #[serde(default)]
+    pub playouts: Option<u32>,
+    /// Threads of an `mcts` bot.
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// Seed of an `mcts` bot, which makes its searches reproducible.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Search depth of a `minimax` bot.
+    #[serde(default)]
+    pub depth: Option<u32>,
+    /// Score below which a `minimax` or `mcts` bot resigns.
+    #[serde(default)]
+    pub resign_threshold: Option<f32>,
+    /// Whether the bot is wrapped in a [`TacticalFilter`]. Defaults to every
+    /// kind but `random`.
+    #[serde(default)]
+    pub tactical: Option<bool>,
+}
+
+/// The bots listed by a manifest file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct BotManifest {
+    /// The bots, in the order of the file.
+    pub bots: Vec<BotEntry>,
+}
+
+impl BotManifest {
+    /// Reads a manifest from `path`, as JSON if it ends in `.json` and as TOML otherwise.
+    ///
+    /// # Errors
+    /// Returns `GameYError::IoError` if the file cannot be read and
+    /// `GameYError::InvalidBotManifest` if it cannot be parsed.
+    pub fn load(path: &Path) -> Result<Self, GameYError> {
+        let text = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read bot manifest {}", path.display()),
+            error: e.to_string(),
+        })?;
+        let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&text).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&text).map_err(|e| e.message().to_string())
+        };
+        parsed.map_err(|message| GameYError::InvalidBotManifest {
+            path: path.display().to_string(),
+            message,
+        })
+    }
+
+    /// Builds the registry of the manifest bots.
+    ///
+    /// The `minimax` bots cache their searches in `table`, if there is one,
+    /// and the search bots without a `resign_threshold` of their own resign
+    /// below `resign_threshold`.
+    ///
+    /// # Errors
+    /// Returns the entry and the reason, as a message, if an entry has an
+    /// unknown kind, parameters of another kind or invalid values, or if two
+    /// entries share an id.
+    pub fn registry(
+        &self,
+        table: Option<&Arc<TranspositionTable>>,
+        resign_threshold: Option<f32>,
+    ) -> Result<YBotRegistry, String> {
+        let mut registry = YBotRegistry::new();
+        for (i, entry) in self.bots.iter().enumerate() {
+            let bot = entry
+                .build(table, resign_threshold)
+                .map_err(|message| format!("bot {} ('{}'): {}", i, entry.id, message))?;
+            if registry.find(&entry.id).is_some() {
+                return Err(format!("bot {} ('{}'): the id is already taken", i, entry.id));
+            }
+            registry = registry.with_bot(Arc::new(ManifestBot {
+                id: entry.id.clone(),
+                inner: bot,
+            }));
+        }
+        Ok(registry)
+    }
+}
+
+impl BotEntry {
+    /// Creates the bot described by the entry.
+    fn build(
+        &self,
+        table: Option<&Arc<TranspositionTable>>,
+        resign_threshold: Option<f32>,
+    ) -> Result<Arc<dyn YBot>, String> {
+        if self.id.trim().is_empty() {
+            return Err("the id must not be empty".to_string());
+        }
+        let allowed: &[&str] = match self.kind.as_str() {
+            "random" | "smart_random" | "bridge" => &[],
+            "minimax" => &["depth", "resign_threshold"],
+            "mcts" => &["playouts", "threads", "seed", "resign_threshold"],
+            kind => {
+                return Err(format!(
+                    "unknown kind '{}', expected one of: {}",
+                    kind,
+                    BOT_KINDS.join(", ")
+                ));
+            }
+        };
+        let given = [
+            ("playouts", self.playouts.is_some()),
+            ("threads", self.threads.is_some()),
+            ("seed", self.seed.is_some()),
+            ("depth", self.depth.is_some()),
+            ("resign_threshold", self.resign_threshold.is_some()),
+        ];
+        if let Some((param, _)) = given.iter().find(|(param, set)| *set && !allowed.contains(param)) {
+            return Err(format!("a {} bot has no {}", self.kind, param));
+        }
+        if self.playouts == Some(0) || self.threads == Some(0) || self.depth == Some(0) {
+            return Err("playouts, threads and depth must be at least 1".to_string());
+        }
+        if self.resign_threshold.is_some_and(|threshold| !threshold.is_finite()) {
+            return Err("resign_threshold must be a finite number".to_string());
+        }
+
+        let resign_threshold = self.resign_threshold.or(resign_threshold);
+        let tactical = self.tactical.unwrap_or(self.kind != "random");
+        let bot: Arc<dyn YBot> = match self.kind.as_str() {
+            "random" => filtered(RandomBot, tactical),
+            "smart_random" => filtered(SmartRandomBot, tactical),
+            "bridge" => filtered(BridgeBot, tactical),
+            "minimax" => {
+                let mut minimax = MinimaxBot::new(self.depth.unwrap_or(super::MINIMAX_DEPTH), table.cloned());
+                if let Some(threshold) = resign_threshold {
+                    minimax = minimax.with_resign_threshold(threshold);
+                }
+                filtered(minimax, tactical)
+            }
+            _ => {
+                let mut mcts = MctsBot::new(self.playouts.unwrap_or(super::MCTS_ITERATIONS))
+                    .with_threads(self.threads.unwrap_or(1));
+                if let Some(seed) = self.seed {
+                    mcts = mcts.with_seed(seed);
+                }
+                if let Some(threshold) = resign_threshold {
+                    mcts = mcts.with_resign_threshold(threshold);
+                }
+                filtered(mcts, tactical)
+            }
+        };
+        Ok(bot)
+    }
+}
+
+fn filtered<B: YBot + 'static>(bot: B, tactical: bool) -> Arc<dyn YBot> {
+    if tactical {
+        Arc::new(TacticalFilter::new(bot))
+    } else {
+        Arc::new(bot)
+    }
+}
+
+impl YBotRegistry {
+    /// Creates a registry with the bots of the manifest at `path`.
+    ///
+    /// See [`crate::manifest`] for the format of the file.
+    ///
+    /// # Errors
+    /// Returns the errors of [`BotManifest::load`], and
+    /// `GameYError::InvalidBotManifest` if an entry has an unknown kind or
+    /// invalid parameters.
+    pub fn from_manifest<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let path = path.as_ref();
+        BotManifest::load(path)?
+            .registry(None, None)
+            .map_err(|message| GameYError::InvalidBotManifest {
+                path: path.display().to_string(),
+                message,
+            })
+    }
+}
+
+/// A bot registered under the id given by the manifest.
+struct ManifestBot {
+    id: String,
+    inner: Arc<dyn YBot>,
+}
+
+impl YBot for ManifestBot {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.inner.choose_move(board)
+    }
+
+    fn choose_move_with_deadline(&self, board: &GameY, deadline: Instant) -> Option<Coordinates> {
+        self.inner.choose_move_with_deadline(board, deadline)
+    }
+
+    fn choose_move_with_stats(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Coordinates>, MoveStats) {
+        self.inner.choose_move_with_stats(board, deadline)
+    }
+
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<Movement>, MoveStats) {
+        self.inner.choose_movement(board, deadline)
+    }
+
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, MoveStats) {
+        self.inner.choose_move_with_context(board, context, deadline)
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Movement>, MoveStats) {
+        self.inner.choose_movement_with_context(board, context, deadline)
+    }
+
+    fn analyze_progressive(&self, board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        self.inner.analyze_progressive(board, sink, deadline)
+    }
+
+    fn should_swap(&self, board: &GameY) -> bool {
+        self.inner.should_swap(board)
+    }
+
+    fn evaluate(&self, board: &GameY, player: PlayerId) -> Option<f32> {
+        self.inner.evaluate(board, player)
+    }
+
+    fn plays_out_of_turn(&self) -> bool {
+        self.inner.plays_out_of_turn()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, kind: &str) -> BotEntry {
+        BotEntry {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            playouts: None,
+            threads: None,
+            seed: None,
+            depth: None,
+            resign_threshold: None,
+            tactical: None,
+        }
+    }
+
+    fn manifest(bots: Vec<BotEntry>) -> BotManifest {
+        BotManifest { bots }
+    }
+
+    fn rejection(bots: Vec<BotEntry>) -> String {
+        match manifest(bots).registry(None, None) {
+            Ok(_) => panic!("the manifest was accepted"),
+            Err(message) => message,
+        }
+    }
+
+    #[test]
+    fn test_bots_are_found_by_their_manifest_id() {
+        let registry = manifest(vec![entry("easy_bot", "random"), entry("solid_bot", "bridge")])
+            .registry(None, None)
+            .unwrap();
+        assert_eq!(registry.names(), vec!["easy_bot".to_string(), "solid_bot".to_string()]);
+        assert_eq!(registry.find("Solid_Bot").unwrap().name(), "solid_bot");
+        assert!(registry.find("bridge_bot").is_none());
+    }
+
+    #[test]
+    fn test_unknown_kinds_are_rejected() {
+        let error = rejection(vec![entry("alpha", "alphazero")]);
+        assert!(error.contains("bot 0 ('alpha')"), "{}", error);
+        assert!(error.contains("unknown kind 'alphazero'"), "{}", error);
+        assert!(error.contains("mcts"), "{}", error);
+    }
+
+    #[test]
+    fn test_parameters_must_fit_the_kind() {
+        let mut deep_random = entry("deep_random", "random");
+        deep_random.depth = Some(4);
+        let error = rejection(vec![deep_random]);
+        assert!(error.contains("a random bot has no depth"), "{}", error);
+
+        let mut idle = entry("idle", "mcts");
+        idle.playouts = Some(0);
+        assert!(rejection(vec![idle]).contains("at least 1"));
+    }
+
+    #[test]
+    fn test_ids_must_be_unique() {
+        let error = rejection(vec![entry("twin", "random"), entry("TWIN", "bridge")]);
+        assert!(error.contains("already taken"), "{}", error);
+    }
+
+    #[test]
+    fn test_seeded_mcts_bots_are_reproducible() {
+        let mut seeded = entry("mcts_fast", "mcts");
+        seeded.playouts = Some(100);
+        seeded.seed = Some(42);
+        let game = GameY::new(4);
+        let first = manifest(vec![seeded.clone()]).registry(None, None).unwrap();
+        let second = manifest(vec![seeded]).registry(None, None).unwrap();
+        assert_eq!(
+            first.find("mcts_fast").unwrap().choose_move(&game),
+            second.find("mcts_fast").unwrap().choose_move(&game)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Internal counters exposed through `GET /metrics`.
+
+use crate::{TtStats, state::AppState};
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds in milliseconds of the buckets of a [`LatencyHistogram`];
+/// slower moves fall in a last, unbounded bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [10, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// Counts how long the bots took to choose their moves.
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS_MS.len() + 1],
+    sum_ms: AtomicU64,
+}
+
+impl LatencyHistogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts one move chosen in `elapsed`.
+    pub fn record(&self, elapsed: Duration) {
+        let ms = elapsed.as_millis().try_into().unwrap_or(u64::MAX);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_ms.fetch_add(ms, Ordering::Relaxed);
+    }
+
+    /// Returns the current counts.
+    pub fn snapshot(&self) -> LatencySnapshot {
+        let buckets: Vec<LatencyBucket> = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| LatencyBucket {
+                le_ms: LATENCY_BUCKETS_MS.get(i).copied(),
+                count: count.load(Ordering::Relaxed),
+            })
+            .collect();
+        LatencySnapshot {
+            count: buckets.iter().map(|bucket| bucket.count).sum(),
+            sum_ms: self.sum_ms.load(Ordering::Relaxed),
+            buckets,
+        }
+    }
+}
+
+/// One bucket of a [`LatencySnapshot`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBucket {
+    /// Upper bound of the bucket in milliseconds, `None` for the last one.
+    pub le_ms: Option<u64>,
+    /// Moves that took longer than the previous bound and at most this one.
+    pub count: u64,
+}
+
+/// The counts of a [`LatencyHistogram`] at some point.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LatencySnapshot {
+    /// Moves counted.
+    pub count: u64,
+    /// Total time of the moves in milliseconds.
+    pub sum_ms: u64,
+    /// Moves per bucket, from the fastest.
+    pub buckets: Vec<LatencyBucket>,
+}
+
+/// Internal counters of the server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Metrics {
+    /// Hits, misses and stores of the shared transposition table.
+    pub transposition_table: TtStats,
+    /// Hosted games removed because nobody used them for the configured time.
+    pub expired_games: u64,
+    /// Time the bots took to choose their moves on the choose endpoint.
+    pub move_latency: LatencySnapshot,
+}
+
+impl Metrics {
+    /// Collects the current counters from the application state.
+    pub fn collect(state: &AppState) -> Self {
+        Self {
+            transposition_table: state.transposition_table.stats(),
+            expired_games: state.expired_games.load(Ordering::Relaxed),
+            move_latency: state.move_latency.snapshot(),
+        }
+    }
+}
+
+/// Handler for the `GET /metrics` endpoint.
+pub async fn metrics(State(state): State<AppState>) -> Json<Metrics> {
+    Json(Metrics::collect(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YBotRegistry;
+
+    #[test]
+    fn test_collect_reads_table_stats() {
+        let state = AppState::new(YBotRegistry::new());
+        state.transposition_table.probe(1);
+        let metrics = Metrics::collect(&state);
+        assert_eq!(metrics.transposition_table.misses, 1);
+        assert_eq!(metrics.transposition_table.stores, 0);
+    }
+
+    #[test]
+    fn test_latency_histogram_buckets() {
+        let histogram = LatencyHistogram::new();
+        for ms in [0, 10, 11, 7000] {
+            histogram.record(Duration::from_millis(ms));
+        }
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 4);
+        assert_eq!(snapshot.sum_ms, 7021);
+        assert_eq!(snapshot.buckets.len(), LATENCY_BUCKETS_MS.len() + 1);
+        assert_eq!(snapshot.buckets[0], LatencyBucket { le_ms: Some(10), count: 2 });
+        assert_eq!(snapshot.buckets[1], LatencyBucket { le_ms: Some(50), count: 1 });
+        assert_eq!(snapshot.buckets.last(), Some(&LatencyBucket { le_ms: None, count: 1 }));
+    }
+}
@@ -6,8 +6,36 @@
 //! The server exposes endpoints for checking bot status and requesting moves.
 //!
 //! # Endpoints
-//! - `GET /status` - Health check endpoint
-//! - `POST /{api_version}/ybot/choose/{bot_id}` - Request a move from a bot
+//! - `GET /status` - Health check endpoint, answering a plain `OK`
+//! - `GET /health` - Version, uptime, bots, hosted games and archive state as JSON
+//! - `GET /livez` - Liveness probe, answering `OK` while the process serves requests
+//! - `GET /readyz` - Readiness probe, 503 until the server is warmed up, with the state of each component
+//! - `GET /versions` - List the supported API versions
+//! - `GET /game` - Current game state, including the players and the clock when there is a time control
+//! - `GET /game/history?offset=&limit=` - Page of the moves of the current game
+//! - `GET /game/token` - Share-link token of the current position
+//! - `POST /load-token` - Replace the current game with the position of a token
+//! - `POST /execute-move` - Play a human move and let the bot answer (409 while the bot is still thinking)
+//! - `POST /reset` - Start a new game, optionally with a size, player names and a time control
+//! - `GET /stats` - Statistics about the games played since the server started
+//! - `GET /ladder` - Elo ratings of the humans and bots, from the highest (see [`ladder`])
+//! - `GET /metrics` - Internal counters, such as the transposition table statistics
+//! - `POST /review` - Review a finished game and flag its blunders
+//! - `GET /analysis/ownership?playouts=200` - Chance of the first player owning each cell of the current game
+//! - `GET /archive?limit=&offset=` - Finished games, newest first, when the server archives them
+//! - `GET /archive/{id}` - One archived game
+//! - `POST /games` - Host a game whose seats are humans or bots; returns the seat tokens
+//! - `GET /games/{id}` - A hosted game, including whether it is the turn of the token's seat
+//! - `POST /games/{id}/move` - Play a move with the token of the seat to move
+//! - `GET /games/{id}/wait?after=&timeout_ms=` - Wait until a hosted game has more moves
+//! - `POST /{api_version}/ybot/choose/{bot_id}?time_ms=500` - Request a move from a bot
+//! - `POST /{api_version}/ybot/choose-batch/{bot_id}?time_ms=` - Request moves for up to 256 positions at once (see [`batch`])
+//! - `POST /{api_version}/ybot/analyze-stream?bot_id=&time_ms=` - Stream the progress of a bot's analysis as server-sent events
+//! - `/admin/...` - Reset and clean-up routes, only when enabled (see [`admin`])
+//!
+//! Request and response bodies are JSON by default. Clients can send
+//! `Content-Type: application/msgpack` and ask for `Accept: application/msgpack`
+//! to use MessagePack instead (see [`wire`]).
 //!
 //! # Example
 //! ```no_run
@@ -20,51 +48,353 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Configuration
+//! The port, address, default board size and bot, CORS origins, API keys and
+//! request rate limit come from [`ServerConfig`], which can be read from a
+//! `gamey.toml` file and overridden with `GAMEY_*` environment variables (see
+//! [`config`]). The bots can be listed in a manifest file given by
+//! [`ServerConfig::bots_manifest`] instead (see [`manifest`]).
+//!
+//! # Features
+//! - `sqlite`: archive finished games in the SQLite database given by
+//!   [`ServerConfig::database_url`] or in the configured state directory.
+//! - `tls`: serve HTTPS with the certificate and key given by
+//!   [`ServerConfig::tls_cert_path`] and [`ServerConfig::tls_key_path`].
 
+pub mod admin;
+pub mod analyze;
+pub mod archive;
+pub mod batch;
+pub mod chaos;
 pub mod choose;
+pub mod config;
 pub mod error;
+pub mod expiry;
+pub mod games;
+pub mod health;
+pub mod history;
+pub mod ladder;
+pub mod limits;
+pub mod manifest;
+pub mod metrics;
+pub mod ownership;
+pub mod rate_limit;
+pub mod replies;
+pub mod reproduce;
+pub mod responses;
+pub mod review;
+pub mod share;
 pub mod state;
+pub mod stats;
+pub mod sync;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod version;
+pub mod wire;
 use axum::response::IntoResponse;
 use std::sync::Arc;
-pub use choose::MoveResponse;
+pub use admin::{AdminGame, AdminGamesResponse, GcResponse, ResetAllResponse};
+pub use analyze::AnalyzeQuery;
+pub use archive::{ArchivedGame, GameArchive, MemoryArchive};
+#[cfg(feature = "sqlite")]
+pub use archive::SqliteArchive;
+pub use batch::{BatchResponse, BatchResult};
+pub use chaos::ChaosConfig;
+pub use choose::{ChooseRequest, IndexedMove, MoveList, MoveResponse};
+pub use config::{DifficultyPreset, ServerConfig};
 pub use error::ErrorResponse;
+pub use games::{CreateGameRequest, CreatedGame, HostedGameState, HostedGames, SeatKind, SeatState};
+pub use health::{ArchiveHealth, ComponentStatus, HealthResponse, Readiness, ReadinessResponse};
+pub use history::{HistoryEntry, HistoryPage, MoveKind};
+pub use ladder::{LadderObserver, LadderResponse, LadderStore};
+pub use manifest::{BotEntry, BotManifest};
+pub use metrics::{LatencyBucket, LatencyHistogram, LatencySnapshot, Metrics};
+pub use ownership::OwnershipResponse;
+pub use rate_limit::RateLimiter;
+pub use replies::{MoveReplies, PendingMove, SeqCheck};
+pub use responses::{FinishReason, GameStateResponse, GameStatusDto, MoveDto, MoveResultResponse};
+pub use stats::{GameStats, OpeningStatsResponse};
+pub use share::PositionToken;
 pub use version::*;
+pub use wire::{Negotiated, Wire, WireFormat};
 
-use crate::{GameYError, RandomBot, YBotRegistry, state::AppState};
+use crate::prelude::*;
+use crate::{
+    Clock, MctsBot, MinimaxBot, PlayerInfo, RandomBot, BridgeBot, SmartRandomBot, TacticalFilter, TimeControl, TranspositionTable,
+    state::{AppState, DEFAULT_TT_CAPACITY},
+};
+use std::time::Instant;
 
 use serde::Deserialize;
 
 
 // This helps Rust to understand the JSON that receive from Node
 #[derive(Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MoveRequest {
-    pub index: u32
+    pub index: u32,
+    /// Optional sequence number of the move. A retry with the same number gets
+    /// the reply of the first request instead of playing the move again.
+    #[serde(default)]
+    pub seq: Option<u64>,
+    /// Optional bot or difficulty preset (`easy`, `medium`, `hard`) that
+    /// answers this move. Defaults to the bot of player 1, if the game was
+    /// reset with one, and then to the server's default bot.
+    #[serde(default)]
+    pub bot: Option<String>,
 }
 
 
 // Routes
 /// Creates the Axum router with the given state.
 ///
+/// The configured CORS origins, request rate limit, body size limit,
+/// timeouts, compression and chaos of the state's [`ServerConfig`] are
+/// applied to every route (see [`limits`] and [`chaos`]), and the `/admin`
+/// routes are only mounted if it enables them. Handler panics are answered with a 500 error.
+///
 /// This is useful for testing the API without binding to a network port.
 pub fn create_router(state: AppState) -> axum::Router {
-    axum::Router::new()
+    let cors = cors_layer(&state.config);
+    let compress = state.config.enable_compression;
+    // Las revisiones, los lotes y las esperas largas tienen su propio tiempo límite
+    let slow = axum::Router::new()
+        .route("/review", axum::routing::post(review::review))
+        .route("/replay", axum::routing::post(reproduce::replay))
+        .route("/stats/openings", axum::routing::get(stats::opening_stats))
+        .route("/games/{id}/wait", axum::routing::get(games::wait_for_move))
+        .route(
+            "/{api_version}/ybot/choose-batch/{bot_id}",
+            axum::routing::post(batch::choose_batch),
+        )
+        .route(
+            "/{api_version}/ybot/analyze-stream",
+            axum::routing::post(analyze::analyze_stream),
+        );
+    let mut router = axum::Router::new()
         .route("/status", axum::routing::get(status))
+        .route("/health", axum::routing::get(health::health))
+        .route("/livez", axum::routing::get(health::livez))
+        .route("/readyz", axum::routing::get(health::readyz))
+        .route("/versions", axum::routing::get(version::versions))
         .route("/execute-move", axum::routing::post(realizar_movimiento)) // new
         .route("/reset", axum::routing::post(reiniciar_juego)) // new
+        .route("/game", axum::routing::get(obtener_juego))
+        .route("/game/history", axum::routing::get(history::game_history))
+        .route("/game/token", axum::routing::get(share::game_token))
+        .route("/load-token", axum::routing::post(share::load_token))
+        .route("/stats", axum::routing::get(stats::stats))
+        .route("/ladder", axum::routing::get(ladder::ladder))
+        .route("/metrics", axum::routing::get(metrics::metrics))
+        .route("/analysis/ownership", axum::routing::get(ownership::ownership))
+        .route("/archive", axum::routing::get(archive::list_archive))
+        .route("/archive/{id}", axum::routing::get(archive::get_archived_game))
+        .route("/games", axum::routing::post(games::create_game))
+        .route("/games/{id}", axum::routing::get(games::get_game))
+        .route("/games/{id}/move", axum::routing::post(games::play_move))
         .route(
             "/{api_version}/ybot/choose/{bot_id}",
             axum::routing::post(choose::choose),
+        );
+    if state.config.enable_admin {
+        router = router.nest("/admin", admin::router(state.clone()));
+    }
+    #[cfg(test)]
+    {
+        router = router.route("/test/panic", axum::routing::post(tests::panic_holding_the_game));
+    }
+    let router = limits::limit_time(router, state.config.request_timeout())
+        .merge(limits::limit_time(slow, state.config.long_request_timeout()));
+    let router = limits::limit_body(router, state.config.max_body_bytes)
+        .layer(axum::middleware::from_fn_with_state(state.clone(), chaos::inject_chaos))
+        .layer(axum::middleware::from_fn(limits::limit_errors))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_requests,
+        ))
+        .with_state(state);
+    let router = limits::catch_panics(router);
+    let router = if compress { router.layer(compression_layer()) } else { router };
+    match cors {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+/// Gzip and deflate compression of the responses.
+///
+/// The default predicate of `tower_http` never compresses small bodies,
+/// images or event streams (`text/event-stream`): compressing an event stream
+/// would hold its events back until a block is full.
+fn compression_layer() -> tower_http::compression::CompressionLayer {
+    tower_http::compression::CompressionLayer::new()
+        .gzip(true)
+        .deflate(true)
+}
+
+/// CORS layer allowing the configured origins, if there are any.
+fn cors_layer(config: &ServerConfig) -> Option<tower_http::cors::CorsLayer> {
+    use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+    if config.cors_origins.is_empty() {
+        return None;
+    }
+    let origin = if config.cors_origins.iter().any(|o| o == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            config
+                .cors_origins
+                .iter()
+                .filter_map(|origin| origin.parse().ok()),
         )
-        .with_state(state)
+    };
+    Some(CorsLayer::new().allow_origin(origin).allow_methods(Any).allow_headers(Any))
 }
 
 /// Creates the default application state with the standard bot registry.
 ///
-/// The default state includes the `RandomBot` which selects moves randomly.
+/// The default state includes the `RandomBot` which selects moves randomly, the
+/// `SmartRandomBot`, which does the same but skips dead cells, the `BridgeBot`,
+/// which plays the bridge strategy, the
+/// `MinimaxBot`, whose searches are cached in the state's transposition table,
+/// and the `MctsBot`, which stops at the request deadline. All but the
+/// `RandomBot`, kept as a baseline, are wrapped in a `TacticalFilter` so they
+/// never miss a win or a forced block.
 pub fn create_default_state() -> AppState {
-    let bots = YBotRegistry::new().with_bot(Arc::new(RandomBot));
-    AppState::new(bots)
+    let table = Arc::new(TranspositionTable::new(DEFAULT_TT_CAPACITY));
+    AppState::new(default_bots(&table, None)).with_transposition_table(table)
+}
+
+/// The standard bot registry, whose search bots share `table` and resign
+/// below `resign_threshold`, if there is one.
+fn default_bots(table: &Arc<TranspositionTable>, resign_threshold: Option<f32>) -> YBotRegistry {
+    let mut minimax = MinimaxBot::new(MINIMAX_DEPTH, Some(Arc::clone(table)));
+    let mut mcts = MctsBot::new(MCTS_ITERATIONS);
+    if let Some(threshold) = resign_threshold {
+        minimax = minimax.with_resign_threshold(threshold);
+        mcts = mcts.with_resign_threshold(threshold);
+    }
+    YBotRegistry::new()
+        .with_bot(Arc::new(RandomBot))
+        .with_bot(Arc::new(TacticalFilter::new(SmartRandomBot)))
+        .with_bot(Arc::new(TacticalFilter::new(BridgeBot)))
+        .with_bot(Arc::new(TacticalFilter::new(minimax)))
+        .with_bot(Arc::new(TacticalFilter::new(mcts)))
+}
+
+/// Search depth of the registered `minimax_bot`.
+const MINIMAX_DEPTH: u32 = 3;
+
+/// Playouts of the registered `mcts_bot`.
+const MCTS_ITERATIONS: u32 = 5000;
+
+/// A copy of the standard bot `name` whose choices depend only on the position and `seed`.
+///
+/// The bridge and minimax bots do not use randomness, so they only lose the
+/// shared search cache; the MCTS bot draws its playouts from `seed`. The
+/// search bots resign below `resign_threshold`, as the registered ones do.
+/// Returns `None` for the random bots and the bots outside the standard
+/// registry, which cannot be seeded.
+pub(crate) fn seeded_bot(name: &str, seed: u64, resign_threshold: Option<f32>) -> Option<Arc<dyn YBot>> {
+    let mut minimax = MinimaxBot::new(MINIMAX_DEPTH, None);
+    let mut mcts = MctsBot::new(MCTS_ITERATIONS).with_seed(seed);
+    if let Some(threshold) = resign_threshold {
+        minimax = minimax.with_resign_threshold(threshold);
+        mcts = mcts.with_resign_threshold(threshold);
+    }
+    match name {
+        "bridge_bot" => Some(Arc::new(TacticalFilter::new(BridgeBot))),
+        "minimax_bot" => Some(Arc::new(TacticalFilter::new(minimax))),
+        "mcts_bot" => Some(Arc::new(TacticalFilter::new(mcts))),
+        _ => None,
+    }
+}
+
+/// Creates the default state with the settings of `config` and the extras it
+/// enables, such as the archive and the ladder file.
+///
+/// # Errors
+/// Returns `GameYError::InvalidConfig` if the settings are invalid, the
+/// default bot is not registered or a release build has `chaos` without
+/// `allow_chaos`, the errors of [`BotManifest::load`] and
+/// `GameYError::InvalidBotManifest` if the bot manifest cannot be read or
+/// lists an invalid bot, `GameYError::ServerError` if a database is
+/// configured but the server was built without the `sqlite` feature,
+/// `GameYError::ArchiveError` if the database cannot be opened, and the
+/// errors of [`LadderStore::persist_to`] if the saved ladder cannot be read.
+pub fn create_state(config: &ServerConfig) -> Result<AppState, GameYError> {
+    config.validate()?;
+    config.check_chaos(!cfg!(debug_assertions))?;
+    let table = Arc::new(TranspositionTable::new(DEFAULT_TT_CAPACITY));
+    let mut bots = match &config.bots_manifest {
+        Some(path) => BotManifest::load(path)?
+            .registry(Some(&table), config.resign_threshold)
+            .map_err(|message| GameYError::InvalidBotManifest {
+                path: path.display().to_string(),
+                message,
+            })?,
+        None => default_bots(&table, config.resign_threshold),
+    };
+    for (alias, name) in &config.bot_aliases {
+        if bots.find(name).is_none() {
+            return Err(GameYError::InvalidConfig {
+                message: format!(
+                    "Alias '{}' names unknown bot '{}', expected one of: {}",
+                    alias,
+                    name,
+                    bots.names().join(", ")
+                ),
+            });
+        }
+        bots = bots.with_alias(alias, name);
+    }
+    for (name, preset) in &config.difficulty_presets {
+        if bots.find(&preset.bot).is_none() {
+            return Err(GameYError::InvalidConfig {
+                message: format!(
+                    "Difficulty preset '{}' names unknown bot '{}', expected one of: {}",
+                    name,
+                    preset.bot,
+                    bots.names().join(", ")
+                ),
+            });
+        }
+    }
+    if bots.find(&config.default_bot).is_none() {
+        return Err(GameYError::InvalidConfig {
+            message: format!(
+                "Unknown default bot '{}', expected one of: {}",
+                config.default_bot,
+                bots.names().join(", ")
+            ),
+        });
+    }
+    let state = AppState::from_config(bots, config.clone()).with_transposition_table(table);
+    if let Some(dir) = &config.state_dir {
+        std::fs::create_dir_all(dir).map_err(|e| GameYError::IoError {
+            message: format!("Failed to create state directory {}", dir.display()),
+            error: e.to_string(),
+        })?;
+        state.ladder.persist_to(&dir.join(ladder::LADDER_FILE))?;
+        state.readiness.set("ladder", ComponentStatus::Ready);
+    }
+    #[cfg(feature = "sqlite")]
+    if config.database_url.is_none()
+        && let Some(dir) = &config.state_dir
+    {
+        let path = dir.join("games.db");
+        return Ok(state.with_archive(Arc::new(SqliteArchive::open(&path.to_string_lossy())?)));
+    }
+    match &config.database_url {
+        #[cfg(feature = "sqlite")]
+        Some(url) => Ok(state.with_archive(Arc::new(SqliteArchive::open(url)?))),
+        #[cfg(not(feature = "sqlite"))]
+        Some(_) => Err(GameYError::ServerError {
+            message: "A database was configured, but the server was built without the sqlite feature".to_string(),
+        }),
+        None => Ok(state),
+    }
 }
 
 /// Starts the bot server on the specified port.
@@ -79,24 +409,86 @@ pub fn create_default_state() -> AppState {
 /// - The TCP port cannot be bound (e.g., port already in use, permission denied)
 /// - The server encounters an error while running
 pub async fn run_bot_server(port: u16) -> Result<(), GameYError> {
-    let state = create_default_state();
-    let app = create_router(state);
+    run_bot_server_with_config(ServerConfig::new(port)).await
+}
+
+/// Starts the bot server with the given settings.
+///
+/// This function blocks until the server is shut down.
+///
+/// The settings are checked before binding, so invalid ones stop the server
+/// with a clear message instead of failing on the first request. Abandoned
+/// hosted games are removed in the background (see [`expiry`]) until the
+/// server shuts down on Ctrl+C. `GET /readyz` answers 503 until the bots have
+/// warmed up (see [`health::warm_up`]), after the state is loaded and the
+/// port is bound.
+///
+/// # Errors
+/// Returns the errors of [`create_state`] and [`run_bot_server`].
+pub async fn run_bot_server_with_config(config: ServerConfig) -> Result<(), GameYError> {
+    let state = create_state(&config)?;
+    let tls = tls_of(&config)?;
+    let app = create_router(state.clone());
 
-    let addr = format!("0.0.0.0:{}", port);
+    let addr = config.address();
     let listener = tokio::net::TcpListener::bind(&addr)
         .await
         .map_err(|e| GameYError::ServerError {
             message: format!("Failed to bind to {}: {}", addr, e),
         })?;
 
-    println!("Server mode: Listening on http://{}", addr);
-    axum::serve(listener, app)
-        .await
-        .map_err(|e| GameYError::ServerError {
-            message: format!("Server error: {}", e),
-        })?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    println!("Server mode: Listening on {}://{}", scheme, addr);
+    let warming = state.clone();
+    tokio::task::spawn_blocking(move || health::warm_up(&warming));
+    let (stop, shutdown) = tokio::sync::watch::channel(false);
+    let expiry = expiry::spawn_expiry(state, shutdown);
+    let ctrl_c = async {
+        // Without a signal handler the server runs until it is killed
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    let served = match tls {
+        #[cfg(feature = "tls")]
+        Some(tls) => {
+            let listener = listener.into_std().map_err(|e| GameYError::ServerError {
+                message: format!("Server error: {}", e),
+            })?;
+            tls::serve_tls(listener, app, tls, ctrl_c).await
+        }
+        #[cfg(not(feature = "tls"))]
+        Some(never) => match never {},
+        None => axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+            .with_graceful_shutdown(ctrl_c)
+            .await
+            .map_err(|e| GameYError::ServerError {
+                message: format!("Server error: {}", e),
+            }),
+    };
+    stop.send_replace(true);
+    let _ = expiry.await;
+    served
+}
 
-    Ok(())
+/// The TLS settings of `config`, read from its files, or `None` to serve plain HTTP.
+#[cfg(feature = "tls")]
+fn tls_of(config: &ServerConfig) -> Result<Option<axum_server::tls_rustls::RustlsConfig>, GameYError> {
+    match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert), Some(key)) => tls::load_tls_config(cert, key).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Always `None`: without the `tls` feature configuring TLS is an error.
+#[cfg(not(feature = "tls"))]
+fn tls_of(config: &ServerConfig) -> Result<Option<std::convert::Infallible>, GameYError> {
+    if config.tls_cert_path.is_some() || config.tls_key_path.is_some() {
+        return Err(GameYError::ServerError {
+            message: "TLS was configured, but the server was built without the tls feature".to_string(),
+        });
+    }
+    Ok(None)
 }
 
 /// Health check endpoint handler.
@@ -109,76 +501,397 @@ pub async fn status() -> impl IntoResponse {
 
 // New
 // This endpoint handles the move made by the human player and then triggers the bot's response.
+//
+// The game lock is only held to apply each move: the bot thinks on a copy of
+// the position, so other requests are not blocked meanwhile. A move sent while
+// the bot is still thinking, or a bot move for a game that changed in the
+// meantime (e.g. after a reset), is rejected with 409 Conflict. A retry of
+// a numbered move still being played waits for its reply instead.
 pub async fn realizar_movimiento (
     axum::extract::State(state): axum::extract::State<AppState>,
-    axum::extract::Json(payload): axum::extract::Json<MoveRequest>
-) -> impl IntoResponse {
-
-    // 1. Bloqueamos el Mutex
-    let mut game = state.game.lock().unwrap();
-
-    // 2. Movimiento Humano (Azul)
-    let b_size = game.board_size();
-    let coords = crate::Coordinates::from_index(payload.index, b_size);
-    
-    let human_movement = crate::Movement::Placement { 
-        player: crate::PlayerId::new(0),
-        coords,
-    };
+    format: WireFormat,
+    Negotiated(payload): Negotiated<MoveRequest>
+) -> Result<Wire<MoveResultResponse>, Wire<ErrorResponse>> {
 
-    // Intentamos añadir el movimiento
-    if let Err(e) = game.add_move(human_movement) {
-        println!("Aviso: Movimiento humano no válido: {:?}", e);
+    // 0. Un reintento de una jugada ya numerada recibe la misma respuesta; si
+    // la jugada aún se está procesando, el reintento espera a su respuesta
+    let mut pending = None;
+    if let Some(seq) = payload.seq {
+        loop {
+            let waiting = {
+                let mut replies = state.replies.lock();
+                match replies.check(seq) {
+                    SeqCheck::New => {
+                        let ticket = replies.reserve(seq);
+                        pending = Some(PendingMove::new(&state.replies, seq, ticket));
+                        break;
+                    }
+                    SeqCheck::Duplicate(reply) => return Ok(format.reply(*reply)),
+                    SeqCheck::InFlight => replies.wait_for(seq),
+                    SeqCheck::Stale { last } => {
+                        return Err(format.error(
+                            conflict(&format!("Move {} is older than the last move {}", seq, last), "STALE_MOVE")
+                                .with_details(serde_json::json!({ "last_seq": last })),
+                        ));
+                    }
+                }
+            };
+            if let Some(mut done) = waiting {
+                // El canal se cierra cuando la jugada termina, con o sin respuesta
+                let _ = done.changed().await;
+            }
+        }
     }
 
-    // 3. Turno del Bot (Rojo) (si no ha ganado el humano ya)
-    if !game.check_game_over() {
-        if let Some(bot) = state.bots().find("random_bot") {
-        // Desreferenciamos el mutex guard con &*game
-        if let Some(bot_coords) = bot.choose_move(&*game) {
-            let bot_move = crate::Movement::Placement {
-                player: crate::PlayerId::new(1),
-                coords: bot_coords,
-            };
-            let _ = game.add_move(bot_move);
+    // 1. Movimiento Humano (Azul), con el Mutex bloqueado solo lo justo
+    let (human_move, snapshot, game_bot) = {
+        let mut game = state.game.lock();
+        let mut clock = state.clock.lock();
+
+        // Si el reloj del humano ya ha caído, pierde por tiempo antes de mover
+        let now = Instant::now();
+        check_flag(&mut game, clock.as_mut(), now);
+
+        // Si el bot aún está pensando su jugada, el humano no puede mover
+        if !game.check_game_over() && !game.is_players_turn(crate::PlayerId::new(0)) {
+            return Err(format.error(conflict(
+                "The bot is still thinking about its move",
+                "MOVE_IN_PROGRESS",
+            )));
         }
+
+        // El bot que contesta se elige antes de jugar, para no mover si no existe
+        let game_bot = state
+            .game_bot(payload.bot.as_deref(), &game)
+            .map_err(|error| format.error(error))?;
+
+        let b_size = game.board_size();
+        let coords = crate::Coordinates::try_from_index(payload.index, b_size).map_err(|e| {
+            format.error(ErrorResponse::error(&e.to_string(), None, None).with_code("INVALID_MOVE"))
+        })?;
+
+        let human_movement = crate::Movement::Placement {
+            player: crate::PlayerId::new(0),
+            coords,
+        };
+
+        // El jugador 1 lleva el nombre del bot que contesta, para el ladder y las estadísticas
+        if !game.check_game_over() {
+            game_bot.seat_in(&mut game);
+        }
+
+        // Intentamos añadir el movimiento
+        let played = if game.check_game_over() {
+            println!("Aviso: La partida ya ha terminado");
+            None
+        } else if let Err(e) = game.add_move(human_movement.clone()) {
+            println!("Aviso: Movimiento humano no válido: {:?}", e);
+            None
+        } else {
+            press_clock(&mut game, clock.as_mut(), now);
+            Some(human_movement)
+        };
+        (played, game.clone(), game_bot)
+    };
+
+    // 2. Turno del Bot (Rojo) (si no ha ganado el humano ya). Se ejecuta en
+    // una tarea aparte para que termine aunque el cliente se desconecte.
+    let bot_move = tokio::spawn(bot_turn(state.clone(), snapshot, game_bot))
+        .await
+        .map_err(|e| {
+            format.error(
+                ErrorResponse::error(&format!("The bot failed to move: {}", e), None, None)
+                    .with_status(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+            )
+        })?
+        .map_err(|error| format.error(error))?;
+
+    // 3. Extraer el ganador
+    let game = state.game.lock();
+    let clock = state.clock.lock();
+    if let Some(winner) = responses::winner_of(&game) {
+        println!("¡Tenemos un ganador!: {:?}", winner);
     }
+
+    // 4. Respuesta (con el tablero en YEN), guardada si la jugada venía numerada
+    let (bot_move, bot) = bot_move.unzip();
+    let reply = MoveResultResponse::new(&game, clock.as_ref(), human_move.as_ref(), bot_move.as_ref())
+        .answered_by(bot);
+    if let Some(pending) = pending {
+        pending.record(reply.clone());
     }
-    
+    Ok(format.reply(reply))
+}
 
-    // 4. Extraer el ganador
-    let winner_id = match game.status() {
-        &crate::core::game::GameStatus::Finished { winner } => Some(winner.id()),
-        _ => None,
-    };
+/// The move `bot` plays as the side to move of `position` in the server game.
+///
+/// On its first decision the bot may swap, and with a resign threshold it
+/// may resign instead of placing a stone. The bot thinks with the tables of `context`.
+pub(crate) fn game_bot_decision(
+    bot: &dyn YBot,
+    position: &GameY,
+    context: &crate::BotContext,
+    deadline: Option<Instant>,
+) -> Option<crate::Movement> {
+    // Regla del pastel: en su primera decisión el bot puede intercambiar
+    if position.move_count() == 1 && position.swap_allowed() && bot.should_swap(position) {
+        return Some(crate::Movement::Action {
+            player: position.next_player()?,
+            action: crate::GameAction::Swap,
+        });
+    }
+    bot.choose_movement_with_context(position, context, deadline).0
+}
+
+/// Lets `game_bot` answer the position `snapshot` and applies its move.
+///
+/// The bot thinks without holding the game lock, which is taken again only to
+/// apply the move. Returns the move of the bot with the name of the bot that
+/// played it, `None` if it did not move because the game is over or it is not
+/// its turn, or a 409 `STATE_CHANGED` error if the game is no longer the one
+/// the bot thought about.
+async fn bot_turn(
+    state: AppState,
+    snapshot: GameY,
+    game_bot: state::GameBot,
+) -> Result<Option<(crate::Movement, String)>, ErrorResponse> {
+    // Tras una jugada humana ignorada sigue siendo el turno del humano
+    if !snapshot.is_players_turn(crate::PlayerId::new(1)) {
+        return Ok(None);
+    }
+    let state::GameBot { bot, think_time } = game_bot;
+    let mut name = bot.name().to_string();
+    let (version, hash) = (snapshot.ply(), snapshot.position_hash());
+    let position = snapshot.clone();
+    let context = state.bot_contexts.get(snapshot.board_size());
+    let decision = tokio::task::spawn_blocking(move || {
+        let deadline = think_time.map(|time| Instant::now() + time);
+        game_bot_decision(bot.as_ref(), &position, &context, deadline)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        tracing::warn!("Game bot failed, playing a random move: {}", e);
+        name = RandomBot.name().to_string();
+        RandomBot.choose_move(&snapshot).map(|coords| crate::Movement::Placement {
+            player: crate::PlayerId::new(1),
+            coords,
+        })
+    });
 
-    if winner_id.is_some() {
-        println!("¡Tenemos un ganador!: {:?}", winner_id);
+    // Volvemos a bloquear solo para comprobar que nada ha cambiado y aplicar la jugada
+    let mut game = state.game.lock();
+    if game.ply() != version || game.position_hash() != hash {
+        return Err(conflict(
+            "The game changed while the bot was thinking",
+            "STATE_CHANGED",
+        ));
+    }
+    let mut clock = state.clock.lock();
+    // Si el bot ha pensado más de lo que le quedaba, pierde sin que su jugada cuente
+    let now = Instant::now();
+    if check_flag(&mut game, clock.as_mut(), now) {
+        return Ok(None);
+    }
+    if let Some(movement) = decision
+        && game.add_move(movement.clone()).is_ok()
+    {
+        press_clock(&mut game, clock.as_mut(), now);
+        return Ok(Some((movement, name)));
     }
+    Ok(None)
+}
 
-    // 5. Respuesta (Convertimos a YEN)
-    let yen_data: crate::YEN = (&*game).into();
-    
-    axum::Json(serde_json::json!({
-        "board": yen_data,
-        "winner": winner_id
-    }))
+/// A 409 Conflict error with the given message and code.
+fn conflict(message: &str, code: &str) -> ErrorResponse {
+    ErrorResponse::error(message, None, None)
+        .with_code(code)
+        .with_status(axum::http::StatusCode::CONFLICT)
 }
 
+/// Body accepted by the reset endpoint to configure the new game.
+///
+/// Every field is optional; an empty body creates a game of the configured
+/// default size without clock.
+#[derive(Deserialize, Debug, Default)]
+pub struct ResetRequest {
+    /// Size of the new board (defaults to [`ServerConfig::default_board_size`]).
+    pub size: Option<u32>,
+    /// Time control for the new game. Without it the game has no clock.
+    /// Times over the limits of [`TimeControl::check`] are rejected with a
+    /// 400 `INVALID_TIME_CONTROL` error.
+    pub time_control: Option<TimeControl>,
+    /// Display information of the players. Missing players get default names.
+    #[serde(default)]
+    pub players: Vec<PlayerInfo>,
+}
 
 // New
 // This endpoint resets the game to its initial state.
 pub async fn reiniciar_juego(
-    axum::extract::State(state): axum::extract::State<AppState>
-) -> impl IntoResponse {
+    axum::extract::State(state): axum::extract::State<AppState>,
+    format: WireFormat,
+    payload: Option<axum::extract::Json<ResetRequest>>,
+) -> Result<Wire<GameStateResponse>, Wire<ErrorResponse>> {
 
-    let mut game = state.game.lock().unwrap();
+    let request = payload.map(|p| p.0).unwrap_or_default();
+    let size = request.size.unwrap_or(state.config.default_board_size);
+    let new_game = GameY::try_new(size)
+        .map_err(|e| format.error(ErrorResponse::error(&e.to_string(), None, None).with_code("INVALID_SIZE")))?;
+    if let Some(control) = &request.time_control {
+        control.check().map_err(|message| {
+            format.error(ErrorResponse::error(&message, None, None).with_code("INVALID_TIME_CONTROL"))
+        })?;
+    }
+    let mut game = state.game.lock();
 
-    // Reiniciamos el juego creando una nueva instancia de GameY
-    *game = crate::core::game::GameY::new(5);
+    // Reiniciamos el juego con una nueva instancia de GameY; los observadores
+    // (como las estadísticas) se conservan y cuentan la partida abandonada
+    game.reset_to(new_game.with_player_info(request.players));
+
+    // El reloj arranca para el primer jugador en cuanto se crea la partida
+    *state.clock.lock() = request.time_control.map(|control| {
+        let mut clock = Clock::new(control, 2);
+        if let Some(first) = game.side_to_move() {
+            clock.start(first, Instant::now());
+        }
+        clock
+    });
 
     println!("--> Juego reiniciado.");
 
-    let yen_data: crate::YEN = (&*game).into();
-    axum::Json(yen_data)
+    Ok(format.reply(GameStateResponse::new(&game, state.clock.lock().as_ref())))
+}
+
+/// Returns the current game: board, players, winner and clock (if any).
+///
+/// # Route
+/// `GET /game`
+pub async fn obtener_juego(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    format: WireFormat,
+) -> Wire<GameStateResponse> {
+    let (snapshot, clock) = snapshot_of(&state);
+    format.reply(GameStateResponse::from_snapshot(&snapshot, clock))
+}
+
+/// Copies the game and the clock of the server, releasing the locks at once.
+///
+/// Read-only replies are built from the copy afterwards, so a slow
+/// serialization does not hold up the moves.
+pub(crate) fn snapshot_of(state: &AppState) -> (crate::GameSnapshot, Option<crate::ClockSnapshot>) {
+    let game = state.game.lock();
+    let clock = state.clock.lock();
+    (game.snapshot(), clock.as_ref().map(|clock| clock.snapshot(Instant::now())))
+}
+
+/// Ends `game` on time if the running clock has run out at `now`, before the
+/// side to move plays. Returns `true` if someone lost on time.
+fn check_flag(game: &mut GameY, clock: Option<&mut Clock>, now: Instant) -> bool {
+    match clock.and_then(|clock| clock.check_flag(now)) {
+        Some(flagged) => {
+            game.lose_on_time(flagged);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Presses the clock after a move accepted at `now`.
+///
+/// The mover's flag was checked at the same instant with [`check_flag`], so
+/// the press cannot flag them; if it does anyway, they lose on time.
+fn press_clock(game: &mut GameY, clock: Option<&mut Clock>, now: Instant) {
+    if let Some(clock) = clock
+        && let Err(flagged) = clock.press(now, game.side_to_move())
+    {
+        game.lose_on_time(flagged);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::extract::State;
+    use axum::http::{Request, StatusCode, header::CONTENT_TYPE};
+    use tower::ServiceExt;
+
+    async fn encoding_of(content_type: &'static str) -> Option<String> {
+        let body = format!("data: {}\n\n", "x".repeat(1024));
+        let app = axum::Router::new()
+            .route("/events", axum::routing::get(move || async move { ([(CONTENT_TYPE, content_type)], body) }))
+            .layer(compression_layer());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/events")
+                    .header("accept-encoding", "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response
+            .headers()
+            .get("content-encoding")
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_event_streams_are_not_compressed() {
+        assert_eq!(encoding_of("text/event-stream").await, None);
+        assert_eq!(encoding_of("text/plain").await.as_deref(), Some("gzip"));
+    }
+
+    /// Route mounted only in tests: panics while holding the lock of the game.
+    pub(super) async fn panic_holding_the_game(State(state): State<AppState>) -> &'static str {
+        let _game = state.game.lock();
+        panic!("handler panicked while holding the game");
+    }
+
+    fn post(uri: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_the_game_survives_a_panic_while_locked() {
+        let app = create_router(create_default_state());
+        let response = app.clone().oneshot(post("/test/panic", "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = app.oneshot(post("/execute-move", r#"{"index": 0}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_a_slow_reader_does_not_block_moves() {
+        let state = create_default_state();
+        let app = create_router(state.clone());
+        let (taken, snapshot_taken) = std::sync::mpsc::channel();
+        let reader_state = state.clone();
+        // A reader that takes its time to serialize, after copying the game
+        let reader = std::thread::spawn(move || {
+            let (snapshot, clock) = snapshot_of(&reader_state);
+            taken.send(()).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            GameStateResponse::from_snapshot(&snapshot, clock)
+        });
+        tokio::task::spawn_blocking(move || snapshot_taken.recv().unwrap()).await.unwrap();
+
+        let started = Instant::now();
+        let response = app.oneshot(post("/execute-move", r#"{"index": 0}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(started.elapsed() < std::time::Duration::from_millis(400), "{:?}", started.elapsed());
+        assert!(!reader.is_finished());
+
+        // The reader describes the game as it was when it copied it
+        let reply = tokio::task::spawn_blocking(move || reader.join().unwrap()).await.unwrap();
+        assert!(!reply.board.layout().contains('B'));
+        assert!(YEN::from(&*state.game.lock()).layout().contains('B'));
+    }
 }
@@ -0,0 +1,52 @@
+//! Ownership heat map of the server game: `GET /analysis/ownership`.
+//!
+//! For every cell, the fraction of random playouts in which the first player
+//! ends up owning it, as computed by [`crate::analysis::ownership`]. The
+//! webapp shades the cells with it.
+
+use crate::{analysis, state::AppState};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use serde::{Deserialize, Serialize};
+
+/// Playouts run when the request does not ask for a number.
+pub const DEFAULT_OWNERSHIP_PLAYOUTS: u32 = 200;
+
+/// Most playouts a request may ask for.
+pub const MAX_OWNERSHIP_PLAYOUTS: u32 = 2000;
+
+/// Query parameters of the ownership endpoint.
+#[derive(Deserialize, Debug, Default)]
+pub struct OwnershipQuery {
+    /// Number of playouts, clamped to [`MAX_OWNERSHIP_PLAYOUTS`].
+    pub playouts: Option<u32>,
+    /// Seed of the playouts, random if missing.
+    pub seed: Option<u64>,
+}
+
+/// The ownership of every cell of the server game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OwnershipResponse {
+    /// Playouts actually run.
+    pub playouts: u32,
+    /// For each cell index, the fraction of playouts in which player 0 owns the cell.
+    pub ownership: Vec<f32>,
+}
+
+/// Handler for `GET /analysis/ownership?playouts=200`.
+///
+/// The playouts run on a blocking thread, on a snapshot of the game.
+pub async fn ownership(State(state): State<AppState>, Query(query): Query<OwnershipQuery>) -> Json<OwnershipResponse> {
+    let playouts = query
+        .playouts
+        .unwrap_or(DEFAULT_OWNERSHIP_PLAYOUTS)
+        .clamp(1, MAX_OWNERSHIP_PLAYOUTS);
+    let seed = query.seed.unwrap_or_else(rand::random);
+    let snapshot = state.game.lock().snapshot();
+    let ownership = tokio::task::spawn_blocking(move || analysis::snapshot_ownership(&snapshot, playouts, seed))
+        .await
+        .unwrap_or_default();
+    Json(OwnershipResponse { playouts, ownership })
+}
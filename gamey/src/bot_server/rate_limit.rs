@@ -0,0 +1,89 @@
+//! Per-client request limit of the bot server.
+//!
+//! When [`crate::ServerConfig::rate_limit_per_minute`] is set, every client
+//! (identified by its IP address) may send that many requests per minute.
+//! Further requests in the same minute get 429 Too Many Requests with the
+//! `RATE_LIMITED` code and a `retry_after_s` detail.
+
+use crate::ErrorResponse;
+use crate::state::AppState;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use crate::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Length of the window the limit applies to.
+pub const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Counts the requests of every client in fixed one-minute windows.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: u32,
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter accepting `limit` requests per client and minute.
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request of `client` at `now`.
+    ///
+    /// Returns `Err` with the time until the window ends if the client is over the limit.
+    pub fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut windows = self.windows.lock();
+        // Los clientes cuya ventana ya terminó no necesitan seguir guardados
+        windows.retain(|_, (start, _)| now.duration_since(*start) < RATE_WINDOW);
+        let (start, count) = windows.entry(client.to_string()).or_insert((now, 0));
+        if *count >= self.limit {
+            return Err(RATE_WINDOW.saturating_sub(now.duration_since(*start)));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Middleware rejecting the requests of clients over the limit of the state's [`RateLimiter`].
+pub async fn limit_requests(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(limiter) = &state.rate_limiter else {
+        return next.run(request).await;
+    };
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or_else(|| "unknown".to_string(), |info| info.0.ip().to_string());
+    match limiter.check(&client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => ErrorResponse::error("Too many requests, try again later", None, None)
+            .with_code("RATE_LIMITED")
+            .with_details(serde_json::json!({ "retry_after_s": retry_after.as_secs().max(1) }))
+            .with_status(StatusCode::TOO_MANY_REQUESTS)
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_per_client_and_window() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+        assert!(limiter.check("a", start).is_ok());
+        assert!(limiter.check("a", start).is_ok());
+        assert!(limiter.check("a", start + Duration::from_secs(1)).is_err());
+        // Other clients have their own count
+        assert!(limiter.check("b", start).is_ok());
+        // The count starts again with the next window
+        assert!(limiter.check("a", start + RATE_WINDOW).is_ok());
+    }
+}
@@ -0,0 +1,261 @@
+//! Idempotent move submission for the server's game.
+//!
+//! Clients may number their moves with a sequence number (`seq` in
+//! [`crate::MoveRequest`]). The replies to the last numbered moves are kept in
+//! [`MoveReplies`], so a retry of a move that was already played gets the
+//! same reply instead of being played again. A move being played is
+//! reserved with a [`PendingMove`], so a retry arriving meanwhile waits for
+//! its reply. The cache belongs to the current game and is emptied by a
+//! [`RepliesObserver`] when the game is reset.
+
+use crate::{GameObserver, GameY, MoveResultResponse};
+use std::collections::VecDeque;
+use crate::sync::Mutex;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Number of replies kept by [`MoveReplies::default`].
+pub const DEFAULT_REPLY_CAPACITY: usize = 16;
+
+/// What to do with a move carrying a sequence number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeqCheck {
+    /// The move was not seen before and must be played.
+    New,
+    /// The move was already played; this is its reply.
+    Duplicate(Box<MoveResultResponse>),
+    /// The move is being played; its reply is not known yet.
+    InFlight,
+    /// The number is lower than the last accepted one and its reply is gone.
+    Stale {
+        /// The last accepted sequence number.
+        last: u64,
+    },
+}
+
+/// The replies to the last numbered moves of the current game.
+#[derive(Debug, Clone)]
+pub struct MoveReplies {
+    last_seq: Option<u64>,
+    replies: VecDeque<(u64, MoveResultResponse)>,
+    capacity: usize,
+    /// Moves being played: sequence number, ticket and a channel closed when done.
+    pending: Vec<(u64, u64, watch::Sender<()>)>,
+    next_ticket: u64,
+}
+
+impl Default for MoveReplies {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLY_CAPACITY)
+    }
+}
+
+impl MoveReplies {
+    /// Creates an empty cache keeping at most `capacity` replies (at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            last_seq: None,
+            replies: VecDeque::new(),
+            capacity: capacity.max(1),
+            pending: Vec::new(),
+            next_ticket: 0,
+        }
+    }
+
+    /// Returns the last accepted sequence number, if any.
+    pub fn last_seq(&self) -> Option<u64> {
+        self.last_seq
+    }
+
+    /// Decides whether the move numbered `seq` is new, a retry, in flight or stale.
+    pub fn check(&self, seq: u64) -> SeqCheck {
+        if let Some((_, reply)) = self.replies.iter().find(|(s, _)| *s == seq) {
+            return SeqCheck::Duplicate(Box::new(reply.clone()));
+        }
+        if self.pending.iter().any(|(s, _, _)| *s == seq) {
+            return SeqCheck::InFlight;
+        }
+        match self.last_seq {
+            Some(last) if seq <= last => SeqCheck::Stale { last },
+            _ => SeqCheck::New,
+        }
+    }
+
+    /// Stores the reply to the move numbered `seq`, dropping the oldest reply if full.
+    pub fn record(&mut self, seq: u64, reply: MoveResultResponse) {
+        if self.replies.len() == self.capacity {
+            self.replies.pop_front();
+        }
+        self.replies.push_back((seq, reply));
+        self.last_seq = Some(self.last_seq.map_or(seq, |last| last.max(seq)));
+    }
+
+    /// Marks the move numbered `seq` as being played and returns its ticket.
+    ///
+    /// Call it under the same lock as the [`MoveReplies::check`] that found
+    /// the move [`SeqCheck::New`]; [`PendingMove`] wraps the ticket.
+    pub fn reserve(&mut self, seq: u64) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        self.pending.push((seq, ticket, watch::channel(()).0));
+        ticket
+    }
+
+    /// Returns a channel closed once the move numbered `seq` in flight is done,
+    /// or `None` if it is not in flight.
+    pub fn wait_for(&self, seq: u64) -> Option<watch::Receiver<()>> {
+        self.pending
+            .iter()
+            .find(|(s, _, _)| *s == seq)
+            .map(|(_, _, done)| done.subscribe())
+    }
+
+    /// Ends the reservation `ticket`, waking the retries waiting for it.
+    /// Returns whether it was still held (a reset drops every reservation).
+    fn release(&mut self, ticket: u64) -> bool {
+        let held = self.pending.len();
+        self.pending.retain(|(_, t, _)| *t != ticket);
+        self.pending.len() != held
+    }
+
+    /// Forgets every reply, reservation and sequence number.
+    pub fn clear(&mut self) {
+        self.last_seq = None;
+        self.replies.clear();
+        self.pending.clear();
+    }
+}
+
+/// A numbered move being played, reserved in [`MoveReplies`].
+///
+/// Its reply is stored with [`PendingMove::record`]. If it is dropped without
+/// a reply (the move failed or the client went away) the reservation is
+/// released, and a waiting retry plays the move itself.
+pub struct PendingMove {
+    replies: Arc<Mutex<MoveReplies>>,
+    seq: u64,
+    ticket: u64,
+}
+
+impl PendingMove {
+    /// Wraps the reservation `ticket` of the move numbered `seq`.
+    pub fn new(replies: &Arc<Mutex<MoveReplies>>, seq: u64, ticket: u64) -> Self {
+        Self {
+            replies: Arc::clone(replies),
+            seq,
+            ticket,
+        }
+    }
+
+    /// Stores the reply to the move and wakes the retries waiting for it.
+    /// Nothing is stored if the game was reset meanwhile.
+    pub fn record(self, reply: MoveResultResponse) {
+        let mut replies = self.replies.lock();
+        if replies.release(self.ticket) {
+            replies.record(self.seq, reply);
+        }
+    }
+}
+
+impl Drop for PendingMove {
+    fn drop(&mut self) {
+        self.replies.lock().release(self.ticket);
+    }
+}
+
+/// Game observer that empties [`MoveReplies`] when the game is reset.
+pub struct RepliesObserver {
+    replies: Arc<Mutex<MoveReplies>>,
+}
+
+impl RepliesObserver {
+    /// Creates an observer clearing `replies` on every reset.
+    pub fn new(replies: Arc<Mutex<MoveReplies>>) -> Self {
+        Self { replies }
+    }
+}
+
+impl GameObserver for RepliesObserver {
+    fn on_reset(&self, _game: &GameY) {
+        self.replies.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reply told apart from the others by its board size.
+    fn reply(size: u32) -> MoveResultResponse {
+        MoveResultResponse::new(&GameY::new(size), None, None, None)
+    }
+
+    fn duplicate(size: u32) -> SeqCheck {
+        SeqCheck::Duplicate(Box::new(reply(size)))
+    }
+
+    #[test]
+    fn test_new_duplicate_and_stale() {
+        let mut replies = MoveReplies::default();
+        assert_eq!(replies.check(1), SeqCheck::New);
+        replies.record(1, reply(1));
+        replies.record(2, reply(2));
+
+        assert_eq!(replies.check(1), duplicate(1));
+        assert_eq!(replies.check(2), duplicate(2));
+        assert_eq!(replies.check(3), SeqCheck::New);
+        assert_eq!(replies.last_seq(), Some(2));
+    }
+
+    #[test]
+    fn test_capacity_is_bounded() {
+        let mut replies = MoveReplies::new(2);
+        for seq in 1..=3 {
+            replies.record(seq, reply(seq as u32));
+        }
+        assert_eq!(replies.check(1), SeqCheck::Stale { last: 3 });
+        assert_eq!(replies.check(2), duplicate(2));
+        assert_eq!(replies.check(0), SeqCheck::Stale { last: 3 });
+    }
+
+    #[test]
+    fn test_pending_moves_are_in_flight_until_recorded() {
+        let replies = Arc::new(Mutex::new(MoveReplies::default()));
+        let ticket = replies.lock().reserve(1);
+        let pending = PendingMove::new(&replies, 1, ticket);
+        assert_eq!(replies.lock().check(1), SeqCheck::InFlight);
+        let done = replies.lock().wait_for(1).unwrap();
+
+        pending.record(reply(1));
+        assert!(done.has_changed().is_err());
+        assert_eq!(replies.lock().check(1), duplicate(1));
+        assert!(replies.lock().wait_for(1).is_none());
+    }
+
+    #[test]
+    fn test_dropped_pending_moves_are_released() {
+        let replies = Arc::new(Mutex::new(MoveReplies::default()));
+        let ticket = replies.lock().reserve(1);
+        drop(PendingMove::new(&replies, 1, ticket));
+        assert_eq!(replies.lock().check(1), SeqCheck::New);
+
+        // A move reserved before a reset does not record into the new game
+        let ticket = replies.lock().reserve(2);
+        let pending = PendingMove::new(&replies, 2, ticket);
+        replies.lock().clear();
+        pending.record(reply(2));
+        assert_eq!(replies.lock().check(2), SeqCheck::New);
+    }
+
+    #[test]
+    fn test_reset_clears_the_replies() {
+        let replies = Arc::new(Mutex::new(MoveReplies::default()));
+        let mut game = GameY::new(3);
+        game.subscribe(Box::new(RepliesObserver::new(Arc::clone(&replies))));
+        replies.lock().record(5, reply(5));
+
+        game.reset_to(GameY::new(4));
+        assert_eq!(replies.lock().check(5), SeqCheck::New);
+        assert_eq!(replies.lock().last_seq(), None);
+    }
+}
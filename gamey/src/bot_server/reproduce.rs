@@ -0,0 +1,223 @@
+//! The `POST /replay` endpoint.
+//!
+//! Replays a game reported by a user move by move, checking that every move
+//! is legal and, at the moves of the bot, that the bot seeded as in the report
+//! chooses the same move again. The report points at the first move where
+//! either check fails, to reproduce "the bot played an impossible move".
+
+use crate::bot_server::{game_bot_decision, seeded_bot};
+use crate::bot_server::review::{ReviewMove, movement_of};
+use crate::{BotContext, GameStatusDto, GameY, MoveDto, PlayerId, YBot, error::ErrorResponse, state::AppState};
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+
+fn default_bot_player() -> u32 {
+    1
+}
+
+/// Body of the `POST /replay` request.
+#[derive(Deserialize, Debug)]
+pub struct ReplayRequest {
+    /// Board size of the game.
+    pub size: u32,
+    /// Moves of the game in order; players alternate as in a normal game.
+    pub moves: Vec<ReviewMove>,
+    /// The bot that played in the game.
+    pub bot: String,
+    /// The player the bot played as, 1 by default as in the server game.
+    #[serde(default = "default_bot_player")]
+    pub bot_player: u32,
+    /// Seed of the bot's randomness.
+    pub seed: u64,
+}
+
+/// What replaying one move found.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayedPly {
+    /// Number of the move, starting at 1.
+    pub ply: usize,
+    /// Whether the move could be played.
+    pub legal: bool,
+    /// Why the move could not be played.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The status of the game after the move, if it was legal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<GameStatusDto>,
+    /// Whether the bot was to move.
+    pub by_bot: bool,
+    /// The move the seeded bot chooses here, at the moves of a seeded bot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<MoveDto>,
+    /// Whether `expected` is the move played, at the moves of a seeded bot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reproduced: Option<bool>,
+}
+
+/// Body of the `POST /replay` response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayResponse {
+    /// The bot that played in the game.
+    pub bot_id: String,
+    /// The seed the bot was replayed with.
+    pub seed: u64,
+    /// Whether the bot could be seeded. The choices of bots that cannot,
+    /// such as the random bots, are not checked.
+    pub seeded: bool,
+    /// One entry per move, up to the first illegal one.
+    pub plies: Vec<ReplayedPly>,
+    /// The first move that is illegal or that the seeded bot does not reproduce.
+    pub first_divergence: Option<usize>,
+}
+
+/// Replays `moves` on an empty board of `size`, checking the moves of
+/// `bot_player` against the choices of `bot`, if given.
+///
+/// Stops after the first illegal move, since the moves after it were played
+/// on another position.
+///
+/// # Errors
+/// Returns a message if `size` is not a valid board size.
+pub fn replay_game(
+    size: u32,
+    moves: &[ReviewMove],
+    bot: Option<&dyn YBot>,
+    bot_player: PlayerId,
+) -> Result<Vec<ReplayedPly>, String> {
+    let mut game = GameY::try_new(size).map_err(|e| e.to_string())?;
+    let context = BotContext::new(size);
+    let mut plies = Vec::with_capacity(moves.len());
+    for (i, mv) in moves.iter().enumerate() {
+        let by_bot = game.next_player() == Some(bot_player);
+        let expected = match bot {
+            Some(bot) if by_bot => game_bot_decision(bot, &game, &context, None),
+            _ => None,
+        };
+        let mut ply = ReplayedPly {
+            ply: i + 1,
+            legal: false,
+            error: None,
+            status: None,
+            by_bot,
+            expected: expected.as_ref().map(|movement| MoveDto::new(movement, size)),
+            reproduced: None,
+        };
+        let played = movement_of(&game, i, mv).and_then(|movement| {
+            game.add_move(movement.clone())
+                .map(|_| movement)
+                .map_err(|e| format!("Move {}: {}", i + 1, e))
+        });
+        match played {
+            Ok(movement) => {
+                ply.legal = true;
+                ply.status = Some(GameStatusDto::of(&game));
+                if bot.is_some() && by_bot {
+                    ply.reproduced = Some(expected.as_ref() == Some(&movement));
+                }
+                plies.push(ply);
+            }
+            Err(error) => {
+                ply.error = Some(error);
+                plies.push(ply);
+                break;
+            }
+        }
+    }
+    Ok(plies)
+}
+
+/// Handler for the `POST /replay` endpoint.
+pub async fn replay(
+    State(state): State<AppState>,
+    Json(request): Json<ReplayRequest>,
+) -> Result<Json<ReplayResponse>, ErrorResponse> {
+    let bot_id = state.find_bot(&request.bot, None)?.name().to_string();
+    let seeded = seeded_bot(&bot_id, request.seed, state.config.resign_threshold);
+    let is_seeded = seeded.is_some();
+    let bot_player = PlayerId::new(request.bot_player);
+    let plies = tokio::task::spawn_blocking(move || {
+        replay_game(request.size, &request.moves, seeded.as_deref(), bot_player)
+    })
+    .await
+    .map_err(|e| ErrorResponse::error(&format!("Replay failed: {}", e), None, Some(bot_id.clone())))?
+    .map_err(|e| {
+        ErrorResponse::error(&format!("Invalid game: {}", e), None, Some(bot_id.clone())).with_code("INVALID_SIZE")
+    })?;
+    let first_divergence = plies
+        .iter()
+        .find(|ply| !ply.legal || ply.reproduced == Some(false))
+        .map(|ply| ply.ply);
+    Ok(Json(ReplayResponse {
+        bot_id,
+        seed: request.seed,
+        seeded: is_seeded,
+        plies,
+        first_divergence,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, Movement};
+
+    fn cells(moves: &[u32]) -> Vec<ReviewMove> {
+        moves.iter().map(|cell| ReviewMove::Cell(*cell)).collect()
+    }
+
+    #[test]
+    fn test_an_illegal_move_stops_the_replay() {
+        let plies = replay_game(4, &cells(&[0, 5, 2, 5, 7]), None, PlayerId::new(1)).unwrap();
+        assert_eq!(plies.len(), 4);
+        assert!(plies[..3].iter().all(|ply| ply.legal && ply.status.is_some()));
+        assert!(!plies[3].legal);
+        assert!(plies[3].error.as_deref().unwrap().starts_with("Move 4"));
+        assert!(plies.iter().all(|ply| ply.reproduced.is_none()));
+        assert_eq!(plies.iter().filter(|ply| ply.by_bot).count(), 2);
+    }
+
+    #[test]
+    fn test_seeded_bot_moves_are_reproduced() {
+        let bot = seeded_bot("mcts_bot", 7, None).unwrap();
+        // A game where the bot answers the human, who plays the first free cell
+        let mut game = GameY::new(4);
+        let mut moves = Vec::new();
+        for _ in 0..3 {
+            let Some(human) = game.available_cells().iter().min().copied() else {
+                break;
+            };
+            if game.check_game_over() {
+                break;
+            }
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::from_index(human, 4),
+            })
+            .unwrap();
+            moves.push(ReviewMove::Cell(human));
+            if let Some(Movement::Placement { player, coords }) = game_bot_decision(bot.as_ref(), &game, &BotContext::new(4), None) {
+                game.add_move(Movement::Placement { player, coords }).unwrap();
+                moves.push(ReviewMove::Cell(coords.to_index(4)));
+            }
+        }
+        let plies = replay_game(4, &moves, Some(bot.as_ref()), PlayerId::new(1)).unwrap();
+        assert_eq!(plies.len(), moves.len());
+        assert!(plies.iter().any(|ply| ply.by_bot));
+        assert!(plies.iter().filter(|ply| ply.by_bot).all(|ply| ply.reproduced == Some(true)));
+
+        // Another cell at the first bot move is flagged there
+        let other = (0..10).find(|cell| *cell != 0 && moves[1] != ReviewMove::Cell(*cell)).unwrap();
+        moves.truncate(2);
+        moves[1] = ReviewMove::Cell(other);
+        let plies = replay_game(4, &moves, Some(bot.as_ref()), PlayerId::new(1)).unwrap();
+        assert_eq!(plies[1].reproduced, Some(false));
+        assert!(plies[1].legal);
+    }
+
+    #[test]
+    fn test_random_bots_cannot_be_seeded() {
+        assert!(seeded_bot("random_bot", 7, None).is_none());
+        assert!(seeded_bot("smart_random_bot", 7, None).is_none());
+        assert!(seeded_bot("bridge_bot", 7, Some(-0.9)).is_some());
+    }
+}
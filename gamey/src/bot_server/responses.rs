@@ -0,0 +1,324 @@
+//! Bodies of the replies about the server game.
+//!
+//! `POST /execute-move` answers with a [`MoveResultResponse`], and
+//! `POST /reset` and `GET /game` with a [`GameStateResponse`]. Their JSON
+//! shapes are pinned by the tests of this module, so a change to them has to
+//! be made on purpose.
+
+use crate::prelude::*;
+use crate::{Clock, ClockSnapshot, GameSnapshot, PlayerInfo};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Why a finished game ended.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// The winner connected the sides.
+    Connect,
+    /// The loser resigned.
+    Resign,
+    /// The loser ran out of time.
+    Time,
+    /// The loser forfeited, e.g. with an illegal move.
+    Forfeit,
+}
+
+impl From<WinReason> for FinishReason {
+    fn from(reason: WinReason) -> Self {
+        match reason {
+            WinReason::Connection => FinishReason::Connect,
+            WinReason::Resignation => FinishReason::Resign,
+            WinReason::Timeout => FinishReason::Time,
+            WinReason::Forfeit => FinishReason::Forfeit,
+        }
+    }
+}
+
+/// Whether the game goes on and who is to move, or who won and how.
+///
+/// Serialized with a `state` tag, e.g. `{"state": "finished", "winner": 1, "reason": "connect"}`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum GameStatusDto {
+    /// The game is in progress.
+    Ongoing {
+        /// The id of the player to move.
+        next_player: u32,
+    },
+    /// The game is over.
+    Finished {
+        /// The id of the winner.
+        winner: u32,
+        /// How the game ended.
+        reason: FinishReason,
+    },
+}
+
+impl GameStatusDto {
+    /// Returns the status of `game`.
+    pub fn of(game: &GameY) -> Self {
+        game.status().into()
+    }
+}
+
+impl From<&GameStatus> for GameStatusDto {
+    fn from(status: &GameStatus) -> Self {
+        match status {
+            GameStatus::Ongoing { next_player } => GameStatusDto::Ongoing {
+                next_player: next_player.id(),
+            },
+            GameStatus::Finished { winner, reason } => GameStatusDto::Finished {
+                winner: winner.id(),
+                reason: (*reason).into(),
+            },
+        }
+    }
+}
+
+/// A move of the server game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MoveDto {
+    /// The id of the player who moved.
+    pub player: u32,
+    /// The cell index of the stone placed, for placements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    /// The action played instead of a stone, for swaps and resignations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<GameAction>,
+}
+
+impl MoveDto {
+    /// Describes `movement`, played on a board of `board_size`.
+    pub fn new(movement: &Movement, board_size: u32) -> Self {
+        match movement {
+            Movement::Placement { player, coords } => MoveDto {
+                player: player.id(),
+                index: Some(coords.to_index(board_size)),
+                action: None,
+            },
+            Movement::Action { player, action } => MoveDto {
+                player: player.id(),
+                index: None,
+                action: Some(action.clone()),
+            },
+        }
+    }
+}
+
+/// Reply of `POST /execute-move`: the position after the human move and the bot's answer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MoveResultResponse {
+    /// The position in YEN format.
+    pub board: YEN,
+    /// Whether the game goes on, and who moves or won.
+    pub status: GameStatusDto,
+    /// The id of the winner, if the game is over. Same as in `status`.
+    pub winner: Option<u32>,
+    /// The human move, or `None` if it was not played, e.g. because the game was over.
+    pub last_move: Option<MoveDto>,
+    /// The bot's answer, or `None` if the bot did not move.
+    pub bot_move: Option<MoveDto>,
+    /// Name of the bot that played `bot_move`, once presets and aliases are resolved.
+    pub bot: Option<String>,
+    /// Whether the bot answered with the swap rule.
+    pub swapped: bool,
+    /// Whether the bot resigned.
+    pub resigned: bool,
+    /// The id of the player who ran out of time, if any.
+    pub lost_on_time: Option<u32>,
+    /// The clock, when the game has a time control.
+    pub clock: Option<ClockSnapshot>,
+    /// Number of moves played, swaps and resignations included; see [`GameY::ply`].
+    pub ply: usize,
+}
+
+impl MoveResultResponse {
+    /// Describes `game` after the human move `last_move` and the bot's answer `bot_move`.
+    pub fn new(game: &GameY, clock: Option<&Clock>, last_move: Option<&Movement>, bot_move: Option<&Movement>) -> Self {
+        let size = game.board_size();
+        let bot_action = match bot_move {
+            Some(Movement::Action { action, .. }) => Some(action),
+            _ => None,
+        };
+        MoveResultResponse {
+            board: game.into(),
+            status: GameStatusDto::of(game),
+            winner: winner_of(game),
+            last_move: last_move.map(|movement| MoveDto::new(movement, size)),
+            bot_move: bot_move.map(|movement| MoveDto::new(movement, size)),
+            bot: None,
+            swapped: bot_action == Some(&GameAction::Swap),
+            resigned: bot_action == Some(&GameAction::Resign),
+            lost_on_time: game.lost_on_time().map(|p| p.id()),
+            clock: clock.map(|c| c.snapshot(Instant::now())),
+            ply: game.ply(),
+        }
+    }
+
+    /// Names the bot that played `bot_move`.
+    pub fn answered_by(mut self, bot: Option<String>) -> Self {
+        self.bot = bot;
+        self
+    }
+}
+
+/// State of the server game returned by `GET /game` and `POST /reset`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GameStateResponse {
+    /// The position in YEN format.
+    pub board: YEN,
+    /// Whether the game goes on, and who moves or won.
+    pub status: GameStatusDto,
+    /// Display information of every player, in player order.
+    pub players: Vec<PlayerInfo>,
+    /// The id of the winner, if the game is over.
+    pub winner: Option<u32>,
+    /// The id of the player who ran out of time, if any.
+    pub lost_on_time: Option<u32>,
+    /// The clock, when the game has a time control.
+    pub clock: Option<ClockSnapshot>,
+    /// The sides touched by the stones of each player, in player order, such as `["A", "C"]`.
+    pub sides_touched: Vec<Vec<String>>,
+    /// Number of moves played, swaps and resignations included; see [`GameY::ply`].
+    ///
+    /// It grows by one with every move, so a client that sees it grow by more
+    /// has missed an update.
+    pub ply: usize,
+}
+
+impl GameStateResponse {
+    /// Describes `game` and its `clock`, if it has one.
+    pub fn new(game: &GameY, clock: Option<&Clock>) -> Self {
+        Self::from_snapshot(&game.snapshot(), clock.map(|c| c.snapshot(Instant::now())))
+    }
+
+    /// Describes the game of `snapshot` and the `clock` read with it.
+    ///
+    /// Handlers take both while holding the locks of the game and build the
+    /// response after releasing them.
+    pub fn from_snapshot(snapshot: &GameSnapshot, clock: Option<ClockSnapshot>) -> Self {
+        GameStateResponse {
+            board: snapshot.into(),
+            status: snapshot.status().into(),
+            players: snapshot.players().to_vec(),
+            winner: snapshot.status().winner().map(|winner| winner.id()),
+            lost_on_time: snapshot.lost_on_time().map(|p| p.id()),
+            clock,
+            sides_touched: (0..snapshot.num_players())
+                .map(|id| {
+                    snapshot
+                        .sides_touched_named(PlayerId::new(id))
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                })
+                .collect(),
+            ply: snapshot.ply(),
+        }
+    }
+}
+
+/// Returns the id of the winner, if the game is over.
+pub(crate) fn winner_of(game: &GameY) -> Option<u32> {
+    game.status().winner().map(|winner| winner.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, PlayerId};
+
+    fn place(player: u32, index: u32) -> Movement {
+        Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::from_index(index, 2),
+        }
+    }
+
+    #[test]
+    fn test_move_result_json_shape() {
+        let mut game = GameY::new(2);
+        game.add_move(place(0, 0)).unwrap();
+        game.add_move(place(1, 1)).unwrap();
+        let response = MoveResultResponse::new(&game, None, Some(&place(0, 0)), Some(&place(1, 1)))
+            .answered_by(Some("random_bot".to_string()));
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            concat!(
+                r#"{"board":{"size":2,"turn":0,"players":["B","R"],"layout":"B/R."},"#,
+                r#""status":{"state":"ongoing","next_player":0},"winner":null,"#,
+                r#""last_move":{"player":0,"index":0},"bot_move":{"player":1,"index":1},"bot":"random_bot","#,
+                r#""swapped":false,"resigned":false,"lost_on_time":null,"clock":null,"ply":2}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_resignation_json_shape() {
+        let mut game = GameY::new(2);
+        game.add_move(place(0, 0)).unwrap();
+        let resign = Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        };
+        game.add_move(resign.clone()).unwrap();
+        let response = MoveResultResponse::new(&game, None, None, Some(&resign));
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["status"],
+            serde_json::json!({"state": "finished", "winner": 0, "reason": "resign"})
+        );
+        assert_eq!(json["winner"], 0);
+        assert_eq!(json["last_move"], serde_json::Value::Null);
+        assert_eq!(json["bot_move"], serde_json::json!({"player": 1, "action": "resign"}));
+        assert_eq!(json["resigned"], true);
+        let back: MoveResultResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(back, response);
+    }
+
+    #[test]
+    fn test_status_reports_a_forfeit() {
+        let mut game = GameY::new(2);
+        game.forfeit(PlayerId::new(0));
+        let response = GameStateResponse::new(&game, None);
+        assert_eq!(
+            response.status,
+            GameStatusDto::Finished {
+                winner: 1,
+                reason: FinishReason::Forfeit
+            }
+        );
+        assert_eq!(response.winner, Some(1));
+        assert_eq!(serde_json::to_value(response.status).unwrap()["reason"], "forfeit");
+    }
+
+    #[test]
+    fn test_game_state_json_shape() {
+        let response = GameStateResponse::new(&GameY::new(2), None);
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            concat!(
+                r#"{"board":{"size":2,"turn":0,"players":["B","R"],"layout":"./.."},"#,
+                r#""status":{"state":"ongoing","next_player":0},"#,
+                r#""players":[{"id":0,"name":"Player 0","kind":"human"},{"id":1,"name":"Player 1","kind":"human"}],"#,
+                r#""winner":null,"lost_on_time":null,"clock":null,"sides_touched":[[],[]],"ply":0}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_game_state_reports_the_sides_touched() {
+        let mut game = GameY::new(2);
+        game.add_move(place(0, 0)).unwrap();
+        game.add_move(place(1, 1)).unwrap();
+        let response = GameStateResponse::new(&game, None);
+        assert_eq!(response.sides_touched, [vec!["B", "C"], vec!["A", "B"]]);
+    }
+}
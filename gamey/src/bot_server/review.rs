@@ -0,0 +1,146 @@
+//! The `POST /review` endpoint.
+//!
+//! Replays a game given as a list of moves and returns the review produced by
+//! [`crate::review::review_game`].
+
+use crate::review::{Annotation, review_game};
+use crate::prelude::*;
+use crate::{error::ErrorResponse, state::AppState};
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+
+/// Bot used to suggest moves when the request does not name one.
+pub const DEFAULT_REVIEW_BOT: &str = "minimax_bot";
+
+/// Default evaluation drop above which a move is a blunder.
+pub const DEFAULT_BLUNDER_THRESHOLD: f32 = 1.0;
+
+/// A move of the reviewed game: a cell index, or `"swap"` / `"resign"`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ReviewMove {
+    /// A stone placed on the cell with this index.
+    Cell(u32),
+    /// A non-placement action, written as `"swap"` or `"resign"`.
+    Action(String),
+}
+
+/// Body of the `POST /review` request.
+#[derive(Deserialize, Debug)]
+pub struct ReviewRequest {
+    /// Board size of the game.
+    pub size: u32,
+    /// Moves of the game in order; players alternate as in a normal game.
+    pub moves: Vec<ReviewMove>,
+    /// Bot suggesting the better moves (defaults to [`DEFAULT_REVIEW_BOT`]).
+    pub bot: Option<String>,
+    /// Evaluation drop above which a move is a blunder.
+    pub threshold: Option<f32>,
+}
+
+/// Body of the `POST /review` response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReviewResponse {
+    /// The bot that suggested the better moves.
+    pub bot_id: String,
+    /// One annotation per stone placement.
+    pub annotations: Vec<Annotation>,
+    /// Number of annotations flagged as blunders.
+    pub blunders: usize,
+}
+
+/// Rebuilds a game from its move list.
+pub fn replay_moves(size: u32, moves: &[ReviewMove]) -> Result<GameY, String> {
+    let mut game = GameY::try_new(size).map_err(|e| e.to_string())?;
+    for (i, mv) in moves.iter().enumerate() {
+        let movement = movement_of(&game, i, mv)?;
+        game.add_move(movement)
+            .map_err(|e| format!("Move {}: {}", i + 1, e))?;
+    }
+    Ok(game)
+}
+
+/// The movement of `mv`, the move number `i + 1`, made by the player in turn of `game`.
+///
+/// Whether the movement can be played is left to [`GameY::add_move`].
+pub(crate) fn movement_of(game: &GameY, i: usize, mv: &ReviewMove) -> Result<Movement, String> {
+    let player = game
+        .next_player()
+        .ok_or_else(|| format!("Move {} was played after the end of the game", i + 1))?;
+    match mv {
+        ReviewMove::Cell(idx) if *idx < game.total_cells() => Ok(Movement::Placement {
+            player,
+            coords: Coordinates::from_index(*idx, game.board_size()),
+        }),
+        ReviewMove::Cell(idx) => Err(format!("Move {}: cell {} is out of bounds", i + 1, idx)),
+        ReviewMove::Action(action) => match action.as_str() {
+            "swap" => Ok(Movement::Action {
+                player,
+                action: GameAction::Swap,
+            }),
+            "resign" => Ok(Movement::Action {
+                player,
+                action: GameAction::Resign,
+            }),
+            other => Err(format!("Move {}: unknown action '{}'", i + 1, other)),
+        },
+    }
+}
+
+/// Handler for the `POST /review` endpoint.
+pub async fn review(
+    State(state): State<AppState>,
+    Json(request): Json<ReviewRequest>,
+) -> Result<Json<ReviewResponse>, ErrorResponse> {
+    let bot_id = request.bot.unwrap_or_else(|| DEFAULT_REVIEW_BOT.to_string());
+    let bot = state.find_bot(&bot_id, None)?;
+    let game = replay_moves(request.size, &request.moves)
+        .map_err(|e| ErrorResponse::error(&format!("Invalid game: {}", e), None, Some(bot_id.clone())))?;
+    let threshold = request.threshold.unwrap_or(DEFAULT_BLUNDER_THRESHOLD);
+
+    let annotations = tokio::task::spawn_blocking(move || review_game(&game, bot.as_ref(), threshold))
+        .await
+        .map_err(|e| ErrorResponse::error(&format!("Review failed: {}", e), None, Some(bot_id.clone())))?;
+    let blunders = annotations.iter().filter(|a| a.blunder).count();
+    Ok(Json(ReviewResponse {
+        bot_id,
+        annotations,
+        blunders,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_moves_deserialize() {
+        let moves: Vec<ReviewMove> = serde_json::from_str(r#"[4, "swap", 2, "resign"]"#).unwrap();
+        assert_eq!(
+            moves,
+            vec![
+                ReviewMove::Cell(4),
+                ReviewMove::Action("swap".to_string()),
+                ReviewMove::Cell(2),
+                ReviewMove::Action("resign".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_moves() {
+        let moves = vec![ReviewMove::Cell(0), ReviewMove::Action("swap".to_string()), ReviewMove::Cell(1)];
+        let game = replay_moves(3, &moves).unwrap();
+        assert_eq!(game.history().len(), 3);
+    }
+
+    #[test]
+    fn test_replay_rejects_bad_moves() {
+        assert!(replay_moves(3, &[ReviewMove::Cell(6)]).is_err());
+        assert!(replay_moves(3, &[ReviewMove::Cell(0), ReviewMove::Cell(0)]).is_err());
+        assert!(replay_moves(3, &[ReviewMove::Action("pass".to_string())]).is_err());
+        assert!(replay_moves(1, &[ReviewMove::Cell(0), ReviewMove::Cell(0)])
+            .unwrap_err()
+            .contains("after the end"));
+    }
+}
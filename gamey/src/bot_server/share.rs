@@ -0,0 +1,48 @@
+//! Share links: `GET /game/token` and `POST /load-token`.
+//!
+//! Positions travel as the URL-safe tokens of [`YEN::to_url_token`], so the
+//! webapp can put them in a link and load them back later.
+
+use crate::{
+    ErrorResponse, GameY, YEN,
+    state::AppState,
+    wire::{Negotiated, Wire, WireFormat},
+};
+use axum::{Json, extract::State};
+use serde::{Deserialize, Serialize};
+
+/// A position encoded as a share-link token.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PositionToken {
+    /// The token, as produced by [`YEN::to_url_token`].
+    pub token: String,
+}
+
+/// Handler for `GET /game/token`: the token of the current game's position.
+pub async fn game_token(State(state): State<AppState>) -> Json<PositionToken> {
+    let snapshot = state.game.lock().snapshot();
+    let yen: YEN = (&snapshot).into();
+    Json(PositionToken {
+        token: yen.to_url_token(),
+    })
+}
+
+/// Handler for `POST /load-token`: replaces the current game with the position of a token.
+///
+/// The loaded game has no clock. Returns the new position as YEN.
+pub async fn load_token(
+    State(state): State<AppState>,
+    format: WireFormat,
+    Negotiated(request): Negotiated<PositionToken>,
+) -> Result<Wire<YEN>, Wire<ErrorResponse>> {
+    let invalid = |message: String| {
+        format.error(ErrorResponse::error(&message, None, None).with_code("INVALID_TOKEN"))
+    };
+    let yen = YEN::from_url_token(&request.token).map_err(|e| invalid(e.to_string()))?;
+    let loaded = GameY::try_from(yen).map_err(|e| invalid(format!("Invalid position: {}", e)))?;
+
+    let mut game = state.game.lock();
+    game.reset_to(loaded);
+    *state.clock.lock() = None;
+    Ok(format.reply((&*game).into()))
+}
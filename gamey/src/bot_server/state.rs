@@ -1,7 +1,18 @@
-use crate::YBotRegistry;
+use crate::prelude::*;
+use crate::{BotContexts, Clock, ErrorResponse, GameArchive, PlayerInfo, PlayerKind, TranspositionTable};
+use crate::archive::ArchiveObserver;
 use std::sync::Arc;
-use crate::core::game::GameY;
-use std::sync::Mutex;
+use crate::config::ServerConfig;
+use crate::games::HostedGames;
+use crate::health::{ComponentStatus, Readiness};
+use crate::ladder::{LadderObserver, LadderStore};
+use crate::metrics::LatencyHistogram;
+use crate::rate_limit::RateLimiter;
+use crate::replies::{MoveReplies, RepliesObserver};
+use crate::bot_server::stats::{GameStats, StatsObserver};
+use crate::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::time::{Duration, Instant};
 
 /// Shared application state for the bot server.
 ///
@@ -13,21 +24,228 @@ pub struct AppState {
     /// The registry of available bots, wrapped in Arc for thread-safe sharing.
     bots: Arc<YBotRegistry>,
     pub game: Arc<Mutex<GameY>>, // NEW: The actual game state, wrapped in Arc and Mutex for safe concurrent access
+    /// The clock of the current game, present only when it was created with a time control.
+    pub clock: Arc<Mutex<Option<Clock>>>,
+    /// Statistics about the games played since the server started.
+    pub stats: Arc<Mutex<GameStats>>,
+    /// Elo ratings of the players of the finished games.
+    pub ladder: Arc<LadderStore>,
+    /// Replies to the last numbered moves of the current game, for retried requests.
+    pub replies: Arc<Mutex<MoveReplies>>,
+    /// The games with seats for humans and bots, besides the built-in game.
+    pub hosted_games: Arc<HostedGames>,
+    /// Number of hosted games removed because they were abandoned.
+    pub expired_games: Arc<AtomicU64>,
+    /// Time the bots took to answer the choose endpoint.
+    pub move_latency: Arc<LatencyHistogram>,
+    /// Search cache shared by the search bots across requests.
+    pub transposition_table: Arc<TranspositionTable>,
+    /// Topologies and Zobrist keys by board size, handed to the bots with every move.
+    pub bot_contexts: Arc<BotContexts>,
+    /// Longest time a bot may think on the choose endpoint.
+    pub max_think_time: Duration,
+    /// Where finished games are stored, if the server archives them.
+    pub archive: Option<Arc<dyn GameArchive>>,
+    /// The settings the server was started with.
+    pub config: Arc<ServerConfig>,
+    /// Request counts per client, when the settings limit the request rate.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// When the state was created, for the uptime of `GET /health`.
+    pub started_at: Instant,
+    /// Whether the server is ready for traffic, for `GET /readyz`.
+    pub readiness: Arc<Readiness>,
 }
 
+/// The bot answering a move of the server game, see [`AppState::game_bot`].
+#[derive(Clone)]
+pub struct GameBot {
+    /// The bot, found in the registry.
+    pub bot: Arc<dyn YBot>,
+    /// Thinking time given by a difficulty preset, at most the server's
+    /// `max_think_time`. `None` lets the bot play at its own pace.
+    pub think_time: Option<Duration>,
+}
+
+impl GameBot {
+    /// Names player 1 of `game` after this bot, so that the observers of the
+    /// game credit its result to the bot that actually answered.
+    ///
+    /// A player 1 already played by this bot keeps its name.
+    pub fn seat_in(&self, game: &mut GameY) {
+        let seat = PlayerId::new(1);
+        let id = self.bot.name();
+        let seated = game
+            .players()
+            .into_iter()
+            .any(|info| info.id == seat && info.kind == PlayerKind::Bot(id.to_string()));
+        if !seated {
+            game.set_player_info(PlayerInfo::new(seat, id, PlayerKind::Bot(id.to_string())));
+        }
+    }
+}
+
+/// Id of the bot that answers the human moves of the server's game, as player 1,
+/// unless [`ServerConfig::default_bot`] names another one.
+pub const GAME_BOT_ID: &str = "random_bot";
+
+/// Number of entries of the transposition table created by [`AppState::new`].
+pub const DEFAULT_TT_CAPACITY: usize = 1 << 16;
+
+/// Default value of [`AppState::max_think_time`].
+pub const DEFAULT_MAX_THINK_TIME: Duration = Duration::from_secs(5);
+
 impl AppState {
-    /// Creates a new application state with the given bot registry.
+    /// Creates a new application state with the given bot registry and the default settings.
+    ///
+    /// The game is observed by a [`StatsObserver`] that keeps `stats` up to date,
+    /// by a [`RepliesObserver`] that forgets the replies of the previous game
+    /// and by a [`LadderObserver`] that rates its players.
     pub fn new(bots: YBotRegistry) -> Self {
+        Self::from_config(bots, ServerConfig::default())
+    }
+
+    /// Creates a new application state with the given bot registry and settings.
+    ///
+    /// The game has the configured default size and is answered by the
+    /// configured default bot. The settings are not validated here; see
+    /// [`crate::create_state`].
+    pub fn from_config(bots: YBotRegistry, config: ServerConfig) -> Self {
+        let stats = Arc::new(Mutex::new(GameStats::new()));
+        let replies = Arc::new(Mutex::new(MoveReplies::default()));
+        let mut game = GameY::new(config.default_board_size);
+        game.subscribe(Box::new(StatsObserver::new(Arc::clone(&stats))));
+        game.subscribe(Box::new(RepliesObserver::new(Arc::clone(&replies))));
+        let ladder = Arc::new(LadderStore::new(config.ladder_k_factor));
+        game.subscribe(Box::new(LadderObserver::new(Arc::clone(&ladder))));
         Self {
             bots: Arc::new(bots),
-            game: Arc::new(Mutex::new(GameY::new(5))) // NEW: Initialize the game state with a new GameY instance of size 5
+            game: Arc::new(Mutex::new(game)),
+            clock: Arc::new(Mutex::new(None)),
+            stats,
+            ladder,
+            replies,
+            hosted_games: Arc::new(HostedGames::new()),
+            expired_games: Arc::new(AtomicU64::new(0)),
+            move_latency: Arc::new(LatencyHistogram::new()),
+            transposition_table: Arc::new(TranspositionTable::new(DEFAULT_TT_CAPACITY)),
+            bot_contexts: Arc::new(BotContexts::new()),
+            max_think_time: DEFAULT_MAX_THINK_TIME,
+            archive: None,
+            rate_limiter: config.rate_limit_per_minute.map(|limit| Arc::new(RateLimiter::new(limit))),
+            started_at: Instant::now(),
+            // Lista hasta que alguien la marque, normalmente tras el calentamiento
+            readiness: Arc::new(Readiness::new(&[
+                ("bots", ComponentStatus::Ready),
+                ("archive", ComponentStatus::Disabled),
+                ("ladder", ComponentStatus::Disabled),
+                ("warm_up", ComponentStatus::Pending),
+            ])),
+            config: Arc::new(config),
         }
     }
 
+    /// Stores every game of the server in `archive` when it finishes.
+    pub fn with_archive(mut self, archive: Arc<dyn GameArchive>) -> Self {
+        self.game
+            .lock()
+            .subscribe(Box::new(ArchiveObserver::new(Arc::clone(&archive))));
+        self.archive = Some(archive);
+        self.readiness.set("archive", ComponentStatus::Ready);
+        self
+    }
+
+    /// Sets the longest time a bot may think on the choose endpoint.
+    pub fn with_max_think_time(mut self, max_think_time: Duration) -> Self {
+        self.max_think_time = max_think_time;
+        self
+    }
+
+    /// Replaces the transposition table, typically with one already shared by the registered bots.
+    pub fn with_transposition_table(mut self, table: Arc<TranspositionTable>) -> Self {
+        self.transposition_table = table;
+        self
+    }
+
     /// Returns a clone of the Arc-wrapped bot registry.
     pub fn bots(&self) -> Arc<YBotRegistry> {
         Arc::clone(&self.bots)
     }
+
+    /// Looks up a bot by name or alias, as every route that takes a bot id does.
+    ///
+    /// # Errors
+    /// Returns a 404 `UNKNOWN_BOT` error listing the registered bots in its
+    /// message and, as `available_bots`, in its details, so the client can
+    /// correct the id.
+    pub fn find_bot(&self, bot_id: &str, api_version: Option<&str>) -> Result<Arc<dyn YBot>, ErrorResponse> {
+        self.bots.find(bot_id).ok_or_else(|| {
+            let names = self.bots.names();
+            ErrorResponse::error(
+                &format!("Bot not found: {}, available bots: [{}]", bot_id, names.join(", ")),
+                api_version.map(str::to_string),
+                Some(bot_id.to_string()),
+            )
+            .with_code("UNKNOWN_BOT")
+            .with_status(axum::http::StatusCode::NOT_FOUND)
+            .with_details(serde_json::json!({ "available_bots": names }))
+        })
+    }
+
+    /// Chooses the bot that answers a move of the server game.
+    ///
+    /// `requested` names a difficulty preset of the settings or a bot. Without
+    /// it, the bot given as player 1 of `game` answers, if any, and otherwise
+    /// the configured default bot. The move handler then names player 1
+    /// after the chosen bot (see [`GameBot::seat_in`]), so later moves
+    /// without a name are answered by the same bot.
+    ///
+    /// # Errors
+    /// Returns a 400 `UNKNOWN_BOT` error listing the presets and the
+    /// registered bots in its details if the name matches neither.
+    pub fn game_bot(&self, requested: Option<&str>, game: &GameY) -> Result<GameBot, ErrorResponse> {
+        let configured = game
+            .players()
+            .into_iter()
+            .find(|info| info.id == PlayerId::new(1))
+            .and_then(|info| match info.kind {
+                PlayerKind::Bot(name) => Some(name),
+                PlayerKind::Human => None,
+            });
+        let name = requested
+            .map(str::to_string)
+            .or(configured)
+            .unwrap_or_else(|| self.config.default_bot.clone());
+        let lowercase = name.to_lowercase();
+        let preset = self
+            .config
+            .difficulty_presets
+            .iter()
+            .find(|(preset, _)| preset.to_lowercase() == lowercase)
+            .map(|(_, preset)| preset);
+        let (bot_id, time_ms) = match preset {
+            Some(preset) => (preset.bot.as_str(), preset.time_ms),
+            None => (name.as_str(), None),
+        };
+        let bot = self.bots.find(bot_id).ok_or_else(|| {
+            let names = self.bots.names();
+            let presets: Vec<&String> = self.config.difficulty_presets.keys().collect();
+            ErrorResponse::error(
+                &format!(
+                    "Unknown bot or difficulty '{}', expected one of: {}",
+                    name,
+                    presets.iter().map(|p| p.as_str()).chain(names.iter().map(String::as_str)).collect::<Vec<_>>().join(", ")
+                ),
+                None,
+                Some(name.clone()),
+            )
+            .with_code("UNKNOWN_BOT")
+            .with_details(serde_json::json!({ "available_bots": names, "presets": presets }))
+        })?;
+        Ok(GameBot {
+            bot,
+            think_time: time_ms.map(|ms| Duration::from_millis(ms).min(self.max_think_time)),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -49,6 +267,30 @@ mod tests {
         assert!(state.bots().names().contains(&"random_bot".to_string()));
     }
 
+    #[test]
+    fn test_from_config_uses_the_default_board_size() {
+        let config = ServerConfig {
+            default_board_size: 8,
+            ..ServerConfig::default()
+        };
+        let state = AppState::from_config(YBotRegistry::new(), config);
+        assert_eq!(state.game.lock().board_size(), 8);
+        assert_eq!(state.config.default_board_size, 8);
+    }
+
+    #[test]
+    fn test_new_state_has_no_clock() {
+        let state = AppState::new(YBotRegistry::new());
+        assert!(state.clock.lock().is_none());
+    }
+
+    #[test]
+    fn test_with_transposition_table_shares_the_table() {
+        let table = Arc::new(TranspositionTable::new(32));
+        let state = AppState::new(YBotRegistry::new()).with_transposition_table(Arc::clone(&table));
+        assert!(Arc::ptr_eq(&state.transposition_table, &table));
+    }
+
     #[test]
     fn test_state_clone() {
         let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
@@ -0,0 +1,355 @@
+//! In-memory statistics about the games played on the server.
+//!
+//! The counters live for the lifetime of the process and are exposed through
+//! `GET /stats`. They are updated by a [`StatsObserver`] subscribed to the
+//! server's game. `GET /stats/openings` mines the win rates of the openings
+//! from the archive instead, so they outlive the process.
+
+use crate::prelude::*;
+use crate::archive::{all_games, archive_of, internal_error};
+use crate::stats::{OpeningSort, OpeningStat, OpeningStatsOptions};
+use crate::{ErrorResponse, GameObserver, PlayerKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::sync::Mutex;
+use std::sync::Arc;
+
+/// Accumulated statistics about the games played on the server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GameStats {
+    /// Number of games in which at least one move was played.
+    pub games_started: u64,
+    /// Number of games that reached a finished status.
+    pub games_finished: u64,
+    /// Number of started games that were reset before finishing.
+    pub games_abandoned: u64,
+    /// Wins of player 0 (index 0) and player 1 (index 1).
+    pub wins_by_player: [u64; 2],
+    /// Wins of each bot, keyed by bot id.
+    pub wins_by_bot: BTreeMap<String, u64>,
+    /// Number of finished games that ended by resignation.
+    pub resignations: u64,
+    /// Total number of moves of all finished games.
+    pub total_moves: u64,
+    /// Average number of moves of the finished games.
+    pub average_game_length: f64,
+}
+
+impl GameStats {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that a new game has started.
+    pub fn record_started(&mut self) {
+        self.games_started += 1;
+    }
+
+    /// Records that a started game was discarded before finishing.
+    pub fn record_abandoned(&mut self) {
+        self.games_abandoned += 1;
+    }
+
+    /// Records a finished game.
+    ///
+    /// `winning_bot` is the id of the bot that won, if the winner was a bot.
+    /// Games that are still ongoing are ignored.
+    pub fn record_finished(&mut self, game: &GameY, winning_bot: Option<&str>) {
+        let winner = match game.status() {
+            GameStatus::Finished { winner, .. } => *winner,
+            GameStatus::Ongoing { .. } => return,
+        };
+        self.games_finished += 1;
+        if let Some(wins) = self.wins_by_player.get_mut(winner.id() as usize) {
+            *wins += 1;
+        }
+        if let Some(bot_id) = winning_bot {
+            *self.wins_by_bot.entry(bot_id.to_string()).or_insert(0) += 1;
+        }
+        if matches!(
+            game.last_move(),
+            Some(Movement::Action {
+                action: GameAction::Resign,
+                ..
+            })
+        ) {
+            self.resignations += 1;
+        }
+        self.total_moves += game.move_count() as u64;
+        self.average_game_length = self.total_moves as f64 / self.games_finished as f64;
+    }
+}
+
+/// Game observer that keeps [`GameStats`] up to date.
+///
+/// A game counts as started with its first move, as finished when it is won
+/// and as abandoned when it is reset after starting but before finishing.
+/// A win is credited to a bot when the game names the winner as that bot,
+/// as the server game does with the bot that answers it.
+pub struct StatsObserver {
+    stats: Arc<Mutex<GameStats>>,
+    in_progress: AtomicBool,
+}
+
+impl StatsObserver {
+    /// Creates an observer updating `stats`.
+    pub fn new(stats: Arc<Mutex<GameStats>>) -> Self {
+        Self {
+            stats,
+            in_progress: AtomicBool::new(false),
+        }
+    }
+}
+
+impl GameObserver for StatsObserver {
+    fn on_move(&self, game: &GameY, _movement: &Movement, _status: &GameStatus) {
+        if game.move_count() == 1 {
+            self.in_progress.store(true, Ordering::Relaxed);
+            self.stats.lock().record_started();
+        }
+    }
+
+    fn on_finish(&self, game: &GameY, winner: PlayerId) {
+        // A game lost on time before its first move was never counted as started
+        let started = self.in_progress.swap(false, Ordering::Relaxed);
+        let winning_bot = game
+            .players()
+            .into_iter()
+            .find(|info| info.id == winner)
+            .and_then(|info| match info.kind {
+                PlayerKind::Bot(id) => Some(id),
+                PlayerKind::Human => None,
+            });
+        let mut stats = self.stats.lock();
+        if !started {
+            stats.record_started();
+        }
+        stats.record_finished(game, winning_bot.as_deref());
+    }
+
+    fn on_reset(&self, _game: &GameY) {
+        if self.in_progress.swap(false, Ordering::Relaxed) {
+            self.stats.lock().record_abandoned();
+        }
+    }
+}
+
+/// Handler for the `GET /stats` endpoint.
+pub async fn stats(
+    axum::extract::State(state): axum::extract::State<crate::state::AppState>,
+) -> axum::Json<GameStats> {
+    axum::Json(state.stats.lock().clone())
+}
+
+/// Query parameters of `GET /stats/openings`.
+#[derive(Deserialize, Debug, Default)]
+pub struct OpeningStatsQuery {
+    /// Openings played in fewer games are left out. Defaults to 1.
+    pub min_games: Option<u32>,
+    /// Order of the openings, `games` (the default) or `win_rate`.
+    pub sort: Option<OpeningSort>,
+}
+
+/// Reply of `GET /stats/openings`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OpeningStatsResponse {
+    /// Archived games read, including those that do not count, such as
+    /// games ended before the first stone.
+    pub games: u32,
+    /// The openings, in the order asked for.
+    pub openings: Vec<OpeningStat>,
+}
+
+/// Handler for `GET /stats/openings?min_games=&sort=`: the win rate of the
+/// first player for every opening cell of the archived games.
+///
+/// Symmetric openings are counted together, see [`crate::stats::opening_stats`].
+/// Fails with a 404 `ARCHIVE_DISABLED` error if the server does not archive games.
+pub async fn opening_stats(
+    axum::extract::State(state): axum::extract::State<crate::state::AppState>,
+    axum::extract::Query(query): axum::extract::Query<OpeningStatsQuery>,
+) -> Result<axum::Json<OpeningStatsResponse>, ErrorResponse> {
+    let archive = archive_of(&state)?;
+    let archived = all_games(archive.as_ref()).map_err(internal_error)?;
+    let games = archived.iter().filter_map(|archived| match archived.to_game() {
+        Ok(game) => Some(game),
+        Err(e) => {
+            // Una partida que no se puede repetir no cuenta, pero no impide ver las demás
+            tracing::warn!("Skipping archived game {}: {}", archived.id, e);
+            None
+        }
+    });
+    let mut options = OpeningStatsOptions::new().with_sort(query.sort.unwrap_or_default());
+    if let Some(min_games) = query.min_games {
+        options = options.with_min_games(min_games);
+    }
+    Ok(axum::Json(OpeningStatsResponse {
+        games: archived.len() as u32,
+        openings: crate::stats::opening_stats(games, &options),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, PlayerId, PlayerInfo};
+
+    fn won_by_player_0() -> GameY {
+        let mut game = GameY::new(1);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(0, 0, 0),
+        })
+        .unwrap();
+        game
+    }
+
+    fn resigned_by_player_0() -> GameY {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 0, 0),
+        })
+        .unwrap();
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(0, 2, 0),
+        })
+        .unwrap();
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_new_stats_are_empty() {
+        let stats = GameStats::new();
+        assert_eq!(stats.games_started, 0);
+        assert_eq!(stats.games_finished, 0);
+        assert_eq!(stats.average_game_length, 0.0);
+        assert!(stats.wins_by_bot.is_empty());
+    }
+
+    #[test]
+    fn test_record_started_and_abandoned() {
+        let mut stats = GameStats::new();
+        stats.record_started();
+        stats.record_started();
+        stats.record_abandoned();
+        assert_eq!(stats.games_started, 2);
+        assert_eq!(stats.games_abandoned, 1);
+    }
+
+    #[test]
+    fn test_record_finished_counts_winner() {
+        let mut stats = GameStats::new();
+        stats.record_finished(&won_by_player_0(), None);
+        assert_eq!(stats.games_finished, 1);
+        assert_eq!(stats.wins_by_player, [1, 0]);
+        assert_eq!(stats.resignations, 0);
+        assert_eq!(stats.average_game_length, 1.0);
+    }
+
+    #[test]
+    fn test_record_finished_counts_resignation_and_bot_win() {
+        let mut stats = GameStats::new();
+        stats.record_finished(&resigned_by_player_0(), Some("random_bot"));
+        assert_eq!(stats.wins_by_player, [0, 1]);
+        assert_eq!(stats.resignations, 1);
+        assert_eq!(stats.wins_by_bot.get("random_bot"), Some(&1));
+    }
+
+    #[test]
+    fn test_average_game_length() {
+        let mut stats = GameStats::new();
+        stats.record_finished(&won_by_player_0(), None);
+        stats.record_finished(&resigned_by_player_0(), Some("random_bot"));
+        assert_eq!(stats.total_moves, 4);
+        assert_eq!(stats.average_game_length, 2.0);
+    }
+
+    #[test]
+    fn test_ongoing_game_is_ignored() {
+        let mut stats = GameStats::new();
+        stats.record_finished(&GameY::new(3), None);
+        assert_eq!(stats, GameStats::new());
+    }
+
+    #[test]
+    fn test_stats_observer_counts_game_lifecycle() {
+        let stats = Arc::new(Mutex::new(GameStats::new()));
+        let mut game = GameY::new(3).with_player_info(vec![PlayerInfo::new(
+            PlayerId::new(1),
+            "Random",
+            PlayerKind::Bot("random_bot".to_string()),
+        )]);
+        game.subscribe(Box::new(StatsObserver::new(Arc::clone(&stats))));
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 0, 0),
+        })
+        .unwrap();
+        game.reset();
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game.reset();
+
+        let stats = stats.lock();
+        assert_eq!(stats.games_started, 2);
+        assert_eq!(stats.games_abandoned, 1);
+        assert_eq!(stats.games_finished, 1);
+        assert_eq!(stats.wins_by_bot.get("random_bot"), Some(&1));
+    }
+
+    #[test]
+    fn test_stats_observer_starts_a_game_lost_before_its_first_move() {
+        let stats = Arc::new(Mutex::new(GameStats::new()));
+        let mut game = GameY::new(3);
+        game.subscribe(Box::new(StatsObserver::new(Arc::clone(&stats))));
+        game.lose_on_time(PlayerId::new(0));
+        game.reset();
+
+        let stats = stats.lock();
+        assert_eq!(stats.games_started, 1);
+        assert_eq!(stats.games_finished, 1);
+        assert_eq!(stats.games_abandoned, 0);
+        assert_eq!(stats.wins_by_player, [0, 1]);
+    }
+
+    #[test]
+    fn test_stats_observer_credits_the_bot_named_by_the_game() {
+        let stats = Arc::new(Mutex::new(GameStats::new()));
+        let mut game = GameY::new(3);
+        game.subscribe(Box::new(StatsObserver::new(Arc::clone(&stats))));
+        // Unnamed, the winner is no bot
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game.reset();
+        game.set_player_info(PlayerInfo::new(
+            PlayerId::new(1),
+            "minimax_bot",
+            PlayerKind::Bot("minimax_bot".to_string()),
+        ));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        let stats = stats.lock();
+        assert_eq!(stats.wins_by_player, [0, 2]);
+        assert_eq!(stats.wins_by_bot.len(), 1);
+        assert_eq!(stats.wins_by_bot.get("minimax_bot"), Some(&1));
+    }
+}
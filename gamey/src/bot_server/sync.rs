@@ -0,0 +1,53 @@
+//! A mutex that survives panics.
+//!
+//! A handler that panics while holding a [`std::sync::Mutex`] poisons it, and
+//! every later `lock().unwrap()` panics too, so one bad request would take the
+//! server's game hostage until a restart. The state of the server is shared
+//! through this [`Mutex`] instead, whose [`Mutex::lock`] recovers the data of
+//! a poisoned lock. The handlers keep their updates small enough that the
+//! data is still consistent after a panic.
+
+use std::sync::MutexGuard;
+
+/// A [`std::sync::Mutex`] that ignores poisoning.
+#[derive(Debug, Default)]
+pub struct Mutex<T>(std::sync::Mutex<T>);
+
+impl<T> Mutex<T> {
+    /// Creates a mutex holding `value`.
+    pub fn new(value: T) -> Self {
+        Self(std::sync::Mutex::new(value))
+    }
+
+    /// Locks the mutex, even if a thread panicked while holding it.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Consumes the mutex and returns its value.
+    pub fn into_inner(self) -> T {
+        self.0.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_lock_recovers_after_a_panic() {
+        let counter = Arc::new(Mutex::new(1));
+        let held = Arc::clone(&counter);
+        let result = std::thread::spawn(move || {
+            let mut value = held.lock();
+            *value += 1;
+            panic!("boom");
+        })
+        .join();
+        assert!(result.is_err());
+        assert_eq!(*counter.lock(), 2);
+        *counter.lock() += 1;
+        assert_eq!(Arc::into_inner(counter).unwrap().into_inner(), 3);
+    }
+}
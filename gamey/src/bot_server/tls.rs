@@ -0,0 +1,83 @@
+//! HTTPS for standalone deployments (needs the `tls` feature).
+//!
+//! When [`crate::ServerConfig::tls_cert_path`] and
+//! [`crate::ServerConfig::tls_key_path`] are set,
+//! [`crate::run_bot_server_with_config`] serves HTTPS with rustls instead of
+//! plain HTTP. Both files are PEM: the certificate chain, leaf first, and its
+//! private key. They are read before the server starts, so unreadable or
+//! mismatched files stop it with a clear message.
+
+use crate::GameYError;
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time the open connections get to finish once the server is asked to stop.
+pub const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Reads the certificate chain at `cert` and the private key at `key`.
+///
+/// # Errors
+/// Returns `GameYError::InvalidConfig` naming the file if a file cannot be
+/// read or holds no certificate or key, and if the key does not belong to
+/// the certificate.
+pub fn load_tls_config(cert: &Path, key: &Path) -> Result<RustlsConfig, GameYError> {
+    let invalid = |message: String| GameYError::InvalidConfig { message };
+    let certs = CertificateDer::pem_file_iter(cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(format!("Cannot read TLS certificate {}: {}", cert.display(), e)))?;
+    if certs.is_empty() {
+        return Err(invalid(format!("No certificate found in {}", cert.display())));
+    }
+    let private_key = PrivateKeyDer::from_pem_file(key)
+        .map_err(|e| invalid(format!("Cannot read TLS key {}: {}", key.display(), e)))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, private_key))
+        .map_err(|e| {
+            invalid(format!(
+                "TLS key {} does not fit certificate {}: {}",
+                key.display(),
+                cert.display(),
+                e
+            ))
+        })?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(RustlsConfig::from_config(Arc::new(config)))
+}
+
+/// Serves `app` over HTTPS on `listener` until `shutdown` completes.
+///
+/// Open connections get [`SHUTDOWN_GRACE`] to finish after `shutdown`.
+///
+/// # Errors
+/// Returns `GameYError::ServerError` if the listener cannot be used or the server fails.
+pub async fn serve_tls(
+    listener: std::net::TcpListener,
+    app: axum::Router,
+    tls: RustlsConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), GameYError> {
+    let server_error = |e: std::io::Error| GameYError::ServerError {
+        message: format!("Server error: {}", e),
+    };
+    listener.set_nonblocking(true).map_err(server_error)?;
+    let handle = axum_server::Handle::new();
+    let stopper = handle.clone();
+    tokio::spawn(async move {
+        shutdown.await;
+        stopper.graceful_shutdown(Some(SHUTDOWN_GRACE));
+    });
+    axum_server::from_tcp_rustls(listener, tls)
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .map_err(server_error)
+}
@@ -1,16 +1,135 @@
 use crate::error::ErrorResponse;
+use serde::{Deserialize, Serialize};
 
 /// The currently supported API version.
 pub const SUPPORTED_VERSION: &str = "v1";
 
+/// Error code returned when a request targets an unknown API version.
+pub const BAD_VERSION: &str = "BAD_VERSION";
+
+/// A single API version known to the server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiVersion {
+    /// The version identifier as it appears in the URL (e.g., "v1").
+    pub name: String,
+    /// Whether the version is still accepted but scheduled for removal.
+    pub deprecated: bool,
+}
+
+/// The registry of API versions accepted by the server.
+///
+/// Every versioned endpoint (`/{api_version}/...`) must validate its path
+/// parameter through [`check_api_version`], which consults [`SUPPORTED_VERSIONS`].
+#[derive(Debug, Clone, Copy)]
+pub struct SupportedVersions {
+    /// Pairs of (version name, deprecated), ordered from oldest to newest.
+    entries: &'static [(&'static str, bool)],
+}
+
+/// The API versions currently accepted by the server.
+pub const SUPPORTED_VERSIONS: SupportedVersions =
+    SupportedVersions::new(&[(SUPPORTED_VERSION, false)]);
+
+impl SupportedVersions {
+    /// Creates a registry from `(name, deprecated)` pairs ordered from oldest to newest.
+    pub const fn new(entries: &'static [(&'static str, bool)]) -> Self {
+        Self { entries }
+    }
+
+    /// Returns all accepted versions, including deprecated ones.
+    pub fn versions(&self) -> Vec<ApiVersion> {
+        self.entries
+            .iter()
+            .map(|(name, deprecated)| ApiVersion {
+                name: name.to_string(),
+                deprecated: *deprecated,
+            })
+            .collect()
+    }
+
+    /// Returns the names of all accepted versions.
+    pub fn names(&self) -> Vec<String> {
+        self.entries.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    /// Returns the newest non-deprecated version.
+    pub fn latest(&self) -> &'static str {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(_, deprecated)| !deprecated)
+            .or(self.entries.last())
+            .map(|(name, _)| *name)
+            .unwrap_or(SUPPORTED_VERSION)
+    }
+
+    /// Looks up a version by name.
+    pub fn find(&self, version: &str) -> Option<ApiVersion> {
+        self.versions().into_iter().find(|v| v.name == version)
+    }
+
+    /// Validates a requested version against this registry.
+    ///
+    /// Deprecated versions are accepted (a warning is logged). Unknown versions
+    /// produce an [`ErrorResponse`] with code [`BAD_VERSION`] whose details list
+    /// the supported versions and the latest one.
+    pub fn check(&self, version: &str) -> Result<ApiVersion, ErrorResponse> {
+        match self.find(version) {
+            Some(found) => {
+                if found.deprecated {
+                    tracing::warn!(
+                        "Deprecated API version {} requested, latest is {}",
+                        version,
+                        self.latest()
+                    );
+                }
+                Ok(found)
+            }
+            None => Err(ErrorResponse::error(
+                &format!(
+                    "Unsupported API version: {}. Supported versions are [{}], latest is {}",
+                    version,
+                    self.names().join(", "),
+                    self.latest()
+                ),
+                Some(version.to_string()),
+                None,
+            )
+            .with_code(BAD_VERSION)
+            .with_details(serde_json::json!(VersionsResponse::from(self)))),
+        }
+    }
+}
+
+/// Response body of the `GET /versions` endpoint.
+///
+/// The same structure is included as `details` in `BAD_VERSION` errors so that
+/// clients can auto-negotiate the version to use.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VersionsResponse {
+    /// All accepted versions, oldest first.
+    pub versions: Vec<ApiVersion>,
+    /// The newest non-deprecated version.
+    pub latest: String,
+}
+
+impl From<&SupportedVersions> for VersionsResponse {
+    fn from(supported: &SupportedVersions) -> Self {
+        Self {
+            versions: supported.versions(),
+            latest: supported.latest().to_string(),
+        }
+    }
+}
+
 /// Validates that the requested API version is supported.
 ///
 /// # Arguments
 /// * `version` - The API version string from the request path
 ///
 /// # Returns
-/// * `Ok(())` if the version is supported
-/// * `Err(ErrorResponse)` if the version is not supported
+/// * `Ok(())` if the version is supported (possibly deprecated)
+/// * `Err(ErrorResponse)` with code `BAD_VERSION` if the version is not supported
 ///
 /// # Example
 /// ```
@@ -20,18 +139,14 @@ pub const SUPPORTED_VERSION: &str = "v1";
 /// assert!(check_api_version("v2").is_err());
 /// ```
 pub fn check_api_version(version: &str) -> Result<(), ErrorResponse> {
-    if version != SUPPORTED_VERSION {
-        Err(ErrorResponse::error(
-            &format!(
-                "Unsupported API version: {}. Supported version is {}",
-                version, SUPPORTED_VERSION
-            ),
-            Some(version.to_string()),
-            None,
-        ))
-    } else {
-        Ok(())
-    }
+    SUPPORTED_VERSIONS.check(version).map(|_| ())
+}
+
+/// Handler for the `GET /versions` endpoint.
+///
+/// Lists the accepted API versions and the latest one.
+pub async fn versions() -> axum::Json<VersionsResponse> {
+    axum::Json(VersionsResponse::from(&SUPPORTED_VERSIONS))
 }
 
 #[cfg(test)]
@@ -71,4 +186,36 @@ mod tests {
     fn test_supported_version_constant() {
         assert_eq!(SUPPORTED_VERSION, "v1");
     }
+
+    #[test]
+    fn test_unsupported_version_has_code_and_details() {
+        let err = check_api_version("v9").unwrap_err();
+        assert_eq!(err.code.as_deref(), Some(BAD_VERSION));
+        let details: VersionsResponse = serde_json::from_value(*err.details.unwrap()).unwrap();
+        assert_eq!(details.latest, "v1");
+        assert_eq!(details.versions.len(), 1);
+        assert_eq!(details.versions[0].name, "v1");
+    }
+
+    #[test]
+    fn test_deprecated_version_is_accepted() {
+        let supported = SupportedVersions::new(&[("v0", true), ("v1", false)]);
+        let version = supported.check("v0").unwrap();
+        assert!(version.deprecated);
+        assert_eq!(supported.latest(), "v1");
+    }
+
+    #[test]
+    fn test_latest_skips_deprecated_versions() {
+        let supported = SupportedVersions::new(&[("v1", false), ("v2", true)]);
+        assert_eq!(supported.latest(), "v1");
+        assert_eq!(supported.names(), vec!["v1", "v2"]);
+    }
+
+    #[test]
+    fn test_versions_response_from_registry() {
+        let response = VersionsResponse::from(&SUPPORTED_VERSIONS);
+        assert_eq!(response.latest, SUPPORTED_VERSION);
+        assert!(response.versions.iter().all(|v| !v.deprecated));
+    }
 }
@@ -0,0 +1,226 @@
+//! Content negotiation between JSON and MessagePack.
+//!
+//! Handlers read their body with [`Negotiated`], which decodes JSON or
+//! MessagePack depending on the `Content-Type` header, and answer with a
+//! [`Wire`] built from the [`WireFormat`] the client asked for in `Accept`.
+//! JSON stays the default when a client does not ask for anything else.
+
+use crate::ErrorResponse;
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{ACCEPT, CONTENT_TYPE, VARY},
+        request::Parts,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::convert::Infallible;
+
+/// Media type of MessagePack bodies.
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Media types accepted as MessagePack, including the unofficial ones still in use.
+const MSGPACK_MEDIA_TYPES: [&str; 3] = [
+    MSGPACK_CONTENT_TYPE,
+    "application/x-msgpack",
+    "application/vnd.msgpack",
+];
+
+/// Encoding of a request or response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    /// `application/json`, the default.
+    #[default]
+    Json,
+    /// `application/msgpack`, with structs encoded as maps.
+    MessagePack,
+}
+
+impl WireFormat {
+    /// Returns the format of a media type such as `application/json; charset=utf-8`,
+    /// or `None` if the server does not speak it.
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+        if essence == "application/json" || essence.ends_with("+json") {
+            Some(WireFormat::Json)
+        } else if MSGPACK_MEDIA_TYPES.contains(&essence.as_str()) {
+            Some(WireFormat::MessagePack)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the first format listed in the `Accept` header, or JSON if none is.
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(Self::from_media_type)
+            .unwrap_or_default()
+    }
+
+    /// Returns the `Content-Type` of bodies in this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            WireFormat::Json => "application/json",
+            WireFormat::MessagePack => MSGPACK_CONTENT_TYPE,
+        }
+    }
+
+    /// Encodes `value` in this format.
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            WireFormat::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            WireFormat::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// A successful response carrying `value` in this format.
+    pub fn reply<T>(self, value: T) -> Wire<T> {
+        Wire {
+            format: self,
+            status: StatusCode::OK,
+            value,
+        }
+    }
+
+    /// An error response in this format, with the status of `error`.
+    pub fn error(self, error: ErrorResponse) -> Wire<ErrorResponse> {
+        Wire {
+            format: self,
+            status: error.status_code(),
+            value: error,
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for WireFormat {
+    type Rejection = Infallible;
+
+    /// Extracts the response format the client asked for in `Accept`.
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(WireFormat::from_accept(&parts.headers))
+    }
+}
+
+/// A response body in the format negotiated with the client.
+#[derive(Debug, Clone)]
+pub struct Wire<T> {
+    format: WireFormat,
+    status: StatusCode,
+    value: T,
+}
+
+impl<T: Serialize> IntoResponse for Wire<T> {
+    fn into_response(self) -> Response {
+        match self.format.encode(&self.value) {
+            Ok(body) => (
+                self.status,
+                [
+                    (CONTENT_TYPE, HeaderValue::from_static(self.format.content_type())),
+                    (VARY, HeaderValue::from_static("accept")),
+                ],
+                body,
+            )
+                .into_response(),
+            Err(e) => {
+                tracing::error!("Failed to encode a response: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+/// Request body decoded from JSON or MessagePack according to `Content-Type`.
+///
+/// JSON bodies (and requests without a `Content-Type`) are handled exactly like
+/// [`Json`]. MessagePack bodies that cannot be decoded are rejected with a 400
+/// `INVALID_BODY` error, and other content types with a 415
+/// `UNSUPPORTED_MEDIA_TYPE` error, both in the format the client accepts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Negotiated<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for Negotiated<T> {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let accept = WireFormat::from_accept(req.headers());
+        let content_type = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .map(|value| value.to_str().ok().and_then(WireFormat::from_media_type));
+
+        match content_type {
+            Some(Some(WireFormat::MessagePack)) => {
+                let bytes = Bytes::from_request(req, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+                rmp_serde::from_slice(&bytes).map(Negotiated).map_err(|e| {
+                    let error = ErrorResponse::error(&format!("Invalid MessagePack body: {}", e), None, None)
+                        .with_code("INVALID_BODY");
+                    accept.error(error).into_response()
+                })
+            }
+            Some(None) => {
+                let error = ErrorResponse::error(
+                    &format!("Unsupported content type, use application/json or {}", MSGPACK_CONTENT_TYPE),
+                    None,
+                    None,
+                )
+                .with_code("UNSUPPORTED_MEDIA_TYPE")
+                .with_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+                Err(accept.error(error).into_response())
+            }
+            Some(Some(WireFormat::Json)) | None => Json::<T>::from_request(req, state)
+                .await
+                .map(|Json(value)| Negotiated(value))
+                .map_err(IntoResponse::into_response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_types() {
+        assert_eq!(WireFormat::from_media_type("application/json"), Some(WireFormat::Json));
+        assert_eq!(
+            WireFormat::from_media_type("Application/JSON; charset=utf-8"),
+            Some(WireFormat::Json)
+        );
+        assert_eq!(WireFormat::from_media_type("application/problem+json"), Some(WireFormat::Json));
+        assert_eq!(WireFormat::from_media_type("application/msgpack"), Some(WireFormat::MessagePack));
+        assert_eq!(WireFormat::from_media_type("application/x-msgpack"), Some(WireFormat::MessagePack));
+        assert_eq!(WireFormat::from_media_type("text/plain"), None);
+    }
+
+    #[test]
+    fn test_accept_picks_the_first_known_format() {
+        let accept = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(ACCEPT, HeaderValue::from_str(value).unwrap());
+            WireFormat::from_accept(&headers)
+        };
+        assert_eq!(WireFormat::from_accept(&HeaderMap::new()), WireFormat::Json);
+        assert_eq!(accept("*/*"), WireFormat::Json);
+        assert_eq!(accept("application/msgpack"), WireFormat::MessagePack);
+        assert_eq!(accept("text/html, application/msgpack, application/json"), WireFormat::MessagePack);
+        assert_eq!(accept("application/json, application/msgpack"), WireFormat::Json);
+    }
+
+    #[test]
+    fn test_error_keeps_its_status() {
+        let error = ErrorResponse::error("gone", None, None).with_status(StatusCode::NOT_FOUND);
+        let response = WireFormat::MessagePack.error(error).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[CONTENT_TYPE], MSGPACK_CONTENT_TYPE);
+    }
+}
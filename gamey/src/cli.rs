@@ -5,13 +5,28 @@
 //! - Human vs Human: Two players take turns at the same terminal
 //! - Human vs Computer: Play against a bot
 //! - Server: Run as an HTTP server for bot API
+//!
+//! It also provides the `tournament` subcommand to compare bots, the
+//! `selfplay` subcommand to generate games, optionally exported as training
+//! data, the `stats` subcommand to summarize a collection of games, the
+//! `openings` subcommand to list the win rates of its openings, the
+//! `ytp` subcommand to drive a bot through a GTP-style protocol, the
+//! `puzzle` subcommand to check a directory of puzzles, the `convert`
+//! subcommand to translate saved games between formats and the `replay`
+//! subcommand to step through a saved game.
 
+use crate::export::{ExportFormat, append_rows, to_training_rows};
+use crate::tournament::{self, TournamentConfig};
 use crate::{
-    Coordinates, GameAction, Movement, RandomBot, RenderOptions, YBot, YBotRegistry, game,
+    BridgeBot, Coordinates, GameAction, MctsBot, MinimaxBot, Movement, RandomBot, RenderOptions, SmartRandomBot, YBot,
+    YBotRegistry, game,
 };
-use crate::{GameStatus, GameY, PlayerId};
+use crate::analysis::PositionReport;
+use crate::notation::{ConvertFormat, convert, convert_dir};
+use crate::stats::{OpeningSort, OpeningStat, OpeningStatsOptions, opening_stats};
+use crate::{CollectionEntry, CollectionStats, CollectionWriter, GameStatus, GameY, MoveStatsTotals, PlayerId};
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use rustyline::DefaultEditor;
 use rustyline::error::ReadlineError;
 use std::fmt::Display;
@@ -36,9 +51,266 @@ pub struct CliArgs {
     #[arg(short, long, default_value = "random_bot")]
     pub bot: String,
 
-    /// Port to run the server on (only used with --mode=server)
-    #[arg(short, long, default_value_t = 3000)]
-    pub port: u16,
+    /// Port to run the server on (only used with --mode=server), default = 3000.
+    /// Overrides the configuration file and `GAMEY_PORT`.
+    #[arg(short, long)]
+    pub port: Option<u16>,
+
+    /// Server configuration file (only used with --mode=server),
+    /// default = gamey.toml if it exists
+    #[arg(long)]
+    pub config: Option<std::path::PathBuf>,
+
+    /// SQLite database where the server archives finished games
+    /// (only used with --mode=server, needs the sqlite feature)
+    #[arg(long)]
+    pub database_url: Option<String>,
+
+    /// Lets a release build of the server inject the latency and failures of
+    /// the `chaos` settings (only used with --mode=server)
+    #[arg(long)]
+    pub allow_chaos: bool,
+
+    /// Optional subcommand; without one the game runs in the selected mode.
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Subcommands that run instead of an interactive game.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    /// Play a round-robin tournament between bots and print the standings.
+    Tournament(TournamentArgs),
+    /// Let a bot play against itself, optionally exporting training data.
+    Selfplay(SelfplayArgs),
+    /// Summarize the winners and lengths of the games in a collection file.
+    Stats(StatsArgs),
+    /// List the win rate of the first player for each opening of a collection file.
+    Openings(OpeningsArgs),
+    /// Speak the YTP text protocol on stdin and stdout, for GUIs and referees.
+    Ytp(YtpArgs),
+    /// Check a YEN file and list all its problems.
+    Validate(ValidateArgs),
+    /// Verify every puzzle file of a directory.
+    Puzzle(PuzzleArgs),
+    /// Print the graph of neighboring cells in the Graphviz DOT language.
+    Dot(DotArgs),
+    /// Print the evaluation, best moves and threats of a saved position.
+    Analyze(AnalyzeArgs),
+    /// Convert saved games between YEN, SGF, game records and move lists.
+    Convert(ConvertArgs),
+    /// Step through a saved game, or play it back on its own with --auto.
+    Replay(ReplayArgs),
+    /// Write the JSON Schemas of YEN and the server payloads.
+    #[cfg(feature = "schema")]
+    Schema(SchemaArgs),
+}
+
+/// Arguments of the `schema` subcommand.
+#[cfg(feature = "schema")]
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct SchemaArgs {
+    /// Directory where the `.schema.json` files are written.
+    #[arg(long, default_value = "schemas")]
+    pub out: std::path::PathBuf,
+}
+
+/// Arguments of the `validate` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ValidateArgs {
+    /// The YEN file to check.
+    pub file: std::path::PathBuf,
+}
+
+/// Arguments of the `puzzle` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct PuzzleArgs {
+    /// Directory with the puzzles, one `.json` file each.
+    pub dir: std::path::PathBuf,
+}
+
+/// Arguments of the `dot` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct DotArgs {
+    /// A saved game whose board and stones are drawn.
+    pub file: Option<std::path::PathBuf>,
+
+    /// Size of the empty board drawn when no file is given.
+    #[arg(long, default_value_t = 5, conflicts_with = "file")]
+    pub size: u32,
+}
+
+/// Arguments of the `analyze` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct AnalyzeArgs {
+    /// The saved game or position to analyze (YEN, SGF or a game record).
+    pub file: std::path::PathBuf,
+
+    /// The bot asked for its move.
+    #[arg(long, default_value = "mcts_bot")]
+    pub bot: String,
+
+    /// Number of best moves listed.
+    #[arg(long, default_value_t = 5)]
+    pub top: usize,
+
+    /// Print the report as JSON instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of the `convert` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ConvertArgs {
+    /// The game to convert, or a directory of games.
+    pub input: std::path::PathBuf,
+
+    /// The converted file, or the directory the converted games are written to.
+    pub output: std::path::PathBuf,
+
+    /// Format of the input, instead of the one of its extension.
+    #[arg(long, value_enum)]
+    pub from: Option<ConvertFormat>,
+
+    /// Format of the output, instead of the one of its extension.
+    /// Needed when converting a directory.
+    #[arg(long, value_enum)]
+    pub to: Option<ConvertFormat>,
+
+    /// Board size of the move lists being read.
+    #[arg(long)]
+    pub size: Option<u32>,
+
+    /// Also convert the games in the subdirectories of the input directory.
+    #[arg(long)]
+    pub recursive: bool,
+}
+
+/// Arguments of the `replay` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ReplayArgs {
+    /// The saved game to replay (YEN, SGF or a game record).
+    pub file: std::path::PathBuf,
+
+    /// Play the whole game back without reading commands.
+    #[arg(long)]
+    pub auto: bool,
+
+    /// Time between two moves with --auto, like `500ms` or `2s`.
+    #[arg(long, default_value = "500ms", value_parser = parse_delay)]
+    pub delay: std::time::Duration,
+}
+
+/// Reads a delay in milliseconds (`500ms`, or a bare `500`) or seconds (`2s`, `1.5s`).
+pub fn parse_delay(text: &str) -> std::result::Result<std::time::Duration, String> {
+    let invalid = || format!("invalid delay '{}', expected something like 500ms or 2s", text);
+    let (number, unit) = match text.strip_suffix("ms") {
+        Some(millis) => (millis, 0.001),
+        None => match text.strip_suffix('s') {
+            Some(secs) => (secs, 1.0),
+            None => (text, 0.001),
+        },
+    };
+    let value: f64 = number.trim().parse().map_err(|_| invalid())?;
+    std::time::Duration::try_from_secs_f64(value * unit).map_err(|_| invalid())
+}
+
+/// Arguments of the `ytp` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct YtpArgs {
+    /// The bot answering `genmove`.
+    #[arg(long, default_value = "random_bot")]
+    pub bot: String,
+}
+
+/// Arguments of the `stats` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct StatsArgs {
+    /// The collection file (JSON lines, one game per line).
+    pub collection: std::path::PathBuf,
+
+    /// Print the summary as JSON instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of the `openings` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct OpeningsArgs {
+    /// The collection file (JSON lines, one game per line).
+    pub collection: std::path::PathBuf,
+
+    /// Leave out the openings played in fewer games.
+    #[arg(long, default_value_t = 1)]
+    pub min_games: u32,
+
+    /// Order of the openings.
+    #[arg(long, value_enum, default_value_t = OpeningSort::Games)]
+    pub sort: OpeningSort,
+
+    /// Print the openings as JSON instead of text.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments of the `selfplay` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct SelfplayArgs {
+    /// Number of games to play.
+    #[arg(long, default_value_t = 10)]
+    pub games: u32,
+
+    /// Board size of every game.
+    #[arg(long, default_value_t = 5)]
+    pub size: u32,
+
+    /// The bot playing both sides.
+    #[arg(long, default_value = "random_bot")]
+    pub bot: String,
+
+    /// Append the (position, move) pairs of every game to this file.
+    #[arg(long)]
+    pub export: Option<std::path::PathBuf>,
+
+    /// Format of the exported file.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+    pub format: ExportFormat,
+
+    /// Append every game to this collection file.
+    #[arg(long)]
+    pub collection: Option<std::path::PathBuf>,
+}
+
+/// Arguments of the `tournament` subcommand.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct TournamentArgs {
+    /// Games played by each pair of bots.
+    #[arg(long, default_value_t = 10)]
+    pub games: u32,
+
+    /// Board size of every game.
+    #[arg(long, default_value_t = 5)]
+    pub size: u32,
+
+    /// Seed for the schedule.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Comma-separated names of the bots taking part.
+    #[arg(long, value_delimiter = ',', default_value = "random_bot,minimax_bot,mcts_bot")]
+    pub bots: Vec<String>,
+
+    /// Number of games played in parallel.
+    #[arg(long, default_value_t = 1)]
+    pub threads: usize,
+
+    /// Print the result as JSON instead of a table.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Append every game to this collection file.
+    #[arg(long)]
+    pub collection: Option<std::path::PathBuf>,
 }
 
 /// The game mode determining how the game is played.
@@ -78,7 +350,7 @@ pub fn run_cli_game() -> Result<()> {
         None => select_board_size()?,
     };
 
-    let bots_registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+    let bots_registry = cli_bot_registry();
     let bot: Arc<dyn YBot> = match bots_registry.find(&args.bot) {
         Some(b) => b,
         None => {
@@ -91,13 +363,13 @@ pub fn run_cli_game() -> Result<()> {
         }
     };
 
-    let mut game = game::GameY::new(board_size);
+    let mut game = game::GameY::try_new(board_size)?;
 
     loop {
         println!("{}", game.render(&render_options));
         let status = game.status();
         match status {
-            GameStatus::Finished { winner } => {
+            GameStatus::Finished { winner, .. } => {
                 println!("Game over! Winner: {}", winner);
                 break;
             }
@@ -135,6 +407,298 @@ pub fn run_cli_game() -> Result<()> {
     Ok(())
 }
 
+/// Returns the bots available to the CLI.
+pub fn cli_bot_registry() -> YBotRegistry {
+    YBotRegistry::new()
+        .with_bot(Arc::new(RandomBot))
+        .with_bot(Arc::new(SmartRandomBot))
+        .with_bot(Arc::new(BridgeBot))
+        .with_bot(Arc::new(MinimaxBot::new(2, None)))
+        .with_bot(Arc::new(MctsBot::new(1000)))
+}
+
+/// Runs the `tournament` subcommand and prints the standings.
+pub fn run_tournament(args: &TournamentArgs) -> Result<()> {
+    let registry = cli_bot_registry();
+    let bots = args
+        .bots
+        .iter()
+        .map(|name| {
+            registry.find(name).ok_or_else(|| {
+                anyhow::anyhow!("Bot '{}' not found. Available bots: {:?}", name, registry.names())
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let config = TournamentConfig {
+        games_per_pair: args.games,
+        board_size: args.size,
+        seed: args.seed,
+        threads: args.threads,
+        ..Default::default()
+    };
+    let result = match &args.collection {
+        Some(path) => tournament::run_into_collection(&bots, &config, path)?,
+        None => tournament::run(&bots, &config),
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!("{}", result);
+    }
+    Ok(())
+}
+
+/// Runs the `selfplay` subcommand and prints a summary of the results.
+pub fn run_selfplay(args: &SelfplayArgs) -> Result<()> {
+    let registry = cli_bot_registry();
+    let bot = registry.find(&args.bot).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Bot '{}' not found. Available bots: {:?}",
+            args.bot,
+            registry.names()
+        )
+    })?;
+    let mut wins = [0u32; 2];
+    let mut totals = MoveStatsTotals::default();
+    let mut rows = 0;
+    let mut collection = args
+        .collection
+        .as_ref()
+        .map(CollectionWriter::append)
+        .transpose()?;
+    for _ in 0..args.games {
+        let (game, game_totals) = tournament::play_game_with_stats(bot.as_ref(), bot.as_ref(), args.size);
+        for player_totals in &game_totals {
+            totals.merge(player_totals);
+        }
+        if let Some(writer) = &mut collection {
+            let entry = CollectionEntry::new(&game)
+                .with_tag("source", "selfplay")
+                .with_tag("bot", bot.name());
+            writer.write_entry(&entry)?;
+        }
+        if let GameStatus::Finished { winner, .. } = game.status()
+            && let Some(count) = wins.get_mut(winner.id() as usize)
+        {
+            *count += 1;
+        }
+        if let Some(path) = &args.export {
+            let game_rows = to_training_rows(&game);
+            rows += game_rows.len();
+            append_rows(path, &game_rows, args.format)?;
+        }
+    }
+    println!(
+        "{} games: player 0 won {}, player 1 won {}",
+        args.games, wins[0], wins[1]
+    );
+    println!("{}: {}", bot.name(), totals);
+    if let Some(path) = &args.export {
+        println!("Exported {} rows to {}", rows, path.display());
+    }
+    Ok(())
+}
+
+/// Runs the `ytp` subcommand until `quit` or the end of stdin.
+pub fn run_ytp(args: &YtpArgs) -> Result<()> {
+    let registry = cli_bot_registry();
+    let bot = registry.find(&args.bot).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Bot '{}' not found. Available bots: {:?}",
+            args.bot,
+            registry.names()
+        )
+    })?;
+    let engine = crate::ytp::YtpEngine::new(&registry).with_bot(bot);
+    crate::ytp::run_engine(engine, io::stdin().lock(), io::stdout().lock())?;
+    Ok(())
+}
+
+/// Runs the `schema` subcommand and prints the written files.
+#[cfg(feature = "schema")]
+pub fn run_schema(args: &SchemaArgs) -> Result<()> {
+    for path in crate::schema::export_all(&args.out)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Runs the `validate` subcommand: prints every issue of the file.
+///
+/// Fails if the file cannot be read or has issues that prevent loading it.
+pub fn run_validate(args: &ValidateArgs) -> Result<()> {
+    let issues = crate::yen::validate_file(&args.file)?;
+    for issue in &issues {
+        println!("{}: {}", args.file.display(), issue);
+    }
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    if errors > 0 {
+        anyhow::bail!("{} has {} error(s)", args.file.display(), errors);
+    }
+    if issues.is_empty() {
+        println!("{}: no problems found", args.file.display());
+    }
+    Ok(())
+}
+
+/// Runs the `dot` subcommand: prints the graph of the board of the file, or
+/// of an empty board of the given size.
+pub fn run_dot(args: &DotArgs) -> Result<()> {
+    let game = match &args.file {
+        Some(file) => game::GameY::load_from_file(file)?,
+        None => game::GameY::try_new(args.size)?,
+    };
+    let engine = game.engine();
+    print!("{}", crate::debug::to_dot(engine.topology(), Some(engine.occupants())));
+    Ok(())
+}
+
+/// Analyzes the position of the file given to the `analyze` subcommand.
+///
+/// Fails if the file cannot be loaded or the bot is unknown.
+pub fn analyze_file(args: &AnalyzeArgs) -> Result<PositionReport> {
+    analyze(args).map(|(_, report)| report)
+}
+
+/// Runs the `analyze` subcommand: prints the board and its report.
+pub fn run_analyze(args: &AnalyzeArgs) -> Result<()> {
+    let (game, report) = analyze(args)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", game.render(&RenderOptions::default()));
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+/// Runs the `convert` subcommand: converts a game, or every game of a
+/// directory, and warns on stderr about what the target format drops.
+pub fn run_convert(args: &ConvertArgs) -> Result<()> {
+    if !args.input.is_dir() {
+        for warning in convert(&args.input, &args.output, args.from, args.to, args.size)? {
+            eprintln!("warning: {}: {}", args.output.display(), warning);
+        }
+        return Ok(());
+    }
+    let to = args
+        .to
+        .ok_or_else(|| anyhow::anyhow!("Converting a directory needs the target format, give it with --to"))?;
+    let converted = convert_dir(&args.input, &args.output, args.from, to, args.size, args.recursive)?;
+    for (file, warnings) in &converted {
+        for warning in warnings {
+            eprintln!("warning: {}: {}", file.display(), warning);
+        }
+    }
+    println!("Converted {} games to {}", converted.len(), to);
+    Ok(())
+}
+
+/// Runs the `replay` subcommand: steps through the game with commands read
+/// from stdin, or animates it with `--auto`.
+pub fn run_replay(args: &ReplayArgs) -> Result<()> {
+    let game = GameY::load_from_file(&args.file)?;
+    let mut replay = game.replay();
+    let options = RenderOptions {
+        highlight_last_move: true,
+        ..RenderOptions::default()
+    };
+    if args.auto {
+        crate::viewer::animate(&mut replay, io::stdout().lock(), args.delay, &options)?;
+    } else {
+        println!("Commands: n (next), p (previous), g <ply> (go to), q (quit)");
+        crate::viewer::run(&mut replay, io::stdin().lock(), io::stdout().lock(), &options)?;
+    }
+    Ok(())
+}
+
+fn analyze(args: &AnalyzeArgs) -> Result<(GameY, PositionReport)> {
+    let registry = cli_bot_registry();
+    let bot = registry.find(&args.bot).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Bot '{}' not found. Available bots: {:?}",
+            args.bot,
+            registry.names()
+        )
+    })?;
+    let game = GameY::load_from_file(&args.file)?;
+    let report = crate::analysis::report(&game, bot.as_ref(), args.top);
+    Ok((game, report))
+}
+
+/// Runs the `puzzle` subcommand: verifies every `.json` file of the directory.
+///
+/// Prints one line per puzzle and fails if any of them is wrong.
+pub fn run_puzzle(args: &PuzzleArgs) -> Result<()> {
+    let mut files = std::fs::read_dir(&args.dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    files.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+    files.sort();
+    let mut wrong = 0;
+    for path in &files {
+        match crate::puzzle::Puzzle::load(path).and_then(|puzzle| crate::puzzle::verify(&puzzle)) {
+            Ok(()) => println!("{}: ok", path.display()),
+            Err(e) => {
+                wrong += 1;
+                println!("{}: {}", path.display(), e);
+            }
+        }
+    }
+    if wrong > 0 {
+        anyhow::bail!("{} of {} puzzle(s) are wrong", wrong, files.len());
+    }
+    println!("{} puzzle(s) verified", files.len());
+    Ok(())
+}
+
+/// Runs the `stats` subcommand and prints the summary of the collection.
+pub fn run_stats(args: &StatsArgs) -> Result<()> {
+    let stats = CollectionStats::from_path(&args.collection)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        println!("{}", stats);
+    }
+    Ok(())
+}
+
+/// Counts the openings of the collection given to the `openings` subcommand.
+///
+/// Fails on the first game of the collection that cannot be read.
+pub fn collection_openings(args: &OpeningsArgs) -> Result<Vec<OpeningStat>> {
+    let options = OpeningStatsOptions::new()
+        .with_min_games(args.min_games)
+        .with_sort(args.sort);
+    let mut error = None;
+    let games = crate::iter_games(&args.collection).map_while(|game| game.map_err(|e| error = Some(e)).ok());
+    let stats = opening_stats(games, &options);
+    match error {
+        Some(e) => Err(e.into()),
+        None => Ok(stats),
+    }
+}
+
+/// Runs the `openings` subcommand and prints one line per opening.
+pub fn run_openings(args: &OpeningsArgs) -> Result<()> {
+    let stats = collection_openings(args)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    println!("{:<12} {:>6} {:>6} {:>8}", "Opening", "Games", "Wins", "Win rate");
+    for stat in &stats {
+        println!(
+            "{:<12} {:>6} {:>6} {:>7.1}%",
+            stat.coords.to_string(),
+            stat.games,
+            stat.first_player_wins,
+            stat.win_rate() * 100.0
+        );
+    }
+    Ok(())
+}
+
 /// Promps the user to select a board size if not provided via CLI args.
 fn select_board_size() -> Result<u32> {
     println!("Selecciona el tamaño del tablero:");
@@ -198,6 +762,9 @@ fn process_input(
         Command::ShowColors => {
             render_options.show_colors = !render_options.show_colors;
         }
+        Command::ShowLabels => {
+            render_options.show_labels = !render_options.show_labels;
+        }
         Command::Help => {
             print_help();
         }
@@ -265,6 +832,7 @@ pub fn parse_command(input: &str, bound: u32) -> Command {
         "show_colors" => Command::ShowColors,
         "show_coords" => Command::Show3DCoords,
         "show_idx" => Command::ShowIdx,
+        "show_labels" => Command::ShowLabels,
         str => match parse_idx(str, bound) {
             Ok(idx) => Command::Place { idx },
             Err(e) => Command::Error {
@@ -282,6 +850,7 @@ fn print_help() {
     println!("  show_coords     - Toggle showing coordinates on the board");
     println!("  show_idx        - Toggle showing index numbers on the board");
     println!("  show_colors     - Toggle showing colors on the board");
+    println!("  show_labels     - Toggle showing row and column labels on the board");
     println!("  save <filename> - Save the current game state to a file");
     println!("  load <filename> - Load a game state from a file");
     println!("  exit            - Exit the game");
@@ -309,6 +878,8 @@ pub enum Command {
     ShowColors,
     /// Toggle display of cell indices.
     ShowIdx,
+    /// Toggle display of row and column labels.
+    ShowLabels,
     /// Exit the game.
     Exit,
     /// Show help message.
@@ -469,6 +1040,12 @@ mod tests {
         assert_eq!(cmd, Command::ShowIdx);
     }
 
+    #[test]
+    fn test_parse_command_show_labels() {
+        let cmd = parse_command("show_labels", 10);
+        assert_eq!(cmd, Command::ShowLabels);
+    }
+
     #[test]
     fn test_parse_command_save() {
         let cmd = parse_command("save game.json", 10);
@@ -0,0 +1,207 @@
+//! Typed HTTP client of the bot server API.
+//!
+//! [`BotServerClient`] sends the requests of the server routes and decodes
+//! the replies into the same structs the server encodes them from, so the
+//! two cannot drift apart. Error replies are decoded into the server's
+//! [`ErrorResponse`] and returned as [`ClientError::Api`].
+//!
+//! # Example
+//! ```no_run
+//! use gamey::client::BotServerClient;
+//!
+//! # async fn play() -> Result<(), gamey::client::ClientError> {
+//! let client = BotServerClient::new("http://localhost:3000");
+//! client.reset(7).await?;
+//! let reply = client.execute_move(0).await?;
+//! println!("The bot answered {:?}", reply.bot_move);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ErrorResponse, GameStateResponse, MoveResponse, MoveResultResponse, YEN};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+
+/// Why a request of [`BotServerClient`] failed.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The server could not be reached or the connection failed.
+    #[error("Request to {url} failed: {source}")]
+    Transport {
+        /// The URL of the request.
+        url: String,
+        /// The error of the HTTP client.
+        source: reqwest::Error,
+    },
+
+    /// The server answered with an error.
+    #[error("Server answered {status}: {}", error.message)]
+    Api {
+        /// HTTP status of the reply.
+        status: StatusCode,
+        /// The error sent by the server.
+        error: ErrorResponse,
+    },
+
+    /// The reply could not be decoded into the expected type.
+    #[error("Unexpected reply with status {status}: {message}")]
+    Decode {
+        /// HTTP status of the reply.
+        status: StatusCode,
+        /// Why the body did not decode, with the body when it is not JSON.
+        message: String,
+    },
+}
+
+/// Client of a running bot server.
+#[derive(Debug, Clone)]
+pub struct BotServerClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl BotServerClient {
+    /// Creates a client of the server at `base_url`, such as `http://localhost:3000`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// Creates a client that sends its requests with `http`, for custom
+    /// timeouts or headers.
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self { base_url, http }
+    }
+
+    /// Returns the URL of the server, without a trailing slash.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Checks that the server is up with `GET /status` and returns its answer, `OK`.
+    ///
+    /// # Errors
+    /// Returns [`ClientError::Transport`] if the server cannot be reached and
+    /// [`ClientError::Api`] if it answers with an error.
+    pub async fn status(&self) -> Result<String, ClientError> {
+        let url = self.url("/status");
+        let response = self.send(&url, self.http.get(&url)).await?;
+        let status = response.status();
+        let text = response.text().await.map_err(|source| ClientError::Transport { url, source })?;
+        if status.is_success() {
+            Ok(text)
+        } else {
+            Err(error_of(status, &text))
+        }
+    }
+
+    /// Plays the human move at cell `index` of the server game with
+    /// `POST /execute-move` and returns the position after the bot's answer.
+    ///
+    /// # Errors
+    /// Returns the errors of [`BotServerClient::status`], such as an
+    /// [`ClientError::Api`] error for a cell off the board, and
+    /// [`ClientError::Decode`] if the reply is not a [`MoveResultResponse`].
+    pub async fn execute_move(&self, index: u32) -> Result<MoveResultResponse, ClientError> {
+        let url = self.url("/execute-move");
+        let request = self.http.post(&url).json(&serde_json::json!({ "index": index }));
+        self.request(&url, request).await
+    }
+
+    /// Starts a new server game of `size` with `POST /reset`.
+    ///
+    /// # Errors
+    /// Returns the errors of [`BotServerClient::execute_move`].
+    pub async fn reset(&self, size: u32) -> Result<GameStateResponse, ClientError> {
+        let url = self.url("/reset");
+        let request = self.http.post(&url).json(&serde_json::json!({ "size": size }));
+        self.request(&url, request).await
+    }
+
+    /// Asks the bot `bot_id` for its move in the position `yen` with
+    /// `POST /{version}/ybot/choose/{bot_id}`.
+    ///
+    /// # Errors
+    /// Returns the errors of [`BotServerClient::execute_move`], such as an
+    /// [`ClientError::Api`] error with code `UNKNOWN_BOT`.
+    pub async fn choose(&self, version: &str, bot_id: &str, yen: &YEN) -> Result<MoveResponse, ClientError> {
+        let url = self.url(&format!("/{}/ybot/choose/{}", version, bot_id));
+        let request = self.http.post(&url).json(yen);
+        self.request(&url, request).await
+    }
+
+    /// Returns the server game with `GET /game`.
+    ///
+    /// # Errors
+    /// Returns the errors of [`BotServerClient::execute_move`].
+    pub async fn game_state(&self) -> Result<GameStateResponse, ClientError> {
+        let url = self.url("/game");
+        self.request(&url, self.http.get(&url)).await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send(&self, url: &str, request: RequestBuilder) -> Result<reqwest::Response, ClientError> {
+        request.send().await.map_err(|source| ClientError::Transport {
+            url: url.to_string(),
+            source,
+        })
+    }
+
+    /// Sends `request` and decodes its JSON reply, or the error of the server.
+    async fn request<T: DeserializeOwned>(&self, url: &str, request: RequestBuilder) -> Result<T, ClientError> {
+        let response = self.send(url, request).await?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(|source| ClientError::Transport {
+            url: url.to_string(),
+            source,
+        })?;
+        if !status.is_success() {
+            return Err(error_of(status, &String::from_utf8_lossy(&body)));
+        }
+        serde_json::from_slice(&body).map_err(|e| ClientError::Decode {
+            status,
+            message: e.to_string(),
+        })
+    }
+}
+
+/// The error of a reply with `status`, from the [`ErrorResponse`] in `body` if there is one.
+fn error_of(status: StatusCode, body: &str) -> ClientError {
+    match serde_json::from_str::<ErrorResponse>(body) {
+        Ok(error) => ClientError::Api { status, error },
+        Err(_) => ClientError::Decode {
+            status,
+            message: body.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_bodies_become_api_errors() {
+        let body = r#"{"api_version":"v1","bot_id":"nobot","message":"Bot not found: nobot","code":"UNKNOWN_BOT"}"#;
+        match error_of(StatusCode::NOT_FOUND, body) {
+            ClientError::Api { status, error } => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(error.code.as_deref(), Some("UNKNOWN_BOT"));
+            }
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+        let error = error_of(StatusCode::BAD_GATEWAY, "upstream down");
+        assert!(matches!(error, ClientError::Decode { .. }));
+        assert_eq!(error.to_string(), "Unexpected reply with status 502 Bad Gateway: upstream down");
+    }
+
+    #[test]
+    fn test_base_url_loses_its_trailing_slash() {
+        let client = BotServerClient::new("http://localhost:3000/");
+        assert_eq!(client.base_url(), "http://localhost:3000");
+        assert_eq!(client.url("/game"), "http://localhost:3000/game");
+    }
+}
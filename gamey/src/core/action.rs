@@ -1,9 +1,14 @@
+use crate::GameYError;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
 
 /// Represents special game actions that are not regular piece placements.
 ///
 /// These actions allow players to perform non-placement moves during the game.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
 pub enum GameAction {
     /// The swap rule: allows the second player to swap colors after the first move.
     /// This is commonly used in games like Hex and Y to balance first-move advantage.
@@ -21,10 +26,46 @@ impl Display for GameAction {
     }
 }
 
+impl GameAction {
+    /// Returns the lowercase name of the action used in move notations: `swap` or `resign`.
+    pub fn notation(&self) -> &'static str {
+        match self {
+            GameAction::Swap => "swap",
+            GameAction::Resign => "resign",
+        }
+    }
+}
+
+impl FromStr for GameAction {
+    type Err = GameYError;
+
+    /// Reads `swap` or `resign`, as written by [`GameAction::notation`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "swap" => Ok(GameAction::Swap),
+            "resign" => Ok(GameAction::Resign),
+            _ => Err(GameYError::InvalidMoveNotation {
+                text: s.to_string(),
+                message: "expected 'swap' or 'resign'".to_string(),
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        for action in [GameAction::Swap, GameAction::Resign] {
+            assert_eq!(action.notation().parse::<GameAction>().unwrap(), action);
+        }
+        for text in ["Swap", " swap", "pass", ""] {
+            assert!(text.parse::<GameAction>().is_err(), "{:?}", text);
+        }
+    }
+
     #[test]
     fn test_display_swap() {
         assert_eq!(format!("{}", GameAction::Swap), "Swap");
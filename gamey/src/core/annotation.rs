@@ -0,0 +1,162 @@
+//! Comments, marked cells and evaluations attached to the moves of a game.
+//!
+//! Annotations are for teaching and analysis: they are kept by game records
+//! and SGF files, and can be shown under the board by [`GameY::render`], but
+//! they are not part of the position, so YEN ignores them.
+
+use crate::{Coordinates, GameY, GameYError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What an annotator says about a move.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MoveAnnotation {
+    /// Free text about the move.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub comment: String,
+    /// Cells to highlight on the board after the move.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<Coordinates>,
+    /// Evaluation of the position after the move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eval: Option<f32>,
+}
+
+impl MoveAnnotation {
+    /// Creates an annotation with only a comment.
+    pub fn comment(comment: impl Into<String>) -> Self {
+        Self {
+            comment: comment.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds `coords` to the marked cells.
+    pub fn with_mark(mut self, coords: Coordinates) -> Self {
+        self.marks.push(coords);
+        self
+    }
+
+    /// Sets the evaluation.
+    pub fn with_eval(mut self, eval: f32) -> Self {
+        self.eval = Some(eval);
+        self
+    }
+
+    /// Returns `true` if the annotation says nothing.
+    pub fn is_empty(&self) -> bool {
+        self.comment.is_empty() && self.marks.is_empty() && self.eval.is_none()
+    }
+}
+
+impl GameY {
+    /// Attaches `annotation` to move `ply`, replacing any previous one.
+    ///
+    /// Move 1 is the first move of the history; ply 0 annotates the empty
+    /// board, for example with a comment about the whole game.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidPly` if the game has fewer than `ply`
+    /// moves, and `GameYError::InvalidCoordinates` or
+    /// `GameYError::CoordOutOfRange` if a mark is not a cell of the board.
+    pub fn annotate(&mut self, ply: usize, annotation: MoveAnnotation) -> Result<()> {
+        let moves = self.move_count();
+        if ply > moves {
+            return Err(GameYError::InvalidPly { ply, moves });
+        }
+        for mark in &annotation.marks {
+            mark.try_to_index(self.board_size)?;
+        }
+        self.annotations.insert(ply, annotation);
+        Ok(())
+    }
+
+    /// Returns the annotation of move `ply`, if it has one.
+    pub fn annotation(&self, ply: usize) -> Option<&MoveAnnotation> {
+        self.annotations.get(&ply)
+    }
+
+    /// Removes and returns the annotation of move `ply`.
+    pub fn remove_annotation(&mut self, ply: usize) -> Option<MoveAnnotation> {
+        self.annotations.remove(&ply)
+    }
+
+    /// Returns every annotation by ply.
+    pub fn annotations(&self) -> &HashMap<usize, MoveAnnotation> {
+        &self.annotations
+    }
+
+    /// Returns the annotation of the last move played, or of the empty board.
+    pub fn last_annotation(&self) -> Option<&MoveAnnotation> {
+        self.annotation(self.move_count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, PlayerId};
+
+    fn game_with_moves() -> GameY {
+        let moves = [
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(2, 0, 0),
+            },
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(0, 1, 1),
+            },
+        ];
+        GameY::from_moves(3, &moves).unwrap()
+    }
+
+    #[test]
+    fn test_annotate_and_read_back() {
+        let mut game = game_with_moves();
+        let note = MoveAnnotation::comment("takes the corner").with_eval(0.5);
+        game.annotate(1, note.clone()).unwrap();
+        game.annotate(0, MoveAnnotation::comment("a short game")).unwrap();
+        assert_eq!(game.annotation(1), Some(&note));
+        assert_eq!(game.annotations().len(), 2);
+        assert_eq!(game.last_annotation(), None);
+        assert_eq!(game.remove_annotation(1), Some(note));
+        assert_eq!(game.annotation(1), None);
+    }
+
+    #[test]
+    fn test_annotate_rejects_future_plies_and_bad_marks() {
+        let mut game = game_with_moves();
+        assert!(matches!(
+            game.annotate(3, MoveAnnotation::comment("too far")),
+            Err(GameYError::InvalidPly { ply: 3, moves: 2 })
+        ));
+        let off_board = MoveAnnotation::default().with_mark(Coordinates::new(1, 1, 1));
+        assert!(game.annotate(2, off_board).is_err());
+        assert!(game.annotations().is_empty());
+    }
+
+    #[test]
+    fn test_render_shows_the_comment_of_the_last_move() {
+        let mut game = game_with_moves();
+        game.annotate(1, MoveAnnotation::comment("first")).unwrap();
+        game.annotate(2, MoveAnnotation::comment("last")).unwrap();
+        let options = crate::RenderOptions {
+            show_comment: true,
+            ..Default::default()
+        };
+        let rendered = game.render(&options);
+        assert!(rendered.ends_with("last\n"), "{}", rendered);
+        assert!(!rendered.contains("first"));
+        assert!(!game.render(&crate::RenderOptions::default()).contains("last"));
+    }
+
+    #[test]
+    fn test_annotations_are_not_part_of_the_position() {
+        let mut game = game_with_moves();
+        let plain = game.clone();
+        game.annotate(2, MoveAnnotation::comment("even")).unwrap();
+        assert_eq!(game, plain);
+        assert_eq!(crate::YEN::from(&game), crate::YEN::from(&plain));
+    }
+}
@@ -0,0 +1,281 @@
+//! Per-player game clocks.
+//!
+//! A [`Clock`] tracks the remaining thinking time of each player under a
+//! [`TimeControl`] (initial time plus a per-move increment). The type is pure:
+//! every operation takes the current [`Instant`] as a parameter, so callers
+//! (and tests) decide where time comes from.
+
+use crate::PlayerId;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Time control settings: initial time per player and increment per move.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    /// Initial thinking time for each player, in seconds.
+    pub initial_s: u64,
+    /// Time added to a player's clock after each of their moves, in seconds.
+    pub increment_s: u64,
+}
+
+/// Largest initial time accepted by [`TimeControl::check`]: one day.
+pub const MAX_INITIAL_S: u64 = 24 * 60 * 60;
+
+/// Largest increment accepted by [`TimeControl::check`]: one hour.
+pub const MAX_INCREMENT_S: u64 = 60 * 60;
+
+impl TimeControl {
+    /// Checks that the times are at most [`MAX_INITIAL_S`] and [`MAX_INCREMENT_S`].
+    ///
+    /// # Errors
+    /// Returns a message naming the first time out of range.
+    pub fn check(&self) -> Result<(), String> {
+        if self.initial_s > MAX_INITIAL_S {
+            return Err(format!("Initial time {}s is over the maximum of {}s", self.initial_s, MAX_INITIAL_S));
+        }
+        if self.increment_s > MAX_INCREMENT_S {
+            return Err(format!("Increment {}s is over the maximum of {}s", self.increment_s, MAX_INCREMENT_S));
+        }
+        Ok(())
+    }
+}
+
+/// A chess-style clock with one countdown per player.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: Vec<Duration>,
+    /// The player whose time is running and the instant it started running.
+    running: Option<(PlayerId, Instant)>,
+    /// The player who ran out of time, if any.
+    flagged: Option<PlayerId>,
+}
+
+/// A serializable view of a [`Clock`] at a given instant.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ClockSnapshot {
+    /// Remaining time of each player in milliseconds, indexed by player id.
+    pub remaining_ms: Vec<u64>,
+    /// The player whose clock is running, if any.
+    pub running: Option<u32>,
+    /// The player who lost on time, if any.
+    pub flagged: Option<u32>,
+}
+
+impl Clock {
+    /// Creates a stopped clock giving every player the initial time.
+    pub fn new(control: TimeControl, num_players: usize) -> Self {
+        Self {
+            control,
+            remaining: vec![Duration::from_secs(control.initial_s); num_players],
+            running: None,
+            flagged: None,
+        }
+    }
+
+    /// Returns the time control of this clock.
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Starts (or restarts) the countdown of `player` at `now`.
+    pub fn start(&mut self, player: PlayerId, now: Instant) {
+        if self.flagged.is_none() {
+            self.running = Some((player, now));
+        }
+    }
+
+    /// Returns the player whose clock is currently running.
+    pub fn running(&self) -> Option<PlayerId> {
+        self.running.map(|(player, _)| player)
+    }
+
+    /// Returns the player who ran out of time, if any.
+    pub fn flagged(&self) -> Option<PlayerId> {
+        self.flagged
+    }
+
+    /// Returns the remaining time of `player` as seen at `now`.
+    pub fn remaining(&self, player: PlayerId, now: Instant) -> Duration {
+        let stored = self.remaining[player.id() as usize];
+        match self.running {
+            Some((running, since)) if running == player => {
+                stored.saturating_sub(now.saturating_duration_since(since))
+            }
+            _ => stored,
+        }
+    }
+
+    /// Checks whether the running player has run out of time at `now`.
+    ///
+    /// Once a player flags, the clock stops and the flag is permanent.
+    pub fn check_flag(&mut self, now: Instant) -> Option<PlayerId> {
+        if self.flagged.is_some() {
+            return self.flagged;
+        }
+        if let Some((player, _)) = self.running
+            && self.remaining(player, now).is_zero()
+        {
+            self.remaining[player.id() as usize] = Duration::ZERO;
+            self.running = None;
+            self.flagged = Some(player);
+        }
+        self.flagged
+    }
+
+    /// Records that the running player completed a move at `now`.
+    ///
+    /// Deducts the elapsed time, adds the increment and starts the clock of
+    /// `next` (or stops the clock when `next` is `None`, e.g. the game ended).
+    /// Returns `Err(player)` if the mover had already run out of time. A
+    /// move should not be applied before knowing that: check the flag with
+    /// [`Clock::check_flag`] at the same `now` first.
+    pub fn press(&mut self, now: Instant, next: Option<PlayerId>) -> Result<(), PlayerId> {
+        if let Some(flagged) = self.check_flag(now) {
+            return Err(flagged);
+        }
+        if let Some((player, _)) = self.running {
+            let left = self.remaining(player, now);
+            self.remaining[player.id() as usize] =
+                left.saturating_add(Duration::from_secs(self.control.increment_s));
+        }
+        self.running = next.map(|player| (player, now));
+        Ok(())
+    }
+
+    /// Stops the running countdown without adding any increment.
+    pub fn stop(&mut self, now: Instant) {
+        if let Some((player, _)) = self.running {
+            self.remaining[player.id() as usize] = self.remaining(player, now);
+        }
+        self.running = None;
+    }
+
+    /// Returns a serializable view of the clock at `now`.
+    pub fn snapshot(&self, now: Instant) -> ClockSnapshot {
+        ClockSnapshot {
+            remaining_ms: (0..self.remaining.len())
+                .map(|i| {
+                    let millis = self.remaining(PlayerId::new(i as u32), now).as_millis();
+                    u64::try_from(millis).unwrap_or(u64::MAX)
+                })
+                .collect(),
+            running: self.running().map(|p| p.id()),
+            flagged: self.flagged.map(|p| p.id()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blitz() -> TimeControl {
+        TimeControl {
+            initial_s: 10,
+            increment_s: 2,
+        }
+    }
+
+    #[test]
+    fn test_huge_times_saturate_and_are_rejected() {
+        let huge = TimeControl {
+            initial_s: u64::MAX,
+            increment_s: u64::MAX,
+        };
+        assert!(huge.check().is_err());
+        assert!(TimeControl { initial_s: MAX_INITIAL_S, increment_s: u64::MAX }.check().is_err());
+        assert!(TimeControl { initial_s: MAX_INITIAL_S, increment_s: MAX_INCREMENT_S }.check().is_ok());
+
+        let mut clock = Clock::new(huge, 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(0), t0);
+        clock.press(t0 + Duration::from_secs(1), Some(PlayerId::new(1))).unwrap();
+        assert_eq!(clock.remaining(PlayerId::new(0), t0), Duration::MAX);
+        assert_eq!(clock.snapshot(t0).remaining_ms, [u64::MAX, u64::MAX]);
+    }
+
+    #[test]
+    fn test_new_clock_gives_initial_time() {
+        let clock = Clock::new(blitz(), 2);
+        let now = Instant::now();
+        assert_eq!(clock.remaining(PlayerId::new(0), now), Duration::from_secs(10));
+        assert_eq!(clock.remaining(PlayerId::new(1), now), Duration::from_secs(10));
+        assert_eq!(clock.running(), None);
+    }
+
+    #[test]
+    fn test_running_clock_counts_down() {
+        let mut clock = Clock::new(blitz(), 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(0), t0);
+        let t1 = t0 + Duration::from_secs(3);
+        assert_eq!(clock.remaining(PlayerId::new(0), t1), Duration::from_secs(7));
+        assert_eq!(clock.remaining(PlayerId::new(1), t1), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_press_adds_increment_and_switches() {
+        let mut clock = Clock::new(blitz(), 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(0), t0);
+
+        let t1 = t0 + Duration::from_secs(3);
+        clock.press(t1, Some(PlayerId::new(1))).unwrap();
+        // 10 - 3 + 2
+        assert_eq!(clock.remaining(PlayerId::new(0), t1), Duration::from_secs(9));
+        assert_eq!(clock.running(), Some(PlayerId::new(1)));
+
+        let t2 = t1 + Duration::from_secs(5);
+        clock.press(t2, Some(PlayerId::new(0))).unwrap();
+        // 10 - 5 + 2
+        assert_eq!(clock.remaining(PlayerId::new(1), t2), Duration::from_secs(7));
+        assert_eq!(clock.remaining(PlayerId::new(0), t2), Duration::from_secs(9));
+    }
+
+    #[test]
+    fn test_flag_falls_when_time_runs_out() {
+        let mut clock = Clock::new(blitz(), 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(0), t0);
+
+        let late = t0 + Duration::from_secs(11);
+        assert_eq!(clock.check_flag(late), Some(PlayerId::new(0)));
+        assert_eq!(clock.remaining(PlayerId::new(0), late), Duration::ZERO);
+        assert_eq!(clock.running(), None);
+    }
+
+    #[test]
+    fn test_press_after_flag_is_rejected() {
+        let mut clock = Clock::new(blitz(), 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(0), t0);
+
+        let late = t0 + Duration::from_secs(10);
+        assert_eq!(clock.press(late, Some(PlayerId::new(1))), Err(PlayerId::new(0)));
+        assert_eq!(clock.flagged(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_stop_freezes_remaining_time() {
+        let mut clock = Clock::new(blitz(), 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(1), t0);
+        clock.stop(t0 + Duration::from_secs(4));
+        let much_later = t0 + Duration::from_secs(100);
+        assert_eq!(clock.remaining(PlayerId::new(1), much_later), Duration::from_secs(6));
+        assert_eq!(clock.check_flag(much_later), None);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut clock = Clock::new(blitz(), 2);
+        let t0 = Instant::now();
+        clock.start(PlayerId::new(0), t0);
+        let snapshot = clock.snapshot(t0 + Duration::from_millis(1500));
+        assert_eq!(snapshot.remaining_ms, vec![8500, 10000]);
+        assert_eq!(snapshot.running, Some(0));
+        assert_eq!(snapshot.flagged, None);
+    }
+}
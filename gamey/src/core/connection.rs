@@ -0,0 +1,586 @@
+//! The parts of a game that do not depend on the shape of the board.
+//!
+//! [`ConnectionGame`] keeps the engine, the status, the history, the free
+//! cells, the players and the observers of any connection game played on a
+//! [`BoardTopology`]. Each game only adds how its moves and cells are named:
+//! [`GameY`](crate::GameY) is a `ConnectionGame` on a [`TriangularTopology`](crate::TriangularTopology)
+//! and [`GameHex`](crate::GameHex) one on a [`HexTopology`](crate::HexTopology).
+
+use crate::core::history::CompactHistory;
+use crate::core::observer::Observers;
+use crate::core::opening::OpeningRules;
+use crate::{
+    BoardTopology, CellIndex, GameAction, GameEngine, GameObserver, GameStatus, GameYError, MoveAnnotation, Movement,
+    PlayerId, PlayerInfo, Result, WinReason,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+
+/// What a move does, once its cell is translated to an index of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveEffect<'a> {
+    /// A stone on the cell with this index.
+    Placement(CellIndex),
+    /// A swap or a resignation.
+    Action(&'a GameAction),
+}
+
+/// A move of a connection game, like [`Movement`] for Y.
+///
+/// Games name their cells differently; this trait is all a
+/// [`ConnectionGame`] needs to know about the moves it plays.
+pub trait GameMove: Clone + Debug + Display + PartialEq {
+    /// Returns the player who makes the move.
+    fn player(&self) -> PlayerId;
+
+    /// Returns what the move does on a board of `board_size`.
+    ///
+    /// Fails if the move places a stone off the board.
+    fn kind(&self, board_size: u32) -> Result<MoveEffect<'_>>;
+
+    /// Builds the move of `player` that does `effect` on a board of `board_size`.
+    ///
+    /// The inverse of [`GameMove::kind`], used to rebuild stored histories.
+    fn from_effect(player: PlayerId, effect: MoveEffect<'_>, board_size: u32) -> Self;
+
+    /// Returns the error reported when `player` places a stone on `cell`,
+    /// which is occupied, on a board of `board_size`.
+    fn occupied_error(player: PlayerId, cell: CellIndex, board_size: u32) -> GameYError;
+
+    /// Returns the error reported when this move is added to a finished game.
+    fn game_over_error(self) -> GameYError;
+}
+
+/// The state of a connection game on the board `T`, with moves of type `M`.
+///
+/// Players take turns placing stones until one of them connects the regions
+/// of [`BoardTopology::winning_mask_for`]; swaps and resignations are played
+/// as actions.
+#[derive(Debug, Clone)]
+pub struct ConnectionGame<T: BoardTopology, M: GameMove = Movement> {
+    // Number of cells of each side of the board.
+    pub(in crate::core) board_size: u32,
+
+    // The generic game engine handling topology and connectivity
+    pub(in crate::core) engine: GameEngine<T>,
+
+    pub(in crate::core) status: GameStatus,
+
+    // History of moves made in the game, packed; see `ConnectionGame::history`.
+    pub(in crate::core) history: CompactHistory,
+
+    // Player who lost the game by running out of time, if that is how it ended.
+    pub(in crate::core) lost_on_time: Option<PlayerId>,
+
+    // Player who forfeited the game, if that is how it ended.
+    pub(in crate::core) forfeited: Option<PlayerId>,
+
+    // Number of players taking turns, 2 for the standard game.
+    pub(in crate::core) num_players: u32,
+
+    // Names of the players, if they were given explicitly.
+    pub(in crate::core) player_info: Option<Vec<PlayerInfo>>,
+
+    // Symbols of the players in YEN layouts, if they are not the standard ones.
+    pub(in crate::core) player_symbols: Option<Vec<char>>,
+
+    // Swap and first-move restrictions chosen at creation. See `GameY::with_opening`.
+    pub(in crate::core) rules: OpeningRules,
+
+    // Comments, marks and evaluations of the moves, by ply. See `GameY::annotate`.
+    pub(in crate::core) annotations: HashMap<usize, MoveAnnotation>,
+
+    // Observers notified of moves, wins and resets. Not copied by `clone`.
+    pub(in crate::core) observers: Observers<T, M>,
+}
+
+/// Two games are equal when they are the same position: same board size and
+/// number of players, the same stones on the same cells and the same status.
+/// How the position was reached is not compared; see [`ConnectionGame::same_history`].
+impl<T: BoardTopology, M: GameMove> PartialEq for ConnectionGame<T, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.board_size == other.board_size
+            && self.num_players == other.num_players
+            && self.status == other.status
+            && self.engine.occupants() == other.engine.occupants()
+    }
+}
+
+impl<T: BoardTopology, M: GameMove> Eq for ConnectionGame<T, M> {}
+
+/// Hashes the board size, the Zobrist hash of the stones and the status, in
+/// agreement with the position equality.
+impl<T: BoardTopology, M: GameMove> Hash for ConnectionGame<T, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.board_size.hash(state);
+        self.num_players.hash(state);
+        self.position_hash().hash(state);
+        self.status.hash(state);
+    }
+}
+
+impl<T: BoardTopology, M: GameMove> ConnectionGame<T, M> {
+    /// Creates an empty game of `num_players` on `topology`, with the first player to move.
+    pub(crate) fn from_topology(board_size: u32, topology: T, num_players: u32) -> Self {
+        Self {
+            board_size,
+            engine: GameEngine::new(topology),
+            history: CompactHistory::default(),
+            status: GameStatus::Ongoing {
+                next_player: PlayerId::new(0),
+            },
+            lost_on_time: None,
+            forfeited: None,
+            num_players,
+            player_info: None,
+            player_symbols: None,
+            rules: OpeningRules::default(),
+            annotations: HashMap::new(),
+            observers: Observers::default(),
+        }
+    }
+
+    /// Registers an observer that is notified of every later move, win and reset.
+    ///
+    /// Observers stay with this game only; clones of the game start without observers.
+    pub fn subscribe(&mut self, observer: Box<dyn GameObserver<T, M>>) {
+        self.observers.0.push(observer);
+    }
+
+    /// Restarts the game with an empty board of the same size, players and names.
+    pub fn reset(&mut self)
+    where
+        T: Clone,
+    {
+        self.reset_to(self.empty_copy());
+    }
+
+    /// Returns an empty game with the size, players and names of this one.
+    pub(crate) fn empty_copy(&self) -> Self
+    where
+        T: Clone,
+    {
+        let mut fresh = Self::from_topology(self.board_size, self.engine.topology().clone(), self.num_players);
+        fresh.player_info = self.player_info.clone();
+        fresh.player_symbols = self.player_symbols.clone();
+        fresh.rules = self.rules.clone();
+        fresh
+    }
+
+    /// Replaces this game with `game`, keeping the observers of this game.
+    ///
+    /// Observers registered on `game` are dropped. Every observer is notified
+    /// with [`GameObserver::on_reset`].
+    pub fn reset_to(&mut self, game: Self) {
+        let observers = std::mem::take(&mut self.observers);
+        *self = game;
+        self.observers = observers;
+        self.notify(|observer, game| observer.on_reset(game));
+    }
+
+    /// Calls `event` on every observer with a shared reference to the game.
+    fn notify(&mut self, event: impl Fn(&dyn GameObserver<T, M>, &Self)) {
+        if self.observers.0.is_empty() {
+            return;
+        }
+        let observers = std::mem::take(&mut self.observers);
+        for observer in &observers.0 {
+            event(observer.as_ref(), self);
+        }
+        self.observers = observers;
+    }
+
+    /// Notifies the observers of the last move and, if it ended the game, of the win.
+    fn notify_move(&mut self, was_over: bool) {
+        self.notify(|observer, game| {
+            if let Some(movement) = game.last_move() {
+                observer.on_move(game, &movement, &game.status);
+            }
+        });
+        if !was_over {
+            self.notify_finish();
+        }
+    }
+
+    /// Notifies the observers of the winner, if the game is over.
+    fn notify_finish(&mut self) {
+        if let GameStatus::Finished { winner, .. } = self.status {
+            self.notify(|observer, game| observer.on_finish(game, winner));
+        }
+    }
+
+    /// Attaches display information to the players of this game.
+    ///
+    /// Entries for ids outside the game are ignored; players without an entry
+    /// keep their default information.
+    pub fn with_player_info(mut self, players: Vec<PlayerInfo>) -> Self {
+        let players: Vec<PlayerInfo> = players
+            .into_iter()
+            .filter(|info| info.id.id() < self.num_players)
+            .collect();
+        self.player_info = (!players.is_empty()).then_some(players);
+        self
+    }
+
+    /// Replaces the display information of the player `info.id`, keeping
+    /// that of the other players.
+    ///
+    /// Information for an id outside the game is ignored.
+    pub fn set_player_info(&mut self, info: PlayerInfo) {
+        if info.id.id() >= self.num_players {
+            return;
+        }
+        let players = self.player_info.get_or_insert_with(Vec::new);
+        players.retain(|other| other.id != info.id);
+        players.push(info);
+    }
+
+    /// Returns the display information of every player, in player order.
+    ///
+    /// Players without explicit information are called "Player 0", "Player 1", ...
+    pub fn players(&self) -> Vec<PlayerInfo> {
+        (0..self.num_players)
+            .map(PlayerId::new)
+            .map(|id| {
+                self.player_info
+                    .iter()
+                    .flatten()
+                    .find(|info| info.id == id)
+                    .cloned()
+                    .unwrap_or_else(|| PlayerInfo::default_for(id))
+            })
+            .collect()
+    }
+
+    /// Returns the number of players taking turns in this game.
+    pub fn num_players(&self) -> u32 {
+        self.num_players
+    }
+
+    /// Returns the current game status.
+    pub fn status(&self) -> &GameStatus {
+        &self.status
+    }
+
+    /// Returns true if the game has ended (has a winner).
+    pub fn check_game_over(&self) -> bool {
+        match self.status {
+            GameStatus::Ongoing { .. } => false,
+            GameStatus::Finished { .. } => true,
+        }
+    }
+
+    /// Returns the underlying engine (topology, occupancy and connected groups).
+    pub fn engine(&self) -> &GameEngine<T> {
+        &self.engine
+    }
+
+    /// Returns the Zobrist hash of the current position.
+    ///
+    /// The hash is maintained incrementally by the engine, so this is O(1).
+    /// Unlike [`crate::GameY::canonical_key`], symmetric positions hash differently.
+    pub fn position_hash(&self) -> u64 {
+        self.engine.hash()
+    }
+
+    /// Returns the moves played so far, in order.
+    ///
+    /// The game keeps its moves packed in a few bytes each and rebuilds them
+    /// on every call, so [`ConnectionGame::move_count`] and
+    /// [`ConnectionGame::last_move`] are cheaper when they are enough.
+    pub fn history(&self) -> Cow<'_, [M]> {
+        Cow::Owned(self.history.moves(self.board_size).collect())
+    }
+
+    /// Returns the number of moves played so far.
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns the last move played, if any.
+    pub fn last_move(&self) -> Option<M> {
+        self.history.last(self.board_size)
+    }
+
+    /// Returns `true` if both games are the same position reached by the same
+    /// moves, in the same order.
+    ///
+    /// Stricter than `==`, which only compares the positions.
+    pub fn same_history(&self, other: &Self) -> bool {
+        self == other
+            && self.history == other.history
+            && self.lost_on_time == other.lost_on_time
+            && self.forfeited == other.forfeited
+    }
+
+    /// Returns the list of available cell indices where pieces can be placed.
+    ///
+    /// A view of the empty cells of the engine, whose order changes as moves
+    /// are made; see [`GameEngine::empty_cells`].
+    pub fn available_cells(&self) -> &Vec<u32> {
+        self.engine.empty_list()
+    }
+
+    /// Returns the total number of cells on the board.
+    pub fn total_cells(&self) -> u32 {
+        self.engine.topology().total_cells() as u32
+    }
+
+    /// Checks if the movement is made by the correct player.
+    ///
+    /// Returns an error if it's not the specified player's turn.
+    pub fn check_player_turn(&self, movement: &M) -> Result<()> {
+        if let GameStatus::Ongoing { next_player } = self.status {
+            let player = movement.player();
+            if player != next_player {
+                return Err(GameYError::InvalidPlayerTurn {
+                    expected: next_player,
+                    found: player,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the player who should make the next move, or None if the game is over.
+    pub fn next_player(&self) -> Option<PlayerId> {
+        if let GameStatus::Ongoing { next_player } = self.status {
+            Some(next_player)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of plies played: every move of the history, placement or action.
+    ///
+    /// A swap counts as the ply of the player who swaps, and a resignation
+    /// as the last ply of the game. Stones placed before the first move,
+    /// such as random openings, are not plies. The ply only grows, so a
+    /// client that sees it jump by more than one has missed an update.
+    pub fn ply(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns the player to move, or `None` if the game is over.
+    ///
+    /// The same as [`ConnectionGame::next_player`], named after the position
+    /// rather than the sequence of moves.
+    pub fn side_to_move(&self) -> Option<PlayerId> {
+        self.next_player()
+    }
+
+    /// Returns `true` if the game is ongoing and `player` is to move.
+    pub fn is_players_turn(&self, player: PlayerId) -> bool {
+        self.side_to_move() == Some(player)
+    }
+
+    /// Gives the turn to `player` in a position read from a layout, unless the game is over.
+    pub(crate) fn set_next_player(&mut self, player: PlayerId) {
+        if !self.check_game_over() {
+            self.status = GameStatus::Ongoing { next_player: player };
+        }
+    }
+
+    /// Adds a move to the game.
+    ///
+    /// Observers are notified after the move is added.
+    ///
+    /// # Errors
+    /// Besides illegal moves, rejects the moves forbidden by the opening
+    /// options of the game: `GameYError::SwapNotAllowed` and
+    /// `GameYError::ForbiddenOpening`; see [`crate::OpeningOptions`].
+    pub fn add_move(&mut self, movement: M) -> Result<()> {
+        if self.check_game_over() {
+            return Err(movement.game_over_error());
+        }
+        self.check_opening_rules(&movement)?;
+        self.apply_move(movement)
+    }
+
+    /// Checks `movement` against the opening options the game was created with.
+    pub fn check_opening_rules(&self, movement: &M) -> Result<()> {
+        match movement.kind(self.board_size)? {
+            MoveEffect::Action(GameAction::Swap) if self.rules.no_swap => Err(GameYError::SwapNotAllowed {
+                player: movement.player(),
+            }),
+            MoveEffect::Placement(idx) if self.history.len() == 0 => match self.rules.forbidden_radius(idx) {
+                Some(radius) => Err(GameYError::ForbiddenOpening {
+                    index: idx as u32,
+                    radius,
+                }),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Plays `movement` even if the game is already over.
+    ///
+    /// Used to rebuild positions whose layout has stones beyond the winning one.
+    pub(crate) fn apply_move(&mut self, movement: M) -> Result<()> {
+        let was_over = self.check_game_over();
+        match movement.kind(self.board_size)? {
+            MoveEffect::Placement(idx) => self.handle_placement(&movement, idx)?,
+            MoveEffect::Action(action) => self.handle_action(movement.player(), action),
+        }
+        self.history.push(&movement, self.board_size);
+        self.notify_move(was_over);
+        Ok(())
+    }
+
+    /// Orchestrates the placement logic
+    fn handle_placement(&mut self, movement: &M, idx: CellIndex) -> Result<()> {
+        self.validate_placement(movement, idx)?;
+        let player = movement.player();
+        // The engine only fails on occupied cells, already ruled out above
+        let won = self
+            .engine
+            .make_move(idx, player)
+            .map_err(|_| M::occupied_error(player, idx, self.board_size))?;
+        self.update_status_after_placement(player, won);
+        Ok(())
+    }
+
+    /// Updates the game status (Finished vs Ongoing)
+    fn update_status_after_placement(&mut self, player: PlayerId, won: bool) {
+        if self.check_game_over() {
+            tracing::info!("Game was already over. Move ignored for status update.");
+        } else if won {
+            tracing::debug!("Player {} wins the game!", player);
+            self.status = GameStatus::Finished {
+                winner: player,
+                reason: WinReason::Connection,
+            };
+        } else {
+            self.status = GameStatus::Ongoing {
+                next_player: next_player_after(player, self.num_players),
+            };
+        }
+    }
+
+    /// Handles non-placement actions (Resign, Swap, etc.)
+    ///
+    /// With more than two players a resignation ends the game in favor of the
+    /// next player in turn order.
+    fn handle_action(&mut self, player: PlayerId, action: &GameAction) {
+        match action {
+            GameAction::Resign => {
+                self.status = GameStatus::Finished {
+                    winner: next_player_after(player, self.num_players),
+                    reason: WinReason::Resignation,
+                };
+            }
+            GameAction::Swap => {
+                self.status = GameStatus::Ongoing {
+                    next_player: next_player_after(player, self.num_players),
+                };
+            }
+        }
+    }
+
+    /// Ends the game because `player` ran out of time, making the opponent the winner.
+    ///
+    /// Has no effect if the game is already over.
+    pub fn lose_on_time(&mut self, player: PlayerId) {
+        if self.check_game_over() {
+            return;
+        }
+        tracing::debug!("Player {} lost on time", player);
+        self.status = GameStatus::Finished {
+            winner: next_player_after(player, self.num_players),
+            reason: WinReason::Timeout,
+        };
+        self.lost_on_time = Some(player);
+        self.notify_finish();
+    }
+
+    /// Returns the player who lost on time, if the game ended that way.
+    pub fn lost_on_time(&self) -> Option<PlayerId> {
+        self.lost_on_time
+    }
+
+    /// Ends the game because `player` forfeited, for example by answering
+    /// with an illegal move, making the next player the winner.
+    ///
+    /// Has no effect if the game is already over.
+    pub fn forfeit(&mut self, player: PlayerId) {
+        if self.check_game_over() {
+            return;
+        }
+        tracing::debug!("Player {} forfeits", player);
+        self.status = GameStatus::Finished {
+            winner: next_player_after(player, self.num_players),
+            reason: WinReason::Forfeit,
+        };
+        self.forfeited = Some(player);
+        self.notify_finish();
+    }
+
+    /// Returns the player who forfeited, if the game ended that way.
+    pub fn forfeited(&self) -> Option<PlayerId> {
+        self.forfeited
+    }
+
+    /// Handles validation logic (Game Over checks and Occupancy)
+    fn validate_placement(&self, movement: &M, idx: CellIndex) -> Result<()> {
+        if self.check_game_over() {
+            tracing::info!("Game is already over. {} could be ignored", movement);
+        }
+        if self.engine.occupant(idx).is_some() {
+            return Err(M::occupied_error(movement.player(), idx, self.board_size));
+        }
+        Ok(())
+    }
+
+    /// Returns the size of the board (number of cells of each side).
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+}
+
+/// Returns the player who moves after `player` in a game of `count` players.
+pub(crate) fn next_player_after(player: PlayerId, count: u32) -> PlayerId {
+    PlayerId::new((player.id() + 1) % count.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameHex, GameY, HexCell, HexMove};
+
+    #[test]
+    fn test_next_player_after() {
+        assert_eq!(next_player_after(PlayerId::new(0), 2), PlayerId::new(1));
+        assert_eq!(next_player_after(PlayerId::new(1), 2), PlayerId::new(0));
+        assert_eq!(next_player_after(PlayerId::new(1), 3), PlayerId::new(2));
+        assert_eq!(next_player_after(PlayerId::new(2), 3), PlayerId::new(0));
+    }
+
+    #[test]
+    fn test_shared_rules_on_both_boards() {
+        let mut y = GameY::new(3);
+        y.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(4, 3),
+        })
+        .unwrap();
+        let mut hex = GameHex::new(3);
+        hex.add_move(HexMove::Placement {
+            player: PlayerId::new(0),
+            cell: HexCell::from_index(4, 3),
+        })
+        .unwrap();
+        for (available, total) in [(y.available_cells(), y.total_cells()), (hex.available_cells(), hex.total_cells())] {
+            assert_eq!(available.len() as u32, total - 1);
+            assert!(!available.contains(&4));
+        }
+        assert_eq!(y.next_player(), hex.next_player());
+
+        let mut fresh = hex.clone();
+        fresh.reset();
+        assert!(fresh.history().is_empty());
+        assert_eq!(fresh.available_cells().len(), 9);
+    }
+}
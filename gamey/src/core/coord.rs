@@ -2,6 +2,8 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
+use crate::GameYError;
+
 /// Represents barycentric coordinates (x, y, z) on a triangular board.
 ///
 /// In a triangular board of size N, valid coordinates satisfy:
@@ -12,7 +14,12 @@ use serde::{Deserialize, Serialize};
 /// - x = 0 means the cell touches side A
 /// - y = 0 means the cell touches side B
 /// - z = 0 means the cell touches side C
+///
+/// [`Coordinates::new`] and the index conversions trust their arguments to
+/// be on the board; use [`Coordinates::try_new`], [`Coordinates::try_from_index`]
+/// and [`Coordinates::try_to_index`] for values that come from outside.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Coordinates {
     x: u32,
     y: u32,
@@ -25,6 +32,18 @@ impl Coordinates {
         Self { x, y, z }
     }
 
+    /// Creates coordinates that lie on a board of `board_size`.
+    ///
+    /// # Errors
+    /// Returns `GameYError::CoordOutOfRange` if a component is not below
+    /// `board_size` and `GameYError::InvalidCoordinates` if the components do
+    /// not add up to `board_size - 1`.
+    pub fn try_new(x: u32, y: u32, z: u32, board_size: u32) -> Result<Self, GameYError> {
+        let coords = Self::new(x, y, z);
+        coords.check_on_board(board_size)?;
+        Ok(coords)
+    }
+
     /// Returns the x coordinate (distance from side A).
     pub fn x(&self) -> u32 {
         self.x
@@ -70,6 +89,50 @@ impl Coordinates {
         row_start_index + c
     }
 
+    /// Converts a linear index to coordinates, checking that the index is on
+    /// a board of `board_size`.
+    ///
+    /// # Errors
+    /// Returns `GameYError::CellIndexOutOfRange` if `index` is not below the
+    /// number of cells of the board.
+    pub fn try_from_index(index: u32, board_size: u32) -> Result<Self, GameYError> {
+        let size = board_size as u64;
+        if index as u64 >= size * (size + 1) / 2 {
+            return Err(GameYError::CellIndexOutOfRange { index, board_size });
+        }
+        Ok(Self::from_index(index, board_size))
+    }
+
+    /// Converts these coordinates to a linear index, checking first that they
+    /// lie on a board of `board_size`.
+    ///
+    /// # Errors
+    /// Fails like [`Coordinates::try_new`].
+    pub fn try_to_index(&self, board_size: u32) -> Result<u32, GameYError> {
+        self.check_on_board(board_size)?;
+        Ok(self.to_index(board_size))
+    }
+
+    /// Checks the invariant of the coordinates of a board of `board_size`.
+    fn check_on_board(&self, board_size: u32) -> Result<(), GameYError> {
+        for (id_coord, coord) in [('x', self.x), ('y', self.y), ('z', self.z)] {
+            if coord >= board_size {
+                return Err(GameYError::CoordOutOfRange {
+                    id_coord,
+                    coord,
+                    board_size,
+                });
+            }
+        }
+        if self.x as u64 + self.y as u64 + self.z as u64 != board_size as u64 - 1 {
+            return Err(GameYError::InvalidCoordinates {
+                coordinates: *self,
+                board_size,
+            });
+        }
+        Ok(())
+    }
+
     /// Creates coordinates from a slice of 3 u32 values.
     ///
     /// Returns `None` if the slice does not have exactly 3 elements.
@@ -184,6 +247,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fallible_roundtrip_every_cell_of_small_boards() {
+        for board_size in 1..=8 {
+            let total_cells = (board_size * (board_size + 1)) / 2;
+            for idx in 0..total_cells {
+                let coords = Coordinates::try_from_index(idx, board_size).unwrap();
+                assert_eq!(coords.x() + coords.y() + coords.z(), board_size - 1);
+                assert_eq!(
+                    Coordinates::try_new(coords.x(), coords.y(), coords.z(), board_size).unwrap(),
+                    coords
+                );
+                assert_eq!(coords.try_to_index(board_size).unwrap(), idx);
+            }
+            assert!(matches!(
+                Coordinates::try_from_index(total_cells, board_size),
+                Err(GameYError::CellIndexOutOfRange { index, .. }) if index == total_cells
+            ));
+        }
+    }
+
+    #[test]
+    fn test_fallible_conversions_reject_cells_off_the_board() {
+        assert!(matches!(
+            Coordinates::try_new(3, 0, 0, 3),
+            Err(GameYError::CoordOutOfRange { id_coord: 'x', coord: 3, board_size: 3 })
+        ));
+        assert!(matches!(
+            Coordinates::try_new(0, 0, 5, 3),
+            Err(GameYError::CoordOutOfRange { id_coord: 'z', .. })
+        ));
+        assert!(matches!(
+            Coordinates::try_new(1, 1, 1, 3),
+            Err(GameYError::InvalidCoordinates { board_size: 3, .. })
+        ));
+        assert!(Coordinates::try_new(0, 0, 0, 0).is_err());
+        assert!(Coordinates::new(1, 2, 3).try_to_index(4).is_err());
+        assert!(Coordinates::try_from_index(0, 0).is_err());
+        assert!(Coordinates::new(u32::MAX, u32::MAX, 2).try_to_index(u32::MAX).is_err());
+    }
+
     #[test]
     fn test_corner_touches_two_sides() {
         // Top corner touches sides B and C (y=0 and z=0)
@@ -0,0 +1,231 @@
+//! Cell-by-cell comparison of two positions.
+//!
+//! Useful to see where two copies of a game drifted apart, for example the
+//! board a client believes in and the one kept by the server, and to print
+//! readable test failures.
+
+use crate::core::game::{apply_player_color, indent};
+use crate::{Cell, Coordinates, GameY, GameYError, RenderOptions, Result};
+use std::fmt::Write;
+
+/// A cell whose content differs between two positions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellDiff {
+    /// The cell that differs.
+    pub coords: Coordinates,
+    /// Content of the cell in the first position.
+    pub left: Cell,
+    /// Content of the cell in the second position.
+    pub right: Cell,
+}
+
+/// How a cell changed from the first position to the second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    /// A stone was placed on an empty cell.
+    Added,
+    /// A stone was taken from the cell.
+    Removed,
+    /// The stone belongs to another player.
+    Changed,
+}
+
+impl DiffKind {
+    /// Returns the marker used by [`GameY::render_diff`]: `+`, `-` or `!`.
+    pub fn marker(self) -> char {
+        match self {
+            DiffKind::Added => '+',
+            DiffKind::Removed => '-',
+            DiffKind::Changed => '!',
+        }
+    }
+
+    // ANSI colors of the markers: green, red and yellow.
+    fn color(self) -> u8 {
+        match self {
+            DiffKind::Added => 32,
+            DiffKind::Removed => 31,
+            DiffKind::Changed => 33,
+        }
+    }
+}
+
+impl CellDiff {
+    /// Returns how the cell changed.
+    pub fn kind(&self) -> DiffKind {
+        match (self.left, self.right) {
+            (Cell::Empty, _) => DiffKind::Added,
+            (_, Cell::Empty) => DiffKind::Removed,
+            _ => DiffKind::Changed,
+        }
+    }
+}
+
+impl GameY {
+    /// Returns the cells whose content differs between this position and `other`,
+    /// in index order. Identical boards give an empty list.
+    ///
+    /// Only the stones are compared, not the history or whose turn it is.
+    ///
+    /// # Errors
+    /// Returns `GameYError::BoardSizeMismatch` if the boards have different sizes.
+    pub fn diff(&self, other: &GameY) -> Result<Vec<CellDiff>> {
+        if self.board_size() != other.board_size() {
+            return Err(GameYError::BoardSizeMismatch {
+                left: self.board_size(),
+                right: other.board_size(),
+            });
+        }
+        Ok((0..self.total_cells())
+            .map(|idx| Coordinates::from_index(idx, self.board_size()))
+            .filter_map(|coords| {
+                let (left, right) = (self.cell(&coords), other.cell(&coords));
+                (left != right).then_some(CellDiff { coords, left, right })
+            })
+            .collect())
+    }
+
+    /// Renders `other` with the cells that differ from this position marked:
+    /// `+` for added stones, `-` for removed ones and `!` for stones of another player.
+    ///
+    /// Removed stones show the player they belonged to. With `show_colors` the
+    /// markers are green, red and yellow. `show_idx` and `show_3d_coords` are
+    /// ignored to keep the markers aligned. Boards of different sizes cannot
+    /// be compared; the text then only says so.
+    pub fn render_diff(&self, other: &GameY, options: &RenderOptions) -> String {
+        let diffs = match self.diff(other) {
+            Ok(diffs) => diffs,
+            Err(e) => return format!("--- {} ---\n", e),
+        };
+        let size = self.board_size();
+        let mut result = String::new();
+        let _ = writeln!(
+            result,
+            "--- Diff of Game of Y (Size {}): {} cell(s) differ ---",
+            size,
+            diffs.len()
+        );
+
+        for row in 0..size {
+            let x = size - 1 - row;
+            indent(&mut result, x * 2);
+            for y in 0..=row {
+                let coords = Coordinates::new(x, y, row - y);
+                let diff = diffs.iter().find(|diff| diff.coords == coords);
+                let shown = match diff {
+                    Some(diff) if diff.kind() == DiffKind::Removed => diff.left,
+                    _ => other.cell(&coords),
+                };
+                let (symbol, player) = match shown {
+                    Cell::Occupied(player) => (player.to_string(), Some(player)),
+                    Cell::Empty => (".".to_string(), None),
+                };
+                let symbol = if options.show_colors {
+                    apply_player_color(symbol, player)
+                } else {
+                    symbol
+                };
+                let marker = match diff.map(CellDiff::kind) {
+                    Some(kind) if options.show_colors => {
+                        format!("\x1b[{}m{}\x1b[0m", kind.color(), kind.marker())
+                    }
+                    Some(kind) => kind.marker().to_string(),
+                    None => " ".to_string(),
+                };
+                let _ = write!(result, "{}{}  ", marker, symbol);
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, PlayerId};
+
+    fn place(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    const PLAIN: RenderOptions = RenderOptions {
+        show_3d_coords: false,
+        show_idx: false,
+        show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
+    };
+
+    #[test]
+    fn test_identical_boards_have_no_diff() {
+        let mut game = GameY::new(4);
+        place(&mut game, 0, Coordinates::new(1, 1, 1));
+        assert!(game.diff(&game.clone()).unwrap().is_empty());
+        assert!(GameY::new(3).diff(&GameY::new(3)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_added_stone() {
+        let before = GameY::new(3);
+        let mut after = before.clone();
+        place(&mut after, 0, Coordinates::new(0, 1, 1));
+
+        let diffs = before.diff(&after).unwrap();
+        assert_eq!(
+            diffs,
+            vec![CellDiff {
+                coords: Coordinates::new(0, 1, 1),
+                left: Cell::Empty,
+                right: Cell::Occupied(PlayerId::new(0)),
+            }]
+        );
+        assert_eq!(diffs[0].kind(), DiffKind::Added);
+        assert_eq!(after.diff(&before).unwrap()[0].kind(), DiffKind::Removed);
+
+        let text = before.render_diff(&after, &PLAIN);
+        assert!(text.contains("1 cell(s) differ"));
+        assert_eq!(text.lines().last().unwrap().trim_end(), " .  +0   .");
+    }
+
+    #[test]
+    fn test_recolored_stone() {
+        let coords = Coordinates::new(2, 0, 0);
+        let mut blue = GameY::new(3);
+        place(&mut blue, 0, coords);
+        let mut red = GameY::new(3);
+        red.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: crate::GameAction::Swap,
+        })
+        .unwrap();
+        place(&mut red, 1, coords);
+
+        let diffs = blue.diff(&red).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].kind(), DiffKind::Changed);
+        assert_eq!(diffs[0].right, Cell::Occupied(PlayerId::new(1)));
+
+        let text = blue.render_diff(&red, &PLAIN);
+        assert!(text.lines().nth(1).unwrap().contains("!1"));
+        let colored = blue.render_diff(&red, &RenderOptions::default());
+        assert!(colored.contains("\x1b[33m!\x1b[0m"));
+    }
+
+    #[test]
+    fn test_different_sizes_are_rejected() {
+        let result = GameY::new(3).diff(&GameY::new(4));
+        assert!(matches!(
+            result,
+            Err(GameYError::BoardSizeMismatch { left: 3, right: 4 })
+        ));
+        let text = GameY::new(3).render_diff(&GameY::new(4), &PLAIN);
+        assert!(text.contains("sizes 3 and 4"), "{}", text);
+    }
+}
@@ -0,0 +1,222 @@
+//! Setting up positions stone by stone.
+//!
+//! [`GameY::editor`] opens an [`Editor`] on the stones of a game, where stones
+//! of any player can be placed and erased in any order, without turns or
+//! history. [`Editor::finish`] turns the result back into a game, for
+//! composing puzzles and test positions.
+
+use crate::core::topology::{CellIndex, GameEngine, TriangularTopology};
+use crate::{Coordinates, GameStatus, GameY, GameYError, PlayerId, Result, WinReason};
+
+/// A board whose stones can be placed and erased freely.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, PlayerId};
+///
+/// let mut editor = GameY::new(3).editor();
+/// editor.place(PlayerId::new(1), Coordinates::new(2, 0, 0)).unwrap();
+/// let game = editor.finish(PlayerId::new(0)).unwrap();
+///
+/// assert!(game.history().is_empty());
+/// assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Editor {
+    /// The game being edited, emptied of its stones and history.
+    empty: GameY,
+    /// The stones placed so far.
+    engine: GameEngine<TriangularTopology>,
+}
+
+impl GameY {
+    /// Opens an editor on the stones of this game, keeping its size, players and names.
+    ///
+    /// The game itself is not changed; [`Editor::finish`] builds a new one.
+    pub fn editor(&self) -> Editor {
+        Editor {
+            empty: self.empty_copy(),
+            engine: self.engine.clone(),
+        }
+    }
+}
+
+impl Editor {
+    /// Places a stone of `player` on `coords`.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidPosition` if `player` does not play this
+    /// game, the errors of [`Coordinates::try_to_index`] if `coords` is not a
+    /// cell of the board and `GameYError::Occupied` if the cell has a stone.
+    pub fn place(&mut self, player: PlayerId, coords: Coordinates) -> Result<&mut Self> {
+        if player.id() >= self.empty.num_players {
+            return Err(GameYError::InvalidPosition {
+                message: format!("player {} is not one of the {} players", player, self.empty.num_players),
+            });
+        }
+        let cell = coords.try_to_index(self.empty.board_size)? as CellIndex;
+        self.engine
+            .make_move(cell, player)
+            .map_err(|_| GameYError::Occupied { coordinates: coords, player })?;
+        Ok(self)
+    }
+
+    /// Erases the stone on `coords` and returns its owner, or `None` if the cell was empty.
+    ///
+    /// # Errors
+    /// Returns the errors of [`Coordinates::try_to_index`] if `coords` is not
+    /// a cell of the board.
+    pub fn erase(&mut self, coords: Coordinates) -> Result<Option<PlayerId>> {
+        let cell = coords.try_to_index(self.empty.board_size)? as CellIndex;
+        let owner = self.engine.occupant(cell);
+        if owner.is_some() {
+            // The cell exists and has a stone, so the engine cannot fail
+            let _ = self.engine.remove_piece(cell);
+        }
+        Ok(owner)
+    }
+
+    /// Erases every stone.
+    pub fn clear(&mut self) -> &mut Self {
+        self.engine = GameEngine::new(self.engine.topology().clone());
+        self
+    }
+
+    /// Returns the stone on `coords`, or `None` if the cell is empty or off the board.
+    pub fn stone(&self, coords: Coordinates) -> Option<PlayerId> {
+        let cell = coords.try_to_index(self.empty.board_size).ok()? as CellIndex;
+        self.engine.occupant(cell)
+    }
+
+    /// Builds a game with the edited stones and an empty history.
+    ///
+    /// The status comes from the stones: a player with a winning chain has
+    /// won, and otherwise `next_player` is to move.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidPosition` if `next_player` does not play
+    /// this game or if more than one player has a winning chain.
+    pub fn finish(self, next_player: PlayerId) -> Result<GameY> {
+        let num_players = self.empty.num_players;
+        if next_player.id() >= num_players {
+            return Err(GameYError::InvalidPosition {
+                message: format!("player {} is not one of the {} players", next_player, num_players),
+            });
+        }
+        let mut game = self.empty;
+        let mut winners = Vec::new();
+        for (cell, occupant) in self.engine.occupants().iter().enumerate() {
+            let Some(player) = *occupant else {
+                continue;
+            };
+            if game.engine.make_move(cell, player) == Ok(true) && !winners.contains(&player) {
+                winners.push(player);
+            }
+        }
+        game.status = match winners.as_slice() {
+            [] => GameStatus::Ongoing { next_player },
+            [winner] => GameStatus::Finished {
+                winner: *winner,
+                reason: WinReason::Connection,
+            },
+            _ => {
+                return Err(GameYError::InvalidPosition {
+                    message: format!("{} players have a winning chain", winners.len()),
+                });
+            }
+        };
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, YEN};
+
+    fn p(id: u32) -> PlayerId {
+        PlayerId::new(id)
+    }
+
+    #[test]
+    fn test_edit_erase_and_finish() {
+        let mut editor = GameY::new(3).editor();
+        editor.place(p(0), Coordinates::new(2, 0, 0)).unwrap();
+        editor.place(p(0), Coordinates::new(0, 2, 0)).unwrap();
+        editor.place(p(1), Coordinates::new(0, 0, 2)).unwrap();
+        editor.place(p(1), Coordinates::new(1, 1, 0)).unwrap();
+        assert_eq!(editor.erase(Coordinates::new(1, 1, 0)).unwrap(), Some(p(1)));
+        assert_eq!(editor.erase(Coordinates::new(1, 1, 0)).unwrap(), None);
+        let game = editor.finish(p(1)).unwrap();
+
+        assert!(game.history().is_empty());
+        assert_eq!(game.next_player(), Some(p(1)));
+        let yen = YEN::from(&game);
+        assert_eq!(yen.layout(), "B/../R.B");
+        assert_eq!(yen.turn(), 1);
+        let occupied = [Coordinates::new(2, 0, 0), Coordinates::new(0, 2, 0), Coordinates::new(0, 0, 2)];
+        for coords in occupied {
+            assert!(!game.available_cells().contains(&coords.to_index(3)));
+        }
+        assert_eq!(game.available_cells().len(), 3);
+        assert_eq!(GameY::try_from(yen).unwrap(), game);
+    }
+
+    #[test]
+    fn test_finish_recomputes_the_winner() {
+        let mut editor = GameY::new(3).editor();
+        editor.place(p(1), Coordinates::new(2, 0, 0)).unwrap();
+        editor.place(p(1), Coordinates::new(0, 2, 0)).unwrap();
+        assert_eq!(editor.clone().finish(p(0)).unwrap().status(), &GameStatus::Ongoing { next_player: p(0) });
+
+        editor.place(p(1), Coordinates::new(1, 1, 0)).unwrap();
+        let game = editor.clone().finish(p(0)).unwrap();
+        assert_eq!(
+            game.status(),
+            &GameStatus::Finished {
+                winner: p(1),
+                reason: WinReason::Connection
+            }
+        );
+        assert_eq!(game.available_cells().len(), 3);
+
+        // Erasing the connecting stone takes the win back
+        editor.erase(Coordinates::new(1, 1, 0)).unwrap();
+        assert_eq!(editor.finish(p(0)).unwrap().next_player(), Some(p(0)));
+    }
+
+    #[test]
+    fn test_editing_a_played_game_drops_its_history() {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Placement {
+            player: p(0),
+            coords: Coordinates::new(1, 1, 0),
+        })
+        .unwrap();
+        let mut editor = game.editor();
+        assert_eq!(editor.stone(Coordinates::new(1, 1, 0)), Some(p(0)));
+        editor.clear();
+        assert_eq!(editor.stone(Coordinates::new(1, 1, 0)), None);
+        let edited = editor.finish(p(0)).unwrap();
+        assert_eq!(edited, GameY::new(3));
+        assert_eq!(game.history().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_edits_are_rejected() {
+        let mut editor = GameY::new(3).editor();
+        editor.place(p(0), Coordinates::new(2, 0, 0)).unwrap();
+        assert!(matches!(
+            editor.place(p(1), Coordinates::new(2, 0, 0)),
+            Err(GameYError::Occupied { .. })
+        ));
+        assert!(matches!(
+            editor.place(p(2), Coordinates::new(1, 1, 0)),
+            Err(GameYError::InvalidPosition { .. })
+        ));
+        assert!(editor.place(p(0), Coordinates::new(3, 0, 0)).is_err());
+        assert!(editor.erase(Coordinates::new(1, 1, 1)).is_err());
+        assert!(matches!(editor.finish(p(2)), Err(GameYError::InvalidPosition { .. })));
+    }
+}
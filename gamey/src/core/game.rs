@@ -1,7 +1,13 @@
-use crate::core::topology::BoardTopology;
-use crate::core::topology::{GameEngine, TriangularTopology};
-use crate::{Coordinates, GameAction, GameYError, Movement, PlayerId, RenderOptions, YEN};
-use std::fmt::Write;
+use crate::core::topology::{CellIndex, RegionMask, TriangularTopology};
+use crate::notation::move_text::column_name;
+use crate::{
+    ConnectionGame, Coordinates, GameAction, GameFormat, GameYError, Movement, PlayerId, RenderOptions, YEN, YenIssue, heat_map_char,
+    from_sgf, read_record, to_sgf, write_record,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write as IoWrite};
 use std::path::Path;
 
 /// A Result type alias for game operations that may fail with a `GameYError`.
@@ -13,21 +19,35 @@ pub type Result<T> = std::result::Result<T, crate::GameYError>;
 /// take turns placing pieces. The goal is to connect all three sides
 /// of the triangle with a single chain of connected pieces.
 ///
-/// The struct wich controls the game
-#[derive(Debug, Clone)]
-pub struct GameY {
-    // Size of the board (length of one side of the triangular board).
-    board_size: u32,
+/// The rules shared with other boards live in [`ConnectionGame`]; this
+/// module adds the coordinates, rendering and file formats of Y.
+pub type GameY = ConnectionGame<TriangularTopology>;
 
-    // The generic game engine handling topology and connectivity
-    engine: GameEngine<TriangularTopology>,
+/// Symbols used for each player in YEN layouts, in player order.
+///
+/// The length of this list is also the maximum number of players in a game.
+pub const PLAYER_SYMBOLS: [char; 6] = ['B', 'R', 'G', 'Y', 'P', 'O'];
 
-    status: GameStatus,
+/// Smallest board size accepted by [`GameY::try_new`].
+pub const MIN_BOARD_SIZE: u32 = 1;
 
-    // History of moves made in the game.
-    history: Vec<Movement>,
+/// Largest board size accepted by [`GameY::try_new`].
+///
+/// A board of this size has 5050 cells; larger ones make the precomputed
+/// adjacency of the topology grow quadratically for no playable benefit.
+pub const MAX_BOARD_SIZE: u32 = 100;
 
-    available_cells: Vec<u32>,
+/// Checks that `size` is between [`MIN_BOARD_SIZE`] and [`MAX_BOARD_SIZE`].
+pub(crate) fn check_board_size(size: u32) -> Result<()> {
+    if (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size) {
+        Ok(())
+    } else {
+        Err(GameYError::InvalidBoardSize {
+            size,
+            min: MIN_BOARD_SIZE,
+            max: MAX_BOARD_SIZE,
+        })
+    }
 }
 
 /// Represents the state of a single cell on the board.
@@ -40,203 +60,257 @@ pub enum Cell {
 }
 
 impl GameY {
-    /// Creates a new game with the specified board size and number of players.
+    /// Creates a new two-player game with the specified board size.
+    ///
+    /// # Panics
+    /// Panics if `board_size` is not between [`MIN_BOARD_SIZE`] and
+    /// [`MAX_BOARD_SIZE`]; use [`GameY::try_new`] for sizes that come from outside.
     pub fn new(board_size: u32) -> Self {
-        let total_cells = (board_size * (board_size + 1)) / 2;
-        let topology = TriangularTopology::new(board_size);
-        let engine = GameEngine::new(topology);
-
-        Self {
-            board_size,
-            engine,
-            history: Vec::new(),
-            status: GameStatus::Ongoing {
-                next_player: PlayerId::new(0),
-            },
-            available_cells: (0..total_cells).collect(),
-        }
+        Self::with_players(board_size, 2)
     }
 
-    /// Returns the current game status.
-    pub fn status(&self) -> &GameStatus {
-        &self.status
+    /// Creates a new two-player game, checking the board size.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidBoardSize` if `board_size` is not between
+    /// [`MIN_BOARD_SIZE`] and [`MAX_BOARD_SIZE`].
+    pub fn try_new(board_size: u32) -> Result<Self> {
+        check_board_size(board_size)?;
+        Ok(Self::new(board_size))
     }
 
-    /// Returns true if the game has ended (has a winner).
-    pub fn check_game_over(&self) -> bool {
-        match self.status {
-            GameStatus::Ongoing { .. } => false,
-            GameStatus::Finished { winner: _ } => true,
+    /// Creates a new game with the specified board size and number of players.
+    ///
+    /// `num_players` is clamped to `2..=PLAYER_SYMBOLS.len()`. With more than
+    /// two players each player must connect its own pair of sides, see
+    /// [`TriangularTopology::with_players`].
+    ///
+    /// # Panics
+    /// Panics for board sizes that [`GameY::try_new`] rejects.
+    pub fn with_players(board_size: u32, num_players: u32) -> Self {
+        if let Err(e) = check_board_size(board_size) {
+            panic!("{}", e);
         }
+        let num_players = num_players.clamp(2, PLAYER_SYMBOLS.len() as u32);
+        let topology = TriangularTopology::with_players(board_size, num_players);
+        Self::from_topology(board_size, topology, num_players)
     }
 
-    /// Returns the list of available cell indices where pieces can be placed.
-    pub fn available_cells(&self) -> &Vec<u32> {
-        &self.available_cells
+    /// Uses `symbols` for the players in YEN layouts, one per player in player order.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidNumPlayers` if there is not one symbol per
+    /// player, and the error of [`YenIssue::ReservedPlayerSymbol`] or
+    /// [`YenIssue::DuplicatePlayer`] for a `.`, a `/` or a repeated symbol.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{GameY, YEN};
+    ///
+    /// let game = GameY::new(3).with_player_symbols(vec!['X', 'O']).unwrap();
+    /// assert_eq!(YEN::from(&game).players(), &['X', 'O']);
+    /// assert!(GameY::new(3).with_player_symbols(vec!['X', 'X']).is_err());
+    /// ```
+    pub fn with_player_symbols(mut self, symbols: Vec<char>) -> Result<Self> {
+        if symbols.len() != self.num_players as usize {
+            return Err(GameYError::InvalidNumPlayers {
+                num_players: symbols.len() as u32,
+                expected: self.num_players,
+            });
+        }
+        if let Some(issue) = crate::yen::symbol_issues(&symbols).into_iter().next() {
+            return Err(issue.into());
+        }
+        let standard = symbols[..] == PLAYER_SYMBOLS[..symbols.len()];
+        self.player_symbols = (!standard).then_some(symbols);
+        Ok(self)
     }
 
-    /// Returns the total number of cells on the board.
-    pub fn total_cells(&self) -> u32 {
-        (self.board_size * (self.board_size + 1)) / 2
+    /// Returns the symbols of the players in YEN layouts, in player order.
+    ///
+    /// They are the first [`PLAYER_SYMBOLS`] unless the game was read from a
+    /// YEN with other symbols or given them with [`GameY::with_player_symbols`].
+    pub fn player_symbols(&self) -> Vec<char> {
+        match &self.player_symbols {
+            Some(symbols) => symbols.clone(),
+            None => PLAYER_SYMBOLS[..self.num_players as usize].to_vec(),
+        }
     }
 
-    /// Checks if the movement is made by the correct player.
+    /// Returns a hash of the board that is identical for all symmetric positions.
     ///
-    /// Returns an error if it's not the specified player's turn.
-    pub fn check_player_turn(&self, movement: &Movement) -> Result<()> {
-        if let GameStatus::Ongoing { next_player } = self.status {
-            let player = match movement {
-                Movement::Placement { player, .. } => *player,
-                Movement::Action { player, .. } => *player,
-            };
-            if player != next_player {
-                return Err(GameYError::InvalidPlayerTurn {
-                    expected: next_player,
-                    found: player,
-                });
-            }
-        }
-        Ok(())
+    /// Each of the six board symmetries is applied to the layout, the smallest
+    /// resulting layout is kept and hashed together with the board size. Only
+    /// stone placement is considered, not whose turn it is.
+    pub fn canonical_key(&self) -> u64 {
+        let canonical = self
+            .engine
+            .topology()
+            .symmetries()
+            .iter()
+            .map(|perm| self.cell_codes(perm))
+            .min()
+            .unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        self.board_size.hash(&mut hasher);
+        canonical.hash(&mut hasher);
+        hasher.finish()
     }
 
-    /// Returns the player who should make the next move, or None if the game is over.
-    pub fn next_player(&self) -> Option<PlayerId> {
-        if let GameStatus::Ongoing { next_player } = self.status {
-            Some(next_player)
-        } else {
-            None
+    /// Returns the occupancy of every cell after applying the permutation `perm`.
+    ///
+    /// Empty cells are encoded as 0 and a stone of player `p` as `p + 1`.
+    pub(crate) fn cell_codes(&self, perm: &[CellIndex]) -> Vec<u8> {
+        let mut codes = vec![0; perm.len()];
+        for (cell, occupant) in self.engine.occupants().iter().enumerate() {
+            codes[perm[cell]] = occupant.map_or(0, |p| p.id() as u8 + 1);
         }
+        codes
     }
 
-    /// Loads a game state from a YEN format file.
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let filename = path.as_ref().display().to_string();
-        let file_content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
-            message: format!("Failed to read file: {}", filename),
-            error: e.to_string(),
-        })?;
-        let yen: YEN =
-            serde_json::from_str(&file_content).map_err(|e| GameYError::SerdeError { error: e })?;
-        GameY::try_from(yen)
+    /// Returns what occupies the cell at `coords`.
+    pub fn cell(&self, coords: &Coordinates) -> Cell {
+        let idx = coords.to_index(self.board_size) as usize;
+        match self.engine.occupant(idx) {
+            Some(player) => Cell::Occupied(player),
+            None => Cell::Empty,
+        }
     }
 
-    /// Saves the game state to a file in YEN format.
-    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let yen: YEN = self.into();
-        let json_content =
-            serde_json::to_string_pretty(&yen).map_err(|e| GameYError::SerdeError { error: e })?;
-        let filename = path.as_ref().display().to_string();
-        std::fs::write(path, json_content).map_err(|e| GameYError::IoError {
-            message: format!("Failed to write file: {}", filename),
-            error: e.to_string(),
-        })?;
-        Ok(())
+    /// Returns the sides of the board touched by the stones of `player`: the
+    /// union of the regions of all their groups.
+    pub fn sides_touched(&self, player: PlayerId) -> RegionMask {
+        self.engine.groups(player).into_iter().fold(0, |sides, regions| sides | regions)
     }
 
-    /// Adds a move to the game.
-    pub fn add_move(&mut self, movement: Movement) -> Result<()> {
-        match &movement {
-            Movement::Placement { player, coords } => {
-                self.handle_placement(*player, *coords)?;
-            }
-            Movement::Action { player, action } => {
-                self.handle_action(*player, action);
-            }
-        }
-        self.history.push(movement);
-        Ok(())
+    /// Returns the names of the sides touched by the stones of `player`, such
+    /// as `["A", "C"]`, in alphabetical order.
+    pub fn sides_touched_named(&self, player: PlayerId) -> Vec<&'static str> {
+        let sides = self.sides_touched(player);
+        (0..RegionMask::BITS)
+            .map(|bit| 1 << bit)
+            .filter(|side| sides & side != 0)
+            .filter_map(TriangularTopology::side_name)
+            .collect()
     }
 
-    /// Orchestrates the placement logic
-    fn handle_placement(&mut self, player: PlayerId, coords: Coordinates) -> Result<()> {
-        self.validate_placement(player, coords)?;
+    /// Loads a game from a file, detecting its format.
+    ///
+    /// The format is chosen from the extension (`.yen`/`.json`, `.sgf`, `.ygr`)
+    /// or, if the extension is unknown, from the first bytes of the file; see
+    /// [`GameFormat::detect`]. A YEN file that exists but cannot be parsed
+    /// (for example because a write was interrupted) is reported as
+    /// [`GameYError::CorruptFile`].
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bytes = read_file(path.as_ref())?;
+        let format = GameFormat::detect(&path, &bytes);
+        Self::parse_file(path.as_ref(), &bytes, format)
+    }
 
-        let idx = coords.to_index(self.board_size);
+    /// Loads a game from a file in the given format, whatever its extension.
+    pub fn load_from_file_as<P: AsRef<Path>>(path: P, format: GameFormat) -> Result<Self> {
+        let bytes = read_file(path.as_ref())?;
+        Self::parse_file(path.as_ref(), &bytes, format)
+    }
 
-        match self.engine.make_move(idx as usize, player) {
-            Ok(won) => {
-                self.available_cells.retain(|&x| x != idx);
-                self.update_status_after_placement(player, won);
-                Ok(())
+    /// Parses the content of the file `path` as `format`, naming the file in errors.
+    fn parse_file(path: &Path, bytes: &[u8], format: GameFormat) -> Result<Self> {
+        let filename = path.display().to_string();
+        let result = match format {
+            GameFormat::Yen => {
+                let yen: YEN =
+                    serde_json::from_slice(bytes).map_err(|e| GameYError::CorruptFile {
+                        path: filename.clone(),
+                        error: e,
+                    })?;
+                GameY::try_from(yen)
             }
-            Err(e) => {
-                // Map generic engine error to specific GameYError
-                // In a real scenario, we might want to parse 'e' or have typed errors in engine
-                if e.contains("ocupada") || e.contains("occupied") {
-                    Err(GameYError::Occupied {
-                        coordinates: coords,
-                        player,
-                    })
-                } else {
-                    // Fallback or panic? For now, treat as occupied or logic error
-                    Err(GameYError::Occupied {
-                        coordinates: coords,
-                        player,
-                    })
-                }
-            }
-        }
+            GameFormat::Sgf => match std::str::from_utf8(bytes) {
+                Ok(text) => from_sgf(text),
+                Err(_) => Err(GameYError::InvalidFormat {
+                    format,
+                    message: "the file is not valid UTF-8".to_string(),
+                }),
+            },
+            GameFormat::Record => read_record(bytes),
+        };
+        result.map_err(|e| match e {
+            GameYError::InvalidFormat { format, message } => GameYError::InvalidFormat {
+                format,
+                message: format!("{}: {}", filename, message),
+            },
+            other => other,
+        })
     }
 
-    /// Updates the game status (Finished vs Ongoing)
-    fn update_status_after_placement(&mut self, player: PlayerId, won: bool) {
-        if self.check_game_over() {
-            tracing::info!("Game was already over. Move ignored for status update.");
-        } else if won {
-            tracing::debug!("Player {} wins the game!", player);
-            self.status = GameStatus::Finished { winner: player };
-        } else {
-            // tracing::debug!("No win yet..."); // Optional debug
-            self.status = GameStatus::Ongoing {
-                next_player: other_player(player),
-            };
-        }
+    /// Loads a game state in YEN format from any reader.
+    pub fn load_from_reader<R: Read>(reader: R) -> Result<Self> {
+        let yen: YEN =
+            serde_json::from_reader(reader).map_err(|e| GameYError::SerdeError { error: e })?;
+        GameY::try_from(yen)
     }
 
-    /// Handles non-placement actions (Resign, Swap, etc.)
-    fn handle_action(&mut self, player: PlayerId, action: &GameAction) {
-        match action {
-            GameAction::Resign => {
-                self.status = GameStatus::Finished {
-                    winner: other_player(player),
-                };
-            }
-            GameAction::Swap => {
-                self.status = GameStatus::Ongoing {
-                    next_player: other_player(player),
-                };
-            }
-        }
+    /// Saves the game to a file, in the format matching its extension.
+    ///
+    /// Files with an unknown extension are saved as YEN.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let format = GameFormat::from_extension(&path).unwrap_or(GameFormat::Yen);
+        self.save_to_file_as(path, format)
     }
 
-    /// Handles validation logic (Game Over checks and Occupancy)
-    fn validate_placement(&self, player: PlayerId, coords: Coordinates) -> Result<()> {
-        if self.check_game_over() {
-            tracing::info!("Game is already over. Move at {} could be ignored", coords);
-        }
-        // Occupancy check is now done by engine, but we can double check here or let engine fail
-        // For consistency with previous error reporting order:
-        let idx = coords.to_index(self.board_size);
-        if self.engine.state[idx as usize].is_some() {
-            return Err(GameYError::Occupied {
-                coordinates: coords,
-                player,
-            });
+    /// Saves the game to a file in the given format.
+    ///
+    /// The game is written to a temporary file in the same directory, synced
+    /// to disk and then renamed over `path`, so a crash never leaves a
+    /// truncated file behind.
+    pub fn save_to_file_as<P: AsRef<Path>>(&self, path: P, format: GameFormat) -> Result<()> {
+        let path = path.as_ref();
+        let filename = path.display().to_string();
+        let io_error = |e: std::io::Error| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        };
+        let tmp_name = format!(
+            ".{}.tmp",
+            path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default()
+        );
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let write_tmp = || -> Result<()> {
+            let mut file = std::fs::File::create(&tmp_path).map_err(io_error)?;
+            match format {
+                GameFormat::Yen => self.save_to_writer(&mut file)?,
+                GameFormat::Sgf => file.write_all(to_sgf(self)?.as_bytes()).map_err(io_error)?,
+                GameFormat::Record => write_record(self, &mut file)?,
+            }
+            file.sync_all().map_err(io_error)
+        };
+        let result = write_tmp().and_then(|_| std::fs::rename(&tmp_path, path).map_err(io_error));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
         }
-        Ok(())
+        result
     }
 
-    /// Returns the size of the board (length of one side of the triangle).
-    pub fn board_size(&self) -> u32 {
-        self.board_size
+    /// Writes the game state in YEN format to any writer.
+    pub fn save_to_writer<W: IoWrite>(&self, mut writer: W) -> Result<()> {
+        let yen: YEN = self.into();
+        serde_json::to_writer_pretty(&mut writer, &yen)
+            .map_err(|e| GameYError::SerdeError { error: e })?;
+        writer.flush().map_err(|e| GameYError::IoError {
+            message: "Failed to flush game".to_string(),
+            error: e.to_string(),
+        })
     }
 
     /// Returns the neighboring coordinates for a given cell.
     /// Used mainly for tests now, delegating to topology
     #[cfg(test)]
     fn get_neighbors(&self, coords: &Coordinates) -> Vec<Coordinates> {
+        use crate::core::topology::BoardTopology;
         let idx = coords.to_index(self.board_size);
-        let neighbor_indices = self.engine.topology.get_neighbors(idx as usize);
+        let neighbor_indices = self.engine.topology().get_neighbors(idx as usize);
         neighbor_indices
             .iter()
             .map(|&i| Coordinates::from_index(i as u32, self.board_size))
@@ -244,31 +318,130 @@ impl GameY {
     }
 
     /// Renders the current state of the board as a text string.
-    /// If `show_coordinates` is true, the coordinates of each cell will be displayed.
+    ///
+    /// Same text as [`GameY::render_to`], in a string allocated once with
+    /// [`GameY::estimated_render_len`].
     pub fn render(&self, options: &RenderOptions) -> String {
-        let mut result = String::new();
-        let coords_size = self.board_size.to_string().len();
-        let _ = writeln!(result, "--- Game of Y (Size {}) ---", self.board_size);
+        let mut result = String::with_capacity(self.estimated_render_len(options));
+        // Writing to a String never fails
+        let _ = self.render_to(&mut result, options);
+        result
+    }
 
-        let indent_multiplier = self.get_indent_multiplier(options);
+    /// Writes the board as text to `w`: a header, then one line per row, with
+    /// no trailing spaces.
+    ///
+    /// # Errors
+    /// Returns the errors of `w`.
+    pub fn render_to<W: std::fmt::Write>(&self, w: &mut W, options: &RenderOptions) -> std::fmt::Result {
+        let coords_size = digits(self.board_size);
+        writeln!(w, "--- Game of Y (Size {}) ---", self.board_size)?;
+
+        let indent_multiplier = self.get_indent_multiplier(options) as usize;
+        // Row numbers are right-aligned in a margin as wide as the largest one
+        let margin = if options.show_labels { coords_size + 1 } else { 0 };
+        let separator = cell_separator(options);
 
         for row in 0..self.board_size {
             let x = self.board_size - 1 - row;
-            indent(&mut result, x * indent_multiplier);
+            if options.show_labels {
+                write!(w, "{:>w$} ", row + 1, w = coords_size)?;
+            }
+            write!(w, "{:1$}", "", x as usize * indent_multiplier)?;
 
             for y in 0..=row {
+                if y > 0 {
+                    w.write_str(separator)?;
+                }
                 let z = row - y;
-                let coords = Coordinates::new(x, y, z);
-                let cell_str = self.format_cell(coords, options, coords_size);
-                let _ = write!(result, "{}   ", cell_str);
+                self.write_cell(w, Coordinates::new(x, y, z), options, coords_size)?;
             }
 
-            result.push('\n');
+            w.write_char('\n')?;
             if options.show_idx || options.show_3d_coords {
-                result.push('\n');
+                w.write_char('\n')?;
             }
         }
-        result
+        if options.show_labels {
+            self.write_column_labels(w, options, margin, coords_size)?;
+        }
+        if let Some(comment) = self.rendered_comment(options) {
+            writeln!(w, "{}", comment)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the length in bytes of the text of [`GameY::render_to`] with
+    /// `options`, to allocate the buffer it is written to at once.
+    pub fn estimated_render_len(&self, options: &RenderOptions) -> usize {
+        let coords_size = digits(self.board_size);
+        let margin = if options.show_labels { coords_size + 1 } else { 0 };
+        let indent_multiplier = self.get_indent_multiplier(options) as usize;
+        let separator = cell_separator(options).len();
+        let newlines = if options.show_idx || options.show_3d_coords { 2 } else { 1 };
+
+        let mut len = "--- Game of Y (Size ) ---\n".len() + coords_size;
+        for row in 0..self.board_size {
+            let x = self.board_size - 1 - row;
+            len += margin + x as usize * indent_multiplier + row as usize * separator + newlines;
+            for y in 0..=row {
+                len += self.cell_len(Coordinates::new(x, y, row - y), options, coords_size);
+            }
+        }
+        if options.show_labels {
+            len += margin + self.column_labels_len(options, coords_size) + 1;
+        }
+        if let Some(comment) = self.rendered_comment(options) {
+            len += comment.len() + 1;
+        }
+        len
+    }
+
+    /// The comment written below the board, if `options` ask for it and there is one.
+    fn rendered_comment(&self, options: &RenderOptions) -> Option<&str> {
+        if !options.show_comment {
+            return None;
+        }
+        self.last_annotation()
+            .map(|annotation| annotation.comment.as_str())
+            .filter(|comment| !comment.is_empty())
+    }
+
+    /// Writes the column letters, each one below its cell of the bottom row.
+    fn write_column_labels<W: std::fmt::Write>(
+        &self,
+        w: &mut W,
+        options: &RenderOptions,
+        margin: usize,
+        width: usize,
+    ) -> std::fmt::Result {
+        write!(w, "{:1$}", "", margin)?;
+        let separator = cell_separator(options).len();
+        for y in 0..self.board_size {
+            let name = column_name(y);
+            w.write_str(&name)?;
+            if y + 1 < self.board_size {
+                let cell = self.visible_cell_width(Coordinates::new(0, y, self.board_size - 1 - y), options, width);
+                write!(w, "{:1$}", "", (cell + separator).saturating_sub(name.len()))?;
+            }
+        }
+        w.write_char('\n')
+    }
+
+    /// Length of the line of column letters, without margin and newline.
+    fn column_labels_len(&self, options: &RenderOptions, width: usize) -> usize {
+        let separator = cell_separator(options).len();
+        (0..self.board_size)
+            .map(|y| {
+                let name = column_name(y).len();
+                if y + 1 < self.board_size {
+                    let cell = self.visible_cell_width(Coordinates::new(0, y, self.board_size - 1 - y), options, width);
+                    name.max(cell + separator)
+                } else {
+                    name
+                }
+            })
+            .sum()
     }
 
     fn get_indent_multiplier(&self, options: &RenderOptions) -> u32 {
@@ -280,41 +453,131 @@ impl GameY {
         }
     }
 
-    fn format_cell(&self, coords: Coordinates, options: &RenderOptions, width: usize) -> String {
+    /// The color code of the stone on `idx`, if `options` color the stones.
+    fn cell_color(&self, idx: u32, options: &RenderOptions) -> Option<u8> {
+        if !options.show_colors {
+            return None;
+        }
+        let player = self.engine.occupant(idx as usize)?;
+        PLAYER_COLORS.get(player.id() as usize).copied()
+    }
+
+    fn write_cell<W: std::fmt::Write>(
+        &self,
+        w: &mut W,
+        coords: Coordinates,
+        options: &RenderOptions,
+        width: usize,
+    ) -> std::fmt::Result {
         let idx = coords.to_index(self.board_size);
-        let player = self.engine.state[idx as usize];
+        let player = self.engine.occupant(idx as usize);
+        let color = self.cell_color(idx, options);
+        if let Some(color) = color {
+            write!(w, "\x1b[{}m", color)?;
+        }
 
-        // 1. Base symbol
-        let mut symbol = match player {
-            Some(p) => format!("{}", p),
-            None => ".".to_string(),
-        };
+        // 1. Base symbol, in reverse video if it is the last move
+        let highlighted = self.highlighted_cell(options) == Some(idx);
+        if highlighted {
+            w.write_str(HIGHLIGHT_ON)?;
+        }
+        let heat = options.heat_map.as_ref().and_then(|values| values.get(idx as usize));
+        match (player, heat) {
+            (Some(p), _) => write!(w, "{}", p)?,
+            (None, Some(value)) => w.write_char(heat_map_char(*value))?,
+            (None, None) => w.write_char('.')?,
+        }
+        if highlighted {
+            w.write_str(HIGHLIGHT_OFF)?;
+        }
 
         // 2. Append metadata (3D Coords / Index)
         if options.show_3d_coords {
-            symbol.push_str(&format!(
+            write!(
+                w,
                 "({:0w$},{:0w$},{:0w$})",
                 coords.x(),
                 coords.y(),
                 coords.z(),
                 w = width
-            ));
+            )?;
         }
         if options.show_idx {
-            let idx = coords.to_index(self.board_size);
-            symbol.push_str(&format!("({}) ", idx));
+            write!(w, "({})", idx)?;
+        }
+
+        // 3. Close the color
+        if color.is_some() {
+            w.write_str("\x1b[0m")?;
+        }
+        Ok(())
+    }
+
+    /// The cell of the last move, if `options` highlight it and it was a placement.
+    fn highlighted_cell(&self, options: &RenderOptions) -> Option<u32> {
+        if !options.highlight_last_move {
+            return None;
+        }
+        match self.last_move()? {
+            Movement::Placement { coords, .. } => Some(coords.to_index(self.board_size)),
+            Movement::Action { .. } => None,
         }
+    }
 
-        // 3. Apply colors
-        if options.show_colors {
-            symbol = apply_player_color(symbol, player);
+    /// Width on screen of the cell written by `write_cell`, which leaves out the colors.
+    fn visible_cell_width(&self, coords: Coordinates, options: &RenderOptions, width: usize) -> usize {
+        let idx = coords.to_index(self.board_size);
+        let mut len = match self.engine.occupant(idx as usize) {
+            Some(p) => digits(p.id()),
+            None => 1,
+        };
+        if options.show_3d_coords {
+            len += 3 * width + 4;
+        }
+        if options.show_idx {
+            len += digits(idx) + 2;
         }
+        len
+    }
 
-        symbol
+    /// Length in bytes of the cell written by `write_cell`.
+    fn cell_len(&self, coords: Coordinates, options: &RenderOptions, width: usize) -> usize {
+        let idx = coords.to_index(self.board_size);
+        let color = match self.cell_color(idx, options) {
+            Some(color) => digits(color as u32) + "\x1b[m\x1b[0m".len(),
+            None => 0,
+        };
+        let highlight = if self.highlighted_cell(options) == Some(idx) {
+            HIGHLIGHT_ON.len() + HIGHLIGHT_OFF.len()
+        } else {
+            0
+        };
+        self.visible_cell_width(coords, options, width) + color + highlight
     }
 }
 
-fn indent(str: &mut String, level: u32) {
+/// Turns the reverse video of the last move on and off.
+const HIGHLIGHT_ON: &str = "\x1b[7m";
+const HIGHLIGHT_OFF: &str = "\x1b[27m";
+
+/// What goes between two cells of a row. The index of a cell is followed by an extra space.
+fn cell_separator(options: &RenderOptions) -> &'static str {
+    if options.show_idx { "    " } else { "   " }
+}
+
+/// Number of decimal digits of `n`.
+fn digits(n: u32) -> usize {
+    n.checked_ilog10().map_or(1, |log| log as usize + 1)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| GameYError::IoError {
+        message: format!("Failed to read file: {}", path.display()),
+        error: e.to_string(),
+    })
+}
+
+pub(crate) fn indent(str: &mut String, level: u32) {
     str.push_str(&" ".repeat(level as usize));
 }
 
@@ -322,125 +585,229 @@ fn indent(str: &mut String, level: u32) {
 impl TryFrom<YEN> for GameY {
     type Error = GameYError;
 
+    /// The `players` symbols of the YEN decide both the number of players
+    /// and the characters accepted in the layout: the symbol at index `i` is
+    /// player `i`. The game keeps them, so converting it back to YEN writes
+    /// the same symbols. Fewer than two symbols fall back to the standard
+    /// `['B', 'R']`. Player names are read from the metadata, if present.
+    ///
+    /// Fails with the first error of [`crate::yen::validate`]; its warnings,
+    /// such as a turn that does not match the stones, are ignored.
+    ///
+    /// A `result` finishes the game even if the layout has no winning chain:
+    /// the loser (the player before the winner) resigns or loses on time.
+    /// Fails with [`GameYError::InvalidYENResult`] if the result names an
+    /// unknown player or reason, claims a connection the layout does not
+    /// show, or disagrees with the winner of the layout.
     fn try_from(game: YEN) -> Result<Self> {
-        let mut ygame = GameY::new(game.size());
-        let rows: Vec<&str> = game.layout().split('/').collect();
-        if rows.len() as u32 != game.size() {
-            return Err(GameYError::InvalidYENLayout {
-                expected: game.size(),
-                found: rows.len() as u32,
-            });
+        if let Some(issue) = crate::yen::validate(&game).into_iter().find(YenIssue::is_error) {
+            return Err(issue.into());
         }
-        for (row, row_str) in rows.iter().enumerate() {
-            let cells: Vec<char> = row_str.chars().collect();
-            if cells.len() as u32 != row as u32 + 1 {
-                return Err(GameYError::InvalidYENLayoutLine {
-                    expected: row as u32 + 1,
-                    found: cells.len() as u32,
-                    line: row as u32,
-                });
-            }
-            for (col, cell) in cells.iter().enumerate() {
+        let symbols = game.effective_players();
+        let mut ygame = GameY::with_players(game.size(), symbols.len() as u32);
+        for (row, row_str) in crate::yen::layout_rows(game.layout()).into_iter().enumerate() {
+            for (col, cell) in row_str.chars().enumerate() {
+                let Some(player) = symbols.iter().position(|symbol| *symbol == cell) else {
+                    continue;
+                };
                 let x = game.size() - 1 - (row as u32);
                 let y = col as u32;
                 let z = game.size() - 1 - x - y;
-                let coords = Coordinates::new(x, y, z);
-                match cell {
-                    'B' => {
-                        ygame.add_move(Movement::Placement {
-                            player: PlayerId::new(0),
-                            coords,
-                        })?;
-                    }
-                    'R' => {
-                        ygame.add_move(Movement::Placement {
-                            player: PlayerId::new(1),
-                            coords,
-                        })?;
-                    }
-                    '.' => {}
-                    _ => {
-                        return Err(GameYError::InvalidCharInLayout {
-                            char: *cell,
-                            row,
-                            col,
-                        });
-                    }
-                }
+                ygame.apply_move(Movement::Placement {
+                    player: PlayerId::new(player as u32),
+                    coords: Coordinates::try_new(x, y, z, game.size())?,
+                })?;
             }
         }
-        Ok(ygame)
+        // The stones are placed in layout order, so the turn comes from the YEN
+        if !ygame.check_game_over() && game.turn() < ygame.num_players {
+            ygame.status = GameStatus::Ongoing {
+                next_player: PlayerId::new(game.turn()),
+            };
+        }
+        if let Some(result) = game.result() {
+            ygame.restore_result(result, &symbols)?;
+        }
+        if let Some(metadata) = game.metadata() {
+            ygame = ygame.with_player_info(metadata.players.clone());
+        }
+        ygame.with_player_symbols(symbols)
     }
 }
 
-// Converts a GameY instance into its YEN representation.
-impl From<&GameY> for YEN {
-    fn from(game: &GameY) -> Self {
-        let size = game.board_size;
-        let turn = match game.status {
-            GameStatus::Finished { winner } => other_player(winner).id() as u32,
-            GameStatus::Ongoing { next_player } => next_player.id(),
+impl GameY {
+    /// Finishes a game rebuilt from a YEN layout as its `result` says.
+    fn restore_result(&mut self, result: &str, symbols: &[char]) -> Result<()> {
+        let invalid = |message: &str| GameYError::InvalidYENResult {
+            result: result.to_string(),
+            message: message.to_string(),
         };
-        let mut layout = String::new();
-        let total_cells = (game.board_size * (game.board_size + 1)) / 2;
-        let players = vec!['B', 'R'];
-        for idx in 0..total_cells {
-            let player = game.engine.state[idx as usize];
-            let cell_char = match player {
-                Some(p) if p.id() == 0 => 'B', // player 0
-                Some(p) if p.id() == 1 => 'R', // player 1
-                _ => '.',                      // empty cell
-            };
-            layout.push(cell_char);
+        let (symbol, reason) = result
+            .split_once('+')
+            .ok_or_else(|| invalid("expected the winner's symbol, '+' and a reason"))?;
+        let mut chars = symbol.chars();
+        let winner = match (chars.next(), chars.next()) {
+            (Some(symbol), None) => symbols.iter().position(|s| *s == symbol),
+            _ => None,
+        }
+        .map(|p| PlayerId::new(p as u32))
+        .ok_or_else(|| invalid(&format!("'{}' is not a player symbol", symbol)))?;
 
-            // Check if we need a separator '/'
-            // This is tricky without coords, so let's compute coords
-            let coords = Coordinates::from_index(idx, game.board_size);
-            if coords.z() == 0 && coords.x() > 0 {
-                layout.push('/'); // separate rows with '/'
+        if let GameStatus::Finished { winner: actual, .. } = self.status {
+            return if actual == winner {
+                Ok(())
+            } else {
+                Err(invalid(&format!("the layout is won by player {}", actual)))
+            };
+        }
+        let loser = PlayerId::new((winner.id() + self.num_players - 1) % self.num_players);
+        match reason {
+            "resign" => self.apply_move(Movement::Action {
+                player: loser,
+                action: GameAction::Resign,
+            }),
+            "time" => {
+                self.lose_on_time(loser);
+                Ok(())
             }
+            "forfeit" => {
+                self.forfeit(loser);
+                Ok(())
+            }
+            "connect" => Err(invalid("the layout has no winning chain")),
+            _ => Err(invalid(&format!("unknown reason '{}'", reason))),
         }
-        YEN::new(size, turn, players, layout)
     }
 }
 
-fn other_player(player: PlayerId) -> PlayerId {
-    // Assuming two players with IDs 0 and 1
-    if player.id() == 0 {
-        PlayerId::new(1)
-    } else {
-        PlayerId::new(0)
+impl GameY {
+    /// Converts the game into its YEN representation.
+    ///
+    /// # Errors
+    /// Returns [`GameYError::InvalidGameState`] if the board does not hold one
+    /// cell per position of a triangle of the board size, or a cell holds a
+    /// player without a symbol.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::GameY;
+    ///
+    /// let yen = GameY::new(3).to_yen().unwrap();
+    /// assert_eq!(yen.layout(), "./../...");
+    /// ```
+    pub fn to_yen(&self) -> Result<YEN> {
+        let expected = (self.board_size * (self.board_size + 1) / 2) as usize;
+        if self.total_cells() as usize != expected {
+            return Err(GameYError::InvalidGameState {
+                message: format!(
+                    "a board of size {} has {} cells, found {}",
+                    self.board_size,
+                    expected,
+                    self.total_cells()
+                ),
+            });
+        }
+        self.snapshot().to_yen()
     }
 }
 
-fn apply_player_color(symbol: String, player: Option<PlayerId>) -> String {
-    match player {
-        Some(p) if p.id() == 0 => format!("\x1b[34m{}\x1b[0m", symbol), // Blue
-        Some(p) if p.id() == 1 => format!("\x1b[31m{}\x1b[0m", symbol), // Red
-        _ => symbol,
+// Converts a GameY instance into its YEN representation.
+impl From<&GameY> for YEN {
+    /// Panics if the board is inconsistent, which the moves of a game never
+    /// cause; use [`GameY::to_yen`] to get an error instead.
+    fn from(game: &GameY) -> Self {
+        game.to_yen().expect("the board of a game is consistent")
+    }
+}
+
+/// ANSI colors of each player, matching [`PLAYER_SYMBOLS`]:
+/// blue, red, green, yellow, magenta and cyan.
+const PLAYER_COLORS: [u8; 6] = [34, 31, 32, 33, 35, 36];
+
+pub(crate) fn apply_player_color(symbol: String, player: Option<PlayerId>) -> String {
+    match player.and_then(|p| PLAYER_COLORS.get(p.id() as usize)) {
+        Some(color) => format!("\x1b[{}m{}\x1b[0m", color, symbol),
+        None => symbol,
+    }
+}
+
+/// How a finished game was won.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WinReason {
+    /// The winner connected the sides of the board.
+    #[default]
+    Connection,
+    /// The loser resigned.
+    Resignation,
+    /// The loser ran out of time.
+    Timeout,
+    /// The loser forfeited, e.g. by answering with an illegal move.
+    Forfeit,
+}
+
+impl WinReason {
+    /// Returns the reason as written in a YEN `result`, e.g. `"resign"` in `"B+resign"`.
+    pub fn notation(&self) -> &'static str {
+        match self {
+            WinReason::Connection => "connect",
+            WinReason::Resignation => "resign",
+            WinReason::Timeout => "time",
+            WinReason::Forfeit => "forfeit",
+        }
     }
 }
 
 /// Represents the current status of a game.
-#[derive(Debug, Clone)]
+///
+/// Serialized with a `state` tag, e.g.
+/// `{"state": "finished", "winner": 0, "reason": "connection"}`. A missing
+/// `reason` is read as a connection, as written before reasons were kept.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum GameStatus {
     /// The game is still in progress with the specified player to move next.
     Ongoing { next_player: PlayerId },
     /// The game has ended with a winner.
-    Finished { winner: PlayerId },
+    Finished {
+        winner: PlayerId,
+        #[serde(default)]
+        reason: WinReason,
+    },
+}
+
+impl GameStatus {
+    /// Returns the winner, if the game is over.
+    pub fn winner(&self) -> Option<PlayerId> {
+        match self {
+            GameStatus::Finished { winner, .. } => Some(*winner),
+            GameStatus::Ongoing { .. } => None,
+        }
+    }
+
+    /// Returns how the game was won, if it is over.
+    pub fn reason(&self) -> Option<WinReason> {
+        match self {
+            GameStatus::Finished { reason, .. } => Some(*reason),
+            GameStatus::Ongoing { .. } => None,
+        }
+    }
+
+    /// Returns `true` if the game has ended.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, GameStatus::Finished { .. })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{GameObserver, PlayerInfo};
     use std::collections::HashSet;
 
-    #[test]
-    fn test_other_player() {
-        assert_eq!(other_player(PlayerId::new(0)), PlayerId::new(1));
-        assert_eq!(other_player(PlayerId::new(1)), PlayerId::new(0));
-    }
-
     #[test]
     fn test_game_initialization() {
         let game = GameY::new(7);
@@ -544,7 +911,7 @@ mod tests {
         }
 
         match game.status {
-            GameStatus::Finished { winner } => {
+            GameStatus::Finished { winner, .. } => {
                 assert_eq!(winner, PlayerId::new(0));
             }
             _ => panic!("Game should be finished with a winner"),
@@ -594,7 +961,7 @@ mod tests {
         let yen: YEN = serde_json::from_str(yen_str).unwrap();
         let game = GameY::try_from(yen).unwrap();
         match game.status {
-            GameStatus::Finished { winner } => {
+            GameStatus::Finished { winner, .. } => {
                 assert_eq!(winner, PlayerId::new(0));
             }
             _ => panic!("Game should be finished with a winner"),
@@ -613,7 +980,7 @@ mod tests {
         let yen: YEN = serde_json::from_str(yen_str).unwrap();
         let game = GameY::try_from(yen).unwrap();
         match game.status {
-            GameStatus::Finished { winner } => {
+            GameStatus::Finished { winner, .. } => {
                 assert_eq!(winner, PlayerId::new(0));
             }
             other => panic!("Game should be finished with a winner. Found: {:?}", other),
@@ -632,7 +999,7 @@ mod tests {
         let yen: YEN = serde_json::from_str(yen_str).unwrap();
         let game = GameY::try_from(yen).unwrap();
         match game.status {
-            GameStatus::Finished { winner } => {
+            GameStatus::Finished { winner, .. } => {
                 assert_eq!(winner, PlayerId::new(0));
             }
             other => panic!("Game should be finished with a winner. Found {:?}", other),
@@ -657,4 +1024,479 @@ mod tests {
             _ => panic!("Game should be ongoing"),
         }
     }
+
+    #[test]
+    fn test_three_player_game() {
+        let mut game = GameY::with_players(4, 3);
+        assert_eq!(game.num_players(), 3);
+        let moves = [
+            (0, Coordinates::new(1, 0, 2)),
+            // Corners do not count as sides, so this does not win for player 1
+            (1, Coordinates::new(3, 0, 0)),
+            (2, Coordinates::new(1, 1, 1)),
+        ];
+        for (player, coords) in moves {
+            assert_eq!(game.next_player(), Some(PlayerId::new(player)));
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords,
+            })
+            .unwrap();
+        }
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+
+        let yen: YEN = (&game).into();
+        assert_eq!(yen.players(), &['B', 'R', 'G']);
+        assert_eq!(yen.layout(), "R/../BG./....");
+        let loaded = GameY::try_from(yen).unwrap();
+        assert_eq!(loaded.num_players(), 3);
+
+        // Player 0 connects its sides x = 0 and y = 0
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(0, 1, 2),
+        })
+        .unwrap();
+        match game.status {
+            GameStatus::Finished { winner, .. } => assert_eq!(winner, PlayerId::new(0)),
+            other => panic!("Player 0 should have won. Found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_default_player_info() {
+        let names: Vec<String> = GameY::new(3).players().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["Player 0", "Player 1"]);
+        let yen: YEN = (&GameY::new(3)).into();
+        assert!(yen.metadata().is_none());
+    }
+
+    #[test]
+    fn test_player_info_round_trips_through_saved_game() {
+        use crate::PlayerKind;
+
+        let mut game = GameY::new(3).with_player_info(vec![
+            PlayerInfo::new(PlayerId::new(0), "Alice", PlayerKind::Human),
+            PlayerInfo::new(
+                PlayerId::new(1),
+                "Mcts",
+                PlayerKind::Bot("mcts_bot".to_string()),
+            ),
+        ]);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(1, 1, 0),
+        })
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.yen");
+        game.save_to_file(&path).unwrap();
+        let loaded = GameY::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded.players(), game.players());
+        assert_eq!(loaded.players()[1].kind, PlayerKind::Bot("mcts_bot".to_string()));
+        let yen: YEN = (&loaded).into();
+        assert_eq!(yen.layout(), "./.B/...");
+    }
+
+    #[test]
+    fn test_player_info_outside_the_game_is_ignored() {
+        let game = GameY::new(3).with_player_info(vec![PlayerInfo::default_for(PlayerId::new(5))]);
+        assert_eq!(game.players().len(), 2);
+        let yen: YEN = (&game).into();
+        assert!(yen.metadata().is_none());
+    }
+
+    /// Records every callback as a short string.
+    struct RecordingObserver(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl GameObserver for RecordingObserver {
+        fn on_move(&self, game: &GameY, _movement: &Movement, status: &GameStatus) {
+            let event = match status {
+                GameStatus::Ongoing { .. } => format!("move {}", game.history().len()),
+                GameStatus::Finished { .. } => format!("winning move {}", game.history().len()),
+            };
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn on_finish(&self, _game: &GameY, winner: PlayerId) {
+            self.0.lock().unwrap().push(format!("finish {}", winner));
+        }
+
+        fn on_reset(&self, game: &GameY) {
+            self.0.lock().unwrap().push(format!("reset {}", game.history().len()));
+        }
+    }
+
+    #[test]
+    fn test_observer_callback_order() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut game = GameY::new(2);
+        game.subscribe(Box::new(RecordingObserver(std::sync::Arc::clone(&events))));
+        for (player, idx) in [(0, 0), (1, 1), (0, 2)] {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords: Coordinates::from_index(idx, 2),
+            })
+            .unwrap();
+        }
+        // A clone does not notify the observers of the original game
+        let mut copy = game.clone();
+        copy.reset();
+        game.reset();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["move 1", "move 2", "winning move 3", "finish 0", "reset 0"]
+        );
+    }
+
+    #[test]
+    fn test_observer_is_notified_of_loss_on_time() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut game = GameY::new(3);
+        game.subscribe(Box::new(RecordingObserver(std::sync::Arc::clone(&events))));
+        game.lose_on_time(PlayerId::new(0));
+        game.lose_on_time(PlayerId::new(1));
+        assert_eq!(*events.lock().unwrap(), vec!["finish 1"]);
+    }
+
+    #[test]
+    fn test_each_way_of_winning_sets_its_reason() {
+        let place = |player: u32, coords: Coordinates| Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        };
+        let mut connected = GameY::new(1);
+        connected.add_move(place(0, Coordinates::new(0, 0, 0))).unwrap();
+
+        let mut resigned = GameY::new(3);
+        resigned
+            .add_move(Movement::Action {
+                player: PlayerId::new(0),
+                action: GameAction::Resign,
+            })
+            .unwrap();
+
+        let mut timed_out = GameY::new(3);
+        timed_out.lose_on_time(PlayerId::new(0));
+
+        let mut forfeited = GameY::new(3);
+        forfeited.forfeit(PlayerId::new(0));
+        // The first reason sticks
+        forfeited.lose_on_time(PlayerId::new(1));
+
+        for (game, winner, reason, name) in [
+            (&connected, 0, WinReason::Connection, "connection"),
+            (&resigned, 1, WinReason::Resignation, "resignation"),
+            (&timed_out, 1, WinReason::Timeout, "timeout"),
+            (&forfeited, 1, WinReason::Forfeit, "forfeit"),
+        ] {
+            let status = game.status();
+            assert!(status.is_finished());
+            assert_eq!(status.winner(), Some(PlayerId::new(winner)));
+            assert_eq!(status.reason(), Some(reason));
+            assert_eq!(serde_json::to_value(status).unwrap()["reason"], name);
+        }
+        assert_eq!(forfeited.forfeited(), Some(PlayerId::new(0)));
+        assert_eq!(forfeited.lost_on_time(), None);
+
+        let ongoing = GameY::new(3);
+        assert!(!ongoing.status().is_finished());
+        assert_eq!(ongoing.status().winner(), None);
+        assert_eq!(ongoing.status().reason(), None);
+    }
+
+    #[test]
+    fn test_ply_counts_swaps_and_resignations() {
+        let mut game = GameY::new(4);
+        assert_eq!(game.ply(), 0);
+        assert_eq!(game.side_to_move(), Some(PlayerId::new(0)));
+        assert!(game.is_players_turn(PlayerId::new(0)));
+        assert!(!game.is_players_turn(PlayerId::new(1)));
+
+        let moves = [
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(1, 1, 1),
+            },
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            },
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(3, 0, 0),
+            },
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Resign,
+            },
+        ];
+        let movers = [1, 0, 1];
+        for (ply, movement) in moves.into_iter().enumerate() {
+            game.add_move(movement).unwrap();
+            assert_eq!(game.ply(), ply + 1);
+            if let Some(mover) = movers.get(ply) {
+                assert_eq!(game.side_to_move(), Some(PlayerId::new(*mover)));
+                assert!(game.is_players_turn(PlayerId::new(*mover)));
+            }
+        }
+        assert_eq!(game.ply(), 4);
+        assert_eq!(game.snapshot().ply(), 4);
+        assert_eq!(game.side_to_move(), None);
+        assert!(!game.is_players_turn(PlayerId::new(0)));
+        assert!(!game.is_players_turn(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_finished_status_without_reason_reads_as_connection() {
+        let status: GameStatus = serde_json::from_str(r#"{"state": "finished", "winner": 1}"#).unwrap();
+        assert_eq!(
+            status,
+            GameStatus::Finished {
+                winner: PlayerId::new(1),
+                reason: WinReason::Connection
+            }
+        );
+    }
+
+    #[test]
+    fn test_yen_symbols_outside_players_are_rejected() {
+        let yen = YEN::new(2, 0, vec!['X', 'O'], "X/.B".to_string());
+        assert!(matches!(
+            GameY::try_from(yen),
+            Err(GameYError::InvalidCharInLayout { char: 'B', .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_yen_rejects_a_state_of_the_wrong_length() {
+        let mut game = GameY::new(3);
+        game.engine.occupants_mut().push(None);
+        assert!(matches!(game.to_yen(), Err(GameYError::InvalidGameState { .. })));
+        game.engine.occupants_mut().truncate(4);
+        assert!(matches!(game.to_yen(), Err(GameYError::InvalidGameState { .. })));
+    }
+
+    #[test]
+    fn test_to_yen_rejects_an_occupant_without_a_symbol() {
+        let mut game = GameY::new(3);
+        game.engine.occupants_mut()[4] = Some(PlayerId::new(2));
+        match game.to_yen() {
+            Err(GameYError::InvalidGameState { message }) => {
+                assert_eq!(message, "cell 4 holds unknown player 2")
+            }
+            other => panic!("expected an invalid state, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_to_yen_matches_the_from_conversion() {
+        let game = game_with_stone(4, Coordinates::new(1, 1, 1));
+        let yen = game.to_yen().unwrap();
+        assert_eq!(yen.layout(), "./../.B./....");
+        assert_eq!(yen, YEN::from(&game));
+    }
+
+    fn game_with_stone(size: u32, coords: Coordinates) -> GameY {
+        let mut game = GameY::new(size);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_canonical_key_is_equal_for_symmetric_openings() {
+        // The six images of (3, 1, 0) under the board symmetries
+        let spots = [
+            Coordinates::new(3, 1, 0),
+            Coordinates::new(3, 0, 1),
+            Coordinates::new(1, 3, 0),
+            Coordinates::new(0, 3, 1),
+            Coordinates::new(1, 0, 3),
+            Coordinates::new(0, 1, 3),
+        ];
+        let keys: HashSet<u64> = spots
+            .iter()
+            .map(|coords| game_with_stone(5, *coords).canonical_key())
+            .collect();
+        assert_eq!(keys.len(), 1);
+        assert_ne!(
+            keys.into_iter().next(),
+            Some(game_with_stone(5, Coordinates::new(2, 1, 1)).canonical_key())
+        );
+    }
+
+    #[test]
+    fn test_canonical_key_depends_on_size_and_colour() {
+        assert_ne!(GameY::new(3).canonical_key(), GameY::new(4).canonical_key());
+
+        let mut red = GameY::new(3);
+        red.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        red.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(2, 0, 0),
+        })
+        .unwrap();
+        let blue = game_with_stone(3, Coordinates::new(2, 0, 0));
+        assert_ne!(red.canonical_key(), blue.canonical_key());
+    }
+
+    #[test]
+    fn test_position_hash_follows_moves() {
+        let empty = GameY::new(4).position_hash();
+        let a = game_with_stone(4, Coordinates::new(3, 0, 0));
+        let b = game_with_stone(4, Coordinates::new(0, 3, 0));
+        assert_ne!(a.position_hash(), empty);
+        assert_ne!(a.position_hash(), b.position_hash());
+        assert_eq!(
+            a.position_hash(),
+            game_with_stone(4, Coordinates::new(3, 0, 0)).position_hash()
+        );
+    }
+
+    #[test]
+    fn test_distinct_positions_have_distinct_keys() {
+        use rand::seq::SliceRandom;
+
+        let mut rng = rand::rng();
+        let mut seen: std::collections::HashMap<Vec<u8>, u64> = std::collections::HashMap::new();
+        for _ in 0..300 {
+            let mut game = GameY::new(4);
+            let mut cells: Vec<u32> = (0..10).collect();
+            cells.shuffle(&mut rng);
+            let stones = rand::random_range(0..6);
+            for (turn, idx) in cells.into_iter().take(stones).enumerate() {
+                game.add_move(Movement::Placement {
+                    player: PlayerId::new(turn as u32 % 2),
+                    coords: Coordinates::from_index(idx, 4),
+                })
+                .unwrap();
+            }
+            let canonical = game
+                .engine
+                .topology()
+                .symmetries()
+                .iter()
+                .map(|perm| game.cell_codes(perm))
+                .min()
+                .unwrap();
+            let key = game.canonical_key();
+            for (other, other_key) in &seen {
+                assert_eq!(*other == canonical, *other_key == key);
+            }
+            seen.insert(canonical, key);
+        }
+    }
+
+    #[test]
+    fn test_try_new_checks_the_board_size() {
+        for size in [0, MAX_BOARD_SIZE + 1, u32::MAX] {
+            assert!(matches!(
+                GameY::try_new(size),
+                Err(GameYError::InvalidBoardSize { size: s, min: 1, max: MAX_BOARD_SIZE }) if s == size
+            ));
+        }
+        assert_eq!(GameY::try_new(1).unwrap().total_cells(), 1);
+        assert_eq!(GameY::try_new(MAX_BOARD_SIZE).unwrap().total_cells(), 5050);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid board size 0")]
+    fn test_new_panics_for_a_board_without_cells() {
+        GameY::new(0);
+    }
+
+    #[test]
+    fn test_yen_with_a_board_too_large_is_rejected() {
+        let yen = YEN::new(MAX_BOARD_SIZE + 1, 0, vec!['B', 'R'], ".".to_string());
+        assert!(matches!(GameY::try_from(yen), Err(GameYError::InvalidBoardSize { .. })));
+    }
+
+    #[test]
+    fn test_placement_off_the_board_is_rejected() {
+        let mut game = GameY::new(3);
+        for coords in [Coordinates::new(3, 0, 0), Coordinates::new(1, 1, 1)] {
+            let placement = Movement::Placement {
+                player: PlayerId::new(0),
+                coords,
+            };
+            assert!(game.add_move(placement).is_err(), "{}", coords);
+        }
+        assert!(game.history().is_empty());
+        assert_eq!(game.available_cells().len(), 6);
+    }
+
+    #[test]
+    fn test_games_compare_by_position() {
+        let play = |cells: &[u32]| {
+            let mut game = GameY::new(4);
+            for (turn, idx) in cells.iter().enumerate() {
+                game.add_move(Movement::Placement {
+                    player: PlayerId::new(turn as u32 % 2),
+                    coords: Coordinates::from_index(*idx, 4),
+                })
+                .unwrap();
+            }
+            game
+        };
+        let a = play(&[0, 5, 3, 9]);
+        let b = play(&[3, 9, 0, 5]);
+        assert_eq!(a, b);
+        assert!(!a.same_history(&b));
+        assert!(a.same_history(&a.clone()));
+        let hashes: HashSet<GameY> = [a.clone(), b].into_iter().collect();
+        assert_eq!(hashes.len(), 1);
+
+        assert_ne!(a, play(&[0, 5, 3, 8]));
+        assert_ne!(a, play(&[0, 5, 3]));
+        assert_ne!(GameY::new(4), GameY::new(5));
+        assert_ne!(GameY::new(4), GameY::with_players(4, 3));
+    }
+
+    #[test]
+    fn test_a_corner_stone_touches_both_of_its_sides() {
+        let mut game = GameY::new(5);
+        let blue = PlayerId::new(0);
+        assert_eq!(game.sides_touched(blue), 0);
+        assert!(game.sides_touched_named(blue).is_empty());
+
+        game.add_move(Movement::Placement {
+            player: blue,
+            coords: Coordinates::new(4, 0, 0),
+        })
+        .unwrap();
+        assert_eq!(game.sides_touched_named(blue), ["B", "C"]);
+        assert!(game.sides_touched_named(PlayerId::new(1)).is_empty());
+    }
+
+    #[test]
+    fn test_connected_groups_report_the_union_of_their_sides() {
+        let mut game = GameY::new(5);
+        let red = PlayerId::new(1);
+        for coords in [Coordinates::new(0, 2, 2), Coordinates::new(2, 0, 2)] {
+            game.add_move(Movement::Placement { player: red, coords }).unwrap();
+        }
+        assert_eq!(game.engine().groups(red).len(), 2);
+        assert_eq!(game.sides_touched_named(red), ["A", "B"]);
+
+        game.add_move(Movement::Placement {
+            player: red,
+            coords: Coordinates::new(1, 1, 2),
+        })
+        .unwrap();
+        assert_eq!(game.engine().groups(red).len(), 1);
+        let regions = game.engine().regions_of_group(Coordinates::new(1, 1, 2).to_index(5) as CellIndex);
+        assert_eq!(Some(game.sides_touched(red)), regions);
+        assert_eq!(game.sides_touched_named(red), ["A", "B"]);
+    }
 }
@@ -0,0 +1,296 @@
+//! The game of Hex on the generic engine.
+//!
+//! Hex is played on a rhombus of `size × size` hexagonal cells. The first
+//! player connects the top and bottom edges, the second the left and right
+//! ones, and the board cannot fill up without one of them winning.
+//! [`GameHex`] is a [`ConnectionGame`] on a [`HexTopology`], like
+//! [`GameY`](crate::GameY) on the triangle; [`crate::HEN`] is its exchange format.
+
+use crate::core::game::{apply_player_color, indent};
+use crate::{
+    BoardTopology, Cell, CellIndex, ConnectionGame, GameAction, GameMove, GameYError, HexTopology, MoveEffect,
+    PlayerId, RenderOptions, Result,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+
+/// A cell of a Hex board: its row from the top and its column from the left, from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HexCell {
+    /// Row of the cell, 0 is the top edge.
+    pub row: u32,
+    /// Column of the cell, 0 is the left edge.
+    pub col: u32,
+}
+
+impl HexCell {
+    /// Creates a cell from its row and column.
+    pub fn new(row: u32, col: u32) -> Self {
+        Self { row, col }
+    }
+
+    /// Returns the cell with index `index` on a board of `size`, counting row by row.
+    pub fn from_index(index: u32, size: u32) -> Self {
+        Self::new(index / size, index % size)
+    }
+
+    /// Returns the index of the cell on a board of `size`, counting row by row.
+    pub fn to_index(&self, size: u32) -> u32 {
+        self.row * size + self.col
+    }
+}
+
+/// Written as the usual Hex name: the column letter and the row number, like `c3`.
+///
+/// Columns after `z` continue with `aa`, `ab`...
+impl Display for HexCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut column = self.col + 1;
+        let mut letters = Vec::new();
+        while column > 0 {
+            column -= 1;
+            letters.push((b'a' + (column % 26) as u8) as char);
+            column /= 26;
+        }
+        write!(f, "{}{}", letters.iter().rev().collect::<String>(), self.row + 1)
+    }
+}
+
+/// A move of a Hex game, like [`crate::Movement`] for Y.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HexMove {
+    /// A stone placement.
+    Placement {
+        /// The player making the placement.
+        player: PlayerId,
+        /// The cell where the stone is placed.
+        cell: HexCell,
+    },
+    /// A swap or a resignation.
+    Action {
+        /// The player performing the action.
+        player: PlayerId,
+        /// The action being performed.
+        action: GameAction,
+    },
+}
+
+impl Display for HexMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HexMove::Placement { player, cell } => write!(f, "Player {} places at {}", player, cell),
+            HexMove::Action { player, action } => write!(f, "Player {} performs action {}", player, action),
+        }
+    }
+}
+
+/// Placements off the board fail with `GameYError::CoordOutOfRange`, and
+/// placements on occupied cells with `GameYError::OccupiedCell`.
+impl GameMove for HexMove {
+    fn player(&self) -> PlayerId {
+        match self {
+            HexMove::Placement { player, .. } | HexMove::Action { player, .. } => *player,
+        }
+    }
+
+    fn kind(&self, board_size: u32) -> Result<MoveEffect<'_>> {
+        match self {
+            HexMove::Placement { cell, .. } => {
+                for (id_coord, coord) in [('r', cell.row), ('c', cell.col)] {
+                    if coord >= board_size {
+                        return Err(GameYError::CoordOutOfRange {
+                            id_coord,
+                            coord,
+                            board_size,
+                        });
+                    }
+                }
+                Ok(MoveEffect::Placement(cell.to_index(board_size) as usize))
+            }
+            HexMove::Action { action, .. } => Ok(MoveEffect::Action(action)),
+        }
+    }
+
+    fn from_effect(player: PlayerId, effect: MoveEffect<'_>, board_size: u32) -> Self {
+        match effect {
+            MoveEffect::Placement(idx) => HexMove::Placement {
+                player,
+                cell: HexCell::from_index(idx as u32, board_size),
+            },
+            MoveEffect::Action(action) => HexMove::Action {
+                player,
+                action: action.clone(),
+            },
+        }
+    }
+
+    fn occupied_error(player: PlayerId, cell: CellIndex, board_size: u32) -> GameYError {
+        GameYError::OccupiedCell {
+            cell: HexCell::from_index(cell as u32, board_size).to_string(),
+            player,
+        }
+    }
+
+    fn game_over_error(self) -> GameYError {
+        GameYError::MoveAfterGameOver {
+            movement: self.to_string(),
+        }
+    }
+}
+
+/// The state of a two-player game of Hex.
+///
+/// Moves, turns, observers and the rest of the rules are those of every
+/// [`ConnectionGame`]; adding a move after the game is over fails with
+/// `GameYError::MoveAfterGameOver`.
+pub type GameHex = ConnectionGame<HexTopology, HexMove>;
+
+impl GameHex {
+    /// Creates an empty game on a board of `board_size × board_size` cells.
+    pub fn new(board_size: u32) -> Self {
+        Self::from_topology(board_size, HexTopology::new(board_size), 2)
+    }
+
+    /// Returns what is on `cell`. Cells off the board are empty.
+    pub fn cell(&self, cell: HexCell) -> Cell {
+        if cell.row >= self.board_size || cell.col >= self.board_size {
+            return Cell::Empty;
+        }
+        match self.engine.occupant(cell.to_index(self.board_size) as usize) {
+            Some(player) => Cell::Occupied(player),
+            None => Cell::Empty,
+        }
+    }
+
+    /// Renders the board as text, each row shifted half a cell from the one above.
+    ///
+    /// `show_idx` adds the index of every cell and `show_colors` colors the
+    /// stones; barycentric coordinates do not exist on a Hex board.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let mut result = String::new();
+        let _ = writeln!(result, "--- Game of Hex (Size {}) ---", self.board_size);
+        let shift = if options.show_idx { 4 } else { 2 };
+        for row in 0..self.board_size {
+            indent(&mut result, row * shift);
+            for col in 0..self.board_size {
+                let idx = HexCell::new(row, col).to_index(self.board_size);
+                let player = self.engine.occupant(idx as usize);
+                let mut symbol = match player {
+                    Some(p) => format!("{}", p),
+                    None => ".".to_string(),
+                };
+                if options.show_idx {
+                    symbol.push_str(&format!("({}) ", idx));
+                }
+                if options.show_colors {
+                    symbol = apply_player_color(symbol, player);
+                }
+                let _ = write!(result, "{}   ", symbol);
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Returns true if `player` connects the two edges they must join.
+    pub fn connects(&self, player: PlayerId) -> bool {
+        let target = self.engine.topology().winning_mask_for(player);
+        self.engine.groups(player).iter().any(|regions| regions & target == target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(game: &mut GameHex, player: u32, row: u32, col: u32) {
+        game.add_move(HexMove::Placement {
+            player: PlayerId::new(player),
+            cell: HexCell::new(row, col),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_first_player_connects_top_and_bottom() {
+        let mut game = GameHex::new(3);
+        place(&mut game, 0, 0, 1);
+        place(&mut game, 1, 0, 0);
+        place(&mut game, 0, 1, 1);
+        place(&mut game, 1, 1, 0);
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        place(&mut game, 0, 2, 0);
+        assert_eq!(game.status().winner(), Some(PlayerId::new(0)));
+        assert!(game.connects(PlayerId::new(0)));
+        assert!(!game.connects(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_second_player_connects_left_and_right() {
+        let mut game = GameHex::new(3);
+        place(&mut game, 0, 0, 0);
+        place(&mut game, 1, 1, 0);
+        place(&mut game, 0, 0, 1);
+        place(&mut game, 1, 1, 1);
+        place(&mut game, 0, 0, 2);
+        // The top row touches both side edges, but they are not the first player's
+        assert!(!game.check_game_over());
+        place(&mut game, 1, 1, 2);
+        assert_eq!(game.status().winner(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_illegal_moves() {
+        let mut game = GameHex::new(3);
+        place(&mut game, 0, 1, 1);
+        let occupied = game.add_move(HexMove::Placement {
+            player: PlayerId::new(1),
+            cell: HexCell::new(1, 1),
+        });
+        assert!(matches!(occupied, Err(GameYError::OccupiedCell { ref cell, .. }) if cell == "b2"));
+        let outside = game.add_move(HexMove::Placement {
+            player: PlayerId::new(1),
+            cell: HexCell::new(0, 3),
+        });
+        assert!(matches!(outside, Err(GameYError::CoordOutOfRange { id_coord: 'c', .. })));
+        game.add_move(HexMove::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        assert_eq!(game.status().winner(), Some(PlayerId::new(0)));
+        assert!(matches!(
+            game.add_move(HexMove::Placement { player: PlayerId::new(1), cell: HexCell::new(0, 0) }),
+            Err(GameYError::MoveAfterGameOver { .. })
+        ));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut game = GameHex::new(2);
+        place(&mut game, 0, 0, 1);
+        let options = RenderOptions {
+            show_3d_coords: false,
+            show_idx: false,
+            show_colors: false,
+            show_comment: false,
+            show_labels: false,
+            heat_map: None,
+            highlight_last_move: false,
+        };
+        assert_eq!(
+            game.render(&options),
+            "--- Game of Hex (Size 2) ---\n.   0   \n  .   .   \n"
+        );
+    }
+
+    #[test]
+    fn test_cell_names() {
+        assert_eq!(HexCell::new(0, 0).to_string(), "a1");
+        assert_eq!(HexCell::new(10, 2).to_string(), "c11");
+        assert_eq!(HexCell::new(0, 26).to_string(), "aa1");
+        assert_eq!(HexCell::from_index(7, 3), HexCell::new(2, 1));
+        assert_eq!(HexCell::new(2, 1).to_index(3), 7);
+    }
+}
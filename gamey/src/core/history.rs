@@ -0,0 +1,224 @@
+//! Compact storage of the moves of a game.
+//!
+//! Servers keep thousands of finished games in memory for spectators, and a
+//! [`Movement`](crate::Movement) takes several words. [`CompactHistory`] keeps
+//! each move in a single `u32` instead: a placement is its player and cell
+//! index, and swaps and resignations are escaped cell codes. The moves are
+//! only rebuilt when they are read, see [`crate::ConnectionGame::history`].
+
+use crate::{GameAction, GameMove, MoveEffect, PlayerId};
+
+// Una palabra compacta guarda el jugador en los bits 16..24 y la celda en los
+// bits 0..16. Los códigos más altos de celda son las acciones.
+const PLAYER_SHIFT: u32 = 16;
+const CELL_MASK: u32 = 0xFFFF;
+const COMPACT_SWAP: u32 = 0xFFFE;
+const COMPACT_RESIGN: u32 = 0xFFFF;
+
+// Los movimientos que no caben (jugador > 255 o celda >= 0xFFFE) se guardan
+// como esta marca seguida de dos palabras: el jugador y el código completo.
+const ESCAPE: u32 = u32::MAX;
+const WIDE_SWAP: u32 = u32::MAX - 1;
+const WIDE_RESIGN: u32 = u32::MAX;
+
+/// The moves of a game packed in one `u32` each, or three for the rare moves
+/// of players above 255 or cells above 65533.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CompactHistory {
+    words: Vec<u32>,
+    // Número de movimientos; difiere de `words.len()` si hay movimientos escapados.
+    len: usize,
+    // Posición en `words` de la primera palabra del último movimiento.
+    last: usize,
+}
+
+impl CompactHistory {
+    /// Appends `movement`, played on a board of `board_size`.
+    ///
+    /// The move must already be valid there, as it is once played.
+    pub(crate) fn push<M: GameMove>(&mut self, movement: &M, board_size: u32) {
+        let player = movement.player().id();
+        let code = match movement.kind(board_size) {
+            Ok(MoveEffect::Placement(idx)) => idx as u32,
+            Ok(MoveEffect::Action(GameAction::Swap)) => WIDE_SWAP,
+            Ok(MoveEffect::Action(GameAction::Resign)) => WIDE_RESIGN,
+            Err(_) => unreachable!("only moves already played are recorded"),
+        };
+        let cell = match code {
+            WIDE_SWAP => Some(COMPACT_SWAP),
+            WIDE_RESIGN => Some(COMPACT_RESIGN),
+            idx => (idx < COMPACT_SWAP).then_some(idx),
+        };
+        self.last = self.words.len();
+        match cell {
+            Some(cell) if player <= u8::MAX as u32 => self.words.push(player << PLAYER_SHIFT | cell),
+            _ => self.words.extend([ESCAPE, player, code]),
+        }
+        self.len += 1;
+    }
+
+    /// Returns the number of moves.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of bytes the moves take on the heap.
+    #[cfg(test)]
+    pub(crate) fn heap_size(&self) -> usize {
+        self.words.capacity() * std::mem::size_of::<u32>()
+    }
+
+    /// Rebuilds the moves, in order, for a board of `board_size`.
+    pub(crate) fn moves<M: GameMove>(&self, board_size: u32) -> impl Iterator<Item = M> + '_ {
+        let mut words = self.words.iter().copied();
+        std::iter::from_fn(move || decode(&mut words, board_size))
+    }
+
+    /// Rebuilds the last move for a board of `board_size`, without the others.
+    pub(crate) fn last<M: GameMove>(&self, board_size: u32) -> Option<M> {
+        decode(&mut self.words[self.last..].iter().copied(), board_size)
+    }
+}
+
+/// Reads the next move from `words`, on a board of `board_size`.
+fn decode<M: GameMove>(words: &mut impl Iterator<Item = u32>, board_size: u32) -> Option<M> {
+    let word = words.next()?;
+    let (player, code) = if word == ESCAPE {
+        (words.next()?, words.next()?)
+    } else {
+        let code = match word & CELL_MASK {
+            COMPACT_SWAP => WIDE_SWAP,
+            COMPACT_RESIGN => WIDE_RESIGN,
+            idx => idx,
+        };
+        (word >> PLAYER_SHIFT, code)
+    };
+    let effect = match code {
+        WIDE_SWAP => MoveEffect::Action(&GameAction::Swap),
+        WIDE_RESIGN => MoveEffect::Action(&GameAction::Resign),
+        idx => MoveEffect::Placement(idx as usize),
+    };
+    Some(M::from_effect(PlayerId::new(player), effect, board_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameHex, GameY, HexCell, HexMove, Movement};
+
+    /// A long game: every cell of a board of `size`, in order, then a resignation.
+    fn long_game(size: u32) -> GameY {
+        let mut game = GameY::new(size);
+        let cells = game.total_cells();
+        for idx in 0..cells {
+            let player = PlayerId::new(idx % 2);
+            let coords = Coordinates::from_index(idx, size);
+            game.apply_move(Movement::Placement { player, coords }).unwrap();
+        }
+        let player = PlayerId::new(cells % 2);
+        game.apply_move(Movement::Action {
+            player,
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_packed_moves_take_a_fifth_of_the_memory() {
+        let game = long_game(30);
+        let moves = game.history();
+        assert_eq!(moves.len(), 466);
+        assert_eq!(game.move_count(), moves.len());
+
+        // Lo que ocupaba el historial como `Vec<Movement>`, con el mismo crecimiento
+        let mut unpacked = Vec::new();
+        for movement in moves.iter() {
+            unpacked.push(movement.clone());
+        }
+        let unpacked = unpacked.capacity() * std::mem::size_of::<Movement>();
+        let packed = game.history.heap_size();
+        assert_eq!(std::mem::size_of::<Movement>(), 20);
+        assert!(packed * 5 <= unpacked, "{} bytes packed against {} unpacked", packed, unpacked);
+    }
+
+    #[test]
+    fn test_materialized_moves_equal_the_played_ones() {
+        let mut game = GameY::new(4);
+        let played = vec![
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(3, 0, 0),
+            },
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            },
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(0, 1, 2),
+            },
+            Movement::Action {
+                player: PlayerId::new(0),
+                action: GameAction::Resign,
+            },
+        ];
+        for movement in &played {
+            game.add_move(movement.clone()).unwrap();
+            assert_eq!(game.last_move().as_ref(), Some(movement));
+        }
+        assert_eq!(game.history(), &played[..]);
+        assert_eq!(long_game(6).history().last(), long_game(6).last_move().as_ref());
+    }
+
+    #[test]
+    fn test_moves_that_do_not_fit_a_word_are_escaped() {
+        let size = 400;
+        let wide = [
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::from_index(70_000, size),
+            },
+            Movement::Placement {
+                player: PlayerId::new(300),
+                coords: Coordinates::from_index(5, size),
+            },
+            Movement::Action {
+                player: PlayerId::new(u32::MAX),
+                action: GameAction::Swap,
+            },
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::from_index(COMPACT_SWAP, size),
+            },
+        ];
+        let mut history = CompactHistory::default();
+        for movement in &wide {
+            history.push(movement, size);
+            assert_eq!(history.last::<Movement>(size).as_ref(), Some(movement));
+        }
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.words.len(), 12);
+        assert_eq!(history.moves::<Movement>(size).collect::<Vec<_>>(), wide);
+    }
+
+    #[test]
+    fn test_hex_histories_are_packed_too() {
+        let mut game = GameHex::new(5);
+        let played = [
+            HexMove::Placement {
+                player: PlayerId::new(0),
+                cell: HexCell::new(2, 2),
+            },
+            HexMove::Placement {
+                player: PlayerId::new(1),
+                cell: HexCell::new(4, 0),
+            },
+        ];
+        for movement in &played {
+            game.add_move(movement.clone()).unwrap();
+        }
+        assert_eq!(game.history(), &played[..]);
+        assert_eq!(game.last_move(), Some(played[1].clone()));
+    }
+}
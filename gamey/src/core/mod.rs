@@ -3,24 +3,62 @@
 //! This module contains the fundamental types for representing and playing Y:
 //! - [`Coordinates`]: Barycentric coordinates on the triangular board
 //! - [`GameY`]: The main game state and logic
+//! - [`ConnectionGame`]: The rules shared by every board, see [`GameMove`]
 //! - [`GameStatus`]: Whether the game is ongoing or finished
+//! - [`WinReason`]: How a finished game was won
 //! - [`Player`] and [`PlayerId`]: Player representation
 //! - [`Movement`]: A move (placement or action) in the game
 //! - [`GameAction`]: Special actions like swap or resign
 //! - [`RenderOptions`]: Configuration for board rendering
+//! - [`Clock`] and [`TimeControl`]: Per-player time control
+//! - [`CellDiff`]: Cells that differ between two positions, see [`GameY::diff`]
+//! - [`GameObserver`]: Callbacks notified of moves, wins and resets
+//! - [`Replay`]: A cursor over the positions of a game, see [`GameY::replay`]
+//! - [`GameHex`]: The game of Hex on the same engine, see [`HexTopology`]
+//! - [`Symmetry`]: Rotations and reflections of the board, see [`GameY::transform`]
+//! - [`GameTree`]: The moves of a game with their variations, for analysis
+//! - [`MoveAnnotation`]: Comments, marks and evaluations of moves, see [`GameY::annotate`]
+//! - [`Editor`]: Free placing and erasing of stones, see [`GameY::editor`]
+//! - [`OpeningOptions`]: Fairness options of new games, see [`GameY::with_opening`]
+//! - [`GameSnapshot`]: An immutable copy of a position for readers, see [`GameY::snapshot`]
 
 pub mod action;
+pub mod annotation;
+pub mod clock;
+pub mod connection;
 pub mod coord;
+pub mod diff;
+pub mod editor;
 pub mod game;
+pub mod hex;
+mod history;
 pub mod movement;
+pub mod observer;
+pub mod opening;
 pub mod player;
 pub mod render_options;
+pub mod replay;
+pub mod snapshot;
+pub mod symmetry;
 pub mod topology;
+pub mod tree;
 
 pub use action::*;
+pub use annotation::*;
+pub use clock::*;
+pub use connection::*;
 pub use coord::*;
+pub use diff::*;
+pub use editor::*;
 pub use game::*;
+pub use hex::*;
 pub use movement::*;
+pub use observer::*;
+pub use opening::*;
 pub use player::*;
 pub use render_options::*;
+pub use replay::*;
+pub use snapshot::*;
+pub use symmetry::*;
 pub use topology::*;
+pub use tree::*;
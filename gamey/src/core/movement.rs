@@ -1,11 +1,16 @@
-use crate::{Coordinates, GameAction, PlayerId};
+use crate::notation::move_text::{cell_name, parse_cell_name};
+use crate::{CellIndex, Coordinates, GameAction, GameMove, GameYError, MoveEffect, PLAYER_SYMBOLS, PlayerId};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 /// Represents a move that a player can make during the game.
 ///
 /// A movement can either be placing a piece on the board at specific coordinates,
 /// or performing a special game action like swapping or resigning.
-#[derive(Debug, Clone)]
+///
+/// In JSON a movement is an object with a `type` of `placement` or `action`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Movement {
     /// A piece placement on the board.
     Placement {
@@ -38,6 +43,134 @@ impl Display for Movement {
     }
 }
 
+impl Movement {
+    /// Returns the player who makes the move.
+    pub fn player(&self) -> PlayerId {
+        match self {
+            Movement::Placement { player, .. } | Movement::Action { player, .. } => *player,
+        }
+    }
+
+    /// Returns the short notation of the move on a board of `size`, like `B c4`,
+    /// `R swap` or `B resign`.
+    ///
+    /// The player is written as its YEN symbol and the cell as in
+    /// [`crate::GameY::to_move_text`]. [`Movement::parse`] reads it back.
+    pub fn notation(&self, size: u32) -> MovementNotation<'_> {
+        MovementNotation { movement: self, size }
+    }
+
+    /// Reads a move written by [`Movement::notation`] for a board of `size`.
+    ///
+    /// The text must be exactly a player symbol, one space and a cell name,
+    /// `swap` or `resign`. Symbols are uppercase and cells and actions lowercase.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidMoveNotation` saying what is wrong with the text.
+    pub fn parse(text: &str, size: u32) -> Result<Movement, GameYError> {
+        let error = |message: String| GameYError::InvalidMoveNotation {
+            text: text.to_string(),
+            message,
+        };
+        if text.trim() != text {
+            return Err(error("leading or trailing whitespace".to_string()));
+        }
+        let Some((symbol, target)) = text.split_once(' ') else {
+            return Err(error("expected a player symbol, a space and a cell or action".to_string()));
+        };
+        if target.is_empty() || target.contains(char::is_whitespace) {
+            return Err(error("expected a single space between the player and the move".to_string()));
+        }
+        let player = parse_player(symbol).map_err(error)?;
+        if let Ok(action) = target.parse::<GameAction>() {
+            return Ok(Movement::Action { player, action });
+        }
+        if target.eq_ignore_ascii_case("swap") || target.eq_ignore_ascii_case("resign") {
+            return Err(error(format!("actions are lowercase, expected '{}'", target.to_ascii_lowercase())));
+        }
+        if target.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(error(format!("cell names are lowercase, expected '{}'", target.to_ascii_lowercase())));
+        }
+        let coords = parse_cell_name(target, size)
+            .ok_or_else(|| error(format!("'{}' is not a cell of a board of size {}", target, size)))?;
+        Ok(Movement::Placement { player, coords })
+    }
+}
+
+/// Placements off the board fail with the errors of [`Coordinates::try_to_index`].
+impl GameMove for Movement {
+    fn player(&self) -> PlayerId {
+        Movement::player(self)
+    }
+
+    fn kind(&self, board_size: u32) -> Result<MoveEffect<'_>, GameYError> {
+        Ok(match self {
+            Movement::Placement { coords, .. } => MoveEffect::Placement(coords.try_to_index(board_size)? as usize),
+            Movement::Action { action, .. } => MoveEffect::Action(action),
+        })
+    }
+
+    fn from_effect(player: PlayerId, effect: MoveEffect<'_>, board_size: u32) -> Self {
+        match effect {
+            MoveEffect::Placement(idx) => Movement::Placement {
+                player,
+                coords: Coordinates::from_index(idx as u32, board_size),
+            },
+            MoveEffect::Action(action) => Movement::Action {
+                player,
+                action: action.clone(),
+            },
+        }
+    }
+
+    fn occupied_error(player: PlayerId, cell: CellIndex, board_size: u32) -> GameYError {
+        GameYError::Occupied {
+            coordinates: Coordinates::from_index(cell as u32, board_size),
+            player,
+        }
+    }
+
+    fn game_over_error(self) -> GameYError {
+        GameYError::GameOver { movement: self }
+    }
+}
+
+/// Reads a player symbol of [`PLAYER_SYMBOLS`].
+fn parse_player(symbol: &str) -> Result<PlayerId, String> {
+    let mut chars = symbol.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return Err(format!("'{}' is not a player symbol, expected one of {:?}", symbol, PLAYER_SYMBOLS));
+    };
+    if let Some(id) = PLAYER_SYMBOLS.iter().position(|s| *s == c) {
+        return Ok(PlayerId::new(id as u32));
+    }
+    if PLAYER_SYMBOLS.contains(&c.to_ascii_uppercase()) {
+        return Err(format!("player symbols are uppercase, expected '{}'", c.to_ascii_uppercase()));
+    }
+    Err(format!("unknown player '{}', expected one of {:?}", c, PLAYER_SYMBOLS))
+}
+
+/// The short notation of a move, see [`Movement::notation`].
+#[derive(Debug, Clone, Copy)]
+pub struct MovementNotation<'a> {
+    movement: &'a Movement,
+    size: u32,
+}
+
+impl Display for MovementNotation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let player = self.movement.player();
+        match PLAYER_SYMBOLS.get(player.id() as usize) {
+            Some(symbol) => write!(f, "{} ", symbol)?,
+            None => write!(f, "{} ", player)?,
+        }
+        match self.movement {
+            Movement::Placement { coords, .. } => write!(f, "{}", cell_name(*coords, self.size)),
+            Movement::Action { action, .. } => write!(f, "{}", action.notation()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,6 +202,28 @@ mod tests {
         assert_eq!(format!("{}", movement), "Player 0 performs action Resign");
     }
 
+    #[test]
+    fn test_json_form() {
+        let placement = Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(1, 2, 3),
+        };
+        let swap = Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        };
+        assert_eq!(
+            serde_json::to_string(&placement).unwrap(),
+            r#"{"type":"placement","player":0,"coords":{"x":1,"y":2,"z":3}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&swap).unwrap(),
+            r#"{"type":"action","player":1,"action":"swap"}"#
+        );
+        let back: Movement = serde_json::from_str(&serde_json::to_string(&swap).unwrap()).unwrap();
+        assert_eq!(back, swap);
+    }
+
     #[test]
     fn test_clone() {
         let movement = Movement::Placement {
@@ -78,4 +233,55 @@ mod tests {
         let cloned = movement.clone();
         assert_eq!(format!("{}", movement), format!("{}", cloned));
     }
+
+    #[test]
+    fn test_notation_round_trip() {
+        let place = |player: u32, x: u32, y: u32, z: u32| Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        };
+        let act = |player: u32, action: GameAction| Movement::Action {
+            player: PlayerId::new(player),
+            action,
+        };
+        let cases = [
+            (place(0, 1, 2, 1), "B c4"),
+            (place(1, 4, 0, 0), "R a1"),
+            (place(2, 0, 0, 4), "G a5"),
+            (act(1, GameAction::Swap), "R swap"),
+            (act(0, GameAction::Resign), "B resign"),
+        ];
+        for (movement, text) in cases {
+            assert_eq!(movement.notation(5).to_string(), text);
+            assert_eq!(Movement::parse(text, 5).unwrap(), movement, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("b c4", "player symbols are uppercase, expected 'B'"),
+            ("X c4", "unknown player 'X'"),
+            ("BR c4", "'BR' is not a player symbol"),
+            (" B c4", "leading or trailing whitespace"),
+            ("B c4 ", "leading or trailing whitespace"),
+            ("B  c4", "expected a single space"),
+            ("B\tc4", "expected a player symbol, a space"),
+            ("B", "expected a player symbol, a space"),
+            ("B C4", "cell names are lowercase, expected 'c4'"),
+            ("R Swap", "actions are lowercase, expected 'swap'"),
+            ("B f4", "'f4' is not a cell of a board of size 5"),
+            ("B a6", "'a6' is not a cell of a board of size 5"),
+            ("B pass", "'pass' is not a cell"),
+        ];
+        for (text, expected) in cases {
+            match Movement::parse(text, 5) {
+                Err(GameYError::InvalidMoveNotation { text: t, message }) => {
+                    assert_eq!(t, text);
+                    assert!(message.contains(expected), "{:?}: {}", text, message);
+                }
+                other => panic!("{:?} gave {:?}", text, other),
+            }
+        }
+    }
 }
@@ -0,0 +1,52 @@
+//! Observers notified of the changes of a game.
+//!
+//! A [`GameObserver`] registered with [`ConnectionGame::subscribe`] is called
+//! synchronously after every accepted move, when the game finishes and when
+//! it is reset. Callbacks receive the game by shared reference, so they can
+//! inspect it but never change it.
+
+use crate::{BoardTopology, ConnectionGame, GameMove, GameStatus, Movement, PlayerId, TriangularTopology};
+use std::fmt;
+
+/// Receives the events of a game.
+///
+/// Every method has an empty default implementation, so observers only
+/// implement the events they care about. Observers use interior mutability to
+/// keep their own state.
+///
+/// The parameters default to Y, so `impl GameObserver for X` observes a
+/// [`crate::GameY`]; other games name their topology and move type.
+pub trait GameObserver<T: BoardTopology = TriangularTopology, M: GameMove = Movement>: Send + Sync {
+    /// Called after `movement` was added to the game; `status` is the new status.
+    fn on_move(&self, _game: &ConnectionGame<T, M>, _movement: &M, _status: &GameStatus) {}
+
+    /// Called once when the game finishes, after the `on_move` of the deciding move.
+    fn on_finish(&self, _game: &ConnectionGame<T, M>, _winner: PlayerId) {}
+
+    /// Called after the game was reset; `game` is the new, empty game.
+    fn on_reset(&self, _game: &ConnectionGame<T, M>) {}
+}
+
+/// The observers registered on a game.
+///
+/// Cloning a game does not clone its observers: bots clone games constantly
+/// while searching, and those copies must not publish events.
+pub(crate) struct Observers<T: BoardTopology, M: GameMove>(pub(crate) Vec<Box<dyn GameObserver<T, M>>>);
+
+impl<T: BoardTopology, M: GameMove> Default for Observers<T, M> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: BoardTopology, M: GameMove> Clone for Observers<T, M> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T: BoardTopology, M: GameMove> fmt::Debug for Observers<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observer(s)", self.0.len())
+    }
+}
@@ -0,0 +1,357 @@
+//! Fairness options chosen when a game is created.
+//!
+//! The first player of Y has a big advantage. The swap (pie) rule is the
+//! usual answer, and [`OpeningOptions`] offers three others instead: a random
+//! first player, random stones placed before play starts, and a ban on
+//! opening near the center. [`GameY::with_opening`] applies them to a new game.
+//!
+//! ```
+//! use gamey::{GameY, OpeningOptions};
+//!
+//! let options = OpeningOptions::new()
+//!     .with_swap_rule(false)
+//!     .with_random_opening_stones(2)
+//!     .with_forbidden_center(1);
+//! let game = GameY::new(7).with_opening(&options, &mut rand::rng()).unwrap();
+//!
+//! // The random stones are part of the position, not of the history
+//! assert_eq!(game.engine().occupants().iter().flatten().count(), 2);
+//! assert!(game.history().is_empty());
+//! ```
+
+use crate::core::topology::CellIndex;
+use crate::{Coordinates, GameY, GameYError, PlayerId, Result};
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+/// How a new game starts.
+///
+/// The swap rule is on by default, as in every game so far; the other
+/// options replace it, so they must be combined with `swap_rule: false`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct OpeningOptions {
+    /// Whether the second player may swap after the first move (the pie rule).
+    pub swap_rule: bool,
+    /// Whether the first player is drawn at random instead of being player 0.
+    pub random_first_player: bool,
+    /// Number of stones placed on random cells before play starts, taking
+    /// turns from the first player. They are part of the position, not of the history.
+    pub random_opening_stones: u8,
+    /// Radius around the center where the first move may not be played, if any.
+    ///
+    /// With radius 0 only the center cells are banned; the center is one
+    /// cell or, on boards whose center falls between cells, the nearest three.
+    pub forbid_center_opening: Option<u32>,
+}
+
+impl Default for OpeningOptions {
+    fn default() -> Self {
+        OpeningOptions {
+            swap_rule: true,
+            random_first_player: false,
+            random_opening_stones: 0,
+            forbid_center_opening: None,
+        }
+    }
+}
+
+impl OpeningOptions {
+    /// Creates the default options: the swap rule and nothing else.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows or forbids the swap rule.
+    pub fn with_swap_rule(mut self, swap_rule: bool) -> Self {
+        self.swap_rule = swap_rule;
+        self
+    }
+
+    /// Draws the first player at random.
+    pub fn with_random_first_player(mut self, random_first_player: bool) -> Self {
+        self.random_first_player = random_first_player;
+        self
+    }
+
+    /// Places `stones` stones on random cells before play starts.
+    pub fn with_random_opening_stones(mut self, stones: u8) -> Self {
+        self.random_opening_stones = stones;
+        self
+    }
+
+    /// Forbids first moves within `radius` of the center.
+    pub fn with_forbidden_center(mut self, radius: u32) -> Self {
+        self.forbid_center_opening = Some(radius);
+        self
+    }
+
+    /// Checks that the options can be used together on a board of `board_size`.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidOpeningOptions` if the swap rule is combined
+    /// with another option, or if the random stones could already win: a
+    /// winning chain has at least `board_size` stones.
+    pub fn validate(&self, board_size: u32) -> Result<()> {
+        let invalid = |message: String| Err(GameYError::InvalidOpeningOptions { message });
+        if self.swap_rule {
+            let others = [
+                (self.random_first_player, "random_first_player"),
+                (self.random_opening_stones > 0, "random_opening_stones"),
+                (self.forbid_center_opening.is_some(), "forbid_center_opening"),
+            ];
+            if let Some((_, name)) = others.iter().find(|(set, _)| *set) {
+                return invalid(format!("{} replaces the swap rule, which must be turned off", name));
+            }
+        }
+        if u32::from(self.random_opening_stones) >= board_size {
+            return invalid(format!(
+                "{} random stones could decide a game of size {}, use fewer than {}",
+                self.random_opening_stones, board_size, board_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The opening rules a game keeps enforcing once it is created.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OpeningRules {
+    // Set when the swap rule was turned off at creation.
+    pub(crate) no_swap: bool,
+    // Radius and cells of the banned center, for the first move.
+    pub(crate) forbidden_center: Option<(u32, Vec<CellIndex>)>,
+}
+
+impl OpeningRules {
+    /// The radius of the banned center, if `cell` is in it.
+    pub(crate) fn forbidden_radius(&self, cell: CellIndex) -> Option<u32> {
+        let (radius, cells) = self.forbidden_center.as_ref()?;
+        cells.contains(&cell).then_some(*radius)
+    }
+}
+
+impl GameY {
+    /// Applies `options` to this new game, drawing with `rng`.
+    ///
+    /// The random stones are placed as a setup, so the game starts with an
+    /// empty history and the first real move is the one the center ban
+    /// applies to. The game keeps forbidding swaps and central openings
+    /// when it is [`reset`](crate::ConnectionGame::reset).
+    ///
+    /// # Errors
+    /// Returns the errors of [`OpeningOptions::validate`], and
+    /// `GameYError::InvalidPosition` if the game already has stones.
+    pub fn with_opening<R: Rng + ?Sized>(self, options: &OpeningOptions, rng: &mut R) -> Result<GameY> {
+        options.validate(self.board_size)?;
+        if self.engine.occupants().iter().any(Option::is_some) {
+            return Err(GameYError::InvalidPosition {
+                message: "opening options apply to new games only".to_string(),
+            });
+        }
+        let mut game = self;
+        game.rules = OpeningRules {
+            no_swap: !options.swap_rule,
+            forbidden_center: options
+                .forbid_center_opening
+                .map(|radius| (radius, center_cells(game.board_size, radius))),
+        };
+        let num_players = game.num_players;
+        let first = if options.random_first_player {
+            rng.random_range(0..num_players)
+        } else {
+            0
+        };
+        let mut editor = game.editor();
+        let cells: Vec<u32> = (0..game.total_cells()).collect();
+        for (i, idx) in cells
+            .choose_multiple(rng, options.random_opening_stones as usize)
+            .enumerate()
+        {
+            let player = PlayerId::new((first + i as u32) % num_players);
+            editor.place(player, Coordinates::from_index(*idx, game.board_size))?;
+        }
+        let stones = u32::from(options.random_opening_stones);
+        editor.finish(PlayerId::new((first + stones) % num_players))
+    }
+
+    /// Returns `true` if players may swap in this game.
+    ///
+    /// Only games created with [`OpeningOptions::swap_rule`] off forbid it.
+    pub fn swap_allowed(&self) -> bool {
+        !self.rules.no_swap
+    }
+}
+
+/// The cells within `radius` of the center of a board of `board_size`.
+fn center_cells(board_size: u32, radius: u32) -> Vec<CellIndex> {
+    // Distances are taken three times over, so the center (size-1)/3 stays an integer
+    let center = board_size as i64 - 1;
+    let off_center = |coords: &Coordinates| {
+        [coords.x(), coords.y(), coords.z()]
+            .iter()
+            .map(|c| (3 * *c as i64 - center).abs())
+            .sum::<i64>()
+    };
+    let all: Vec<Coordinates> = (0..board_size * (board_size + 1) / 2)
+        .map(|idx| Coordinates::from_index(idx, board_size))
+        .collect();
+    let nearest = all.iter().map(off_center).min().unwrap_or(0);
+    let middle: Vec<&Coordinates> = all.iter().filter(|c| off_center(c) == nearest).collect();
+    all.iter()
+        .filter(|c| middle.iter().any(|m| distance(c, m) <= radius))
+        .map(|c| c.to_index(board_size) as CellIndex)
+        .collect()
+}
+
+/// Number of steps between two cells of the board.
+fn distance(a: &Coordinates, b: &Coordinates) -> u32 {
+    (a.x().abs_diff(b.x()) + a.y().abs_diff(b.y()) + a.z().abs_diff(b.z())) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, Movement, YEN};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn no_swap() -> OpeningOptions {
+        OpeningOptions::new().with_swap_rule(false)
+    }
+
+    fn stones(game: &GameY) -> Vec<Option<PlayerId>> {
+        game.engine().occupants().to_vec()
+    }
+
+    #[test]
+    fn test_default_options_change_nothing() {
+        let game = GameY::new(5).with_opening(&OpeningOptions::new(), &mut rand::rng()).unwrap();
+        assert_eq!(game, GameY::new(5));
+        assert!(game.swap_allowed());
+    }
+
+    #[test]
+    fn test_options_replacing_the_swap_rule_require_turning_it_off() {
+        for options in [
+            OpeningOptions::new().with_random_first_player(true),
+            OpeningOptions::new().with_random_opening_stones(1),
+            OpeningOptions::new().with_forbidden_center(0),
+        ] {
+            let error = GameY::new(5).with_opening(&options, &mut rand::rng()).unwrap_err();
+            assert!(matches!(error, GameYError::InvalidOpeningOptions { .. }), "{:?}", error);
+            assert!(error.to_string().contains("swap rule"), "{}", error);
+            assert!(options.with_swap_rule(false).validate(5).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_random_stones_must_not_decide_the_game() {
+        assert!(no_swap().with_random_opening_stones(4).validate(5).is_ok());
+        let error = no_swap().with_random_opening_stones(5).validate(5).unwrap_err();
+        assert!(error.to_string().contains("fewer than 5"), "{}", error);
+    }
+
+    #[test]
+    fn test_random_first_player_draws_both_players() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let options = no_swap().with_random_first_player(true);
+        let firsts: Vec<u32> = (0..32)
+            .map(|_| GameY::new(4).with_opening(&options, &mut rng).unwrap().next_player().unwrap().id())
+            .collect();
+        assert!(firsts.contains(&0) && firsts.contains(&1), "{:?}", firsts);
+    }
+
+    #[test]
+    fn test_random_stones_alternate_and_are_not_history() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let game = GameY::new(7)
+            .with_opening(&no_swap().with_random_opening_stones(3), &mut rng)
+            .unwrap();
+        let placed: Vec<PlayerId> = stones(&game).into_iter().flatten().collect();
+        assert_eq!(placed.len(), 3);
+        assert_eq!(placed.iter().filter(|p| p.id() == 0).count(), 2);
+        assert!(game.history().is_empty());
+        assert_eq!(game.ply(), 0);
+        assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+        assert!(!game.check_game_over());
+    }
+
+    #[test]
+    fn test_pre_placed_stones_survive_yen() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let options = no_swap().with_random_first_player(true).with_random_opening_stones(4);
+        for _ in 0..8 {
+            let game = GameY::new(6).with_opening(&options, &mut rng).unwrap();
+            let yen = YEN::from(&game);
+            let loaded = GameY::try_from(yen).unwrap();
+            assert_eq!(stones(&loaded), stones(&game));
+            assert_eq!(loaded.next_player(), game.next_player());
+        }
+    }
+
+    #[test]
+    fn test_center_openings_are_rejected() {
+        // The center of a board of size 7 is the cell (2, 2, 2)
+        let mut game = GameY::new(7).with_opening(&no_swap().with_forbidden_center(1), &mut rand::rng()).unwrap();
+        let player = PlayerId::new(0);
+        let center = Coordinates::new(2, 2, 2);
+        let error = game.add_move(Movement::Placement { player, coords: center }).unwrap_err();
+        assert!(
+            matches!(error, GameYError::ForbiddenOpening { index, radius: 1 } if index == center.to_index(7)),
+            "{:?}",
+            error
+        );
+        let next_to_it = Coordinates::new(3, 2, 1);
+        assert!(game.add_move(Movement::Placement { player, coords: next_to_it }).is_err());
+
+        game.add_move(Movement::Placement {
+            player,
+            coords: Coordinates::new(6, 0, 0),
+        })
+        .unwrap();
+        // Only the first move is restricted
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: center,
+        })
+        .unwrap();
+
+        game.reset();
+        assert!(game.add_move(Movement::Placement { player, coords: center }).is_err());
+    }
+
+    #[test]
+    fn test_center_between_cells_bans_the_nearest_three() {
+        assert_eq!(center_cells(7, 0), [Coordinates::new(2, 2, 2).to_index(7) as CellIndex]);
+        let mut around = center_cells(5, 0);
+        around.sort_unstable();
+        let mut expected: Vec<CellIndex> = [(2, 1, 1), (1, 2, 1), (1, 1, 2)]
+            .iter()
+            .map(|(x, y, z)| Coordinates::new(*x, *y, *z).to_index(5) as CellIndex)
+            .collect();
+        expected.sort_unstable();
+        assert_eq!(around, expected);
+    }
+
+    #[test]
+    fn test_swaps_need_the_swap_rule() {
+        let swap = |game: &mut GameY| {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(0, 0, 4),
+            })
+            .unwrap();
+            game.add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            })
+        };
+        assert!(swap(&mut GameY::new(5)).is_ok());
+        let mut game = GameY::new(5).with_opening(&no_swap(), &mut rand::rng()).unwrap();
+        assert!(!game.swap_allowed());
+        let error = swap(&mut game).unwrap_err();
+        assert!(matches!(error, GameYError::SwapNotAllowed { player } if player.id() == 1), "{:?}", error);
+    }
+}
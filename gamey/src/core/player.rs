@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 /// Represents a player in the game with an identifier and a name.
@@ -30,11 +31,54 @@ impl Display for Player {
     }
 }
 
+/// Who is behind a seat: a person or a bot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerKind {
+    /// A human player.
+    Human,
+    /// A bot, identified by its registry name.
+    Bot(String),
+}
+
+/// Display information about a player taking part in a game.
+///
+/// Games without explicit information use [`PlayerInfo::default_for`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlayerInfo {
+    /// The player this information belongs to.
+    pub id: PlayerId,
+    /// Name shown to users.
+    pub name: String,
+    /// Whether the player is a human or a bot.
+    pub kind: PlayerKind,
+}
+
+impl PlayerInfo {
+    /// Creates the information of a player.
+    pub fn new(id: PlayerId, name: impl Into<String>, kind: PlayerKind) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            kind,
+        }
+    }
+
+    /// Default information of a player: a human called "Player {id}".
+    pub fn default_for(id: PlayerId) -> Self {
+        Self::new(id, format!("Player {}", id), PlayerKind::Human)
+    }
+}
+
 /// A unique identifier for a player.
 ///
 /// This is a lightweight wrapper around a `u32` that provides type safety
 /// for player identification throughout the game.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
 pub struct PlayerId(u32);
 
 impl PlayerId {
@@ -95,6 +139,24 @@ mod tests {
         assert_eq!(format!("{}", player), "Player 1: Bob");
     }
 
+    #[test]
+    fn test_player_info_default() {
+        let info = PlayerInfo::default_for(PlayerId::new(1));
+        assert_eq!(info.name, "Player 1");
+        assert_eq!(info.kind, PlayerKind::Human);
+    }
+
+    #[test]
+    fn test_player_info_serialization() {
+        let info = PlayerInfo::new(PlayerId::new(1), "Bot", PlayerKind::Bot("random_bot".to_string()));
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"id": 1, "name": "Bot", "kind": {"bot": "random_bot"}})
+        );
+        assert_eq!(serde_json::from_value::<PlayerInfo>(json).unwrap(), info);
+    }
+
     #[test]
     fn test_player_clone() {
         let id = PlayerId::new(0);
@@ -8,6 +8,30 @@ pub struct RenderOptions {
     pub show_idx: bool,
     /// If true, use ANSI color codes to distinguish players.
     pub show_colors: bool,
+    /// If true, write the comment of the last move below the board, see
+    /// [`crate::GameY::annotate`].
+    pub show_comment: bool,
+    /// If true, number the rows on the left and letter the columns below the
+    /// board, so a cell reads as in the move text notation, e.g. `b3`.
+    /// Ignored by Hex boards.
+    pub show_labels: bool,
+    /// If set, shade the empty cells by these values, one per cell index, from
+    /// `' '` for 0.0 to `'@'` for 1.0 (see [`HEAT_MAP_RAMP`]). Meant for the
+    /// [`crate::analysis::ownership`] of the position; ignored by Hex boards.
+    pub heat_map: Option<Vec<f32>>,
+    /// If true, show the stone of the last move in reverse video, so it can be
+    /// told apart when stepping through a game. Ignored by Hex boards.
+    pub highlight_last_move: bool,
+}
+
+/// Characters of a heat map, from the lowest to the highest value.
+pub const HEAT_MAP_RAMP: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// The character of `value`, clamped to [0, 1], in [`HEAT_MAP_RAMP`].
+pub fn heat_map_char(value: f32) -> char {
+    let last = HEAT_MAP_RAMP.len() - 1;
+    let step = (value.clamp(0.0, 1.0) * last as f32).round() as usize;
+    HEAT_MAP_RAMP[step.min(last)]
 }
 
 impl Default for RenderOptions {
@@ -16,6 +40,10 @@ impl Default for RenderOptions {
             show_3d_coords: false,
             show_idx: true,
             show_colors: true,
+            show_comment: false,
+            show_labels: false,
+            heat_map: None,
+            highlight_last_move: false,
         }
     }
 }
@@ -30,6 +58,19 @@ mod tests {
         assert!(!options.show_3d_coords);
         assert!(options.show_idx);
         assert!(options.show_colors);
+        assert!(!options.show_comment);
+        assert!(!options.show_labels);
+        assert!(options.heat_map.is_none());
+        assert!(!options.highlight_last_move);
+    }
+
+    #[test]
+    fn test_heat_map_char() {
+        assert_eq!(heat_map_char(0.0), ' ');
+        assert_eq!(heat_map_char(0.5), '+');
+        assert_eq!(heat_map_char(1.0), '@');
+        assert_eq!(heat_map_char(7.0), '@');
+        assert_eq!(heat_map_char(f32::NAN), ' ');
     }
 
     #[test]
@@ -38,6 +79,10 @@ mod tests {
             show_3d_coords: true,
             show_idx: false,
             show_colors: false,
+            show_comment: false,
+            show_labels: false,
+            heat_map: None,
+            highlight_last_move: false,
         };
         assert!(options.show_3d_coords);
         assert!(!options.show_idx);
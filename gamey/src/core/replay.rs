@@ -0,0 +1,225 @@
+//! Building games from move lists and stepping through their positions.
+//!
+//! [`GameY::from_moves`] plays a list of moves on an empty board and tells
+//! which move could not be played. [`GameY::replay`] returns a [`Replay`], a
+//! cursor over the positions of a game that moves forward and back one move
+//! at a time, for animating a finished game.
+
+use crate::{GameY, GameYError, Movement, PlayerId, Result};
+
+impl GameY {
+    /// Plays `moves` in order on an empty two-player board of `size`.
+    ///
+    /// Every move must be on the board, made by the player in turn and legal
+    /// in the position reached so far.
+    ///
+    /// # Errors
+    /// Returns `GameYError::InvalidBoardSize` if `size` is out of range, and
+    /// `GameYError::InvalidMoveList` with the index of the first move that
+    /// cannot be played, starting at 0.
+    pub fn from_moves(size: u32, moves: &[Movement]) -> Result<GameY> {
+        let mut game = GameY::try_new(size)?;
+        for (index, movement) in moves.iter().enumerate() {
+            let error = |message: String| GameYError::InvalidMoveList { index, message };
+            if let Movement::Placement { coords, .. } = movement {
+                let sum = coords.x() as u64 + coords.y() as u64 + coords.z() as u64;
+                if sum + 1 != size as u64 {
+                    return Err(error(format!("{} is not a cell of a board of size {}", coords, size)));
+                }
+            }
+            game.check_player_turn(movement).map_err(|e| error(e.to_string()))?;
+            game.add_move(movement.clone()).map_err(|e| error(e.to_string()))?;
+        }
+        Ok(game)
+    }
+
+    /// Returns a cursor over the positions of this game, starting at the empty board.
+    pub fn replay(&self) -> Replay {
+        let start = self.empty_copy();
+        Replay {
+            current: start.clone(),
+            start,
+            moves: self.history().to_vec(),
+            lost_on_time: self.lost_on_time(),
+            ply: 0,
+        }
+    }
+}
+
+/// A cursor over the positions of a game, from the empty board to the last move.
+///
+/// A game of N moves has N + 1 positions; position `ply` is the board after
+/// its first `ply` moves. The last position of a game lost on time is also
+/// marked as lost on time.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    start: GameY,
+    current: GameY,
+    moves: Vec<Movement>,
+    lost_on_time: Option<PlayerId>,
+    ply: usize,
+}
+
+impl Replay {
+    /// Returns the number of moves of the game.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Returns `true` if the game has no moves.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Returns the number of moves played in the current position.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Returns the current position.
+    pub fn current(&self) -> &GameY {
+        &self.current
+    }
+
+    /// Returns the moves of the game.
+    pub fn moves(&self) -> &[Movement] {
+        &self.moves
+    }
+
+    /// Plays the next move and returns it, or `None` at the last position.
+    pub fn step_forward(&mut self) -> Option<&Movement> {
+        let movement = self.moves.get(self.ply)?;
+        // The moves come from a game that accepted them, even past its end
+        let _ = self.current.apply_move(movement.clone());
+        self.ply += 1;
+        if self.ply == self.moves.len()
+            && let Some(player) = self.lost_on_time
+        {
+            self.current.lose_on_time(player);
+        }
+        Some(movement)
+    }
+
+    /// Takes back the last played move and returns it, or `None` at the empty board.
+    pub fn step_back(&mut self) -> Option<&Movement> {
+        let ply = self.ply.checked_sub(1)?;
+        self.seek(ply);
+        self.moves.get(ply)
+    }
+
+    /// Moves to the position after the first `ply` moves, or to the last one if `ply` is larger.
+    pub fn seek(&mut self, ply: usize) {
+        let ply = ply.min(self.moves.len());
+        if ply < self.ply {
+            self.current = self.start.clone();
+            self.ply = 0;
+        }
+        while self.ply < ply {
+            self.step_forward();
+        }
+    }
+
+    /// Returns every position of the game, from the empty board to the last move.
+    pub fn positions(&self) -> Vec<GameY> {
+        let mut cursor = self.clone();
+        cursor.seek(0);
+        let mut positions = vec![cursor.current.clone()];
+        while cursor.step_forward().is_some() {
+            positions.push(cursor.current.clone());
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameAction, GameStatus, YEN};
+
+    fn place(player: u32, x: u32, y: u32, z: u32) -> Movement {
+        Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        }
+    }
+
+    fn layout(game: &GameY) -> String {
+        YEN::from(game).layout().to_string()
+    }
+
+    #[test]
+    fn test_from_moves_builds_the_position() {
+        let moves = [place(0, 2, 0, 0), place(1, 0, 2, 0), place(0, 1, 0, 1)];
+        let game = GameY::from_moves(3, &moves).unwrap();
+        assert_eq!(game.history(), &moves[..]);
+        assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_from_moves_reports_the_failing_index() {
+        let occupied = [place(0, 2, 0, 0), place(1, 0, 2, 0), place(0, 2, 0, 0)];
+        let err = GameY::from_moves(3, &occupied).unwrap_err();
+        assert!(matches!(err, GameYError::InvalidMoveList { index: 2, .. }), "{}", err);
+
+        let out_of_turn = [place(0, 2, 0, 0), place(0, 0, 2, 0)];
+        let err = GameY::from_moves(3, &out_of_turn).unwrap_err();
+        assert!(matches!(err, GameYError::InvalidMoveList { index: 1, .. }), "{}", err);
+
+        let off_board = [place(0, 3, 0, 0)];
+        let err = GameY::from_moves(3, &off_board).unwrap_err();
+        assert!(matches!(err, GameYError::InvalidMoveList { index: 0, .. }), "{}", err);
+
+        let err = GameY::from_moves(u32::MAX, &[]).unwrap_err();
+        assert!(matches!(err, GameYError::InvalidBoardSize { .. }), "{}", err);
+    }
+
+    #[test]
+    fn test_replay_visits_every_position() {
+        let moves = [place(0, 2, 0, 0), place(1, 0, 2, 0), place(0, 1, 0, 1)];
+        let game = GameY::from_moves(3, &moves).unwrap();
+        let positions = game.replay().positions();
+        assert_eq!(positions.len(), moves.len() + 1);
+        assert!(positions[0].history().is_empty());
+        assert_eq!(layout(&positions[3]), layout(&game));
+    }
+
+    #[test]
+    fn test_cursor_steps_forward_and_back() {
+        let moves = [
+            place(0, 2, 0, 0),
+            place(1, 0, 2, 0),
+            Movement::Action {
+                player: PlayerId::new(0),
+                action: GameAction::Resign,
+            },
+        ];
+        let game = GameY::from_moves(3, &moves).unwrap();
+        let mut replay = game.replay();
+        assert_eq!(replay.len(), 3);
+        assert_eq!(replay.step_back(), None);
+
+        let mut visited = 1;
+        while replay.step_forward().is_some() {
+            visited += 1;
+        }
+        assert_eq!(visited, 4);
+        assert_eq!(replay.current().status(), game.status());
+
+        assert_eq!(replay.step_back(), Some(&moves[2]));
+        assert_eq!(replay.ply(), 2);
+        assert!(!replay.current().check_game_over());
+        replay.seek(1);
+        assert_eq!(replay.current().history(), &moves[..1]);
+        replay.seek(10);
+        assert_eq!(replay.ply(), 3);
+    }
+
+    #[test]
+    fn test_last_position_keeps_the_loss_on_time() {
+        let mut game = GameY::from_moves(3, &[place(0, 2, 0, 0)]).unwrap();
+        game.lose_on_time(PlayerId::new(1));
+        let positions = game.replay().positions();
+        assert_eq!(positions[0].status(), &GameStatus::Ongoing { next_player: PlayerId::new(0) });
+        assert_eq!(positions[1].lost_on_time(), Some(PlayerId::new(1)));
+    }
+}
@@ -0,0 +1,328 @@
+//! Immutable copies of a position for readers.
+//!
+//! A server keeps its games behind a lock, and turning a big game into YEN or
+//! JSON takes far longer than copying its board. [`GameY::snapshot`] copies
+//! what the read-only responses need into a [`GameSnapshot`] that can be
+//! serialized after the lock is released, while moves go on.
+
+use crate::core::connection::next_player_after;
+use crate::core::history::CompactHistory;
+use crate::core::topology::{CellIndex, GameEngine, RegionMask, TriangularTopology};
+use crate::{GameStatus, GameY, GameYError, Movement, PlayerId, PlayerInfo, Result, YEN, YenMetadata};
+use std::sync::Arc;
+
+/// A position of a [`GameY`] frozen at the time it was taken.
+///
+/// Cloning a snapshot only clones `Arc`s. It is `Send + Sync`, so it can be
+/// moved to another task or thread and read from several at once.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, YEN};
+///
+/// let game = GameY::new(3);
+/// let snapshot = game.snapshot();
+/// assert_eq!(YEN::from(&snapshot), YEN::from(&game));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameSnapshot {
+    board_size: u32,
+    num_players: u32,
+    cell_count: usize,
+    // One bitboard per player: bit `i` of word `i / 64` is set if the player holds cell `i`.
+    occupancy: Arc<[Box<[u64]>]>,
+    status: GameStatus,
+    history: Arc<CompactHistory>,
+    symbols: Arc<[char]>,
+    players: Arc<[PlayerInfo]>,
+    named_players: bool,
+    lost_on_time: Option<PlayerId>,
+    forfeited: Option<PlayerId>,
+    sides_touched: Arc<[RegionMask]>,
+}
+
+impl GameY {
+    /// Copies the position into a [`GameSnapshot`] that outlives any borrow of the game.
+    ///
+    /// Taking a snapshot copies the board and the packed moves, but builds
+    /// no strings, so it is cheap enough to do while holding a lock.
+    pub fn snapshot(&self) -> GameSnapshot {
+        let occupants = self.engine.occupants();
+        let words = occupants.len().div_ceil(64);
+        // Stones of unknown players get a bitboard too, so `to_yen` can report them
+        let boards = occupants
+            .iter()
+            .flatten()
+            .map(|player| player.id() + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.num_players);
+        let mut occupancy = vec![vec![0u64; words].into_boxed_slice(); boards as usize];
+        for (cell, occupant) in occupants.iter().enumerate() {
+            if let Some(player) = occupant {
+                occupancy[player.id() as usize][cell / 64] |= 1 << (cell % 64);
+            }
+        }
+        GameSnapshot {
+            board_size: self.board_size,
+            num_players: self.num_players,
+            cell_count: occupants.len(),
+            occupancy: occupancy.into(),
+            status: self.status.clone(),
+            history: Arc::new(self.history.clone()),
+            symbols: self.player_symbols().into(),
+            players: self.players().into(),
+            named_players: self.player_info.is_some(),
+            lost_on_time: self.lost_on_time,
+            forfeited: self.forfeited,
+            sides_touched: (0..self.num_players)
+                .map(|id| self.sides_touched(PlayerId::new(id)))
+                .collect(),
+        }
+    }
+}
+
+impl GameSnapshot {
+    /// Returns the number of cells of each side of the board.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the number of players taking turns.
+    pub fn num_players(&self) -> u32 {
+        self.num_players
+    }
+
+    /// Returns the number of cells of the board.
+    pub fn total_cells(&self) -> usize {
+        self.cell_count
+    }
+
+    /// Returns the player holding `cell`, or `None` if it is empty or off the board.
+    pub fn occupant(&self, cell: CellIndex) -> Option<PlayerId> {
+        if cell >= self.cell_count {
+            return None;
+        }
+        self.occupancy
+            .iter()
+            .position(|board| board[cell / 64] & (1 << (cell % 64)) != 0)
+            .map(|player| PlayerId::new(player as u32))
+    }
+
+    /// Returns the status of the game.
+    pub fn status(&self) -> &GameStatus {
+        &self.status
+    }
+
+    /// Returns the player to move, or `None` if the game is over.
+    pub fn next_player(&self) -> Option<PlayerId> {
+        match self.status {
+            GameStatus::Ongoing { next_player } => Some(next_player),
+            GameStatus::Finished { .. } => None,
+        }
+    }
+
+    /// Returns the number of moves played.
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns the number of plies played, as [`GameY::ply`].
+    pub fn ply(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns the last move played, if any.
+    pub fn last_move(&self) -> Option<Movement> {
+        self.history.last(self.board_size)
+    }
+
+    /// Returns the moves played, oldest first.
+    pub fn moves(&self) -> impl Iterator<Item = Movement> + '_ {
+        self.history.moves(self.board_size)
+    }
+
+    /// Returns the display information of every player, in player order.
+    pub fn players(&self) -> &[PlayerInfo] {
+        &self.players
+    }
+
+    /// Returns the symbols of the players in YEN layouts, in player order.
+    pub fn player_symbols(&self) -> &[char] {
+        &self.symbols
+    }
+
+    /// Returns the player who lost on time, if the game ended that way.
+    pub fn lost_on_time(&self) -> Option<PlayerId> {
+        self.lost_on_time
+    }
+
+    /// Returns the player who forfeited, if the game ended that way.
+    pub fn forfeited(&self) -> Option<PlayerId> {
+        self.forfeited
+    }
+
+    /// Returns the sides touched by the stones of `player`, as [`GameY::sides_touched`].
+    pub fn sides_touched(&self, player: PlayerId) -> RegionMask {
+        self.sides_touched.get(player.id() as usize).copied().unwrap_or(0)
+    }
+
+    /// Returns the names of the sides touched by the stones of `player`, as
+    /// [`GameY::sides_touched_named`].
+    pub fn sides_touched_named(&self, player: PlayerId) -> Vec<&'static str> {
+        let sides = self.sides_touched(player);
+        (0..RegionMask::BITS)
+            .map(|bit| 1 << bit)
+            .filter(|side| sides & side != 0)
+            .filter_map(TriangularTopology::side_name)
+            .collect()
+    }
+
+    /// Rebuilds the engine of the position, with its connected groups.
+    ///
+    /// This costs about as much as replaying the game, so it is meant to be
+    /// called after the lock of the game is released.
+    pub fn engine(&self) -> GameEngine<TriangularTopology> {
+        let mut engine = GameEngine::new(TriangularTopology::with_players(self.board_size, self.num_players));
+        for cell in 0..self.cell_count {
+            if let Some(player) = self.occupant(cell) {
+                // Every cell of the snapshot comes from a board that held it
+                let _ = engine.make_move(cell, player);
+            }
+        }
+        engine
+    }
+
+    /// Returns the YEN result of the game (e.g. `"B+resign"`), or `None` while it is ongoing.
+    fn result_text(&self) -> Option<String> {
+        let GameStatus::Finished { winner, reason } = self.status else {
+            return None;
+        };
+        Some(format!("{}+{}", self.symbols[winner.id() as usize], reason.notation()))
+    }
+
+    /// Converts the position into its YEN representation, as [`GameY::to_yen`].
+    pub fn to_yen(&self) -> Result<YEN> {
+        let size = self.board_size;
+        let expected = (size * (size + 1) / 2) as usize;
+        if self.cell_count != expected {
+            return Err(GameYError::InvalidGameState {
+                message: format!(
+                    "a board of size {} has {} cells, found {}",
+                    size, expected, self.cell_count
+                ),
+            });
+        }
+        let mut rows = Vec::with_capacity(size as usize);
+        let mut cells = 0..self.cell_count;
+        // Row `r` of the triangle, from the top, holds `r + 1` cells
+        for row in 0..size as usize {
+            let mut line = String::with_capacity(row + 1);
+            for idx in cells.by_ref().take(row + 1) {
+                line.push(match self.occupant(idx) {
+                    None => '.',
+                    Some(player) => *self.symbols.get(player.id() as usize).ok_or_else(|| {
+                        GameYError::InvalidGameState {
+                            message: format!("cell {} holds unknown player {}", idx, player.id()),
+                        }
+                    })?,
+                });
+            }
+            rows.push(line);
+        }
+        let turn = match self.status {
+            GameStatus::Finished { winner, .. } => next_player_after(winner, self.num_players).id(),
+            GameStatus::Ongoing { next_player } => next_player.id(),
+        };
+        let mut yen = YEN::new(size, turn, self.symbols.to_vec(), rows.join("/"));
+        if let Some(result) = self.result_text() {
+            yen = yen.with_result(result);
+        }
+        Ok(match self.named_players {
+            true => yen.with_metadata(YenMetadata {
+                players: self.players.to_vec(),
+            }),
+            false => yen,
+        })
+    }
+}
+
+impl From<&GameSnapshot> for YEN {
+    /// Panics if the board is inconsistent, which the moves of a game never
+    /// cause; use [`GameSnapshot::to_yen`] to get an error instead.
+    fn from(snapshot: &GameSnapshot) -> Self {
+        snapshot.to_yen().expect("the board of a game is consistent")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameAction, PlayerKind};
+
+    fn place(game: &mut GameY, player: u32, index: u32) {
+        let coords = Coordinates::from_index(index, game.board_size());
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<GameSnapshot>();
+    }
+
+    #[test]
+    fn test_snapshot_matches_the_game() {
+        let mut game = GameY::new(12).with_player_info(vec![PlayerInfo::new(
+            PlayerId::new(1),
+            "Bot",
+            PlayerKind::Bot("random_bot".to_string()),
+        )]);
+        // Cells on both sides of the 64-cell boundary of a bitboard word
+        for (player, index) in [(0, 0), (1, 63), (0, 64), (1, 77)] {
+            place(&mut game, player, index);
+        }
+        let snapshot = game.snapshot();
+        assert_eq!(YEN::from(&snapshot), YEN::from(&game));
+        assert_eq!(snapshot.status(), game.status());
+        assert_eq!(snapshot.move_count(), 4);
+        assert_eq!(snapshot.last_move(), game.last_move());
+        assert_eq!(snapshot.moves().collect::<Vec<_>>(), game.history().to_vec());
+        assert_eq!(snapshot.players(), game.players());
+        for cell in 0..game.total_cells() as usize {
+            assert_eq!(snapshot.occupant(cell), game.engine().occupant(cell), "cell {}", cell);
+        }
+        assert_eq!(snapshot.occupant(78), None);
+        assert_eq!(snapshot.occupant(1000), None);
+        for id in 0..2 {
+            let player = PlayerId::new(id);
+            assert_eq!(snapshot.sides_touched(player), game.sides_touched(player));
+            assert_eq!(snapshot.sides_touched_named(player), game.sides_touched_named(player));
+        }
+        assert_eq!(snapshot.engine().groups(PlayerId::new(0)), game.engine().groups(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_snapshot_does_not_follow_later_moves() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 0);
+        let snapshot = game.snapshot();
+        place(&mut game, 1, 1);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        assert_eq!(snapshot.move_count(), 1);
+        assert_eq!(snapshot.next_player(), Some(PlayerId::new(1)));
+        assert_eq!(snapshot.occupant(1), None);
+        assert_eq!(YEN::from(&snapshot).layout(), "B/../...");
+        assert_eq!(YEN::from(&game.snapshot()).result(), Some("R+resign"));
+    }
+}
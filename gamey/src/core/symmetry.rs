@@ -0,0 +1,156 @@
+//! Rotations and reflections of the board.
+//!
+//! The triangle has six symmetries: the identity, two rotations and three
+//! reflections. [`GameY::transform`] applies one to a whole game, which is
+//! useful to augment training data, to look positions up in an opening book
+//! and to show a board from another player's side.
+
+use crate::{CellIndex, Coordinates, GameY, Movement};
+
+/// A symmetry of the triangular board.
+///
+/// Each symmetry permutes the barycentric coordinates `(x, y, z)` of the cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// Leaves every cell in place.
+    Identity,
+    /// A third of a turn: `(x, y, z)` goes to `(y, z, x)`.
+    Rotate120,
+    /// Two thirds of a turn: `(x, y, z)` goes to `(z, x, y)`.
+    Rotate240,
+    /// Mirrors the board through the corner opposite side A, swapping `y` and `z`.
+    ReflectA,
+    /// Mirrors the board through the corner opposite side B, swapping `x` and `z`.
+    ReflectB,
+    /// Mirrors the board through the corner opposite side C, swapping `x` and `y`.
+    ReflectC,
+}
+
+impl Symmetry {
+    /// The six symmetries, in the order of [`crate::TriangularTopology::symmetries`].
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::Identity,
+        Symmetry::Rotate120,
+        Symmetry::Rotate240,
+        Symmetry::ReflectA,
+        Symmetry::ReflectB,
+        Symmetry::ReflectC,
+    ];
+
+    /// Returns the position of this symmetry in [`Symmetry::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the symmetry that undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate120 => Symmetry::Rotate240,
+            Symmetry::Rotate240 => Symmetry::Rotate120,
+            other => other,
+        }
+    }
+
+    /// Returns the image of `coords`.
+    pub fn apply(self, coords: Coordinates) -> Coordinates {
+        let (x, y, z) = (coords.x(), coords.y(), coords.z());
+        match self {
+            Symmetry::Identity => coords,
+            Symmetry::Rotate120 => Coordinates::new(y, z, x),
+            Symmetry::Rotate240 => Coordinates::new(z, x, y),
+            Symmetry::ReflectA => Coordinates::new(x, z, y),
+            Symmetry::ReflectB => Coordinates::new(z, y, x),
+            Symmetry::ReflectC => Coordinates::new(y, x, z),
+        }
+    }
+}
+
+impl GameY {
+    /// Returns this game with every stone and every move moved by `symmetry`.
+    ///
+    /// The game is replayed move by move on an empty board, so its engine,
+    /// status and history are those of a game played directly on the
+    /// transformed cells. Transforming by [`Symmetry::inverse`] gives back
+    /// the original game.
+    pub fn transform(&self, symmetry: Symmetry) -> GameY {
+        let perm: &[CellIndex] = self.engine().topology().symmetry(symmetry.index());
+        let size = self.board_size();
+        let mut game = self.empty_copy();
+        for movement in self.history().iter() {
+            let movement = match movement {
+                Movement::Placement { player, coords } => Movement::Placement {
+                    player: *player,
+                    coords: Coordinates::from_index(perm[coords.to_index(size) as usize] as u32, size),
+                },
+                action => action.clone(),
+            };
+            // The moves come from a game that accepted them, and symmetric cells are
+            // free and winning exactly when the original ones are
+            let _ = game.apply_move(movement);
+        }
+        if let Some(player) = self.lost_on_time() {
+            game.lose_on_time(player);
+        }
+        if let Some(player) = self.forfeited() {
+            game.forfeit(player);
+        }
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameStatus, PlayerId};
+
+    fn play(size: u32, cells: &[u32]) -> GameY {
+        let mut game = GameY::new(size);
+        for idx in cells {
+            let player = game.next_player().unwrap();
+            let coords = Coordinates::from_index(*idx, size);
+            game.add_move(Movement::Placement { player, coords }).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_apply_matches_the_topology_maps() {
+        let game = GameY::new(5);
+        for symmetry in Symmetry::ALL {
+            let perm = game.engine().topology().symmetry(symmetry.index());
+            for idx in 0..game.total_cells() {
+                let coords = Coordinates::from_index(idx, 5);
+                assert_eq!(symmetry.apply(coords).to_index(5), perm[idx as usize] as u32);
+                assert_eq!(symmetry.inverse().apply(symmetry.apply(coords)), coords);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform_then_inverse_is_the_identity() {
+        let game = play(5, &[4, 7, 12, 0, 3]);
+        for symmetry in Symmetry::ALL {
+            let moved = game.transform(symmetry);
+            assert_eq!(moved.history().len(), game.history().len());
+            assert!(moved.transform(symmetry.inverse()).same_history(&game), "{:?}", symmetry);
+        }
+        assert!(game.transform(Symmetry::Identity).same_history(&game));
+        assert_ne!(game.transform(Symmetry::Rotate120), game);
+    }
+
+    #[test]
+    fn test_win_survives_every_symmetry() {
+        // Player 0 fills side B (y = 0) from corner to corner
+        let game = play(3, &[0, 2, 1, 5, 3]);
+        let winner = GameStatus::Finished {
+            winner: PlayerId::new(0),
+            reason: crate::WinReason::Connection,
+        };
+        assert_eq!(game.status(), &winner);
+        for symmetry in Symmetry::ALL {
+            let moved = game.transform(symmetry);
+            assert_eq!(moved.status(), &winner, "{:?}", symmetry);
+            assert_eq!(moved.canonical_key(), game.canonical_key());
+        }
+    }
+}
@@ -3,26 +3,59 @@
 //! Gestiona el estado del juego y la conectividad (Union-Find) sin saber la forma del tablero.
 
 use crate::PlayerId;
-use super::{BoardTopology, CellIndex, RegionMask};
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use super::{BoardTopology, CellIndex, RegionMask, Zobrist};
 
 /// Estructura auxiliar para el algoritmo Union-Find.
 #[derive(Clone, Debug)]
-pub struct DisjointSet {
-    pub parent: usize,
+struct DisjointSet {
+    parent: usize,
     /// Máscara de bits de todas las regiones que este conjunto toca.
-    pub regions_touched: RegionMask,
+    regions_touched: RegionMask,
 }
 
 /// El Motor de Juego Genérico.
 #[derive(Clone, Debug)]
 pub struct GameEngine<T: BoardTopology> {
-    pub topology: T,
+    topology: T,
     /// Estado del tablero: Quién ocupa cada celda (None si está vacía).
-    pub state: Vec<Option<PlayerId>>,
+    state: Vec<Option<PlayerId>>,
     /// Estructura Union-Find para rastrear grupos conectados.
-    pub sets: Vec<DisjointSet>,
+    sets: Vec<DisjointSet>,
     /// Mapa que dice a qué conjunto (set) pertenece cada celda ocupada.
-    pub cell_set_map: Vec<Option<usize>>,
+    cell_set_map: Vec<Option<usize>>,
+    /// Tabla de claves para el hash incremental de la posición.
+    zobrist: Zobrist,
+    /// Hash Zobrist de la posición actual.
+    hash: u64,
+    /// Registro de movimientos para poder deshacerlos.
+    journal: Vec<MoveRecord>,
+    /// Celdas vacías en cualquier orden, como `u32` igual que `GameY::available_cells`.
+    /// Se quitan con `swap_remove` para que colocar, deshacer y muestrear sean O(1).
+    empty: Vec<u32>,
+    /// Posición de cada celda dentro de `empty`, o None si está ocupada.
+    empty_slot: Vec<Option<usize>>,
+}
+
+/// Error de las operaciones que editan piezas ya colocadas.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum EngineError {
+    /// La celda no existe en el tablero.
+    #[error("Celda fuera de límites: {0}")]
+    OutOfBounds(CellIndex),
+    /// La celda no tiene ninguna pieza que quitar.
+    #[error("Celda vacía: {0}")]
+    EmptyCell(CellIndex),
+}
+
+/// Lo necesario para deshacer un movimiento: la celda y los conjuntos modificados.
+#[derive(Clone, Debug)]
+struct MoveRecord {
+    cell: CellIndex,
+    player: PlayerId,
+    /// Valores anteriores de los conjuntos modificados por uniones y compresión de rutas.
+    changes: Vec<(usize, DisjointSet)>,
 }
 
 impl<T: BoardTopology> GameEngine<T> {
@@ -34,9 +67,87 @@ impl<T: BoardTopology> GameEngine<T> {
             state: vec![None; size],
             sets: Vec::new(),
             cell_set_map: vec![None; size],
+            zobrist: Zobrist::new(size),
+            hash: 0,
+            journal: Vec::new(),
+            empty: (0..size as u32).collect(),
+            empty_slot: (0..size).map(Some).collect(),
         }
     }
 
+    /// Devuelve la topología del tablero.
+    pub fn topology(&self) -> &T {
+        &self.topology
+    }
+
+    /// Devuelve el jugador que ocupa `cell`, o None si está vacía o fuera de límites.
+    pub fn occupant(&self, cell: CellIndex) -> Option<PlayerId> {
+        self.state.get(cell).copied().flatten()
+    }
+
+    /// Indica si `cell` existe y no tiene pieza.
+    pub fn is_empty(&self, cell: CellIndex) -> bool {
+        self.state.get(cell).is_some_and(Option::is_none)
+    }
+
+    /// Devuelve el ocupante de cada celda, en el orden de sus índices.
+    pub fn occupants(&self) -> &[Option<PlayerId>] {
+        &self.state
+    }
+
+    /// Acceso directo al estado para que las pruebas fabriquen tableros inconsistentes.
+    #[cfg(test)]
+    pub(crate) fn occupants_mut(&mut self) -> &mut Vec<Option<PlayerId>> {
+        &mut self.state
+    }
+
+    /// Devuelve las celdas vacías, en un orden que cambia con los movimientos.
+    pub fn empty_cells(&self) -> impl Iterator<Item = CellIndex> + '_ {
+        self.empty.iter().map(|cell| *cell as CellIndex)
+    }
+
+    /// Devuelve el número de celdas vacías.
+    pub fn empty_count(&self) -> usize {
+        self.empty.len()
+    }
+
+    /// Elige una celda vacía al azar en O(1), o None si el tablero está lleno.
+    pub fn random_empty<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<CellIndex> {
+        self.empty.choose(rng).map(|cell| *cell as CellIndex)
+    }
+
+    /// Las celdas vacías tal y como las guarda el motor, para `GameY::available_cells`.
+    pub(crate) fn empty_list(&self) -> &Vec<u32> {
+        &self.empty
+    }
+
+    /// Saca `cell` de las celdas vacías moviendo la última a su hueco.
+    fn take_empty(&mut self, cell: CellIndex) {
+        let Some(slot) = self.empty_slot[cell].take() else {
+            return;
+        };
+        self.empty.swap_remove(slot);
+        if let Some(moved) = self.empty.get(slot) {
+            self.empty_slot[*moved as CellIndex] = Some(slot);
+        }
+    }
+
+    /// Devuelve `cell` a las celdas vacías.
+    fn release_empty(&mut self, cell: CellIndex) {
+        if self.empty_slot[cell].is_none() {
+            self.empty_slot[cell] = Some(self.empty.len());
+            self.empty.push(cell as u32);
+        }
+    }
+
+    /// Devuelve el hash Zobrist de la posición actual.
+    ///
+    /// Dos posiciones con las mismas piedras tienen el mismo hash sin importar
+    /// el orden en que se jugaron.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
     /// Intenta realizar un movimiento en la celda especificada por el jugador.
     /// Devuelve Ok(true) si el movimiento ganó el juego, Ok(false) si no, o Err si es inválido.
     pub fn make_move(&mut self, cell: CellIndex, player: PlayerId) -> Result<bool, String> {
@@ -49,6 +160,13 @@ impl<T: BoardTopology> GameEngine<T> {
 
         // 1. Colocar Pieza
         self.state[cell] = Some(player);
+        self.take_empty(cell);
+        self.hash ^= self.zobrist.key(cell, player);
+        self.journal.push(MoveRecord {
+            cell,
+            player,
+            changes: Vec::new(),
+        });
 
         // 2. Crear nuevo Conjunto para esta pieza
         let regions = self.topology.get_cell_regions(cell);
@@ -62,16 +180,15 @@ impl<T: BoardTopology> GameEngine<T> {
         // 3. Conectar con vecinos del mismo jugador
         // Clonamos los vecinos para evitar problemas de préstamo (borrow checker)
         let neighbors = self.topology.get_neighbors(cell).to_vec();
+        let target = self.topology.winning_mask_for(player);
         let mut won = false;
 
         for neighbor in neighbors {
-            if let Some(p) = self.state[neighbor] {
-                if p == player {
-                    let neighbor_set_idx = self.cell_set_map[neighbor].unwrap();
-                    // Unir conjuntos y verificar si ganamos
-                    if self.union(new_set_idx, neighbor_set_idx) {
-                        won = true;
-                    }
+            if self.state[neighbor] == Some(player) {
+                let neighbor_set_idx = self.cell_set_map[neighbor].unwrap();
+                // Unir conjuntos y verificar si ganamos
+                if self.union(new_set_idx, neighbor_set_idx, target) {
+                    won = true;
                 }
             }
         }
@@ -79,7 +196,7 @@ impl<T: BoardTopology> GameEngine<T> {
         // Caso especial: Una sola pieza conecta todo (ej: tablero minúsculo)
         if !won {
              let root = self.find(new_set_idx);
-             if (self.sets[root].regions_touched & self.topology.winning_mask()) == self.topology.winning_mask() {
+             if (self.sets[root].regions_touched & target) == target {
                  won = true;
              }
         }
@@ -87,6 +204,167 @@ impl<T: BoardTopology> GameEngine<T> {
         Ok(won)
     }
 
+    /// Indica si una pieza de `player` en `cell` ganaría la partida, sin modificar el estado.
+    ///
+    /// Coincide con el resultado de `make_move` sobre el mismo estado. Devuelve
+    /// false si la celda está ocupada o fuera de límites.
+    pub fn would_win(&self, cell: CellIndex, player: PlayerId) -> bool {
+        if cell >= self.topology.total_cells() || self.state[cell].is_some() {
+            return false;
+        }
+        let target = self.topology.winning_mask_for(player);
+        let mut regions = self.topology.get_cell_regions(cell);
+        for &neighbor in self.topology.get_neighbors(cell) {
+            if self.state[neighbor] == Some(player) {
+                regions |= self.regions_of_group(neighbor).unwrap_or(0);
+            }
+        }
+        (regions & target) == target
+    }
+
+    /// Deshace el último movimiento y devuelve la celda y el jugador que lo hizo.
+    ///
+    /// Restaura el estado, los conjuntos Union-Find y el hash exactamente como
+    /// estaban antes del movimiento. Devuelve None si no hay movimientos.
+    pub fn undo_last(&mut self) -> Option<(CellIndex, PlayerId)> {
+        let record = self.journal.pop()?;
+        for (idx, previous) in record.changes.into_iter().rev() {
+            self.sets[idx] = previous;
+        }
+        if let Some(set) = self.cell_set_map[record.cell] {
+            self.sets.truncate(set);
+        }
+        self.state[record.cell] = None;
+        self.release_empty(record.cell);
+        self.cell_set_map[record.cell] = None;
+        self.hash ^= self.zobrist.key(record.cell, record.player);
+        Some((record.cell, record.player))
+    }
+
+    /// Quita la pieza de `cell`, sea de quien sea.
+    ///
+    /// Union-Find no sabe partir conjuntos, así que se reconstruyen los
+    /// conjuntos de todas las piezas del tablero: O(n·α(n)) para n celdas, de
+    /// sobra para los tamaños de tablero de Y. Después [`GameEngine::winner`]
+    /// refleja la posición nueva, que puede dejar de estar ganada. El registro
+    /// de movimientos se vacía, por lo que `undo_last` no puede deshacer
+    /// movimientos anteriores a la edición.
+    ///
+    /// # Errors
+    /// Devuelve `EngineError::OutOfBounds` si la celda no existe y
+    /// `EngineError::EmptyCell` si no tiene pieza.
+    pub fn remove_piece(&mut self, cell: CellIndex) -> Result<(), EngineError> {
+        if cell >= self.topology.total_cells() {
+            return Err(EngineError::OutOfBounds(cell));
+        }
+        let player = self.state[cell].ok_or(EngineError::EmptyCell(cell))?;
+        self.state[cell] = None;
+        self.release_empty(cell);
+        self.hash ^= self.zobrist.key(cell, player);
+        self.rebuild_sets();
+        Ok(())
+    }
+
+    /// Cambia el dueño de la pieza de `cell` a `new_player`.
+    ///
+    /// Equivale a [`GameEngine::remove_piece`] seguido de `make_move`, con el
+    /// mismo coste. Devuelve true si la pieza recoloreada gana la partida.
+    ///
+    /// # Errors
+    /// Los mismos que [`GameEngine::remove_piece`].
+    pub fn recolor_piece(&mut self, cell: CellIndex, new_player: PlayerId) -> Result<bool, EngineError> {
+        self.remove_piece(cell)?;
+        // La celda existe y acaba de quedar vacía, así que make_move no falla
+        let won = self.make_move(cell, new_player).unwrap_or(false);
+        self.journal.clear();
+        Ok(won)
+    }
+
+    /// Devuelve el jugador con un grupo que toca todas sus regiones objetivo, si lo hay.
+    pub fn winner(&self) -> Option<PlayerId> {
+        (0..self.state.len()).find_map(|cell| {
+            let player = self.state[cell]?;
+            let target = self.topology.winning_mask_for(player);
+            let regions = self.regions_of_group(cell)?;
+            ((regions & target) == target).then_some(player)
+        })
+    }
+
+    /// Rehace los conjuntos Union-Find a partir del estado del tablero y vacía el registro.
+    fn rebuild_sets(&mut self) {
+        self.sets.clear();
+        self.journal.clear();
+        self.cell_set_map.iter_mut().for_each(|set| *set = None);
+        for cell in 0..self.state.len() {
+            let Some(player) = self.state[cell] else {
+                continue;
+            };
+            let set = self.sets.len();
+            self.sets.push(DisjointSet {
+                parent: set,
+                regions_touched: self.topology.get_cell_regions(cell),
+            });
+            self.cell_set_map[cell] = Some(set);
+            let target = self.topology.winning_mask_for(player);
+            let neighbors = self.topology.get_neighbors(cell).to_vec();
+            for neighbor in neighbors {
+                if self.state[neighbor] == Some(player)
+                    && let Some(other) = self.cell_set_map[neighbor]
+                {
+                    self.union(set, other, target);
+                }
+            }
+        }
+    }
+
+    /// Guarda el valor actual de un conjunto en el registro del último movimiento.
+    fn record_change(&mut self, i: usize) {
+        if let Some(record) = self.journal.last_mut() {
+            record.changes.push((i, self.sets[i].clone()));
+        }
+    }
+
+    /// Devuelve las regiones que toca el grupo al que pertenece la celda, o None si está vacía.
+    pub fn regions_of_group(&self, cell: CellIndex) -> Option<RegionMask> {
+        let set = self.cell_set_map.get(cell).copied().flatten()?;
+        Some(self.sets[self.root_of(set)].regions_touched)
+    }
+
+    /// Indica si las celdas `a` y `b` están ocupadas y pertenecen al mismo grupo conectado.
+    pub fn same_group(&self, a: CellIndex, b: CellIndex) -> bool {
+        match (self.cell_set_map.get(a).copied().flatten(), self.cell_set_map.get(b).copied().flatten()) {
+            (Some(a), Some(b)) => self.root_of(a) == self.root_of(b),
+            _ => false,
+        }
+    }
+
+    /// Devuelve las regiones tocadas por cada grupo (componente conexa) del jugador.
+    pub fn groups(&self, player: PlayerId) -> Vec<RegionMask> {
+        let mut roots = Vec::new();
+        let mut result = Vec::new();
+        for (cell, occupant) in self.state.iter().enumerate() {
+            if *occupant != Some(player) {
+                continue;
+            }
+            if let Some(set) = self.cell_set_map[cell] {
+                let root = self.root_of(set);
+                if !roots.contains(&root) {
+                    roots.push(root);
+                    result.push(self.sets[root].regions_touched);
+                }
+            }
+        }
+        result
+    }
+
+    /// Encuentra la raíz de un conjunto sin modificar la estructura (sin compresión de ruta).
+    fn root_of(&self, mut i: usize) -> usize {
+        while self.sets[i].parent != i {
+            i = self.sets[i].parent;
+        }
+        i
+    }
+
     /// Encuentra el representante (raíz) del conjunto al que pertenece i (con compresión de ruta).
     fn find(&mut self, i: usize) -> usize {
         if self.sets[i].parent == i {
@@ -94,18 +372,23 @@ impl<T: BoardTopology> GameEngine<T> {
         } else {
             let parent = self.sets[i].parent;
             let root = self.find(parent);
-            self.sets[i].parent = root;
+            if parent != root {
+                self.record_change(i);
+                self.sets[i].parent = root;
+            }
             root
         }
     }
 
     /// Une dos conjuntos y actualiza las regiones tocadas.
-    /// Devuelve true si la unión resulta en una condición de victoria.
-    fn union(&mut self, i: usize, j: usize) -> bool {
+    /// Devuelve true si el conjunto resultante toca todas las regiones de `target`.
+    fn union(&mut self, i: usize, j: usize, target: RegionMask) -> bool {
         let root_i = self.find(i);
         let root_j = self.find(j);
 
         if root_i != root_j {
+            self.record_change(root_j);
+            self.record_change(root_i);
             // Unir J en I
             self.sets[root_j].parent = root_i;
             // Combinar las regiones tocadas (OR bit a bit)
@@ -113,7 +396,235 @@ impl<T: BoardTopology> GameEngine<T> {
         }
 
         // Verificar victoria
-        let target = self.topology.winning_mask();
         (self.sets[root_i].regions_touched & target) == target
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, TriangularTopology};
+
+    fn idx(x: u32, y: u32, z: u32, size: u32) -> CellIndex {
+        Coordinates::new(x, y, z).to_index(size) as CellIndex
+    }
+
+    #[test]
+    fn test_regions_of_group_of_empty_cell_is_none() {
+        let engine = GameEngine::new(TriangularTopology::new(3));
+        assert_eq!(engine.regions_of_group(0), None);
+    }
+
+    #[test]
+    fn test_accessors_follow_moves_and_undos() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        let p1 = PlayerId::new(1);
+        assert_eq!(engine.topology().total_cells(), 6);
+        engine.make_move(2, p1).unwrap();
+        assert_eq!(engine.occupant(2), Some(p1));
+        assert!(!engine.is_empty(2));
+        assert!(engine.is_empty(3));
+        assert_eq!(engine.occupants().iter().flatten().count(), 1);
+        assert_eq!(engine.regions_of_group(2), Some(engine.topology().get_cell_regions(2)));
+
+        engine.undo_last();
+        assert_eq!(engine.occupant(2), None);
+        assert!(engine.is_empty(2));
+        assert_eq!(engine.regions_of_group(2), None);
+    }
+
+    #[test]
+    fn test_accessors_outside_the_board() {
+        let engine = GameEngine::new(TriangularTopology::new(3));
+        // Una celda inexistente no tiene dueño, pero tampoco está vacía
+        assert_eq!(engine.occupant(6), None);
+        assert!(!engine.is_empty(6));
+        assert_eq!(engine.regions_of_group(6), None);
+    }
+
+    #[test]
+    fn test_groups_merge_regions() {
+        let mut engine = GameEngine::new(TriangularTopology::new(4));
+        let p0 = PlayerId::new(0);
+        // Dos piedras separadas y luego una que las une
+        engine.make_move(idx(3, 0, 0, 4), p0).unwrap();
+        engine.make_move(idx(1, 0, 2, 4), p0).unwrap();
+        assert_eq!(engine.groups(p0).len(), 2);
+
+        engine.make_move(idx(2, 0, 1, 4), p0).unwrap();
+        let groups = engine.groups(p0);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(Some(groups[0]), engine.regions_of_group(idx(3, 0, 0, 4)));
+    }
+
+    #[test]
+    fn test_groups_are_per_player() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        engine.make_move(0, PlayerId::new(0)).unwrap();
+        engine.make_move(1, PlayerId::new(1)).unwrap();
+        assert_eq!(engine.groups(PlayerId::new(0)).len(), 1);
+        assert_eq!(engine.groups(PlayerId::new(1)).len(), 1);
+        assert!(engine.groups(PlayerId::new(2)).is_empty());
+    }
+
+    #[test]
+    fn test_hash_is_independent_of_move_order() {
+        let p0 = PlayerId::new(0);
+        let p1 = PlayerId::new(1);
+        let mut a = GameEngine::new(TriangularTopology::new(4));
+        a.make_move(0, p0).unwrap();
+        a.make_move(4, p1).unwrap();
+        a.make_move(7, p0).unwrap();
+
+        let mut b = GameEngine::new(TriangularTopology::new(4));
+        b.make_move(7, p0).unwrap();
+        b.make_move(0, p0).unwrap();
+        b.make_move(4, p1).unwrap();
+
+        assert_eq!(a.hash(), b.hash());
+        assert_ne!(a.hash(), 0);
+    }
+
+    #[test]
+    fn test_different_positions_hash_differently() {
+        let mut a = GameEngine::new(TriangularTopology::new(4));
+        let mut b = GameEngine::new(TriangularTopology::new(4));
+        a.make_move(0, PlayerId::new(0)).unwrap();
+        b.make_move(0, PlayerId::new(1)).unwrap();
+        assert_ne!(a.hash(), b.hash());
+
+        let mut c = GameEngine::new(TriangularTopology::new(4));
+        c.make_move(1, PlayerId::new(0)).unwrap();
+        assert_ne!(a.hash(), c.hash());
+    }
+
+    #[test]
+    fn test_undo_restores_hash_and_groups() {
+        let mut engine = GameEngine::new(TriangularTopology::new(4));
+        let p0 = PlayerId::new(0);
+        engine.make_move(idx(3, 0, 0, 4), p0).unwrap();
+        engine.make_move(idx(1, 0, 2, 4), p0).unwrap();
+        let before = engine.hash();
+        let groups_before = engine.groups(p0);
+
+        // La piedra del medio une los dos grupos
+        engine.make_move(idx(2, 0, 1, 4), p0).unwrap();
+        assert_ne!(engine.hash(), before);
+        assert_eq!(engine.groups(p0).len(), 1);
+
+        assert_eq!(engine.undo_last(), Some((idx(2, 0, 1, 4), p0)));
+        assert_eq!(engine.hash(), before);
+        assert_eq!(engine.groups(p0), groups_before);
+        assert_eq!(engine.state[idx(2, 0, 1, 4)], None);
+        assert_eq!(engine.regions_of_group(idx(2, 0, 1, 4)), None);
+    }
+
+    #[test]
+    fn test_undo_everything_returns_to_empty() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        let empty = engine.hash();
+        engine.make_move(0, PlayerId::new(0)).unwrap();
+        engine.make_move(1, PlayerId::new(1)).unwrap();
+        engine.make_move(2, PlayerId::new(0)).unwrap();
+        while engine.undo_last().is_some() {}
+        assert_eq!(engine.hash(), empty);
+        assert!(engine.sets.is_empty());
+        assert!(engine.state.iter().all(|cell| cell.is_none()));
+        // El motor sigue siendo utilizable después de deshacer
+        assert_eq!(engine.make_move(0, PlayerId::new(0)), Ok(false));
+    }
+
+    #[test]
+    fn test_removing_a_stone_splits_the_group_and_unwins() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        let p0 = PlayerId::new(0);
+        engine.make_move(idx(2, 0, 0, 3), p0).unwrap();
+        engine.make_move(idx(1, 0, 1, 3), p0).unwrap();
+        assert_eq!(engine.make_move(idx(0, 0, 2, 3), p0), Ok(true));
+        assert_eq!(engine.winner(), Some(p0));
+
+        engine.remove_piece(idx(1, 0, 1, 3)).unwrap();
+        assert_eq!(engine.winner(), None);
+        assert_eq!(engine.groups(p0).len(), 2);
+        assert_eq!(engine.state[idx(1, 0, 1, 3)], None);
+        assert_eq!(engine.undo_last(), None);
+
+        // El hueco se puede volver a jugar y vuelve a ganar
+        assert_eq!(engine.make_move(idx(1, 0, 1, 3), p0), Ok(true));
+        assert_eq!(engine.winner(), Some(p0));
+    }
+
+    #[test]
+    fn test_recolor_piece_changes_owner_and_hash() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        engine.make_move(0, PlayerId::new(0)).unwrap();
+        let mut expected = GameEngine::new(TriangularTopology::new(3));
+        expected.make_move(0, PlayerId::new(1)).unwrap();
+
+        assert_eq!(engine.recolor_piece(0, PlayerId::new(1)), Ok(false));
+        assert_eq!(engine.state, expected.state);
+        assert_eq!(engine.hash(), expected.hash());
+        assert!(engine.groups(PlayerId::new(0)).is_empty());
+    }
+
+    #[test]
+    fn test_remove_piece_errors() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        assert_eq!(engine.remove_piece(0), Err(EngineError::EmptyCell(0)));
+        assert_eq!(engine.remove_piece(6), Err(EngineError::OutOfBounds(6)));
+        assert_eq!(engine.recolor_piece(0, PlayerId::new(1)), Err(EngineError::EmptyCell(0)));
+        assert_eq!(EngineError::OutOfBounds(6).to_string(), "Celda fuera de límites: 6");
+    }
+
+    /// Comprueba que las celdas vacías del motor son exactamente las de `state`.
+    fn assert_empty_cells_match_state<T: BoardTopology>(engine: &GameEngine<T>) {
+        let mut empty: Vec<CellIndex> = engine.empty_cells().collect();
+        empty.sort_unstable();
+        let expected: Vec<CellIndex> = (0..engine.state.len()).filter(|c| engine.state[*c].is_none()).collect();
+        assert_eq!(empty, expected);
+        assert_eq!(engine.empty_count(), expected.len());
+    }
+
+    #[test]
+    fn test_empty_cells_follow_moves_and_undos() {
+        let mut engine = GameEngine::new(TriangularTopology::new(4));
+        assert_eq!(engine.empty_count(), 10);
+        // Movimientos y deshacer intercalados, incluida la última celda de la lista
+        for (cell, player) in [(9, 0), (0, 1), (4, 0)] {
+            engine.make_move(cell, PlayerId::new(player)).unwrap();
+            assert_empty_cells_match_state(&engine);
+        }
+        engine.undo_last();
+        assert_empty_cells_match_state(&engine);
+        engine.make_move(5, PlayerId::new(0)).unwrap();
+        engine.make_move(4, PlayerId::new(1)).unwrap();
+        assert_empty_cells_match_state(&engine);
+        engine.remove_piece(0).unwrap();
+        assert_empty_cells_match_state(&engine);
+        engine.recolor_piece(9, PlayerId::new(1)).unwrap();
+        assert_empty_cells_match_state(&engine);
+        while engine.undo_last().is_some() {
+            assert_empty_cells_match_state(&engine);
+        }
+        assert_eq!(engine.empty_count(), 7);
+    }
+
+    #[test]
+    fn test_random_empty_only_returns_empty_cells() {
+        let mut engine = GameEngine::new(TriangularTopology::new(2));
+        let mut rng = rand::rng();
+        engine.make_move(0, PlayerId::new(0)).unwrap();
+        engine.make_move(2, PlayerId::new(1)).unwrap();
+        for _ in 0..20 {
+            assert_eq!(engine.random_empty(&mut rng), Some(1));
+        }
+        engine.make_move(1, PlayerId::new(0)).unwrap();
+        assert_eq!(engine.random_empty(&mut rng), None);
+    }
+
+    #[test]
+    fn test_undo_on_fresh_engine() {
+        let mut engine = GameEngine::new(TriangularTopology::new(3));
+        assert_eq!(engine.undo_last(), None);
+    }
+}
@@ -0,0 +1,143 @@
+//! Implementación de Topología Hexagonal (tablero de Hex).
+//!
+//! El tablero es un rombo de `size × size` celdas. La celda de la fila `r` y
+//! la columna `c` tiene el índice `r * size + c` y seis vecinos como máximo.
+
+use crate::PlayerId;
+use super::{BoardTopology, CellIndex, RegionInfo, RegionMask};
+use std::sync::Arc;
+
+/// Topología para un tablero de Hex.
+///
+/// El primer jugador debe unir el borde superior con el inferior y el
+/// segundo el izquierdo con el derecho.
+#[derive(Clone, Debug)]
+pub struct HexTopology {
+    pub size: u32,
+    /// Lista de adyacencia pre-calculada: adjacency[cell] = [vecino1, vecino2, ...]
+    adjacency: Arc<[Vec<CellIndex>]>,
+    /// Regiones pre-calculadas para cada celda
+    regions: Arc<[RegionMask]>,
+}
+
+impl HexTopology {
+    // Definición de bits para los bordes del rombo
+    pub(crate) const TOP: u32 = 1 << 0; // fila 0
+    pub(crate) const BOTTOM: u32 = 1 << 1; // última fila
+    pub(crate) const LEFT: u32 = 1 << 2; // columna 0
+    pub(crate) const RIGHT: u32 = 1 << 3; // última columna
+
+    /// Crea una nueva topología de Hex del tamaño dado.
+    /// Pre-calcula todos los vecinos y regiones para un acceso O(1) durante el juego.
+    pub fn new(size: u32) -> Self {
+        let n = size as i64;
+        let total_cells = (size * size) as usize;
+        let mut adjacency = vec![Vec::new(); total_cells];
+        let mut regions = vec![0; total_cells];
+
+        for row in 0..n {
+            for col in 0..n {
+                let idx = (row * n + col) as usize;
+
+                let mut mask = 0;
+                if row == 0 { mask |= Self::TOP; }
+                if row == n - 1 { mask |= Self::BOTTOM; }
+                if col == 0 { mask |= Self::LEFT; }
+                if col == n - 1 { mask |= Self::RIGHT; }
+                regions[idx] = mask;
+
+                let candidates = [
+                    (row - 1, col),
+                    (row - 1, col + 1),
+                    (row, col - 1),
+                    (row, col + 1),
+                    (row + 1, col - 1),
+                    (row + 1, col),
+                ];
+                for (r, c) in candidates {
+                    if (0..n).contains(&r) && (0..n).contains(&c) {
+                        adjacency[idx].push((r * n + c) as CellIndex);
+                    }
+                }
+            }
+        }
+
+        Self {
+            size,
+            adjacency: adjacency.into(),
+            regions: regions.into(),
+        }
+    }
+}
+
+impl BoardTopology for HexTopology {
+    fn total_cells(&self) -> usize {
+        (self.size * self.size) as usize
+    }
+
+    fn get_neighbors(&self, cell: CellIndex) -> &[CellIndex] {
+        &self.adjacency[cell]
+    }
+
+    fn get_cell_regions(&self, cell: CellIndex) -> RegionMask {
+        self.regions[cell]
+    }
+
+    /// Los cuatro bordes; cada jugador solo necesita dos, ver [`BoardTopology::winning_mask_for`].
+    fn winning_mask(&self) -> RegionMask {
+        Self::TOP | Self::BOTTOM | Self::LEFT | Self::RIGHT
+    }
+
+    fn winning_mask_for(&self, player: PlayerId) -> RegionMask {
+        if player.id().is_multiple_of(2) {
+            Self::TOP | Self::BOTTOM
+        } else {
+            Self::LEFT | Self::RIGHT
+        }
+    }
+
+    fn regions(&self) -> Vec<RegionInfo> {
+        [
+            (Self::TOP, "top"),
+            (Self::BOTTOM, "bottom"),
+            (Self::LEFT, "left"),
+            (Self::RIGHT, "right"),
+        ]
+        .into_iter()
+        .map(|(mask, name)| RegionInfo { mask, name: name.to_string() })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let topology = HexTopology::new(3);
+        // Una esquina aguda y una obtusa
+        assert_eq!(topology.get_neighbors(0), &[1, 3]);
+        assert_eq!(topology.get_neighbors(2), &[1, 4, 5]);
+        // Centro
+        assert_eq!(topology.get_neighbors(4), &[1, 2, 3, 5, 6, 7]);
+        for cell in 0..topology.total_cells() {
+            for neighbor in topology.get_neighbors(cell) {
+                assert!(topology.get_neighbors(*neighbor).contains(&cell));
+            }
+        }
+    }
+
+    #[test]
+    fn test_regions_and_corners() {
+        let topology = HexTopology::new(4);
+        for region in topology.regions() {
+            assert_eq!(topology.cells_in_region(region.mask).len(), 4, "{}", region.name);
+        }
+        assert_eq!(topology.corner_cells(), vec![0, 3, 12, 15]);
+        assert_ne!(
+            topology.winning_mask_for(PlayerId::new(0)),
+            topology.winning_mask_for(PlayerId::new(1))
+        );
+    }
+}
@@ -1,13 +1,20 @@
 //! Módulo de Topología del Tablero.
 //!
 //! Este módulo define la abstracción de un tablero (`BoardTopology`) y proporciona
-//! implementaciones concretas (como `TriangularTopology`) y un motor de juego genérico (`GameEngine`).
+//! implementaciones concretas (`TriangularTopology` para Y y `HexTopology` para Hex) y un
+//! motor de juego genérico (`GameEngine`).
 
 pub mod engine;
+pub mod hex;
 pub mod triangular;
+pub mod zobrist;
 
 pub use engine::*;
+pub use hex::*;
 pub use triangular::*;
+pub use zobrist::*;
+
+use crate::PlayerId;
 
 /// Representa un identificador único para una celda en el tablero.
 pub type CellIndex = usize;
@@ -16,6 +23,15 @@ pub type CellIndex = usize;
 /// Usamos una máscara de bits para eficiencia.
 pub type RegionMask = u32;
 
+/// Una región del tablero que cuenta para ganar, por ejemplo un lado del triángulo.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionInfo {
+    /// El bit de la región en las máscaras de [`BoardTopology::get_cell_regions`].
+    pub mask: RegionMask,
+    /// Nombre corto de la región, por ejemplo "A".
+    pub name: String,
+}
+
 /// El Contrato (Trait): Cualquier forma de tablero debe implementar esto.
 pub trait BoardTopology {
     /// Devuelve el número total de celdas en el tablero.
@@ -29,4 +45,29 @@ pub trait BoardTopology {
 
     /// Define la combinación de regiones necesaria para ganar.
     fn winning_mask(&self) -> RegionMask;
+
+    /// Define las regiones que debe conectar un jugador concreto.
+    ///
+    /// Por defecto todos los jugadores comparten el objetivo de [`BoardTopology::winning_mask`].
+    fn winning_mask_for(&self, _player: PlayerId) -> RegionMask {
+        self.winning_mask()
+    }
+
+    /// Devuelve las regiones del tablero que cuentan para ganar, con su nombre.
+    fn regions(&self) -> Vec<RegionInfo>;
+
+    /// Devuelve las celdas que pertenecen a alguna de las regiones de `mask`, en orden.
+    fn cells_in_region(&self, mask: RegionMask) -> Vec<CellIndex> {
+        (0..self.total_cells())
+            .filter(|cell| self.get_cell_regions(*cell) & mask != 0)
+            .collect()
+    }
+
+    /// Devuelve las esquinas: las celdas que tocan al menos dos regiones ganadoras.
+    fn corner_cells(&self) -> Vec<CellIndex> {
+        let winning = self.winning_mask();
+        (0..self.total_cells())
+            .filter(|cell| (self.get_cell_regions(*cell) & winning).count_ones() >= 2)
+            .collect()
+    }
 }
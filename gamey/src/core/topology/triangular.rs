@@ -2,17 +2,25 @@
 //!
 //! Traduce la geometría de coordenadas (x, y, z) a un grafo de celdas conectadas.
 
-use crate::Coordinates;
-use super::{BoardTopology, CellIndex, RegionMask};
+use crate::{Coordinates, PlayerId};
+use super::{BoardTopology, CellIndex, RegionInfo, RegionMask};
+use std::sync::Arc;
 
 /// Topología para un tablero triangular regular.
+///
+/// Las tablas pre-calculadas se comparten con `Arc`, así que clonar la
+/// topología (y con ella el motor y la partida) no las copia.
 #[derive(Clone, Debug)]
 pub struct TriangularTopology {
     pub size: u32,
+    /// Número de jugadores; con más de dos cada jugador tiene su propio par de lados
+    players: u32,
     /// Lista de adyacencia pre-calculada: adjacency[cell] = [vecino1, vecino2, ...]
-    adjacency: Vec<Vec<CellIndex>>,
+    adjacency: Arc<[Vec<CellIndex>]>,
     /// Regiones pre-calculadas para cada celda
-    regions: Vec<RegionMask>,
+    regions: Arc<[RegionMask]>,
+    /// Simetrías pre-calculadas como permutaciones de índices de celda
+    symmetries: Arc<[Vec<CellIndex>]>,
 }
 
 impl TriangularTopology {
@@ -21,10 +29,23 @@ impl TriangularTopology {
     const SIDE_B: u32 = 1 << 1; // y=0
     const SIDE_C: u32 = 1 << 2; // z=0
 
+    /// Devuelve el nombre del lado con el bit `side`: "A", "B" o "C", o None si no es un lado.
+    pub fn side_name(side: RegionMask) -> Option<&'static str> {
+        match side {
+            Self::SIDE_A => Some("A"),
+            Self::SIDE_B => Some("B"),
+            Self::SIDE_C => Some("C"),
+            _ => None,
+        }
+    }
+
     /// Crea una nueva topología triangular del tamaño dado.
     /// Pre-calcula todos los vecinos y regiones para un acceso O(1) durante el juego.
+    ///
+    /// # Panics
+    /// Si el número de celdas no cabe en un `u32`.
     pub fn new(size: u32) -> Self {
-        let total_cells = (size * (size + 1)) / 2;
+        let total_cells = triangle_cells(size).expect("board size too large");
         let mut adjacency = vec![Vec::new(); total_cells as usize];
         let mut regions = vec![0; total_cells as usize];
 
@@ -64,17 +85,84 @@ impl TriangularTopology {
             }
         }
 
+        // 3. Calcular simetrías: cada permutación de (x, y, z) es una rotación o reflexión
+        let symmetries: Vec<Vec<CellIndex>> = SYMMETRY_AXES
+            .iter()
+            .map(|axes| {
+                (0..total_cells)
+                    .map(|idx| {
+                        let c = Coordinates::from_index(idx, size);
+                        let xyz = [c.x(), c.y(), c.z()];
+                        let image = Coordinates::new(xyz[axes[0]], xyz[axes[1]], xyz[axes[2]]);
+                        image.to_index(size) as CellIndex
+                    })
+                    .collect()
+            })
+            .collect();
+
         Self {
             size,
-            adjacency,
-            regions,
+            players: 2,
+            adjacency: adjacency.into(),
+            regions: regions.into(),
+            symmetries: symmetries.into(),
+        }
+    }
+
+    /// Crea una topología para una partida de `players` jugadores.
+    ///
+    /// Con dos jugadores ambos deben conectar los tres lados. Con más, el
+    /// jugador `p` debe conectar los lados `p % 3` y `(p + 1) % 3`, y las
+    /// esquinas no cuentan como lado (si no, una sola piedra uniría dos lados).
+    pub fn with_players(size: u32, players: u32) -> Self {
+        let mut topology = Self::new(size);
+        topology.players = players;
+        if players > 2 {
+            topology.regions = topology
+                .regions
+                .iter()
+                .map(|region| if region.count_ones() > 1 { 0 } else { *region })
+                .collect();
         }
+        topology
+    }
+
+    /// Devuelve las 6 simetrías del tablero (3 rotaciones × reflexión).
+    ///
+    /// Cada simetría es una permutación `perm` donde `perm[celda]` es la imagen
+    /// de la celda. La primera es siempre la identidad.
+    pub fn symmetries(&self) -> Vec<Vec<CellIndex>> {
+        self.symmetries.to_vec()
+    }
+
+    /// Devuelve la simetría número `index` (de 0 a 5) en el orden de [`TriangularTopology::symmetries`].
+    ///
+    /// # Panics
+    /// Si `index` es 6 o mayor.
+    pub fn symmetry(&self, index: usize) -> &[CellIndex] {
+        &self.symmetries[index]
     }
 }
 
+/// Número de celdas de un triángulo de lado `size`, o `None` si no cabe en un `u32`.
+fn triangle_cells(size: u32) -> Option<u32> {
+    let size = size as u64;
+    u32::try_from(size * (size + 1) / 2).ok()
+}
+
+/// Orden de las coordenadas (x, y, z) para cada simetría; la primera es la identidad.
+const SYMMETRY_AXES: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [0, 2, 1],
+    [2, 1, 0],
+    [1, 0, 2],
+];
+
 impl BoardTopology for TriangularTopology {
     fn total_cells(&self) -> usize {
-        ((self.size * (self.size + 1)) / 2) as usize
+        self.adjacency.len()
     }
 
     fn get_neighbors(&self, cell: CellIndex) -> &[CellIndex] {
@@ -89,4 +177,123 @@ impl BoardTopology for TriangularTopology {
         // Para ganar en Y, necesitas tocar los 3 lados
         Self::SIDE_A | Self::SIDE_B | Self::SIDE_C
     }
+
+    fn winning_mask_for(&self, player: PlayerId) -> RegionMask {
+        if self.players <= 2 {
+            return self.winning_mask();
+        }
+        let sides = [Self::SIDE_A, Self::SIDE_B, Self::SIDE_C];
+        let p = player.id() as usize;
+        sides[p % 3] | sides[(p + 1) % 3]
+    }
+
+    /// Los tres lados: "A" (x = 0), "B" (y = 0) y "C" (z = 0).
+    ///
+    /// Con más de dos jugadores las esquinas no pertenecen a ningún lado.
+    fn regions(&self) -> Vec<RegionInfo> {
+        [Self::SIDE_A, Self::SIDE_B, Self::SIDE_C]
+            .into_iter()
+            .filter_map(|mask| Some(RegionInfo { mask, name: Self::side_name(mask)?.to_string() }))
+            .collect()
+    }
+
+    /// Las tres esquinas del triángulo, aunque con más de dos jugadores no cuenten como lado.
+    fn corner_cells(&self) -> Vec<CellIndex> {
+        (0..self.total_cells())
+            .filter(|cell| {
+                let c = Coordinates::from_index(*cell as u32, self.size);
+                [c.x(), c.y(), c.z()].iter().filter(|v| **v == 0).count() >= 2
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_players_need_all_sides() {
+        let topology = TriangularTopology::new(4);
+        assert_eq!(topology.winning_mask_for(PlayerId::new(0)), 0b111);
+        assert_eq!(topology.winning_mask_for(PlayerId::new(1)), 0b111);
+    }
+
+    #[test]
+    fn test_three_players_need_two_sides_each() {
+        let topology = TriangularTopology::with_players(4, 3);
+        let masks: Vec<_> = (0..3).map(|p| topology.winning_mask_for(PlayerId::new(p))).collect();
+        assert_eq!(masks, vec![0b011, 0b110, 0b101]);
+        // Corners touch no side in multi-player games
+        assert_eq!(topology.get_cell_regions(0), 0);
+        assert_eq!(topology.get_cell_regions(1), TriangularTopology::SIDE_B);
+    }
+
+    #[test]
+    fn test_six_symmetries_starting_with_identity() {
+        let topology = TriangularTopology::new(4);
+        let symmetries = topology.symmetries();
+        assert_eq!(symmetries.len(), 6);
+        assert_eq!(symmetries[0], (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_symmetries_are_permutations() {
+        let topology = TriangularTopology::new(5);
+        for perm in topology.symmetries() {
+            let mut sorted = perm.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..15).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_symmetries_preserve_adjacency() {
+        let topology = TriangularTopology::new(5);
+        for perm in topology.symmetries() {
+            for cell in 0..topology.total_cells() {
+                let mut expected: Vec<_> = topology.get_neighbors(cell).iter().map(|&n| perm[n]).collect();
+                let mut actual = topology.get_neighbors(perm[cell]).to_vec();
+                expected.sort();
+                actual.sort();
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn test_corners_map_to_corners() {
+        let topology = TriangularTopology::new(3);
+        let corners = [0, 3, 5];
+        for perm in topology.symmetries() {
+            for corner in corners {
+                assert!(corners.contains(&perm[corner]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_regions_sides_and_corners() {
+        let topology = TriangularTopology::new(5);
+        let regions = topology.regions();
+        let names: Vec<&str> = regions.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["A", "B", "C"]);
+        let all = regions.iter().fold(0, |mask, r| mask | r.mask);
+        assert_eq!(all, topology.winning_mask());
+
+        for region in &regions {
+            let cells = topology.cells_in_region(region.mask);
+            assert_eq!(cells.len(), 5, "side {}", region.name);
+            assert!(cells.iter().all(|c| topology.get_cell_regions(*c) & region.mask != 0));
+        }
+        assert_eq!(topology.cells_in_region(all).len(), 12);
+        assert_eq!(topology.corner_cells(), vec![0, 10, 14]);
+    }
+
+    #[test]
+    fn test_corners_without_sides_with_three_players() {
+        let topology = TriangularTopology::with_players(5, 3);
+        assert_eq!(topology.corner_cells(), vec![0, 10, 14]);
+        assert_eq!(topology.cells_in_region(TriangularTopology::SIDE_A).len(), 3);
+    }
 }
@@ -0,0 +1,77 @@
+//! Hashing Zobrist de posiciones.
+//!
+//! Cada par (celda, jugador) recibe un número aleatorio de 64 bits; el hash de
+//! una posición es el XOR de los números de todas las piedras colocadas, de
+//! modo que se actualiza en O(1) al colocar o retirar una piedra.
+
+use crate::PlayerId;
+use super::CellIndex;
+use std::sync::Arc;
+
+/// Número de jugadores para los que se generan claves.
+const ZOBRIST_PLAYERS: usize = 2;
+
+/// Tabla de claves Zobrist para un tablero concreto.
+///
+/// Las claves se comparten entre clones.
+#[derive(Clone, Debug)]
+pub struct Zobrist {
+    /// keys[celda * ZOBRIST_PLAYERS + jugador]
+    keys: Arc<[u64]>,
+}
+
+impl Zobrist {
+    /// Genera la tabla para `total_cells` celdas.
+    ///
+    /// La semilla depende solo del número de celdas, así que dos motores del
+    /// mismo tamaño producen siempre los mismos hashes.
+    pub fn new(total_cells: usize) -> Self {
+        let mut state = 0x9E37_79B9_7F4A_7C15 ^ total_cells as u64;
+        let keys = (0..total_cells * ZOBRIST_PLAYERS)
+            .map(|_| splitmix64(&mut state))
+            .collect();
+        Self { keys }
+    }
+
+    /// Devuelve la clave de una piedra de `player` en `cell`.
+    pub fn key(&self, cell: CellIndex, player: PlayerId) -> u64 {
+        let player = player.id() as usize;
+        match self.keys.get(cell * ZOBRIST_PLAYERS + player) {
+            Some(key) if player < ZOBRIST_PLAYERS => *key,
+            // Jugadores sin clave propia: derivamos una a partir de la del jugador 0
+            _ => self.keys[cell * ZOBRIST_PLAYERS].rotate_left(player as u32 * 7 + 1),
+        }
+    }
+}
+
+/// Generador SplitMix64: rápido, determinista y con buena dispersión de bits.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_tables_are_deterministic() {
+        let a = Zobrist::new(15);
+        let b = Zobrist::new(15);
+        assert_eq!(a.key(3, PlayerId::new(1)), b.key(3, PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_keys_are_distinct() {
+        let table = Zobrist::new(21);
+        let keys: HashSet<u64> = (0..21)
+            .flat_map(|cell| (0..3).map(move |p| (cell, p)))
+            .map(|(cell, p)| table.key(cell, PlayerId::new(p)))
+            .collect();
+        assert_eq!(keys.len(), 63);
+    }
+}
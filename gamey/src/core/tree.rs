@@ -0,0 +1,440 @@
+//! Variation trees for analysing games.
+//!
+//! A [`GameY`] only keeps the moves that were played. A [`GameTree`] also
+//! keeps the alternatives tried while analysing it: every node is a move,
+//! its children are the different answers to it and the first child
+//! continues the main line. The tree is built from a game with
+//! [`GameTree::from_game`] and turned back into one with
+//! [`GameTree::to_game`]; the game itself stays linear.
+//!
+//! Trees are written with [`crate::tree_to_sgf`], where variations are
+//! native, and [`crate::write_tree_record`].
+
+use crate::{Coordinates, GameY, MoveAnnotation, Movement, Result};
+
+/// Index of a node in a [`GameTree`].
+pub type NodeId = usize;
+
+/// A move of a [`GameTree`] with its comment, marks and evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    movement: Option<Movement>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    /// A comment on the position after the move.
+    pub comment: Option<String>,
+    /// Cells to highlight in the position after the move.
+    pub marks: Vec<Coordinates>,
+    /// An evaluation of the position after the move.
+    pub eval: Option<f32>,
+}
+
+impl TreeNode {
+    fn new(movement: Option<Movement>, parent: Option<NodeId>) -> Self {
+        Self {
+            movement,
+            parent,
+            children: Vec::new(),
+            comment: None,
+            marks: Vec::new(),
+            eval: None,
+        }
+    }
+
+    /// Returns the move leading to this node, `None` for the root.
+    pub fn movement(&self) -> Option<&Movement> {
+        self.movement.as_ref()
+    }
+
+    /// Returns the node before this one, `None` for the root.
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// Returns the moves played after this one, the main line first.
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    /// Returns the comment, marks and evaluation as an annotation, if there is any.
+    pub fn annotation(&self) -> Option<MoveAnnotation> {
+        let annotation = MoveAnnotation {
+            comment: self.comment.clone().unwrap_or_default(),
+            marks: self.marks.clone(),
+            eval: self.eval,
+        };
+        (!annotation.is_empty()).then_some(annotation)
+    }
+
+    /// Replaces the comment, marks and evaluation with those of `annotation`.
+    pub fn set_annotation(&mut self, annotation: MoveAnnotation) {
+        self.comment = Some(annotation.comment).filter(|comment| !comment.is_empty());
+        self.marks = annotation.marks;
+        self.eval = annotation.eval;
+    }
+}
+
+/// The moves of a game and their variations, with a cursor on one position.
+///
+/// The root is the empty board and every other node is a legal move in the
+/// position of its parent. Adding a move that is already a child of the
+/// current node moves the cursor there instead of duplicating it.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameTree, Movement, PlayerId};
+///
+/// let place = |player, x, y, z| Movement::Placement {
+///     player: PlayerId::new(player),
+///     coords: Coordinates::new(x, y, z),
+/// };
+/// let mut tree = GameTree::new(3);
+/// tree.add_move(place(0, 2, 0, 0)).unwrap();
+/// tree.add_move(place(1, 0, 2, 0)).unwrap();
+/// tree.back();
+/// tree.add_move(place(1, 0, 0, 2)).unwrap();
+/// assert_eq!(tree.node(tree.root()).unwrap().children().len(), 1);
+/// assert_eq!(tree.to_game().move_count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    start: GameY,
+    nodes: Vec<TreeNode>,
+    cursor: NodeId,
+    current: GameY,
+}
+
+impl GameTree {
+    /// Creates a tree with only the empty two-player board of `size`.
+    ///
+    /// # Panics
+    /// Panics if `size` is not a valid board size, like [`GameY::new`].
+    pub fn new(size: u32) -> Self {
+        Self::from_game(&GameY::new(size))
+    }
+
+    /// Creates a tree whose only line is the history of `game`, with the
+    /// cursor at its last move.
+    ///
+    /// The tree starts from an empty board with the size and players of
+    /// `game`. The annotations of the game go to the nodes of their moves.
+    pub fn from_game(game: &GameY) -> Self {
+        let start = game.empty_copy();
+        let mut tree = Self {
+            current: start.clone(),
+            start,
+            nodes: vec![TreeNode::new(None, None)],
+            cursor: 0,
+        };
+        for movement in game.history().iter() {
+            let id = tree.push_node(movement.clone());
+            // The moves come from a game that accepted them
+            let _ = tree.current.apply_move(movement.clone());
+            tree.cursor = id;
+        }
+        for (&ply, annotation) in game.annotations() {
+            // Node ids of a single line are the plies
+            if let Some(node) = tree.nodes.get_mut(ply) {
+                node.set_annotation(annotation.clone());
+            }
+        }
+        tree
+    }
+
+    /// Returns the game of the main line, following the first child of every
+    /// node, with the annotations of its nodes.
+    pub fn to_game(&self) -> GameY {
+        let line = self.main_line();
+        let mut game = self.game_at(*line.last().unwrap_or(&self.root()));
+        for (ply, node) in std::iter::once(self.root()).chain(line).enumerate() {
+            if let Some(annotation) = self.nodes[node].annotation() {
+                // The marks were checked when they were set on the game or read
+                let _ = game.annotate(ply, annotation);
+            }
+        }
+        game
+    }
+
+    /// Returns the id of the root, the empty board.
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    /// Returns the number of nodes, counting the root.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the tree has no moves.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
+    }
+
+    /// Returns the size of the board.
+    pub fn board_size(&self) -> u32 {
+        self.start.board_size()
+    }
+
+    /// Returns the empty game the tree starts from, with its players.
+    pub fn start(&self) -> &GameY {
+        &self.start
+    }
+
+    /// Returns the nodes in the order they were added, starting with the root.
+    ///
+    /// A parent always comes before its children.
+    pub fn nodes(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    /// Returns the node `id`, if it exists.
+    pub fn node(&self, id: NodeId) -> Option<&TreeNode> {
+        self.nodes.get(id)
+    }
+
+    /// Returns the node `id` to change its comment or evaluation.
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut TreeNode> {
+        self.nodes.get_mut(id)
+    }
+
+    /// Returns the id of the node under the cursor.
+    pub fn cursor(&self) -> NodeId {
+        self.cursor
+    }
+
+    /// Returns the node under the cursor.
+    pub fn current_node(&self) -> &TreeNode {
+        &self.nodes[self.cursor]
+    }
+
+    /// Returns the node under the cursor to change its comment or evaluation.
+    pub fn current_node_mut(&mut self) -> &mut TreeNode {
+        &mut self.nodes[self.cursor]
+    }
+
+    /// Returns the position under the cursor.
+    pub fn game(&self) -> &GameY {
+        &self.current
+    }
+
+    /// Plays `movement` in the position under the cursor and moves the cursor to it.
+    ///
+    /// If the move is already a child of the current node, the cursor moves
+    /// to that child and the tree is unchanged.
+    ///
+    /// # Errors
+    /// Returns the error of [`GameY::add_move`] if the move cannot be played.
+    /// Like there, the turn is not checked; see [`GameY::check_player_turn`].
+    pub fn add_move(&mut self, movement: Movement) -> Result<NodeId> {
+        self.current.add_move(movement.clone())?;
+        let existing = self.nodes[self.cursor]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].movement.as_ref() == Some(&movement));
+        self.cursor = match existing {
+            Some(child) => child,
+            None => self.push_node(movement),
+        };
+        Ok(self.cursor)
+    }
+
+    /// Moves the cursor to the parent of the current node. Returns `false` at the root.
+    pub fn back(&mut self) -> bool {
+        match self.nodes[self.cursor].parent {
+            Some(parent) => self.goto(parent),
+            None => false,
+        }
+    }
+
+    /// Moves the cursor along the main line. Returns `false` at the end of a line.
+    pub fn forward(&mut self) -> bool {
+        self.enter_variation(0)
+    }
+
+    /// Moves the cursor to the child `index` of the current node, where 0 is
+    /// the main line. Returns `false` if there is no such child.
+    pub fn enter_variation(&mut self, index: usize) -> bool {
+        match self.nodes[self.cursor].children.get(index) {
+            Some(&child) => self.goto(child),
+            None => false,
+        }
+    }
+
+    /// Moves the cursor to the root.
+    pub fn to_root(&mut self) {
+        self.goto(self.root());
+    }
+
+    /// Moves the cursor to node `id`. Returns `false` if there is no such node.
+    pub fn goto(&mut self, id: NodeId) -> bool {
+        if id >= self.nodes.len() {
+            return false;
+        }
+        self.current = self.game_at(id);
+        self.cursor = id;
+        true
+    }
+
+    /// Returns the nodes from the root to `id`, without the root.
+    pub fn path(&self, id: NodeId) -> Vec<NodeId> {
+        let mut path = Vec::new();
+        let mut current = self.nodes.get(id).map(|_| id);
+        while let Some(node) = current.filter(|&node| node != self.root()) {
+            path.push(node);
+            current = self.nodes[node].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Returns the nodes of the main line, without the root.
+    pub fn main_line(&self) -> Vec<NodeId> {
+        let mut line = Vec::new();
+        let mut node = self.root();
+        while let Some(&child) = self.nodes[node].children.first() {
+            line.push(child);
+            node = child;
+        }
+        line
+    }
+
+    /// Returns the game after the moves from the root to `id`.
+    pub fn game_at(&self, id: NodeId) -> GameY {
+        let mut game = self.start.clone();
+        for node in self.path(id) {
+            if let Some(movement) = &self.nodes[node].movement {
+                // Every move was legal when it was added
+                let _ = game.apply_move(movement.clone());
+            }
+        }
+        game
+    }
+
+    /// Adds `movement` as the last child of the cursor and returns its id.
+    fn push_node(&mut self, movement: Movement) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(TreeNode::new(Some(movement), Some(self.cursor)));
+        self.nodes[self.cursor].children.push(id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, GameYError, PlayerId};
+
+    fn place(player: u32, x: u32, y: u32, z: u32) -> Movement {
+        Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        }
+    }
+
+    /// A game of four moves with a second answer to the third one.
+    fn tree_with_variation() -> GameTree {
+        let game = GameY::from_moves(
+            4,
+            &[place(0, 3, 0, 0), place(1, 0, 3, 0), place(0, 1, 1, 1), place(1, 0, 0, 3)],
+        )
+        .unwrap();
+        let mut tree = GameTree::from_game(&game);
+        tree.goto(tree.main_line()[2]);
+        tree.current_node_mut().comment = Some("the center".to_string());
+        tree.add_move(place(1, 2, 1, 0)).unwrap();
+        tree.current_node_mut().eval = Some(-0.5);
+        tree.add_move(place(0, 0, 1, 2)).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_from_game_keeps_the_history() {
+        let game = GameY::from_moves(3, &[place(0, 2, 0, 0), place(1, 0, 2, 0)]).unwrap();
+        let tree = GameTree::from_game(&game);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.cursor(), 2);
+        assert_eq!(tree.game().history(), game.history());
+        assert_eq!(tree.to_game().history(), game.history());
+        assert!(GameTree::new(3).is_empty());
+    }
+
+    #[test]
+    fn test_two_variations_at_ply_three() {
+        let mut tree = tree_with_variation();
+        let third = tree.main_line()[2];
+        assert_eq!(tree.node(third).unwrap().children().len(), 2);
+        assert_eq!(tree.game().history().len(), 5);
+
+        // The main line is unchanged
+        assert_eq!(tree.to_game().history().len(), 4);
+        assert_eq!(tree.to_game().history()[3], place(1, 0, 0, 3));
+
+        // Walk the main line from the root
+        tree.to_root();
+        while tree.forward() {}
+        assert_eq!(tree.game().history(), tree.to_game().history());
+
+        // Back to ply 3 and into the variation
+        assert!(tree.back());
+        assert_eq!(tree.current_node().comment.as_deref(), Some("the center"));
+        assert!(tree.enter_variation(1));
+        assert_eq!(tree.current_node().movement(), Some(&place(1, 2, 1, 0)));
+        assert_eq!(tree.current_node().eval, Some(-0.5));
+        assert!(tree.forward());
+        assert!(!tree.forward());
+        assert_eq!(tree.game().history().len(), 5);
+        assert_eq!(tree.path(tree.cursor()).len(), 5);
+        assert!(!tree.enter_variation(2));
+    }
+
+    #[test]
+    fn test_adding_an_existing_move_follows_it() {
+        let mut tree = tree_with_variation();
+        let len = tree.len();
+        tree.to_root();
+        let first = tree.main_line()[0];
+        assert_eq!(tree.add_move(place(0, 3, 0, 0)).unwrap(), first);
+        assert_eq!(tree.len(), len);
+    }
+
+    #[test]
+    fn test_illegal_moves_are_rejected() {
+        let mut tree = tree_with_variation();
+        tree.to_root();
+        tree.forward();
+        assert!(matches!(
+            tree.add_move(place(1, 3, 0, 0)),
+            Err(GameYError::Occupied { .. })
+        ));
+        assert_eq!(tree.cursor(), tree.main_line()[0]);
+        assert!(!tree.goto(tree.len()));
+    }
+
+    #[test]
+    fn test_annotations_move_between_game_and_tree() {
+        let mut game = GameY::from_moves(3, &[place(0, 2, 0, 0), place(1, 0, 2, 0)]).unwrap();
+        let note = MoveAnnotation::comment("answer").with_mark(Coordinates::new(0, 1, 1));
+        game.annotate(2, note.clone()).unwrap();
+        game.annotate(0, MoveAnnotation::default().with_eval(0.0)).unwrap();
+
+        let tree = GameTree::from_game(&game);
+        assert_eq!(tree.current_node().comment.as_deref(), Some("answer"));
+        assert_eq!(tree.node(tree.root()).unwrap().eval, Some(0.0));
+        assert_eq!(tree.to_game().annotations(), game.annotations());
+        assert_eq!(tree.to_game().annotation(2), Some(&note));
+    }
+
+    #[test]
+    fn test_no_moves_after_a_resignation() {
+        let mut tree = GameTree::new(3);
+        tree.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        assert!(tree.add_move(place(1, 2, 0, 0)).is_err());
+        assert!(tree.to_game().check_game_over());
+    }
+}
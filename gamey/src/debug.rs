@@ -0,0 +1,120 @@
+//! Debugging aids for board topologies.
+//!
+//! [`to_dot`] writes the adjacency graph of a topology in the Graphviz DOT
+//! language, to check by eye that a new topology links the cells it should.
+//! `gamey dot` prints it for a board size or a saved game:
+//!
+//! ```bash
+//! gamey dot --size 4 | dot -Tsvg > board.svg
+//! ```
+
+use crate::PlayerId;
+use crate::core::topology::{BoardTopology, CellIndex};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Fill colors of the stones of each player, the ANSI colors of the text renderer.
+const PLAYER_FILLS: [&str; 6] = ["blue", "red", "green", "yellow", "magenta", "cyan"];
+
+/// Writes the adjacency graph of `topology` as an undirected Graphviz graph.
+///
+/// Every cell is a node labeled with its index and the names of the regions
+/// it belongs to. The shape tells how many regions that is: a circle for
+/// none, a box for one and a diamond for a corner of two or more. With
+/// `state`, the occupied cells are filled with the color of their player.
+/// Each pair of neighbors is joined by a single edge.
+///
+/// # Example
+///
+/// ```
+/// use gamey::TriangularTopology;
+///
+/// let dot = gamey::debug::to_dot(&TriangularTopology::new(2), None);
+/// assert!(dot.starts_with("graph board {"));
+/// assert!(dot.contains("0 -- 1;"));
+/// ```
+pub fn to_dot<T: BoardTopology>(topology: &T, state: Option<&[Option<PlayerId>]>) -> String {
+    let regions = topology.regions();
+    let mut dot = String::from("graph board {\n");
+    for cell in 0..topology.total_cells() {
+        let mask = topology.get_cell_regions(cell);
+        let names: Vec<&str> = regions
+            .iter()
+            .filter(|region| mask & region.mask != 0)
+            .map(|region| region.name.as_str())
+            .collect();
+        let (label, shape) = match names.len() {
+            0 => (cell.to_string(), "circle"),
+            1 => (format!("{}\\n{}", cell, names[0]), "box"),
+            _ => (format!("{}\\n{}", cell, names.join(",")), "diamond"),
+        };
+        let _ = write!(dot, "  {} [label=\"{}\", shape={}", cell, label, shape);
+        if let Some(player) = state.and_then(|state| state.get(cell).copied().flatten()) {
+            let fill = PLAYER_FILLS.get(player.id() as usize).unwrap_or(&"gray");
+            let _ = write!(dot, ", style=filled, fillcolor={}", fill);
+        }
+        dot.push_str("];\n");
+    }
+    for (a, b) in edges(topology) {
+        let _ = writeln!(dot, "  {} -- {};", a, b);
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// The pairs of neighboring cells, smaller index first, each pair once.
+fn edges<T: BoardTopology>(topology: &T) -> BTreeSet<(CellIndex, CellIndex)> {
+    (0..topology.total_cells())
+        .flat_map(|cell| {
+            topology
+                .get_neighbors(cell)
+                .iter()
+                .map(move |neighbor| (cell.min(*neighbor), cell.max(*neighbor)))
+        })
+        .filter(|(a, b)| a != b)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameY, Movement, TriangularTopology};
+
+    /// Counts the node and edge statements of a graph written by `to_dot`.
+    fn count(dot: &str) -> (usize, usize) {
+        let statements: Vec<&str> = dot
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.ends_with(';'))
+            .collect();
+        let edges = statements.iter().filter(|line| line.contains(" -- ")).count();
+        (statements.len() - edges, edges)
+    }
+
+    #[test]
+    fn test_size_4_board_has_10_nodes_and_18_edges() {
+        let dot = to_dot(&TriangularTopology::new(4), None);
+        assert!(dot.starts_with("graph board {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(count(&dot), (10, 18));
+        assert!(!dot.contains("fillcolor"));
+    }
+
+    #[test]
+    fn test_regions_and_stones_are_drawn() {
+        let mut game = GameY::new(4);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(1, 1, 1),
+        })
+        .unwrap();
+        let engine = game.engine();
+        let dot = to_dot(engine.topology(), Some(engine.occupants()));
+        let center = Coordinates::new(1, 1, 1).to_index(4);
+        assert!(dot.contains(&format!("  {} [label=\"{}\", shape=circle, style=filled, fillcolor=red];", center, center)));
+        // The top corner is on two sides, the cells between corners on one
+        assert!(dot.contains("  0 [label=\"0\\nB,C\", shape=diamond];"));
+        assert!(dot.contains("shape=box"));
+        assert_eq!(dot.matches("fillcolor").count(), 1);
+    }
+}
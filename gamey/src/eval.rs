@@ -0,0 +1,129 @@
+//! Static evaluation of Y positions.
+//!
+//! This module provides [`evaluate`], a heuristic score shared by the search
+//! bots, hints and blunder detection. The score combines:
+//!
+//! - how many winning regions (sides) the player's best group touches,
+//! - the minimum number of empty cells the player still needs to connect all
+//!   sides, see [`crate::analysis::connection_distance`],
+//! - a small bonus for stones close to the center of the board.
+//!
+//! The score of a player is always the opposite of the score of the opponent,
+//! and decided positions evaluate to `f32::INFINITY` for the winner and
+//! `f32::NEG_INFINITY` for the loser.
+
+use crate::analysis::connection_distance_in;
+use crate::{BoardTopology, Coordinates, GameStatus, GameY, PlayerId, center_distance};
+
+/// Weight of each winning region touched by the player's best group.
+const REGION_WEIGHT: f32 = 1.0;
+/// Weight of each empty cell still needed to connect all sides.
+const DISTANCE_WEIGHT: f32 = 1.0;
+/// Weight of the center-control term.
+const CENTER_WEIGHT: f32 = 0.05;
+
+/// Evaluates the position from the point of view of `player`.
+///
+/// Positive values favor `player`, negative values favor the opponent, and
+/// `evaluate(game, p) == -evaluate(game, opponent)` for two-player games.
+pub fn evaluate(game: &GameY, player: PlayerId) -> f32 {
+    if let GameStatus::Finished { winner, .. } = game.status() {
+        return if *winner == player {
+            f32::INFINITY
+        } else {
+            f32::NEG_INFINITY
+        };
+    }
+    let opponent = PlayerId::new(1 - player.id().min(1));
+    player_score(game, player) - player_score(game, opponent)
+}
+
+/// Heuristic score of a single player, independent of the opponent's score.
+fn player_score(game: &GameY, player: PlayerId) -> f32 {
+    let engine = game.engine();
+    let winning = engine.topology().winning_mask_for(player);
+
+    let best_regions = engine
+        .groups(player)
+        .iter()
+        .map(|regions| (regions & winning).count_ones())
+        .max()
+        .unwrap_or(0);
+
+    let distance = connection_distance_in(engine, player)
+        .map(|d| d as f32)
+        .unwrap_or(engine.topology().total_cells() as f32);
+
+    let size = game.board_size();
+    let max_distance = (size as f32 - 1.0).max(1.0);
+    let center: f32 = engine
+        .occupants()
+        .iter()
+        .enumerate()
+        .filter(|(_, occupant)| **occupant == Some(player))
+        .map(|(idx, _)| {
+            let coords = Coordinates::from_index(idx as u32, size);
+            1.0 - center_distance(&coords, size) as f32 / max_distance
+        })
+        .sum();
+
+    REGION_WEIGHT * best_regions as f32 - DISTANCE_WEIGHT * distance + CENTER_WEIGHT * center
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_empty_board_is_even() {
+        let game = GameY::new(5);
+        assert_eq!(evaluate(&game, PlayerId::new(0)), 0.0);
+    }
+
+    #[test]
+    fn test_evaluation_is_symmetric() {
+        let mut game = GameY::new(5);
+        play(&mut game, 0, Coordinates::new(2, 1, 1));
+        play(&mut game, 1, Coordinates::new(4, 0, 0));
+        play(&mut game, 0, Coordinates::new(1, 1, 2));
+        let p0 = evaluate(&game, PlayerId::new(0));
+        let p1 = evaluate(&game, PlayerId::new(1));
+        assert!((p0 + p1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_nearly_winning_position_scores_higher() {
+        // Player 0 owns the whole side x = 0 except one corner cell
+        let mut nearly = GameY::new(4);
+        play(&mut nearly, 0, Coordinates::new(0, 3, 0));
+        play(&mut nearly, 1, Coordinates::new(3, 0, 0));
+        play(&mut nearly, 0, Coordinates::new(0, 2, 1));
+        play(&mut nearly, 1, Coordinates::new(2, 1, 0));
+        play(&mut nearly, 0, Coordinates::new(0, 1, 2));
+
+        let mut even = GameY::new(4);
+        play(&mut even, 0, Coordinates::new(0, 3, 0));
+        play(&mut even, 1, Coordinates::new(0, 0, 3));
+
+        let player = PlayerId::new(0);
+        assert!(evaluate(&nearly, player) > evaluate(&even, player));
+        assert!(evaluate(&nearly, player) > 0.0);
+    }
+
+    #[test]
+    fn test_decided_positions() {
+        let mut game = GameY::new(1);
+        play(&mut game, 0, Coordinates::new(0, 0, 0));
+        assert_eq!(evaluate(&game, PlayerId::new(0)), f32::INFINITY);
+        assert_eq!(evaluate(&game, PlayerId::new(1)), f32::NEG_INFINITY);
+    }
+}
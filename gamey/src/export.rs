@@ -0,0 +1,245 @@
+//! Export of games as training data for learned policies.
+//!
+//! [`to_training_rows`] turns every stone placement of a game into a
+//! [`TrainingRow`]: the board before the move seen from the mover's side, the
+//! cell that was chosen and the final result for the mover. [`append_rows`]
+//! appends rows to a file as JSON lines or CSV.
+
+use crate::{GameStatus, GameY, GameYError, Movement, PlayerId};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// A single (position, move) training example.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TrainingRow {
+    /// Board before the move, one value per cell index: 1 for the mover's
+    /// stones, -1 for the opponent's and 0 for empty cells.
+    pub board: Vec<i8>,
+    /// The cell index the mover chose.
+    pub cell: u32,
+    /// The player who made the move.
+    pub player: u32,
+    /// Final result for the mover: 1 win, -1 loss, 0 unfinished game.
+    pub outcome: i8,
+}
+
+/// File format used by [`append_rows`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON object per line.
+    Jsonl,
+    /// Comma-separated values with a header; the board is a space-separated list.
+    Csv,
+}
+
+/// Header written at the top of new CSV files.
+pub const CSV_HEADER: &str = "player,cell,outcome,board";
+
+/// Converts every stone placement of `game` into a training row.
+///
+/// Stones keep their owner when the swap rule is applied, so the perspective
+/// of each row follows the player id of the mover, also after a swap.
+/// Swaps and resignations produce no row.
+pub fn to_training_rows(game: &GameY) -> Vec<TrainingRow> {
+    let winner = match game.status() {
+        GameStatus::Finished { winner, .. } => Some(*winner),
+        GameStatus::Ongoing { .. } => None,
+    };
+    let size = game.board_size();
+    let mut owners: Vec<Option<PlayerId>> = vec![None; game.total_cells() as usize];
+    let mut rows = Vec::new();
+
+    for movement in game.history().iter() {
+        if let Movement::Placement { player, coords } = movement {
+            let cell = coords.to_index(size);
+            rows.push(TrainingRow {
+                board: owners
+                    .iter()
+                    .map(|owner| match owner {
+                        Some(p) if p == player => 1,
+                        Some(_) => -1,
+                        None => 0,
+                    })
+                    .collect(),
+                cell,
+                player: player.id(),
+                outcome: match winner {
+                    Some(w) if w == *player => 1,
+                    Some(_) => -1,
+                    None => 0,
+                },
+            });
+            owners[cell as usize] = Some(*player);
+        }
+    }
+    rows
+}
+
+/// Appends `rows` to the file at `path`, creating it if needed.
+///
+/// CSV files get a header line when they are created (or empty).
+pub fn append_rows<P: AsRef<Path>>(
+    path: P,
+    rows: &[TrainingRow],
+    format: ExportFormat,
+) -> Result<(), GameYError> {
+    let filename = path.as_ref().display().to_string();
+    let io_error = |e: std::io::Error| GameYError::IoError {
+        message: format!("Failed to write training data: {}", filename),
+        error: e.to_string(),
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(io_error)?;
+    let is_empty = file.metadata().map_err(io_error)?.len() == 0;
+
+    let mut out = String::new();
+    match format {
+        ExportFormat::Jsonl => {
+            for row in rows {
+                let line =
+                    serde_json::to_string(row).map_err(|e| GameYError::SerdeError { error: e })?;
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        ExportFormat::Csv => {
+            if is_empty {
+                out.push_str(CSV_HEADER);
+                out.push('\n');
+            }
+            for row in rows {
+                let board: Vec<String> = row.board.iter().map(|v| v.to_string()).collect();
+                out.push_str(&format!(
+                    "{},{},{},{}\n",
+                    row.player,
+                    row.cell,
+                    row.outcome,
+                    board.join(" ")
+                ));
+            }
+        }
+    }
+    file.write_all(out.as_bytes()).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameAction};
+
+    fn place(game: &mut GameY, player: u32, idx: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::from_index(idx, game.board_size()),
+        })
+        .unwrap();
+    }
+
+    /// Size 2: player 0 takes cells 0 and 2 and wins, player 1 takes cell 1.
+    fn short_game() -> GameY {
+        let mut game = GameY::new(2);
+        place(&mut game, 0, 0);
+        place(&mut game, 1, 1);
+        place(&mut game, 0, 2);
+        game
+    }
+
+    #[test]
+    fn test_rows_of_short_game() {
+        let rows = to_training_rows(&short_game());
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].board, vec![0, 0, 0]);
+        assert_eq!(rows[0].cell, 0);
+        assert_eq!(rows[0].outcome, 1);
+        // Player 1 sees player 0's stone as the opponent's
+        assert_eq!(rows[1].board, vec![-1, 0, 0]);
+        assert_eq!(rows[1].outcome, -1);
+        assert_eq!(rows[2].board, vec![1, -1, 0]);
+        assert_eq!(rows[2].player, 0);
+    }
+
+    #[test]
+    fn test_resignation_produces_no_row() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 4);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        let rows = to_training_rows(&game);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].outcome, 1);
+    }
+
+    #[test]
+    fn test_swapped_game_keeps_stone_owners() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 0);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        // After the swap player 0 moves again, the opening stone is still its own
+        place(&mut game, 0, 5);
+        place(&mut game, 1, 4);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        let rows = to_training_rows(&game);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].player, 0);
+        assert_eq!(rows[1].board, vec![1, 0, 0, 0, 0, 0]);
+        assert_eq!(rows[2].player, 1);
+        assert_eq!(rows[2].board, vec![-1, 0, 0, 0, 0, -1]);
+        assert_eq!(rows[1].outcome, -1);
+        assert_eq!(rows[2].outcome, 1);
+    }
+
+    #[test]
+    fn test_unfinished_game_has_zero_outcome() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 0);
+        assert_eq!(to_training_rows(&game)[0].outcome, 0);
+    }
+
+    #[test]
+    fn test_append_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.jsonl");
+        let rows = to_training_rows(&short_game());
+        append_rows(&path, &rows, ExportFormat::Jsonl).unwrap();
+        append_rows(&path, &rows, ExportFormat::Jsonl).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 6);
+        let first: TrainingRow = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first, rows[0]);
+    }
+
+    #[test]
+    fn test_append_csv_writes_header_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        let rows = to_training_rows(&short_game());
+        append_rows(&path, &rows, ExportFormat::Csv).unwrap();
+        append_rows(&path, &rows, ExportFormat::Csv).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[2], "1,1,-1,-1 0 0");
+    }
+}
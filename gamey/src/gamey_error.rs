@@ -5,7 +5,7 @@
 
 use thiserror::Error;
 
-use crate::{Coordinates, Movement, PlayerId};
+use crate::{Coordinates, GameFormat, Movement, PlayerId};
 
 /// Errors that can occur during Y game operations.
 ///
@@ -31,6 +31,91 @@ pub enum GameYError {
         error: serde_json::Error,
     },
 
+    /// A saved game file exists but is not valid YEN, e.g. because it was truncated.
+    #[error("Corrupt game file {path}: {error}")]
+    CorruptFile {
+        /// The file that could not be parsed.
+        path: String,
+        /// The underlying serde_json error.
+        error: serde_json::Error,
+    },
+
+    /// Data in a game file format could not be read.
+    #[error("Invalid {format} data: {message}")]
+    InvalidFormat {
+        /// The format that was attempted.
+        format: GameFormat,
+        /// What is wrong, including the file name when reading a file.
+        message: String,
+    },
+
+    /// A line of a game collection file could not be read.
+    #[error("Invalid game collection {path}, line {line}: {message}")]
+    InvalidCollection {
+        /// The collection file.
+        path: String,
+        /// Line of the offending game, starting at 1.
+        line: usize,
+        /// What is wrong with the game.
+        message: String,
+    },
+
+    /// A share-link token could not be decoded into a position.
+    #[error("Invalid position token: {message}")]
+    InvalidToken {
+        /// Why the token was rejected.
+        message: String,
+    },
+
+    /// A textual move list could not be read.
+    #[error("Invalid move text at line {line}, column {column}: {message}")]
+    InvalidMoveText {
+        /// Line of the offending token, starting at 1.
+        line: usize,
+        /// Column of the offending token, starting at 1.
+        column: usize,
+        /// What is wrong, including the move number for illegal moves.
+        message: String,
+    },
+
+    /// A move of a move list is out of the board or cannot be played.
+    #[error("Invalid move list at index {index}: {message}")]
+    InvalidMoveList {
+        /// Position of the offending move in the list, starting at 0.
+        index: usize,
+        /// Why the move was rejected.
+        message: String,
+    },
+
+    /// A single move written like `B c4` or `R swap` could not be read.
+    #[error("Invalid move notation '{text}': {message}")]
+    InvalidMoveNotation {
+        /// The text that was read.
+        text: String,
+        /// What is wrong with it.
+        message: String,
+    },
+
+    /// A ply past the moves of a game, for example when annotating it.
+    #[error("Invalid ply {ply}, the game has {moves} moves")]
+    InvalidPly {
+        /// The rejected ply.
+        ply: usize,
+        /// Number of moves of the game.
+        moves: usize,
+    },
+
+    /// A board size outside the sizes a game supports.
+    #[error("Invalid board size {size}, expected {min} to {max}")]
+    InvalidBoardSize {
+        /// The rejected size.
+        size: u32,
+        /// The smallest supported size.
+        min: u32,
+        /// The largest supported size.
+        max: u32,
+    },
+
     /// Wrong number of coordinates provided.
     #[error("Invalid coordinates: expected {expected} coords, found {found}")]
     BadCoordsNumber {
@@ -51,6 +136,24 @@ pub enum GameYError {
         board_size: u32,
     },
 
+    /// The coordinates have valid components that do not add up to `board_size - 1`.
+    #[error("Coordinates {coordinates} are not on a board of size {board_size}: x + y + z must be the size minus 1")]
+    InvalidCoordinates {
+        /// The coordinates off the board.
+        coordinates: Coordinates,
+        /// The size of the board.
+        board_size: u32,
+    },
+
+    /// A cell index is not below the number of cells of the board.
+    #[error("Cell index {index} is out of range for board size {board_size}")]
+    CellIndexOutOfRange {
+        /// The invalid index.
+        index: u32,
+        /// The size of the board.
+        board_size: u32,
+    },
+
     /// Attempted to place a piece on an already occupied cell.
     #[error("Player {player} tries to place a stone on an occupied position: {coordinates}")]
     Occupied {
@@ -60,8 +163,24 @@ pub enum GameYError {
         player: PlayerId,
     },
 
+    /// Attempted to place a stone on an occupied cell of a board without barycentric coordinates.
+    #[error("Player {player} tries to place a stone on an occupied cell: {cell}")]
+    OccupiedCell {
+        /// The name of the occupied cell, such as `c3`.
+        cell: String,
+        /// The player who attempted the placement.
+        player: PlayerId,
+    },
+
+    /// A HEN document does not describe a valid Hex position.
+    #[error("Invalid HEN: {message}")]
+    InvalidHEN {
+        /// What is wrong with the document.
+        message: String,
+    },
+
     /// Invalid character found in a YEN layout string.
-    #[error("Invalid character '{char}' in layout at row {row}, column {col}")]
+    #[error("Invalid character '{char}' in layout at row {row}, column {col} in \"{text}\"")]
     InvalidCharInLayout {
         /// The invalid character.
         char: char,
@@ -69,6 +188,8 @@ pub enum GameYError {
         row: usize,
         /// Column index where the character was found.
         col: usize,
+        /// The text of the row.
+        text: String,
     },
 
     /// Attempted to make a move in a finished game.
@@ -78,6 +199,13 @@ pub enum GameYError {
         movement: Movement,
     },
 
+    /// Attempted to make a move in a finished game of a board other than Y.
+    #[error("Attempt to play movement {movement} in a finished game")]
+    MoveAfterGameOver {
+        /// The movement that was attempted, as text.
+        movement: String,
+    },
+
     /// Wrong player attempted to make a move.
     #[error("Wrong player in movement: Expected player {expected}, found player {found}")]
     InvalidPlayerTurn {
@@ -87,6 +215,43 @@ pub enum GameYError {
         found: PlayerId,
     },
 
+    /// A swap in a game created without the swap rule.
+    #[error("Player {player} cannot swap: the game is played without the swap rule")]
+    SwapNotAllowed {
+        /// The player who tried to swap.
+        player: PlayerId,
+    },
+
+    /// A first move in the center of a game that forbids it.
+    #[error("The first move cannot be played on cell {index}, within {radius} of the center")]
+    ForbiddenOpening {
+        /// Index of the cell of the move.
+        index: u32,
+        /// Radius of the forbidden center.
+        radius: u32,
+    },
+
+    /// Opening options that cannot be used together or on the board.
+    #[error("Invalid opening options: {message}")]
+    InvalidOpeningOptions {
+        /// Which options conflict and why.
+        message: String,
+    },
+
+    /// A position built in the editor cannot be played from.
+    #[error("Invalid position: {message}")]
+    InvalidPosition {
+        /// What is wrong with the position.
+        message: String,
+    },
+
+    /// The board of a game is inconsistent and cannot be written out.
+    #[error("Invalid game state: {message}")]
+    InvalidGameState {
+        /// Which invariant of the board does not hold.
+        message: String,
+    },
+
     /// Invalid number of players specified.
     #[error("Invalid number of players: {num_players}, expected {expected}")]
     InvalidNumPlayers {
@@ -106,7 +271,7 @@ pub enum GameYError {
     },
 
     /// A specific line in the YEN layout has wrong number of cells.
-    #[error("Invalid YEN layout line: expected {expected} rows, found {found} rows at line {line}")]
+    #[error("Invalid YEN layout line: expected {expected} rows, found {found} rows at line {line} \"{text}\"")]
     InvalidYENLayoutLine {
         /// Expected number of cells in the line.
         expected: u32,
@@ -114,6 +279,78 @@ pub enum GameYError {
         found: u32,
         /// The line number with the error.
         line: u32,
+        /// The text of the line.
+        text: String,
+    },
+
+    /// A YEN layout starts with a separator or has two separators in a row.
+    #[error("Invalid YEN layout: row {row} is empty")]
+    EmptyLayoutRow {
+        /// The empty row.
+        row: u32,
+    },
+
+    /// A YEN layout ends with a separator.
+    #[error("Invalid YEN layout: trailing '/' after the last row")]
+    TrailingSeparator,
+
+    /// The result of a YEN cannot be read or contradicts its layout.
+    #[error("Invalid YEN result '{result}': {message}")]
+    InvalidYENResult {
+        /// The result as written in the YEN.
+        result: String,
+        /// Why it cannot be applied to the position.
+        message: String,
+    },
+
+    /// Two positions cannot be compared because their boards have different sizes.
+    #[error("Cannot compare boards of sizes {left} and {right}")]
+    BoardSizeMismatch {
+        /// Size of the first board.
+        left: u32,
+        /// Size of the second board.
+        right: u32,
+    },
+
+    /// An opening book file is malformed or does not match its board size.
+    #[error("Invalid opening book {path}: {message}")]
+    InvalidOpeningBook {
+        /// The file the book was loaded from.
+        path: String,
+        /// Description of the problem, including the offending entry.
+        message: String,
+    },
+
+    /// A bot manifest cannot be parsed or lists a bot that cannot be built.
+    #[error("Invalid bot manifest {path}: {message}")]
+    InvalidBotManifest {
+        /// The file the manifest was loaded from.
+        path: String,
+        /// Description of the problem, including the offending bot.
+        message: String,
+    },
+
+    /// A game file cannot be converted to another format.
+    #[error("Cannot convert {path}: {message}")]
+    InvalidConversion {
+        /// The file being read or written.
+        path: String,
+        /// Description of the problem, naming the format involved.
+        message: String,
+    },
+
+    /// The archive of finished games could not be read or written.
+    #[error("Archive error: {message}")]
+    ArchiveError {
+        /// Description of what went wrong.
+        message: String,
+    },
+
+    /// The server settings are invalid.
+    #[error("Invalid configuration: {message}")]
+    InvalidConfig {
+        /// Description of the offending setting.
+        message: String,
     },
 
     /// Server operation failed.
@@ -163,6 +400,86 @@ mod tests {
         assert!(msg.contains("board size 5"));
     }
 
+    #[test]
+    fn test_invalid_ply_display() {
+        let err = GameYError::InvalidPly { ply: 5, moves: 3 };
+        assert_eq!(err.to_string(), "Invalid ply 5, the game has 3 moves");
+    }
+
+    #[test]
+    fn test_invalid_board_size_display() {
+        let err = GameYError::InvalidBoardSize { size: 0, min: 1, max: 100 };
+        assert_eq!(err.to_string(), "Invalid board size 0, expected 1 to 100");
+    }
+
+    #[test]
+    fn test_invalid_coordinates_display() {
+        let err = GameYError::InvalidCoordinates {
+            coordinates: Coordinates::new(1, 1, 1),
+            board_size: 3,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Coordinates (1, 1, 1) are not on a board of size 3: x + y + z must be the size minus 1"
+        );
+    }
+
+    #[test]
+    fn test_cell_index_out_of_range_display() {
+        let err = GameYError::CellIndexOutOfRange { index: 6, board_size: 3 };
+        assert_eq!(err.to_string(), "Cell index 6 is out of range for board size 3");
+    }
+
+    #[test]
+    fn test_swap_not_allowed_display() {
+        let err = GameYError::SwapNotAllowed {
+            player: PlayerId::new(1),
+        };
+        assert_eq!(err.to_string(), "Player 1 cannot swap: the game is played without the swap rule");
+    }
+
+    #[test]
+    fn test_forbidden_opening_display() {
+        let err = GameYError::ForbiddenOpening { index: 12, radius: 1 };
+        assert_eq!(err.to_string(), "The first move cannot be played on cell 12, within 1 of the center");
+    }
+
+    #[test]
+    fn test_invalid_opening_options_display() {
+        let err = GameYError::InvalidOpeningOptions {
+            message: "too many stones".to_string(),
+        };
+        assert_eq!(err.to_string(), "Invalid opening options: too many stones");
+    }
+
+    #[test]
+    fn test_invalid_position_display() {
+        let err = GameYError::InvalidPosition {
+            message: "two players have won".to_string(),
+        };
+        assert_eq!(err.to_string(), "Invalid position: two players have won");
+    }
+
+    #[test]
+    fn test_invalid_game_state_display() {
+        let err = GameYError::InvalidGameState {
+            message: "cell 3 holds unknown player 7".to_string(),
+        };
+        assert_eq!(err.to_string(), "Invalid game state: cell 3 holds unknown player 7");
+    }
+
+    #[test]
+    fn test_occupied_cell_display() {
+        let err = GameYError::OccupiedCell {
+            cell: "c3".to_string(),
+            player: PlayerId::new(1),
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Player 1 tries to place a stone on an occupied cell: c3"
+        );
+    }
+
     #[test]
     fn test_occupied_display() {
         let err = GameYError::Occupied {
@@ -180,11 +497,13 @@ mod tests {
             char: 'X',
             row: 1,
             col: 2,
+            text: "B.X".to_string(),
         };
         let msg = format!("{}", err);
         assert!(msg.contains("'X'"));
         assert!(msg.contains("row 1"));
         assert!(msg.contains("column 2"));
+        assert!(msg.contains("\"B.X\""));
     }
 
     #[test]
@@ -226,11 +545,119 @@ mod tests {
             expected: 4,
             found: 2,
             line: 3,
+            text: "BR".to_string(),
         };
         let msg = format!("{}", err);
         assert!(msg.contains("expected 4"));
         assert!(msg.contains("found 2"));
         assert!(msg.contains("line 3"));
+        assert!(msg.contains("\"BR\""));
+    }
+
+    #[test]
+    fn test_empty_layout_row_display() {
+        let err = GameYError::EmptyLayoutRow { row: 1 };
+        assert_eq!(format!("{}", err), "Invalid YEN layout: row 1 is empty");
+    }
+
+    #[test]
+    fn test_trailing_separator_display() {
+        let msg = format!("{}", GameYError::TrailingSeparator);
+        assert!(msg.contains("trailing '/'"));
+    }
+
+    #[test]
+    fn test_corrupt_file_display() {
+        let err = GameYError::CorruptFile {
+            path: "game.yen".to_string(),
+            error: serde_json::from_str::<serde_json::Value>("{\"size\": 3,").unwrap_err(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Corrupt game file game.yen"));
+        assert!(msg.contains("EOF"));
+    }
+
+    #[test]
+    fn test_invalid_format_display() {
+        let err = GameYError::InvalidFormat {
+            format: GameFormat::Sgf,
+            message: "missing SZ property".to_string(),
+        };
+        assert_eq!(format!("{}", err), "Invalid SGF data: missing SZ property");
+    }
+
+    #[test]
+    fn test_invalid_token_display() {
+        let err = GameYError::InvalidToken {
+            message: "checksum mismatch".to_string(),
+        };
+        assert_eq!(format!("{}", err), "Invalid position token: checksum mismatch");
+    }
+
+    #[test]
+    fn test_invalid_move_text_display() {
+        let err = GameYError::InvalidMoveText {
+            line: 2,
+            column: 7,
+            message: "unknown token 'x'".to_string(),
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Invalid move text at line 2, column 7: unknown token 'x'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_move_list_display() {
+        let err = GameYError::InvalidMoveList {
+            index: 2,
+            message: "cell 9 is out of a board of 6 cells".to_string(),
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Invalid move list at index 2: cell 9 is out of a board of 6 cells"
+        );
+    }
+
+    #[test]
+    fn test_invalid_move_notation_display() {
+        let err = GameYError::InvalidMoveNotation {
+            text: "b c4".to_string(),
+            message: "unknown player 'b'".to_string(),
+        };
+        assert_eq!(format!("{}", err), "Invalid move notation 'b c4': unknown player 'b'");
+    }
+
+    #[test]
+    fn test_invalid_opening_book_display() {
+        let err = GameYError::InvalidOpeningBook {
+            path: "book.json".to_string(),
+            message: "entry 1 has 4 rows".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("book.json"));
+        assert!(msg.contains("entry 1"));
+    }
+
+    #[test]
+    fn test_invalid_bot_manifest_display() {
+        let err = GameYError::InvalidBotManifest {
+            path: "bots.toml".to_string(),
+            message: "bot 1 ('alpha'): unknown kind 'alphazero'".to_string(),
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Invalid bot manifest bots.toml: bot 1 ('alpha'): unknown kind 'alphazero'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_conversion_display() {
+        let err = GameYError::InvalidConversion {
+            path: "game.sgf".to_string(),
+            message: "not a valid SGF: missing size".to_string(),
+        };
+        assert_eq!(format!("{}", err), "Cannot convert game.sgf: not a valid SGF: missing size");
     }
 
     #[test]
@@ -243,6 +670,17 @@ mod tests {
         assert!(msg.contains("Failed to bind to port 3000"));
     }
 
+    #[test]
+    fn test_invalid_config_display() {
+        let err = GameYError::InvalidConfig {
+            message: "port must be between 1 and 65535".to_string(),
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Invalid configuration: port must be between 1 and 65535"
+        );
+    }
+
     #[test]
     fn test_error_is_debug() {
         let err = GameYError::IoError {
@@ -5,17 +5,47 @@
 //!
 //! # Modules
 //!
+//! - [`analysis`]: Position analysis such as the distance to a connection
 //! - [`core`]: Core game types including board, coordinates, and game logic
 //! - [`bot`]: Bot implementations for computer opponents
-//! - [`bot_server`]: HTTP server for bot API
+//! - [`bot_server`]: HTTP server for bot API (`server` feature)
 //! - [`cli`]: Command-line interface for interactive play
-//! - [`notation`]: Game notation formats (YEN)
+//! - `client`: Typed HTTP client of the server API (`client` feature)
+//! - [`debug`]: Graphviz export of the board graph, for checking topologies
+//! - [`eval`]: Static evaluation of positions shared by bots and analysis
+//! - [`export`]: Export of games as training data
+//! - [`notation`]: Game notation formats (YEN, SGF, binary game records, move text and collections)
+//! - [`prelude`]: The stable types most programs need, in one import
+//! - [`puzzle`]: Find-the-winning-move puzzles, their verification and mining from games
+//! - [`rating`]: Elo rating math and a ladder kept across games
+//! - [`review`]: Post-game review that flags blunders
+//! - `schema`: JSON Schemas of YEN and the server API (`schema` feature)
+//! - [`stats`]: Win rates of the opening moves of finished games
+//! - `testing`: Proptest strategies for positions, games and YEN (`testing` feature)
+//! - [`tournament`]: Round-robin tournaments between bots with Elo ratings
+//! - [`viewer`]: Stepping through a saved game in the terminal
+//! - [`ytp`]: GTP-style text protocol to drive the bots from other programs
 //! - [`gamey_error`]: Error types for the library
 //!
+//! # Features
+//!
+//! - `server` (default): the [`bot_server`] module and the `gamey` binary. Without
+//!   it the game logic, bots and formats build without axum and tokio:
+//!   `gamey = { version = "0.1", default-features = false }`.
+//! - `sqlite`: archive of finished games of the server in SQLite.
+//! - `client`: the `client` module, a typed HTTP client of the server API
+//!   built on reqwest. Implies `server`.
+//! - `schema`: the `schema` module and subcommand, which export JSON Schemas
+//!   of YEN and the server payloads. Implies `server`.
+//! - `testing`: the `testing` module with proptest strategies that generate
+//!   positions, games and YEN documents for property tests.
+//! - `parallel`: runs the playouts of [`MctsBot::with_threads`] on the rayon
+//!   thread pool.
+//!
 //! # Example
 //!
 //! ```
-//! use gamey::{GameY, Coordinates, Movement, PlayerId};
+//! use gamey::prelude::*;
 //!
 //! // Create a new game with board size 5
 //! let mut game = GameY::new(5);
@@ -28,15 +58,35 @@
 //! game.add_move(movement).unwrap();
 //! ```
 
+pub mod analysis;
 pub mod bot;
 pub mod cli;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod core;
+pub mod debug;
+pub mod eval;
+pub mod export;
 pub mod gamey_error;
 pub mod notation;
+pub mod prelude;
+pub mod puzzle;
+pub mod rating;
+pub mod review;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod stats;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod tournament;
+pub mod viewer;
+pub mod ytp;
+#[cfg(feature = "server")]
 pub mod bot_server;
 pub use bot::*;
 pub use cli::*;
 pub use core::*;
 pub use gamey_error::*;
 pub use notation::*;
+#[cfg(feature = "server")]
 pub use bot_server::*;
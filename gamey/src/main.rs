@@ -17,10 +17,59 @@
 //!
 //! # Start the bot server on port 3000
 //! gamey --mode server --port 3000
+//!
+//! # Archive the finished games of the server (needs the sqlite feature)
+//! gamey --mode server --database-url games.db
+//!
+//! # Read the server settings from a file; GAMEY_* variables and flags override it
+//! GAMEY_DEFAULT_BOT=bridge_bot gamey --mode server --config server.toml
+//!
+//! # Compare bots in a round-robin tournament
+//! gamey tournament --games 10 --size 5 --seed 42
+//!
+//! # Generate self-play games as training data
+//! gamey selfplay --games 100 --bot mcts_bot --export games.jsonl
+//!
+//! # Keep the games of a self-play run and summarize them
+//! gamey selfplay --games 100 --collection games.jsonl
+//! gamey stats games.jsonl
+//!
+//! # Win rates of the openings played in at least 10 games, best first
+//! gamey openings games.jsonl --min-games 10 --sort win-rate
+//!
+//! # Let a GUI or referee drive the MCTS bot over stdin/stdout
+//! gamey ytp --bot mcts_bot
+//!
+//! # List every problem of a hand-edited YEN file
+//! gamey validate position.yen
+//!
+//! # Check the solutions of a directory of puzzles
+//! gamey puzzle puzzles/
+//!
+//! # Evaluate a saved position and list the five best moves
+//! gamey analyze position.yen --bot mcts_bot --top 5
+//!
+//! # Convert a game record to SGF, or a directory of YEN files to records
+//! gamey convert game.ygr game.sgf
+//! gamey convert positions/ records/ --to record --recursive
+//!
+//! # Step through a saved game, or animate it at two moves per second
+//! gamey replay game.ygr
+//! gamey replay game.ygr --auto --delay 500ms
+//!
+//! # Draw the cells and their neighbors of a saved game with Graphviz
+//! gamey dot position.yen | dot -Tsvg > position.svg
+//!
+//! # Write the JSON Schemas of the API (needs the schema feature)
+//! gamey schema --out schemas
 //! ```
 
 use clap::Parser;
-use gamey::{self, CliArgs, Mode, run_bot_server, run_cli_game};
+use gamey::{
+    self, CliArgs, CliCommand, Mode, ServerConfig, run_analyze, run_bot_server_with_config, run_cli_game,
+    run_convert, run_dot, run_openings, run_puzzle, run_replay, run_selfplay, run_stats, run_tournament, run_validate,
+    run_ytp,
+};
 use tracing_subscriber::prelude::*;
 
 /// Main entry point for the GameY application.
@@ -32,9 +81,44 @@ async fn main() {
     tracing_subscriber::registry().init();
     let args = CliArgs::parse();
 
-    if args.mode == Mode::Server {
+    if let Some(command) = &args.command {
+        let result = match command {
+            CliCommand::Tournament(tournament) => run_tournament(tournament),
+            CliCommand::Selfplay(selfplay) => run_selfplay(selfplay),
+            CliCommand::Stats(stats) => run_stats(stats),
+            CliCommand::Openings(openings) => run_openings(openings),
+            CliCommand::Ytp(ytp) => run_ytp(ytp),
+            CliCommand::Validate(validate) => run_validate(validate),
+            CliCommand::Puzzle(puzzle) => run_puzzle(puzzle),
+            CliCommand::Dot(dot) => run_dot(dot),
+            CliCommand::Analyze(analyze) => run_analyze(analyze),
+            CliCommand::Convert(convert) => run_convert(convert),
+            CliCommand::Replay(replay) => run_replay(replay),
+            #[cfg(feature = "schema")]
+            CliCommand::Schema(schema) => gamey::run_schema(schema),
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    } else if args.mode == Mode::Server {
         // Wait for the bot server to run and handle requests
-        if let Err(e) = run_bot_server(args.port).await {
+        // Command-line options win over GAMEY_* variables, which win over the file
+        let mut config = match ServerConfig::load(args.config.as_deref()) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+        if let Some(port) = args.port {
+            config.port = port;
+        }
+        if args.database_url.is_some() {
+            config.database_url = args.database_url.clone();
+        }
+        config.allow_chaos = args.allow_chaos;
+        if let Err(e) = run_bot_server_with_config(config).await {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
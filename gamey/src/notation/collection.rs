@@ -0,0 +1,458 @@
+use crate::{GameStatus, GameY, GameYError, Movement, PLAYER_SYMBOLS, PlayerId, YEN};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// One game of a collection file: a single JSON line.
+///
+/// The final position is kept as YEN (with the player information in its
+/// metadata) next to the full move history, so a game can be shown without
+/// replaying it and replayed when needed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CollectionEntry {
+    /// The final position of the game.
+    pub yen: YEN,
+    /// Every move of the game, in order.
+    pub history: Vec<Movement>,
+    /// The player who lost on time, if that is how the game ended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lost_on_time: Option<PlayerId>,
+    /// The player who forfeited, if that is how the game ended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forfeited: Option<PlayerId>,
+    /// Free-form information about the game, such as the bots that played it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl CollectionEntry {
+    /// Creates the entry of `game`, without tags.
+    pub fn new(game: &GameY) -> Self {
+        Self {
+            yen: game.into(),
+            history: game.history().to_vec(),
+            lost_on_time: game.lost_on_time(),
+            forfeited: game.forfeited(),
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a tag to the entry.
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Rebuilds the game by replaying its history.
+    ///
+    /// Fails if the board size is out of range, if a move cannot be played or
+    /// if the replayed position does not match the stored YEN.
+    pub fn to_game(&self) -> Result<GameY, String> {
+        crate::check_board_size(self.yen.size()).map_err(|e| e.to_string())?;
+        let players = self
+            .yen
+            .metadata()
+            .map(|m| m.players.clone())
+            .unwrap_or_default();
+        let mut game = GameY::with_players(self.yen.size(), self.yen.players().len() as u32)
+            .with_player_info(players);
+        for (i, movement) in self.history.iter().enumerate() {
+            game.add_move(movement.clone())
+                .map_err(|e| format!("move {} cannot be played: {}", i + 1, e))?;
+        }
+        if let Some(player) = self.lost_on_time {
+            game.lose_on_time(player);
+        }
+        if let Some(player) = self.forfeited {
+            game.forfeit(player);
+        }
+        let replayed: YEN = (&game).into();
+        if replayed.layout() != self.yen.layout() {
+            return Err(format!(
+                "history leads to layout {}, but the game stores {}",
+                replayed.layout(),
+                self.yen.layout()
+            ));
+        }
+        Ok(game)
+    }
+}
+
+/// Writes games to a collection file, one JSON line per game.
+///
+/// Every game is written with a single write of a complete line, so a
+/// process that is killed loses at most the game it was writing.
+#[derive(Debug)]
+pub struct CollectionWriter {
+    file: File,
+    path: PathBuf,
+}
+
+impl CollectionWriter {
+    /// Creates a new collection at `path`, replacing any existing file.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let file = File::create(&path).map_err(|e| io_error("create", path.as_ref(), e))?;
+        Ok(Self {
+            file,
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Opens the collection at `path` to add games at its end, creating it if needed.
+    ///
+    /// If a previous process died in the middle of a game, the incomplete
+    /// last line is removed first, so the file stays readable.
+    pub fn append<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| io_error("open", path, e))?;
+        let len = complete_length(&mut file).map_err(|e| io_error("read", path, e))?;
+        let current = file.metadata().map_err(|e| io_error("read", path, e))?.len();
+        if len != current {
+            tracing::warn!(
+                "Dropping {} bytes of an incomplete game at the end of {}",
+                current - len,
+                path.display()
+            );
+            file.set_len(len).map_err(|e| io_error("repair", path, e))?;
+        }
+        file.seek(SeekFrom::End(0))
+            .map_err(|e| io_error("open", path, e))?;
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Appends `game` to the collection.
+    pub fn write_game(&mut self, game: &GameY) -> Result<(), GameYError> {
+        self.write_entry(&CollectionEntry::new(game))
+    }
+
+    /// Appends an entry to the collection.
+    pub fn write_entry(&mut self, entry: &CollectionEntry) -> Result<(), GameYError> {
+        let mut line =
+            serde_json::to_vec(entry).map_err(|e| GameYError::SerdeError { error: e })?;
+        line.push(b'\n');
+        self.file
+            .write_all(&line)
+            .map_err(|e| io_error("write", &self.path, e))
+    }
+}
+
+/// Length of `file` up to the end of its last complete line.
+fn complete_length(file: &mut File) -> std::io::Result<u64> {
+    const BLOCK: u64 = 4096;
+    let len = file.metadata()?.len();
+    let mut end = len;
+    let mut buf = vec![0; BLOCK as usize];
+    while end > 0 {
+        let start = end.saturating_sub(BLOCK);
+        let block = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(block)?;
+        if let Some(pos) = block.iter().rposition(|b| *b == b'\n') {
+            return Ok(start + pos as u64 + 1);
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+/// Reads the entries of a collection one line at a time.
+///
+/// Blank lines are skipped. Malformed lines are reported as
+/// [`GameYError::InvalidCollection`] with their line number, and reading can
+/// continue after them.
+#[derive(Debug)]
+pub struct CollectionReader<R> {
+    reader: R,
+    path: String,
+    line: usize,
+}
+
+impl CollectionReader<BufReader<File>> {
+    /// Opens the collection file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let file = File::open(&path).map_err(|e| io_error("open", path.as_ref(), e))?;
+        Ok(Self::new(
+            BufReader::new(file),
+            &path.as_ref().display().to_string(),
+        ))
+    }
+}
+
+impl<R: BufRead> CollectionReader<R> {
+    /// Reads a collection from `reader`; `path` names it in error messages.
+    pub fn new(reader: R, path: &str) -> Self {
+        Self {
+            reader,
+            path: path.to_string(),
+            line: 0,
+        }
+    }
+
+    fn invalid(&self, message: String) -> GameYError {
+        GameYError::InvalidCollection {
+            path: self.path.clone(),
+            line: self.line,
+            message,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CollectionReader<R> {
+    type Item = Result<CollectionEntry, GameYError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut buf) {
+                Ok(0) => return None,
+                Ok(_) if buf.trim().is_empty() => continue,
+                Ok(_) => {
+                    return Some(
+                        serde_json::from_str(&buf).map_err(|e| self.invalid(e.to_string())),
+                    );
+                }
+                Err(e) => return Some(Err(self.invalid(e.to_string()))),
+            }
+        }
+    }
+}
+
+/// Streams the games of the collection at `path`, replaying each one.
+///
+/// Only one line is held in memory at a time. If the file cannot be opened
+/// the iterator yields that error and ends.
+pub fn iter_games<P: AsRef<Path>>(path: P) -> impl Iterator<Item = Result<GameY, GameYError>> {
+    let (mut reader, error) = match CollectionReader::open(path) {
+        Ok(reader) => (Some(reader), None),
+        Err(e) => (None, Some(e)),
+    };
+    error.into_iter().map(Err).chain(std::iter::from_fn(move || {
+        let reader = reader.as_mut()?;
+        let entry = reader.next()?;
+        Some(entry.and_then(|entry| entry.to_game().map_err(|message| reader.invalid(message))))
+    }))
+}
+
+/// Summary of the games of a collection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CollectionStats {
+    /// Number of games.
+    pub games: u32,
+    /// Games won by each player, indexed by player id.
+    pub wins: Vec<u32>,
+    /// Games that were not finished.
+    pub unfinished: u32,
+    /// Fewest moves in a game.
+    pub min_moves: u32,
+    /// Most moves in a game.
+    pub max_moves: u32,
+    /// Moves of all the games together.
+    pub total_moves: u64,
+}
+
+impl CollectionStats {
+    /// Summarizes the collection at `path`, stopping at the first invalid game.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let mut stats = Self::default();
+        for game in iter_games(path) {
+            stats.add(&game?);
+        }
+        Ok(stats)
+    }
+
+    /// Adds a game to the summary.
+    pub fn add(&mut self, game: &GameY) {
+        let moves = game.move_count() as u32;
+        self.min_moves = if self.games == 0 {
+            moves
+        } else {
+            self.min_moves.min(moves)
+        };
+        self.max_moves = self.max_moves.max(moves);
+        self.total_moves += moves as u64;
+        self.games += 1;
+        match game.status() {
+            GameStatus::Finished { winner, .. } => {
+                let id = winner.id() as usize;
+                if self.wins.len() <= id {
+                    self.wins.resize(id + 1, 0);
+                }
+                self.wins[id] += 1;
+            }
+            GameStatus::Ongoing { .. } => self.unfinished += 1,
+        }
+    }
+
+    /// Average number of moves per game, 0 for an empty collection.
+    pub fn average_moves(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.total_moves as f64 / self.games as f64
+        }
+    }
+}
+
+impl fmt::Display for CollectionStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} games", self.games)?;
+        for (id, wins) in self.wins.iter().enumerate() {
+            let symbol = PLAYER_SYMBOLS.get(id).copied().unwrap_or('?');
+            writeln!(f, "Player {} ({}): {} wins", id, symbol, wins)?;
+        }
+        writeln!(f, "Unfinished: {}", self.unfinished)?;
+        write!(
+            f,
+            "Moves: min {}, average {:.1}, max {}",
+            self.min_moves,
+            self.average_moves(),
+            self.max_moves
+        )
+    }
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> GameYError {
+    GameYError::IoError {
+        message: format!("Failed to {} game collection {}", action, path.display()),
+        error: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, RandomBot, tournament};
+
+    fn random_game(size: u32) -> GameY {
+        tournament::play_game(&RandomBot, &RandomBot, size)
+    }
+
+    #[test]
+    fn test_write_and_stream_100_games() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("games.jsonl");
+        let games: Vec<GameY> = (0..100).map(|i| random_game(3 + i % 5)).collect();
+
+        let mut writer = CollectionWriter::create(&path).unwrap();
+        for game in &games {
+            writer.write_game(game).unwrap();
+        }
+
+        let loaded: Vec<GameY> = iter_games(&path).map(|g| g.unwrap()).collect();
+        assert_eq!(loaded.len(), 100);
+        for i in [0, 17, 58, 99] {
+            let expected: YEN = (&games[i]).into();
+            let actual: YEN = (&loaded[i]).into();
+            assert_eq!(actual.layout(), expected.layout());
+            assert_eq!(loaded[i].history(), games[i].history());
+        }
+    }
+
+    #[test]
+    fn test_append_adds_games_and_repairs_a_partial_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("games.jsonl");
+        CollectionWriter::append(&path)
+            .unwrap()
+            .write_game(&random_game(4))
+            .unwrap();
+
+        // A process killed in the middle of a write
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"yen\": {\"size\"").unwrap();
+
+        let mut writer = CollectionWriter::append(&path).unwrap();
+        writer.write_game(&random_game(5)).unwrap();
+        let sizes: Vec<u32> = iter_games(&path).map(|g| g.unwrap().board_size()).collect();
+        assert_eq!(sizes, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_entries_keep_tags_players_and_time_losses() {
+        let mut game = GameY::new(3).with_player_info(vec![crate::PlayerInfo::new(
+            PlayerId::new(0),
+            "Ana",
+            crate::PlayerKind::Human,
+        )]);
+        game.lose_on_time(PlayerId::new(0));
+        let entry = CollectionEntry::new(&game).with_tag("source", "test");
+
+        let line = serde_json::to_string(&entry).unwrap();
+        let read: CollectionEntry = serde_json::from_str(&line).unwrap();
+        assert_eq!(read.tags["source"], "test");
+        let loaded = read.to_game().unwrap();
+        assert_eq!(loaded.players()[0].name, "Ana");
+        assert_eq!(loaded.lost_on_time(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_bad_lines_report_their_number() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("games.jsonl");
+        let mut game = random_game(3);
+        let mut entry = CollectionEntry::new(&game);
+        let good = serde_json::to_string(&entry).unwrap();
+        game = GameY::new(3);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        entry.history = game.history().to_vec();
+        let mismatched = serde_json::to_string(&entry).unwrap();
+        std::fs::write(&path, format!("{}\n\nnot json\n{}\n", good, mismatched)).unwrap();
+
+        let results: Vec<_> = iter_games(&path).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(GameYError::InvalidCollection { line: 3, .. })));
+        let err = results[2].as_ref().unwrap_err().to_string();
+        assert!(err.contains("layout"), "{}", err);
+    }
+
+    #[test]
+    fn test_missing_file_yields_one_error() {
+        let results: Vec<_> = iter_games("/no/such/collection.jsonl").collect();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(GameYError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("games.jsonl");
+        let mut writer = CollectionWriter::create(&path).unwrap();
+        let mut short = GameY::new(3);
+        short
+            .add_move(Movement::Action {
+                player: PlayerId::new(0),
+                action: GameAction::Resign,
+            })
+            .unwrap();
+        writer.write_game(&short).unwrap();
+        writer.write_game(&GameY::new(3)).unwrap();
+        writer.write_game(&random_game(3)).unwrap();
+
+        let stats = CollectionStats::from_path(&path).unwrap();
+        assert_eq!(stats.games, 3);
+        assert_eq!(stats.unfinished, 1);
+        assert_eq!(stats.wins.iter().sum::<u32>(), 2);
+        assert_eq!(stats.min_moves, 0);
+        assert!(stats.max_moves >= 3);
+        assert!(stats.to_string().starts_with("3 games\n"));
+    }
+}
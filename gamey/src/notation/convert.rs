@@ -0,0 +1,350 @@
+//! Conversion of saved games between YEN, SGF, game records and move lists.
+//!
+//! [`convert`] reads a file in one [`ConvertFormat`] and writes it in
+//! another, returning a warning for everything the target format cannot
+//! keep, such as the history of a game written as YEN. [`convert_dir`] does
+//! the same for every game file of a directory.
+//!
+//! A YEN file has no move order. Before it is written in a format with a
+//! history, its stones are ordered into a legal game: the players take turns
+//! from player 0, each placing its stones in index order. Positions whose
+//! stone counts cannot take turns, or that would be won before the last
+//! stone, cannot be converted.
+
+use crate::{GameFormat, GameStatus, GameY, GameYError, Movement, PlayerId, PlayerInfo, PlayerKind};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The formats [`convert`] reads and writes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ConvertFormat {
+    /// Y Exchange Notation (`.yen`, `.json`): the position only.
+    Yen,
+    /// Smart Game Format (`.sgf`): the moves, player names and annotations.
+    Sgf,
+    /// Binary game record (`.ygr`): the moves and all player information.
+    Record,
+    /// Numbered move list (`.txt`, `.moves`), see [`GameY::to_move_text`]:
+    /// the moves only. Reading one needs the board size.
+    Moves,
+}
+
+impl ConvertFormat {
+    /// Returns the format matching the extension of `path`, if it is a known one.
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "txt" | "moves" => Some(ConvertFormat::Moves),
+            _ => GameFormat::from_extension(path).map(Self::from),
+        }
+    }
+
+    /// The usual file extension of the format, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self.game_format() {
+            Some(format) => format.extension(),
+            None => "txt",
+        }
+    }
+
+    /// The matching [`GameFormat`], `None` for move lists.
+    fn game_format(&self) -> Option<GameFormat> {
+        match self {
+            ConvertFormat::Yen => Some(GameFormat::Yen),
+            ConvertFormat::Sgf => Some(GameFormat::Sgf),
+            ConvertFormat::Record => Some(GameFormat::Record),
+            ConvertFormat::Moves => None,
+        }
+    }
+}
+
+impl From<GameFormat> for ConvertFormat {
+    fn from(format: GameFormat) -> Self {
+        match format {
+            GameFormat::Yen => ConvertFormat::Yen,
+            GameFormat::Sgf => ConvertFormat::Sgf,
+            GameFormat::Record => ConvertFormat::Record,
+        }
+    }
+}
+
+impl fmt::Display for ConvertFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.game_format() {
+            Some(format) => write!(f, "{}", format),
+            None => write!(f, "move list"),
+        }
+    }
+}
+
+/// Converts the game in `input` and writes it to `output`.
+///
+/// The formats come from the extensions unless `from` or `to` are given;
+/// `size` is the board size of a move list being read. Returns the warnings
+/// about what `output` lost.
+///
+/// # Errors
+/// Returns `GameYError::InvalidConversion` naming the file and the problem
+/// if a format cannot be inferred, the input cannot be read in its format or
+/// the game cannot be written in the target one.
+pub fn convert(
+    input: &Path,
+    output: &Path,
+    from: Option<ConvertFormat>,
+    to: Option<ConvertFormat>,
+    size: Option<u32>,
+) -> Result<Vec<String>, GameYError> {
+    let from = format_of(input, from)?;
+    let to = format_of(output, to)?;
+    let game = read(input, from, size)?;
+    let warnings = lost_in(&game, from, to);
+    let game = if from == ConvertFormat::Yen && to != ConvertFormat::Yen {
+        ordered(&game).map_err(|message| conversion_error(input, format!("{} as {}", message, to)))?
+    } else {
+        game
+    };
+    write(&game, output, to)?;
+    Ok(warnings)
+}
+
+/// Converts every game file of `input` into `to`, writing them with the same
+/// relative paths under `output`.
+///
+/// Files whose extension is not a known format are skipped unless `from`
+/// forces one. Subdirectories are only converted if `recursive`. Returns the
+/// converted files, each with its warnings.
+///
+/// # Errors
+/// Returns `GameYError::IoError` if a directory cannot be read or created,
+/// and stops at the first file that [`convert`] fails on.
+pub fn convert_dir(
+    input: &Path,
+    output: &Path,
+    from: Option<ConvertFormat>,
+    to: ConvertFormat,
+    size: Option<u32>,
+    recursive: bool,
+) -> Result<Vec<(PathBuf, Vec<String>)>, GameYError> {
+    let mut converted = Vec::new();
+    for file in game_files(input, from, recursive)? {
+        let relative = file.strip_prefix(input).unwrap_or(&file);
+        let target = output.join(relative).with_extension(to.extension());
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| GameYError::IoError {
+                message: format!("Failed to create directory {}", parent.display()),
+                error: e.to_string(),
+            })?;
+        }
+        let warnings = convert(&file, &target, from, Some(to), size)?;
+        converted.push((target, warnings));
+    }
+    Ok(converted)
+}
+
+/// The game files under `dir`, sorted.
+fn game_files(dir: &Path, from: Option<ConvertFormat>, recursive: bool) -> Result<Vec<PathBuf>, GameYError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| GameYError::IoError {
+        message: format!("Failed to read directory {}", dir.display()),
+        error: e.to_string(),
+    })?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| GameYError::IoError {
+                message: format!("Failed to read directory {}", dir.display()),
+                error: e.to_string(),
+            })?
+            .path();
+        if path.is_dir() {
+            if recursive {
+                files.extend(game_files(&path, from, recursive)?);
+            }
+        } else if from.is_some() || ConvertFormat::from_extension(&path).is_some() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn format_of(path: &Path, given: Option<ConvertFormat>) -> Result<ConvertFormat, GameYError> {
+    given.or_else(|| ConvertFormat::from_extension(path)).ok_or_else(|| {
+        conversion_error(
+            path,
+            "unknown extension, give the format with --from or --to".to_string(),
+        )
+    })
+}
+
+fn read(path: &Path, format: ConvertFormat, size: Option<u32>) -> Result<GameY, GameYError> {
+    let failed = |e: GameYError| conversion_error(path, format!("not a valid {}: {}", format, e));
+    match format.game_format() {
+        Some(game_format) => GameY::load_from_file_as(path, game_format).map_err(failed),
+        None => {
+            let size = size.ok_or_else(|| {
+                conversion_error(
+                    path,
+                    "the board size of a move list must be given with --size".to_string(),
+                )
+            })?;
+            let text = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+                message: format!("Failed to read file: {}", path.display()),
+                error: e.to_string(),
+            })?;
+            GameY::from_move_text(size, &text).map_err(failed)
+        }
+    }
+}
+
+fn write(game: &GameY, path: &Path, format: ConvertFormat) -> Result<(), GameYError> {
+    match format.game_format() {
+        Some(game_format) => game.save_to_file_as(path, game_format).map_err(|e| match e {
+            io @ GameYError::IoError { .. } => io,
+            e => conversion_error(path, format!("cannot be written as {}: {}", format, e)),
+        }),
+        None => std::fs::write(path, game.to_move_text() + "\n").map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", path.display()),
+            error: e.to_string(),
+        }),
+    }
+}
+
+fn conversion_error(path: &Path, message: String) -> GameYError {
+    GameYError::InvalidConversion {
+        path: path.display().to_string(),
+        message,
+    }
+}
+
+/// What writing `game`, read as `from`, in the format `to` loses.
+pub fn lost_in(game: &GameY, from: ConvertFormat, to: ConvertFormat) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let has_history = from != ConvertFormat::Yen && game.move_count() > 0;
+    let named = game
+        .players()
+        .iter()
+        .any(|info| *info != PlayerInfo::default_for(info.id));
+    let bots = game
+        .players()
+        .iter()
+        .any(|info| matches!(info.kind, PlayerKind::Bot(_)));
+    let annotated = !game.annotations().is_empty();
+    match to {
+        ConvertFormat::Yen if has_history => {
+            warnings.push("YEN keeps the position but drops the move history".to_string())
+        }
+        ConvertFormat::Sgf if bots => {
+            warnings.push("SGF keeps the player names but not which players are bots".to_string())
+        }
+        ConvertFormat::Moves if named => warnings.push("a move list drops the player names".to_string()),
+        _ => {}
+    }
+    if annotated && matches!(to, ConvertFormat::Yen | ConvertFormat::Moves) {
+        warnings.push(format!("{} drops the annotations of the moves", to));
+    }
+    if from == ConvertFormat::Yen && to != ConvertFormat::Yen && game.move_count() > 0 {
+        warnings.push("YEN has no move order, the stones take turns in index order".to_string());
+    }
+    warnings
+}
+
+/// Orders the stones of a position read from YEN into a legal game.
+///
+/// The players take turns from player 0, as YEN assumes, each placing its
+/// stones in index order. The result of a game finished without a
+/// connection is kept.
+fn ordered(game: &GameY) -> Result<GameY, String> {
+    let size = game.board_size();
+    let mut stones: Vec<Vec<Movement>> = vec![Vec::new(); game.num_players() as usize];
+    for movement in game.history().iter() {
+        if let Movement::Placement { player, .. } = movement {
+            stones[player.id() as usize].push(movement.clone());
+        }
+    }
+    for own in &mut stones {
+        own.sort_by_key(|movement| match movement {
+            Movement::Placement { coords, .. } => coords.to_index(size),
+            Movement::Action { .. } => 0,
+        });
+        own.reverse();
+    }
+    let mut replay = game.empty_copy();
+    let mut player = 0;
+    while stones.iter().any(|own| !own.is_empty()) {
+        let Some(movement) = stones[player].pop() else {
+            return Err(format!("player {} has too few stones to take turns", player));
+        };
+        replay
+            .add_move(movement)
+            .map_err(|e| format!("the stones cannot be ordered into a legal game ({})", e))?;
+        player = (player + 1) % stones.len();
+    }
+    Ok(restore_result(game, replay))
+}
+
+/// Finishes `replay` as `game` when the position was won without a connection.
+fn restore_result(game: &GameY, mut replay: GameY) -> GameY {
+    if let (GameStatus::Finished { winner, .. }, GameStatus::Ongoing { .. }) = (game.status(), replay.status()) {
+        let players = game.num_players();
+        replay.lose_on_time(PlayerId::new((winner.id() + players - 1) % players));
+    }
+    replay
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YEN;
+
+    fn position(layout: &str, turn: u32) -> GameY {
+        let size = layout.split('/').count() as u32;
+        GameY::try_from(YEN::new(size, turn, vec!['B', 'R'], layout.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_formats_from_extensions() {
+        assert_eq!(ConvertFormat::from_extension("a.yen"), Some(ConvertFormat::Yen));
+        assert_eq!(ConvertFormat::from_extension("a.SGF"), Some(ConvertFormat::Sgf));
+        assert_eq!(ConvertFormat::from_extension("a.ygr"), Some(ConvertFormat::Record));
+        assert_eq!(ConvertFormat::from_extension("a.txt"), Some(ConvertFormat::Moves));
+        assert_eq!(ConvertFormat::from_extension("a.png"), None);
+        assert_eq!(ConvertFormat::Moves.extension(), "txt");
+    }
+
+    #[test]
+    fn test_yen_stones_take_turns() {
+        // Red's stone comes first in the layout, but Blue starts
+        let game = ordered(&position("R/B./...", 0)).unwrap();
+        assert_eq!(game.history().len(), 2);
+        assert!(matches!(game.history()[0], Movement::Placement { player, .. } if player.id() == 0));
+        assert_eq!(YEN::from(&game).layout(), "R/B./...");
+    }
+
+    #[test]
+    fn test_positions_that_cannot_take_turns_are_rejected() {
+        let error = ordered(&position("B/B./...", 0)).unwrap_err();
+        assert!(error.contains("player 1 has too few stones"), "{}", error);
+    }
+
+    #[test]
+    fn test_lossy_conversions_are_reported() {
+        let mut game = GameY::new(3).with_player_info(vec![PlayerInfo::new(
+            PlayerId::new(1),
+            "Random",
+            PlayerKind::Bot("random_bot".to_string()),
+        )]);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: crate::Coordinates::from_index(0, 3),
+        })
+        .unwrap();
+        assert_eq!(lost_in(&game, ConvertFormat::Record, ConvertFormat::Yen).len(), 1);
+        assert!(lost_in(&game, ConvertFormat::Record, ConvertFormat::Sgf)[0].contains("bots"));
+        assert!(lost_in(&game, ConvertFormat::Record, ConvertFormat::Moves)[0].contains("names"));
+        assert!(lost_in(&game, ConvertFormat::Sgf, ConvertFormat::Record).is_empty());
+        assert!(lost_in(&game, ConvertFormat::Yen, ConvertFormat::Record)[0].contains("index order"));
+    }
+}
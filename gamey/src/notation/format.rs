@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// The file formats a game can be saved in.
+///
+/// [`crate::GameY::load_from_file`] and [`crate::GameY::save_to_file`] pick
+/// the format with [`GameFormat::detect`]; the `_as` variants force one.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum GameFormat {
+    /// Y Exchange Notation (`.yen`, `.json`): the position only, without history.
+    Yen,
+    /// Smart Game Format (`.sgf`): the move list and player names.
+    Sgf,
+    /// Binary game record (`.ygr`): the move list and all player information.
+    Record,
+}
+
+impl GameFormat {
+    /// Every supported format.
+    pub const ALL: [GameFormat; 3] = [GameFormat::Yen, GameFormat::Sgf, GameFormat::Record];
+
+    /// Returns the format matching the extension of `path`, if it is a known one.
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yen" | "json" => Some(GameFormat::Yen),
+            "sgf" => Some(GameFormat::Sgf),
+            "ygr" => Some(GameFormat::Record),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the first bytes of a file.
+    ///
+    /// Records start with their magic bytes, SGF with `(` and YEN with `{`,
+    /// ignoring leading whitespace.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(crate::RECORD_MAGIC) {
+            return Some(GameFormat::Record);
+        }
+        match bytes.iter().find(|b| !b.is_ascii_whitespace())? {
+            b'(' => Some(GameFormat::Sgf),
+            b'{' => Some(GameFormat::Yen),
+            _ => None,
+        }
+    }
+
+    /// Chooses the format of `path` from its extension, then from its content.
+    ///
+    /// Falls back to YEN when neither identifies the format.
+    pub fn detect<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Self {
+        Self::from_extension(&path)
+            .or_else(|| Self::sniff(bytes))
+            .unwrap_or(GameFormat::Yen)
+    }
+
+    /// The usual file extension of the format, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GameFormat::Yen => "yen",
+            GameFormat::Sgf => "sgf",
+            GameFormat::Record => "ygr",
+        }
+    }
+}
+
+impl fmt::Display for GameFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GameFormat::Yen => "YEN",
+            GameFormat::Sgf => "SGF",
+            GameFormat::Record => "record",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(GameFormat::from_extension("a/game.yen"), Some(GameFormat::Yen));
+        assert_eq!(GameFormat::from_extension("game.JSON"), Some(GameFormat::Yen));
+        assert_eq!(GameFormat::from_extension("game.sgf"), Some(GameFormat::Sgf));
+        assert_eq!(GameFormat::from_extension("game.ygr"), Some(GameFormat::Record));
+        assert_eq!(GameFormat::from_extension("game.txt"), None);
+        assert_eq!(GameFormat::from_extension("game"), None);
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(GameFormat::sniff(b"YGR\x01rest"), Some(GameFormat::Record));
+        assert_eq!(GameFormat::sniff(b"\n  (;FF[4]"), Some(GameFormat::Sgf));
+        assert_eq!(GameFormat::sniff(b"{\"size\": 3}"), Some(GameFormat::Yen));
+        assert_eq!(GameFormat::sniff(b"hello"), None);
+        assert_eq!(GameFormat::sniff(b""), None);
+    }
+
+    #[test]
+    fn test_detect_prefers_extension() {
+        assert_eq!(GameFormat::detect("game.sgf", b"{}"), GameFormat::Sgf);
+        assert_eq!(GameFormat::detect("game.txt", b"(;)"), GameFormat::Sgf);
+        assert_eq!(GameFormat::detect("game.txt", b"???"), GameFormat::Yen);
+    }
+}
@@ -0,0 +1,199 @@
+//! Hex Exchange Notation (HEN), the counterpart of [`crate::YEN`] for Hex.
+
+use crate::{GameHex, GameStatus, GameYError, HexCell, HexMove, PLAYER_SYMBOLS, PlayerId};
+use serde::{Deserialize, Serialize};
+
+/// The game a HEN document describes. Only Hex exists for now.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HenGame {
+    /// The game of Hex.
+    Hex,
+}
+
+/// Hex Exchange Notation (HEN) - a position of a Hex game.
+///
+/// Like YEN, but the layout has `size` rows of `size` cells, and a `game`
+/// field names the game, so that HEN and YEN documents cannot be confused.
+///
+/// # Example
+/// ```json
+/// {
+///   "game": "hex",
+///   "size": 3,
+///   "turn": 1,
+///   "players": ["B", "R"],
+///   "layout": ".B./.../..."
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HEN {
+    /// The game of the document, always `"hex"`.
+    game: HenGame,
+    /// Number of cells of each edge of the board.
+    size: u32,
+    /// The index of the player whose turn it is (0 or 1).
+    turn: u32,
+    /// Character symbols of the two players.
+    players: Vec<char>,
+    /// The rows of the board from the top, separated by '/', with cells
+    /// represented by player symbols or '.' for empty cells.
+    layout: String,
+}
+
+impl HEN {
+    /// Creates a new HEN representation.
+    pub fn new(size: u32, turn: u32, players: Vec<char>, layout: String) -> Self {
+        HEN {
+            game: HenGame::Hex,
+            size,
+            turn,
+            players,
+            layout,
+        }
+    }
+
+    /// Returns the game of the document.
+    pub fn game(&self) -> HenGame {
+        self.game
+    }
+
+    /// Returns the number of cells of each edge of the board.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the index of the player to move.
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Returns the symbols of the players.
+    pub fn players(&self) -> &[char] {
+        &self.players
+    }
+
+    /// Returns the layout of the board.
+    pub fn layout(&self) -> &str {
+        &self.layout
+    }
+}
+
+impl From<&GameHex> for HEN {
+    fn from(game: &GameHex) -> Self {
+        let size = game.board_size();
+        let turn = match game.status() {
+            GameStatus::Ongoing { next_player } => next_player.id(),
+            GameStatus::Finished { winner, .. } => (winner.id() + 1) % 2,
+        };
+        let rows: Vec<String> = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| match game.engine().occupant(HexCell::new(row, col).to_index(size) as usize) {
+                        Some(player) => PLAYER_SYMBOLS[player.id() as usize],
+                        None => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        HEN::new(size, turn, PLAYER_SYMBOLS[..2].to_vec(), rows.join("/"))
+    }
+}
+
+impl TryFrom<HEN> for GameHex {
+    type Error = GameYError;
+
+    /// Places the stones of the layout row by row and gives the turn to `turn`.
+    ///
+    /// Fails with [`GameYError::InvalidHEN`] if there are not two players,
+    /// the turn is not 0 or 1, or the layout does not have `size` rows of
+    /// `size` player symbols or dots.
+    fn try_from(hen: HEN) -> Result<Self, GameYError> {
+        let invalid = |message: String| GameYError::InvalidHEN { message };
+        if hen.players.len() != 2 {
+            return Err(invalid(format!("expected 2 players, found {}", hen.players.len())));
+        }
+        if hen.turn > 1 {
+            return Err(invalid(format!("turn {} is not 0 or 1", hen.turn)));
+        }
+        let rows: Vec<&str> = hen.layout.split('/').collect();
+        if rows.len() != hen.size as usize {
+            return Err(invalid(format!("expected {} rows, found {}", hen.size, rows.len())));
+        }
+        let mut game = GameHex::new(hen.size);
+        for (row, cells) in rows.iter().enumerate() {
+            if cells.chars().count() != hen.size as usize {
+                return Err(invalid(format!(
+                    "row {} has {} cells, expected {}",
+                    row + 1,
+                    cells.chars().count(),
+                    hen.size
+                )));
+            }
+            for (col, symbol) in cells.chars().enumerate() {
+                if symbol == '.' {
+                    continue;
+                }
+                let player = hen
+                    .players
+                    .iter()
+                    .position(|p| *p == symbol)
+                    .ok_or_else(|| invalid(format!("unknown symbol '{}' in row {}", symbol, row + 1)))?;
+                game.apply_move(HexMove::Placement {
+                    player: PlayerId::new(player as u32),
+                    cell: HexCell::new(row as u32, col as u32),
+                })?;
+            }
+        }
+        // The stones are placed in layout order, so the turn comes from the HEN
+        game.set_next_player(PlayerId::new(hen.turn));
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut game = GameHex::new(3);
+        game.add_move(HexMove::Placement {
+            player: PlayerId::new(0),
+            cell: HexCell::new(0, 1),
+        })
+        .unwrap();
+        let hen = HEN::from(&game);
+        assert_eq!(hen.layout(), ".B./.../...");
+        assert_eq!(hen.turn(), 1);
+        let json = serde_json::to_value(&hen).unwrap();
+        assert_eq!(json["game"], "hex");
+
+        let back = GameHex::try_from(serde_json::from_value::<HEN>(json).unwrap()).unwrap();
+        assert_eq!(back.next_player(), Some(PlayerId::new(1)));
+        assert_eq!(HEN::from(&back), hen);
+    }
+
+    #[test]
+    fn test_won_layout_is_finished() {
+        let hen = HEN::new(2, 1, vec!['B', 'R'], "B./B.".to_string());
+        let game = GameHex::try_from(hen).unwrap();
+        assert_eq!(game.status().winner(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        let yen = serde_json::json!({"size": 2, "turn": 0, "players": ["B", "R"], "layout": "./.."});
+        assert!(serde_json::from_value::<HEN>(yen).is_err());
+        for (layout, message) in [("../..", ""), ("B./.", "row 2 has 1 cells"), ("X./..", "unknown symbol 'X'"), ("..", "expected 2 rows")] {
+            let result = GameHex::try_from(HEN::new(2, 0, vec!['B', 'R'], layout.to_string()));
+            if message.is_empty() {
+                assert!(result.is_ok());
+            } else {
+                let err = result.unwrap_err().to_string();
+                assert!(err.contains(message), "{}: {}", layout, err);
+            }
+        }
+        assert!(GameHex::try_from(HEN::new(2, 2, vec!['B', 'R'], "../..".to_string())).is_err());
+    }
+}
@@ -4,6 +4,30 @@
 //! in a compact, portable way. Currently supported:
 //!
 //! - [`YEN`]: Y Exchange Notation - a JSON-based format inspired by chess FEN
+//! - [`HEN`]: Hex Exchange Notation, the same for [`crate::GameHex`]
+//! - SGF: Smart Game Format, see [`to_sgf`] and [`from_sgf`], and [`tree_to_sgf`] for variations
+//! - Game records: a binary format keeping the whole history, see [`write_record`]
+//!   and [`write_tree_record`]
+//! - Collections: many games in one JSON-lines file, see [`CollectionWriter`] and [`iter_games`]
+//! - Move text: numbered move lists like `1. a1 b3 2. c3`, see [`crate::GameY::to_move_text`]
+//! - URL tokens: short base64url strings for share links, see [`YEN::to_url_token`]
+//!
+//! [`GameFormat`] names these formats and detects them from file names and contents,
+//! and [`convert()`] converts saved games between them.
 
+pub mod collection;
+pub mod convert;
+pub mod format;
+pub mod hen;
+pub mod move_text;
+pub mod record;
+pub mod sgf;
+pub mod token;
 pub mod yen;
+pub use collection::*;
+pub use convert::*;
+pub use format::*;
+pub use hen::*;
+pub use record::*;
+pub use sgf::*;
 pub use yen::*;
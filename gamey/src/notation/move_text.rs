@@ -0,0 +1,316 @@
+use crate::{Coordinates, GameAction, GameY, GameYError, Movement, PLAYER_SYMBOLS, PlayerId};
+
+impl GameY {
+    /// Writes the moves of the game as a numbered move list, like `1. a1 b3 2. c3 swap`.
+    ///
+    /// Every round of moves is preceded by its number. A cell is written as
+    /// its column letter inside the row followed by the row number counted
+    /// from the top corner: `a1` is the top corner and `a3`, `c3` are the two
+    /// other corners of a size 3 board. Columns after `z` continue with `aa`,
+    /// `ab`... Swaps and resignations are written as `swap` and `resign`.
+    ///
+    /// A finished game ends with a result marker made of the winner's symbol
+    /// and `+`, like `B+` or `R+`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{Coordinates, GameY, Movement, PlayerId};
+    ///
+    /// let mut game = GameY::new(3);
+    /// game.add_move(Movement::Placement {
+    ///     player: PlayerId::new(0),
+    ///     coords: Coordinates::new(0, 1, 1),
+    /// })
+    /// .unwrap();
+    /// assert_eq!(game.to_move_text(), "1. b3");
+    /// ```
+    pub fn to_move_text(&self) -> String {
+        let mut tokens = Vec::new();
+        for (ply, movement) in self.history().iter().enumerate() {
+            if ply % self.num_players() as usize == 0 {
+                tokens.push(format!("{}.", ply / self.num_players() as usize + 1));
+            }
+            tokens.push(match movement {
+                Movement::Placement { coords, .. } => cell_name(*coords, self.board_size()),
+                Movement::Action { action, .. } => action.notation().to_string(),
+            });
+        }
+        if let crate::GameStatus::Finished { winner, .. } = self.status() {
+            tokens.push(format!("{}+", PLAYER_SYMBOLS[winner.id() as usize]));
+        }
+        tokens.join(" ")
+    }
+
+    /// Reads a two-player game of size `size` from a move list written by [`GameY::to_move_text`].
+    ///
+    /// Tokens are separated by any whitespace, so the list may span several
+    /// lines. Move numbers are optional but must be right when present.
+    /// Every move is replayed with [`GameY::add_move`], so illegal moves are
+    /// rejected. A result marker must be the last token and agree with the
+    /// game; if the moves do not finish the game, it means the player to move
+    /// lost on time.
+    ///
+    /// Errors are [`GameYError::InvalidMoveText`] with the line and column of
+    /// the offending token, or [`GameYError::InvalidBoardSize`] if `size` is
+    /// out of range.
+    pub fn from_move_text(size: u32, text: &str) -> Result<GameY, GameYError> {
+        let mut game = GameY::try_new(size)?;
+        let mut result: Option<PlayerId> = None;
+
+        for token in tokenize(text) {
+            let error = |message: String| GameYError::InvalidMoveText {
+                line: token.line,
+                column: token.column,
+                message,
+            };
+            let ply = game.move_count() + 1;
+            if result.is_some() {
+                return Err(error(format!(
+                    "unexpected '{}' after the result",
+                    token.text
+                )));
+            }
+
+            if let Some(number) = token.text.strip_suffix('.') {
+                let expected = game.move_count() / game.num_players() as usize + 1;
+                match number.parse::<usize>() {
+                    Ok(n) if n == expected => continue,
+                    Ok(n) => {
+                        return Err(error(format!(
+                            "move number {} should be {}",
+                            n, expected
+                        )));
+                    }
+                    Err(_) => return Err(error(format!("bad move number '{}'", token.text))),
+                }
+            }
+            if let Some(symbol) = token.text.strip_suffix('+') {
+                let winner = PLAYER_SYMBOLS[..game.num_players() as usize]
+                    .iter()
+                    .position(|s| symbol.len() == 1 && symbol.starts_with(*s))
+                    .ok_or_else(|| error(format!("unknown result '{}'", token.text)))?;
+                result = Some(PlayerId::new(winner as u32));
+                if let Some(player) = game.next_player() {
+                    game.lose_on_time(player);
+                }
+                if let crate::GameStatus::Finished { winner: actual, .. } = game.status()
+                    && actual.id() != winner as u32
+                {
+                    return Err(error(format!(
+                        "result '{}' does not match the game, won by {}",
+                        token.text, PLAYER_SYMBOLS[actual.id() as usize]
+                    )));
+                }
+                continue;
+            }
+
+            let player = game
+                .next_player()
+                .ok_or_else(|| error(format!("move {} '{}' after the end of the game", ply, token.text)))?;
+            let movement = match token.text.to_ascii_lowercase().as_str() {
+                "swap" => Movement::Action {
+                    player,
+                    action: GameAction::Swap,
+                },
+                "resign" => Movement::Action {
+                    player,
+                    action: GameAction::Resign,
+                },
+                name => Movement::Placement {
+                    player,
+                    coords: parse_cell_name(name, size).ok_or_else(|| {
+                        error(format!("move {} '{}' is not a cell of the board", ply, token.text))
+                    })?,
+                },
+            };
+            game.add_move(movement)
+                .map_err(|e| error(format!("move {} '{}' is illegal: {}", ply, token.text, e)))?;
+        }
+        Ok(game)
+    }
+}
+
+/// A whitespace separated word of a move list and where it starts.
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+/// Splits `text` into tokens, remembering their 1-based line and column.
+///
+/// A move number glued to the following move (`1.a1`) gives two tokens.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (line, content) in text.lines().enumerate() {
+        let mut start = None;
+        for (i, c) in content.char_indices().chain([(content.len(), ' ')]) {
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some(i),
+                (true, Some(s)) => {
+                    split_move_number(&content[s..i], line + 1, content[..s].chars().count() + 1, &mut tokens);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    tokens
+}
+
+fn split_move_number<'a>(word: &'a str, line: usize, column: usize, tokens: &mut Vec<Token<'a>>) {
+    let digits = word.chars().take_while(char::is_ascii_digit).count();
+    let split = if digits > 0 && word[digits..].starts_with('.') && word.len() > digits + 1 {
+        digits + 1
+    } else {
+        word.len()
+    };
+    tokens.push(Token {
+        text: &word[..split],
+        line,
+        column,
+    });
+    if split < word.len() {
+        tokens.push(Token {
+            text: &word[split..],
+            line,
+            column: column + split,
+        });
+    }
+}
+
+/// Name of a cell: the column letters inside the row, then the row number from the top.
+pub(crate) fn cell_name(coords: Coordinates, size: u32) -> String {
+    let row = size - coords.x();
+    column_name(coords.y()) + &row.to_string()
+}
+
+/// Letters of the column `y` of a cell: `a` to `z`, then `aa`, `ab`...
+pub(crate) fn column_name(y: u32) -> String {
+    let mut column = y + 1;
+    let mut letters = Vec::new();
+    while column > 0 {
+        column -= 1;
+        letters.push((b'a' + (column % 26) as u8) as char);
+        column /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Reads a cell name written by [`cell_name`], if it is on a board of `size`.
+pub(crate) fn parse_cell_name(name: &str, size: u32) -> Option<Coordinates> {
+    let letters = name.chars().take_while(char::is_ascii_lowercase).count();
+    if letters == 0 {
+        return None;
+    }
+    let mut column: u32 = 0;
+    for c in name[..letters].chars() {
+        column = column.checked_mul(26)?.checked_add(c as u32 - 'a' as u32 + 1)?;
+    }
+    let row: u32 = name[letters..].parse().ok()?;
+    if row == 0 || row > size || column > row {
+        return None;
+    }
+    let x = size - row;
+    let y = column - 1;
+    Some(Coordinates::new(x, y, size - 1 - x - y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(game: &mut GameY, idx: u32) {
+        let player = game.next_player().unwrap();
+        let coords = Coordinates::from_index(idx, game.board_size());
+        game.add_move(Movement::Placement { player, coords }).unwrap();
+    }
+
+    #[test]
+    fn test_cell_names() {
+        assert_eq!(cell_name(Coordinates::new(2, 0, 0), 3), "a1");
+        assert_eq!(cell_name(Coordinates::new(0, 0, 2), 3), "a3");
+        assert_eq!(cell_name(Coordinates::new(0, 2, 0), 3), "c3");
+        assert_eq!(cell_name(Coordinates::new(0, 26, 3), 30), "aa30");
+        for size in [1, 5, 30] {
+            for idx in 0..size * (size + 1) / 2 {
+                let coords = Coordinates::from_index(idx, size);
+                assert_eq!(parse_cell_name(&cell_name(coords, size), size), Some(coords));
+            }
+        }
+        assert_eq!(parse_cell_name("b1", 3), None);
+        assert_eq!(parse_cell_name("a4", 3), None);
+        assert_eq!(parse_cell_name("a0", 3), None);
+        assert_eq!(parse_cell_name("3", 3), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut game = GameY::new(5);
+        place(&mut game, 4);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        place(&mut game, 12);
+        place(&mut game, 0);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        let text = game.to_move_text();
+        assert_eq!(text, "1. b3 swap 2. c5 a1 3. resign R+");
+        let loaded = GameY::from_move_text(5, &text).unwrap();
+        assert_eq!(loaded.history(), game.history());
+        assert!(matches!(loaded.status(), crate::GameStatus::Finished { winner, .. } if winner.id() == 1));
+    }
+
+    #[test]
+    fn test_round_trip_of_a_loss_on_time() {
+        let mut game = GameY::new(4);
+        place(&mut game, 3);
+        game.lose_on_time(PlayerId::new(1));
+
+        let text = game.to_move_text();
+        assert_eq!(text, "1. a3 B+");
+        let loaded = GameY::from_move_text(4, &text).unwrap();
+        assert_eq!(loaded.lost_on_time(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_read_is_lenient_with_layout() {
+        let game = GameY::from_move_text(4, "1.a1  b2\n 2. C4\n\n").unwrap();
+        assert_eq!(game.history().len(), 3);
+        assert_eq!(GameY::from_move_text(4, "").unwrap().history().len(), 0);
+        assert!(matches!(GameY::from_move_text(0, ""), Err(GameYError::InvalidBoardSize { .. })));
+    }
+
+    #[test]
+    fn test_illegal_move_reports_its_number_and_position() {
+        let err = GameY::from_move_text(4, "1. a1 b2\n2. c3 a1 3. d4").unwrap_err();
+        assert!(matches!(err, GameYError::InvalidMoveText { line: 2, column: 7, .. }));
+        assert!(err.to_string().contains("move 4 'a1' is illegal"), "{}", err);
+    }
+
+    #[test]
+    fn test_bad_tokens_are_located() {
+        let err = GameY::from_move_text(3, "1. a1\n   zz9").unwrap_err();
+        assert!(matches!(err, GameYError::InvalidMoveText { line: 2, column: 4, .. }));
+
+        let err = GameY::from_move_text(3, "2. a1").unwrap_err();
+        assert!(err.to_string().contains("move number 2 should be 1"));
+
+        let err = GameY::from_move_text(3, "1. a1 B+ b2").unwrap_err();
+        assert!(err.to_string().contains("after the result"));
+
+        let err = GameY::from_move_text(3, "1. a1 resign R+").unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{}", err);
+
+        assert!(GameY::from_move_text(3, "1. a1 G+").is_err());
+        assert!(GameY::from_move_text(3, "1. a1 resign b2").is_err());
+    }
+}
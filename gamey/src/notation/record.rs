@@ -0,0 +1,571 @@
+use crate::{
+    Coordinates, GameAction, GameFormat, GameTree, GameY, GameYError, MoveAnnotation, Movement,
+    NodeId, PlayerId, PlayerInfo,
+};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+/// Magic bytes at the start of every game record, followed by the version byte.
+pub const RECORD_MAGIC: &[u8; 3] = b"YGR";
+
+/// Version of the record layout written by [`write_record`].
+pub const RECORD_VERSION: u8 = 1;
+
+/// Chunk with the explicit player information, as JSON.
+const CHUNK_PLAYERS: u8 = b'P';
+/// Chunk with the move list.
+const CHUNK_MOVES: u8 = b'M';
+/// Chunk with the annotations of the moves, as JSON.
+const CHUNK_ANNOTATIONS: u8 = b'A';
+/// Chunk with the variations and annotations of a [`GameTree`].
+const CHUNK_TREE: u8 = b'T';
+/// Chunk with how the game ended, when the moves do not show it.
+const CHUNK_RESULT: u8 = b'R';
+
+/// Flags of a node of the tree chunk.
+const NODE_COMMENT: u8 = 1;
+const NODE_EVAL: u8 = 2;
+const NODE_MARKS: u8 = 4;
+
+const MOVE_PLACEMENT: u8 = 0;
+const MOVE_SWAP: u8 = 1;
+const MOVE_RESIGN: u8 = 2;
+
+const RESULT_TIMEOUT: u8 = 0;
+const RESULT_FORFEIT: u8 = 1;
+
+/// Writes `game` as a binary game record (`.ygr`).
+///
+/// Unlike YEN, a record keeps the full move history, so the exact game can be
+/// replayed. The layout is:
+///
+/// - the magic bytes `YGR` and a version byte,
+/// - the board size as a little-endian `u32` and the number of players as a `u8`,
+/// - a sequence of chunks, each a tag byte, a little-endian `u32` length and
+///   the payload. Readers skip chunks with unknown tags.
+///
+/// The moves chunk stores every move as a kind byte (placement, swap or
+/// resign), the player byte and, for placements, the cell index as a `u32`.
+/// A game lost on time or by forfeit has a result chunk after the moves with
+/// the reason byte (0 for time, 1 for forfeit) and the loser's player byte.
+/// If the game has annotations, an annotations chunk follows with a JSON
+/// object from plies to [`crate::MoveAnnotation`]s.
+pub fn write_record<W: Write>(game: &GameY, writer: W) -> Result<(), GameYError> {
+    write_bytes(&record_bytes(game)?, writer)
+}
+
+/// Writes a game tree as a game record, like [`write_record`], with its variations.
+///
+/// The moves chunk holds the main line, so [`read_record`] reads the tree as
+/// that game. A tree chunk follows with every node in the order of
+/// [`GameTree::nodes`]: the index of its parent as a `u32` (except for the
+/// root), its move as in the moves chunk (except for the root), a flags byte
+/// and, when flagged, the comment as a `u32` length and UTF-8 bytes, the
+/// evaluation as a little-endian `f32` and the marks as a `u32` count and
+/// the cell index of each mark.
+pub fn write_tree_record<W: Write>(tree: &GameTree, writer: W) -> Result<(), GameYError> {
+    let size = tree.board_size();
+    let mut bytes = record_bytes(&tree.to_game())?;
+    let mut nodes = Vec::new();
+    for node in tree.nodes() {
+        if let (Some(parent), Some(movement)) = (node.parent(), node.movement()) {
+            nodes.extend_from_slice(&(parent as u32).to_le_bytes());
+            push_move(&mut nodes, movement, size);
+        }
+        let mut flags = 0;
+        if node.comment.is_some() {
+            flags |= NODE_COMMENT;
+        }
+        if node.eval.is_some() {
+            flags |= NODE_EVAL;
+        }
+        if !node.marks.is_empty() {
+            flags |= NODE_MARKS;
+        }
+        nodes.push(flags);
+        if let Some(comment) = &node.comment {
+            nodes.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            nodes.extend_from_slice(comment.as_bytes());
+        }
+        if let Some(eval) = node.eval {
+            nodes.extend_from_slice(&eval.to_le_bytes());
+        }
+        if !node.marks.is_empty() {
+            nodes.extend_from_slice(&(node.marks.len() as u32).to_le_bytes());
+            for mark in &node.marks {
+                nodes.extend_from_slice(&mark.to_index(size).to_le_bytes());
+            }
+        }
+    }
+    push_chunk(&mut bytes, CHUNK_TREE, &nodes);
+    write_bytes(&bytes, writer)
+}
+
+/// Encodes the header, players and moves of `game`.
+fn record_bytes(game: &GameY) -> Result<Vec<u8>, GameYError> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(RECORD_MAGIC);
+    bytes.push(RECORD_VERSION);
+    bytes.extend_from_slice(&game.board_size().to_le_bytes());
+    bytes.push(game.num_players() as u8);
+
+    let named: Vec<PlayerInfo> = game
+        .players()
+        .into_iter()
+        .filter(|info| *info != PlayerInfo::default_for(info.id))
+        .collect();
+    if !named.is_empty() {
+        let payload =
+            serde_json::to_vec(&named).map_err(|e| GameYError::SerdeError { error: e })?;
+        push_chunk(&mut bytes, CHUNK_PLAYERS, &payload);
+    }
+
+    let mut moves = Vec::new();
+    for movement in game.history().iter() {
+        push_move(&mut moves, movement, game.board_size());
+    }
+    push_chunk(&mut bytes, CHUNK_MOVES, &moves);
+
+    if let Some(player) = game.lost_on_time() {
+        push_chunk(&mut bytes, CHUNK_RESULT, &[RESULT_TIMEOUT, player.id() as u8]);
+    } else if let Some(player) = game.forfeited() {
+        push_chunk(&mut bytes, CHUNK_RESULT, &[RESULT_FORFEIT, player.id() as u8]);
+    }
+
+    if !game.annotations().is_empty() {
+        let annotations: BTreeMap<_, _> = game.annotations().iter().collect();
+        let payload =
+            serde_json::to_vec(&annotations).map_err(|e| GameYError::SerdeError { error: e })?;
+        push_chunk(&mut bytes, CHUNK_ANNOTATIONS, &payload);
+    }
+    Ok(bytes)
+}
+
+fn write_bytes<W: Write>(bytes: &[u8], mut writer: W) -> Result<(), GameYError> {
+    writer
+        .write_all(bytes)
+        .and_then(|_| writer.flush())
+        .map_err(|e| GameYError::IoError {
+            message: "Failed to write game record".to_string(),
+            error: e.to_string(),
+        })
+}
+
+/// Appends a move: its kind, the player and, for placements, the cell index.
+fn push_move(bytes: &mut Vec<u8>, movement: &Movement, size: u32) {
+    match movement {
+        Movement::Placement { player, coords } => {
+            bytes.push(MOVE_PLACEMENT);
+            bytes.push(player.id() as u8);
+            bytes.extend_from_slice(&coords.to_index(size).to_le_bytes());
+        }
+        Movement::Action { player, action } => {
+            bytes.push(match action {
+                GameAction::Swap => MOVE_SWAP,
+                GameAction::Resign => MOVE_RESIGN,
+            });
+            bytes.push(player.id() as u8);
+        }
+    }
+}
+
+/// Reads a game written by [`write_record`], replaying its moves.
+///
+/// The variations of a record written by [`write_tree_record`] are skipped.
+pub fn read_record<R: Read>(reader: R) -> Result<GameY, GameYError> {
+    let bytes = read_bytes(reader)?;
+    Ok(parse_record(&bytes)?.0)
+}
+
+/// Reads a game tree written by [`write_tree_record`].
+///
+/// A record without variations, such as one written by [`write_record`],
+/// is read as a tree with a single line. The cursor is left at the root.
+pub fn read_tree_record<R: Read>(reader: R) -> Result<GameTree, GameYError> {
+    let bytes = read_bytes(reader)?;
+    let (game, nodes) = parse_record(&bytes)?;
+    let mut tree = match nodes {
+        Some(payload) => read_tree(&game, payload)?,
+        None => GameTree::from_game(&game),
+    };
+    tree.to_root();
+    Ok(tree)
+}
+
+fn read_bytes<R: Read>(mut reader: R) -> Result<Vec<u8>, GameYError> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| GameYError::IoError {
+            message: "Failed to read game record".to_string(),
+            error: e.to_string(),
+        })?;
+    Ok(bytes)
+}
+
+/// Reads the header and chunks of a record: the game of its moves and the payload of its tree chunk.
+fn parse_record(bytes: &[u8]) -> Result<(GameY, Option<&[u8]>), GameYError> {
+    let mut input = RecordReader { bytes, pos: 0 };
+
+    if input.take(3)? != RECORD_MAGIC {
+        return Err(invalid("missing YGR magic bytes".to_string()));
+    }
+    let version = input.u8()?;
+    if version != RECORD_VERSION {
+        return Err(invalid(format!("unsupported version {}", version)));
+    }
+    let size = input.u32()?;
+    crate::check_board_size(size).map_err(|e| invalid(e.to_string()))?;
+    let num_players = input.u8()? as u32;
+    let mut game = GameY::with_players(size, num_players);
+    let mut tree = None;
+
+    while !input.is_empty() {
+        let tag = input.u8()?;
+        let len = input.u32()? as usize;
+        let payload = input.take(len)?;
+        match tag {
+            CHUNK_PLAYERS => {
+                let players: Vec<PlayerInfo> = serde_json::from_slice(payload)
+                    .map_err(|e| invalid(format!("bad players chunk: {}", e)))?;
+                game = game.with_player_info(players);
+            }
+            CHUNK_MOVES => replay_moves(&mut game, payload)?,
+            CHUNK_ANNOTATIONS => {
+                let annotations: BTreeMap<usize, MoveAnnotation> = serde_json::from_slice(payload)
+                    .map_err(|e| invalid(format!("bad annotations chunk: {}", e)))?;
+                for (ply, annotation) in annotations {
+                    game.annotate(ply, annotation)
+                        .map_err(|e| invalid(format!("bad annotation of move {}: {}", ply, e)))?;
+                }
+            }
+            CHUNK_TREE => tree = Some(payload),
+            CHUNK_RESULT => match payload {
+                [RESULT_TIMEOUT, player] => game.lose_on_time(PlayerId::new(*player as u32)),
+                [RESULT_FORFEIT, player] => game.forfeit(PlayerId::new(*player as u32)),
+                _ => return Err(invalid(format!("bad result chunk {:?}", payload))),
+            },
+            _ => tracing::debug!("Skipping unknown record chunk {}", tag),
+        }
+    }
+    Ok((game, tree))
+}
+
+/// Plays the moves of a moves chunk on `game`.
+fn replay_moves(game: &mut GameY, payload: &[u8]) -> Result<(), GameYError> {
+    let mut input = RecordReader {
+        bytes: payload,
+        pos: 0,
+    };
+    while !input.is_empty() {
+        let ply = game.move_count() + 1;
+        let movement = input.movement(game.board_size(), &format!("move {}", ply))?;
+        game.add_move(movement)
+            .map_err(|e| invalid(format!("move {} cannot be played: {}", ply, e)))?;
+    }
+    Ok(())
+}
+
+/// Rebuilds the tree of a tree chunk, starting from the players of `game`.
+fn read_tree(game: &GameY, payload: &[u8]) -> Result<GameTree, GameYError> {
+    let mut input = RecordReader {
+        bytes: payload,
+        pos: 0,
+    };
+    let mut tree = GameTree::from_game(&game.empty_copy());
+    // Ids in the tree of the nodes read so far, which may differ if a move is repeated
+    let mut ids: Vec<NodeId> = Vec::new();
+    while !input.is_empty() {
+        let number = ids.len();
+        if number > 0 {
+            let parent = input.u32()? as usize;
+            let &parent_id = ids
+                .get(parent)
+                .ok_or_else(|| invalid(format!("node {} has unknown parent {}", number, parent)))?;
+            let movement = input.movement(tree.board_size(), &format!("node {}", number))?;
+            tree.goto(parent_id);
+            tree.add_move(movement)
+                .map_err(|e| invalid(format!("node {} cannot be played: {}", number, e)))?;
+        }
+        ids.push(tree.cursor());
+        let flags = input.u8()?;
+        if flags & NODE_COMMENT != 0 {
+            let len = input.u32()? as usize;
+            let comment = std::str::from_utf8(input.take(len)?)
+                .map_err(|_| invalid(format!("node {} has a comment that is not UTF-8", number)))?;
+            tree.current_node_mut().comment = Some(comment.to_string());
+        }
+        if flags & NODE_EVAL != 0 {
+            let mut buf = [0; 4];
+            buf.copy_from_slice(input.take(4)?);
+            tree.current_node_mut().eval = Some(f32::from_le_bytes(buf));
+        }
+        if flags & NODE_MARKS != 0 {
+            for _ in 0..input.u32()? {
+                let idx = input.u32()?;
+                let mark = Coordinates::try_from_index(idx, tree.board_size())
+                    .map_err(|_| invalid(format!("node {} marks cell {} outside the board", number, idx)))?;
+                tree.current_node_mut().marks.push(mark);
+            }
+        }
+    }
+    Ok(tree)
+}
+
+fn push_chunk(bytes: &mut Vec<u8>, tag: u8, payload: &[u8]) {
+    bytes.push(tag);
+    bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(payload);
+}
+
+fn invalid(message: String) -> GameYError {
+    GameYError::InvalidFormat {
+        format: GameFormat::Record,
+        message,
+    }
+}
+
+/// Cursor over the bytes of a record that reports truncation as an error.
+struct RecordReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RecordReader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], GameYError> {
+        let end = self.pos.saturating_add(len);
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or_else(|| invalid(format!("unexpected end of data at byte {}", self.pos)))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, GameYError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, GameYError> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    /// Reads a move written by [`push_move`]; `what` names it in errors.
+    fn movement(&mut self, size: u32, what: &str) -> Result<Movement, GameYError> {
+        let kind = self.u8()?;
+        let player = PlayerId::new(self.u8()? as u32);
+        Ok(match kind {
+            MOVE_PLACEMENT => {
+                let idx = self.u32()?;
+                let coords = Coordinates::try_from_index(idx, size)
+                    .map_err(|_| invalid(format!("{} is outside the board: cell {}", what, idx)))?;
+                Movement::Placement { player, coords }
+            }
+            MOVE_SWAP => Movement::Action {
+                player,
+                action: GameAction::Swap,
+            },
+            MOVE_RESIGN => Movement::Action {
+                player,
+                action: GameAction::Resign,
+            },
+            other => return Err(invalid(format!("{} has unknown kind {}", what, other))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameStatus, PlayerKind, WinReason};
+
+    fn sample_game() -> GameY {
+        let mut game = GameY::new(3).with_player_info(vec![PlayerInfo::new(
+            PlayerId::new(1),
+            "Random",
+            PlayerKind::Bot("random_bot".to_string()),
+        )]);
+        let moves = [
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(1, 1, 0),
+            },
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            },
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(0, 0, 2),
+            },
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Resign,
+            },
+        ];
+        for movement in moves {
+            game.add_move(movement).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_round_trip_keeps_history_and_players() {
+        let game = sample_game();
+        let mut bytes = Vec::new();
+        write_record(&game, &mut bytes).unwrap();
+        assert!(bytes.starts_with(b"YGR\x01"));
+
+        let loaded = read_record(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.players(), game.players());
+        assert!(loaded.check_game_over());
+    }
+
+    #[test]
+    fn test_annotations_survive_a_round_trip() {
+        let mut game = sample_game();
+        let marked = MoveAnnotation::comment("too close to the edge")
+            .with_mark(Coordinates::new(0, 1, 1))
+            .with_eval(-0.25);
+        game.annotate(1, marked).unwrap();
+        game.annotate(3, MoveAnnotation::comment("the only move")).unwrap();
+
+        let mut bytes = Vec::new();
+        write_record(&game, &mut bytes).unwrap();
+        let loaded = read_record(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.annotations(), game.annotations());
+        assert_eq!(loaded.annotation(1).unwrap().marks, vec![Coordinates::new(0, 1, 1)]);
+
+        // Through a tree, whose nodes keep them too
+        let mut bytes = Vec::new();
+        write_tree_record(&GameTree::from_game(&game), &mut bytes).unwrap();
+        let tree = read_tree_record(bytes.as_slice()).unwrap();
+        assert_eq!(tree.to_game().annotations(), game.annotations());
+    }
+
+    #[test]
+    fn test_truncated_record_is_rejected() {
+        let mut bytes = Vec::new();
+        write_record(&sample_game(), &mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 3);
+        let err = read_record(bytes.as_slice()).unwrap_err();
+        assert!(matches!(
+            err,
+            GameYError::InvalidFormat {
+                format: GameFormat::Record,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("unexpected end"));
+    }
+
+    #[test]
+    fn test_timeouts_and_forfeits_survive_a_round_trip() {
+        for (end, reason) in [
+            (GameY::lose_on_time as fn(&mut GameY, PlayerId), WinReason::Timeout),
+            (GameY::forfeit, WinReason::Forfeit),
+        ] {
+            let mut game = GameY::new(3);
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(1, 1, 0),
+            })
+            .unwrap();
+            end(&mut game, PlayerId::new(1));
+
+            let mut bytes = Vec::new();
+            write_record(&game, &mut bytes).unwrap();
+            let loaded = read_record(bytes.as_slice()).unwrap();
+            assert_eq!(
+                loaded.status(),
+                &GameStatus::Finished {
+                    winner: PlayerId::new(0),
+                    reason
+                }
+            );
+            assert!(loaded.same_history(&game));
+        }
+    }
+
+    #[test]
+    fn test_unknown_chunks_are_skipped() {
+        let mut bytes = Vec::new();
+        write_record(&GameY::new(2), &mut bytes).unwrap();
+        push_chunk(&mut bytes, b'Z', b"future data");
+        assert_eq!(read_record(bytes.as_slice()).unwrap().board_size(), 2);
+    }
+
+    #[test]
+    fn test_tree_round_trip_keeps_variations_and_comments() {
+        let mut tree = GameTree::from_game(&sample_game());
+        tree.to_root();
+        tree.current_node_mut().comment = Some("Opening ñ".to_string());
+        tree.forward();
+        tree.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(0, 2, 0),
+        })
+        .unwrap();
+        tree.current_node_mut().eval = Some(-1.5);
+        tree.current_node_mut().comment = Some("instead of swapping".to_string());
+
+        let mut bytes = Vec::new();
+        write_tree_record(&tree, &mut bytes).unwrap();
+        let loaded = read_tree_record(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.nodes(), tree.nodes());
+        assert_eq!(loaded.start().players(), tree.start().players());
+
+        // Plain readers see the main line
+        assert_eq!(read_record(bytes.as_slice()).unwrap().history(), sample_game().history());
+    }
+
+    #[test]
+    fn test_plain_record_reads_as_a_single_line() {
+        let mut bytes = Vec::new();
+        write_record(&sample_game(), &mut bytes).unwrap();
+        let tree = read_tree_record(bytes.as_slice()).unwrap();
+        assert_eq!(tree.len(), sample_game().history().len() + 1);
+        assert_eq!(tree.to_game().history(), sample_game().history());
+    }
+
+    #[test]
+    fn test_tree_node_with_unknown_parent_is_rejected() {
+        let mut bytes = Vec::new();
+        write_record(&GameY::new(3), &mut bytes).unwrap();
+        let mut nodes = vec![0];
+        nodes.extend_from_slice(&7u32.to_le_bytes());
+        push_move(&mut nodes, &Movement::Action { player: PlayerId::new(0), action: GameAction::Resign }, 3);
+        nodes.push(0);
+        push_chunk(&mut bytes, CHUNK_TREE, &nodes);
+        let err = read_tree_record(bytes.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("node 1 has unknown parent 7"), "{}", err);
+    }
+
+    #[test]
+    fn test_oversized_board_is_rejected() {
+        let mut bytes = Vec::new();
+        write_record(&GameY::new(3), &mut bytes).unwrap();
+        // The size follows the magic bytes and the version
+        bytes[4..8].copy_from_slice(&1_000_000u32.to_le_bytes());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("huge.ygr");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let err = GameY::load_from_file(&path).unwrap_err();
+        assert!(matches!(err, GameYError::InvalidFormat { format: GameFormat::Record, .. }));
+        assert!(err.to_string().contains("1000000"), "{}", err);
+    }
+
+    #[test]
+    fn test_bad_magic_is_rejected() {
+        let err = read_record(&b"{\"size\": 3}"[..]).unwrap_err();
+        assert!(err.to_string().contains("magic"));
+    }
+}
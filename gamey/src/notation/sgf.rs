@@ -0,0 +1,530 @@
+use crate::{
+    Coordinates, GameAction, GameFormat, GameTree, GameY, GameYError, Movement, NodeId, PlayerId,
+    PlayerInfo, PlayerKind, TreeNode,
+};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Largest board whose points can be written with SGF letters (`a-z`, `A-Z`).
+pub const SGF_MAX_SIZE: u32 = 52;
+
+/// Writes a two-player game in Smart Game Format.
+///
+/// Player 0 plays `B` and player 1 plays `W`; stones keep their color after a
+/// swap. A point is written as two letters, the column inside the row and the
+/// row counted from the top corner (`aa` is the top corner). Swaps and
+/// resignations are written as the values `swap` and `resign`. Player names
+/// are kept in `PB`/`PW`, but whether a player is a bot is lost.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, to_sgf};
+///
+/// assert_eq!(to_sgf(&GameY::new(3)).unwrap(), "(;FF[4]GM[Y]SZ[3])");
+/// ```
+pub fn to_sgf(game: &GameY) -> Result<String, GameYError> {
+    tree_to_sgf(&GameTree::from_game(game))
+}
+
+/// Writes a game tree in Smart Game Format, like [`to_sgf`], with its variations.
+///
+/// The first child of a node is written first, so it stays the main line
+/// when the file is read back. Comments are written as `C`, marked cells as
+/// `MA` and evaluations as `V`, all on the node of the move they belong to.
+pub fn tree_to_sgf(tree: &GameTree) -> Result<String, GameYError> {
+    let start = tree.start();
+    if start.num_players() > 2 {
+        return Err(invalid("SGF only supports two players".to_string()));
+    }
+    let size = tree.board_size();
+    if size > SGF_MAX_SIZE {
+        return Err(invalid(format!(
+            "board size {} is larger than {}",
+            size, SGF_MAX_SIZE
+        )));
+    }
+
+    let mut sgf = format!("(;FF[4]GM[Y]SZ[{}]", size);
+    for info in start.players() {
+        if info != PlayerInfo::default_for(info.id) {
+            let property = if info.id.id() == 0 { "PB" } else { "PW" };
+            sgf.push_str(&format!("{}[{}]", property, escape(&info.name)));
+        }
+    }
+    let root = tree.root();
+    write_annotations(&mut sgf, tree, root);
+    write_lines(&mut sgf, tree, root);
+    sgf.push(')');
+    Ok(sgf)
+}
+
+/// Writes the moves after `node`: a sequence while there is one answer,
+/// then one parenthesized variation per answer.
+fn write_lines(sgf: &mut String, tree: &GameTree, mut node: NodeId) {
+    let size = tree.board_size();
+    loop {
+        let children = tree.node(node).map(TreeNode::children).unwrap_or_default();
+        match children {
+            [] => return,
+            [child] => {
+                write_move(sgf, tree, *child, size);
+                node = *child;
+            }
+            _ => {
+                for &child in children {
+                    sgf.push('(');
+                    write_move(sgf, tree, child, size);
+                    write_lines(sgf, tree, child);
+                    sgf.push(')');
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Writes the node of a move with its comment and evaluation.
+fn write_move(sgf: &mut String, tree: &GameTree, node: NodeId, size: u32) {
+    let Some(movement) = tree.node(node).and_then(TreeNode::movement) else {
+        return;
+    };
+    let (player, value) = match movement {
+        Movement::Placement { player, coords } => (player, point(*coords, size)),
+        Movement::Action {
+            player,
+            action: GameAction::Swap,
+        } => (player, "swap".to_string()),
+        Movement::Action {
+            player,
+            action: GameAction::Resign,
+        } => (player, "resign".to_string()),
+    };
+    let color = if player.id() == 0 { 'B' } else { 'W' };
+    sgf.push_str(&format!(";{}[{}]", color, value));
+    write_annotations(sgf, tree, node);
+}
+
+fn write_annotations(sgf: &mut String, tree: &GameTree, node: NodeId) {
+    let Some(node) = tree.node(node) else {
+        return;
+    };
+    if let Some(comment) = &node.comment {
+        sgf.push_str(&format!("C[{}]", escape(comment)));
+    }
+    if !node.marks.is_empty() {
+        sgf.push_str("MA");
+        for mark in &node.marks {
+            sgf.push_str(&format!("[{}]", point(*mark, tree.board_size())));
+        }
+    }
+    if let Some(eval) = node.eval {
+        sgf.push_str(&format!("V[{}]", eval));
+    }
+}
+
+/// Reads a game written in Smart Game Format, replaying the main line.
+///
+/// Variations other than the first one of each branch are ignored. The
+/// comments (`C`), marks (`MA`) and evaluations (`V`) of the main line
+/// become the annotations of the game.
+pub fn from_sgf(input: &str) -> Result<GameY, GameYError> {
+    let sgf = parse_sgf(input).map_err(invalid)?;
+    let nodes = sgf.main_line();
+    let root = nodes
+        .first()
+        .ok_or_else(|| invalid("the game has no nodes".to_string()))?;
+    let mut tree = empty_tree(root)?;
+    play_nodes(&mut tree, nodes.iter().copied())?;
+    Ok(tree.to_game())
+}
+
+/// Reads a game tree written in Smart Game Format, keeping every variation,
+/// comment (`C`), mark (`MA`) and evaluation (`V`).
+///
+/// The cursor of the tree is left at the root.
+pub fn tree_from_sgf(input: &str) -> Result<GameTree, GameYError> {
+    let sgf = parse_sgf(input).map_err(invalid)?;
+    let root = sgf
+        .nodes
+        .first()
+        .ok_or_else(|| invalid("the game has no nodes".to_string()))?;
+    let mut tree = empty_tree(root)?;
+    play_tree(&mut tree, &sgf)?;
+    tree.to_root();
+    Ok(tree)
+}
+
+/// Creates the empty tree described by the root node: its size and player names.
+fn empty_tree(root: &SgfNode) -> Result<GameTree, GameYError> {
+    if let Some(game) = root.get("GM")
+        && game != "Y"
+    {
+        return Err(invalid(format!("GM[{}] is not a game of Y", game)));
+    }
+    let size: u32 = root
+        .get("SZ")
+        .ok_or_else(|| invalid("missing SZ property".to_string()))?
+        .trim()
+        .parse()
+        .map_err(|_| invalid("SZ is not a number".to_string()))?;
+    if size == 0 || size > SGF_MAX_SIZE {
+        return Err(invalid(format!("unsupported board size {}", size)));
+    }
+
+    let names: Vec<PlayerInfo> = [("PB", 0), ("PW", 1)]
+        .iter()
+        .filter_map(|(property, id)| {
+            root.get(property)
+                .map(|name| PlayerInfo::new(PlayerId::new(*id), name, PlayerKind::Human))
+        })
+        .collect();
+    Ok(GameTree::from_game(&GameY::new(size).with_player_info(names)))
+}
+
+/// Plays the sequence of `sgf` from the cursor, then each of its variations from where the sequence ends.
+fn play_tree(tree: &mut GameTree, sgf: &SgfTree) -> Result<(), GameYError> {
+    play_nodes(tree, sgf.nodes.iter())?;
+    let branch = tree.cursor();
+    for variation in &sgf.variations {
+        play_tree(tree, variation)?;
+        tree.goto(branch);
+    }
+    Ok(())
+}
+
+/// Plays the moves of `nodes` from the cursor, annotating the node reached after each one.
+fn play_nodes<'a>(tree: &mut GameTree, nodes: impl IntoIterator<Item = &'a SgfNode>) -> Result<(), GameYError> {
+    let size = tree.board_size();
+    for node in nodes {
+        for (color, id) in [("B", 0), ("W", 1)] {
+            let Some(value) = node.get(color) else {
+                continue;
+            };
+            let ply = tree.game().move_count() + 1;
+            let player = PlayerId::new(id);
+            let movement = match value {
+                "swap" => Movement::Action {
+                    player,
+                    action: GameAction::Swap,
+                },
+                "resign" => Movement::Action {
+                    player,
+                    action: GameAction::Resign,
+                },
+                _ => Movement::Placement {
+                    player,
+                    coords: parse_point(value, size)
+                        .ok_or_else(|| invalid(format!("move {} has bad point '{}'", ply, value)))?,
+                },
+            };
+            tree.add_move(movement)
+                .map_err(|e| invalid(format!("move {} cannot be played: {}", ply, e)))?;
+        }
+        let current = tree.current_node_mut();
+        if let Some(comment) = node.get("C") {
+            current.comment = Some(comment.to_string());
+        }
+        if let Some(eval) = node.get("V") {
+            let eval = eval
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("V[{}] is not a number", eval)))?;
+            current.eval = Some(eval);
+        }
+        for value in node.values("MA") {
+            let mark = parse_point(value, size).ok_or_else(|| invalid(format!("MA has bad point '{}'", value)))?;
+            current.marks.push(mark);
+        }
+    }
+    Ok(())
+}
+
+/// A node of an SGF game tree: its properties in file order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SgfNode {
+    pub(crate) properties: Vec<(String, Vec<String>)>,
+}
+
+impl SgfNode {
+    /// First value of `property`, if present.
+    pub(crate) fn get(&self, property: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(name, _)| name == property)
+            .and_then(|(_, values)| values.first())
+            .map(String::as_str)
+    }
+
+    /// Every value of `property`, empty if it is not present.
+    pub(crate) fn values(&self, property: &str) -> impl Iterator<Item = &str> {
+        self.properties
+            .iter()
+            .filter(move |(name, _)| name == property)
+            .flat_map(|(_, values)| values.iter().map(String::as_str))
+    }
+}
+
+/// An SGF game tree: a sequence of nodes followed by its variations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SgfTree {
+    pub(crate) nodes: Vec<SgfNode>,
+    pub(crate) variations: Vec<SgfTree>,
+}
+
+impl SgfTree {
+    /// Nodes of the main line: this sequence followed by the first variation, recursively.
+    pub(crate) fn main_line(&self) -> Vec<&SgfNode> {
+        let mut nodes: Vec<&SgfNode> = self.nodes.iter().collect();
+        if let Some(first) = self.variations.first() {
+            nodes.extend(first.main_line());
+        }
+        nodes
+    }
+}
+
+/// Parses the first game tree of an SGF collection.
+pub(crate) fn parse_sgf(input: &str) -> Result<SgfTree, String> {
+    let mut chars = input.chars().peekable();
+    skip_whitespace(&mut chars);
+    parse_tree(&mut chars)
+}
+
+fn parse_tree(chars: &mut Peekable<Chars>) -> Result<SgfTree, String> {
+    if chars.next() != Some('(') {
+        return Err("expected '(' at the start of a game tree".to_string());
+    }
+    let mut tree = SgfTree::default();
+    loop {
+        skip_whitespace(chars);
+        match chars.peek() {
+            Some(';') => {
+                chars.next();
+                tree.nodes.push(parse_node(chars)?);
+            }
+            Some('(') => tree.variations.push(parse_tree(chars)?),
+            Some(')') => {
+                chars.next();
+                return Ok(tree);
+            }
+            Some(c) => return Err(format!("unexpected character '{}'", c)),
+            None => return Err("unexpected end of input, missing ')'".to_string()),
+        }
+    }
+}
+
+fn parse_node(chars: &mut Peekable<Chars>) -> Result<SgfNode, String> {
+    let mut node = SgfNode::default();
+    loop {
+        skip_whitespace(chars);
+        let mut name = String::new();
+        while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+            chars.next();
+        }
+        if name.is_empty() {
+            return Ok(node);
+        }
+        let mut values = Vec::new();
+        skip_whitespace(chars);
+        while chars.peek() == Some(&'[') {
+            chars.next();
+            values.push(parse_value(chars)?);
+            skip_whitespace(chars);
+        }
+        if values.is_empty() {
+            return Err(format!("property {} has no value", name));
+        }
+        node.properties.push((name, values));
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            ']' => return Ok(value),
+            '\\' => value.extend(chars.next()),
+            _ => value.push(c),
+        }
+    }
+    Err("unexpected end of input inside a property value".to_string())
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+/// Escapes the characters with a special meaning inside an SGF value.
+pub(crate) fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+/// Writes `coords` as an SGF point: column inside the row, then row.
+fn point(coords: Coordinates, size: u32) -> String {
+    let row = size - 1 - coords.x();
+    [letter(coords.y()), letter(row)].iter().collect()
+}
+
+/// Reads an SGF point written by [`point`].
+fn parse_point(value: &str, size: u32) -> Option<Coordinates> {
+    let mut letters = value.chars();
+    let col = letter_value(letters.next()?)?;
+    let row = letter_value(letters.next()?)?;
+    if letters.next().is_some() || row >= size || col > row {
+        return None;
+    }
+    let x = size - 1 - row;
+    Some(Coordinates::new(x, col, size - 1 - x - col))
+}
+
+fn letter(value: u32) -> char {
+    if value < 26 {
+        (b'a' + value as u8) as char
+    } else {
+        (b'A' + (value - 26) as u8) as char
+    }
+}
+
+fn letter_value(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 26),
+        _ => None,
+    }
+}
+
+fn invalid(message: String) -> GameYError {
+    GameYError::InvalidFormat {
+        format: GameFormat::Sgf,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MoveAnnotation;
+
+    fn place(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_write_moves_and_names() {
+        let mut game = GameY::new(3).with_player_info(vec![PlayerInfo::new(
+            PlayerId::new(0),
+            "Ana [A]",
+            PlayerKind::Human,
+        )]);
+        place(&mut game, 0, Coordinates::new(2, 0, 0));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        place(&mut game, 0, Coordinates::new(0, 1, 1));
+
+        assert_eq!(
+            to_sgf(&game).unwrap(),
+            "(;FF[4]GM[Y]SZ[3]PB[Ana [A\\]];B[aa];W[swap];B[bc])"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut game = GameY::new(4);
+        place(&mut game, 0, Coordinates::new(1, 1, 1));
+        place(&mut game, 1, Coordinates::new(0, 3, 0));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        let loaded = from_sgf(&to_sgf(&game).unwrap()).unwrap();
+        assert_eq!(loaded.history(), game.history());
+        assert!(loaded.check_game_over());
+    }
+
+    #[test]
+    fn test_read_follows_the_main_line() {
+        let game = from_sgf("(;GM[Y]SZ[3]PW[Bot];B[aa](;W[ab];B[bc])(;W[cc]))").unwrap();
+        assert_eq!(game.history().len(), 3);
+        assert_eq!(game.players()[1].name, "Bot");
+    }
+
+    #[test]
+    fn test_read_errors_name_the_problem() {
+        let missing_size = from_sgf("(;GM[Y];B[aa])").unwrap_err();
+        assert!(missing_size.to_string().contains("SGF"));
+        assert!(missing_size.to_string().contains("missing SZ"));
+
+        let bad_point = from_sgf("(;SZ[3];B[zz])").unwrap_err();
+        assert!(bad_point.to_string().contains("move 1 has bad point 'zz'"));
+
+        assert!(from_sgf("(;SZ[3];B[aa]").is_err());
+        assert!(from_sgf("(;GM[1]SZ[19])").is_err());
+    }
+
+    #[test]
+    fn test_tree_variations_and_comments() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, Coordinates::new(2, 0, 0));
+        place(&mut game, 1, Coordinates::new(0, 2, 0));
+        let mut tree = GameTree::from_game(&game);
+        tree.current_node_mut().comment = Some("natural [reply]".to_string());
+        tree.back();
+        tree.current_node_mut().eval = Some(0.25);
+        tree.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(0, 1, 1),
+        })
+        .unwrap();
+        tree.current_node_mut().comment = Some("sharper".to_string());
+
+        let sgf = tree_to_sgf(&tree).unwrap();
+        assert_eq!(
+            sgf,
+            "(;FF[4]GM[Y]SZ[3];B[aa]V[0.25](;W[cc]C[natural [reply\\]])(;W[bc]C[sharper]))"
+        );
+        let loaded = tree_from_sgf(&sgf).unwrap();
+        assert_eq!(loaded.nodes(), tree.nodes());
+        assert_eq!(loaded.cursor(), loaded.root());
+        assert_eq!(from_sgf(&sgf).unwrap().history(), game.history());
+    }
+
+    #[test]
+    fn test_annotations_survive_a_round_trip() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, Coordinates::new(2, 0, 0));
+        place(&mut game, 1, Coordinates::new(0, 2, 0));
+        let marked = MoveAnnotation::comment("threatens both sides")
+            .with_mark(Coordinates::new(1, 1, 0))
+            .with_mark(Coordinates::new(0, 1, 1));
+        game.annotate(1, marked).unwrap();
+        game.annotate(2, MoveAnnotation::comment("blocks").with_eval(-0.5)).unwrap();
+
+        let sgf = to_sgf(&game).unwrap();
+        assert!(sgf.contains(";B[aa]C[threatens both sides]MA[bb][bc]"), "{}", sgf);
+        let loaded = from_sgf(&sgf).unwrap();
+        assert_eq!(loaded.annotations(), game.annotations());
+    }
+
+    #[test]
+    fn test_tree_rejects_a_bad_evaluation() {
+        let err = tree_from_sgf("(;SZ[3];B[aa]V[high])").unwrap_err();
+        assert!(err.to_string().contains("V[high] is not a number"));
+    }
+
+    #[test]
+    fn test_more_than_two_players_cannot_be_written() {
+        assert!(to_sgf(&GameY::with_players(4, 3)).is_err());
+    }
+}
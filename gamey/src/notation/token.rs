@@ -0,0 +1,260 @@
+use crate::{GameYError, PLAYER_SYMBOLS, YEN};
+
+/// Alphabet of base64url (RFC 4648, section 5), written without padding.
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Longest run of equal cells stored in a single byte.
+const MAX_RUN: usize = 32;
+
+impl YEN {
+    /// Encodes the position as a short, URL-safe token for share links.
+    ///
+    /// The token is the base64url encoding (without padding) of:
+    ///
+    /// - the board size, the player to move and the number of players, one byte each,
+    /// - the layout, run-length encoded: every byte holds a cell value in its
+    ///   top 3 bits (0 for empty, `p + 1` for player `p`) and the run length
+    ///   minus one in its low 5 bits,
+    /// - a CRC-8 of all the previous bytes.
+    ///
+    /// Only the position is kept: custom player symbols come back as the
+    /// default ones and metadata is dropped. Characters of the layout that
+    /// are not player symbols count as empty cells. Board sizes above 255 do
+    /// not fit in the size byte and are not supported.
+    pub fn to_url_token(&self) -> String {
+        let cells: Vec<u8> = self
+            .layout()
+            .chars()
+            .filter(|c| *c != '/')
+            .map(|c| {
+                self.players()
+                    .iter()
+                    .position(|symbol| *symbol == c)
+                    .map_or(0, |p| p as u8 + 1)
+            })
+            .collect();
+
+        let mut bytes = vec![
+            self.size() as u8,
+            self.turn() as u8,
+            self.players().len() as u8,
+        ];
+        let mut i = 0;
+        while i < cells.len() {
+            let value = cells[i];
+            let run = cells[i..]
+                .iter()
+                .take(MAX_RUN)
+                .take_while(|c| **c == value)
+                .count();
+            bytes.push(value << 5 | (run - 1) as u8);
+            i += run;
+        }
+        bytes.push(crc8(&bytes));
+        encode_base64url(&bytes)
+    }
+
+    /// Decodes a token created by [`YEN::to_url_token`].
+    ///
+    /// Fails with [`GameYError::InvalidToken`] if the token is not base64url,
+    /// its checksum does not match, or it does not describe a valid board.
+    pub fn from_url_token(token: &str) -> Result<YEN, GameYError> {
+        let bytes = decode_base64url(token)?;
+        let Some((&checksum, data)) = bytes.split_last() else {
+            return Err(invalid("the token is empty"));
+        };
+        if data.len() < 3 {
+            return Err(invalid("the token is too short"));
+        }
+        if crc8(data) != checksum {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        let (size, turn, num_players) = (data[0] as u32, data[1] as u32, data[2] as usize);
+        if size == 0 {
+            return Err(invalid("impossible board size 0"));
+        }
+        if !(2..=PLAYER_SYMBOLS.len()).contains(&num_players) {
+            return Err(invalid(&format!("impossible number of players {}", num_players)));
+        }
+        if turn as usize >= num_players {
+            return Err(invalid(&format!("turn {} for {} players", turn, num_players)));
+        }
+        let players = PLAYER_SYMBOLS[..num_players].to_vec();
+
+        let total_cells = (size * (size + 1) / 2) as usize;
+        let mut cells = Vec::with_capacity(total_cells);
+        for byte in &data[3..] {
+            let value = (byte >> 5) as usize;
+            let run = (byte & 0x1F) as usize + 1;
+            if value > num_players {
+                return Err(invalid(&format!("cell value {} for {} players", value, num_players)));
+            }
+            let symbol = if value == 0 { '.' } else { players[value - 1] };
+            cells.extend(std::iter::repeat_n(symbol, run));
+            if cells.len() > total_cells {
+                return Err(invalid(&format!("more than {} cells for size {}", total_cells, size)));
+            }
+        }
+        if cells.len() != total_cells {
+            return Err(invalid(&format!(
+                "{} cells for size {}, expected {}",
+                cells.len(),
+                size,
+                total_cells
+            )));
+        }
+
+        let mut layout = String::with_capacity(total_cells + size as usize);
+        let mut start = 0;
+        for row in 0..size as usize {
+            if row > 0 {
+                layout.push('/');
+            }
+            layout.extend(&cells[start..=start + row]);
+            start += row + 1;
+        }
+        Ok(YEN::new(size, turn, players, layout))
+    }
+}
+
+fn invalid(message: &str) -> GameYError {
+    GameYError::InvalidToken {
+        message: message.to_string(),
+    }
+}
+
+/// CRC-8 with the polynomial 0x07 (CRC-8/SMBUS).
+fn crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for byte in bytes {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn encode_base64url(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..=chunk.len() {
+            out.push(BASE64URL[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+        }
+    }
+    out
+}
+
+fn decode_base64url(token: &str) -> Result<Vec<u8>, GameYError> {
+    let mut out = Vec::with_capacity(token.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in token.trim_end_matches('=').bytes() {
+        let value = BASE64URL
+            .iter()
+            .position(|b| *b == c)
+            .ok_or_else(|| invalid(&format!("'{}' is not a base64url character", c as char)))?;
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameY, Movement, PlayerId};
+    use rand::seq::SliceRandom;
+
+    fn random_position(size: u32, stones: usize) -> YEN {
+        let mut game = GameY::new(size);
+        let mut cells: Vec<u32> = (0..game.total_cells()).collect();
+        cells.shuffle(&mut rand::rng());
+        for (i, idx) in cells.into_iter().take(stones).enumerate() {
+            // Keep placing after a win so that full boards can be generated too
+            let _ = game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::from_index(idx, size),
+            });
+        }
+        (&game).into()
+    }
+
+    fn assert_round_trip(yen: &YEN) {
+        let token = yen.to_url_token();
+        let decoded = YEN::from_url_token(&token).unwrap();
+        assert_eq!(decoded.size(), yen.size());
+        assert_eq!(decoded.turn(), yen.turn());
+        assert_eq!(decoded.layout(), yen.layout(), "token {}", token);
+    }
+
+    #[test]
+    fn test_round_trip_random_positions() {
+        for _ in 0..200 {
+            let size = rand::random_range(1..=12);
+            let total = (size * (size + 1) / 2) as usize;
+            let stones = rand::random_range(0..=total);
+            assert_round_trip(&random_position(size, stones));
+        }
+    }
+
+    #[test]
+    fn test_round_trip_empty_and_full_boards() {
+        for size in [1, 2, 7, 20] {
+            let total = (size * (size + 1) / 2) as usize;
+            assert_round_trip(&random_position(size, 0));
+            assert_round_trip(&random_position(size, total));
+        }
+    }
+
+    #[test]
+    fn test_size_9_midgame_token_is_short() {
+        let token = random_position(9, 24).to_url_token();
+        assert!(token.len() < 100, "token {} has {} chars", token, token.len());
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_bad_checksum_is_rejected() {
+        let mut token = random_position(5, 6).to_url_token().into_bytes();
+        token[2] = if token[2] == b'A' { b'B' } else { b'A' };
+        let err = YEN::from_url_token(&String::from_utf8(token).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("checksum"));
+    }
+
+    #[test]
+    fn test_impossible_tokens_are_rejected() {
+        let with_crc = |mut bytes: Vec<u8>| {
+            bytes.push(crc8(&bytes));
+            encode_base64url(&bytes)
+        };
+        assert!(YEN::from_url_token(&with_crc(vec![0, 0, 2])).is_err());
+        // Size 2 has 3 cells, but the run covers 4
+        assert!(YEN::from_url_token(&with_crc(vec![2, 0, 2, 0b0000_0011])).is_err());
+        // Only 2 of the 3 cells
+        assert!(YEN::from_url_token(&with_crc(vec![2, 0, 2, 0b0000_0001])).is_err());
+        assert!(YEN::from_url_token(&with_crc(vec![2, 5, 2, 0b0000_0010])).is_err());
+        assert!(YEN::from_url_token("not a token!").is_err());
+        assert!(YEN::from_url_token("").is_err());
+    }
+
+    #[test]
+    fn test_base64url_matches_rfc_vectors() {
+        assert_eq!(encode_base64url(b"f"), "Zg");
+        assert_eq!(encode_base64url(b"fo"), "Zm8");
+        assert_eq!(encode_base64url(b"foo"), "Zm9v");
+        assert_eq!(encode_base64url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_base64url("Zm9vYg").unwrap(), b"foob");
+    }
+}
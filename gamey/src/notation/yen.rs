@@ -1,4 +1,7 @@
+use crate::{GameFormat, GameYError, MAX_BOARD_SIZE, MIN_BOARD_SIZE, PLAYER_SYMBOLS, PlayerInfo};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::Path;
 
 /// Y Exchange Notation (YEN) - a compact format for representing Y game states.
 ///
@@ -7,10 +10,16 @@ use serde::{Deserialize, Serialize};
 ///
 /// # Format
 /// - `size`: The board size (length of one side of the triangle)
-/// - `turn`: Which player's turn it is (0 or 1)
-/// - `players`: Character symbols for each player (e.g., ['B', 'R'] for Blue/Red)
+/// - `turn`: Which player's turn it is (0-indexed)
+/// - `players`: Character symbols for each player (e.g., ['B', 'R'] for Blue/Red);
+///   its length is the number of players
 /// - `layout`: A compact string where rows are separated by '/', and cells are
 ///   represented by player symbols or '.' for empty cells
+/// - `result`: How the game ended, as the winner's symbol and the reason
+///   (`connect`, `resign`, `time` or `forfeit`), e.g. `"B+resign"`. Omitted while the
+///   game is ongoing.
+/// - `metadata`: Optional information that does not affect the position, such
+///   as the player names. Omitted when empty.
 ///
 /// # Example
 /// ```json
@@ -21,19 +30,42 @@ use serde::{Deserialize, Serialize};
 ///   "layout": "B/BR/.R."
 /// }
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct YEN {
     /// The board size (length of one side of the triangle).
+    #[cfg_attr(feature = "schema", schemars(range(min = 1)))]
     size: u32,
     /// The index of the player whose turn it is (0-indexed).
+    #[cfg_attr(feature = "schema", schemars(range(max = PLAYER_SYMBOLS.len() - 1)))]
     turn: u32,
-    /// Character symbols representing each player.
+    /// Character symbols representing each player: the symbol at index `i`
+    /// is player `i` in the layout and the result.
+    #[cfg_attr(feature = "schema", schemars(length(min = 2, max = PLAYER_SYMBOLS.len())))]
     players: Vec<char>,
     /// A compact string representation of the board.
     ///
     /// Rows are separated by '/', with cells represented by player symbols
     /// or '.' for empty cells. Example: "B/..R/.B.R"
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = r"^[^/]+(/[^/]+)*$")))]
     layout: String,
+    /// How the game ended: the winner's symbol, `+` and the reason, one of
+    /// `connect`, `resign`, `time` or `forfeit`. Example: "B+resign"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = r"^.\+(connect|resign|time|forfeit)$")))]
+    result: Option<String>,
+    /// Optional metadata about the game.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<YenMetadata>,
+}
+
+/// Information attached to a YEN that does not describe the position itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct YenMetadata {
+    /// Display information of the players, in player order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub players: Vec<PlayerInfo>,
 }
 
 impl YEN {
@@ -50,9 +82,23 @@ impl YEN {
             turn,
             players,
             layout,
+            result: None,
+            metadata: None,
         }
     }
 
+    /// Sets the result of a finished game, such as `"B+resign"`.
+    pub fn with_result(mut self, result: impl Into<String>) -> Self {
+        self.result = Some(result.into());
+        self
+    }
+
+    /// Attaches metadata to this YEN.
+    pub fn with_metadata(mut self, metadata: YenMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
     /// Returns the board layout string.
     pub fn layout(&self) -> &str {
         &self.layout
@@ -72,11 +118,372 @@ impl YEN {
     pub fn players(&self) -> &[char] {
         &self.players
     }
+
+    /// Returns how the game ended, if it is over.
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref()
+    }
+
+    /// Returns the metadata attached to this YEN, if any.
+    pub fn metadata(&self) -> Option<&YenMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Player symbols used to read the layout: `players`, or the standard
+    /// `['B', 'R']` when fewer than two are given.
+    pub(crate) fn effective_players(&self) -> Vec<char> {
+        if self.players.len() >= 2 {
+            self.players.clone()
+        } else {
+            PLAYER_SYMBOLS[..2].to_vec()
+        }
+    }
+}
+
+/// How serious a [`YenIssue`] is.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The YEN loads, but probably does not say what its author meant.
+    Warning,
+    /// The YEN cannot be loaded.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found by [`validate`]. Rows and columns are 0-indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YenIssue {
+    /// The board size is 0.
+    ZeroSize,
+    /// The board size is above [`MAX_BOARD_SIZE`].
+    TooLarge { size: u32, max: u32 },
+    /// Fewer than two player symbols; the layout is read with `B` and `R`.
+    TooFewPlayers { found: usize },
+    /// More player symbols than the game supports.
+    TooManyPlayers { found: usize, max: usize },
+    /// A player symbol appears more than once.
+    DuplicatePlayer { symbol: char },
+    /// A player symbol is `.` or `/`, which have a meaning in the layout.
+    ReservedPlayerSymbol { symbol: char },
+    /// The turn names a player that does not exist.
+    TurnOutOfRange { turn: u32, players: usize },
+    /// The stones on the board say another player should move.
+    TurnMismatch { turn: u32, expected: u32 },
+    /// A player has more stones than the players before them in turn order,
+    /// or more than one stone more than a later player.
+    UnbalancedPieces { counts: Vec<usize> },
+    /// The layout starts with a separator or has two separators in a row.
+    EmptyLayoutRow { row: u32 },
+    /// The layout ends with a separator.
+    TrailingSeparator,
+    /// The layout does not have one row per unit of size.
+    RowCount { expected: u32, found: u32 },
+    /// A row, whose text is `text`, does not have `row + 1` cells.
+    RowLength {
+        row: u32,
+        expected: u32,
+        found: u32,
+        text: String,
+    },
+    /// A cell of the row `text` is neither `.` nor a player symbol.
+    InvalidChar {
+        char: char,
+        row: usize,
+        col: usize,
+        text: String,
+    },
+}
+
+impl YenIssue {
+    /// Returns how serious this issue is.
+    pub fn severity(&self) -> Severity {
+        match self {
+            YenIssue::TooFewPlayers { .. }
+            | YenIssue::TurnOutOfRange { .. }
+            | YenIssue::TurnMismatch { .. }
+            | YenIssue::UnbalancedPieces { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Returns whether this issue prevents loading the YEN.
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl Display for YenIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.severity())?;
+        match self {
+            YenIssue::ZeroSize => write!(f, "the board size must be at least 1"),
+            YenIssue::TooLarge { size, max } => write!(f, "board size {} is larger than the maximum {}", size, max),
+            YenIssue::TooFewPlayers { found } => write!(
+                f,
+                "{} player symbols, the layout is read with the default symbols B and R",
+                found
+            ),
+            YenIssue::TooManyPlayers { found, max } => {
+                write!(f, "{} player symbols, at most {} are supported", found, max)
+            }
+            YenIssue::DuplicatePlayer { symbol } => {
+                write!(f, "player symbol '{}' is used more than once", symbol)
+            }
+            YenIssue::ReservedPlayerSymbol { symbol } => {
+                write!(f, "'{}' cannot be a player symbol", symbol)
+            }
+            YenIssue::TurnOutOfRange { turn, players } => {
+                write!(f, "turn {} for {} players", turn, players)
+            }
+            YenIssue::TurnMismatch { turn, expected } => write!(
+                f,
+                "turn is {}, but the stones on the board say player {} moves",
+                turn, expected
+            ),
+            YenIssue::UnbalancedPieces { counts } => {
+                write!(f, "unbalanced stone counts {:?} in turn order", counts)
+            }
+            YenIssue::EmptyLayoutRow { row } => write!(f, "row {} of the layout is empty", row),
+            YenIssue::TrailingSeparator => write!(f, "the layout ends with a '/'"),
+            YenIssue::RowCount { expected, found } => {
+                write!(f, "{} rows for size {}, expected {}", found, expected, expected)
+            }
+            YenIssue::RowLength {
+                row,
+                expected,
+                found,
+                text,
+            } => {
+                write!(f, "row {} \"{}\" has {} cells, expected {}", row, text, found, expected)
+            }
+            YenIssue::InvalidChar { char, row, col, text } => {
+                write!(f, "invalid character '{}' at row {}, column {} in \"{}\"", char, row, col, text)
+            }
+        }
+    }
+}
+
+impl From<YenIssue> for GameYError {
+    /// Converts an issue into the error [`GameY::try_from`](crate::GameY) reports for it.
+    fn from(issue: YenIssue) -> Self {
+        match issue {
+            YenIssue::ZeroSize => GameYError::InvalidBoardSize {
+                size: 0,
+                min: MIN_BOARD_SIZE,
+                max: MAX_BOARD_SIZE,
+            },
+            YenIssue::TooLarge { size, max } => GameYError::InvalidBoardSize {
+                size,
+                min: MIN_BOARD_SIZE,
+                max,
+            },
+            YenIssue::RowCount { expected, found } => GameYError::InvalidYENLayout { expected, found },
+            YenIssue::EmptyLayoutRow { row } => GameYError::EmptyLayoutRow { row },
+            YenIssue::TrailingSeparator => GameYError::TrailingSeparator,
+            YenIssue::RowLength {
+                row,
+                expected,
+                found,
+                text,
+            } => GameYError::InvalidYENLayoutLine {
+                expected,
+                found,
+                line: row,
+                text,
+            },
+            YenIssue::InvalidChar { char, row, col, text } => GameYError::InvalidCharInLayout { char, row, col, text },
+            YenIssue::TooManyPlayers { found, max } => GameYError::InvalidNumPlayers {
+                num_players: found as u32,
+                expected: max as u32,
+            },
+            other => GameYError::InvalidFormat {
+                format: GameFormat::Yen,
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+/// Checks a YEN in one pass and returns every problem found, in the order:
+/// players, layout (row by row) and turn.
+///
+/// [`GameY::try_from`](crate::GameY) fails with the first
+/// [`Severity::Error`] issue; warnings do not prevent loading.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{YEN, yen};
+///
+/// let issues = yen::validate(&YEN::new(2, 0, vec!['B', 'R'], "X/..".to_string()));
+/// assert_eq!(issues.len(), 1);
+/// assert_eq!(issues[0].to_string(), "error: invalid character 'X' at row 0, column 0 in \"X\"");
+/// ```
+pub fn validate(yen: &YEN) -> Vec<YenIssue> {
+    let mut issues = Vec::new();
+
+    if yen.size == 0 {
+        issues.push(YenIssue::ZeroSize);
+    } else if yen.size > MAX_BOARD_SIZE {
+        issues.push(YenIssue::TooLarge {
+            size: yen.size,
+            max: MAX_BOARD_SIZE,
+        });
+    }
+    if yen.players.len() < 2 {
+        issues.push(YenIssue::TooFewPlayers {
+            found: yen.players.len(),
+        });
+    } else if yen.players.len() > PLAYER_SYMBOLS.len() {
+        issues.push(YenIssue::TooManyPlayers {
+            found: yen.players.len(),
+            max: PLAYER_SYMBOLS.len(),
+        });
+    }
+    let symbols = yen.effective_players();
+    issues.extend(symbol_issues(&symbols));
+
+    // A board of size 0 has no rows to compare the layout with
+    let mut rows: Vec<&str> = match yen.size {
+        0 => Vec::new(),
+        _ => layout_rows(&yen.layout),
+    };
+    // A trailing '/' is reported once, not as an extra empty row
+    if rows.len() > 1 && rows.last() == Some(&"") {
+        issues.push(YenIssue::TrailingSeparator);
+        rows.pop();
+    }
+    for (row, row_str) in rows.iter().enumerate() {
+        if row_str.is_empty() {
+            issues.push(YenIssue::EmptyLayoutRow { row: row as u32 });
+        }
+    }
+    if yen.size > 0 && rows.len() as u32 != yen.size {
+        issues.push(YenIssue::RowCount {
+            expected: yen.size,
+            found: rows.len() as u32,
+        });
+    }
+    let mut counts = vec![0usize; symbols.len()];
+    for (row, row_str) in rows.iter().enumerate() {
+        let cells = row_str.chars().count() as u32;
+        if cells != row as u32 + 1 && cells > 0 {
+            issues.push(YenIssue::RowLength {
+                row: row as u32,
+                expected: row as u32 + 1,
+                found: cells,
+                text: row_str.to_string(),
+            });
+        }
+        for (col, cell) in row_str.chars().enumerate() {
+            if cell == '.' {
+                continue;
+            }
+            match symbols.iter().position(|symbol| *symbol == cell) {
+                Some(player) => counts[player] += 1,
+                None => issues.push(YenIssue::InvalidChar {
+                    char: cell,
+                    row,
+                    col,
+                    text: row_str.to_string(),
+                }),
+            }
+        }
+    }
+
+    if yen.turn as usize >= symbols.len() {
+        issues.push(YenIssue::TurnOutOfRange {
+            turn: yen.turn,
+            players: symbols.len(),
+        });
+    } else if yen.result.is_none() {
+        match turns_for_counts(&counts) {
+            None => issues.push(YenIssue::UnbalancedPieces { counts }),
+            Some(turns) if !turns.contains(&yen.turn) => issues.push(YenIssue::TurnMismatch {
+                turn: yen.turn,
+                expected: turns[0],
+            }),
+            Some(_) => {}
+        }
+    }
+    issues
+}
+
+/// Splits a layout into its rows, without the whitespace around each row.
+///
+/// Whitespace between rows, such as a layout written one row per line, is
+/// not significant. An empty layout has a single empty row.
+pub(crate) fn layout_rows(layout: &str) -> Vec<&str> {
+    layout.trim().split('/').map(str::trim).collect()
+}
+
+/// Reports the player symbols that are reserved (`.` and `/`) or repeated,
+/// each repeated symbol once.
+pub(crate) fn symbol_issues(symbols: &[char]) -> Vec<YenIssue> {
+    let mut issues = Vec::new();
+    for (i, symbol) in symbols.iter().enumerate() {
+        if *symbol == '.' || *symbol == '/' {
+            issues.push(YenIssue::ReservedPlayerSymbol { symbol: *symbol });
+        } else if symbols[..i].iter().filter(|s| *s == symbol).count() == 1 {
+            issues.push(YenIssue::DuplicatePlayer { symbol: *symbol });
+        }
+    }
+    issues
+}
+
+/// Reads the YEN file at `path` and [`validate`]s it.
+///
+/// # Errors
+/// Returns `GameYError::IoError` if the file cannot be read and
+/// `GameYError::CorruptFile` if it is not YEN JSON.
+pub fn validate_file<P: AsRef<Path>>(path: P) -> Result<Vec<YenIssue>, GameYError> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| GameYError::IoError {
+        message: format!("Failed to read file: {}", path.display()),
+        error: e.to_string(),
+    })?;
+    let yen: YEN = serde_json::from_slice(&bytes).map_err(|error| GameYError::CorruptFile {
+        path: path.display().to_string(),
+        error,
+    })?;
+    Ok(validate(&yen))
+}
+
+/// Players who can be on turn when each player has `counts[p]` stones, or
+/// `None` if no game reaches those counts.
+///
+/// Players move in order, so the counts never increase along the turn order
+/// and differ by at most one. In two-player games a swap gives the second
+/// player the first stone, leaving the first player up to two stones ahead.
+fn turns_for_counts(counts: &[usize]) -> Option<Vec<u32>> {
+    if let [first, second] = counts {
+        return match first.checked_sub(*second)? {
+            0 => Some(vec![0]),
+            1 => Some(vec![1, 0]),
+            2 => Some(vec![1]),
+            _ => None,
+        };
+    }
+    let most = counts[0];
+    if counts.windows(2).any(|w| w[0] < w[1]) || counts.iter().any(|c| most - c > 1) {
+        return None;
+    }
+    Some(vec![counts.iter().position(|c| *c < most).unwrap_or(0) as u32])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::GameY;
 
     #[test]
     fn test_new() {
@@ -134,6 +541,15 @@ mod tests {
         assert_eq!(yen.layout(), ".");
     }
 
+    #[test]
+    fn test_metadata_is_omitted_when_absent() {
+        let yen = YEN::new(1, 0, vec!['B', 'R'], ".".to_string());
+        let json = serde_json::to_string(&yen).unwrap();
+        assert!(!json.contains("metadata"));
+        let restored: YEN = serde_json::from_str(&json).unwrap();
+        assert!(restored.metadata().is_none());
+    }
+
     #[test]
     fn test_roundtrip_serialization() {
         let original = YEN::new(4, 1, vec!['B', 'R'], "B/.R/BBR/....".to_string());
@@ -144,4 +560,145 @@ mod tests {
         assert_eq!(original.layout(), restored.layout());
         assert_eq!(original.players(), restored.players());
     }
+
+    #[test]
+    fn test_validate_accepts_good_positions() {
+        assert!(validate(&YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string())).is_empty());
+        assert!(validate(&YEN::new(3, 0, vec!['B', 'R'], "B/R./...".to_string())).is_empty());
+        // After a swap the first player is one or two stones ahead
+        assert!(validate(&YEN::new(3, 0, vec!['B', 'R'], "B/../...".to_string())).is_empty());
+        assert!(validate(&YEN::new(3, 1, vec!['B', 'R'], "B/B./...".to_string())).is_empty());
+        assert!(validate(&YEN::new(3, 2, vec!['B', 'R', 'G'], "B/R./...".to_string())).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let yen = YEN::new(3, 0, vec!['B', 'B'], "X/.../..Z/.".to_string());
+        let issues = validate(&yen);
+        assert_eq!(
+            issues,
+            vec![
+                YenIssue::DuplicatePlayer { symbol: 'B' },
+                YenIssue::RowCount {
+                    expected: 3,
+                    found: 4
+                },
+                YenIssue::InvalidChar {
+                    char: 'X',
+                    row: 0,
+                    col: 0,
+                    text: "X".to_string()
+                },
+                YenIssue::RowLength {
+                    row: 1,
+                    expected: 2,
+                    found: 3,
+                    text: "...".to_string()
+                },
+                YenIssue::InvalidChar {
+                    char: 'Z',
+                    row: 2,
+                    col: 2,
+                    text: "..Z".to_string()
+                },
+                YenIssue::RowLength {
+                    row: 3,
+                    expected: 4,
+                    found: 1,
+                    text: ".".to_string()
+                },
+            ]
+        );
+        assert!(issues.iter().all(YenIssue::is_error));
+    }
+
+    #[test]
+    fn test_validate_warnings() {
+        let issues = validate(&YEN::new(2, 1, vec!['B', 'R'], "./..".to_string()));
+        assert_eq!(issues, vec![YenIssue::TurnMismatch { turn: 1, expected: 0 }]);
+        assert_eq!(issues[0].severity(), Severity::Warning);
+
+        let issues = validate(&YEN::new(3, 0, vec!['B', 'R'], "R/R./...".to_string()));
+        assert_eq!(issues, vec![YenIssue::UnbalancedPieces { counts: vec![0, 2] }]);
+
+        let issues = validate(&YEN::new(1, 4, vec!['B'], ".".to_string()));
+        assert_eq!(
+            issues,
+            vec![
+                YenIssue::TooFewPlayers { found: 1 },
+                YenIssue::TurnOutOfRange { turn: 4, players: 2 }
+            ]
+        );
+        assert!(issues.iter().all(|issue| !issue.is_error()));
+
+        // Finished games can have any turn
+        let finished = YEN::new(2, 1, vec!['B', 'R'], "B/B.".to_string()).with_result("B+connect");
+        assert!(validate(&finished).is_empty());
+    }
+
+    #[test]
+    fn test_validate_players() {
+        let issues = validate(&YEN::new(1, 0, vec!['B', '.', 'R', 'B', 'B'], ".".to_string()));
+        assert!(issues.contains(&YenIssue::ReservedPlayerSymbol { symbol: '.' }));
+        assert_eq!(
+            issues.iter().filter(|i| matches!(i, YenIssue::DuplicatePlayer { .. })).count(),
+            1
+        );
+        let seven = vec!['A', 'B', 'C', 'D', 'E', 'F', 'G'];
+        assert!(validate(&YEN::new(1, 0, seven, ".".to_string())).contains(&YenIssue::TooManyPlayers {
+            found: 7,
+            max: 6
+        }));
+        assert_eq!(validate(&YEN::new(0, 0, vec!['B', 'R'], "".to_string())), vec![YenIssue::ZeroSize]);
+        assert!(validate(&YEN::new(101, 0, vec!['B', 'R'], ".".to_string())).contains(&YenIssue::TooLarge {
+            size: 101,
+            max: 100
+        }));
+    }
+
+    #[test]
+    fn test_issue_messages() {
+        let issue = YenIssue::RowLength {
+            row: 1,
+            expected: 2,
+            found: 3,
+            text: "BRB".to_string(),
+        };
+        assert_eq!(issue.to_string(), "error: row 1 \"BRB\" has 3 cells, expected 2");
+        let error: GameYError = issue.into();
+        assert!(matches!(error, GameYError::InvalidYENLayoutLine { line: 1, .. }));
+        assert!(error.to_string().contains("\"BRB\""));
+    }
+
+    #[test]
+    fn test_malformed_layouts() {
+        type Check = fn(&GameYError) -> bool;
+        let cases: [(u32, &str, Check); 9] = [
+            (4, "B//BB/BBR", |e| matches!(e, GameYError::EmptyLayoutRow { row: 1 })),
+            (3, "/B/BR", |e| matches!(e, GameYError::EmptyLayoutRow { row: 0 })),
+            (3, "B/BR/.R./", |e| matches!(e, GameYError::TrailingSeparator)),
+            (3, "B/BR/.R.//", |e| matches!(e, GameYError::TrailingSeparator)),
+            (2, "B/  /..", |e| matches!(e, GameYError::EmptyLayoutRow { row: 1 })),
+            (1, "", |e| matches!(e, GameYError::EmptyLayoutRow { row: 0 })),
+            (3, "B/BR/. .", |e| {
+                matches!(e, GameYError::InvalidCharInLayout { char: ' ', row: 2, col: 1, text } if text == ". .")
+            }),
+            (3, "B/BRB/...", |e| {
+                matches!(e, GameYError::InvalidYENLayoutLine { line: 1, found: 3, text, .. } if text == "BRB")
+            }),
+            (3, "B/BR", |e| matches!(e, GameYError::InvalidYENLayout { expected: 3, found: 2 })),
+        ];
+        for (size, layout, expected) in cases {
+            let error = GameY::try_from(YEN::new(size, 0, vec!['B', 'R'], layout.to_string())).unwrap_err();
+            assert!(expected(&error), "{:?}: {:?}", layout, error);
+        }
+    }
+
+    #[test]
+    fn test_whitespace_between_rows_is_ignored() {
+        let spaced = YEN::new(3, 0, vec!['B', 'R'], " B /\n  .R/\n  ... ".to_string());
+        assert!(validate(&spaced).is_empty());
+        let game = GameY::try_from(spaced).unwrap();
+        assert_eq!(YEN::from(&game).layout(), "B/.R/...");
+    }
 }
@@ -0,0 +1,25 @@
+//! The types most programs need, in one import.
+//!
+//! ```
+//! use gamey::prelude::*;
+//!
+//! let mut game = GameY::new(3);
+//! game.add_move(Movement::Placement {
+//!     player: PlayerId::new(0),
+//!     coords: Coordinates::new(2, 0, 0),
+//! })
+//! .unwrap();
+//! let yen = YEN::from(&game);
+//! assert_eq!(yen.layout(), "B/../...");
+//! assert!(matches!(game.status(), GameStatus::Ongoing { .. }));
+//! ```
+//!
+//! Everything here is also available from the crate root; the prelude only
+//! gathers the part of the API that is meant to stay stable.
+
+#![deny(missing_docs)]
+
+pub use crate::core::{Coordinates, GameAction, GameStatus, GameY, Movement, PlayerId, RenderOptions, WinReason};
+pub use crate::gamey_error::GameYError;
+pub use crate::notation::YEN;
+pub use crate::bot::{YBot, YBotRegistry};
@@ -0,0 +1,356 @@
+//! Find-the-winning-move puzzles.
+//!
+//! A [`Puzzle`] is a position, the player to move and the moves that win it
+//! within a number of moves. [`verify`] checks that the listed solutions
+//! really win and, for puzzles marked `unique`, that no other move does.
+//! [`mine_puzzles`] extracts such positions from finished games, for example
+//! self-play games of the bots, and [`generate_puzzles`] plays those games.
+//!
+//! The search ignores the swap rule: after the first move of the solver every
+//! reply of the opponent is a stone placement.
+
+use crate::tournament::play_game;
+use crate::{CellIndex, Coordinates, GameEngine, GameY, GameYError, PlayerId, TriangularTopology, YBot, YEN};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Largest number of moves a puzzle may ask for; deeper searches take too long.
+pub const MAX_PUZZLE_DEPTH: u32 = 3;
+
+/// A position where the player to move wins within `depth` of their own moves.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Puzzle {
+    /// The position, which must be a two-player game in progress.
+    pub position: YEN,
+    /// The player who must find the win; the one to move in `position`.
+    pub to_move: PlayerId,
+    /// The first moves that win.
+    pub solutions: Vec<Coordinates>,
+    /// Moves of the solver needed to win: 1 wins at once, 2 wins whatever the
+    /// opponent answers to the first move, and so on.
+    #[serde(default = "default_depth")]
+    pub depth: u32,
+    /// Whether `solutions` lists every winning first move.
+    #[serde(default)]
+    pub unique: bool,
+}
+
+fn default_depth() -> u32 {
+    1
+}
+
+/// Why a puzzle is wrong.
+#[derive(Debug, thiserror::Error)]
+pub enum PuzzleError {
+    /// The position is not a valid game.
+    #[error("Invalid position: {0}")]
+    Position(#[from] GameYError),
+    /// The position is not a two-player game in progress.
+    #[error("The position is not a two-player game in progress")]
+    NotPlayable,
+    /// `to_move` is not the player to move in the position.
+    #[error("Player {found} is not to move, player {expected} is")]
+    WrongPlayer {
+        /// The player to move in the position.
+        expected: PlayerId,
+        /// The player given by the puzzle.
+        found: PlayerId,
+    },
+    /// The depth is 0 or larger than [`MAX_PUZZLE_DEPTH`].
+    #[error("Depth {depth} is not between 1 and {MAX_PUZZLE_DEPTH}")]
+    InvalidDepth {
+        /// The depth of the puzzle.
+        depth: u32,
+    },
+    /// The puzzle lists no solution.
+    #[error("The puzzle has no solutions")]
+    NoSolutions,
+    /// A solution is off the board or on an occupied cell.
+    #[error("Solution {coords} is not an empty cell of the board")]
+    IllegalSolution {
+        /// The offending solution.
+        coords: Coordinates,
+    },
+    /// A solution does not win within the depth.
+    #[error("Solution {coords} does not win in {depth}")]
+    WrongSolution {
+        /// The offending solution.
+        coords: Coordinates,
+        /// The depth of the puzzle.
+        depth: u32,
+    },
+    /// The puzzle is marked unique but another move wins too.
+    #[error("Move {coords} also wins in {depth}, but the puzzle is marked unique")]
+    UnlistedSolution {
+        /// The winning move missing from the solutions.
+        coords: Coordinates,
+        /// The depth of the puzzle.
+        depth: u32,
+    },
+    /// A puzzle file cannot be read or is not a puzzle.
+    #[error("Cannot read puzzle: {0}")]
+    File(String),
+}
+
+impl Puzzle {
+    /// Reads a puzzle from a JSON file.
+    ///
+    /// # Errors
+    /// Returns `PuzzleError::File` if the file cannot be read or parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Puzzle, PuzzleError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| PuzzleError::File(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&text).map_err(|e| PuzzleError::File(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Checks that every solution of `puzzle` wins and, if it is unique, that no other move does.
+///
+/// # Errors
+/// Returns the first problem found, see [`PuzzleError`].
+pub fn verify(puzzle: &Puzzle) -> Result<(), PuzzleError> {
+    if puzzle.depth == 0 || puzzle.depth > MAX_PUZZLE_DEPTH {
+        return Err(PuzzleError::InvalidDepth { depth: puzzle.depth });
+    }
+    let game = GameY::try_from(puzzle.position.clone())?;
+    if game.num_players() != 2 {
+        return Err(PuzzleError::NotPlayable);
+    }
+    let expected = game.next_player().ok_or(PuzzleError::NotPlayable)?;
+    if expected != puzzle.to_move {
+        return Err(PuzzleError::WrongPlayer {
+            expected,
+            found: puzzle.to_move,
+        });
+    }
+    if puzzle.solutions.is_empty() {
+        return Err(PuzzleError::NoSolutions);
+    }
+
+    let size = game.board_size();
+    let mut solver = Solver::new(&game, expected);
+    for coords in &puzzle.solutions {
+        let on_board = coords.x() as u64 + coords.y() as u64 + coords.z() as u64 + 1 == size as u64;
+        if !on_board || game.engine().occupant(coords.to_index(size) as usize).is_some() {
+            return Err(PuzzleError::IllegalSolution { coords: *coords });
+        }
+        if !solver.wins_with(coords.to_index(size) as CellIndex, puzzle.depth) {
+            return Err(PuzzleError::WrongSolution {
+                coords: *coords,
+                depth: puzzle.depth,
+            });
+        }
+    }
+    if puzzle.unique
+        && let Some(coords) = solver
+            .winning_moves(puzzle.depth)
+            .into_iter()
+            .find(|coords| !puzzle.solutions.contains(coords))
+    {
+        return Err(PuzzleError::UnlistedSolution {
+            coords,
+            depth: puzzle.depth,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the moves that win `game` for the player to move within `depth` of their moves.
+///
+/// Empty if the game is over, has more than two players or cannot be won that fast.
+pub fn winning_moves(game: &GameY, depth: u32) -> Vec<Coordinates> {
+    match game.next_player() {
+        Some(player) if game.num_players() == 2 => Solver::new(game, player).winning_moves(depth),
+        _ => Vec::new(),
+    }
+}
+
+/// Extracts the puzzles of the positions of `game`, up to `max_depth`.
+///
+/// A position becomes a puzzle of depth `n` when the player to move wins in
+/// `n` moves but not in fewer. The puzzles list every solution and are
+/// marked unique.
+pub fn mine_puzzles(game: &GameY, max_depth: u32) -> Vec<Puzzle> {
+    let max_depth = max_depth.min(MAX_PUZZLE_DEPTH);
+    game.replay()
+        .positions()
+        .iter()
+        .filter_map(|position| {
+            let to_move = position.next_player()?;
+            (1..=max_depth).find_map(|depth| {
+                let solutions = winning_moves(position, depth);
+                (!solutions.is_empty()).then(|| Puzzle {
+                    position: position.into(),
+                    to_move,
+                    solutions,
+                    depth,
+                    unique: true,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Plays `games` games of `bot` against itself on boards of `size` and mines their puzzles.
+pub fn generate_puzzles(bot: &dyn YBot, size: u32, games: u32, max_depth: u32) -> Vec<Puzzle> {
+    (0..games)
+        .flat_map(|_| mine_puzzles(&play_game(bot, bot, size), max_depth))
+        .collect()
+}
+
+/// Searches the position of a game for forced wins of one player.
+struct Solver {
+    engine: GameEngine<TriangularTopology>,
+    size: u32,
+    player: PlayerId,
+    opponent: PlayerId,
+}
+
+impl Solver {
+    fn new(game: &GameY, player: PlayerId) -> Self {
+        Self {
+            engine: game.engine().clone(),
+            size: game.board_size(),
+            player,
+            opponent: PlayerId::new(1 - player.id()),
+        }
+    }
+
+    fn empty_cells(&self) -> Vec<CellIndex> {
+        let mut cells: Vec<CellIndex> = self.engine.empty_cells().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    fn winning_moves(&mut self, depth: u32) -> Vec<Coordinates> {
+        let size = self.size;
+        self.empty_cells()
+            .into_iter()
+            .filter(|cell| self.wins_with(*cell, depth))
+            .map(|cell| Coordinates::from_index(cell as u32, size))
+            .collect()
+    }
+
+    /// Whether playing `cell` wins within `depth` moves of the player.
+    fn wins_with(&mut self, cell: CellIndex, depth: u32) -> bool {
+        if self.engine.would_win(cell, self.player) {
+            return true;
+        }
+        if depth <= 1 || self.engine.make_move(cell, self.player).is_err() {
+            return false;
+        }
+        let replies = self.empty_cells();
+        let forced = !replies.is_empty()
+            && replies.into_iter().all(|reply| {
+                if self.engine.would_win(reply, self.opponent) || self.engine.make_move(reply, self.opponent).is_err() {
+                    return false;
+                }
+                let wins = self.wins_within(depth - 1);
+                self.engine.undo_last();
+                wins
+            });
+        self.engine.undo_last();
+        forced
+    }
+
+    /// Whether the player, to move, wins within `depth` moves.
+    fn wins_within(&mut self, depth: u32) -> bool {
+        let cells = self.empty_cells();
+        cells.iter().any(|cell| self.engine.would_win(*cell, self.player))
+            || (depth > 1 && cells.into_iter().any(|cell| self.wins_with(cell, depth)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, RandomBot};
+
+    fn place(player: u32, idx: u32) -> Movement {
+        Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::from_index(idx, 3),
+        }
+    }
+
+    // Player 0 holds two corners of side B and only the middle of it is missing
+    fn win_in_one() -> Puzzle {
+        let game = GameY::from_moves(3, &[place(0, 0), place(1, 2), place(0, 3), place(1, 5)]).unwrap();
+        Puzzle {
+            position: (&game).into(),
+            to_move: PlayerId::new(0),
+            solutions: vec![Coordinates::from_index(1, 3)],
+            depth: 1,
+            unique: true,
+        }
+    }
+
+    #[test]
+    fn test_win_in_one_verifies() {
+        verify(&win_in_one()).unwrap();
+    }
+
+    #[test]
+    fn test_wrong_solution_is_rejected() {
+        let mut puzzle = win_in_one();
+        puzzle.solutions = vec![Coordinates::from_index(4, 3)];
+        assert!(matches!(verify(&puzzle), Err(PuzzleError::WrongSolution { depth: 1, .. })));
+
+        puzzle.solutions = vec![Coordinates::from_index(0, 3)];
+        assert!(matches!(verify(&puzzle), Err(PuzzleError::IllegalSolution { .. })));
+
+        puzzle.solutions.clear();
+        assert!(matches!(verify(&puzzle), Err(PuzzleError::NoSolutions)));
+
+        let mut puzzle = win_in_one();
+        puzzle.to_move = PlayerId::new(1);
+        assert!(matches!(verify(&puzzle), Err(PuzzleError::WrongPlayer { .. })));
+    }
+
+    #[test]
+    fn test_unique_puzzle_must_list_every_win() {
+        // On the empty size 2 board the first stone touches two sides and the
+        // second one wins, whatever the opponent does
+        let puzzle = Puzzle {
+            position: (&GameY::new(2)).into(),
+            to_move: PlayerId::new(0),
+            solutions: vec![Coordinates::from_index(0, 2)],
+            depth: 2,
+            unique: true,
+        };
+        assert!(matches!(verify(&puzzle), Err(PuzzleError::UnlistedSolution { depth: 2, .. })));
+        let all = Puzzle {
+            solutions: winning_moves(&GameY::new(2), 2),
+            ..puzzle.clone()
+        };
+        assert_eq!(all.solutions.len(), 3);
+        verify(&all).unwrap();
+        verify(&Puzzle { unique: false, ..puzzle }).unwrap();
+    }
+
+    #[test]
+    fn test_mined_puzzles_verify() {
+        let puzzles = generate_puzzles(&RandomBot, 4, 3, 2);
+        assert!(!puzzles.is_empty());
+        for puzzle in &puzzles {
+            verify(puzzle).unwrap();
+            assert!(puzzle.depth == 1 || winning_moves(&GameY::try_from(puzzle.position.clone()).unwrap(), 1).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_json_form() {
+        let puzzle = win_in_one();
+        let json = serde_json::to_value(&puzzle).unwrap();
+        assert_eq!(json["to_move"], 0);
+        let back: Puzzle = serde_json::from_value(json).unwrap();
+        assert_eq!(back, puzzle);
+        let minimal = serde_json::json!({
+            "position": puzzle.position,
+            "to_move": 0,
+            "solutions": [{"x": 1, "y": 0, "z": 1}]
+        });
+        let read: Puzzle = serde_json::from_value(minimal).unwrap();
+        assert_eq!((read.depth, read.unique), (1, false));
+    }
+}
@@ -0,0 +1,237 @@
+//! Elo ratings of players and bots.
+//!
+//! [`expected_score`] and [`k_factor`] are the rating math shared by the
+//! tournaments and the server. A [`Ladder`] keeps the rating of every
+//! participant across games; it only stores numbers, so it can be saved and
+//! loaded as JSON.
+//!
+//! # Example
+//!
+//! ```
+//! use gamey::rating::{INITIAL_RATING, Ladder};
+//! use gamey::{PlayerId, PlayerInfo, PlayerKind};
+//!
+//! let alice = PlayerInfo::new(PlayerId::new(0), "alice", PlayerKind::Human);
+//! let bot = PlayerInfo::new(PlayerId::new(1), "Random", PlayerKind::Bot("random_bot".to_string()));
+//! let mut ladder = Ladder::new(32.0);
+//! ladder.record(&alice, &bot);
+//! let standings = ladder.standings();
+//! assert_eq!(standings[0].name, "alice");
+//! assert!(standings[0].rating > INITIAL_RATING);
+//! assert_eq!(standings[1].name, "random_bot");
+//! ```
+
+use crate::{PlayerInfo, PlayerKind};
+use serde::{Deserialize, Serialize};
+
+/// Rating every participant starts with.
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// K-factor used when none is configured.
+pub const DEFAULT_K_FACTOR: f64 = 32.0;
+
+/// Games after which a participant's K-factor stops decaying.
+///
+/// Newcomers move twice as fast, so their rating finds its level sooner.
+pub const PROVISIONAL_GAMES: u32 = 20;
+
+/// Expected score of a player rated `a` against a player rated `b`.
+pub fn expected_score(a: f64, b: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((b - a) / 400.0))
+}
+
+/// K-factor of a participant who already played `games` games.
+///
+/// Starts at twice `base` and decays linearly to `base` over the first
+/// [`PROVISIONAL_GAMES`] games.
+pub fn k_factor(base: f64, games: u32) -> f64 {
+    let provisional = PROVISIONAL_GAMES.saturating_sub(games) as f64 / PROVISIONAL_GAMES as f64;
+    base * (1.0 + provisional)
+}
+
+/// The rating of one participant of a [`Ladder`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LadderEntry {
+    /// Name of a human, or id of a bot.
+    pub name: String,
+    /// Whether the participant is a bot.
+    pub bot: bool,
+    /// Current Elo rating.
+    pub rating: f64,
+    /// Rated games played.
+    pub games: u32,
+    /// Rated games won.
+    pub wins: u32,
+    /// Rated games lost.
+    pub losses: u32,
+}
+
+impl LadderEntry {
+    fn new(name: String, bot: bool) -> Self {
+        Self {
+            name,
+            bot,
+            rating: INITIAL_RATING,
+            games: 0,
+            wins: 0,
+            losses: 0,
+        }
+    }
+}
+
+/// Elo ratings updated game after game.
+///
+/// Humans are identified by their name and bots by their id, so a human may
+/// use the name of a bot without sharing its rating.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Ladder {
+    /// K-factor of established participants, see [`k_factor`].
+    pub k_factor: f64,
+    entries: Vec<LadderEntry>,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Self::new(DEFAULT_K_FACTOR)
+    }
+}
+
+impl Ladder {
+    /// Creates an empty ladder with the given base K-factor.
+    pub fn new(k_factor: f64) -> Self {
+        Self {
+            k_factor,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Updates the ratings after `winner` beat `loser`.
+    ///
+    /// Each side moves by its own K-factor, so a newcomer beating an
+    /// established player gains more than the other loses. Participants
+    /// play their first game at [`INITIAL_RATING`].
+    pub fn record(&mut self, winner: &PlayerInfo, loser: &PlayerInfo) {
+        let winner = self.position(winner);
+        let loser = self.position(loser);
+        if winner == loser {
+            return;
+        }
+        let expected = expected_score(self.entries[winner].rating, self.entries[loser].rating);
+        let base = self.k_factor;
+        for (idx, score, expected) in [(winner, 1.0, expected), (loser, 0.0, 1.0 - expected)] {
+            let entry = &mut self.entries[idx];
+            entry.rating += k_factor(base, entry.games) * (score - expected);
+            entry.games += 1;
+        }
+        self.entries[winner].wins += 1;
+        self.entries[loser].losses += 1;
+    }
+
+    /// Returns the rating of `player`, if it played any rated game.
+    pub fn get(&self, player: &PlayerInfo) -> Option<&LadderEntry> {
+        let (name, bot) = key_of(player);
+        self.entries.iter().find(|entry| entry.name == name && entry.bot == bot)
+    }
+
+    /// Returns every entry from the highest rating to the lowest; ties go to
+    /// the participant with more games.
+    pub fn standings(&self) -> Vec<LadderEntry> {
+        let mut standings = self.entries.clone();
+        standings.sort_by(|a, b| b.rating.total_cmp(&a.rating).then(b.games.cmp(&a.games)));
+        standings
+    }
+
+    /// Index of the entry of `player`, creating it if needed.
+    fn position(&mut self, player: &PlayerInfo) -> usize {
+        let (name, bot) = key_of(player);
+        match self.entries.iter().position(|entry| entry.name == name && entry.bot == bot) {
+            Some(idx) => idx,
+            None => {
+                self.entries.push(LadderEntry::new(name.to_string(), bot));
+                self.entries.len() - 1
+            }
+        }
+    }
+}
+
+/// The name a participant is rated under and whether it is a bot.
+fn key_of(player: &PlayerInfo) -> (&str, bool) {
+    match &player.kind {
+        PlayerKind::Bot(id) => (id, true),
+        PlayerKind::Human => (&player.name, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    fn human(name: &str) -> PlayerInfo {
+        PlayerInfo::new(PlayerId::new(0), name, PlayerKind::Human)
+    }
+
+    fn bot(id: &str) -> PlayerInfo {
+        PlayerInfo::new(PlayerId::new(1), "Bot", PlayerKind::Bot(id.to_string()))
+    }
+
+    #[test]
+    fn test_expected_score() {
+        assert_eq!(expected_score(1500.0, 1500.0), 0.5);
+        assert!((expected_score(1900.0, 1500.0) - 0.909).abs() < 0.001);
+        assert!((expected_score(1500.0, 1900.0) + expected_score(1900.0, 1500.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_k_factor_decays_to_the_base() {
+        assert_eq!(k_factor(32.0, 0), 64.0);
+        assert_eq!(k_factor(32.0, PROVISIONAL_GAMES / 2), 48.0);
+        assert_eq!(k_factor(32.0, PROVISIONAL_GAMES), 32.0);
+        assert_eq!(k_factor(32.0, 1000), 32.0);
+    }
+
+    #[test]
+    fn test_first_game_moves_both_ratings_by_the_provisional_k() {
+        let mut ladder = Ladder::new(32.0);
+        ladder.record(&human("alice"), &bot("random_bot"));
+        let alice = ladder.get(&human("alice")).unwrap();
+        let random = ladder.get(&bot("random_bot")).unwrap();
+        assert_eq!(alice.rating, INITIAL_RATING + 32.0);
+        assert_eq!(random.rating, INITIAL_RATING - 32.0);
+        assert_eq!((alice.games, alice.wins, alice.losses), (1, 1, 0));
+        assert_eq!((random.games, random.wins, random.losses), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_upsets_move_ratings_more() {
+        let mut ladder = Ladder::new(32.0);
+        for _ in 0..5 {
+            ladder.record(&bot("mcts_bot"), &bot("random_bot"));
+        }
+        let before = ladder.get(&bot("random_bot")).unwrap().rating;
+        let mut expected = ladder.clone();
+        expected.record(&bot("mcts_bot"), &bot("random_bot"));
+        ladder.record(&bot("random_bot"), &bot("mcts_bot"));
+        let upset = ladder.get(&bot("random_bot")).unwrap().rating - before;
+        let normal = before - expected.get(&bot("random_bot")).unwrap().rating;
+        assert!(upset > normal);
+    }
+
+    #[test]
+    fn test_humans_and_bots_with_the_same_name_are_apart() {
+        let mut ladder = Ladder::default();
+        ladder.record(&human("random_bot"), &bot("random_bot"));
+        let standings = ladder.standings();
+        assert_eq!(standings.len(), 2);
+        assert!(!standings[0].bot);
+        assert!(standings[1].bot);
+    }
+
+    #[test]
+    fn test_ladder_round_trips_through_json() {
+        let mut ladder = Ladder::new(16.0);
+        ladder.record(&human("alice"), &human("bob"));
+        let json = serde_json::to_string(&ladder).unwrap();
+        assert_eq!(serde_json::from_str::<Ladder>(&json).unwrap(), ladder);
+    }
+}
@@ -0,0 +1,171 @@
+//! Post-game review: finding the moves that lost the game.
+//!
+//! [`review_game`] replays a finished (or ongoing) game and, for every stone
+//! placement, compares the evaluation after the move that was played with the
+//! evaluation after the move a reference bot would have played. Moves that
+//! lose more than a threshold are flagged as blunders.
+
+use crate::eval::evaluate;
+use crate::{Coordinates, GameY, Movement, PlayerId, YBot};
+use serde::{Deserialize, Serialize};
+
+/// Evaluation reported for decided positions, so annotations stay finite and serializable.
+pub const WIN_SCORE: f32 = 1000.0;
+
+/// The review of a single stone placement.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Position of the move in the game history, starting at 1.
+    pub move_number: usize,
+    /// The player who made the move.
+    pub player: u32,
+    /// Where the stone was placed.
+    pub played: Coordinates,
+    /// Evaluation for the mover after the played move.
+    pub played_eval: f32,
+    /// The move suggested by the reference bot, if it differs from the played one.
+    pub best_move: Option<Coordinates>,
+    /// Evaluation for the mover after the suggested move.
+    pub best_eval: f32,
+    /// How much worse the played move is than the suggestion (never negative).
+    pub eval_drop: f32,
+    /// True if the drop exceeds the review threshold.
+    pub blunder: bool,
+}
+
+/// Reviews every stone placement of `game` against the moves suggested by `bot`.
+///
+/// Positions are scored with [`crate::eval::evaluate`] from the mover's point
+/// of view; decided positions count as `±WIN_SCORE`. Swaps and resignations
+/// are replayed but not annotated.
+pub fn review_game(game: &GameY, bot: &dyn YBot, threshold: f32) -> Vec<Annotation> {
+    let size = game.board_size();
+    let mut replay = GameY::new(size);
+    let mut annotations = Vec::new();
+
+    for (i, movement) in game.history().iter().enumerate() {
+        if let Movement::Placement { player, coords } = movement {
+            let played_eval = eval_after(&replay, *player, *coords);
+            let suggestion = bot
+                .choose_move(&replay)
+                .filter(|best| best != coords)
+                .map(|best| (best, eval_after(&replay, *player, best)));
+
+            let (best_move, best_eval) = match suggestion {
+                Some((best, eval)) if eval > played_eval => (Some(best), eval),
+                _ => (None, played_eval),
+            };
+            let eval_drop = best_eval - played_eval;
+            annotations.push(Annotation {
+                move_number: i + 1,
+                player: player.id(),
+                played: *coords,
+                played_eval,
+                best_move,
+                best_eval,
+                eval_drop,
+                blunder: eval_drop > threshold,
+            });
+        }
+        if let Err(err) = replay.add_move(movement.clone()) {
+            tracing::warn!("Review stopped at move {}: {}", i + 1, err);
+            break;
+        }
+    }
+    annotations
+}
+
+/// Evaluation for `player` after placing a stone at `coords`.
+pub(crate) fn eval_after(game: &GameY, player: PlayerId, coords: Coordinates) -> f32 {
+    let mut next = game.clone();
+    if next.add_move(Movement::Placement { player, coords }).is_err() {
+        return -WIN_SCORE;
+    }
+    evaluate(&next, player).clamp(-WIN_SCORE, WIN_SCORE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, MinimaxBot};
+
+    fn play(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    /// Player 0 owns the side x = 0 except the corner and can win at (0, 0, 3) or (1, 0, 2).
+    fn win_in_one_position() -> GameY {
+        let mut game = GameY::new(4);
+        play(&mut game, 0, Coordinates::new(0, 3, 0));
+        play(&mut game, 1, Coordinates::new(3, 0, 0));
+        play(&mut game, 0, Coordinates::new(0, 2, 1));
+        play(&mut game, 1, Coordinates::new(2, 1, 0));
+        play(&mut game, 0, Coordinates::new(0, 1, 2));
+        play(&mut game, 1, Coordinates::new(1, 2, 0));
+        game
+    }
+
+    #[test]
+    fn test_missed_win_in_one_is_flagged() {
+        let mut game = win_in_one_position();
+        // Player 0 plays far away instead of winning
+        play(&mut game, 0, Coordinates::new(1, 1, 1));
+
+        let annotations = review_game(&game, &MinimaxBot::new(1, None), 1.0);
+        assert_eq!(annotations.len(), 7);
+        let missed = &annotations[6];
+        assert_eq!(missed.move_number, 7);
+        assert_eq!(missed.player, 0);
+        assert!(missed.blunder);
+        assert_eq!(missed.best_eval, WIN_SCORE);
+        let best = missed.best_move.unwrap();
+        assert!(best == Coordinates::new(0, 0, 3) || best == Coordinates::new(1, 0, 2));
+    }
+
+    #[test]
+    fn test_winning_move_is_not_flagged() {
+        let mut game = win_in_one_position();
+        play(&mut game, 0, Coordinates::new(0, 0, 3));
+
+        let annotations = review_game(&game, &MinimaxBot::new(1, None), 1.0);
+        let last = annotations.last().unwrap();
+        assert!(!last.blunder);
+        assert_eq!(last.eval_drop, 0.0);
+        assert_eq!(last.played_eval, WIN_SCORE);
+    }
+
+    #[test]
+    fn test_swaps_and_resignations_are_skipped() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, Coordinates::new(1, 1, 0));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        play(&mut game, 0, Coordinates::new(0, 0, 2));
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+
+        let annotations = review_game(&game, &MinimaxBot::new(1, None), 1.0);
+        let numbers: Vec<usize> = annotations.iter().map(|a| a.move_number).collect();
+        assert_eq!(numbers, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_annotations_serialize() {
+        let mut game = GameY::new(2);
+        play(&mut game, 0, Coordinates::new(1, 0, 0));
+        let annotations = review_game(&game, &MinimaxBot::new(1, None), 1.0);
+        let json = serde_json::to_string(&annotations).unwrap();
+        let back: Vec<Annotation> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, annotations);
+    }
+}
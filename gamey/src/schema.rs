@@ -0,0 +1,61 @@
+//! JSON Schemas of YEN and the bot server API.
+//!
+//! Other services can validate their payloads against these schemas instead of
+//! keeping hand-written validators in sync with the Rust types. The schemas are
+//! generated from the types themselves, so they include the constraints the
+//! types declare, such as the minimum board size and the layout pattern.
+//!
+//! Only available with the `schema` feature.
+
+use crate::{ChooseRequest, ErrorResponse, GameStateResponse, GameYError, MoveRequest, MoveResponse, MoveResultResponse, YEN};
+use schemars::{JsonSchema, Schema, schema_for};
+use std::path::{Path, PathBuf};
+
+/// Extension of the files written by [`export_all`].
+pub const SCHEMA_EXTENSION: &str = "schema.json";
+
+/// Returns the schema of every exported type, with the file stem it is written to.
+pub fn all_schemas() -> Vec<(&'static str, Schema)> {
+    vec![
+        ("yen", schema::<YEN>()),
+        ("move_request", schema::<MoveRequest>()),
+        ("choose_request", schema::<ChooseRequest>()),
+        ("move_response", schema::<MoveResponse>()),
+        ("error_response", schema::<ErrorResponse>()),
+        ("game_state_response", schema::<GameStateResponse>()),
+        ("move_result_response", schema::<MoveResultResponse>()),
+    ]
+}
+
+/// Returns the schema of `T`.
+pub fn schema<T: JsonSchema>() -> Schema {
+    schema_for!(T)
+}
+
+/// Writes one `<name>.schema.json` file per type of [`all_schemas`] into `dir`,
+/// creating the directory if needed.
+///
+/// Returns the paths of the written files.
+///
+/// # Errors
+/// Returns `GameYError::IoError` if the directory or a file cannot be written.
+pub fn export_all<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>, GameYError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+    let mut paths = Vec::new();
+    for (name, schema) in all_schemas() {
+        let path = dir.join(format!("{}.{}", name, SCHEMA_EXTENSION));
+        let json = serde_json::to_string_pretty(&schema)
+            .map_err(|error| GameYError::SerdeError { error })?;
+        std::fs::write(&path, json + "\n").map_err(|e| io_error(&path, e))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> GameYError {
+    GameYError::IoError {
+        message: format!("writing {}", path.display()),
+        error: error.to_string(),
+    }
+}
@@ -0,0 +1,264 @@
+//! Win rates of the opening moves of finished games.
+//!
+//! [`opening_stats`] groups games by the cell of their first stone and counts
+//! how often the player who placed it went on to win. Openings related by a
+//! symmetry of the board are the same opening: the three corners of a board
+//! are counted as one cell, and so are the mirror images of any other cell.
+//!
+//! # Example
+//!
+//! ```
+//! use gamey::stats::{OpeningStatsOptions, opening_stats};
+//! use gamey::{Coordinates, GameY, Movement, PlayerId};
+//!
+//! // On a board of size 1 the first stone wins at once
+//! let mut game = GameY::new(1);
+//! let coords = Coordinates::new(0, 0, 0);
+//! game.add_move(Movement::Placement { player: PlayerId::new(0), coords }).unwrap();
+//!
+//! let stats = opening_stats(std::iter::once(game), &OpeningStatsOptions::new());
+//! assert_eq!(stats[0].coords, coords);
+//! assert_eq!(stats[0].win_rate(), 1.0);
+//! ```
+
+use crate::{Coordinates, GameY, Movement, Symmetry};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How often the player who opened on one cell won.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpeningStat {
+    /// The cell of the first stone, as the largest of its symmetric images
+    /// when comparing `(x, y, z)`.
+    pub coords: Coordinates,
+    /// Finished games that opened on the cell or one of its images.
+    pub games: u32,
+    /// Those of the games won by the player who placed the first stone.
+    pub first_player_wins: u32,
+}
+
+impl OpeningStat {
+    /// Returns the share of the games won by the first player, between 0 and 1.
+    pub fn win_rate(&self) -> f64 {
+        match self.games {
+            0 => 0.0,
+            games => self.first_player_wins as f64 / games as f64,
+        }
+    }
+}
+
+/// Order of the openings returned by [`opening_stats`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum OpeningSort {
+    /// Most played first.
+    #[default]
+    Games,
+    /// Highest win rate of the first player first.
+    WinRate,
+}
+
+/// Which openings [`opening_stats`] returns and in which order.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpeningStatsOptions {
+    /// Openings played in fewer games are left out.
+    pub min_games: u32,
+    /// Order of the openings; ties go to the most played, then to the
+    /// largest cell.
+    pub sort: OpeningSort,
+}
+
+impl Default for OpeningStatsOptions {
+    fn default() -> Self {
+        Self {
+            min_games: 1,
+            sort: OpeningSort::default(),
+        }
+    }
+}
+
+impl OpeningStatsOptions {
+    /// Returns every opening played at least once, most played first.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Leaves out the openings played in fewer than `min_games` games.
+    pub fn with_min_games(mut self, min_games: u32) -> Self {
+        self.min_games = min_games;
+        self
+    }
+
+    /// Sets the order of the openings.
+    pub fn with_sort(mut self, sort: OpeningSort) -> Self {
+        self.sort = sort;
+        self
+    }
+}
+
+/// Returns the cell that stands for `coords` and its symmetric images.
+pub fn canonical_opening(coords: Coordinates) -> Coordinates {
+    Symmetry::ALL
+        .iter()
+        .map(|symmetry| symmetry.apply(coords))
+        .max_by_key(|c| (c.x(), c.y(), c.z()))
+        .unwrap_or(coords)
+}
+
+/// Counts the games and first-player wins of every opening of `games`.
+///
+/// The opening of a game is its first placement; the player who made it is
+/// the first player, even if a swap follows. Games still in progress and
+/// games with no placement are skipped. Cells of different board sizes never
+/// share coordinates, so games of several sizes can be counted together.
+pub fn opening_stats(games: impl Iterator<Item = GameY>, options: &OpeningStatsOptions) -> Vec<OpeningStat> {
+    let mut by_cell: HashMap<Coordinates, OpeningStat> = HashMap::new();
+    for game in games {
+        let Some(winner) = game.status().winner() else {
+            continue;
+        };
+        let Some((player, coords)) = game.history().iter().find_map(|movement| match movement {
+            Movement::Placement { player, coords } => Some((*player, *coords)),
+            Movement::Action { .. } => None,
+        }) else {
+            continue;
+        };
+        let coords = canonical_opening(coords);
+        let stat = by_cell.entry(coords).or_insert(OpeningStat {
+            coords,
+            games: 0,
+            first_player_wins: 0,
+        });
+        stat.games += 1;
+        if winner == player {
+            stat.first_player_wins += 1;
+        }
+    }
+    let mut stats: Vec<OpeningStat> = by_cell
+        .into_values()
+        .filter(|stat| stat.games >= options.min_games)
+        .collect();
+    stats.sort_by(|a, b| {
+        let order = match options.sort {
+            OpeningSort::Games => std::cmp::Ordering::Equal,
+            OpeningSort::WinRate => b.win_rate().total_cmp(&a.win_rate()),
+        };
+        order
+            .then(b.games.cmp(&a.games))
+            .then((b.coords.x(), b.coords.y(), b.coords.z()).cmp(&(a.coords.x(), a.coords.y(), a.coords.z())))
+    });
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, PlayerId};
+
+    /// A game of size 4 opened by player 0 at `opening` and won by `winner`,
+    /// who resigns for the loser.
+    fn game(opening: Coordinates, winner: u32) -> GameY {
+        let mut game = GameY::new(4);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: opening,
+        })
+        .unwrap();
+        if winner == 1 {
+            game.add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            })
+            .unwrap();
+        }
+        let loser = game.next_player().unwrap();
+        assert_eq!(loser.id(), 1 - winner);
+        game.add_move(Movement::Action {
+            player: loser,
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        game
+    }
+
+    fn corner() -> Coordinates {
+        Coordinates::new(3, 0, 0)
+    }
+
+    fn center() -> Coordinates {
+        Coordinates::new(1, 1, 1)
+    }
+
+    fn edge() -> Coordinates {
+        Coordinates::new(2, 1, 0)
+    }
+
+    fn games() -> Vec<GameY> {
+        vec![
+            // Every corner is the same opening: one win in three
+            game(Coordinates::new(3, 0, 0), 0),
+            game(Coordinates::new(0, 3, 0), 1),
+            game(Coordinates::new(0, 0, 3), 1),
+            // The center wins twice
+            game(center(), 0),
+            game(center(), 0),
+            // Mirror images of an edge cell, lost both times
+            game(Coordinates::new(0, 1, 2), 1),
+            game(Coordinates::new(1, 2, 0), 1),
+            // A game in progress is not counted
+            GameY::from_moves(
+                4,
+                &[Movement::Placement {
+                    player: PlayerId::new(0),
+                    coords: center(),
+                }],
+            )
+            .unwrap(),
+        ]
+    }
+
+    fn summary(stats: &[OpeningStat]) -> Vec<(Coordinates, u32, u32)> {
+        stats.iter().map(|s| (s.coords, s.games, s.first_player_wins)).collect()
+    }
+
+    #[test]
+    fn test_symmetric_openings_are_counted_together() {
+        for coords in [Coordinates::new(0, 3, 0), Coordinates::new(0, 0, 3)] {
+            assert_eq!(canonical_opening(coords), corner());
+        }
+        for coords in [Coordinates::new(0, 1, 2), Coordinates::new(1, 0, 2), Coordinates::new(1, 2, 0)] {
+            assert_eq!(canonical_opening(coords), edge());
+        }
+        assert_eq!(canonical_opening(center()), center());
+    }
+
+    #[test]
+    fn test_most_played_openings_come_first() {
+        let stats = opening_stats(games().into_iter(), &OpeningStatsOptions::new());
+        assert_eq!(summary(&stats), [(corner(), 3, 1), (edge(), 2, 0), (center(), 2, 2)]);
+        assert!((stats[0].win_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sort_by_win_rate() {
+        let options = OpeningStatsOptions::new().with_sort(OpeningSort::WinRate);
+        let stats = opening_stats(games().into_iter(), &options);
+        assert_eq!(summary(&stats), [(center(), 2, 2), (corner(), 3, 1), (edge(), 2, 0)]);
+    }
+
+    #[test]
+    fn test_min_games_leaves_out_rare_openings() {
+        let options = OpeningStatsOptions::new().with_min_games(3);
+        let stats = opening_stats(games().into_iter(), &options);
+        assert_eq!(summary(&stats), [(corner(), 3, 1)]);
+        assert!(opening_stats(games().into_iter(), &options.with_min_games(4)).is_empty());
+    }
+
+    #[test]
+    fn test_sort_parses_from_snake_case() {
+        let sort: OpeningSort = serde_json::from_str("\"win_rate\"").unwrap();
+        assert_eq!(sort, OpeningSort::WinRate);
+        assert_eq!(OpeningStat { coords: center(), games: 0, first_player_wins: 0 }.win_rate(), 0.0);
+    }
+}
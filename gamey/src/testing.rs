@@ -0,0 +1,237 @@
+//! Proptest strategies that generate boards, positions, YEN documents and games.
+//!
+//! Available with the `testing` feature, so other crates (and the server tests)
+//! can generate positions too:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! gamey = { version = "0.1", features = ["testing"] }
+//! ```
+//!
+//! Positions are built by random playouts, so they are always reachable in a
+//! real game. [`arb_yen`] also produces documents that are almost valid, to
+//! exercise the error paths of the loaders.
+
+use crate::{Coordinates, GameAction, GameY, Movement, YEN};
+use proptest::prelude::*;
+
+/// Largest board size generated by [`arb_board_size`].
+pub const MAX_ARB_BOARD_SIZE: u32 = 12;
+
+/// Board sizes from 1 (a single cell) to [`MAX_ARB_BOARD_SIZE`].
+pub fn arb_board_size() -> impl Strategy<Value = u32> {
+    1..=MAX_ARB_BOARD_SIZE
+}
+
+/// Legal positions on a board of `size`, from the empty board to a finished game.
+///
+/// Players place stones in turn on random empty cells; the playout stops after
+/// a random number of moves or when a player wins.
+pub fn arb_position(size: u32) -> impl Strategy<Value = GameY> {
+    let total = size * (size + 1) / 2;
+    (Just((0..total).collect::<Vec<u32>>()).prop_shuffle(), 0..=total)
+        .prop_map(move |(cells, moves)| playout(GameY::new(size), &cells[..moves as usize]))
+}
+
+/// Games whose history may include a swap as the second move and end with a resignation.
+pub fn arb_game_with_history() -> impl Strategy<Value = GameY> {
+    arb_board_size()
+        .prop_flat_map(|size| {
+            let total = size * (size + 1) / 2;
+            (
+                Just(size),
+                Just((0..total).collect::<Vec<u32>>()).prop_shuffle(),
+                0..=total,
+                any::<bool>(),
+                any::<bool>(),
+            )
+        })
+        .prop_map(|(size, cells, moves, swap, resign)| {
+            let cells = &cells[..moves as usize];
+            let mut game = playout(GameY::new(size), &cells[..cells.len().min(1)]);
+            if swap && game.move_count() == 1 && !game.check_game_over() {
+                play_action(&mut game, GameAction::Swap);
+            }
+            let mut game = playout(game, cells.get(1..).unwrap_or_default());
+            if resign {
+                play_action(&mut game, GameAction::Resign);
+            }
+            game
+        })
+}
+
+/// YEN documents: mostly positions from [`arb_position`], plus near-invalid
+/// variants with a wrong turn, a bad character, a missing cell, an extra row
+/// or a repeated player symbol.
+pub fn arb_yen() -> impl Strategy<Value = YEN> {
+    let valid = || {
+        arb_board_size()
+            .prop_flat_map(arb_position)
+            .prop_map(|game| YEN::from(&game))
+    };
+    prop_oneof![
+        3 => valid(),
+        1 => (valid(), 0..5usize, any::<prop::sample::Index>())
+            .prop_map(|(yen, kind, at)| near_invalid(yen, kind, at)),
+    ]
+}
+
+/// Places stones on `cells` in turn order, stopping when the game is over.
+fn playout(mut game: GameY, cells: &[u32]) -> GameY {
+    let size = game.board_size();
+    for &idx in cells {
+        let Some(player) = game.next_player() else {
+            break;
+        };
+        let coords = Coordinates::from_index(idx, size);
+        // The cell may have been taken before a swap; skip it then
+        let _ = game.add_move(Movement::Placement { player, coords });
+    }
+    game
+}
+
+/// Plays `action` for the player to move, if the game is not over.
+fn play_action(game: &mut GameY, action: GameAction) {
+    if let Some(player) = game.next_player() {
+        let _ = game.add_move(Movement::Action { player, action });
+    }
+}
+
+/// Breaks `yen` in one of five ways, `at` choosing the affected cell or row.
+fn near_invalid(yen: YEN, kind: usize, at: prop::sample::Index) -> YEN {
+    let (size, turn, players) = (yen.size(), yen.turn(), yen.players().to_vec());
+    let mut layout: Vec<char> = yen.layout().chars().collect();
+    match kind {
+        0 => YEN::new(size, turn + players.len() as u32, players, yen.layout().to_string()),
+        1 => {
+            let cells: Vec<usize> = (0..layout.len()).filter(|i| layout[*i] != '/').collect();
+            layout[cells[at.index(cells.len())]] = 'X';
+            YEN::new(size, turn, players, layout.into_iter().collect())
+        }
+        2 => {
+            let cells: Vec<usize> = (0..layout.len()).filter(|i| layout[*i] != '/').collect();
+            layout.remove(cells[at.index(cells.len())]);
+            YEN::new(size, turn, players, layout.into_iter().collect())
+        }
+        3 => {
+            let extra = ".".repeat(size as usize + 1);
+            YEN::new(size, turn, players, format!("{}/{}", yen.layout(), extra))
+        }
+        _ => {
+            let mut players = players;
+            players[1] = players[0];
+            YEN::new(size, turn, players, yen.layout().to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoardTopology, GameEngine, GameStatus, PlayerId, RenderOptions, yen};
+
+    proptest! {
+        /// Property: Saving a legal position as YEN and loading it back gives the same YEN.
+        #[test]
+        fn prop_yen_round_trip_is_stable(game in arb_board_size().prop_flat_map(arb_position)) {
+            let yen = YEN::from(&game);
+            let loaded = GameY::try_from(yen.clone()).unwrap();
+            let again = YEN::from(&loaded);
+            prop_assert_eq!(yen.layout(), again.layout());
+            prop_assert_eq!(yen.turn(), again.turn());
+            prop_assert_eq!(yen.result(), again.result());
+            prop_assert_eq!(loaded.check_game_over(), game.check_game_over());
+        }
+
+        /// Property: Games with swaps and resignations keep their result through YEN.
+        #[test]
+        fn prop_game_result_survives_yen(game in arb_game_with_history()) {
+            let loaded = GameY::try_from(YEN::from(&game)).unwrap();
+            match (game.status(), loaded.status()) {
+                (a @ GameStatus::Finished { .. }, b @ GameStatus::Finished { .. }) => prop_assert_eq!(a, b),
+                (GameStatus::Ongoing { .. }, GameStatus::Ongoing { .. }) => {}
+                (a, b) => prop_assert!(false, "status {:?} became {:?}", a, b),
+            }
+        }
+
+        /// Property: A YEN loads exactly when validation finds no errors.
+        #[test]
+        fn prop_validate_agrees_with_loading(yen in arb_yen()) {
+            let has_errors = yen::validate(&yen).iter().any(|issue| issue.is_error());
+            prop_assert_eq!(GameY::try_from(yen).is_err(), has_errors);
+        }
+
+        /// Property: `would_win` predicts what `make_move` reports, for every empty cell and player.
+        #[test]
+        fn prop_would_win_agrees_with_make_move(game in arb_board_size().prop_flat_map(arb_position)) {
+            let engine = game.engine();
+            for &cell in game.available_cells() {
+                for player in [PlayerId::new(0), PlayerId::new(1)] {
+                    let predicted = engine.would_win(cell as usize, player);
+                    let mut played = engine.clone();
+                    prop_assert_eq!(played.make_move(cell as usize, player), Ok(predicted));
+                }
+            }
+        }
+
+        /// Property: Undoing a move restores the engine, and redoing it gives the same result.
+        #[test]
+        fn prop_undo_and_redo_are_inverses(game in arb_board_size().prop_flat_map(arb_position)) {
+            let mut engine = game.engine().clone();
+            let (state, hash) = (engine.occupants().to_vec(), engine.hash());
+            let player = PlayerId::new(0);
+            for &cell in game.available_cells() {
+                let cell = cell as usize;
+                let groups = engine.groups(player);
+                let won = engine.make_move(cell, player).unwrap();
+                let after = (engine.occupants().to_vec(), engine.hash(), engine.groups(player));
+
+                prop_assert_eq!(engine.undo_last(), Some((cell, player)));
+                prop_assert_eq!(engine.occupants(), &state[..]);
+                prop_assert_eq!(engine.hash(), hash);
+                prop_assert_eq!(engine.groups(player), groups);
+
+                prop_assert_eq!(engine.make_move(cell, player), Ok(won));
+                prop_assert_eq!((engine.occupants().to_vec(), engine.hash(), engine.groups(player)), after);
+                engine.undo_last();
+            }
+        }
+
+        /// Property: Removing any stone leaves the engine a fresh one would
+        /// have with the remaining stones, including who has won.
+        #[test]
+        fn prop_remove_piece_matches_a_rebuilt_engine(game in arb_board_size().prop_flat_map(arb_position)) {
+            let engine = game.engine();
+            for cell in (0..engine.topology().total_cells()).filter(|cell| engine.occupant(*cell).is_some()) {
+                let mut edited = engine.clone();
+                edited.remove_piece(cell).unwrap();
+                let mut rebuilt = GameEngine::new(engine.topology().clone());
+                for (other, occupant) in edited.occupants().iter().enumerate() {
+                    if let Some(player) = occupant {
+                        rebuilt.make_move(other, *player).unwrap();
+                    }
+                }
+                prop_assert_eq!(edited.occupants(), rebuilt.occupants());
+                prop_assert_eq!(edited.hash(), rebuilt.hash());
+                prop_assert_eq!(edited.winner(), rebuilt.winner());
+                for player in [PlayerId::new(0), PlayerId::new(1)] {
+                    prop_assert_eq!(edited.groups(player), rebuilt.groups(player));
+                }
+                for other in 0..edited.topology().total_cells() {
+                    prop_assert_eq!(edited.regions_of_group(other), rebuilt.regions_of_group(other));
+                    for &neighbor in engine.topology().get_neighbors(other) {
+                        prop_assert_eq!(edited.same_group(other, neighbor), rebuilt.same_group(other, neighbor));
+                    }
+                }
+            }
+        }
+
+        /// Property: Rendering never panics and shows one line per row.
+        #[test]
+        fn prop_render_never_panics(game in arb_game_with_history(), coords: bool, idx: bool, colors: bool, labels: bool) {
+            let options = RenderOptions { show_3d_coords: coords, show_idx: idx, show_colors: colors, show_comment: false, show_labels: labels, heat_map: None, highlight_last_move: false };
+            let text = game.render(&options);
+            prop_assert!(text.lines().count() >= game.board_size() as usize);
+        }
+    }
+}
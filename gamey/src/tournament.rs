@@ -0,0 +1,569 @@
+//! Round-robin tournaments between bots.
+//!
+//! [`run`] makes every pair of bots play a number of games with alternating
+//! colors, collects wins and losses per bot and rates the bots with Elo. The
+//! result can be serialized to JSON or printed as a table.
+//! [`run_into_collection`] also keeps every game in a collection file.
+//!
+//! # Example
+//!
+//! ```
+//! use gamey::tournament::{self, TournamentConfig};
+//! use gamey::{RandomBot, YBot};
+//! use std::sync::Arc;
+//!
+//! let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(RandomBot)];
+//! let config = TournamentConfig { games_per_pair: 2, board_size: 3, ..Default::default() };
+//! let result = tournament::run(&bots, &config);
+//! assert_eq!(result.games.len(), 2);
+//! ```
+
+use crate::rating::{INITIAL_RATING, expected_score};
+use crate::{
+    CollectionEntry, CollectionWriter, Coordinates, GameAction, GameY, GameYError, MoveStatsTotals, Movement,
+    WinReason, YBot,
+};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Rating every bot starts with.
+pub const INITIAL_ELO: f64 = INITIAL_RATING;
+
+/// Settings of a tournament.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TournamentConfig {
+    /// Games played by each pair of bots; colors alternate between games.
+    pub games_per_pair: u32,
+    /// Board size of every game.
+    pub board_size: u32,
+    /// Seed for the schedule (game order and which bot starts each pair).
+    ///
+    /// Bots that use their own randomness are not affected by the seed.
+    pub seed: Option<u64>,
+    /// Number of worker threads; 1 plays the games sequentially.
+    pub threads: usize,
+    /// Elo K-factor.
+    pub k_factor: f64,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            games_per_pair: 2,
+            board_size: 5,
+            seed: None,
+            threads: 1,
+            k_factor: 32.0,
+        }
+    }
+}
+
+/// The outcome of a single tournament game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// Bot playing as player 0.
+    pub first: String,
+    /// Bot playing as player 1.
+    pub second: String,
+    /// Name of the winning bot, or `None` for a draw.
+    pub winner: Option<String>,
+    /// Number of moves played, including swaps.
+    pub moves: u32,
+    /// True if the loser forfeited by returning no move or an illegal one.
+    pub forfeit: bool,
+}
+
+/// The results of one bot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Standing {
+    /// Bot name.
+    pub name: String,
+    /// Games won.
+    pub wins: u32,
+    /// Games lost.
+    pub losses: u32,
+    /// Games that could not be decided within the move limit.
+    pub draws: u32,
+    /// Final Elo rating.
+    pub elo: f64,
+    /// Change with respect to [`INITIAL_ELO`].
+    pub elo_delta: f64,
+}
+
+impl Standing {
+    /// Tournament points: one per win, half per draw.
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+}
+
+/// The results of a tournament.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    /// The settings the tournament was played with.
+    pub config: TournamentConfig,
+    /// Standings sorted from best to worst.
+    pub standings: Vec<Standing>,
+    /// Every game, in schedule order.
+    pub games: Vec<GameRecord>,
+}
+
+/// Plays a round robin between `bots` and returns the standings.
+///
+/// Bots are identified by their position in the slice, so the same bot type
+/// may appear twice; the names in the result are those returned by
+/// [`YBot::name`], with a `#n` suffix when several bots share a name.
+pub fn run(bots: &[Arc<dyn YBot>], config: &TournamentConfig) -> TournamentResult {
+    run_with(bots, config, &|_, _, _| {})
+}
+
+/// Like [`run`], but appends every game to the collection at `path` as it finishes.
+///
+/// Games are tagged with `source`, `first` and `second` (the bot names).
+/// Fails with the first error writing the collection; the tournament is
+/// played to the end anyway.
+pub fn run_into_collection<P: AsRef<Path>>(
+    bots: &[Arc<dyn YBot>],
+    config: &TournamentConfig,
+    path: P,
+) -> Result<TournamentResult, GameYError> {
+    let writer = Mutex::new(CollectionWriter::append(path)?);
+    let error = Mutex::new(None);
+    let result = run_with(bots, config, &|first: &str, second: &str, game: &GameY| {
+        let entry = CollectionEntry::new(game)
+            .with_tag("source", "tournament")
+            .with_tag("first", first)
+            .with_tag("second", second);
+        if let Err(e) = writer.lock().unwrap().write_entry(&entry) {
+            error.lock().unwrap().get_or_insert(e);
+        }
+    });
+    match error.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
+
+/// Called with the names of both bots and the game after every game of a tournament.
+type GameCallback<'a> = &'a (dyn Fn(&str, &str, &GameY) + Sync);
+
+fn run_with(bots: &[Arc<dyn YBot>], config: &TournamentConfig, on_game: GameCallback) -> TournamentResult {
+    let names = unique_names(bots);
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+
+    // Schedule: (player 0, player 1) for every game
+    let mut schedule = Vec::new();
+    for i in 0..bots.len() {
+        for j in (i + 1)..bots.len() {
+            let (a, b) = if rng.random_bool(0.5) { (i, j) } else { (j, i) };
+            for game in 0..config.games_per_pair {
+                schedule.push(if game % 2 == 0 { (a, b) } else { (b, a) });
+            }
+        }
+    }
+    schedule.shuffle(&mut rng);
+
+    let outcomes = play_schedule(bots, &names, &schedule, config, on_game);
+
+    let mut standings: Vec<Standing> = names
+        .iter()
+        .map(|name| Standing {
+            name: name.clone(),
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            elo: INITIAL_ELO,
+            elo_delta: 0.0,
+        })
+        .collect();
+    let mut games = Vec::with_capacity(schedule.len());
+    for (&(first, second), outcome) in schedule.iter().zip(outcomes) {
+        // Score of the first bot: 1 win, 0.5 draw, 0 loss
+        let score = match outcome.winner {
+            Some(0) => {
+                standings[first].wins += 1;
+                standings[second].losses += 1;
+                1.0
+            }
+            Some(_) => {
+                standings[second].wins += 1;
+                standings[first].losses += 1;
+                0.0
+            }
+            None => {
+                standings[first].draws += 1;
+                standings[second].draws += 1;
+                0.5
+            }
+        };
+        let expected = expected_score(standings[first].elo, standings[second].elo);
+        let delta = config.k_factor * (score - expected);
+        standings[first].elo += delta;
+        standings[second].elo -= delta;
+
+        games.push(GameRecord {
+            first: names[first].clone(),
+            second: names[second].clone(),
+            winner: outcome.winner.map(|w| names[if w == 0 { first } else { second }].clone()),
+            moves: outcome.moves,
+            forfeit: outcome.forfeit,
+        });
+    }
+
+    for standing in &mut standings {
+        standing.elo_delta = standing.elo - INITIAL_ELO;
+    }
+    standings.sort_by(|a, b| {
+        b.points()
+            .total_cmp(&a.points())
+            .then(b.elo.total_cmp(&a.elo))
+    });
+
+    TournamentResult {
+        config: config.clone(),
+        standings,
+        games,
+    }
+}
+
+/// Bot names, with a `#n` suffix for repeated names.
+fn unique_names(bots: &[Arc<dyn YBot>]) -> Vec<String> {
+    bots.iter()
+        .enumerate()
+        .map(|(i, bot)| {
+            let name = bot.name();
+            let repeated = bots.iter().filter(|b| b.name() == name).count() > 1;
+            if repeated {
+                let nth = bots[..i].iter().filter(|b| b.name() == name).count() + 1;
+                format!("{}#{}", name, nth)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect()
+}
+
+/// The result of a single game, from the point of view of the schedule.
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+    /// 0 if player 0 won, 1 if player 1 won, `None` for a draw.
+    winner: Option<u32>,
+    moves: u32,
+    forfeit: bool,
+}
+
+/// Plays every scheduled game, on `config.threads` threads.
+fn play_schedule(
+    bots: &[Arc<dyn YBot>],
+    names: &[String],
+    schedule: &[(usize, usize)],
+    config: &TournamentConfig,
+    on_game: GameCallback,
+) -> Vec<Outcome> {
+    let play = |a: usize, b: usize| {
+        let game = play_game(bots[a].as_ref(), bots[b].as_ref(), config.board_size);
+        on_game(&names[a], &names[b], &game);
+        outcome_of(&game)
+    };
+    let threads = config.threads.max(1).min(schedule.len().max(1));
+    if threads == 1 {
+        return schedule.iter().map(|&(a, b)| play(a, b)).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(vec![None; schedule.len()]);
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(a, b)) = schedule.get(idx) else {
+                        break;
+                    };
+                    let outcome = play(a, b);
+                    outcomes.lock().unwrap()[idx] = Some(outcome);
+                }
+            });
+        }
+    });
+    outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|outcome| outcome.expect("every scheduled game is played"))
+        .collect()
+}
+
+/// Plays one game between `first` (player 0) and `second` (player 1) and returns it.
+///
+/// A bot that returns no move or an illegal move forfeits, and the game
+/// finishes with [`WinReason::Forfeit`]. The game is left ongoing (a draw) only if it exceeds the
+/// move limit.
+pub fn play_game(first: &dyn YBot, second: &dyn YBot, board_size: u32) -> GameY {
+    play_game_with_stats(first, second, board_size).0
+}
+
+/// Like [`play_game`], also adding up the [`crate::MoveStats`] of the moves of each
+/// player, indexed by player id.
+pub fn play_game_with_stats(first: &dyn YBot, second: &dyn YBot, board_size: u32) -> (GameY, [MoveStatsTotals; 2]) {
+    let mut game = GameY::new(board_size);
+    let mut totals = [MoveStatsTotals::default(); 2];
+    // Every cell plus the optional swap
+    let max_moves = game.total_cells() + 1;
+    while let Some(player) = game.next_player() {
+        if game.move_count() as u32 >= max_moves {
+            break;
+        }
+        let bot = if player.id() == 0 { first } else { second };
+        let movement = if player.id() == 1 && game.move_count() == 1 && bot.should_swap(&game) {
+            Some(Movement::Action {
+                player,
+                action: GameAction::Swap,
+            })
+        } else {
+            let (coords, stats) = bot.choose_move_with_stats(&game, None);
+            totals[player.id() as usize].record(&stats);
+            coords.map(|coords: Coordinates| Movement::Placement { player, coords })
+        };
+        let accepted = movement.is_some_and(|m| game.add_move(m).is_ok());
+        if !accepted {
+            game.forfeit(player);
+        }
+    }
+    (game, totals)
+}
+
+/// Summarizes a game played by [`play_game`].
+fn outcome_of(game: &GameY) -> Outcome {
+    Outcome {
+        winner: game.status().winner().map(|winner| winner.id()),
+        moves: game.move_count() as u32,
+        forfeit: game.status().reason() == Some(WinReason::Forfeit),
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .standings
+            .iter()
+            .map(|s| s.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Bot".len());
+        writeln!(
+            f,
+            "{:>3}  {:<width$}  {:>5}  {:>5}  {:>5}  {:>7}  {:>7}",
+            "#", "Bot", "W", "L", "D", "Elo", "Δ"
+        )?;
+        for (rank, s) in self.standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}  {:<width$}  {:>5}  {:>5}  {:>5}  {:>7.1}  {:>+7.1}",
+                rank + 1,
+                s.name,
+                s.wins,
+                s.losses,
+                s.draws,
+                s.elo,
+                s.elo_delta
+            )?;
+        }
+        write!(
+            f,
+            "{} games on a board of size {}",
+            self.games.len(),
+            self.config.board_size
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlayerId, RandomBot};
+
+    /// Plays a winning move if there is one, blocks the opponent's if not, and plays randomly otherwise.
+    struct GreedyBot;
+
+    /// Returns a cell where `player` would connect the three sides.
+    fn winning_cell(game: &GameY, player: PlayerId) -> Option<u32> {
+        game.available_cells().iter().copied().find(|&idx| {
+            let mut engine = game.engine().clone();
+            engine.make_move(idx as usize, player) == Ok(true)
+        })
+    }
+
+    impl YBot for GreedyBot {
+        fn name(&self) -> &str {
+            "greedy_bot"
+        }
+
+        fn choose_move(&self, game: &GameY) -> Option<Coordinates> {
+            let player = game.next_player()?;
+            let opponent = PlayerId::new(1 - player.id());
+            match winning_cell(game, player).or_else(|| winning_cell(game, opponent)) {
+                Some(idx) => Some(Coordinates::from_index(idx, game.board_size())),
+                None => RandomBot.choose_move(game),
+            }
+        }
+    }
+
+    /// A bot that never answers.
+    struct SilentBot;
+
+    impl YBot for SilentBot {
+        fn name(&self) -> &str {
+            "silent_bot"
+        }
+
+        fn choose_move(&self, _game: &GameY) -> Option<Coordinates> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_greedy_bot_finishes_above_random_bot() {
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(GreedyBot)];
+        let config = TournamentConfig {
+            games_per_pair: 30,
+            board_size: 5,
+            seed: Some(7),
+            threads: 4,
+            ..Default::default()
+        };
+        let result = run(&bots, &config);
+        assert_eq!(result.standings[0].name, "greedy_bot");
+        assert!(result.standings[0].elo_delta > 0.0);
+        assert_eq!(result.games.len(), 30);
+    }
+
+    #[test]
+    fn test_colors_alternate_within_a_pair() {
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(GreedyBot)];
+        let config = TournamentConfig {
+            games_per_pair: 4,
+            board_size: 3,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let result = run(&bots, &config);
+        let greedy_first = result.games.iter().filter(|g| g.first == "greedy_bot").count();
+        assert_eq!(greedy_first, 2);
+    }
+
+    #[test]
+    fn test_round_robin_schedule_size() {
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(RandomBot), Arc::new(RandomBot)];
+        let config = TournamentConfig {
+            games_per_pair: 2,
+            board_size: 3,
+            ..Default::default()
+        };
+        let result = run(&bots, &config);
+        // 3 pairs × 2 games
+        assert_eq!(result.games.len(), 6);
+        let names: Vec<_> = result.standings.iter().map(|s| s.name.clone()).collect();
+        assert!(names.contains(&"random_bot#1".to_string()));
+        assert!(names.contains(&"random_bot#3".to_string()));
+        let total_wins: u32 = result.standings.iter().map(|s| s.wins).sum();
+        assert_eq!(total_wins, 6);
+    }
+
+    #[test]
+    fn test_elo_is_zero_sum() {
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(GreedyBot)];
+        let config = TournamentConfig {
+            games_per_pair: 6,
+            board_size: 4,
+            ..Default::default()
+        };
+        let result = run(&bots, &config);
+        let total: f64 = result.standings.iter().map(|s| s.elo_delta).sum();
+        assert!(total.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_silent_bot_forfeits() {
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(SilentBot), Arc::new(RandomBot)];
+        let config = TournamentConfig {
+            games_per_pair: 2,
+            board_size: 3,
+            ..Default::default()
+        };
+        let result = run(&bots, &config);
+        assert!(result.games.iter().all(|g| g.forfeit));
+        let game = play_game(&SilentBot, &RandomBot, 3);
+        assert_eq!(game.status().reason(), Some(WinReason::Forfeit));
+        assert_eq!(game.forfeited(), Some(PlayerId::new(0)));
+        assert!(game.history().is_empty());
+        assert_eq!(result.standings[0].name, "random_bot");
+        assert_eq!(result.standings[0].wins, 2);
+    }
+
+    #[test]
+    fn test_play_game_with_stats_counts_moves_per_player() {
+        let (game, totals) = play_game_with_stats(&RandomBot, &RandomBot, 4);
+        let placements = |id: u32| {
+            game.history()
+                .iter()
+                .filter(|m| matches!(m, Movement::Placement { player, .. } if player.id() == id))
+                .count() as u64
+        };
+        assert_eq!(totals[0].moves, placements(0));
+        assert_eq!(totals[1].moves, placements(1));
+        assert_eq!(totals[0].playouts + totals[1].playouts, 0);
+    }
+
+    #[test]
+    fn test_result_serializes_and_prints() {
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(GreedyBot)];
+        let config = TournamentConfig {
+            games_per_pair: 2,
+            board_size: 3,
+            ..Default::default()
+        };
+        let result = run(&bots, &config);
+        let json = serde_json::to_string(&result).unwrap();
+        let back: TournamentResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, result);
+
+        let table = result.to_string();
+        assert!(table.contains("greedy_bot"));
+        assert!(table.contains("random_bot"));
+        assert!(table.contains("2 games on a board of size 3"));
+    }
+
+    #[test]
+    fn test_run_into_collection_keeps_every_game() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tournament.jsonl");
+        let bots: Vec<Arc<dyn YBot>> = vec![Arc::new(RandomBot), Arc::new(GreedyBot)];
+        let config = TournamentConfig {
+            games_per_pair: 4,
+            board_size: 3,
+            threads: 2,
+            ..Default::default()
+        };
+        let result = run_into_collection(&bots, &config, &path).unwrap();
+        assert_eq!(result.games.len(), 4);
+
+        let entries: Vec<CollectionEntry> = crate::CollectionReader::open(&path)
+            .unwrap()
+            .map(|e| e.unwrap())
+            .collect();
+        assert_eq!(entries.len(), 4);
+        assert!(entries.iter().all(|e| e.tags["source"] == "tournament"));
+        assert!(entries.iter().all(|e| e.tags["first"] != e.tags["second"]));
+    }
+}
@@ -0,0 +1,203 @@
+//! Stepping through a saved game in the terminal.
+//!
+//! [`run`] shows the empty board of a [`Replay`] and then reads one command
+//! per line, redrawing the board after every move it goes to. [`animate`]
+//! plays the whole game on its own, one move per delay, for demos and
+//! recordings. Both return the plies they displayed, in order.
+//!
+//! # Commands
+//! - `n` (or an empty line) - Step to the next move
+//! - `p` - Step back to the previous move
+//! - `g <ply>` - Go to the position after `ply` moves, `g 0` is the empty board
+//! - `q` - Stop
+//!
+//! # Example
+//!
+//! ```
+//! use gamey::{GameY, RenderOptions, viewer};
+//!
+//! let game = GameY::from_move_text(3, "1. a1 b3 2. c3").unwrap();
+//! let mut output = Vec::new();
+//! let shown = viewer::run(&mut game.replay(), "n\ng 3\np\nq\n".as_bytes(), &mut output, &RenderOptions::default()).unwrap();
+//! assert_eq!(shown, vec![0, 1, 3, 2]);
+//! ```
+
+use crate::{GameStatus, RenderOptions, Replay};
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+/// Clears the terminal and moves the cursor to its top left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// A command of the interactive viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewerCommand {
+    /// Step to the next move.
+    Next,
+    /// Step back to the previous move.
+    Previous,
+    /// Go to the position after this many moves.
+    Go(usize),
+    /// Stop viewing.
+    Quit,
+}
+
+impl ViewerCommand {
+    /// Parses a line of input, `None` if it is not a command.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            None | Some("n") | Some("next") => ViewerCommand::Next,
+            Some("p") | Some("prev") => ViewerCommand::Previous,
+            Some("g") | Some("go") => ViewerCommand::Go(words.next()?.parse().ok()?),
+            Some("q") | Some("quit") => ViewerCommand::Quit,
+            Some(_) => return None,
+        };
+        words.next().is_none().then_some(command)
+    }
+}
+
+/// Shows the empty board of `replay` and then follows the commands read from
+/// `reader`, until `q` or the end of the input.
+///
+/// Stepping past either end of the game writes a note instead of a board,
+/// and unknown commands write the list of commands.
+///
+/// # Errors
+/// Returns the errors of `reader` and `writer`.
+pub fn run<R: BufRead, W: Write>(
+    replay: &mut Replay,
+    reader: R,
+    mut writer: W,
+    options: &RenderOptions,
+) -> io::Result<Vec<usize>> {
+    replay.seek(0);
+    let mut shown = vec![replay.ply()];
+    show(&mut writer, replay, options)?;
+    for line in reader.lines() {
+        let moved = match ViewerCommand::parse(&line?) {
+            Some(ViewerCommand::Next) => replay.step_forward().is_some(),
+            Some(ViewerCommand::Previous) => replay.step_back().is_some(),
+            Some(ViewerCommand::Go(ply)) => {
+                replay.seek(ply);
+                true
+            }
+            Some(ViewerCommand::Quit) => break,
+            None => {
+                writeln!(writer, "Commands: n (next), p (previous), g <ply> (go to), q (quit)")?;
+                continue;
+            }
+        };
+        if moved {
+            shown.push(replay.ply());
+            show(&mut writer, replay, options)?;
+        } else if replay.ply() == 0 {
+            writeln!(writer, "Already at the start of the game")?;
+        } else {
+            writeln!(writer, "Already at the last move")?;
+        }
+    }
+    Ok(shown)
+}
+
+/// Shows every position of `replay` in turn, from the empty board to the
+/// last move, waiting `delay` between them and clearing the screen before
+/// each one.
+///
+/// # Errors
+/// Returns the errors of `writer`.
+pub fn animate<W: Write>(
+    replay: &mut Replay,
+    mut writer: W,
+    delay: Duration,
+    options: &RenderOptions,
+) -> io::Result<Vec<usize>> {
+    replay.seek(0);
+    let mut shown = Vec::with_capacity(replay.len() + 1);
+    loop {
+        write!(writer, "{}", CLEAR_SCREEN)?;
+        show(&mut writer, replay, options)?;
+        writer.flush()?;
+        shown.push(replay.ply());
+        if replay.ply() == replay.len() {
+            return Ok(shown);
+        }
+        std::thread::sleep(delay);
+        replay.step_forward();
+    }
+}
+
+/// Writes the move that led to the current position, the board and, at the
+/// end of a finished game, its winner.
+fn show<W: Write>(writer: &mut W, replay: &Replay, options: &RenderOptions) -> io::Result<()> {
+    let game = replay.current();
+    match replay.ply().checked_sub(1).and_then(|last| replay.moves().get(last)) {
+        Some(movement) => writeln!(
+            writer,
+            "Move {}/{}: {}",
+            replay.ply(),
+            replay.len(),
+            movement.notation(game.board_size())
+        )?,
+        None => writeln!(writer, "Move 0/{}: start", replay.len())?,
+    }
+    write!(writer, "{}", game.render(options))?;
+    if let GameStatus::Finished { winner, .. } = game.status() {
+        writeln!(writer, "Player {} wins", winner)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameY;
+
+    fn game() -> GameY {
+        GameY::from_move_text(3, "1. a1 b3 2. c3 a2").unwrap()
+    }
+
+    fn view(script: &str) -> (Vec<usize>, String) {
+        let mut output = Vec::new();
+        let shown = run(&mut game().replay(), script.as_bytes(), &mut output, &RenderOptions::default()).unwrap();
+        (shown, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(ViewerCommand::parse("n"), Some(ViewerCommand::Next));
+        assert_eq!(ViewerCommand::parse(""), Some(ViewerCommand::Next));
+        assert_eq!(ViewerCommand::parse(" p "), Some(ViewerCommand::Previous));
+        assert_eq!(ViewerCommand::parse("g 12"), Some(ViewerCommand::Go(12)));
+        assert_eq!(ViewerCommand::parse("quit"), Some(ViewerCommand::Quit));
+        assert_eq!(ViewerCommand::parse("g"), None);
+        assert_eq!(ViewerCommand::parse("g two"), None);
+        assert_eq!(ViewerCommand::parse("n n"), None);
+        assert_eq!(ViewerCommand::parse("x"), None);
+    }
+
+    #[test]
+    fn test_scripted_session_shows_each_ply() {
+        let (shown, output) = view("n\nn\np\ng 4\np\ng 0\nq\nn\n");
+        assert_eq!(shown, vec![0, 1, 2, 1, 4, 3, 0]);
+        assert!(output.starts_with("Move 0/4: start\n"), "{}", output);
+        assert!(output.contains("Move 2/4: R b3\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_stepping_past_the_ends_shows_nothing() {
+        let (shown, output) = view("p\ng 9\nn\nwhat\n");
+        assert_eq!(shown, vec![0, 4]);
+        assert!(output.contains("Already at the start of the game"));
+        assert!(output.contains("Already at the last move"));
+        assert!(output.contains("Commands: n (next)"));
+    }
+
+    #[test]
+    fn test_animate_shows_every_ply() {
+        let mut output = Vec::new();
+        let shown = animate(&mut game().replay(), &mut output, Duration::ZERO, &RenderOptions::default()).unwrap();
+        assert_eq!(shown, vec![0, 1, 2, 3, 4]);
+        assert_eq!(String::from_utf8(output).unwrap().matches(CLEAR_SCREEN).count(), 5);
+    }
+}
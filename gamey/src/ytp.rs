@@ -0,0 +1,521 @@
+//! YTP, a GTP-style text protocol to drive the bots from other programs.
+//!
+//! Go and Hex GUIs and referees talk to engines with line-based protocols.
+//! [`run`] reads one command per line and answers in the GTP framing: a
+//! success starts with `=`, a failure with `?`, optionally followed by the
+//! id of the command, and every answer ends with an empty line.
+//!
+//! Cells are named as in the move text of [`GameY::to_move_text`]: column
+//! letter then row number from the top corner, like `a1` or `c3`. Colors are
+//! the player symbols, `B` for player 0 and `R` for player 1.
+//!
+//! # Commands
+//! - `boardsize N` - Start an empty game of size N
+//! - `clear_board` - Start an empty game of the current size
+//! - `play <color> <cell|swap|resign>` - Play a move for the player to move
+//! - `genmove <color>` - Let the bot play for the player to move and answer its move
+//! - `showboard` - Print the board
+//! - `name`, `version`, `protocol_version`, `list_commands`, `known_command <command>`
+//! - `quit` - Answer and stop reading
+//!
+//! # Example
+//!
+//! ```
+//! use gamey::{RandomBot, YBotRegistry, ytp};
+//! use std::sync::Arc;
+//!
+//! let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+//! let mut output = Vec::new();
+//! ytp::run("1 boardsize 3\n2 play B a1\nquit\n".as_bytes(), &mut output, &registry).unwrap();
+//! assert_eq!(String::from_utf8(output).unwrap(), "=1\n\n=2\n\n=\n\n");
+//! ```
+
+use crate::notation::move_text::{cell_name, parse_cell_name};
+use crate::{
+    GameAction, GameY, Movement, PLAYER_SYMBOLS, PlayerId, RenderOptions, YBot, YBotRegistry,
+};
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+/// Bot used by [`run`] when the registry has it.
+pub const DEFAULT_BOT: &str = "random_bot";
+
+/// Board size of the game before any `boardsize` command.
+pub const DEFAULT_BOARD_SIZE: u32 = 7;
+
+/// Largest board accepted by `boardsize`, the largest any game supports.
+pub use crate::MAX_BOARD_SIZE;
+
+/// Every command understood by the engine, as listed by `list_commands`.
+pub const COMMANDS: [&str; 11] = [
+    "boardsize",
+    "clear_board",
+    "genmove",
+    "known_command",
+    "list_commands",
+    "name",
+    "play",
+    "protocol_version",
+    "quit",
+    "showboard",
+    "version",
+];
+
+/// A command line split into its optional id, name and arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YtpCommand {
+    /// Numeric id echoed in the answer, if the command had one.
+    pub id: Option<u32>,
+    /// Name of the command.
+    pub name: String,
+    /// Arguments after the name.
+    pub args: Vec<String>,
+}
+
+impl YtpCommand {
+    /// Parses a command line.
+    ///
+    /// Comments (from `#` to the end of the line) are removed. Returns `None`
+    /// for lines without a command.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let first = words.next()?;
+        let (id, name) = match first.parse::<u32>() {
+            Ok(id) => (Some(id), words.next()?),
+            Err(_) => (None, first),
+        };
+        Some(Self {
+            id,
+            name: name.to_string(),
+            args: words.map(str::to_string).collect(),
+        })
+    }
+}
+
+/// The state of a YTP session: the game and the bot that plays `genmove`.
+pub struct YtpEngine {
+    game: GameY,
+    bot: Option<Arc<dyn YBot>>,
+}
+
+impl YtpEngine {
+    /// Creates an engine with an empty game of [`DEFAULT_BOARD_SIZE`].
+    ///
+    /// `genmove` uses [`DEFAULT_BOT`] if the registry has it, and otherwise
+    /// the bot whose name comes first.
+    pub fn new(registry: &YBotRegistry) -> Self {
+        let mut names = registry.names();
+        names.sort();
+        let bot = registry
+            .find(DEFAULT_BOT)
+            .or_else(|| names.first().and_then(|name| registry.find(name)));
+        Self {
+            game: GameY::new(DEFAULT_BOARD_SIZE),
+            bot,
+        }
+    }
+
+    /// Uses `bot` for `genmove`.
+    pub fn with_bot(mut self, bot: Arc<dyn YBot>) -> Self {
+        self.bot = Some(bot);
+        self
+    }
+
+    /// The game being played.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Runs a command and returns the text of its answer or its error.
+    pub fn execute(&mut self, command: &YtpCommand) -> Result<String, String> {
+        let args: Vec<&str> = command.args.iter().map(String::as_str).collect();
+        match (command.name.as_str(), args.as_slice()) {
+            ("name", []) => Ok("gamey".to_string()),
+            ("version", []) => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            ("protocol_version", []) => Ok("2".to_string()),
+            ("list_commands", []) => Ok(COMMANDS.join("\n")),
+            ("known_command", [name]) => Ok(COMMANDS.contains(name).to_string()),
+            ("quit", []) => Ok(String::new()),
+            ("boardsize", [size]) => {
+                self.game = size
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(|size| GameY::try_new(size).ok())
+                    .ok_or_else(|| "unacceptable size".to_string())?;
+                Ok(String::new())
+            }
+            ("clear_board", []) => {
+                self.game = GameY::new(self.game.board_size());
+                Ok(String::new())
+            }
+            ("play", [color, vertex]) => {
+                let player = self.player_to_move(color)?;
+                let movement = parse_vertex(vertex, player, self.game.board_size())
+                    .ok_or_else(|| format!("invalid vertex '{}'", vertex))?;
+                self.game
+                    .add_move(movement)
+                    .map_err(|e| format!("illegal move: {}", e))?;
+                Ok(String::new())
+            }
+            ("genmove", [color]) => {
+                let player = self.player_to_move(color)?;
+                let bot = self.bot.clone().ok_or_else(|| "no bot available".to_string())?;
+                let movement = if self.game.move_count() == 1 && bot.should_swap(&self.game) {
+                    Movement::Action {
+                        player,
+                        action: GameAction::Swap,
+                    }
+                } else {
+                    match bot.choose_move(&self.game) {
+                        Some(coords) => Movement::Placement { player, coords },
+                        None => Movement::Action {
+                            player,
+                            action: GameAction::Resign,
+                        },
+                    }
+                };
+                let answer = vertex_name(&movement, self.game.board_size());
+                self.game
+                    .add_move(movement)
+                    .map_err(|e| format!("bot played an illegal move: {}", e))?;
+                Ok(answer)
+            }
+            ("showboard", []) => {
+                let options = RenderOptions {
+                    show_3d_coords: false,
+                    show_idx: false,
+                    show_colors: false,
+                    show_comment: false,
+                    show_labels: false,
+                    heat_map: None,
+                    highlight_last_move: false,
+                };
+                Ok(format!("\n{}", self.game.render(&options).trim_end()))
+            }
+            (name, _) if COMMANDS.contains(&name) => {
+                Err(format!("wrong number of arguments for {}", name))
+            }
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    /// The player named by `color`, if it is the one to move.
+    fn player_to_move(&self, color: &str) -> Result<PlayerId, String> {
+        let player = parse_color(color).ok_or_else(|| format!("invalid color '{}'", color))?;
+        match self.game.next_player() {
+            None => Err("the game is over".to_string()),
+            Some(next) if next != player => Err(format!(
+                "not the turn of {}, {} is to move",
+                color.to_ascii_uppercase(),
+                PLAYER_SYMBOLS[next.id() as usize]
+            )),
+            Some(_) => Ok(player),
+        }
+    }
+}
+
+/// Reads commands from `reader` until `quit` or the end of input, writing the answers to `writer`.
+///
+/// `genmove` is played by [`DEFAULT_BOT`] or, if the registry does not
+/// have it, by the bot whose name comes first. Use [`YtpEngine::with_bot`]
+/// and [`run_engine`] to choose another one.
+pub fn run<R: BufRead, W: Write>(reader: R, writer: W, registry: &YBotRegistry) -> io::Result<()> {
+    run_engine(YtpEngine::new(registry), reader, writer)
+}
+
+/// Like [`run`], with an engine that was already set up.
+pub fn run_engine<R: BufRead, W: Write>(
+    mut engine: YtpEngine,
+    reader: R,
+    mut writer: W,
+) -> io::Result<()> {
+    for line in reader.lines() {
+        let Some(command) = YtpCommand::parse(&line?) else {
+            continue;
+        };
+        let (prefix, text) = match engine.execute(&command) {
+            Ok(text) => ('=', text),
+            Err(text) => ('?', text),
+        };
+        let id = command.id.map(|id| id.to_string()).unwrap_or_default();
+        let separator = if text.is_empty() || text.starts_with('\n') { "" } else { " " };
+        write!(writer, "{}{}{}{}\n\n", prefix, id, separator, text)?;
+        writer.flush()?;
+        if command.name == "quit" {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The player of a color symbol, case-insensitive.
+fn parse_color(color: &str) -> Option<PlayerId> {
+    let mut chars = color.chars();
+    let symbol = chars.next()?.to_ascii_uppercase();
+    if chars.next().is_some() {
+        return None;
+    }
+    PLAYER_SYMBOLS[..2]
+        .iter()
+        .position(|s| *s == symbol)
+        .map(|id| PlayerId::new(id as u32))
+}
+
+fn parse_vertex(vertex: &str, player: PlayerId, size: u32) -> Option<Movement> {
+    match vertex.to_ascii_lowercase().as_str() {
+        "swap" => Some(Movement::Action {
+            player,
+            action: GameAction::Swap,
+        }),
+        "resign" => Some(Movement::Action {
+            player,
+            action: GameAction::Resign,
+        }),
+        name => parse_cell_name(name, size).map(|coords| Movement::Placement { player, coords }),
+    }
+}
+
+fn vertex_name(movement: &Movement, size: u32) -> String {
+    match movement {
+        Movement::Placement { coords, .. } => cell_name(*coords, size),
+        Movement::Action {
+            action: GameAction::Swap,
+            ..
+        } => "swap".to_string(),
+        Movement::Action {
+            action: GameAction::Resign,
+            ..
+        } => "resign".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameStatus, RandomBot};
+
+    /// Runs `input` through a fresh engine and returns the output.
+    fn session(input: &str) -> String {
+        let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+        let mut output = Vec::new();
+        run(input.as_bytes(), &mut output, &registry).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    /// The answers of a session, without the blank line that ends each of them.
+    fn answers(input: &str) -> Vec<String> {
+        session(input)
+            .split("\n\n")
+            .filter(|a| !a.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn engine() -> YtpEngine {
+        YtpEngine::new(&YBotRegistry::new().with_bot(Arc::new(RandomBot)))
+    }
+
+    fn execute(engine: &mut YtpEngine, line: &str) -> Result<String, String> {
+        engine.execute(&YtpCommand::parse(line).unwrap())
+    }
+
+    /// A bot that always plays the first free cell and never swaps.
+    struct FirstCellBot;
+
+    impl YBot for FirstCellBot {
+        fn name(&self) -> &str {
+            "first_cell_bot"
+        }
+
+        fn choose_move(&self, game: &GameY) -> Option<Coordinates> {
+            let idx = *game.available_cells().first()?;
+            Some(Coordinates::from_index(idx, game.board_size()))
+        }
+    }
+
+    /// A bot that has no move to offer.
+    struct SilentBot;
+
+    impl YBot for SilentBot {
+        fn name(&self) -> &str {
+            "silent_bot"
+        }
+
+        fn choose_move(&self, _game: &GameY) -> Option<Coordinates> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            YtpCommand::parse("12 play B a3"),
+            Some(YtpCommand {
+                id: Some(12),
+                name: "play".to_string(),
+                args: vec!["B".to_string(), "a3".to_string()],
+            })
+        );
+        assert_eq!(
+            YtpCommand::parse("  genmove\tR  # the bot answers"),
+            Some(YtpCommand {
+                id: None,
+                name: "genmove".to_string(),
+                args: vec!["R".to_string()],
+            })
+        );
+        assert_eq!(YtpCommand::parse(""), None);
+        assert_eq!(YtpCommand::parse("   # only a comment"), None);
+        assert_eq!(YtpCommand::parse("7"), None);
+    }
+
+    #[test]
+    fn test_framing_with_and_without_ids() {
+        assert_eq!(
+            session("name\n3 version\n4 foo\nquit\n"),
+            format!("= gamey\n\n=3 {}\n\n?4 unknown command\n\n=\n\n", env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[test]
+    fn test_quit_stops_reading() {
+        assert_eq!(answers("quit\nname\n"), vec!["="]);
+    }
+
+    #[test]
+    fn test_blank_lines_and_comments_get_no_answer() {
+        assert_eq!(answers("\n# comment\n\nname\n"), vec!["= gamey"]);
+    }
+
+    #[test]
+    fn test_play_and_showboard() {
+        let output = answers("boardsize 3\nplay B a1\nplay r c3\nshowboard\n");
+        assert_eq!(output[..3], ["=", "=", "="]);
+        assert!(output[3].starts_with("=\n"));
+        assert!(output[3].contains("Size 3"));
+
+        let mut engine = engine();
+        execute(&mut engine, "boardsize 3").unwrap();
+        execute(&mut engine, "play B a1").unwrap();
+        execute(&mut engine, "play R c3").unwrap();
+        assert_eq!(
+            engine.game().history()[1],
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(0, 2, 0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_out_of_turn_play_is_rejected() {
+        let mut engine = engine();
+        let err = execute(&mut engine, "play R a1").unwrap_err();
+        assert_eq!(err, "not the turn of R, B is to move");
+        assert!(engine.game().history().is_empty());
+
+        execute(&mut engine, "play B a1").unwrap();
+        assert!(execute(&mut engine, "play B b2").is_err());
+        assert!(execute(&mut engine, "genmove B").is_err());
+    }
+
+    #[test]
+    fn test_malformed_commands() {
+        let mut engine = engine();
+        assert_eq!(
+            execute(&mut engine, "play B").unwrap_err(),
+            "wrong number of arguments for play"
+        );
+        assert_eq!(execute(&mut engine, "play X a1").unwrap_err(), "invalid color 'X'");
+        assert_eq!(execute(&mut engine, "play B z9").unwrap_err(), "invalid vertex 'z9'");
+        assert_eq!(execute(&mut engine, "play B 1a").unwrap_err(), "invalid vertex '1a'");
+        assert_eq!(execute(&mut engine, "boardsize 0").unwrap_err(), "unacceptable size");
+        assert_eq!(execute(&mut engine, "boardsize x").unwrap_err(), "unacceptable size");
+        assert_eq!(
+            execute(&mut engine, "boardsize 101").unwrap_err(),
+            "unacceptable size"
+        );
+        assert!(execute(&mut engine, "name extra").is_err());
+        assert!(engine.game().history().is_empty());
+    }
+
+    #[test]
+    fn test_occupied_cell_is_illegal() {
+        let mut engine = engine();
+        execute(&mut engine, "play B a1").unwrap();
+        let err = execute(&mut engine, "play R a1").unwrap_err();
+        assert!(err.starts_with("illegal move"), "{}", err);
+        assert_eq!(engine.game().history().len(), 1);
+    }
+
+    #[test]
+    fn test_genmove_plays_for_the_player_to_move() {
+        let mut engine = engine().with_bot(Arc::new(FirstCellBot));
+        execute(&mut engine, "boardsize 2").unwrap();
+        execute(&mut engine, "play B a2").unwrap();
+        assert_eq!(execute(&mut engine, "genmove R").unwrap(), "a1");
+        assert_eq!(engine.game().history().len(), 2);
+    }
+
+    #[test]
+    fn test_genmove_resigns_without_a_move() {
+        let mut engine = engine().with_bot(Arc::new(SilentBot));
+        assert_eq!(execute(&mut engine, "genmove B").unwrap(), "resign");
+        assert!(matches!(
+            engine.game().status(),
+            GameStatus::Finished { winner, .. } if winner.id() == 1
+        ));
+        assert_eq!(execute(&mut engine, "play R a1").unwrap_err(), "the game is over");
+    }
+
+    #[test]
+    fn test_genmove_without_bots_fails() {
+        let mut engine = YtpEngine::new(&YBotRegistry::new());
+        assert_eq!(execute(&mut engine, "genmove B").unwrap_err(), "no bot available");
+    }
+
+    #[test]
+    fn test_swap_and_resign_vertices() {
+        let mut engine = engine();
+        execute(&mut engine, "play B b2").unwrap();
+        execute(&mut engine, "play R swap").unwrap();
+        // After a swap the first player moves again
+        execute(&mut engine, "play B resign").unwrap();
+        assert!(engine.game().check_game_over());
+    }
+
+    #[test]
+    fn test_clear_board_keeps_the_size() {
+        let mut engine = engine();
+        execute(&mut engine, "boardsize 4").unwrap();
+        execute(&mut engine, "play B a1").unwrap();
+        execute(&mut engine, "clear_board").unwrap();
+        assert_eq!(engine.game().board_size(), 4);
+        assert!(engine.game().history().is_empty());
+    }
+
+    #[test]
+    fn test_known_and_listed_commands() {
+        let mut engine = engine();
+        assert_eq!(execute(&mut engine, "known_command genmove").unwrap(), "true");
+        assert_eq!(execute(&mut engine, "known_command undo").unwrap(), "false");
+        let list = execute(&mut engine, "list_commands").unwrap();
+        assert_eq!(list.lines().count(), COMMANDS.len());
+    }
+
+    #[test]
+    fn test_full_game_against_the_bot() {
+        let mut input = String::from("boardsize 3\n");
+        // Enough genmoves for both sides to fill the board
+        for i in 0..7 {
+            input.push_str(&format!("{} genmove {}\n", i, if i % 2 == 0 { 'B' } else { 'R' }));
+        }
+        let output = answers(&input);
+        assert_eq!(output.len(), 8);
+        assert_eq!(output[0], "=");
+        assert!(output[1].starts_with("=0 "));
+        // A size 3 board is decided within its 6 cells
+        assert_eq!(output[7], "?6 the game is over");
+    }
+}
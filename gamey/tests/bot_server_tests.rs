@@ -1,10 +1,15 @@
+// The HTTP server only exists with the `server` feature
+#![cfg(feature = "server")]
+
 use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
-use gamey::{YBotRegistry, YEN, create_default_state, create_router, state::AppState, RandomBot, MoveResponse, ErrorResponse};
+use gamey::{YBotRegistry, YEN, create_default_state, create_router, state::AppState, RandomBot, MoveResponse, ErrorResponse, VersionsResponse, GameStats, YBot, GameY, Coordinates, SwapAdvisor, AnalysisUpdate, ResignWatch};
 use http_body_util::BodyExt;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tower::ServiceExt;
 
 /// Helper to create a test app with the default state
@@ -41,6 +46,78 @@ async fn test_status_endpoint_returns_ok() {
     assert_eq!(&body[..], b"OK");
 }
 
+// ============================================================================
+// Liveness and readiness probes
+// ============================================================================
+
+#[tokio::test]
+async fn test_readyz_answers_503_until_the_state_is_marked_ready() {
+    let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(RandomBot)));
+    let app = test_app_with_state(state.clone());
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = app.clone().oneshot(get("/readyz")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["ready"], false);
+    assert_eq!(report["components"]["bots"], "ready");
+    assert_eq!(report["components"]["archive"], "disabled");
+    assert_eq!(report["components"]["warm_up"], "pending");
+
+    // The process is alive all along
+    let response = app.clone().oneshot(get("/livez")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    state.readiness.mark_ready();
+    let report = get_json(app, "/readyz").await;
+    assert_eq!(report["ready"], true);
+}
+
+#[tokio::test]
+async fn test_created_state_reports_its_loaded_components() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = gamey::ServerConfig {
+        state_dir: Some(dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let state = gamey::create_state(&config).unwrap();
+    let report = state.readiness.report();
+    assert!(!report.ready);
+    assert_eq!(report.components["ladder"], gamey::ComponentStatus::Ready);
+
+    gamey::health::warm_up(&state);
+    assert!(state.readiness.is_ready());
+}
+
+// ============================================================================
+// Versions endpoint tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_versions_endpoint_lists_supported_versions() {
+    let app = test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/versions")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let versions: VersionsResponse = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(versions.latest, "v1");
+    assert_eq!(versions.versions.len(), 1);
+    assert!(!versions.versions[0].deprecated);
+}
+
 // ============================================================================
 // Choose endpoint tests - Success cases
 // ============================================================================
@@ -103,10 +180,302 @@ async fn test_choose_endpoint_with_partially_filled_board() {
     assert_eq!(move_response.bot_id, "random_bot");
 }
 
+#[tokio::test]
+async fn test_choose_endpoint_with_move_list() {
+    let moves = [(0, 4), (1, 0), (0, 7)];
+    let body = serde_json::json!({
+        "size": 4,
+        "moves": moves.iter().map(|(player, index)| serde_json::json!({"player": player, "index": index})).collect::<Vec<_>>(),
+    });
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+
+    // Applying the same moves and the bot's answer locally gives the echoed hash
+    let mut game = GameY::new(4);
+    for (player, index) in moves {
+        game.add_move(gamey::Movement::Placement {
+            player: gamey::PlayerId::new(player),
+            coords: Coordinates::from_index(index, 4),
+        })
+        .unwrap();
+    }
+    game.add_move(gamey::Movement::Placement {
+        player: gamey::PlayerId::new(1),
+        coords: move_response.coords,
+    })
+    .unwrap();
+    assert_eq!(
+        move_response.position_hash,
+        Some(format!("{:016x}", game.position_hash()))
+    );
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_echoes_the_position_hash_for_yen() {
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+    let mut game = GameY::try_from(yen).unwrap();
+    game.add_move(gamey::Movement::Placement {
+        player: gamey::PlayerId::new(0),
+        coords: move_response.coords,
+    })
+    .unwrap();
+    assert_eq!(
+        move_response.position_hash,
+        Some(format!("{:016x}", game.position_hash()))
+    );
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_includes_the_resulting_board() {
+    // Blue wins by taking (0,1,1)
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "B/B./R.R".to_string());
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/minimax_bot?include=board,eval")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+
+    let mut game = GameY::try_from(yen).unwrap();
+    game.add_move(gamey::Movement::Placement {
+        player: gamey::PlayerId::new(0),
+        coords: move_response.coords,
+    })
+    .unwrap();
+    assert_eq!(move_response.board, Some(YEN::from(&game)));
+    assert_eq!(move_response.status.as_ref(), Some(game.status()));
+    assert_eq!(move_response.wins, Some(true));
+    assert_eq!(move_response.eval, Some(gamey::review::WIN_SCORE));
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_omits_optional_parts_by_default() {
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+    let request = |uri: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_string(&yen).unwrap()))
+            .unwrap()
+    };
+
+    let response = test_app().oneshot(request("/v1/ybot/choose/random_bot")).await.unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    for field in ["board", "status", "wins", "eval"] {
+        assert!(json.get(field).is_none(), "unexpected {}", field);
+    }
+
+    // The random bot cannot evaluate positions, so only the board is added
+    let response = test_app()
+        .oneshot(request("/v1/ybot/choose/random_bot?include=board,eval"))
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+    assert!(move_response.board.is_some());
+    assert_eq!(move_response.wins, Some(false));
+    assert_eq!(move_response.eval, None);
+
+    let response = test_app()
+        .oneshot(request("/v1/ybot/choose/random_bot?include=everything"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("INVALID_INCLUDE"));
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_searches_for_the_asked_player() {
+    // Blue is to move, but Red wins by completing the bottom row at index 4
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "B/B./R.R".to_string());
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/minimax_bot?player=1&include=board")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(move_response.coords, Coordinates::from_index(4, 3));
+    assert_eq!(move_response.board.unwrap().layout(), "B/B./RRR");
+    assert_eq!(move_response.wins, Some(true));
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_rejects_other_players_for_bots_that_cannot_play_out_of_turn() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot))));
+    let yen = YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string());
+    let choose = |player: u32| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/v1/ybot/choose/random_bot?player={}", player))
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_string(&yen).unwrap()))
+            .unwrap()
+    };
+
+    let (status, error) = send(&app, choose(0)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error["code"], "NOT_TO_MOVE");
+    assert_eq!(error["details"], serde_json::json!({"side_to_move": 1, "player": 0}));
+
+    // Asking for the side to move is the same as not asking
+    let (status, chosen) = send(&app, choose(1)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(chosen["coords"], serde_json::to_value(Coordinates::from_index(1, 3)).unwrap());
+
+    let (status, error) = send(&app, choose(2)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "INVALID_PLAYER");
+}
+
 // ============================================================================
 // Choose endpoint tests - Error cases
 // ============================================================================
 
+#[tokio::test]
+async fn test_choose_endpoint_rejects_an_illegal_move_in_the_list() {
+    // The third move takes the cell Red has just played
+    let body = serde_json::json!({
+        "size": 3,
+        "moves": [{"player": 0, "index": 0}, {"player": 1, "index": 1}, {"player": 0, "index": 1}],
+    });
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("INVALID_MOVE"));
+    assert_eq!(error.details.as_deref(), Some(&serde_json::json!({"move_number": 3})));
+    assert!(error.message.contains("occupied"), "{}", error.message);
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_rejects_a_player_moving_twice() {
+    let body = serde_json::json!({
+        "size": 3,
+        "moves": [{"player": 0, "index": 0}, {"player": 1, "index": 1}, {"player": 1, "index": 2}],
+    });
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("INVALID_MOVE"));
+    assert_eq!(error.details.as_deref(), Some(&serde_json::json!({"move_number": 3})));
+}
+
+#[tokio::test]
+async fn test_choose_endpoint_rejects_a_move_list_with_a_bad_size() {
+    let body = serde_json::json!({"size": 100_000, "moves": []});
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("INVALID_SIZE"));
+}
+
+#[tokio::test]
+async fn test_bridge_bot_defends_its_bridge() {
+    // Red has just taken (1,1,2), one carrier of Blue's bridge (2,1,1)-(0,2,2)
+    let yen = YEN::new(5, 0, vec!['B', 'R'], "R/../.B./.R../..B..".to_string());
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/bridge_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(move_response.bot_id, "bridge_bot");
+    assert_eq!(move_response.coords, Coordinates::new(1, 2, 1));
+}
+
 #[tokio::test]
 async fn test_choose_endpoint_with_invalid_api_version() {
     let app = test_app();
@@ -125,7 +494,7 @@ async fn test_choose_endpoint_with_invalid_api_version() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK); // Axum returns 200 with error JSON
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
@@ -134,6 +503,35 @@ async fn test_choose_endpoint_with_invalid_api_version() {
     assert_eq!(error_response.api_version, Some("v2".to_string()));
 }
 
+#[tokio::test]
+async fn test_choose_endpoint_with_unknown_version_lists_supported_versions() {
+    let app = test_app();
+
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v9/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(error_response.code.as_deref(), Some("BAD_VERSION"));
+    let details: VersionsResponse = serde_json::from_value(*error_response.details.unwrap()).unwrap();
+    assert_eq!(details.latest, "v1");
+    assert!(details.versions.iter().any(|v| v.name == "v1"));
+}
+
 #[tokio::test]
 async fn test_choose_endpoint_with_unknown_bot() {
     let app = test_app();
@@ -152,7 +550,7 @@ async fn test_choose_endpoint_with_unknown_bot() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
@@ -160,6 +558,35 @@ async fn test_choose_endpoint_with_unknown_bot() {
     assert!(error_response.message.contains("Bot not found"));
     assert!(error_response.message.contains("unknown_bot"));
     assert_eq!(error_response.bot_id, Some("unknown_bot".to_string()));
+    assert_eq!(error_response.code.as_deref(), Some("UNKNOWN_BOT"));
+    let available = &error_response.details.unwrap()["available_bots"];
+    assert_eq!(
+        *available,
+        serde_json::json!(["bridge_bot", "mcts_bot", "minimax_bot", "random_bot", "smart_random_bot"])
+    );
+}
+
+#[tokio::test]
+async fn test_choose_matches_bot_ids_ignoring_case() {
+    let reply = post_json(test_app(), "/v1/ybot/choose/Random_Bot", empty_board(3)).await;
+    assert_eq!(reply["bot_id"], "Random_Bot");
+}
+
+#[tokio::test]
+async fn test_choose_resolves_configured_aliases() {
+    let mut config = gamey::ServerConfig::default();
+    config.bot_aliases.insert("easy".to_string(), "random_bot".to_string());
+    let app = create_router(gamey::create_state(&config).unwrap());
+    let reply = post_json(app.clone(), "/v1/ybot/choose/EASY", empty_board(3)).await;
+    assert!(reply["coords"].is_object());
+    let reply = post_json(app, "/v1/ybot/choose/random_bot", empty_board(3)).await;
+    assert_eq!(reply["bot_id"], "random_bot");
+
+    config.bot_aliases.insert("hard".to_string(), "deep_blue".to_string());
+    let Err(err) = gamey::create_state(&config) else {
+        panic!("an alias of an unknown bot was accepted");
+    };
+    assert!(err.to_string().contains("Alias 'hard' names unknown bot 'deep_blue'"), "{}", err);
 }
 
 #[tokio::test]
@@ -253,7 +680,7 @@ async fn test_choose_with_empty_bot_registry() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let error_response: ErrorResponse = serde_json::from_slice(&body).unwrap();
@@ -262,59 +689,2306 @@ async fn test_choose_with_empty_bot_registry() {
 }
 
 // ============================================================================
-// Route not found tests
+// Game and time control tests
 // ============================================================================
 
-#[tokio::test]
-async fn test_unknown_route_returns_404() {
-    let app = test_app();
-
+/// Helper to send a JSON POST request and parse the JSON response.
+async fn post_json(app: axum::Router, uri: &str, body: serde_json::Value) -> serde_json::Value {
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/unknown/route")
-                .body(Body::empty())
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
                 .unwrap(),
         )
         .await
         .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
 
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+/// Helper to send a GET request and parse the JSON response.
+async fn get_json(app: axum::Router, uri: &str) -> serde_json::Value {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
 }
 
 #[tokio::test]
-async fn test_wrong_method_on_status_endpoint() {
+async fn test_game_without_time_control_has_no_clock() {
     let app = test_app();
 
-    let response = app
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/status")
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let game = get_json(app, "/game").await;
 
-    // POST to a GET-only endpoint should return 405 Method Not Allowed
-    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(game["board"]["size"], 5);
+    assert!(game["winner"].is_null());
+    assert!(game["clock"].is_null());
 }
 
 #[tokio::test]
-async fn test_get_on_choose_endpoint_returns_method_not_allowed() {
+async fn test_reset_without_body_keeps_default_size() {
     let app = test_app();
 
     let response = app
         .oneshot(
             Request::builder()
-                .method("GET")
-                .uri("/v1/ybot/choose/random_bot")
+                .method("POST")
+                .uri("/reset")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let reset: gamey::GameStateResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(reset.board.size(), 5);
+    assert_eq!(reset.status, gamey::GameStatusDto::Ongoing { next_player: 0 });
+}
+
+#[tokio::test]
+async fn test_reset_with_time_control_starts_clock() {
+    let app = test_app();
+
+    post_json(
+        app.clone(),
+        "/reset",
+        serde_json::json!({"size": 7, "time_control": {"initial_s": 300, "increment_s": 5}}),
+    )
+    .await;
+    let game = get_json(app, "/game").await;
+
+    assert_eq!(game["board"]["size"], 7);
+    assert_eq!(game["clock"]["running"], 0);
+    let remaining = game["clock"]["remaining_ms"].as_array().unwrap();
+    assert_eq!(remaining.len(), 2);
+    assert!(remaining[0].as_u64().unwrap() <= 300_000);
+    assert_eq!(remaining[1], 300_000);
+}
+
+#[tokio::test]
+async fn test_game_includes_default_players() {
+    let game = get_json(test_app(), "/game").await;
+
+    assert_eq!(game["players"][0]["name"], "Player 0");
+    assert_eq!(game["players"][1]["kind"], "human");
+}
+
+#[tokio::test]
+async fn test_reset_with_player_names() {
+    let app = test_app();
+
+    let reset = post_json(
+        app.clone(),
+        "/reset",
+        serde_json::json!({"players": [
+            {"id": 0, "name": "Alice", "kind": "human"},
+            {"id": 1, "name": "Random", "kind": {"bot": "random_bot"}}
+        ]}),
+    )
+    .await;
+    assert_eq!(reset["board"]["metadata"]["players"][0]["name"], "Alice");
+    assert_eq!(reset["players"][1]["name"], "Random");
+
+    let game = get_json(app, "/game").await;
+    assert_eq!(game["players"][0]["name"], "Alice");
+    assert_eq!(game["players"][1]["kind"]["bot"], "random_bot");
+}
+
+#[tokio::test]
+async fn test_token_round_trip_through_the_server() {
+    let app = test_app();
+    post_json(app.clone(), "/execute-move", serde_json::json!({"index": 4})).await;
+    let before = get_json(app.clone(), "/game").await;
+    let token = get_json(app.clone(), "/game/token").await["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    post_json(app.clone(), "/reset", serde_json::json!({})).await;
+    let loaded = post_json(app.clone(), "/load-token", serde_json::json!({"token": token})).await;
+
+    assert_eq!(loaded["layout"], before["board"]["layout"]);
+    let after = get_json(app, "/game").await;
+    assert_eq!(after["board"]["layout"], before["board"]["layout"]);
+}
+
+#[tokio::test]
+async fn test_load_invalid_token_is_rejected() {
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/load-token")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"token": "AAAA"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("INVALID_TOKEN"));
+}
+
+#[tokio::test]
+async fn test_move_response_includes_remaining_times() {
+    let app = test_app();
+
+    post_json(
+        app.clone(),
+        "/reset",
+        serde_json::json!({"time_control": {"initial_s": 300, "increment_s": 5}}),
+    )
+    .await;
+    let result = post_json(app, "/execute-move", serde_json::json!({"index": 0})).await;
+
+    let remaining = result["clock"]["remaining_ms"].as_array().unwrap();
+    // Both players moved once and received the increment
+    assert!(remaining[0].as_u64().unwrap() > 300_000);
+    assert!(remaining[1].as_u64().unwrap() > 300_000);
+    assert_eq!(result["clock"]["running"], 0);
+}
+
+#[tokio::test]
+async fn test_reset_and_create_reject_invalid_board_sizes() {
+    let app = test_app();
+    for (uri, size) in [("/reset", 0), ("/reset", 101), ("/games", 0), ("/games", 101)] {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::json!({"size": size}).to_string()))
+            .unwrap();
+        let (status, error) = send(&app, request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{} {}", uri, size);
+        assert_eq!(error["code"], "INVALID_SIZE");
+        assert_eq!(error["message"], format!("Invalid board size {}, expected 1 to 100", size));
+    }
+    // The server game keeps its size
+    assert_eq!(get_json(app, "/game").await["board"]["size"], 5);
+}
+
+#[tokio::test]
+async fn test_reset_rejects_a_time_control_that_overflows() {
+    let app = test_app();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/reset")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            serde_json::json!({"size": 3, "time_control": {"initial_s": 60, "increment_s": u64::MAX}}).to_string(),
+        ))
+        .unwrap();
+    let (status, error) = send(&app, request).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "INVALID_TIME_CONTROL");
+
+    // The game is untouched and moves are still answered
+    let game = get_json(app.clone(), "/game").await;
+    assert_eq!(game["board"]["size"], 5);
+    assert_eq!(game["clock"], serde_json::Value::Null);
+    let result = post_json(app, "/execute-move", serde_json::json!({"index": 0})).await;
+    assert!(result["bot_move"].is_object(), "{}", result);
+}
+
+#[tokio::test]
+async fn test_move_off_the_board_is_rejected() {
+    let app = test_app();
+    let request = Request::builder()
+        .method("POST")
+        .uri("/execute-move")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::json!({"index": 100_000}).to_string()))
+        .unwrap();
+
+    let (status, error) = send(&app, request).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "INVALID_MOVE");
+    assert!(error["message"].as_str().unwrap().contains("Cell index 100000"), "{}", error);
+    // The game is untouched
+    assert_eq!(get_json(app, "/game").await["board"]["layout"], "./../.../..../.....");
+}
+
+#[tokio::test]
+async fn test_human_without_time_loses_on_time() {
+    let app = test_app();
+
+    post_json(
+        app.clone(),
+        "/reset",
+        serde_json::json!({"time_control": {"initial_s": 0, "increment_s": 0}}),
+    )
+    .await;
+    let result = post_json(app, "/execute-move", serde_json::json!({"index": 0})).await;
+
+    assert_eq!(result["winner"], 1);
+    assert_eq!(result["lost_on_time"], 0);
+    assert_eq!(result["clock"]["flagged"], 0);
+    // The move was not applied
+    assert_eq!(result["board"]["layout"], "./../.../..../.....");
+}
+
+// ============================================================================
+// Swap rule tests
+// ============================================================================
+
+fn swap_advisor_app() -> axum::Router {
+    let bots = YBotRegistry::new().with_bot(Arc::new(SwapAdvisor::new(RandomBot, 1.0)));
+    test_app_with_state(AppState::new(bots))
+}
+
+#[tokio::test]
+async fn test_bot_swaps_center_opening() {
+    let app = swap_advisor_app();
+
+    // Index 4 is the cell (2, 1, 1), next to the center of a size 5 board
+    let result = post_json(app, "/execute-move", serde_json::json!({"index": 4})).await;
+
+    assert_eq!(result["swapped"], true);
+    assert_eq!(result["board"]["layout"], "./../.B./..../.....");
+}
+
+#[tokio::test]
+async fn test_bot_does_not_swap_corner_opening() {
+    let app = swap_advisor_app();
+
+    let result = post_json(app, "/execute-move", serde_json::json!({"index": 0})).await;
+
+    assert_eq!(result["swapped"], false);
+    let layout = result["board"]["layout"].as_str().unwrap();
+    assert_eq!(layout.matches('R').count(), 1);
+}
+
+// ============================================================================
+// Statistics tests
+// ============================================================================
+
+/// A deterministic bot that always plays the first available cell.
+///
+/// It is registered as "random_bot" because that is the bot used by `/execute-move`.
+struct FirstCellBot;
+
+impl YBot for FirstCellBot {
+    fn name(&self) -> &str {
+        "random_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let idx = *board.available_cells().iter().min()?;
+        Some(Coordinates::from_index(idx, board.board_size()))
+    }
+}
+
+#[tokio::test]
+async fn test_stats_after_two_quick_games() {
+    let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot)));
+    let app = test_app_with_state(state);
+
+    // Game 1: size 1, the human wins with the only move
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 1})).await;
+    let result = post_json(app.clone(), "/execute-move", serde_json::json!({"index": 0})).await;
+    assert_eq!(result["winner"], 0);
+
+    // Game 2: size 3, the bot connects the three sides with cells 0, 1 and 3
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 3})).await;
+    for idx in [5, 4, 2] {
+        post_json(app.clone(), "/execute-move", serde_json::json!({"index": idx})).await;
+    }
+
+    let stats: GameStats = serde_json::from_value(get_json(app, "/stats").await).unwrap();
+    assert_eq!(stats.games_started, 2);
+    assert_eq!(stats.games_finished, 2);
+    assert_eq!(stats.wins_by_player, [1, 1]);
+    assert_eq!(stats.wins_by_bot.get("random_bot"), Some(&1));
+    assert_eq!(stats.resignations, 0);
+    assert_eq!(stats.average_game_length, 3.5);
+}
+
+/// [`FirstCellBot`] registered under another name.
+struct RenamedFirstCellBot(&'static str);
+
+impl YBot for RenamedFirstCellBot {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        FirstCellBot.choose_move(board)
+    }
+}
+
+#[tokio::test]
+async fn test_stats_credit_the_bot_that_answered() {
+    let registry = YBotRegistry::new()
+        .with_bot(Arc::new(FirstCellBot))
+        .with_bot(Arc::new(RenamedFirstCellBot("first_cell_bot")));
+    let app = test_app_with_state(AppState::new(registry));
+
+    // The bot connects the three sides with cells 0, 1 and 3
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 3})).await;
+    for idx in [5, 4, 2] {
+        post_json(app.clone(), "/execute-move", serde_json::json!({"index": idx, "bot": "first_cell_bot"})).await;
+    }
+
+    let stats: GameStats = serde_json::from_value(get_json(app, "/stats").await).unwrap();
+    assert_eq!(stats.wins_by_player, [0, 1]);
+    assert_eq!(stats.wins_by_bot.get("first_cell_bot"), Some(&1));
+    assert_eq!(stats.wins_by_bot.get("random_bot"), None);
+}
+
+#[tokio::test]
+async fn test_reset_of_started_game_counts_as_abandoned() {
+    let app = test_app();
+
+    post_json(app.clone(), "/execute-move", serde_json::json!({"index": 0})).await;
+    post_json(app.clone(), "/reset", serde_json::json!({})).await;
+
+    let stats: GameStats = serde_json::from_value(get_json(app, "/stats").await).unwrap();
+    assert_eq!(stats.games_started, 1);
+    assert_eq!(stats.games_abandoned, 1);
+    assert_eq!(stats.games_finished, 0);
+}
+
+// ============================================================================
+// Concurrent moves
+// ============================================================================
+
+/// Plays like [`FirstCellBot`] but takes a while to think.
+struct ThinkingBot;
+
+impl YBot for ThinkingBot {
+    fn name(&self) -> &str {
+        "random_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        std::thread::sleep(Duration::from_millis(200));
+        FirstCellBot.choose_move(board)
+    }
+}
+
+fn move_request(uri: &str, body: serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_concurrent_moves_while_the_bot_thinks() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(ThinkingBot))));
+
+    let (first, second) = tokio::join!(
+        app.clone().oneshot(move_request("/execute-move", serde_json::json!({"index": 4}))),
+        app.clone().oneshot(move_request("/execute-move", serde_json::json!({"index": 7}))),
+    );
+    let mut statuses = [first.unwrap(), second.unwrap()].map(|response| response.status());
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::CONFLICT]);
+
+    // Only the accepted move and the bot's answer are on the board
+    let game = get_json(app, "/game").await;
+    let layout = game["board"]["layout"].as_str().unwrap();
+    assert_eq!(layout.matches('B').count(), 1);
+    assert_eq!(layout.matches('R').count(), 1);
+    assert_eq!(game["board"]["turn"], 0);
+}
+
+#[tokio::test]
+async fn test_reset_while_the_bot_thinks_discards_its_move() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(ThinkingBot))));
+
+    let (moved, _) = tokio::join!(
+        app.clone().oneshot(move_request("/execute-move", serde_json::json!({"index": 4}))),
+        post_json(app.clone(), "/reset", serde_json::json!({"size": 4})),
+    );
+    let response = moved.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("STATE_CHANGED"));
+
+    let game = get_json(app, "/game").await;
+    assert_eq!(game["board"]["layout"], "./../.../....");
+}
+
+#[tokio::test]
+async fn test_execute_move_reports_both_moves() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot))));
+    let reply = post_json(app, "/execute-move", serde_json::json!({"index": 4})).await;
+    let reply: gamey::MoveResultResponse = serde_json::from_value(reply).unwrap();
+
+    assert_eq!(reply.last_move, Some(gamey::MoveDto { player: 0, index: Some(4), action: None }));
+    assert_eq!(reply.bot_move.as_ref().map(|m| m.player), Some(1));
+    assert_eq!(reply.status, gamey::GameStatusDto::Ongoing { next_player: 0 });
+    assert_eq!(reply.winner, None);
+    assert!(!reply.swapped && !reply.resigned);
+}
+
+/// Plays the last free cell, so its moves can be told from the random bot's.
+struct LastCellBot;
+
+impl YBot for LastCellBot {
+    fn name(&self) -> &str {
+        "last_cell_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let idx = *board.available_cells().iter().max()?;
+        Some(Coordinates::from_index(idx, board.board_size()))
+    }
+}
+
+/// A state with the random bot as default and the last cell bot as the `hard` preset.
+fn preset_state() -> AppState {
+    let config = gamey::ServerConfig {
+        difficulty_presets: [("hard".to_string(), gamey::DifficultyPreset::new("last_cell_bot").with_time_ms(100))]
+            .into_iter()
+            .collect(),
+        ..gamey::ServerConfig::default()
+    };
+    let bots = YBotRegistry::new().with_bot(Arc::new(RandomBot)).with_bot(Arc::new(LastCellBot));
+    AppState::from_config(bots, config)
+}
+
+#[tokio::test]
+async fn test_execute_move_with_an_explicit_bot() {
+    let app = test_app_with_state(preset_state());
+    let reply = post_json(app, "/execute-move", serde_json::json!({"index": 4, "bot": "Last_Cell_Bot"})).await;
+    let reply: gamey::MoveResultResponse = serde_json::from_value(reply).unwrap();
+    assert_eq!(reply.bot.as_deref(), Some("last_cell_bot"));
+    assert_eq!(reply.bot_move.and_then(|m| m.index), Some(14));
+}
+
+#[tokio::test]
+async fn test_execute_move_with_a_difficulty_preset() {
+    let app = test_app_with_state(preset_state());
+    let reply = post_json(app, "/execute-move", serde_json::json!({"index": 4, "bot": "HARD"})).await;
+    let reply: gamey::MoveResultResponse = serde_json::from_value(reply).unwrap();
+    assert_eq!(reply.bot.as_deref(), Some("last_cell_bot"));
+    assert_eq!(reply.bot_move.and_then(|m| m.index), Some(14));
+}
+
+#[tokio::test]
+async fn test_execute_move_with_an_unknown_bot_is_rejected() {
+    let app = test_app_with_state(preset_state());
+    let response = app
+        .clone()
+        .oneshot(move_request("/execute-move", serde_json::json!({"index": 4, "bot": "grandmaster"})))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("UNKNOWN_BOT"));
+    assert!(error.message.contains("hard"), "{}", error.message);
+    assert_eq!(error.details.unwrap()["presets"], serde_json::json!(["hard"]));
+
+    // The human move was not played either
+    let game = get_json(app, "/game").await;
+    assert_eq!(game["board"]["layout"], "./../.../..../.....");
+}
+
+#[tokio::test]
+async fn test_execute_move_falls_back_to_the_game_bot_then_the_default() {
+    let app = test_app_with_state(preset_state());
+    let reply = post_json(app.clone(), "/execute-move", serde_json::json!({"index": 4})).await;
+    assert_eq!(reply["bot"], "random_bot");
+
+    // A game reset with a bot as player 1 is answered by that bot
+    post_json(
+        app.clone(),
+        "/reset",
+        serde_json::json!({"players": [
+            {"id": 0, "name": "Alice", "kind": "human"},
+            {"id": 1, "name": "Hard", "kind": {"bot": "hard"}}
+        ]}),
+    )
+    .await;
+    let reply = post_json(app, "/execute-move", serde_json::json!({"index": 4})).await;
+    assert_eq!(reply["bot"], "last_cell_bot");
+    assert_eq!(reply["bot_move"]["index"], 14);
+}
+
+#[tokio::test]
+async fn test_replay_flags_an_illegal_move_at_ply_4() {
+    let app = test_app();
+    let report = post_json(
+        app,
+        "/replay",
+        serde_json::json!({"size": 4, "moves": [0, 5, 2, 5, 7], "bot": "random_bot", "seed": 42}),
+    )
+    .await;
+    // The random bot cannot be seeded, so only the legality of the moves is checked
+    assert_eq!(report["bot_id"], "random_bot");
+    assert_eq!(report["seeded"], false);
+    assert_eq!(report["first_divergence"], 4);
+    let plies = report["plies"].as_array().unwrap();
+    assert_eq!(plies.len(), 4);
+    assert_eq!(plies[3]["legal"], false);
+    assert!(plies[3]["error"].as_str().unwrap().starts_with("Move 4"));
+    assert_eq!(plies[2]["status"], serde_json::json!({"state": "ongoing", "next_player": 1}));
+    assert_eq!(plies[1]["by_bot"], true);
+    assert!(plies[1].get("reproduced").is_none());
+}
+
+#[tokio::test]
+async fn test_retried_move_gets_the_same_reply() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot))));
+
+    let send = || async {
+        let response = app
+            .clone()
+            .oneshot(move_request("/execute-move", serde_json::json!({"index": 4, "seq": 1})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        response.into_body().collect().await.unwrap().to_bytes()
+    };
+    let first = send().await;
+    let retry = send().await;
+    assert_eq!(first, retry);
+
+    // The move was played once and answered once
+    let game = get_json(app.clone(), "/game").await;
+    let layout = game["board"]["layout"].as_str().unwrap().to_string();
+    assert_eq!(layout.matches('B').count(), 1);
+    assert_eq!(layout.matches('R').count(), 1);
+
+    // A new number plays again, and an unknown number below the last one is stale
+    post_json(app.clone(), "/execute-move", serde_json::json!({"index": 7, "seq": 2})).await;
+    let response = app
+        .clone()
+        .oneshot(move_request("/execute-move", serde_json::json!({"index": 8, "seq": 0})))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("STALE_MOVE"));
+    assert_eq!(error.details.as_deref(), Some(&serde_json::json!({"last_seq": 2})));
+
+    // A new game starts numbering again
+    post_json(app.clone(), "/reset", serde_json::json!({})).await;
+    let replayed = post_json(app, "/execute-move", serde_json::json!({"index": 4, "seq": 1})).await;
+    assert_eq!(replayed["board"]["layout"], "R/../.B./..../.....");
+}
+
+#[tokio::test]
+async fn test_concurrent_retries_wait_for_the_same_reply() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(ThinkingBot))));
+
+    let send = || async {
+        let response = app
+            .clone()
+            .oneshot(move_request("/execute-move", serde_json::json!({"index": 4, "seq": 1})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        response.into_body().collect().await.unwrap().to_bytes()
+    };
+    let (first, retry) = tokio::join!(send(), send());
+    assert_eq!(first, retry);
+
+    // The move was played once and answered once
+    let game = get_json(app, "/game").await;
+    let layout = game["board"]["layout"].as_str().unwrap();
+    assert_eq!(layout.matches('B').count(), 1);
+    assert_eq!(layout.matches('R').count(), 1);
+}
+
+// ============================================================================
+// Hosted games with seats
+// ============================================================================
+
+fn seat_request(method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> Request<Body> {
+    let mut builder = Request::builder().method(method).uri(uri).header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {}", token));
+    }
+    builder
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap()
+}
+
+async fn send(app: &axum::Router, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+/// Creates a two-human game of `size` and returns its id and seat tokens.
+async fn create_human_game(app: &axum::Router, size: u32) -> (u64, String, String) {
+    let created = post_json(app.clone(), "/games", serde_json::json!({"size": size})).await;
+    let tokens: Vec<String> = serde_json::from_value(created["tokens"].clone()).unwrap();
+    (created["game"]["id"].as_u64().unwrap(), tokens[0].clone(), tokens[1].clone())
+}
+
+#[tokio::test]
+async fn test_hosted_game_rejects_wrong_token_and_out_of_turn_moves() {
+    let app = test_app();
+    let (id, first, second) = create_human_game(&app, 3).await;
+    let uri = format!("/games/{}/move", id);
+
+    let (status, error) = send(&app, seat_request("POST", &uri, None, Some(serde_json::json!({"index": 0})))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(error["code"], "UNAUTHORIZED");
+
+    let (status, _) = send(&app, seat_request("POST", &uri, Some("forged"), Some(serde_json::json!({"index": 0})))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, error) = send(&app, seat_request("POST", &uri, Some(&second), Some(serde_json::json!({"index": 0})))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(error["code"], "NOT_YOUR_TURN");
+
+    let (status, state) = send(&app, seat_request("POST", &uri, Some(&first), Some(serde_json::json!({"index": 0})))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(state["your_seat"], 0);
+    assert_eq!(state["your_turn"], false);
+    // No bot answers for a human seat
+    assert_eq!(state["board"]["layout"], "B/../...");
+
+    let (status, _) = send(&app, seat_request("POST", &uri, Some(&first), Some(serde_json::json!({"index": 1})))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, seat_request("GET", "/games/999", None, None)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_full_two_human_game() {
+    let app = test_app();
+    let (id, first, second) = create_human_game(&app, 2).await;
+    let uri = format!("/games/{}", id);
+
+    let (_, state) = send(&app, seat_request("GET", &uri, Some(&second), None)).await;
+    assert_eq!(state["seats"][0]["taken"], false);
+    assert_eq!(state["seats"][1]["taken"], true);
+    assert_eq!(state["seats"][1]["kind"], "human");
+    assert_eq!(state["your_turn"], false);
+
+    // The second player waits for the first move while it is played
+    let wait = send(&app, seat_request("GET", &format!("{}/wait?after=0", uri), Some(&second), None));
+    let play = send(
+        &app,
+        seat_request("POST", &format!("{}/move", uri), Some(&first), Some(serde_json::json!({"index": 0}))),
+    );
+    let ((status, waited), _) = tokio::join!(wait, play);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(waited["moves"], 1);
+    assert_eq!(waited["your_turn"], true);
+
+    for (token, index) in [(&second, 1), (&first, 2)] {
+        let (status, _) = send(
+            &app,
+            seat_request("POST", &format!("{}/move", uri), Some(token), Some(serde_json::json!({"index": index}))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, state) = send(&app, seat_request("GET", &uri, None, None)).await;
+    assert_eq!(state["winner"], 0);
+    assert_eq!(state["moves"], 3);
+    assert_eq!(state["board"]["layout"], "B/RB");
+    assert_eq!(state["your_seat"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn test_hosted_game_with_a_bot_seat() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot))));
+    let created = post_json(
+        app.clone(),
+        "/games",
+        serde_json::json!({"size": 3, "seats": [{"kind": "bot", "bot_id": "random_bot"}, {"kind": "human"}]}),
+    )
+    .await;
+    // The bot opens before the reply, and only the human seat gets a token
+    assert_eq!(created["tokens"], serde_json::json!([null, created["tokens"][1]]));
+    assert_eq!(created["game"]["board"]["layout"], "B/../...");
+
+    let token = created["tokens"][1].as_str().unwrap();
+    let id = created["game"]["id"].as_u64().unwrap();
+    let (status, state) = send(
+        &app,
+        seat_request("POST", &format!("/games/{}/move", id), Some(token), Some(serde_json::json!({"index": 5}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(state["board"]["layout"], "B/B./..R");
+    assert_eq!(state["your_turn"], true);
+
+    let (status, error) = send(
+        &app,
+        seat_request("POST", "/games", None, Some(serde_json::json!({"seats": [{"kind": "bot", "bot_id": "nobody"}, {"kind": "human"}]}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["code"], "UNKNOWN_BOT");
+}
+
+#[tokio::test]
+async fn test_hosted_game_with_opening_options() {
+    let app = test_app();
+    let created = post_json(
+        app.clone(),
+        "/games",
+        serde_json::json!({"size": 7, "random_opening_stones": 3, "forbid_center_opening": 0}),
+    )
+    .await;
+    let board = &created["game"]["board"];
+    let stones = board["layout"].as_str().unwrap().chars().filter(|c| *c == 'B' || *c == 'R').count();
+    assert_eq!(stones, 3);
+    assert_eq!(created["game"]["moves"], 0);
+
+    // The seat to move cannot open on the center, (2, 2, 2) on a board of size 7
+    let turn = board["turn"].as_u64().unwrap() as usize;
+    let token = created["tokens"][turn].as_str().unwrap();
+    let uri = format!("/games/{}/move", created["game"]["id"]);
+    let (status, error) = send(&app, seat_request("POST", &uri, Some(token), Some(serde_json::json!({"index": 12})))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "FORBIDDEN_OPENING");
+}
+
+#[tokio::test]
+async fn test_hosted_game_rejects_the_swap_rule_with_other_opening_options() {
+    let app = test_app();
+    let (status, error) = send(
+        &app,
+        seat_request("POST", "/games", None, Some(serde_json::json!({"swap_rule": true, "random_first_player": true}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "INVALID_OPENING");
+    assert!(error["message"].as_str().unwrap().contains("swap rule"), "{}", error);
+
+    let (status, error) = send(
+        &app,
+        seat_request("POST", "/games", None, Some(serde_json::json!({"size": 3, "random_opening_stones": 3}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "INVALID_OPENING");
+}
+
+// ============================================================================
+// Ladder
+// ============================================================================
+
+/// The ladder entry of `name`, as `(rating, games, wins, losses)`.
+fn ladder_entry(ladder: &serde_json::Value, name: &str) -> (f64, u64, u64, u64) {
+    let entry = ladder["ratings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["name"] == name)
+        .unwrap_or_else(|| panic!("{} is not on the ladder: {}", name, ladder));
+    (
+        entry["rating"].as_f64().unwrap(),
+        entry["games"].as_u64().unwrap(),
+        entry["wins"].as_u64().unwrap(),
+        entry["losses"].as_u64().unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_ladder_rates_the_finished_games() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot))));
+    let alice = serde_json::json!([{"id": 0, "name": "alice", "kind": "human"}]);
+    assert_eq!(get_json(app.clone(), "/ladder").await["ratings"], serde_json::json!([]));
+
+    // The server game: alice wins on a board of size 1
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 1, "players": alice})).await;
+    post_json(app.clone(), "/execute-move", serde_json::json!({"index": 0})).await;
+    // An unnamed human is not rated
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 1})).await;
+    post_json(app.clone(), "/execute-move", serde_json::json!({"index": 0})).await;
+    // A hosted game against the bot seat
+    let created = post_json(
+        app.clone(),
+        "/games",
+        serde_json::json!({"size": 1, "seats": [{"kind": "human"}, {"kind": "bot", "bot_id": "random_bot"}], "names": ["alice"]}),
+    )
+    .await;
+    let id = created["game"]["id"].as_u64().unwrap();
+    let token = created["tokens"][0].as_str().unwrap();
+    let (status, _) = send(
+        &app,
+        seat_request("POST", &format!("/games/{}/move", id), Some(token), Some(serde_json::json!({"index": 0}))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let ladder = get_json(app.clone(), "/ladder").await;
+    assert_eq!(ladder["k_factor"], 32.0);
+    assert_eq!(ladder["ratings"].as_array().unwrap().len(), 2);
+    assert_eq!(ladder["ratings"][0]["name"], "alice");
+    let (alice_before, games, wins, losses) = ladder_entry(&ladder, "alice");
+    assert!(alice_before > 1500.0);
+    assert_eq!((games, wins, losses), (2, 2, 0));
+    let (bot_before, games, _, losses) = ladder_entry(&ladder, "random_bot");
+    assert!(bot_before < 1500.0);
+    assert_eq!((games, losses), (2, 2));
+
+    // The bot connects the three sides with cells 0, 1 and 3
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 3, "players": alice})).await;
+    for idx in [5, 4, 2] {
+        post_json(app.clone(), "/execute-move", serde_json::json!({"index": idx})).await;
+    }
+    let ladder = get_json(app, "/ladder").await;
+    let (alice_after, _, _, losses) = ladder_entry(&ladder, "alice");
+    let (bot_after, _, wins, _) = ladder_entry(&ladder, "random_bot");
+    assert!(alice_after < alice_before);
+    assert!(bot_after > bot_before);
+    assert_eq!((losses, wins), (1, 1));
+}
+
+#[tokio::test]
+async fn test_ladder_rates_the_bot_that_answered() {
+    let app = test_app();
+    let alice = serde_json::json!([{"id": 0, "name": "alice", "kind": "human"}]);
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 1, "players": alice})).await;
+    let reply = post_json(app.clone(), "/execute-move", serde_json::json!({"index": 0, "bot": "minimax_bot"})).await;
+    assert_eq!(reply["board"]["metadata"]["players"][1]["kind"]["bot"], "minimax_bot");
+
+    let ladder = get_json(app, "/ladder").await;
+    assert_eq!(ladder["ratings"].as_array().unwrap().len(), 2);
+    let (rating, games, _, losses) = ladder_entry(&ladder, "minimax_bot");
+    assert!(rating < 1500.0);
+    assert_eq!((games, losses), (1, 1));
+    assert!(ladder["ratings"].as_array().unwrap().iter().all(|entry| entry["name"] != "random_bot"));
+}
+
+// ============================================================================
+// Batch choose endpoint
+// ============================================================================
+
+#[tokio::test]
+async fn test_choose_batch_answers_valid_positions_and_flags_a_malformed_one() {
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(FirstCellBot))));
+    let batch = serde_json::json!([
+        {"size": 3, "turn": 0, "players": ["B", "R"], "layout": "./../..."},
+        {"size": 3, "turn": 0, "players": ["B", "R"], "layout": "B/R./.."},
+        {"size": 3, "moves": [{"player": 0, "index": 0}]},
+    ]);
+    let response = post_json(app.clone(), "/v1/ybot/choose-batch/random_bot", batch).await;
+    assert_eq!(response["bot_id"], "random_bot");
+    let results = response["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["index"], 0);
+    assert!(results[1]["error"]["message"].as_str().unwrap().contains("Invalid YEN"), "{}", results[1]);
+    assert_eq!(results[2]["index"], 1);
+
+    // Anything that is not a position is reported inline as well
+    let response = post_json(app.clone(), "/v1/ybot/choose-batch/random_bot", serde_json::json!([{"size": 3}])).await;
+    assert_eq!(response["results"][0]["error"]["code"], "INVALID_POSITION");
+
+    let too_many = serde_json::Value::Array(vec![serde_json::json!({"size": 1, "moves": []}); 257]);
+    let (status, error) = send(&app, seat_request("POST", "/v1/ybot/choose-batch/random_bot", None, Some(too_many))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "BATCH_TOO_LARGE");
+}
+
+// ============================================================================
+// Search bots and metrics
+// ============================================================================
+
+#[tokio::test]
+async fn test_minimax_bot_fills_shared_transposition_table() {
+    let state = create_default_state();
+    let yen = serde_json::to_value(YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string())).unwrap();
+
+    let first = post_json(test_app_with_state(state.clone()), "/v1/ybot/choose/minimax_bot", yen.clone()).await;
+    assert_eq!(first["bot_id"], "minimax_bot");
+    let after_first = get_json(test_app_with_state(state.clone()), "/metrics").await;
+    let stores = after_first["transposition_table"]["stores"].as_u64().unwrap();
+    assert!(stores > 0);
+
+    // The same request again is answered from the table
+    let second = post_json(test_app_with_state(state.clone()), "/v1/ybot/choose/minimax_bot", yen).await;
+    assert_eq!(first["coords"], second["coords"]);
+    let after_second = get_json(test_app_with_state(state), "/metrics").await;
+    assert!(after_second["transposition_table"]["hits"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_choose_reports_stats_and_feeds_the_latency_histogram() {
+    // Asked first, so the search is not answered from the transposition table
+    let state = create_default_state();
+    let with = post_json(
+        test_app_with_state(state.clone()),
+        "/v1/ybot/choose/minimax_bot?include=stats",
+        empty_board(3),
+    )
+    .await;
+    let stats: gamey::MoveStats = serde_json::from_value(with["stats"].clone()).unwrap();
+    assert!(stats.nodes > 0);
+    assert_eq!(stats.playouts, 0);
+
+    let without = post_json(test_app_with_state(state.clone()), "/v1/ybot/choose/minimax_bot", empty_board(3)).await;
+    assert!(without.get("stats").is_none());
+
+    let metrics = get_json(test_app_with_state(state), "/metrics").await;
+    assert_eq!(metrics["move_latency"]["count"], 2);
+}
+
+/// A bot that ignores deadlines and takes far longer than any test budget.
+struct SlowBot;
+
+impl YBot for SlowBot {
+    fn name(&self) -> &str {
+        "slow_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        std::thread::sleep(Duration::from_millis(1500));
+        RandomBot.choose_move(board)
+    }
+}
+
+fn slow_bot_state() -> AppState {
+    AppState::new(YBotRegistry::new().with_bot(Arc::new(SlowBot)))
+}
+
+fn empty_board(size: u32) -> serde_json::Value {
+    let layout = (1..=size)
+        .map(|row| ".".repeat(row as usize))
+        .collect::<Vec<_>>()
+        .join("/");
+    serde_json::to_value(YEN::new(size, 0, vec!['B', 'R'], layout)).unwrap()
+}
+
+#[tokio::test]
+async fn test_bot_move_after_its_flag_fell_is_not_applied() {
+    let app = test_app_with_state(slow_bot_state());
+    post_json(
+        app.clone(),
+        "/reset",
+        serde_json::json!({"size": 3, "time_control": {"initial_s": 1, "increment_s": 0}}),
+    )
+    .await;
+    // The bot thinks for longer than the second it has left
+    let result = post_json(app.clone(), "/execute-move", serde_json::json!({"index": 0, "bot": "slow_bot"})).await;
+
+    assert_eq!(result["bot_move"], serde_json::Value::Null);
+    assert_eq!(result["winner"], 0);
+    assert_eq!(result["lost_on_time"], 1);
+    assert_eq!(result["clock"]["flagged"], 1);
+    assert_eq!(result["board"]["layout"], "B/../...");
+    assert_eq!(get_json(app, "/game").await["board"]["layout"], "B/../...");
+}
+
+#[tokio::test]
+async fn test_slow_bot_falls_back_within_budget() {
+    let start = Instant::now();
+    let response = post_json(
+        test_app_with_state(slow_bot_state()),
+        "/v1/ybot/choose/slow_bot?time_ms=100",
+        empty_board(3),
+    )
+    .await;
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    assert_eq!(response["bot_id"], "slow_bot");
+    let coords: Coordinates = serde_json::from_value(response["coords"].clone()).unwrap();
+    assert!(coords.to_index(3) < 6);
+}
+
+#[tokio::test]
+async fn test_requested_time_is_clamped_to_server_maximum() {
+    let state = slow_bot_state().with_max_think_time(Duration::from_millis(100));
+    let start = Instant::now();
+    post_json(
+        test_app_with_state(state),
+        "/v1/ybot/choose/slow_bot?time_ms=60000",
+        empty_board(3),
+    )
+    .await;
+    assert!(start.elapsed() < Duration::from_millis(1000));
+}
+
+#[tokio::test]
+async fn test_mcts_bot_answers_within_budget() {
+    let start = Instant::now();
+    let response = post_json(test_app(), "/v1/ybot/choose/mcts_bot?time_ms=100", empty_board(9)).await;
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    assert_eq!(response["bot_id"], "mcts_bot");
+}
+
+// ============================================================================
+// Game review
+// ============================================================================
+
+#[tokio::test]
+async fn test_review_flags_missed_win_in_one() {
+    // Player 0 could win at cell 3 or 6 on the 7th move but plays 4
+    let body = serde_json::json!({
+        "size": 4,
+        "moves": [9, 0, 8, 2, 7, 5, 4],
+    });
+    let review = post_json(test_app(), "/review", body).await;
+    assert_eq!(review["bot_id"], "minimax_bot");
+    let annotations = review["annotations"].as_array().unwrap();
+    assert_eq!(annotations.len(), 7);
+    let missed = &annotations[6];
+    assert_eq!(missed["move_number"], 7);
+    assert_eq!(missed["blunder"], true);
+    let best: Coordinates = serde_json::from_value(missed["best_move"].clone()).unwrap();
+    assert!([3, 6].contains(&best.to_index(4)));
+    assert!(review["blunders"].as_u64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_review_rejects_illegal_move_list() {
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/review")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"size": 3, "moves": [0, 0]}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert!(error.message.contains("Move 2"));
+}
+
+// ============================================================================
+// Route not found tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_unknown_route_returns_404() {
+    let app = test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/unknown/route")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_wrong_method_on_status_endpoint() {
+    let app = test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/status")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // POST to a GET-only endpoint should return 405 Method Not Allowed
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn test_get_on_choose_endpoint_returns_method_not_allowed() {
+    let app = test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/ybot/choose/random_bot")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+// ============================================================================
+// Archive tests
+// ============================================================================
+
+/// Plays a size 1 game through the API: the first move wins it.
+async fn finish_game_via_api(app: axum::Router) {
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 1})).await;
+    let response = post_json(app, "/execute-move", serde_json::json!({"index": 0})).await;
+    assert_eq!(response["winner"], 0);
+}
+
+async fn check_archive_routes(state: AppState) {
+    let app = test_app_with_state(state);
+    finish_game_via_api(app.clone()).await;
+    finish_game_via_api(app.clone()).await;
+
+    let page = get_json(app.clone(), "/archive?limit=1").await;
+    assert_eq!(page["limit"], 1);
+    let games = page["games"].as_array().unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0]["id"], 2);
+
+    let game = get_json(app.clone(), "/archive/1").await;
+    assert_eq!(game["size"], 1);
+    assert_eq!(game["winner"], 0);
+    assert_eq!(game["moves"].as_array().unwrap().len(), 1);
+    assert_eq!(game["yen"]["layout"], "B");
+
+    let openings = get_json(app.clone(), "/stats/openings?sort=win_rate").await;
+    assert_eq!(openings["games"], 2);
+    assert_eq!(
+        openings["openings"],
+        serde_json::json!([{"coords": {"x": 0, "y": 0, "z": 0}, "games": 2, "first_player_wins": 2}])
+    );
+    let openings = get_json(app.clone(), "/stats/openings?min_games=3").await;
+    assert_eq!(openings["openings"], serde_json::json!([]));
+
+    let response = app
+        .oneshot(Request::builder().uri("/archive/99").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_finished_games_are_archived() {
+    let state = create_default_state().with_archive(Arc::new(gamey::MemoryArchive::new()));
+    check_archive_routes(state).await;
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_finished_games_are_archived_in_sqlite() {
+    let state = create_default_state().with_archive(Arc::new(gamey::SqliteArchive::in_memory().unwrap()));
+    check_archive_routes(state).await;
+}
+
+#[tokio::test]
+async fn test_archive_routes_without_archive_are_not_found() {
+    for uri in ["/archive", "/stats/openings"] {
+        let response = test_app()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code.as_deref(), Some("ARCHIVE_DISABLED"));
+    }
+}
+
+#[test]
+fn test_database_url_needs_the_sqlite_feature() {
+    let config = gamey::ServerConfig::new(3000).with_database_url(":memory:");
+    let state = gamey::create_state(&config);
+    if cfg!(feature = "sqlite") {
+        assert!(state.unwrap().archive.is_some());
+    } else {
+        assert!(state.is_err());
+    }
+}
+
+// ============================================================================
+// Configuration tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_router_from_config_uses_its_board_size() {
+    let config = gamey::ServerConfig::from_toml("default_board_size = 7\ndefault_bot = \"bridge_bot\"")
+        .unwrap()
+        .apply_env([("GAMEY_DEFAULT_BOARD_SIZE".to_string(), "9".to_string())])
+        .unwrap();
+    let app = create_router(gamey::create_state(&config).unwrap());
+
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri("/game").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let game: gamey::GameStateResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(game.board.size(), 9);
+
+    // Reset and hosted games without a size use it too
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/games")
+                .header("content-type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let created: gamey::CreatedGame = serde_json::from_slice(&body).unwrap();
+    assert_eq!(created.game.board.size(), 9);
+}
+
+#[test]
+fn test_unknown_default_bot_fails_startup() {
+    let config = gamey::ServerConfig {
+        default_bot: "no_such_bot".to_string(),
+        ..gamey::ServerConfig::default()
+    };
+    let Err(err) = gamey::create_state(&config) else {
+        panic!("the state should not be created");
+    };
+    assert!(err.to_string().contains("Unknown default bot 'no_such_bot'"), "{}", err);
+    assert!(err.to_string().contains("random_bot"), "{}", err);
+
+    assert!(gamey::create_state(&gamey::ServerConfig::new(0)).is_err());
+}
+
+#[tokio::test]
+async fn test_bots_come_from_the_configured_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bots.toml");
+    std::fs::write(
+        &path,
+        "[[bots]]\nid = \"mcts_fast\"\nkind = \"mcts\"\nplayouts = 200\nseed = 42\n\n\
+         [[bots]]\nid = \"minimax_shallow\"\nkind = \"minimax\"\ndepth = 1\n",
+    )
+    .unwrap();
+    let registry = gamey::YBotRegistry::from_manifest(&path).unwrap();
+    assert_eq!(registry.find("mcts_fast").unwrap().name(), "mcts_fast");
+    assert_eq!(registry.find("minimax_shallow").unwrap().name(), "minimax_shallow");
+    assert!(registry.find("random_bot").is_none());
+
+    let config = gamey::ServerConfig {
+        bots_manifest: Some(path),
+        default_bot: "mcts_fast".to_string(),
+        difficulty_presets: [("easy".to_string(), gamey::DifficultyPreset::new("minimax_shallow"))].into(),
+        ..gamey::ServerConfig::default()
+    };
+    let app = create_router(gamey::create_state(&config).unwrap());
+    let reply = post_json(app.clone(), "/v1/ybot/choose/minimax_shallow", empty_board(3)).await;
+    assert!(reply["coords"].is_object());
+    let (status, _) = send(&app, seat_request("POST", "/v1/ybot/choose/random_bot", None, Some(empty_board(3)))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn test_invalid_manifest_fails_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bots.json");
+    std::fs::write(&path, r#"{"bots": [{"id": "alpha", "kind": "alphazero"}]}"#).unwrap();
+    let config = gamey::ServerConfig {
+        bots_manifest: Some(path.clone()),
+        ..gamey::ServerConfig::default()
+    };
+    let Err(err) = gamey::create_state(&config) else {
+        panic!("a manifest with an unknown kind was accepted");
+    };
+    assert!(err.to_string().contains("unknown kind 'alphazero'"), "{}", err);
+
+    std::fs::write(&path, r#"{"bots": [{"id": "fast", "kind": "mcts", "playout": 10}]}"#).unwrap();
+    assert!(matches!(
+        gamey::YBotRegistry::from_manifest(&path),
+        Err(gamey::GameYError::InvalidBotManifest { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_rate_limit_rejects_extra_requests() {
+    let config = gamey::ServerConfig {
+        rate_limit_per_minute: Some(2),
+        ..gamey::ServerConfig::default()
+    };
+    let app = create_router(gamey::create_state(&config).unwrap());
+    let status = || Request::builder().uri("/status").body(Body::empty()).unwrap();
+
+    for _ in 0..2 {
+        let response = app.clone().oneshot(status()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app.oneshot(status()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("RATE_LIMITED"));
+}
+
+#[tokio::test]
+async fn test_cors_allows_the_configured_origins() {
+    let config = gamey::ServerConfig {
+        cors_origins: vec!["http://localhost:5173".to_string()],
+        ..gamey::ServerConfig::default()
+    };
+    let app = create_router(gamey::create_state(&config).unwrap());
+    let request = |origin: &str| {
+        Request::builder()
+            .uri("/status")
+            .header("origin", origin)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(request("http://localhost:5173")).await.unwrap();
+    assert_eq!(
+        response.headers().get("access-control-allow-origin").unwrap(),
+        "http://localhost:5173"
+    );
+    let response = app.oneshot(request("http://evil.example")).await.unwrap();
+    assert!(response.headers().get("access-control-allow-origin").is_none());
+
+    // Without origins the server sends no CORS headers
+    let response = test_app().oneshot(request("http://localhost:5173")).await.unwrap();
+    assert!(response.headers().get("access-control-allow-origin").is_none());
+}
+
+
+// ============================================================================
+// MessagePack tests
+// ============================================================================
+
+/// Helper to send a request that accepts MessagePack, with an optional MessagePack body.
+///
+/// Returns the status, the response content type and the body.
+async fn send_msgpack(
+    app: axum::Router,
+    method: &str,
+    uri: &str,
+    body: Option<Vec<u8>>,
+) -> (StatusCode, String, Vec<u8>) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("accept", "application/msgpack");
+    if body.is_some() {
+        request = request.header("content-type", "application/msgpack");
+    }
+    let response = app
+        .oneshot(request.body(Body::from(body.unwrap_or_default())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response.headers()["content-type"].to_str().unwrap().to_string();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, content_type, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_choose_in_msgpack_matches_json() {
+    // A single empty cell, so every bot must choose the same move
+    let yen = YEN::new(2, 0, vec!['B', 'R'], "B/R.".to_string());
+
+    let (status, content_type, body) = send_msgpack(
+        test_app(),
+        "POST",
+        "/v1/ybot/choose/random_bot",
+        Some(rmp_serde::to_vec_named(&yen).unwrap()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/msgpack");
+    let from_msgpack: MoveResponse = rmp_serde::from_slice(&body).unwrap();
+
+    let from_json: MoveResponse = serde_json::from_value(
+        post_json(test_app(), "/v1/ybot/choose/random_bot", serde_json::to_value(&yen).unwrap()).await,
+    )
+    .unwrap();
+    assert_eq!(from_msgpack, from_json);
+    assert_eq!(from_msgpack.coords, Coordinates::new(0, 1, 0));
+}
+
+#[tokio::test]
+async fn test_execute_move_in_msgpack() {
+    let body = rmp_serde::to_vec_named(&serde_json::json!({ "index": 0 })).unwrap();
+    let (status, content_type, body) = send_msgpack(test_app(), "POST", "/execute-move", Some(body)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/msgpack");
+    let response: serde_json::Value = rmp_serde::from_slice(&body).unwrap();
+    let board: YEN = serde_json::from_value(response["board"].clone()).unwrap();
+    assert!(board.layout().starts_with('B'));
+    assert_eq!(board.layout().matches('R').count(), 1);
+    assert_eq!(response["swapped"], false);
+}
+
+#[tokio::test]
+async fn test_game_state_in_msgpack() {
+    let (status, _, body) = send_msgpack(test_app(), "GET", "/game", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let state: gamey::GameStateResponse = rmp_serde::from_slice(&body).unwrap();
+    assert_eq!(state.board.size(), 5);
+    assert_eq!(state.players.len(), 2);
+    assert!(state.winner.is_none());
+}
+
+#[tokio::test]
+async fn test_errors_follow_the_accept_header() {
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+    let (status, content_type, body) = send_msgpack(
+        test_app(),
+        "POST",
+        "/v1/ybot/choose/unknown_bot",
+        Some(rmp_serde::to_vec_named(&yen).unwrap()),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(content_type, "application/msgpack");
+    let error: ErrorResponse = rmp_serde::from_slice(&body).unwrap();
+    assert!(error.message.contains("Bot not found"));
+    assert_eq!(error.bot_id, Some("unknown_bot".to_string()));
+}
+
+#[tokio::test]
+async fn test_invalid_msgpack_body_is_rejected() {
+    let (status, _, body) = send_msgpack(
+        test_app(),
+        "POST",
+        "/v1/ybot/choose/random_bot",
+        Some(vec![0xc1, 0xff, 0x00]),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: ErrorResponse = rmp_serde::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("INVALID_BODY"));
+}
+
+#[tokio::test]
+async fn test_unsupported_content_type_returns_415() {
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "text/plain")
+                .body(Body::from("size 3"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("UNSUPPORTED_MEDIA_TYPE"));
+}
+
+// ============================================================================
+// Generated positions (`testing` feature)
+// ============================================================================
+
+#[cfg(feature = "testing")]
+mod generated {
+    use super::*;
+    use gamey::testing::{arb_board_size, arb_position};
+    use proptest::prelude::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        /// Property: The random bot answers every ongoing position with an empty cell.
+        #[test]
+        fn prop_choose_returns_an_empty_cell(game in arb_board_size().prop_flat_map(arb_position)) {
+            prop_assume!(!game.check_game_over());
+            let yen = YEN::from(&game);
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            let response = runtime.block_on(
+                test_app().oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/v1/ybot/choose/random_bot")
+                        .header("content-type", "application/json")
+                        .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                        .unwrap(),
+                ),
+            ).unwrap();
+            prop_assert_eq!(response.status(), StatusCode::OK);
+
+            let body = runtime.block_on(response.into_body().collect()).unwrap().to_bytes();
+            let chosen: MoveResponse = serde_json::from_slice(&body).unwrap();
+            let index = chosen.coords.to_index(game.board_size());
+            prop_assert!(game.available_cells().contains(&index));
+        }
+    }
+}
+
+// ============================================================================
+// Admin tests
+// ============================================================================
+
+/// Helper to create an app with the admin routes and the API key "secret".
+fn admin_app() -> axum::Router {
+    let config = gamey::ServerConfig {
+        enable_admin: true,
+        api_keys: vec!["secret".to_string()],
+        ..gamey::ServerConfig::default()
+    };
+    create_router(gamey::create_state(&config).unwrap())
+}
+
+/// Helper to send an admin request with the given API key.
+async fn admin_request(app: &axum::Router, method: &str, uri: &str, key: Option<&str>) -> (StatusCode, serde_json::Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(key) = key {
+        request = request.header("x-api-key", key);
+    }
+    let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+}
+
+/// Helper to host a game of two humans and return its id.
+async fn host_game(app: &axum::Router) -> u64 {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/games")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"size": 3}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice::<gamey::CreatedGame>(&body).unwrap().game.id
+}
+
+#[tokio::test]
+async fn test_admin_gc_removes_idle_games() {
+    let app = admin_app();
+    let first = host_game(&app).await;
+    let second = host_game(&app).await;
+
+    let (status, listed) = admin_request(&app, "GET", "/admin/games", Some("secret")).await;
+    assert_eq!(status, StatusCode::OK);
+    let listed: gamey::AdminGamesResponse = serde_json::from_value(listed).unwrap();
+    assert_eq!(listed.games.iter().map(|g| g.id).collect::<Vec<_>>(), vec![first, second]);
+
+    let (status, gc) = admin_request(&app, "POST", "/admin/gc?older_than_s=0", Some("secret")).await;
+    assert_eq!(status, StatusCode::OK);
+    let gc: gamey::GcResponse = serde_json::from_value(gc).unwrap();
+    assert_eq!(gc.removed, vec![first, second]);
+
+    let (_, listed) = admin_request(&app, "GET", "/admin/games", Some("secret")).await;
+    assert_eq!(listed["games"], serde_json::json!([]));
+    let response = app
+        .oneshot(Request::builder().uri(format!("/games/{}", first)).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_reset_all_clears_games_and_stats() {
+    let app = admin_app();
+    host_game(&app).await;
+    let response = app.clone().oneshot(move_request("/execute-move", serde_json::json!({"index": 0}))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, reset) = admin_request(&app, "POST", "/admin/reset-all", Some("secret")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reset["removed_games"], 1);
+
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri("/stats").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(serde_json::from_slice::<GameStats>(&body).unwrap(), GameStats::new());
+    let (_, listed) = admin_request(&app, "GET", "/admin/games", Some("secret")).await;
+    assert_eq!(listed["games"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_admin_routes_need_a_key_and_the_setting() {
+    let app = admin_app();
+    let (status, error) = admin_request(&app, "GET", "/admin/games", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(error["code"], "UNAUTHORIZED");
+    let (status, _) = admin_request(&app, "POST", "/admin/gc", Some("wrong")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    for (method, uri) in [("POST", "/admin/reset-all"), ("GET", "/admin/games"), ("POST", "/admin/gc?older_than_s=0")] {
+        let (status, _) = admin_request(&test_app(), method, uri, Some("secret")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{} {}", method, uri);
+    }
+}
+
+// ============================================================================
+// Expiry tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_abandoned_games_expire_after_the_ttl() {
+    let config = gamey::ServerConfig {
+        game_ttl_s: 60,
+        archive_expired: true,
+        ..gamey::ServerConfig::default()
+    };
+    let archive = Arc::new(gamey::MemoryArchive::new());
+    let state = gamey::create_state(&config).unwrap().with_archive(archive.clone());
+    let app = create_router(state.clone());
+    let empty = host_game(&app).await;
+
+    // A game with a move, which is archived when it expires
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/games")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"size": 3}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let created: gamey::CreatedGame = serde_json::from_slice(&body).unwrap();
+    let token = created.tokens[0].clone().unwrap();
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/games/{}/move", created.game.id))
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"index": 0}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let now = Instant::now();
+    assert!(gamey::expiry::expire_games(&state, now + Duration::from_secs(30)).is_empty());
+    assert_eq!(
+        gamey::expiry::expire_games(&state, now + Duration::from_secs(61)),
+        vec![empty, created.game.id]
+    );
+    assert!(state.hosted_games.is_empty());
+    assert_eq!(gamey::Metrics::collect(&state).expired_games, 2);
+
+    use gamey::GameArchive;
+    let archived = archive.list(10, 0).unwrap();
+    assert_eq!(archived.len(), 1);
+}
+
+// ============================================================================
+// Limit tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_oversized_body_gets_a_json_413() {
+    let layout = "B".repeat(100 * 1024);
+    let body = serde_json::json!({"size": 3, "turn": 0, "players": ["B", "R"], "layout": layout});
+    let response = test_app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/ybot/choose/random_bot")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(error.code.as_deref(), Some("PAYLOAD_TOO_LARGE"));
+}
+
+#[tokio::test]
+async fn test_body_limit_comes_from_the_config() {
+    let config = gamey::ServerConfig {
+        max_body_bytes: 8,
+        ..gamey::ServerConfig::default()
+    };
+    let app = create_router(gamey::create_state(&config).unwrap());
+    let response = app.oneshot(move_request("/execute-move", serde_json::json!({"index": 0}))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+// ============================================================================
+// Compression tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_gzip_response_decompresses_to_the_same_json() {
+    use std::io::Read;
+    let config = gamey::ServerConfig {
+        enable_compression: true,
+        ..gamey::ServerConfig::default()
+    };
+    let app = create_router(gamey::create_state(&config).unwrap());
+    let get = |gzip: bool| {
+        let mut request = Request::builder().uri("/game");
+        if gzip {
+            request = request.header("accept-encoding", "gzip");
+        }
+        request.body(Body::empty()).unwrap()
+    };
+
+    let plain = app.clone().oneshot(get(false)).await.unwrap();
+    assert!(plain.headers().get("content-encoding").is_none());
+    let plain = plain.into_body().collect().await.unwrap().to_bytes();
+
+    let compressed = app.oneshot(get(true)).await.unwrap();
+    assert_eq!(compressed.headers()["content-encoding"], "gzip");
+    let compressed = compressed.into_body().collect().await.unwrap().to_bytes();
+    let mut json = String::new();
+    flate2::read::GzDecoder::new(&compressed[..]).read_to_string(&mut json).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::from_slice::<serde_json::Value>(&plain).unwrap()
+    );
+
+    // Without the setting responses are never compressed
+    let response = test_app().oneshot(get(true)).await.unwrap();
+    assert!(response.headers().get("content-encoding").is_none());
+}
+
+// ============================================================================
+// History tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_history_pages_through_the_game() {
+    let state = create_default_state();
+    {
+        let mut game = state.game.lock();
+        for (ply, index) in [0, 1, 2, 3, 4, 5, 6].into_iter().enumerate() {
+            game.add_move(gamey::Movement::Placement {
+                player: gamey::PlayerId::new(ply as u32 % 2),
+                coords: Coordinates::from_index(index, 5),
+            })
+            .unwrap();
+        }
+    }
+    let app = test_app_with_state(state);
+    let page = |offset: usize| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/game/history?offset={}&limit=3", offset))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            serde_json::from_slice::<gamey::HistoryPage>(&body).unwrap()
+        }
+    };
+
+    let mut plies = Vec::new();
+    for offset in [0, 3, 6] {
+        let page = page(offset).await;
+        assert_eq!(page.total, 7);
+        assert_eq!(page.limit, 3);
+        plies.extend(page.moves.iter().map(|m| m.ply));
+    }
+    assert_eq!(plies, (1..=7).collect::<Vec<_>>());
+
+    let first = page(0).await.moves[1].clone();
+    assert_eq!(first.player, 1);
+    assert_eq!(first.kind, gamey::MoveKind::Placement);
+    assert_eq!(first.index, Some(1));
+    assert_eq!(first.coords, Some(Coordinates::from_index(1, 5)));
+    assert!(first.new_side.is_some());
+
+    // Past the end the page is empty
+    assert!(page(9).await.moves.is_empty());
+}
+
+// ============================================================================
+// TLS tests
+// ============================================================================
+
+/// Helper to write a self-signed certificate for `localhost` and its key; returns their paths.
+#[cfg(feature = "tls")]
+fn self_signed(dir: &std::path::Path, name: &str) -> (std::path::PathBuf, std::path::PathBuf) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert = dir.join(format!("{}.crt", name));
+    let key = dir.join(format!("{}.key", name));
+    std::fs::write(&cert, certified.cert.pem()).unwrap();
+    std::fs::write(&key, certified.key_pair.serialize_pem()).unwrap();
+    (cert, key)
+}
+
+#[cfg(feature = "tls")]
+#[tokio::test]
+async fn test_tls_server_answers_status() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let dir = tempfile::tempdir().unwrap();
+    let (cert, key) = self_signed(dir.path(), "server");
+    let tls = gamey::tls::load_tls_config(&cert, &key).unwrap();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(gamey::tls::serve_tls(listener, test_app(), tls, async {
+        let _ = stopped.await;
+    }));
+
+    // A client trusting only the self-signed certificate
+    let mut roots = rustls::RootCertStore::empty();
+    let pem = std::fs::read(&cert).unwrap();
+    use rustls::pki_types::pem::PemObject;
+    for der in rustls::pki_types::CertificateDer::pem_slice_iter(&pem) {
+        roots.add(der.unwrap()).unwrap();
+    }
+    let client = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(client));
+    let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let name = rustls::pki_types::ServerName::try_from("localhost").unwrap();
+    let mut stream = connector.connect(name, tcp).await.unwrap();
+    stream
+        .write_all(b"GET /status HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.ends_with("OK"), "{}", response);
+
+    stop.send(()).unwrap();
+    server.await.unwrap().unwrap();
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn test_tls_files_are_checked_before_starting() {
+    let dir = tempfile::tempdir().unwrap();
+    let (cert, _) = self_signed(dir.path(), "one");
+    let (_, other_key) = self_signed(dir.path(), "two");
+
+    let Err(err) = gamey::tls::load_tls_config(&cert, &other_key) else {
+        panic!("a key of another certificate should be rejected");
+    };
+    assert!(err.to_string().contains("does not fit"), "{}", err);
+
+    let missing = dir.path().join("missing.crt");
+    let Err(err) = gamey::tls::load_tls_config(&missing, &other_key) else {
+        panic!("a missing certificate should be rejected");
+    };
+    assert!(err.to_string().contains("missing.crt"), "{}", err);
+}
+
+#[cfg(not(feature = "tls"))]
+#[tokio::test]
+async fn test_tls_needs_the_tls_feature() {
+    let config = gamey::ServerConfig {
+        tls_cert_path: Some("server.crt".into()),
+        tls_key_path: Some("server.key".into()),
+        ..gamey::ServerConfig::new(3999)
+    };
+    let err = gamey::run_bot_server_with_config(config).await.unwrap_err();
+    assert!(err.to_string().contains("tls feature"), "{}", err);
+}
+
+// ============================================================================
+// Health tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_health_lists_the_default_bots() {
+    let state = create_default_state();
+    let expected = state.bots().names();
+    let response = test_app_with_state(state)
+        .oneshot(Request::builder().uri("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let health: gamey::HealthResponse = serde_json::from_slice(&body).unwrap();
+    assert_eq!(health.bots, expected);
+    assert_eq!(health.bot_count, 5);
+    assert_eq!(health.status, "ok");
+    assert_eq!(health.archive, gamey::ArchiveHealth::Disabled);
+    assert_eq!(health.active_games, 0);
+}
+
+// ============================================================================
+// Analyze stream tests
+// ============================================================================
+
+/// A bot that reports three improving updates, one every few milliseconds.
+struct ScheduledBot;
+
+impl YBot for ScheduledBot {
+    fn name(&self) -> &str {
+        "scheduled_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        FirstCellBot.choose_move(board)
+    }
+
+    fn analyze_progressive(&self, _board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, _deadline: Instant) {
+        for (depth, best) in [(1, 0), (2, 2), (3, 1)] {
+            std::thread::sleep(Duration::from_millis(20));
+            let update = AnalysisUpdate {
+                depth_or_playouts: depth,
+                best_index: best,
+                score: Some(depth as f32 / 10.0),
+                pv: vec![best],
+            };
+            if !sink(update) {
+                return;
+            }
+        }
+    }
+}
+
+/// A bot that keeps reporting until nobody listens, and records that it stopped.
+struct EndlessBot(Arc<AtomicBool>);
+
+impl YBot for EndlessBot {
+    fn name(&self) -> &str {
+        "endless_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        FirstCellBot.choose_move(board)
+    }
+
+    fn analyze_progressive(&self, _board: &GameY, sink: &mut dyn FnMut(AnalysisUpdate) -> bool, deadline: Instant) {
+        let mut playouts = 0;
+        while Instant::now() < deadline {
+            playouts += 1;
+            let update = AnalysisUpdate {
+                depth_or_playouts: playouts,
+                best_index: 0,
+                score: None,
+                pv: vec![],
+            };
+            if !sink(update) {
+                self.0.store(true, Ordering::SeqCst);
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+/// Splits a server-sent event stream into `(event, data)` pairs.
+fn sse_events(body: &str) -> Vec<(String, serde_json::Value)> {
+    body.split("\n\n")
+        .filter_map(|block| {
+            let field = |name: &str| {
+                block
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .map(|value| value.trim_start().to_string())
+            };
+            let event = field("event:")?;
+            let data = serde_json::from_str(&field("data:")?).unwrap();
+            Some((event, data))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_analyze_stream_sends_the_updates_in_order_and_then_done() {
+    let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(ScheduledBot)));
+    let response = test_app_with_state(state)
+        .oneshot(move_request("/v1/ybot/analyze-stream?bot_id=scheduled_bot", empty_board(3)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let events = sse_events(std::str::from_utf8(&body).unwrap());
+    let names: Vec<_> = events.iter().map(|(event, _)| event.as_str()).collect();
+    assert_eq!(names, ["update", "update", "update", "done"]);
+    let depths: Vec<_> = events.iter().map(|(_, data)| data["depth_or_playouts"].clone()).collect();
+    assert_eq!(depths, [1, 2, 3, 3]);
+    assert_eq!(events[1].1["best_index"], 2);
+    assert_eq!(events[3].1["pv"], serde_json::json!([1]));
+}
+
+#[tokio::test]
+async fn test_analyze_stream_stops_the_search_when_the_client_disconnects() {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let bot = EndlessBot(stopped.clone());
+    let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(bot)));
+    let response = test_app_with_state(state)
+        .oneshot(move_request("/v1/ybot/analyze-stream?bot_id=endless_bot&time_ms=5000", empty_board(3)))
+        .await
+        .unwrap();
+    let mut body = response.into_body();
+    assert!(body.frame().await.is_some());
+    drop(body);
+
+    let start = Instant::now();
+    while !stopped.load(Ordering::SeqCst) {
+        assert!(start.elapsed() < Duration::from_secs(2), "the search kept running");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_analyze_stream_rejects_unknown_bots_before_streaming() {
+    let response = test_app()
+        .oneshot(move_request("/v1/ybot/analyze-stream?bot_id=nope", empty_board(3)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    assert!(error.message.contains("Bot not found: nope"));
+}
+
+// ============================================================================
+// Resignation tests
+// ============================================================================
+
+/// A bot whose search scores come from a script, to cross the resign threshold on cue.
+struct RiggedEvalBot {
+    evals: std::sync::Mutex<Vec<f32>>,
+    watch: ResignWatch,
+}
+
+impl YBot for RiggedEvalBot {
+    fn name(&self) -> &str {
+        "random_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        FirstCellBot.choose_move(board)
+    }
+
+    fn choose_move_with_stats(&self, board: &GameY, _deadline: Option<Instant>) -> (Option<Coordinates>, gamey::MoveStats) {
+        let eval = self.evals.lock().unwrap().remove(0);
+        let stats = gamey::MoveStats {
+            eval: Some(eval),
+            ..Default::default()
+        };
+        (self.choose_move(board), stats)
+    }
+
+    fn choose_movement(&self, board: &GameY, deadline: Option<Instant>) -> (Option<gamey::Movement>, gamey::MoveStats) {
+        let (coords, stats) = self.choose_move_with_stats(board, deadline);
+        (self.watch.decide(board, coords, stats.eval), stats)
+    }
+}
+
+#[tokio::test]
+async fn test_bot_resigns_after_two_lost_moves() {
+    let bot = RiggedEvalBot {
+        evals: std::sync::Mutex::new(vec![0.2, -0.8, -0.9]),
+        watch: ResignWatch::new(-0.5),
+    };
+    let app = test_app_with_state(AppState::new(YBotRegistry::new().with_bot(Arc::new(bot))));
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 5})).await;
+
+    for index in [14, 13] {
+        let reply = post_json(app.clone(), "/execute-move", serde_json::json!({"index": index})).await;
+        assert_eq!(reply["resigned"], false);
+        assert!(reply["winner"].is_null());
+    }
+    let reply = post_json(app.clone(), "/execute-move", serde_json::json!({"index": 12})).await;
+    assert_eq!(reply["resigned"], true);
+    assert_eq!(reply["winner"], 0);
+}
+
+// ============================================================================
+// Ownership tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_ownership_of_the_server_game() {
+    let app = test_app();
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 4})).await;
+    post_json(app.clone(), "/execute-move", serde_json::json!({"index": 4})).await;
+
+    let reply: gamey::OwnershipResponse =
+        serde_json::from_value(get_json(app.clone(), "/analysis/ownership?playouts=50&seed=3").await).unwrap();
+    assert_eq!(reply.playouts, 50);
+    assert_eq!(reply.ownership.len(), 10);
+    assert_eq!(reply.ownership[4], 1.0);
+    assert!(reply.ownership.iter().all(|v| (0.0..=1.0).contains(v)));
+
+    let capped = get_json(app, "/analysis/ownership?playouts=1000000").await;
+    assert_eq!(capped["playouts"], gamey::ownership::MAX_OWNERSHIP_PLAYOUTS);
+}
+
+// ============================================================================
+// Chaos mode
+// ============================================================================
+
+fn chaos_app(chaos: Option<gamey::ChaosConfig>) -> axum::Router {
+    let config = gamey::ServerConfig {
+        chaos,
+        ..Default::default()
+    };
+    create_router(AppState::from_config(
+        YBotRegistry::new().with_bot(Arc::new(RandomBot)),
+        config,
+    ))
+}
+
+#[tokio::test]
+async fn test_chaos_fails_mutating_requests_with_a_marker_header() {
+    let app = chaos_app(Some(gamey::ChaosConfig {
+        latency_ms: 5,
+        error_rate: 1.0,
+    }));
+    for _ in 0..4 {
+        let response = app.clone().oneshot(move_request("/reset", serde_json::json!({}))).await.unwrap();
+        assert!(
+            [StatusCode::INTERNAL_SERVER_ERROR, StatusCode::TOO_MANY_REQUESTS].contains(&response.status()),
+            "{}",
+            response.status()
+        );
+        assert_eq!(response.headers()[gamey::chaos::CHAOS_HEADER], "error");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code.as_deref(), Some("CHAOS"));
+    }
+
+    // Reads are left alone
+    let response = app
+        .oneshot(Request::builder().uri("/game").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(gamey::chaos::CHAOS_HEADER).is_none());
+}
+
+#[tokio::test]
+async fn test_without_chaos_requests_are_answered_normally() {
+    let response = chaos_app(None)
+        .oneshot(move_request("/reset", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(gamey::chaos::CHAOS_HEADER).is_none());
+
+    // Latency alone delays the request and marks it, but lets it through
+    let response = chaos_app(Some(gamey::ChaosConfig {
+        latency_ms: 5,
+        error_rate: 0.0,
+    }))
+    .oneshot(move_request("/reset", serde_json::json!({})))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let marker = response.headers()[gamey::chaos::CHAOS_HEADER].to_str().unwrap();
+    assert!(marker.starts_with("latency="), "{}", marker);
+}
+
+/// Records the context of every decision and plays like [`FirstCellBot`].
+#[derive(Default)]
+struct ContextSpyBot {
+    seen: std::sync::Mutex<Vec<gamey::BotContext>>,
+}
+
+impl YBot for ContextSpyBot {
+    fn name(&self) -> &str {
+        "random_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        FirstCellBot.choose_move(board)
+    }
+
+    fn choose_move_with_context(
+        &self,
+        board: &GameY,
+        context: &gamey::BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<Coordinates>, gamey::MoveStats) {
+        self.seen.lock().unwrap().push(context.clone());
+        self.choose_move_with_stats(board, deadline)
+    }
+
+    fn choose_movement_with_context(
+        &self,
+        board: &GameY,
+        context: &gamey::BotContext,
+        deadline: Option<Instant>,
+    ) -> (Option<gamey::Movement>, gamey::MoveStats) {
+        self.seen.lock().unwrap().push(context.clone());
+        self.choose_movement(board, deadline)
+    }
+}
+
+fn empty_yen(size: u32) -> YEN {
+    let layout = (1..=size).map(|row| ".".repeat(row as usize)).collect::<Vec<_>>().join("/");
+    YEN::new(size, 0, vec!['B', 'R'], layout)
+}
+
+#[tokio::test]
+async fn test_bots_share_one_context_per_board_size() {
+    let spy = Arc::new(ContextSpyBot::default());
+    let state = AppState::new(YBotRegistry::new().with_bot(spy.clone()));
+    let contexts = state.bot_contexts.clone();
+    let app = test_app_with_state(state);
+
+    for size in [5, 9, 5, 9] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/ybot/choose/random_bot")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&empty_yen(size)).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // The game bot thinks with the same tables as the choose endpoint
+    post_json(app.clone(), "/reset", serde_json::json!({"size": 5})).await;
+    post_json(app, "/execute-move", serde_json::json!({"index": 14})).await;
+
+    let seen = spy.seen.lock().unwrap();
+    assert_eq!(seen.len(), 5);
+    let sizes: Vec<u32> = seen.iter().map(|context| context.board_size).collect();
+    assert_eq!(sizes, [5, 9, 5, 9, 5]);
+    assert!(Arc::ptr_eq(&seen[0].topology, &seen[2].topology));
+    assert!(Arc::ptr_eq(&seen[0].zobrist, &seen[4].zobrist));
+    assert!(Arc::ptr_eq(&seen[1].zobrist, &seen[3].zobrist));
+    assert!(!Arc::ptr_eq(&seen[0].topology, &seen[1].topology));
+    assert_eq!(gamey::BoardTopology::total_cells(seen[1].topology.as_ref()), 45);
+    assert_eq!(contexts.sizes(), [5, 9]);
 }
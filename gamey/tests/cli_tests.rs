@@ -275,10 +275,11 @@ use gamey::CliArgs;
 #[test]
 fn test_cli_args_default_values() {
     let args = CliArgs::try_parse_from(["gamey"]).unwrap();
-    assert_eq!(args.size, Some(7));
+    assert_eq!(args.size, None);
     assert_eq!(args.mode, Mode::Human);
     assert_eq!(args.bot, "random_bot");
-    assert_eq!(args.port, 3000);
+    assert_eq!(args.port, None);
+    assert_eq!(args.config, None);
 }
 
 #[test]
@@ -332,13 +333,28 @@ fn test_cli_args_custom_bot_short() {
 #[test]
 fn test_cli_args_custom_port() {
     let args = CliArgs::try_parse_from(["gamey", "--port", "8080"]).unwrap();
-    assert_eq!(args.port, 8080);
+    assert_eq!(args.port, Some(8080));
+}
+
+#[test]
+fn test_cli_args_config_file() {
+    let args = CliArgs::try_parse_from(["gamey", "--mode", "server", "--config", "server.toml"]).unwrap();
+    assert_eq!(args.config, Some(std::path::PathBuf::from("server.toml")));
+    assert_eq!(args.port, None);
+}
+
+#[test]
+fn test_cli_args_allow_chaos() {
+    let args = CliArgs::try_parse_from(["gamey", "--mode", "server"]).unwrap();
+    assert!(!args.allow_chaos);
+    let args = CliArgs::try_parse_from(["gamey", "--mode", "server", "--allow-chaos"]).unwrap();
+    assert!(args.allow_chaos);
 }
 
 #[test]
 fn test_cli_args_custom_port_short() {
     let args = CliArgs::try_parse_from(["gamey", "-p", "9000"]).unwrap();
-    assert_eq!(args.port, 9000);
+    assert_eq!(args.port, Some(9000));
 }
 
 #[test]
@@ -358,7 +374,7 @@ fn test_cli_args_combined_options() {
     assert_eq!(args.size, Some(9));
     assert_eq!(args.mode, Mode::Computer);
     assert_eq!(args.bot, "advanced_bot");
-    assert_eq!(args.port, 5000);
+    assert_eq!(args.port, Some(5000));
 }
 
 #[test]
@@ -390,3 +406,546 @@ fn test_cli_args_version_flag() {
     let result = CliArgs::try_parse_from(["gamey", "--version"]);
     assert!(result.is_err()); // --version causes an error (but it's intentional)
 }
+
+// =============================================================================
+// Tournament subcommand Tests
+// =============================================================================
+
+use gamey::export::{ExportFormat, TrainingRow};
+use gamey::{CliCommand, TournamentArgs, run_tournament};
+use gamey::{SelfplayArgs, StatsArgs, ValidateArgs, YenIssue, run_selfplay, run_stats, run_validate};
+use gamey::stats::OpeningSort;
+use gamey::{Coordinates, GameAction, GameY, Movement, OpeningsArgs, PlayerId, collection_openings, run_openings};
+
+#[test]
+fn test_cli_args_without_subcommand() {
+    let args = CliArgs::try_parse_from(["gamey"]).unwrap();
+    assert_eq!(args.command, None);
+}
+
+#[test]
+fn test_cli_args_tournament_defaults() {
+    let args = CliArgs::try_parse_from(["gamey", "tournament"]).unwrap();
+    match args.command {
+        Some(CliCommand::Tournament(t)) => {
+            assert_eq!(t.games, 10);
+            assert_eq!(t.size, 5);
+            assert_eq!(t.seed, None);
+            assert_eq!(t.bots, vec!["random_bot", "minimax_bot", "mcts_bot"]);
+        }
+        other => panic!("Expected tournament subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_cli_args_tournament_options() {
+    let args = CliArgs::try_parse_from([
+        "gamey", "tournament", "--games", "4", "--size", "7", "--seed", "42", "--bots",
+        "random_bot,mcts_bot",
+    ])
+    .unwrap();
+    match args.command {
+        Some(CliCommand::Tournament(t)) => {
+            assert_eq!(t.games, 4);
+            assert_eq!(t.size, 7);
+            assert_eq!(t.seed, Some(42));
+            assert_eq!(t.bots, vec!["random_bot", "mcts_bot"]);
+        }
+        other => panic!("Expected tournament subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_run_tournament_with_unknown_bot_fails() {
+    let args = TournamentArgs {
+        games: 1,
+        size: 3,
+        seed: None,
+        bots: vec!["random_bot".to_string(), "no_such_bot".to_string()],
+        threads: 1,
+        json: false,
+        collection: None,
+    };
+    let err = run_tournament(&args).unwrap_err();
+    assert!(err.to_string().contains("no_such_bot"));
+}
+
+#[test]
+fn test_run_tournament_small() {
+    let args = TournamentArgs {
+        games: 2,
+        size: 3,
+        seed: Some(1),
+        bots: vec!["random_bot".to_string(), "minimax_bot".to_string()],
+        threads: 2,
+        json: true,
+        collection: None,
+    };
+    assert!(run_tournament(&args).is_ok());
+}
+
+#[test]
+fn test_cli_args_selfplay_export() {
+    let args = CliArgs::try_parse_from([
+        "gamey", "selfplay", "--games", "3", "--export", "rows.csv", "--format", "csv",
+    ])
+    .unwrap();
+    match args.command {
+        Some(CliCommand::Selfplay(s)) => {
+            assert_eq!(s.games, 3);
+            assert_eq!(s.bot, "random_bot");
+            assert_eq!(s.export, Some(std::path::PathBuf::from("rows.csv")));
+            assert_eq!(s.format, ExportFormat::Csv);
+        }
+        other => panic!("Expected selfplay subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_run_selfplay_exports_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("games.jsonl");
+    let args = SelfplayArgs {
+        games: 2,
+        size: 3,
+        bot: "random_bot".to_string(),
+        export: Some(path.clone()),
+        format: ExportFormat::Jsonl,
+        collection: None,
+    };
+    run_selfplay(&args).unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let rows: Vec<TrainingRow> = content
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    // A size 3 board has 6 cells, so each game places between 3 and 6 stones
+    assert!(rows.len() >= 6 && rows.len() <= 12);
+    assert!(rows.iter().all(|r| r.board.len() == 6 && r.outcome != 0));
+}
+
+#[test]
+fn test_cli_args_stats() {
+    let args = CliArgs::try_parse_from(["gamey", "stats", "games.jsonl", "--json"]).unwrap();
+    match args.command {
+        Some(CliCommand::Stats(s)) => {
+            assert_eq!(s.collection, std::path::PathBuf::from("games.jsonl"));
+            assert!(s.json);
+        }
+        other => panic!("Expected stats subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_selfplay_collection_and_stats() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("games.jsonl");
+    let args = SelfplayArgs {
+        games: 3,
+        size: 3,
+        bot: "random_bot".to_string(),
+        export: None,
+        format: ExportFormat::Jsonl,
+        collection: Some(path.clone()),
+    };
+    run_selfplay(&args).unwrap();
+    run_selfplay(&args).unwrap();
+
+    let stats = gamey::CollectionStats::from_path(&path).unwrap();
+    assert_eq!(stats.games, 6);
+    assert_eq!(stats.unfinished, 0);
+    assert!(stats.min_moves >= 3);
+
+    let stats_args = StatsArgs {
+        collection: path,
+        json: false,
+    };
+    assert!(run_stats(&stats_args).is_ok());
+}
+
+#[test]
+fn test_stats_of_missing_collection_fails() {
+    let args = StatsArgs {
+        collection: std::path::PathBuf::from("/no/such/games.jsonl"),
+        json: false,
+    };
+    assert!(run_stats(&args).is_err());
+}
+
+#[test]
+fn test_cli_args_openings() {
+    let args = CliArgs::try_parse_from(["gamey", "openings", "games.jsonl", "--min-games", "5", "--sort", "win-rate"])
+        .unwrap();
+    match args.command {
+        Some(CliCommand::Openings(o)) => {
+            assert_eq!(o.collection, std::path::PathBuf::from("games.jsonl"));
+            assert_eq!(o.min_games, 5);
+            assert_eq!(o.sort, OpeningSort::WinRate);
+            assert!(!o.json);
+        }
+        other => panic!("Expected openings subcommand, got {:?}", other),
+    }
+    let args = CliArgs::try_parse_from(["gamey", "openings", "games.jsonl"]).unwrap();
+    assert!(matches!(args.command, Some(CliCommand::Openings(o)) if o.min_games == 1 && o.sort == OpeningSort::Games));
+}
+
+/// A game of size 3 opened by player 0 at `opening` and resigned by `loser`.
+fn resigned_game(opening: Coordinates, loser: u32) -> GameY {
+    let mut game = GameY::new(3);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: opening,
+    })
+    .unwrap();
+    if loser == 0 {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(0, 1, 1),
+        })
+        .unwrap();
+    }
+    game.add_move(Movement::Action {
+        player: PlayerId::new(loser),
+        action: GameAction::Resign,
+    })
+    .unwrap();
+    game
+}
+
+#[test]
+fn test_openings_of_a_collection() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("games.jsonl");
+    let mut writer = gamey::CollectionWriter::create(&path).unwrap();
+    // Two corners, one won and one lost by the first player, and an edge that wins
+    writer.write_game(&resigned_game(Coordinates::new(2, 0, 0), 1)).unwrap();
+    writer.write_game(&resigned_game(Coordinates::new(0, 2, 0), 0)).unwrap();
+    writer.write_game(&resigned_game(Coordinates::new(0, 1, 1), 1)).unwrap();
+    drop(writer);
+
+    let mut args = OpeningsArgs {
+        collection: path.clone(),
+        min_games: 1,
+        sort: OpeningSort::WinRate,
+        json: false,
+    };
+    let stats = collection_openings(&args).unwrap();
+    let summary: Vec<_> = stats.iter().map(|s| (s.coords, s.games, s.first_player_wins)).collect();
+    assert_eq!(
+        summary,
+        [(Coordinates::new(1, 1, 0), 1, 1), (Coordinates::new(2, 0, 0), 2, 1)]
+    );
+    assert_eq!(stats[1].win_rate(), 0.5);
+    assert!(run_openings(&args).is_ok());
+
+    args.min_games = 2;
+    assert_eq!(collection_openings(&args).unwrap().len(), 1);
+
+    std::fs::write(&path, "not a game\n").unwrap();
+    assert!(collection_openings(&args).is_err());
+}
+
+#[test]
+fn test_cli_args_ytp() {
+    let args = CliArgs::try_parse_from(["gamey", "ytp", "--bot", "mcts_bot"]).unwrap();
+    match args.command {
+        Some(CliCommand::Ytp(y)) => assert_eq!(y.bot, "mcts_bot"),
+        other => panic!("Expected ytp subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_run_ytp_with_unknown_bot_fails() {
+    let args = gamey::YtpArgs {
+        bot: "no_such_bot".to_string(),
+    };
+    assert!(gamey::run_ytp(&args).unwrap_err().to_string().contains("no_such_bot"));
+}
+
+
+#[test]
+fn test_cli_args_validate() {
+    let args = CliArgs::try_parse_from(["gamey", "validate", "position.yen"]).unwrap();
+    match args.command {
+        Some(CliCommand::Validate(v)) => assert_eq!(v.file, std::path::PathBuf::from("position.yen")),
+        other => panic!("Expected validate subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_validate_reports_three_problems() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("broken.yen");
+    // A bad character, a short row and a turn that does not match the stones
+    std::fs::write(
+        &path,
+        r#"{"size": 4, "turn": 1, "players": ["B", "R"], "layout": "B/R./..X/.."}"#,
+    )
+    .unwrap();
+
+    let issues = gamey::yen::validate_file(&path).unwrap();
+    assert_eq!(issues.len(), 3, "{:?}", issues);
+    assert!(issues.contains(&YenIssue::InvalidChar { char: 'X', row: 2, col: 2, text: "..X".to_string() }));
+    assert!(issues.contains(&YenIssue::RowLength { row: 3, expected: 4, found: 2, text: "..".to_string() }));
+    assert!(issues.contains(&YenIssue::TurnMismatch { turn: 1, expected: 0 }));
+
+    assert!(run_validate(&ValidateArgs { file: path }).is_err());
+}
+
+#[test]
+fn test_validate_accepts_a_saved_game() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("game.yen");
+    gamey::GameY::new(4).save_to_file(&path).unwrap();
+
+    assert!(gamey::yen::validate_file(&path).unwrap().is_empty());
+    assert!(run_validate(&ValidateArgs { file: path }).is_ok());
+}
+
+#[test]
+fn test_cli_args_puzzle() {
+    let args = CliArgs::try_parse_from(["gamey", "puzzle", "puzzles"]).unwrap();
+    match args.command {
+        Some(CliCommand::Puzzle(p)) => assert_eq!(p.dir, std::path::PathBuf::from("puzzles")),
+        other => panic!("Expected puzzle subcommand, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_puzzle_directory_is_verified() {
+    use gamey::puzzle::{Puzzle, winning_moves};
+    use gamey::{GameY, PlayerId, PuzzleArgs, run_puzzle};
+
+    let dir = tempfile::tempdir().unwrap();
+    let empty = GameY::new(2);
+    let puzzle = Puzzle {
+        position: (&empty).into(),
+        to_move: PlayerId::new(0),
+        solutions: winning_moves(&empty, 2),
+        depth: 2,
+        unique: true,
+    };
+    std::fs::write(dir.path().join("a.json"), serde_json::to_string(&puzzle).unwrap()).unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not a puzzle").unwrap();
+    let args = PuzzleArgs { dir: dir.path().to_path_buf() };
+    assert!(run_puzzle(&args).is_ok());
+
+    let wrong = Puzzle { depth: 1, ..puzzle };
+    std::fs::write(dir.path().join("b.json"), serde_json::to_string(&wrong).unwrap()).unwrap();
+    let err = run_puzzle(&args).unwrap_err();
+    assert!(err.to_string().contains("1 of 2"), "{}", err);
+}
+
+#[test]
+fn test_cli_args_dot() {
+    let args = CliArgs::try_parse_from(["gamey", "dot", "--size", "4"]).unwrap();
+    match args.command {
+        Some(CliCommand::Dot(d)) => {
+            assert_eq!(d.size, 4);
+            assert_eq!(d.file, None);
+        }
+        other => panic!("Expected dot subcommand, got {:?}", other),
+    }
+    assert!(CliArgs::try_parse_from(["gamey", "dot", "game.yen", "--size", "4"]).is_err());
+}
+
+#[test]
+fn test_run_dot_reads_a_saved_game() {
+    use gamey::{DotArgs, GameY, run_dot};
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("game.yen");
+    GameY::new(3).save_to_file(&file).unwrap();
+    assert!(run_dot(&DotArgs { file: Some(file), size: 5 }).is_ok());
+    let missing = DotArgs {
+        file: Some(dir.path().join("missing.yen")),
+        size: 5,
+    };
+    assert!(run_dot(&missing).is_err());
+}
+
+#[test]
+fn test_cli_args_analyze() {
+    let args = CliArgs::try_parse_from(["gamey", "analyze", "game.yen", "--bot", "minimax_bot", "--top", "3", "--json"]).unwrap();
+    match args.command {
+        Some(CliCommand::Analyze(a)) => {
+            assert_eq!(a.file, std::path::PathBuf::from("game.yen"));
+            assert_eq!((a.bot.as_str(), a.top, a.json), ("minimax_bot", 3, true));
+        }
+        other => panic!("Expected analyze subcommand, got {:?}", other),
+    }
+    let args = CliArgs::try_parse_from(["gamey", "analyze", "game.yen"]).unwrap();
+    assert!(matches!(args.command, Some(CliCommand::Analyze(a)) if a.bot == "mcts_bot" && a.top == 5 && !a.json));
+}
+
+#[test]
+fn test_analyze_reports_a_win_in_one() {
+    use gamey::{AnalyzeArgs, analyze_file, run_analyze};
+
+    // Blue owns the bottom row but its left corner, and wins at 3 or 6
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("position.yen");
+    std::fs::write(&file, r#"{"size":4,"turn":0,"players":["B","R"],"layout":"R/.R/..R/.BBB"}"#).unwrap();
+    let args = AnalyzeArgs {
+        file: file.clone(),
+        bot: "minimax_bot".to_string(),
+        top: 2,
+        json: true,
+    };
+    let report = serde_json::to_value(analyze_file(&args).unwrap()).unwrap();
+    assert_eq!(report["player"], 0);
+    assert_eq!(report["finished"], false);
+    assert_eq!(report["bot"], "minimax_bot");
+    assert_eq!(report["connection_distances"], serde_json::json!([1, 3]));
+    assert_eq!(report["winning_move"], serde_json::json!({"x": 0, "y": 0, "z": 3}));
+    assert_eq!(report["must_block"], serde_json::json!([]));
+    let top: Vec<u64> = report["top_moves"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|candidate| candidate["index"].as_u64().unwrap())
+        .collect();
+    assert_eq!(top, vec![3, 6]);
+    assert_eq!(report["top_moves"][0]["score"], 1000.0);
+    assert!([3, 6].contains(&report["bot_move"]["index"].as_u64().unwrap()));
+    assert!(run_analyze(&args).is_ok());
+
+    let unknown = AnalyzeArgs {
+        bot: "deep_blue".to_string(),
+        ..args
+    };
+    assert!(analyze_file(&unknown).unwrap_err().to_string().contains("deep_blue"));
+}
+
+#[test]
+fn test_cli_args_convert() {
+    use gamey::ConvertFormat;
+
+    let args = CliArgs::try_parse_from([
+        "gamey", "convert", "games", "out", "--from", "moves", "--to", "record", "--size", "5", "--recursive",
+    ])
+    .unwrap();
+    match args.command {
+        Some(CliCommand::Convert(c)) => {
+            assert_eq!((c.input, c.output), ("games".into(), "out".into()));
+            assert_eq!((c.from, c.to), (Some(ConvertFormat::Moves), Some(ConvertFormat::Record)));
+            assert_eq!((c.size, c.recursive), (Some(5), true));
+        }
+        other => panic!("Expected convert subcommand, got {:?}", other),
+    }
+}
+
+fn convert_args(input: &std::path::Path, output: &std::path::Path) -> gamey::ConvertArgs {
+    gamey::ConvertArgs {
+        input: input.to_path_buf(),
+        output: output.to_path_buf(),
+        from: None,
+        to: None,
+        size: None,
+        recursive: false,
+    }
+}
+
+#[test]
+fn test_convert_round_trip_keeps_the_layout() {
+    use gamey::{GameY, YEN, run_convert};
+
+    let dir = tempfile::tempdir().unwrap();
+    let original = r#"{"size":4,"turn":0,"players":["B","R"],"layout":"B/.R/B.R/...."}"#;
+    let yen = dir.path().join("game.yen");
+    std::fs::write(&yen, original).unwrap();
+    let record = dir.path().join("game.ygr");
+    let sgf = dir.path().join("game.sgf");
+    let back = dir.path().join("back.yen");
+    run_convert(&convert_args(&yen, &record)).unwrap();
+    run_convert(&convert_args(&record, &sgf)).unwrap();
+    run_convert(&convert_args(&sgf, &back)).unwrap();
+
+    assert_eq!(GameY::load_from_file(&record).unwrap().history().len(), 4);
+    let original: YEN = serde_json::from_str(original).unwrap();
+    let back: YEN = serde_json::from_str(&std::fs::read_to_string(&back).unwrap()).unwrap();
+    assert_eq!(back.layout(), original.layout());
+    assert_eq!(back.turn(), original.turn());
+}
+
+#[test]
+fn test_convert_errors_name_the_file_and_format() {
+    use gamey::run_convert;
+
+    let dir = tempfile::tempdir().unwrap();
+    let broken = dir.path().join("broken.sgf");
+    std::fs::write(&broken, "not an sgf").unwrap();
+    let error = run_convert(&convert_args(&broken, &dir.path().join("out.yen")))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("broken.sgf"), "{}", error);
+    assert!(error.contains("SGF"), "{}", error);
+
+    // Blue has two stones and Red none: they cannot have taken turns
+    let uneven = dir.path().join("uneven.yen");
+    std::fs::write(&uneven, r#"{"size":3,"turn":1,"players":["B","R"],"layout":"B/B./..."}"#).unwrap();
+    let error = run_convert(&convert_args(&uneven, &dir.path().join("out.ygr")))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("uneven.yen") && error.contains("record"), "{}", error);
+
+    let unknown = run_convert(&convert_args(&uneven, &dir.path().join("out.png"))).unwrap_err();
+    assert!(unknown.to_string().contains("--to"), "{}", unknown);
+}
+
+#[test]
+fn test_convert_directory_recursively() {
+    use gamey::{ConvertFormat, GameY, run_convert};
+
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in");
+    std::fs::create_dir_all(input.join("nested")).unwrap();
+    std::fs::write(input.join("a.txt"), "1. a1 b2\n").unwrap();
+    std::fs::write(input.join("nested/b.txt"), "1. c3\n").unwrap();
+    std::fs::write(input.join("notes.md"), "not a game").unwrap();
+    let output = dir.path().join("out");
+
+    let mut args = convert_args(&input, &output);
+    assert!(run_convert(&args).is_err(), "a directory needs --to");
+    args.to = Some(ConvertFormat::Record);
+    args.size = Some(4);
+    run_convert(&args).unwrap();
+    assert!(output.join("a.ygr").exists());
+    assert!(!output.join("nested").exists());
+
+    args.recursive = true;
+    run_convert(&args).unwrap();
+    assert_eq!(GameY::load_from_file(output.join("nested/b.ygr")).unwrap().history().len(), 1);
+    assert!(!output.join("notes.ygr").exists());
+}
+
+#[test]
+fn test_cli_args_replay() {
+    use std::time::Duration;
+
+    let args = CliArgs::try_parse_from(["gamey", "replay", "game.ygr", "--auto", "--delay", "250ms"]).unwrap();
+    match args.command {
+        Some(CliCommand::Replay(r)) => {
+            assert_eq!(r.file, std::path::PathBuf::from("game.ygr"));
+            assert_eq!((r.auto, r.delay), (true, Duration::from_millis(250)));
+        }
+        other => panic!("Expected replay subcommand, got {:?}", other),
+    }
+    let args = CliArgs::try_parse_from(["gamey", "replay", "game.ygr"]).unwrap();
+    assert!(matches!(args.command, Some(CliCommand::Replay(r)) if !r.auto && r.delay == Duration::from_millis(500)));
+    assert!(CliArgs::try_parse_from(["gamey", "replay", "game.ygr", "--delay", "soon"]).is_err());
+}
+
+#[test]
+fn test_parse_delay() {
+    use gamey::parse_delay;
+    use std::time::Duration;
+
+    assert_eq!(parse_delay("500ms"), Ok(Duration::from_millis(500)));
+    assert_eq!(parse_delay("2s"), Ok(Duration::from_secs(2)));
+    assert_eq!(parse_delay("1.5s"), Ok(Duration::from_millis(1500)));
+    assert_eq!(parse_delay("40"), Ok(Duration::from_millis(40)));
+    assert!(parse_delay("-1s").is_err());
+    assert!(parse_delay("ms").is_err());
+}
@@ -0,0 +1,78 @@
+// The client only exists with the `client` feature
+#![cfg(feature = "client")]
+
+//! Tests of the typed client against a server listening on a free local port.
+
+use gamey::client::{BotServerClient, ClientError};
+use gamey::{RandomBot, YBotRegistry, YEN, create_router, state::AppState};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Starts a server with only the random bot on a free port and returns a client of it.
+async fn start_server() -> BotServerClient {
+    let state = AppState::new(YBotRegistry::new().with_bot(Arc::new(RandomBot)));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = create_router(state).into_make_service_with_connect_info::<SocketAddr>();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    BotServerClient::new(format!("http://{}", addr))
+}
+
+#[tokio::test]
+async fn test_status_and_game_state() {
+    let client = start_server().await;
+    assert_eq!(client.status().await.unwrap(), "OK");
+
+    let reset = client.reset(4).await.unwrap();
+    assert_eq!(reset.board.size(), 4);
+    let state = client.game_state().await.unwrap();
+    assert_eq!(state.board.layout(), reset.board.layout());
+}
+
+#[tokio::test]
+async fn test_execute_move_gets_the_bot_answer() {
+    let client = start_server().await;
+    client.reset(3).await.unwrap();
+
+    let reply = client.execute_move(0).await.unwrap();
+    assert!(reply.bot_move.is_some());
+    assert_eq!(client.game_state().await.unwrap().board.layout(), reply.board.layout());
+
+    match client.execute_move(99).await {
+        Err(ClientError::Api { status, error }) => {
+            assert!(status.is_client_error(), "{}", status);
+            assert!(!error.message.is_empty());
+        }
+        other => panic!("Expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_choose_maps_error_bodies() {
+    let client = start_server().await;
+    let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
+
+    let response = client.choose("v1", "random_bot", &yen).await.unwrap();
+    assert_eq!(response.bot_id, "random_bot");
+    assert_eq!(response.api_version, "v1");
+
+    match client.choose("v1", "deep_blue", &yen).await {
+        Err(ClientError::Api { status, error }) => {
+            assert_eq!(status.as_u16(), 404);
+            assert_eq!(error.code.as_deref(), Some("UNKNOWN_BOT"));
+            assert_eq!(error.bot_id.as_deref(), Some("deep_blue"));
+        }
+        other => panic!("Expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_unreachable_server_is_a_transport_error() {
+    // Nothing listens on the port once the listener is dropped
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let error = BotServerClient::new(format!("http://{}", addr)).status().await.unwrap_err();
+    assert!(matches!(error, ClientError::Transport { .. }), "{:?}", error);
+    assert!(error.to_string().contains(&addr.to_string()));
+}
@@ -1,5 +1,6 @@
 use gamey::{
-    Coordinates, GameAction, GameStatus, GameY, GameYError, Movement, PlayerId, RenderOptions, YEN,
+    Coordinates, GameAction, GameFormat, GameStatus, GameY, GameYError, Movement, PlayerId,
+    RenderOptions, WinReason, YEN,
 };
 use std::fs;
 use tempfile::tempdir;
@@ -165,7 +166,7 @@ fn test_player_0_wins_by_connecting_three_sides() {
 
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(0));
         }
         _ => panic!("Game should be finished"),
@@ -210,7 +211,7 @@ fn test_player_1_wins() {
 
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(1));
         }
         _ => panic!("Game should be finished with player 1 as winner"),
@@ -229,7 +230,7 @@ fn test_single_cell_board_instant_win() {
 
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(0));
         }
         _ => panic!("Game should be finished"),
@@ -264,7 +265,7 @@ fn test_size_2_board_win() {
 
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(0));
         }
         _ => panic!("Game should be finished"),
@@ -373,7 +374,7 @@ fn test_resign_ends_game_with_opponent_winning() {
 
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(1));
         }
         _ => panic!("Game should be finished"),
@@ -400,7 +401,7 @@ fn test_player_1_resign_makes_player_0_win() {
 
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(0));
         }
         _ => panic!("Game should be finished with player 0 as winner"),
@@ -444,6 +445,49 @@ fn test_swap_after_opening_move() {
     assert!(!game.check_game_over());
 }
 
+#[test]
+fn test_lose_on_time_makes_opponent_win() {
+    let mut game = GameY::new(5);
+
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(4, 0, 0),
+    })
+    .unwrap();
+
+    game.lose_on_time(PlayerId::new(1));
+
+    assert!(game.check_game_over());
+    assert_eq!(game.lost_on_time(), Some(PlayerId::new(1)));
+    match game.status() {
+        GameStatus::Finished { winner, .. } => {
+            assert_eq!(*winner, PlayerId::new(0));
+        }
+        _ => panic!("Game should be finished with player 0 as winner"),
+    }
+}
+
+#[test]
+fn test_lose_on_time_after_game_over_is_ignored() {
+    let mut game = GameY::new(5);
+
+    game.add_move(Movement::Action {
+        player: PlayerId::new(0),
+        action: GameAction::Resign,
+    })
+    .unwrap();
+
+    game.lose_on_time(PlayerId::new(1));
+
+    assert_eq!(game.lost_on_time(), None);
+    match game.status() {
+        GameStatus::Finished { winner, .. } => {
+            assert_eq!(*winner, PlayerId::new(1));
+        }
+        _ => panic!("Game should be finished"),
+    }
+}
+
 // ============================================================================
 // YEN Serialization Tests
 // ============================================================================
@@ -486,6 +530,57 @@ fn test_yen_round_trip_with_moves() {
 
     let yen_reloaded: YEN = (&loaded_game).into();
     assert_eq!(yen.layout(), yen_reloaded.layout());
+    assert!(
+        game.diff(&loaded_game).unwrap().is_empty(),
+        "{}",
+        game.render_diff(&loaded_game, &RenderOptions::default())
+    );
+}
+
+#[test]
+fn test_yen_custom_player_symbols_round_trip() {
+    let yen = YEN::new(3, 1, vec!['X', 'O'], "X/O./X..".to_string());
+    let game = GameY::try_from(yen.clone()).unwrap();
+
+    assert_eq!(game.player_symbols(), vec!['X', 'O']);
+    assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+    assert_eq!(game.available_cells().len(), 3);
+    assert_eq!(YEN::from(&game), yen);
+
+    // The symbols survive moves, a reset and the result of the game
+    let mut game = game;
+    game.add_move(Movement::Action {
+        player: PlayerId::new(1),
+        action: GameAction::Resign,
+    })
+    .unwrap();
+    let finished = YEN::from(&game);
+    assert_eq!(finished.players(), &['X', 'O']);
+    assert_eq!(finished.result(), Some("X+resign"));
+    assert_eq!(GameY::try_from(finished.clone()).map(|g| YEN::from(&g)).unwrap(), finished);
+    game.reset();
+    assert_eq!(YEN::from(&game).layout(), "./../...");
+    assert_eq!(YEN::from(&game).players(), &['X', 'O']);
+}
+
+#[test]
+fn test_yen_duplicate_or_reserved_player_symbols_are_rejected() {
+    for players in [vec!['X', 'X'], vec!['X', '.'], vec!['/', 'O']] {
+        let yen = YEN::new(2, 0, players.clone(), "./..".to_string());
+        assert!(
+            matches!(GameY::try_from(yen), Err(GameYError::InvalidFormat { .. })),
+            "{:?}",
+            players
+        );
+        assert!(GameY::new(2).with_player_symbols(players).is_err());
+    }
+    assert!(matches!(
+        GameY::new(2).with_player_symbols(vec!['X', 'O', 'Z']),
+        Err(GameYError::InvalidNumPlayers { num_players: 3, expected: 2 })
+    ));
+    // The standard symbols are accepted as well
+    let game = GameY::new(2).with_player_symbols(vec!['B', 'R']).unwrap();
+    assert_eq!(game.player_symbols(), vec!['B', 'R']);
 }
 
 #[test]
@@ -505,6 +600,115 @@ fn test_yen_preserves_board_state() {
     assert_eq!(game.available_cells().len(), 2);
 }
 
+#[test]
+fn test_yen_turn_decides_the_next_player() {
+    // Red played first after a swap, so Blue is to move even though Red's stone comes later
+    let yen = YEN::new(2, 0, vec!['B', 'R'], "B/.R".to_string());
+    let game = GameY::try_from(yen).unwrap();
+    assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+
+    let yen = YEN::new(2, 1, vec!['B', 'R'], "B/.R".to_string());
+    let game = GameY::try_from(yen).unwrap();
+    assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+}
+
+/// A size 4 game with one stone each, where `player` resigns.
+fn resigned_game(player: u32) -> GameY {
+    let mut game = GameY::new(4);
+    for (id, coords) in [(0, Coordinates::new(3, 0, 0)), (1, Coordinates::new(1, 1, 1))] {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(id),
+            coords,
+        })
+        .unwrap();
+    }
+    game.add_move(Movement::Action {
+        player: PlayerId::new(player),
+        action: GameAction::Resign,
+    })
+    .unwrap();
+    game
+}
+
+#[test]
+fn test_yen_keeps_resignation_after_reload() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("resigned.yen");
+    resigned_game(1).save_to_file(&path).unwrap();
+
+    let mut loaded = GameY::load_from_file(&path).unwrap();
+    assert!(matches!(
+        loaded.status(),
+        GameStatus::Finished { winner, .. } if *winner == PlayerId::new(0)
+    ));
+    let late = loaded.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(0, 0, 3),
+    });
+    assert!(matches!(late, Err(GameYError::GameOver { .. })));
+
+    let yen: YEN = (&loaded).into();
+    assert_eq!(yen.result(), Some("B+resign"));
+}
+
+#[test]
+fn test_yen_result_names_winner_and_reason() {
+    let yen: YEN = (&resigned_game(0)).into();
+    assert_eq!(yen.result(), Some("R+resign"));
+    assert!(serde_json::to_string(&yen).unwrap().contains(r#""result":"R+resign""#));
+
+    let mut on_time = GameY::new(3);
+    on_time.lose_on_time(PlayerId::new(0));
+    let yen: YEN = (&on_time).into();
+    assert_eq!(yen.result(), Some("R+time"));
+    let reloaded = GameY::try_from(yen).unwrap();
+    assert_eq!(reloaded.lost_on_time(), Some(PlayerId::new(0)));
+
+    let mut forfeited = GameY::new(3);
+    forfeited.forfeit(PlayerId::new(0));
+    let yen: YEN = (&forfeited).into();
+    assert_eq!(yen.result(), Some("R+forfeit"));
+    let reloaded = GameY::try_from(yen).unwrap();
+    assert_eq!(reloaded.forfeited(), Some(PlayerId::new(0)));
+    assert_eq!(reloaded.status().reason(), Some(WinReason::Forfeit));
+
+    let connected = GameY::try_from(YEN::new(2, 0, vec!['B', 'R'], "B/BR".to_string())).unwrap();
+    assert!(connected.check_game_over());
+    assert_eq!(YEN::from(&connected).result(), Some("B+connect"));
+}
+
+#[test]
+fn test_yen_without_result_stays_ongoing() {
+    let yen: YEN = serde_json::from_str(
+        r#"{"size": 3, "turn": 0, "players": ["B","R"], "layout": "B/R./..."}"#,
+    )
+    .unwrap();
+    assert_eq!(yen.result(), None);
+    let game = GameY::try_from(yen).unwrap();
+    assert!(!game.check_game_over());
+    assert!(!serde_json::to_string(&YEN::from(&game)).unwrap().contains("result"));
+}
+
+#[test]
+fn test_yen_invalid_results_are_rejected() {
+    let with_result = |layout: &str, result: &str| {
+        GameY::try_from(YEN::new(3, 0, vec!['B', 'R'], layout.to_string()).with_result(result))
+    };
+    for result in ["B", "G+resign", "BR+resign", "B+draw", "B+connect"] {
+        assert!(
+            matches!(with_result("B/R./...", result), Err(GameYError::InvalidYENResult { .. })),
+            "{} was accepted",
+            result
+        );
+    }
+    // The layout is won by B, so R cannot be the winner
+    assert!(matches!(
+        with_result("B/BR/BR.", "R+resign"),
+        Err(GameYError::InvalidYENResult { .. })
+    ));
+    assert!(with_result("B/BR/BR.", "B+connect").is_ok());
+}
+
 #[test]
 fn test_yen_invalid_layout_wrong_rows() {
     let yen_str = r#"{
@@ -545,10 +749,12 @@ fn test_yen_invalid_layout_wrong_cells_in_row() {
             expected,
             found,
             line,
+            text,
         } => {
             assert_eq!(expected, 2);
             assert_eq!(found, 3);
             assert_eq!(line, 1);
+            assert_eq!(text, "RBB");
         }
         other => panic!("Expected InvalidYENLayoutLine error, got {:?}", other),
     }
@@ -568,10 +774,11 @@ fn test_yen_invalid_character() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        GameYError::InvalidCharInLayout { char, row, col } => {
+        GameYError::InvalidCharInLayout { char, row, col, text } => {
             assert_eq!(char, 'X');
             assert_eq!(row, 0);
             assert_eq!(col, 0);
+            assert_eq!(text, "X");
         }
         other => panic!("Expected InvalidCharInLayout error, got {:?}", other),
     }
@@ -637,11 +844,137 @@ fn test_load_invalid_json_file() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        GameYError::SerdeError { error: _ } => {}
-        other => panic!("Expected SerdeError, got {:?}", other),
+        GameYError::CorruptFile { path, error: _ } => {
+            assert!(path.ends_with("invalid.yen"));
+        }
+        other => panic!("Expected CorruptFile, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_load_truncated_file_is_corrupt() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("truncated.yen");
+    let mut game = GameY::new(3);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(2, 0, 0),
+    })
+    .unwrap();
+    game.save_to_file(&file_path).unwrap();
+
+    // Simulate a crash in the middle of a non-atomic write
+    let content = fs::read(&file_path).unwrap();
+    fs::write(&file_path, &content[..content.len() / 2]).unwrap();
+
+    match GameY::load_from_file(&file_path).unwrap_err() {
+        GameYError::CorruptFile { path, error } => {
+            assert!(path.ends_with("truncated.yen"));
+            assert!(error.is_eof());
+        }
+        other => panic!("Expected CorruptFile, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_save_replaces_file_without_leftovers() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("game.yen");
+    fs::write(&file_path, "old content").unwrap();
+
+    GameY::new(4).save_to_file(&file_path).unwrap();
+
+    let loaded = GameY::load_from_file(&file_path).unwrap();
+    assert_eq!(loaded.board_size(), 4);
+    let entries: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+}
+
+#[test]
+fn test_save_to_writer_and_load_from_reader() {
+    let mut game = GameY::new(3);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(1, 1, 0),
+    })
+    .unwrap();
+
+    let mut buffer = Vec::new();
+    game.save_to_writer(&mut buffer).unwrap();
+    let loaded = GameY::load_from_reader(buffer.as_slice()).unwrap();
+
+    let yen_original: YEN = (&game).into();
+    let yen_loaded: YEN = (&loaded).into();
+    assert_eq!(yen_original.layout(), yen_loaded.layout());
+}
+
+fn sample_finished_game() -> GameY {
+    let mut game = GameY::new(4);
+    let moves = [
+        (0, Coordinates::new(1, 1, 1)),
+        (1, Coordinates::new(3, 0, 0)),
+        (0, Coordinates::new(0, 2, 1)),
+    ];
+    for (player, coords) in moves {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+    game.add_move(Movement::Action {
+        player: PlayerId::new(1),
+        action: GameAction::Resign,
+    })
+    .unwrap();
+    game
+}
+
+#[test]
+fn test_same_game_in_every_format_has_the_same_layout() {
+    let dir = tempdir().unwrap();
+    let game = sample_finished_game();
+    let expected: YEN = (&game).into();
+
+    for format in GameFormat::ALL {
+        let path = dir.path().join(format!("game.{}", format.extension()));
+        game.save_to_file(&path).unwrap();
+        let loaded = GameY::load_from_file(&path).unwrap();
+        let yen: YEN = (&loaded).into();
+        assert_eq!(yen.layout(), expected.layout(), "layout differs for {}", format);
     }
 }
 
+#[test]
+fn test_load_sniffs_format_of_unknown_extension() {
+    let dir = tempdir().unwrap();
+    let game = sample_finished_game();
+    for format in [GameFormat::Sgf, GameFormat::Record] {
+        let path = dir.path().join(format!("{}.txt", format.extension()));
+        game.save_to_file_as(&path, format).unwrap();
+        let loaded = GameY::load_from_file(&path).unwrap();
+        // YEN has no history, so only SGF and records can be detected this way
+        assert_eq!(loaded.history().len(), 4);
+    }
+}
+
+#[test]
+fn test_load_as_forced_format_reports_it() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("game.yen");
+    sample_finished_game().save_to_file(&path).unwrap();
+
+    match GameY::load_from_file_as(&path, GameFormat::Record).unwrap_err() {
+        GameYError::InvalidFormat { format, message } => {
+            assert_eq!(format, GameFormat::Record);
+            assert!(message.contains("game.yen"));
+        }
+        other => panic!("Expected InvalidFormat, got {:?}", other),
+    }
+    let err = GameY::load_from_file_as(&path, GameFormat::Sgf).unwrap_err();
+    assert!(err.to_string().starts_with("Invalid SGF data"));
+}
+
 // ============================================================================
 // Coordinate System Tests
 // ============================================================================
@@ -737,6 +1070,10 @@ fn test_render_empty_board() {
         show_3d_coords: false,
         show_idx: false,
         show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
     };
     let rendered = game.render(&options);
 
@@ -762,6 +1099,10 @@ fn test_render_with_pieces() {
         show_3d_coords: false,
         show_idx: false,
         show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
     };
     let rendered = game.render(&options);
 
@@ -776,6 +1117,10 @@ fn test_render_with_3d_coords() {
         show_3d_coords: true,
         show_idx: false,
         show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
     };
     let rendered = game.render(&options);
 
@@ -791,6 +1136,10 @@ fn test_render_with_indices() {
         show_3d_coords: false,
         show_idx: true,
         show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: None,
+        highlight_last_move: false,
     };
     let rendered = game.render(&options);
 
@@ -798,6 +1147,162 @@ fn test_render_with_indices() {
     assert!(rendered.contains("(0)") || rendered.contains("(1)") || rendered.contains("(2)"));
 }
 
+#[test]
+fn test_render_heat_map() {
+    let mut game = GameY::new(2);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(1),
+        coords: Coordinates::new(1, 0, 0),
+    })
+    .unwrap();
+    let options = RenderOptions {
+        show_3d_coords: false,
+        show_idx: false,
+        show_colors: false,
+        show_comment: false,
+        show_labels: false,
+        heat_map: Some(vec![0.7, 1.0, 0.0]),
+        highlight_last_move: false,
+    };
+    let rendered = game.render(&options);
+
+    // The stone keeps its symbol, the empty cells are shaded
+    let rows: Vec<&str> = rendered.lines().skip(1).collect();
+    assert_eq!(rows, ["  1", "@    "]);
+}
+
+#[test]
+fn test_render_highlights_the_last_move() {
+    let game = GameY::from_move_text(3, "1. a1 b3").unwrap();
+    let options = RenderOptions {
+        show_idx: false,
+        show_colors: false,
+        highlight_last_move: true,
+        ..Default::default()
+    };
+    let rows: Vec<String> = game.render(&options).lines().skip(1).map(str::to_string).collect();
+    assert_eq!(rows, ["    0", "  .   .", ".   \x1b[7m1\x1b[27m   ."]);
+
+    // Only the last move is highlighted, and only when asked for
+    assert_eq!(game.render(&options).matches("\x1b[7m").count(), 1);
+    assert!(!game.render(&RenderOptions::default()).contains("\x1b[7m"));
+}
+
+fn render_lines(game: &GameY, show_labels: bool, show_idx: bool) -> Vec<String> {
+    let options = RenderOptions {
+        show_3d_coords: false,
+        show_idx,
+        show_colors: false,
+        show_comment: false,
+        show_labels,
+        heat_map: None,
+        highlight_last_move: false,
+    };
+    game.render(&options).lines().skip(1).map(str::to_string).collect()
+}
+
+#[test]
+fn test_render_labels_size_5() {
+    // The labels read as the move text: b5 is the second cell of the bottom row
+    let game = GameY::from_move_text(5, "1. b5 a1").unwrap();
+    assert_eq!(
+        render_lines(&game, false, false),
+        [
+            "        1",
+            "      .   .",
+            "    .   .   .",
+            "  .   .   .   .",
+            ".   0   .   .   .",
+        ]
+    );
+    assert_eq!(
+        render_lines(&game, true, false),
+        [
+            "1         1",
+            "2       .   .",
+            "3     .   .   .",
+            "4   .   .   .   .",
+            "5 .   0   .   .   .",
+            "  a   b   c   d   e",
+        ]
+    );
+}
+
+#[test]
+fn test_render_labels_with_indices() {
+    let game = GameY::from_move_text(5, "1. b5 a1").unwrap();
+    let lines = render_lines(&game, true, true);
+    assert_eq!(lines[8], "5 .(10)    0(11)    .(12)    .(13)    .(14)");
+    assert_eq!(lines[10], "  a        b        c        d        e");
+}
+
+#[test]
+fn test_render_labels_size_12() {
+    let game = GameY::from_move_text(12, "1. b12 a1").unwrap();
+    let plain = render_lines(&game, false, false);
+    assert_eq!(plain[0], "                      1");
+    assert_eq!(plain[11], ".   0   .   .   .   .   .   .   .   .   .   .");
+    assert_eq!(plain.len(), 12);
+    assert_eq!(
+        render_lines(&game, true, false),
+        [
+            " 1                       1",
+            " 2                     .   .",
+            " 3                   .   .   .",
+            " 4                 .   .   .   .",
+            " 5               .   .   .   .   .",
+            " 6             .   .   .   .   .   .",
+            " 7           .   .   .   .   .   .   .",
+            " 8         .   .   .   .   .   .   .   .",
+            " 9       .   .   .   .   .   .   .   .   .",
+            "10     .   .   .   .   .   .   .   .   .   .",
+            "11   .   .   .   .   .   .   .   .   .   .   .",
+            "12 .   0   .   .   .   .   .   .   .   .   .   .",
+            "   a   b   c   d   e   f   g   h   i   j   k   l",
+        ]
+    );
+}
+
+#[test]
+fn test_render_drops_only_the_trailing_spaces() {
+    // The text written before render_to existed, with spaces at the end of the rows
+    let old = "--- Game of Y (Size 3) ---\n1                 1(2,0,0)(0)    \n\n2         .(1,0,1)(1)    .(1,1,0)(2)    \n\n3 .(0,0,2)(3)    0(0,1,1)(4)    .(0,2,0)(5)    \n\n  a              b              c\n";
+    let game = GameY::from_move_text(3, "1. b3 a1").unwrap();
+    let options = RenderOptions {
+        show_3d_coords: true,
+        show_idx: true,
+        show_colors: false,
+        show_comment: false,
+        show_labels: true,
+        heat_map: None,
+        highlight_last_move: false,
+    };
+    let expected: String = old.lines().map(|line| format!("{}\n", line.trim_end())).collect();
+    assert_eq!(game.render(&options), expected);
+}
+
+#[test]
+fn test_render_to_matches_render_and_its_estimated_len() {
+    let mut game = GameY::from_move_text(11, "1. b11 a1 2. f6 k11").unwrap();
+    game.annotate(game.history().len(), gamey::MoveAnnotation::comment("a quiet move")).unwrap();
+    for flags in 0..64u32 {
+        let options = RenderOptions {
+            show_3d_coords: flags & 1 != 0,
+            show_idx: flags & 2 != 0,
+            show_colors: flags & 4 != 0,
+            show_comment: flags & 8 != 0,
+            show_labels: flags & 16 != 0,
+            heat_map: None,
+            highlight_last_move: flags & 32 != 0,
+        };
+        let mut written = String::new();
+        game.render_to(&mut written, &options).unwrap();
+        assert_eq!(written, game.render(&options));
+        assert_eq!(game.estimated_render_len(&options), written.len(), "flags {:06b}", flags);
+        assert!(written.lines().all(|line| !line.ends_with(' ')), "flags {:06b}", flags);
+    }
+}
+
 // ============================================================================
 // Complex Game Scenarios
 // ============================================================================
@@ -814,9 +1319,7 @@ fn test_full_game_on_size_4_board() {
         (1, Coordinates::new(1, 2, 0)),
         (0, Coordinates::new(1, 0, 2)),
         (1, Coordinates::new(0, 3, 0)),
-        (0, Coordinates::new(0, 0, 3)), // Player 0 now touches side A and B
-        (1, Coordinates::new(0, 2, 1)),
-        (0, Coordinates::new(1, 1, 1)), // Interior cell - connects pieces
+        (0, Coordinates::new(0, 0, 3)), // Player 0 now touches all three sides
     ];
 
     for (player_id, coords) in &moves {
@@ -827,9 +1330,16 @@ fn test_full_game_on_size_4_board() {
         .unwrap();
     }
 
-    // Game might or might not be over depending on the board state
-    // The important thing is all moves executed successfully
-    assert!(game.available_cells().len() < 10);
+    assert!(game.check_game_over());
+    assert_eq!(game.available_cells().len(), 3);
+
+    // Moves after the win are rejected
+    let late = game.add_move(Movement::Placement {
+        player: PlayerId::new(1),
+        coords: Coordinates::new(0, 2, 1),
+    });
+    assert!(matches!(late, Err(GameYError::GameOver { .. })));
+    assert_eq!(game.history().len(), 7);
 }
 
 #[test]
@@ -885,7 +1395,7 @@ fn test_union_find_correctly_merges_components() {
     // Player 0 should now have won by connecting all three sides via side A
     assert!(game.check_game_over());
     match game.status() {
-        GameStatus::Finished { winner } => {
+        GameStatus::Finished { winner, .. } => {
             assert_eq!(*winner, PlayerId::new(0));
         }
         _ => panic!("Player 0 should have won"),
@@ -0,0 +1,24 @@
+// Checks that the game logic does not depend on the `server` feature.
+//
+// CI runs these tests both with the default features and with
+// `cargo test --no-default-features`, so a core module using the server
+// fails to build there.
+
+use gamey::{GameFormat, GameY, RandomBot, YBot, YEN, tournament};
+
+#[test]
+fn test_game_bots_and_formats_work_with_any_features() {
+    let game = tournament::play_game(&RandomBot, &RandomBot, 4);
+    assert!(game.check_game_over());
+    assert!(RandomBot.choose_move(&GameY::new(3)).is_some());
+
+    let dir = tempfile::tempdir().unwrap();
+    for format in GameFormat::ALL {
+        let path = dir.path().join(format!("game.{}", format.extension()));
+        game.save_to_file(&path).unwrap();
+        let loaded = GameY::load_from_file(&path).unwrap();
+        let (a, b): (YEN, YEN) = ((&game).into(), (&loaded).into());
+        assert_eq!(a.layout(), b.layout());
+    }
+}
+
@@ -0,0 +1,66 @@
+// Checks that common programs build from `gamey::prelude` alone.
+//
+// Nothing but the prelude is imported here: a test that needs another path
+// means a type is missing from it.
+
+use gamey::prelude::*;
+use std::sync::Arc;
+
+/// A bot written against the prelude: plays the first empty cell.
+struct FirstEmpty;
+
+impl YBot for FirstEmpty {
+    fn name(&self) -> &str {
+        "first_empty"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        let index = *board.available_cells().iter().min()?;
+        Some(Coordinates::from_index(index, board.board_size()))
+    }
+}
+
+#[test]
+fn test_play_a_game_to_the_end() {
+    let mut game = GameY::new(3);
+    let bot = FirstEmpty;
+    while let GameStatus::Ongoing { next_player } = game.status().clone() {
+        let coords = bot.choose_move(&game).unwrap();
+        game.add_move(Movement::Placement {
+            player: next_player,
+            coords,
+        })
+        .unwrap();
+    }
+    assert!(matches!(game.status(), GameStatus::Finished { .. }));
+}
+
+#[test]
+fn test_resign_and_render() {
+    let mut game = GameY::new(3);
+    game.add_move(Movement::Action {
+        player: PlayerId::new(0),
+        action: GameAction::Resign,
+    })
+    .unwrap();
+    assert_eq!(game.status().winner(), Some(PlayerId::new(1)));
+    assert_eq!(game.status().reason(), Some(WinReason::Resignation));
+    assert!(!game.render(&RenderOptions::default()).is_empty());
+}
+
+#[test]
+fn test_round_trip_through_yen() {
+    let yen: YEN = serde_json::from_str(r#"{"size":3,"turn":1,"players":["B","R"],"layout":"B/../..."}"#).unwrap();
+    let game = GameY::try_from(yen.clone()).unwrap();
+    assert_eq!(YEN::from(&game), yen);
+
+    let error: GameYError = GameY::try_from(YEN::new(3, 0, vec!['B', 'R'], "B/..".to_string())).unwrap_err();
+    assert!(!error.to_string().is_empty());
+}
+
+#[test]
+fn test_register_and_find_a_bot() {
+    let registry = YBotRegistry::new().with_bot(Arc::new(FirstEmpty));
+    let bot = registry.find("first_empty").unwrap();
+    assert_eq!(bot.choose_move(&GameY::new(2)), Some(Coordinates::from_index(0, 2)));
+}
@@ -0,0 +1,135 @@
+// The schemas are only generated with the `schema` feature
+#![cfg(feature = "schema")]
+
+use axum::{body::Body, http::Request};
+use gamey::schema::{all_schemas, export_all, schema};
+use gamey::{
+    Coordinates, ErrorResponse, GameStateResponse, GameY, MoveResponse, Movement, PlayerId, YEN,
+    create_default_state, create_router,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+fn validator<T: schemars::JsonSchema>() -> jsonschema::Validator {
+    let schema = serde_json::to_value(schema::<T>()).unwrap();
+    jsonschema::validator_for(&schema).unwrap()
+}
+
+#[test]
+fn test_known_good_yen_is_valid() {
+    let yen = json!({
+        "size": 3,
+        "turn": 0,
+        "players": ["B", "R"],
+        "layout": "B/BR/.R."
+    });
+    let validator = validator::<YEN>();
+    if let Err(error) = validator.validate(&yen) {
+        panic!("valid YEN rejected: {}", error);
+    }
+}
+
+#[test]
+fn test_yens_written_by_the_engine_are_valid() {
+    let mut game = GameY::new(4);
+    game.add_move(Movement::Placement {
+        player: PlayerId::new(0),
+        coords: Coordinates::new(1, 1, 1),
+    })
+    .unwrap();
+    let validator = validator::<YEN>();
+    for yen in [YEN::from(&game), YEN::from(&GameY::with_players(5, 3))] {
+        let value = serde_json::to_value(&yen).unwrap();
+        assert!(validator.is_valid(&value), "{}", value);
+    }
+}
+
+#[test]
+fn test_yen_constraints_are_in_the_schema() {
+    let validator = validator::<YEN>();
+    let good = json!({"size": 3, "turn": 1, "players": ["B", "R"], "layout": "B/BR/.R."});
+    assert!(validator.is_valid(&good));
+
+    let with = |field: &str, value: Value| {
+        let mut yen = good.clone();
+        yen[field] = value;
+        yen
+    };
+    assert!(!validator.is_valid(&with("size", json!(0))));
+    assert!(!validator.is_valid(&with("turn", json!(6))));
+    assert!(!validator.is_valid(&with("turn", json!(-1))));
+    assert!(!validator.is_valid(&with("layout", json!(["B", "BR"]))));
+    assert!(!validator.is_valid(&with("layout", json!(""))));
+    assert!(!validator.is_valid(&with("players", json!(["B"]))));
+    assert!(!validator.is_valid(&with("players", json!(["BR", "G"]))));
+
+    let mut missing_layout = good.clone();
+    missing_layout.as_object_mut().unwrap().remove("layout");
+    assert!(!validator.is_valid(&missing_layout));
+}
+
+#[test]
+fn test_api_payloads_are_valid() {
+    let response = MoveResponse {
+        api_version: "v1".to_string(),
+        bot_id: "random_bot".to_string(),
+        coords: Coordinates::new(0, 1, 1),
+        position_hash: Some("00000000000000ff".to_string()),
+        board: Some(YEN::from(&GameY::new(3))),
+        status: Some(gamey::GameStatus::Finished {
+            winner: PlayerId::new(1),
+            reason: gamey::WinReason::Connection,
+        }),
+        wins: Some(false),
+        eval: Some(-2.5),
+        stats: Some(gamey::MoveStats {
+            elapsed: std::time::Duration::from_millis(12),
+            nodes: 40,
+            playouts: 300,
+            eval: Some(0.25),
+        }),
+    };
+    assert!(validator::<MoveResponse>().is_valid(&serde_json::to_value(&response).unwrap()));
+
+    let error = ErrorResponse::error("Unknown bot", Some("v1".to_string()), None)
+        .with_code("UNKNOWN_BOT")
+        .with_details(json!({"available": ["random_bot"]}));
+    assert!(validator::<ErrorResponse>().is_valid(&serde_json::to_value(&error).unwrap()));
+
+    assert!(validator::<gamey::MoveRequest>().is_valid(&json!({"index": 3})));
+    assert!(!validator::<gamey::MoveRequest>().is_valid(&json!({"index": "3"})));
+
+    let choose = validator::<gamey::ChooseRequest>();
+    assert!(choose.is_valid(&json!({"size": 5, "moves": [{"player": 0, "index": 3}]})));
+    assert!(choose.is_valid(&serde_json::to_value(YEN::from(&GameY::new(3))).unwrap()));
+    assert!(!choose.is_valid(&json!({"size": 5, "moves": [{"index": 3}]})));
+}
+
+#[tokio::test]
+async fn test_game_endpoint_matches_the_game_state_schema() {
+    let response = create_router(create_default_state())
+        .oneshot(Request::builder().uri("/game").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let value: Value = serde_json::from_slice(&body).unwrap();
+
+    assert!(validator::<GameStateResponse>().is_valid(&value), "{}", value);
+    assert!(serde_json::from_value::<GameStateResponse>(value).is_ok());
+}
+
+#[test]
+fn test_export_all_writes_one_file_per_type() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("schemas");
+    let paths = export_all(&out).unwrap();
+
+    assert_eq!(paths.len(), all_schemas().len());
+    assert!(out.join("yen.schema.json").exists());
+    assert!(out.join("game_state_response.schema.json").exists());
+    for path in paths {
+        let schema: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(jsonschema::validator_for(&schema).is_ok(), "{}", path.display());
+    }
+}
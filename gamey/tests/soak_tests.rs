@@ -0,0 +1,170 @@
+// The HTTP server only exists with the `server` feature
+#![cfg(feature = "server")]
+
+//! Soak test of the server game: many full games played through the HTTP API.
+//!
+//! Every request is mirrored on a local `GameY`, and after each one the board
+//! the server reports must be the local one. A divergence panics with the
+//! requests of the game so far, to replay them by hand.
+
+mod support;
+
+use axum::http::StatusCode;
+use gamey::{Coordinates, GameAction, GameY, MoveDto, Movement, PlayerId, YEN, create_default_state, create_router};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use support::client::TestClient;
+
+/// Games played by the soak test.
+const GAMES: u32 = 100;
+
+/// Seed of the random choices of the test, so a failure can be reproduced.
+const SEED: u64 = 0x5eed;
+
+/// A game played both on the server and locally.
+struct Soak {
+    client: TestClient,
+    local: GameY,
+    /// The requests of the current game, oldest first.
+    log: Vec<String>,
+}
+
+impl Soak {
+    fn diverged(&self, what: &str) -> ! {
+        panic!("{}\nrequests of the game:\n  {}", what, self.log.join("\n  "));
+    }
+
+    /// Checks `reported` and the board of `GET /game` against the local game.
+    ///
+    /// The names of the players are not compared: the server names player 1
+    /// after the bot that answers it.
+    async fn check(&self, reported: &YEN) {
+        let expected = board_of(&YEN::from(&self.local));
+        if board_of(reported) != expected {
+            self.diverged(&format!("the reply has board {:?}, expected {:?}", reported, expected));
+        }
+        let state = self.client.state().await;
+        if board_of(&state.board) != expected {
+            self.diverged(&format!("GET /game has board {:?}, expected {:?}", state.board, expected));
+        }
+    }
+
+    fn play_locally(&mut self, dto: &MoveDto) {
+        let player = PlayerId::new(dto.player);
+        let movement = match (dto.index, &dto.action) {
+            (Some(index), None) => Movement::Placement {
+                player,
+                coords: Coordinates::from_index(index, self.local.board_size()),
+            },
+            (None, Some(action)) => Movement::Action {
+                player,
+                action: action.clone(),
+            },
+            _ => self.diverged(&format!("the server reported the move {:?}", dto)),
+        };
+        if let Err(e) = self.local.add_move(movement) {
+            self.diverged(&format!("the server move {:?} is illegal here: {}", dto, e));
+        }
+    }
+
+    async fn legal_move(&mut self, index: u32) {
+        self.log.push(format!("move {}", index));
+        let reply = match self.client.move_(index).await {
+            Ok(reply) => reply,
+            Err((status, error)) => self.diverged(&format!("legal move rejected with {}: {:?}", status, error)),
+        };
+        if reply.last_move != Some(MoveDto { player: 0, index: Some(index), action: None }) {
+            self.diverged(&format!("the move was reported as {:?}", reply.last_move));
+        }
+        self.play_locally(reply.last_move.as_ref().unwrap());
+        if let Some(bot_move) = &reply.bot_move {
+            self.log.push(format!("  bot answered {:?}", bot_move));
+            self.play_locally(bot_move);
+        }
+        if reply.resigned != (reply.bot_move.as_ref().and_then(|m| m.action.as_ref()) == Some(&GameAction::Resign)) {
+            self.diverged("resigned disagrees with the bot move");
+        }
+        self.check(&reply.board).await;
+    }
+
+    /// A move on an occupied cell, or any move once the game is over, is ignored.
+    async fn ignored_move(&mut self, index: u32) {
+        self.log.push(format!("move {} (ignored)", index));
+        let reply = match self.client.move_(index).await {
+            Ok(reply) => reply,
+            Err((status, error)) => self.diverged(&format!("ignored move rejected with {}: {:?}", status, error)),
+        };
+        if reply.last_move.is_some() || reply.bot_move.is_some() {
+            self.diverged(&format!("ignored move played {:?} and {:?}", reply.last_move, reply.bot_move));
+        }
+        self.check(&reply.board).await;
+    }
+
+    async fn off_board_move(&mut self, index: u32) {
+        self.log.push(format!("move {} (off the board)", index));
+        match self.client.move_(index).await {
+            Err((StatusCode::BAD_REQUEST, error)) if error.code.as_deref() == Some("INVALID_MOVE") => {}
+            other => self.diverged(&format!("off-board move answered {:?}", other.map(|reply| reply.board))),
+        }
+        self.check(&self.client.state().await.board).await;
+    }
+}
+
+/// `yen` without its metadata.
+fn board_of(yen: &YEN) -> YEN {
+    let board = YEN::new(yen.size(), yen.turn(), yen.players().to_vec(), yen.layout().to_string());
+    match yen.result() {
+        Some(result) => board.with_result(result),
+        None => board,
+    }
+}
+
+#[tokio::test]
+async fn test_soak_full_games_keep_server_and_client_in_sync() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut soak = Soak {
+        client: TestClient::new(create_router(create_default_state())),
+        local: GameY::new(3),
+        log: Vec::new(),
+    };
+    for game in 0..GAMES {
+        let size = 3 + game % 7;
+        soak.log = vec![format!("game {} with seed {:#x}: reset {}", game, SEED, size)];
+        let reset = soak.client.reset(size).await;
+        soak.local = GameY::new(size);
+        soak.check(&reset.board).await;
+
+        // Positions to go back to, as the server's share tokens
+        let mut saved: Vec<(String, GameY)> = Vec::new();
+        while !soak.local.check_game_over() {
+            let total = soak.local.total_cells();
+            let occupied: Vec<u32> = (0..total)
+                .filter(|index| !soak.local.available_cells().contains(index))
+                .collect();
+            match rng.random_range(0..10) {
+                0 => soak.off_board_move(total + rng.random_range(0..5)).await,
+                1 if !occupied.is_empty() => {
+                    soak.ignored_move(occupied[rng.random_range(0..occupied.len())]).await
+                }
+                2 if !saved.is_empty() => {
+                    // Go back to an earlier position, the closest the server has to an undo
+                    let (token, position) = saved[rng.random_range(0..saved.len())].clone();
+                    soak.log.push(format!("load-token {}", token));
+                    let loaded = soak.client.load_token(&token).await;
+                    soak.local = position;
+                    soak.check(&loaded).await;
+                }
+                _ => {
+                    if rng.random_bool(0.2) {
+                        saved.push((soak.client.token().await, soak.local.clone()));
+                    }
+                    let available = soak.local.available_cells();
+                    let index = available[rng.random_range(0..available.len())];
+                    soak.legal_move(index).await;
+                }
+            }
+        }
+        let index = rng.random_range(0..soak.local.total_cells());
+        soak.ignored_move(index).await;
+    }
+}
@@ -0,0 +1,83 @@
+//! A typed client for the server game, talking to a router in process.
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::CONTENT_TYPE},
+};
+use gamey::{ErrorResponse, GameStateResponse, MoveResultResponse, PositionToken, YEN};
+use http_body_util::BodyExt;
+use serde::de::DeserializeOwned;
+use tower::ServiceExt;
+
+/// Sends requests about the server game to a router and decodes the replies.
+pub struct TestClient {
+    app: Router,
+}
+
+impl TestClient {
+    /// Creates a client of `app`.
+    pub fn new(app: Router) -> Self {
+        Self { app }
+    }
+
+    /// Starts a new game of `size` with `POST /reset`.
+    pub async fn reset(&self, size: u32) -> GameStateResponse {
+        self.send("POST", "/reset", Some(serde_json::json!({ "size": size })))
+            .await
+            .unwrap_or_else(|(status, error)| panic!("reset failed with {}: {:?}", status, error))
+    }
+
+    /// Plays the human move at cell `index` with `POST /execute-move`.
+    pub async fn move_(&self, index: u32) -> Result<MoveResultResponse, (StatusCode, ErrorResponse)> {
+        self.send("POST", "/execute-move", Some(serde_json::json!({ "index": index })))
+            .await
+    }
+
+    /// Returns the current game from `GET /game`.
+    pub async fn state(&self) -> GameStateResponse {
+        self.send("GET", "/game", None)
+            .await
+            .unwrap_or_else(|(status, error)| panic!("state failed with {}: {:?}", status, error))
+    }
+
+    /// Returns the share token of the current position from `GET /game/token`.
+    pub async fn token(&self) -> String {
+        let token: PositionToken = self
+            .send("GET", "/game/token", None)
+            .await
+            .unwrap_or_else(|(status, error)| panic!("token failed with {}: {:?}", status, error));
+        token.token
+    }
+
+    /// Replaces the game with the position of `token` with `POST /load-token`.
+    pub async fn load_token(&self, token: &str) -> YEN {
+        self.send("POST", "/load-token", Some(serde_json::json!({ "token": token })))
+            .await
+            .unwrap_or_else(|(status, error)| panic!("load-token failed with {}: {:?}", status, error))
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        uri: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<T, (StatusCode, ErrorResponse)> {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+        let response = self.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        if status.is_success() {
+            Ok(serde_json::from_slice(&bytes).unwrap())
+        } else {
+            Err((status, serde_json::from_slice(&bytes).unwrap()))
+        }
+    }
+}
@@ -110,13 +110,13 @@ app.post('/login', async (req, res) => {
 // New
 // Executes a move in the game
 app.post('/move', async (req, res) => {
-  const { cellIndex } = req.body;
+  const { cellIndex, bot } = req.body;
 
   try {
     const rustResponse = await fetch('http://gamey:4000/execute-move', { // LLama al endpoint de Rust para ejecutar el movimiento
       method: 'POST',
       headers: {'Content-Type': 'application/json'},
-      body: JSON.stringify({ index: cellIndex})
+      body: JSON.stringify({ index: cellIndex, bot })
     });
 
     if (!rustResponse.ok) {
@@ -128,7 +128,8 @@ app.post('/move', async (req, res) => {
     const newBoard = await rustResponse.json();
     res.json({ 
       responseFromRust: newBoard.board,
-      winner: newBoard.winner
+      winner: newBoard.winner,
+      bot: newBoard.bot
     });
   }
   catch (e) {
@@ -144,8 +145,8 @@ app.post('/reset', async (req, res) => {
     const rustResponse = await fetch('http://gamey:4000/reset', { // LLama al endpoint de Rust para resetear el juego
       method: 'POST',
     });
-    const newBoard = await rustResponse.json();
-    res.json({ responseFromRust: newBoard});
+    const newGame = await rustResponse.json();
+    res.json({ responseFromRust: newGame.board});
   }
   catch (e) {
     res.status(500).json({error: 'Error communicating with Rust server'});